@@ -1,9 +1,8 @@
 //! Parsing functions for defining annotation
-use my_string::smol_str::SmolStr;
 
 use crate::{
     ExpressionOptions,
-    error::{AstError, GetStartEnd, assert_token},
+    error::{Alternatives, AstError, GetStartEnd, assert_token},
     lexer::{PositionToken, Token},
     parse_annotated, parse_expression, parse_jtype, parse_name, parse_name_single, parse_thing,
     types::{
@@ -24,7 +23,7 @@ pub fn parse_annotation(
     let (name, pos) = parse_name_single(tokens, pos)?;
     let pos = assert_token(tokens, pos, Token::LeftParenCurly)?;
     let mut pos = pos;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     let mut fields = vec![];
     let mut inner = vec![];
     loop {
@@ -40,7 +39,7 @@ pub fn parse_annotation(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("annotation field"), e));
+                errors.push("annotation field", e);
             }
         }
         match parse_thing(tokens, pos) {
@@ -50,13 +49,10 @@ pub fn parse_annotation(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("thing"), e));
+                errors.push("thing", e);
             }
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("annotation"),
-            errors,
-        });
+        return Err(errors.fail("annotation"));
     }
     let end = tokens.end(pos)?;
     Ok((
@@ -1,9 +1,8 @@
 //! Parsing functions for class
-use my_string::smol_str::SmolStr;
 
 use crate::{
     ExpressionOptions,
-    error::{AstError, GetStartEnd, assert_semicolon, assert_token},
+    error::{Alternatives, AstError, GetStartEnd, assert_semicolon, assert_token},
     lexer::{PositionToken, Token},
     parse_annotated, parse_array_type_on_name, parse_block, parse_constructor_header,
     parse_expression, parse_implements, parse_jtype, parse_method_header, parse_name,
@@ -102,7 +101,7 @@ pub fn parse_class_block(
     let mut constructors = vec![];
     let mut inner = vec![];
     let mut pos = pos;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
 
     loop {
         if let Ok(npos) = assert_token(tokens, pos, Token::RightParenCurly) {
@@ -123,7 +122,7 @@ pub fn parse_class_block(
                     continue;
                 }
                 Err(e) => {
-                    errors.push((SmolStr::new_inline("static block"), e));
+                    errors.push("static block", e);
                 }
             },
             Token::LeftParenCurly => match parse_block(tokens, pos) {
@@ -133,7 +132,7 @@ pub fn parse_class_block(
                     continue;
                 }
                 Err(e) => {
-                    errors.push((SmolStr::new_inline("block"), e));
+                    errors.push("block", e);
                 }
             },
             _ => (),
@@ -146,7 +145,7 @@ pub fn parse_class_block(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("class thing"), e));
+                errors.push("class thing", e);
             }
         }
         match parse_class_variable(tokens, pos) {
@@ -156,7 +155,7 @@ pub fn parse_class_block(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("class variable"), e));
+                errors.push("class variable", e);
             }
         }
         match parse_class_method(tokens, pos) {
@@ -166,7 +165,7 @@ pub fn parse_class_block(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("class method"), e));
+                errors.push("class method", e);
             }
         }
         match parse_class_constructor(tokens, pos) {
@@ -176,13 +175,10 @@ pub fn parse_class_block(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("class constructor"), e));
+                errors.push("class constructor", e);
             }
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("class"),
-            errors,
-        });
+        return Err(errors.fail("class"));
     }
 
     let end = tokens.end(pos)?;
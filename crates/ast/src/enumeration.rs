@@ -1,12 +1,11 @@
 //! Parsing functions for enum
-use my_string::smol_str::SmolStr;
 
 use crate::{
     class::{
         parse_class_constructor, parse_class_method, parse_class_variable,
         parse_implemnets_extends_permits, parse_static_block,
     },
-    error::{AstError, GetStartEnd, assert_semicolon, assert_token},
+    error::{Alternatives, AstError, GetStartEnd, assert_semicolon, assert_token},
     lexer::{PositionToken, Token},
     parse_annotated_list, parse_expression_parameters, parse_identifier, parse_name, parse_thing,
     types::{
@@ -27,7 +26,7 @@ pub fn parse_enumeration(
 ) -> Result<(AstThing, usize), AstError> {
     let (name, pos) = parse_identifier(tokens, pos)?;
     let (superclass, implements, permits, pos) = parse_implemnets_extends_permits(tokens, pos)?;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     let pos = assert_token(tokens, pos, Token::LeftParenCurly)?;
     let mut pos = pos;
     let mut variants = vec![];
@@ -45,7 +44,7 @@ pub fn parse_enumeration(
                 pos = npos;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("enum_variant"), e));
+                errors.push("enum_variant", e);
             }
         }
         if let Ok(npos) = assert_token(tokens, pos, Token::RightParenCurly) {
@@ -80,12 +79,9 @@ pub fn parse_enumeration(
                 pos = npos;
                 continue;
             }
-            Err(e) => errors.push((SmolStr::new_inline("enum_members"), e)),
+            Err(e) => errors.push("enum_members", e),
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("enum_variant"),
-            errors,
-        });
+        return Err(errors.fail("enum_variant"));
     }
     if !end_reached {
         match parse_enum_members(
@@ -147,7 +143,7 @@ fn parse_enum_members(
         let npos = assert_token(tokens, pos, Token::LeftParenCurly)?;
         pos = npos;
     }
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     loop {
         errors.clear();
         if let Ok(npos) = assert_token(tokens, pos, Token::RightParenCurly) {
@@ -166,7 +162,7 @@ fn parse_enum_members(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("enum_method"), e));
+                errors.push("enum_method", e);
             }
         }
         match parse_class_constructor(tokens, pos) {
@@ -176,7 +172,7 @@ fn parse_enum_members(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("enum_constructor"), e));
+                errors.push("enum_constructor", e);
             }
         }
         match parse_class_variable(tokens, pos) {
@@ -186,7 +182,7 @@ fn parse_enum_members(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("enum_variable"), e));
+                errors.push("enum_variable", e);
             }
         }
         match parse_static_block(tokens, pos) {
@@ -196,7 +192,7 @@ fn parse_enum_members(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("static block"), e));
+                errors.push("static block", e);
             }
         }
         match parse_thing(tokens, pos) {
@@ -206,13 +202,10 @@ fn parse_enum_members(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("thing"), e));
+                errors.push("thing", e);
             }
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("enum"),
-            errors,
-        });
+        return Err(errors.fail("enum"));
     }
     Ok(pos)
 }
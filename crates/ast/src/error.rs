@@ -1,6 +1,4 @@
 //! Error type and helper
-use my_string::MyString;
-
 use super::lexer::{PositionToken, Token};
 use crate::BlockEntryOptions;
 
@@ -42,9 +40,19 @@ pub enum AstError {
     /// All children errored
     AllChildrenFailed {
         /// Description
-        parent: MyString,
-        /// Related errors
-        errors: Vec<(MyString, Self)>,
+        parent: &'static str,
+        /// Error of the alternative that got the furthest, see [`Alternatives`]
+        errors: Vec<(&'static str, Self)>,
+    },
+    /// All children errored while parsing, [`crate::parse_file`] returns it as
+    /// [`AstError::AllChildrenFailed`]
+    AlternativeFailed {
+        /// Description
+        parent: &'static str,
+        /// Label of the alternative that got the furthest
+        label: &'static str,
+        /// Its error
+        error: Box<Self>,
     },
     /// Invalid string literal
     InvalidString(InvalidToken),
@@ -122,6 +130,7 @@ impl PrintErr for AstError {
                     );
                 }
             }
+            Self::AlternativeFailed { error, .. } => error.print_err(content, tokens),
             Self::AllChildrenFailed { parent, errors } => {
                 if PRINT_ALL_ERRORS {
                     eprintln!("{parent}");
@@ -162,7 +171,7 @@ impl PrintErr for AstError {
 }
 
 #[allow(unused)]
-const fn sort_helper_error(a: &(MyString, AstError)) -> usize {
+const fn sort_helper_error(a: &(&'static str, AstError)) -> usize {
     match &a.1 {
         AstError::ExpectedToken(expected_token) => expected_token.pos,
         AstError::InvalidJtype(invalid_token)
@@ -176,7 +185,8 @@ const fn sort_helper_error(a: &(MyString, AstError)) -> usize {
         | AstError::AllChildrenFailed {
             parent: _,
             errors: _,
-        } => 1000,
+        }
+        | AstError::AlternativeFailed { .. } => 1000,
     }
 }
 impl AstError {
@@ -189,7 +199,65 @@ impl AstError {
         Self::UnexpectedEOF
         // Self::UnexpectedEOF(loc.file().into(), loc.line(), loc.column())
     }
+
+    /// Turn the [`AstError::AlternativeFailed`] of the parse into [`AstError::AllChildrenFailed`]
+    #[must_use]
+    pub fn into_tree(self) -> Self {
+        match self {
+            Self::AlternativeFailed {
+                parent,
+                label,
+                error,
+            } => Self::AllChildrenFailed {
+                parent,
+                errors: vec![(label, error.into_tree())],
+            },
+            other => other,
+        }
+    }
+}
+/// Errors of the alternatives that were tried at a position
+///
+/// Only the error of the alternative that got the furthest is kept, on a tie the one that was
+/// tried first
+#[derive(Debug, Default)]
+pub struct Alternatives {
+    furthest: Option<(usize, &'static str, AstError)>,
+}
+
+impl Alternatives {
+    /// Record the error of a alternative
+    #[inline]
+    pub fn push(&mut self, label: &'static str, error: AstError) {
+        let pos = get_pos(&error).1;
+        if self.furthest.as_ref().is_none_or(|(p, _, _)| pos > *p) {
+            self.furthest = Some((pos, label, error));
+        }
+    }
+
+    /// Forget the errors before trying the alternatives at the next position
+    #[inline]
+    pub fn clear(&mut self) {
+        self.furthest = None;
+    }
+
+    /// Error of the parent whose alternatives all failed
+    #[must_use]
+    pub fn fail(self, parent: &'static str) -> AstError {
+        match self.furthest {
+            Some((_, label, error)) => AstError::AlternativeFailed {
+                parent,
+                label,
+                error: Box::new(error),
+            },
+            None => AstError::AllChildrenFailed {
+                parent,
+                errors: Vec::new(),
+            },
+        }
+    }
 }
+
 /// Get position for `AstError`
 pub fn get_pos(e: &AstError) -> (usize, usize) {
     match e {
@@ -204,6 +272,7 @@ pub fn get_pos(e: &AstError) -> (usize, usize) {
             }
             (0, 0)
         }
+        AstError::AlternativeFailed { error, .. } => get_pos(error),
         AstError::InvalidJtype(invalid_token)
         | AstError::EmptyExpression(invalid_token)
         | AstError::FordbidenExpressionCall(invalid_token)
@@ -214,6 +283,49 @@ pub fn get_pos(e: &AstError) -> (usize, usize) {
     }
 }
 
+/// Parent of the innermost `AllChildrenFailed` on the way to the error that `print_err` shows,
+/// the name of the error when it has no parent
+#[must_use]
+pub fn failing_construct(e: &AstError) -> &str {
+    let mut out = error_name(e);
+    let mut current = e;
+    loop {
+        match current {
+            AstError::AllChildrenFailed { parent, errors } => {
+                out = parent;
+                let Some((_, next)) = errors.iter().max_by_key(|(_, e)| get_pos(e).0) else {
+                    break;
+                };
+                current = next;
+            }
+            AstError::AlternativeFailed { parent, error, .. } => {
+                out = parent;
+                current = error;
+            }
+            _ => break,
+        }
+    }
+    out
+}
+
+/// Name of the variant
+#[must_use]
+pub const fn error_name(e: &AstError) -> &'static str {
+    match e {
+        AstError::ExpectedToken(_) => "ExpectedToken",
+        AstError::InvalidJtype(_) => "InvalidJtype",
+        AstError::EmptyExpression(_) => "EmptyExpression",
+        AstError::FordbidenExpressionCall(_) => "FordbidenExpressionCall",
+        AstError::UnexpectedEOF => "UnexpectedEOF",
+        AstError::IdentifierEmpty(_) => "IdentifierEmpty",
+        AstError::InvalidName(_) => "InvalidName",
+        AstError::InvalidNuget(_) => "InvalidNuget",
+        AstError::AllChildrenFailed { .. } => "AllChildrenFailed",
+        AstError::AlternativeFailed { .. } => "AlternativeFailed",
+        AstError::InvalidString(_) => "InvalidString",
+    }
+}
+
 fn print_helper(content: &str, line: usize, col: usize, msg: &str) {
     let is_zero = line == 0;
     let mut lines = if is_zero {
@@ -321,4 +433,60 @@ mod tests {
     fn config() {
         assert!(!PRINT_ALL_ERRORS);
     }
+
+    #[test]
+    fn construct() {
+        let expected = |pos| {
+            AstError::ExpectedToken(ExpectedToken {
+                expected: Token::Semicolon,
+                pos,
+            })
+        };
+        let error = AstError::AllChildrenFailed {
+            parent: "file",
+            errors: vec![
+                ("import", expected(2)),
+                (
+                    "class",
+                    AstError::AllChildrenFailed {
+                        parent: "block",
+                        errors: vec![("field", expected(9))],
+                    },
+                ),
+            ],
+        };
+        assert_eq!(failing_construct(&error), "block");
+        assert_eq!(failing_construct(&expected(1)), "ExpectedToken");
+    }
+
+    #[test]
+    fn furthest_alternative() {
+        let expected = |pos| {
+            AstError::ExpectedToken(ExpectedToken {
+                expected: Token::Semicolon,
+                pos,
+            })
+        };
+        let mut errors = Alternatives::default();
+        errors.push("field", expected(3));
+        errors.push("method", expected(7));
+        errors.push("class", expected(7));
+        errors.push("import", expected(2));
+        let mut parent = Alternatives::default();
+        parent.push("import", expected(5));
+        parent.push("class", errors.fail("block"));
+        assert_eq!(
+            parent.fail("file").into_tree(),
+            AstError::AllChildrenFailed {
+                parent: "file",
+                errors: vec![(
+                    "class",
+                    AstError::AllChildrenFailed {
+                        parent: "block",
+                        errors: vec![("method", expected(7))],
+                    }
+                )],
+            }
+        );
+    }
 }
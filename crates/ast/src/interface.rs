@@ -1,9 +1,8 @@
 //! Parsing functions for interface
-use my_string::smol_str::SmolStr;
 
 use crate::{
     ExpressionOptions,
-    error::{AstError, GetStartEnd, assert_token},
+    error::{Alternatives, AstError, GetStartEnd, assert_token},
     lexer::{PositionToken, Token},
     parse_annotated, parse_annotated_list, parse_array_type_on_name, parse_block, parse_expression,
     parse_extends, parse_identifier, parse_jtype, parse_method_header, parse_name, parse_permits,
@@ -49,7 +48,7 @@ pub fn parse_interface(
         }
     }
     let pos = assert_token(tokens, pos, Token::LeftParenCurly)?;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     let mut constants = vec![];
     let mut methods = vec![];
     let mut default_methods = vec![];
@@ -70,7 +69,7 @@ pub fn parse_interface(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("interface semicolon"), e));
+                errors.push("interface semicolon", e);
             }
         }
         match parse_interface_constant(tokens, pos) {
@@ -80,7 +79,7 @@ pub fn parse_interface(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("interface_constant"), e));
+                errors.push("interface_constant", e);
             }
         }
         match parse_interface_method(tokens, pos) {
@@ -90,7 +89,7 @@ pub fn parse_interface(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("interface_method"), e));
+                errors.push("interface_method", e);
             }
         }
         match parse_interface_method_impl(tokens, pos) {
@@ -100,7 +99,7 @@ pub fn parse_interface(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("interface_method_impl"), e));
+                errors.push("interface_method_impl", e);
             }
         }
         match parse_thing(tokens, pos) {
@@ -110,13 +109,10 @@ pub fn parse_interface(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("interface thing"), e));
+                errors.push("interface thing", e);
             }
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("interface"),
-            errors,
-        });
+        return Err(errors.fail("interface"));
     }
     let end = tokens.end(pos)?;
 
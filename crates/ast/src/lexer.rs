@@ -32,6 +32,12 @@ impl PositionToken {
     /// End point of Token
     #[must_use]
     pub fn end_point(&self) -> AstPoint {
+        if let Token::StringLiteralMulti(_, lines @ 1.., last) = self.token {
+            return AstPoint {
+                line: self.line + lines,
+                col: last,
+            };
+        }
         AstPoint {
             line: self.line,
             col: self.col + self.token.len(),
@@ -44,15 +50,13 @@ impl Token {
     #[must_use]
     pub fn len(&self) -> usize {
         match self {
-            Self::Identifier(i)
-            | Self::StringLiteral(i)
-            | Self::StringLiteralMulti(i)
-            | Self::CharLiteral(i) => i.len(),
+            Self::Identifier(i) => i.len(),
+            Self::CharLiteral(c) => c.len() + 2,
+            Self::StringLiteral(_, len) | Self::StringLiteralMulti(_, _, len) => *len,
             Self::Number(n) => n.len(),
             Self::HexLiteral(n) | Self::BinaryLiteral(n) => n.len() + 2,
             Self::LineComment(c) => c.len() + 2,
             Self::BlockComment(c, _) => c.len() + 4,
-            Self::AtInterface | Self::Protected => 11,
             Self::LeftParen
             | Self::RightParen
             | Self::Plus
@@ -132,7 +136,7 @@ impl Token {
             | Self::Sealed
             | Self::Module
             | Self::Assert => 6,
-            Self::Interface | Self::Transient => 9,
+            Self::Interface | Self::Protected | Self::Transient => 9,
             Self::Enum
             | Self::Void
             | Self::True
@@ -146,7 +150,7 @@ impl Token {
             | Self::With
             | Self::Open => 4,
             Self::Int | Self::New | Self::For | Self::Try | Self::Non | Self::Var => 3,
-            Self::Implements | Self::InstanceOf | Self::Transitive => 10,
+            Self::AtInterface | Self::Implements | Self::InstanceOf | Self::Transitive => 10,
             Self::Continue
             | Self::Abstract
             | Self::Volatile
@@ -169,10 +173,10 @@ impl fmt::Display for Token {
             Self::Identifier(s) | Self::Number(s) => {
                 write!(f, "{s}")
             }
-            Self::StringLiteral(s) => {
+            Self::StringLiteral(s, _) => {
                 write!(f, "\"{s}\"")
             }
-            Self::StringLiteralMulti(s) => {
+            Self::StringLiteralMulti(s, ..) => {
                 write!(f, "\"\"\"{s}\"\"\"")
             }
             Self::CharLiteral(s) => {
@@ -310,10 +314,10 @@ impl fmt::Debug for Token {
             Self::Number(s) => {
                 write!(f, "Number({s})")
             }
-            Self::StringLiteral(s) => {
+            Self::StringLiteral(s, _) => {
                 write!(f, "String(\"{s}\")")
             }
-            Self::StringLiteralMulti(s) => {
+            Self::StringLiteralMulti(s, ..) => {
                 write!(f, "\"\"\"{s}\"\"\"")
             }
             Self::CharLiteral(s) => {
@@ -451,10 +455,10 @@ pub enum Token {
     BlockComment(Vec<u8>, usize),
     /// Data
     Identifier(MyString),
-    /// "Data"
-    StringLiteral(MyString),
-    /// """Data"""
-    StringLiteralMulti(MyString),
+    /// "Data", with its length in the source
+    StringLiteral(MyString, usize),
+    /// """Data""", with the lines it spans and the length of its last line
+    StringLiteralMulti(MyString, usize, usize),
     /// \r
     CharLiteral(MyString),
     /// 123
@@ -788,11 +792,116 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
     tokens: &mut Vec<PositionToken>,
 ) -> Result<(), LexerError> {
     tokens.clear();
-    let mut line = 0;
-    let mut col = 0;
-    let mut index = 0;
+    lex_from::<INCLUDE_COMMENTS>(input, tokens, 0, AstPoint { line: 0, col: 0 }, |_| false)
+}
 
-    loop {
+/// Edited region of a document, the text from `start` to `old_end` was replaced by the text from
+/// `start` to `new_end`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LexEdit {
+    /// Start of the edit
+    pub start: AstPoint,
+    /// End of the replaced text before the edit
+    pub old_end: AstPoint,
+    /// End of the inserted text after the edit
+    pub new_end: AstPoint,
+}
+
+impl LexEdit {
+    /// Position after the edit of a token that started after the replaced text
+    const fn shift(&self, token: &PositionToken) -> AstPoint {
+        if token.line == self.old_end.line {
+            AstPoint {
+                line: self.new_end.line,
+                col: token.col - self.old_end.col + self.new_end.col,
+            }
+        } else {
+            AstPoint {
+                line: token.line - self.old_end.line + self.new_end.line,
+                col: token.col,
+            }
+        }
+    }
+}
+
+/// Update the tokens of a document after an edit. Lexing starts at the token before the edit and
+/// stops as soon as it produces a token that is also in the old tokens after the edit.
+pub fn relex(
+    input: &[u8],
+    tokens: &mut Vec<PositionToken>,
+    edit: &LexEdit,
+) -> Result<(), LexerError> {
+    if tokens.is_empty() {
+        return lex_mut::<false>(input, tokens);
+    }
+    // The last token before the edit is lexed again, the edit could continue it
+    let before = tokens.partition_point(|t| t.end_point() < edit.start);
+    let (keep, start, index) =
+        before
+            .checked_sub(1)
+            .map_or((0, AstPoint { line: 0, col: 0 }, 0), |keep| {
+                let t = &tokens[keep];
+                let line_start = t.line.checked_sub(1).map_or(0, |nth| {
+                    memchr_iter(b'\n', input)
+                        .nth(nth)
+                        .map_or(input.len(), |i| i + 1)
+                });
+                (keep, t.start_point(), line_start + t.col)
+            });
+    let after = tokens.partition_point(|t| t.start_point() < edit.old_end);
+    let old = &tokens[after..];
+    let mut synced = None;
+    let mut relexed = Vec::new();
+    lex_from::<false>(input, &mut relexed, index, start, |last| {
+        if last.start_point() < edit.new_end {
+            return false;
+        }
+        let i = old.partition_point(|t| edit.shift(t) < last.start_point());
+        let found = old
+            .get(i)
+            .is_some_and(|t| edit.shift(t) == last.start_point() && t.token == last.token);
+        if found {
+            synced = Some(i);
+        }
+        found
+    })?;
+    let mut out = Vec::with_capacity(tokens.len());
+    out.extend_from_slice(&tokens[..keep]);
+    out.append(&mut relexed);
+    if let Some(i) = synced {
+        out.extend(old[i + 1..].iter().map(|t| {
+            let point = edit.shift(t);
+            PositionToken {
+                token: t.token.clone(),
+                line: point.line,
+                col: point.col,
+            }
+        }));
+    }
+    *tokens = out;
+    Ok(())
+}
+
+/// Lex from a byte index with the position of that byte until `stop` returns true for the last
+/// token
+fn lex_from<const INCLUDE_COMMENTS: bool>(
+    input: &[u8],
+    tokens: &mut Vec<PositionToken>,
+    mut index: usize,
+    start: AstPoint,
+    mut stop: impl FnMut(&PositionToken) -> bool,
+) -> Result<(), LexerError> {
+    let mut line = start.line;
+    let mut col = start.col;
+    let mut checked = tokens.len();
+
+    'lex: loop {
+        if tokens.len() != checked {
+            checked = tokens.len();
+            if tokens.last().is_some_and(&mut stop) {
+                break;
+            }
+        }
         let ch = input.get(index);
         let Some(ch) = ch else {
             break;
@@ -931,7 +1040,7 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                         line,
                         col,
                     });
-                    col += 1;
+                    col += 2;
                     index += 1;
                 } else {
                     tokens.push(PositionToken {
@@ -962,14 +1071,13 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                 if let Some(interface) = input.get(index + 1..index + 10)
                     && interface == b"interface"
                 {
-                    col += 1;
                     tokens.push(PositionToken {
                         token: Token::AtInterface,
                         line,
                         col,
                     });
                     col += 10;
-                    index += 10;
+                    index += 9;
                 } else {
                     tokens.push(PositionToken {
                         token: Token::At,
@@ -1018,6 +1126,7 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                         line,
                         col,
                     });
+                    col += 2;
                     index += 1;
                 } else {
                     tokens.push(PositionToken {
@@ -1025,8 +1134,8 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                         line,
                         col,
                     });
+                    col += 1;
                 }
-                col += 1;
             }
             b'&' => {
                 if matches!(input.get(index + 1), Some(b'&')) {
@@ -1099,7 +1208,7 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                         line,
                         col,
                     });
-                    col += 1;
+                    col += 2;
                     index += 1;
                 } else if peek == &b'/' {
                     let s = index + 2;
@@ -1147,7 +1256,7 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                                 col,
                             });
                         }
-                        col += length - (last + 1);
+                        col = length - (last + 1);
                         line += ln_count;
                     } else {
                         // Full comment contains no newline
@@ -1183,20 +1292,30 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                 col += 1;
             }
             b'"' => {
+                let start_line = line;
+                let start_col = col;
                 index += 1;
+                col += 1;
                 let mut str = SmolStrBuilder::new();
                 let mut multi_line = false;
+                let mut unclosed = false;
                 if matches!(input.get(index), Some(b'"'))
                     && matches!(input.get(index + 1), Some(b'"'))
                 {
                     multi_line = true;
                     index += 2;
+                    col += 2;
                 }
                 'string_literal: while let Some(ch) = input.get(index) {
                     if *ch == b'\r' {
                         index += 1;
                         continue;
                     }
+                    if !multi_line && *ch == b'\n' {
+                        // The next line is lexed on its own
+                        unclosed = true;
+                        break 'string_literal;
+                    }
                     if *ch == b'\\' {
                         let Some(peek) = input.get(index + 1) else {
                             break;
@@ -1223,31 +1342,39 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                             && matches!(input.get(index + 2), Some(b'"'))
                         {
                             index += 2;
-                            col += 2;
+                            col += 3;
                             break 'string_literal;
                         }
                     }
                     str.push(*ch as char);
                     index += 1;
-                    col += 1;
+                    if multi_line && *ch == b'\n' {
+                        line += 1;
+                        col = 0;
+                    } else {
+                        col += 1;
+                    }
                 }
-                if multi_line {
-                    tokens.push(PositionToken {
-                        token: Token::StringLiteralMulti(str.finish()),
-                        line,
-                        col,
-                    });
+                let token = if multi_line {
+                    let lines = line - start_line;
+                    let last = if lines == 0 { col - start_col } else { col };
+                    Token::StringLiteralMulti(str.finish(), lines, last)
                 } else {
-                    tokens.push(PositionToken {
-                        token: Token::StringLiteral(str.finish()),
-                        line,
-                        col,
-                    });
+                    Token::StringLiteral(str.finish(), col - start_col)
+                };
+                tokens.push(PositionToken {
+                    token,
+                    line: start_line,
+                    col: start_col,
+                });
+                if unclosed {
+                    continue 'lex;
                 }
-                col += 1;
             }
             b'\'' => {
+                let start_col = col;
                 index += 1;
+                col += 1;
                 let mut char = SmolStrBuilder::new();
                 'char_literal: while let Some(ch) = input.get(index) {
                     if *ch == b'\\' {
@@ -1271,6 +1398,15 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                     if *ch == b'\'' {
                         break 'char_literal;
                     }
+                    if *ch == b'\n' {
+                        // Unclosed, the next line is lexed on its own
+                        tokens.push(PositionToken {
+                            token: Token::CharLiteral(char.finish()),
+                            line,
+                            col: start_col,
+                        });
+                        continue 'lex;
+                    }
                     char.push(*ch as char);
                     index += 1;
                     col += 1;
@@ -1278,7 +1414,7 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                 tokens.push(PositionToken {
                     token: Token::CharLiteral(char.finish()),
                     line,
-                    col,
+                    col: start_col,
                 });
                 col += 1;
             }
@@ -1292,12 +1428,12 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                     col += 2;
                     index += 1;
                 } else {
-                    col += 1;
                     tokens.push(PositionToken {
                         token: Token::Equal,
                         line,
                         col,
                     });
+                    col += 1;
                 }
             }
             b'!' => {
@@ -1384,12 +1520,13 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                                 }
                             }
                             let string = string.finish();
-                            col += string.len();
+                            let len = string.len();
                             tokens.push(PositionToken {
                                 token: Token::HexLiteral(string),
                                 line,
                                 col,
                             });
+                            col += len;
                             continue;
                         }
                         Some(b'b' | b'B') => {
@@ -1404,12 +1541,13 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                                 }
                             }
                             let finish = string.finish();
-                            col += finish.len();
+                            let len = finish.len();
                             tokens.push(PositionToken {
                                 token: Token::BinaryLiteral(finish),
                                 line,
                                 col,
                             });
+                            col += len;
                             continue;
                         }
                         _ => (),
@@ -1426,12 +1564,13 @@ pub fn lex_mut<const INCLUDE_COMMENTS: bool>(
                 }
 
                 let string = string.finish();
-                col += string.len();
+                let len = string.len();
                 tokens.push(PositionToken {
                     token: Token::Number(string),
                     line,
                     col,
                 });
+                col += len;
                 continue;
             }
             b'A'..=b'Z' | b'a'..=b'z' | b'_' | b'$' => {
@@ -1607,7 +1746,7 @@ pub mod tests {
                 PositionToken {
                     token: =,
                     line: 4,
-                    col: 29,
+                    col: 28,
                 },
                 PositionToken {
                     token: new,
@@ -1712,7 +1851,7 @@ pub mod tests {
                 PositionToken {
                     token: =,
                     line: 7,
-                    col: 32,
+                    col: 31,
                 },
                 PositionToken {
                     token: new,
@@ -1772,7 +1911,7 @@ pub mod tests {
                 PositionToken {
                     token: Number(1),
                     line: 8,
-                    col: 11,
+                    col: 10,
                 },
                 PositionToken {
                     token: ,,
@@ -1782,7 +1921,7 @@ pub mod tests {
                 PositionToken {
                     token: String(""),
                     line: 8,
-                    col: 14,
+                    col: 13,
                 },
                 PositionToken {
                     token: ),
@@ -1867,7 +2006,7 @@ pub mod tests {
                 PositionToken {
                     token: =,
                     line: 11,
-                    col: 11,
+                    col: 10,
                 },
                 PositionToken {
                     token: Identifier("a"),
@@ -1907,7 +2046,7 @@ pub mod tests {
                 PositionToken {
                     token: Number(1),
                     line: 12,
-                    col: 16,
+                    col: 15,
                 },
                 PositionToken {
                     token: ;,
@@ -2670,7 +2809,7 @@ pub mod tests {
                 PositionToken {
                     token: Number(0),
                     line: 20,
-                    col: 16,
+                    col: 15,
                 },
                 PositionToken {
                     token: ;,
@@ -3076,7 +3215,7 @@ pub mod tests {
                 PositionToken {
                     token: String("\""),
                     line: 0,
-                    col: 10,
+                    col: 7,
                 },
                 PositionToken {
                     token: +,
@@ -3096,7 +3235,7 @@ pub mod tests {
                 PositionToken {
                     token: String("\""),
                     line: 0,
-                    col: 21,
+                    col: 18,
                 },
                 PositionToken {
                     token: ;,
@@ -3116,7 +3255,7 @@ pub mod tests {
                 PositionToken {
                     token: String("\\"),
                     line: 0,
-                    col: 4,
+                    col: 1,
                 },
             ]
         "#]];
@@ -3139,75 +3278,173 @@ pub mod tests {
                 PositionToken {
                     token: Char('\b'),
                     line: 1,
-                    col: 14,
+                    col: 12,
                 },
                 PositionToken {
                     token: +,
                     line: 1,
-                    col: 16,
+                    col: 17,
                 },
                 PositionToken {
                     token: Char('\t'),
                     line: 2,
-                    col: 14,
+                    col: 12,
                 },
                 PositionToken {
                     token: +,
                     line: 2,
-                    col: 16,
+                    col: 17,
                 },
                 PositionToken {
                     token: Char('\n'),
                     line: 3,
-                    col: 14,
+                    col: 12,
                 },
                 PositionToken {
                     token: +,
                     line: 3,
-                    col: 16,
+                    col: 17,
                 },
                 PositionToken {
                     token: Char('\f'),
                     line: 4,
-                    col: 14,
+                    col: 12,
                 },
                 PositionToken {
                     token: +,
                     line: 4,
-                    col: 16,
+                    col: 17,
                 },
                 PositionToken {
                     token: Char('\r'),
                     line: 5,
-                    col: 14,
+                    col: 12,
                 },
                 PositionToken {
                     token: +,
                     line: 5,
-                    col: 16,
+                    col: 17,
                 },
                 PositionToken {
                     token: Char('\"'),
                     line: 6,
-                    col: 14,
+                    col: 12,
                 },
                 PositionToken {
                     token: +,
                     line: 6,
-                    col: 16,
+                    col: 17,
                 },
                 PositionToken {
                     token: Char('\\'),
                     line: 7,
-                    col: 14,
+                    col: 12,
                 },
                 PositionToken {
                     token: +,
                     line: 7,
-                    col: 16,
+                    col: 17,
                 },
             ]
         "#]];
         expected.assert_debug_eq(&tokens);
     }
+
+    fn point(text: &str, offset: usize) -> crate::types::AstPoint {
+        let before = &text[..offset];
+        crate::types::AstPoint {
+            line: before.matches('\n').count(),
+            col: offset - before.rfind('\n').map_or(0, |i| i + 1),
+        }
+    }
+
+    #[test]
+    fn relex_edits() {
+        let content = "package ch.emilycares;\n@interface Marker {}\npublic class Test {\n    int value = 10;\n    char c = 'a', d = '\\'';\n    String text = \"a\\\"b\";\n    void a(int x) { x *= 2; x %= 3; x /= 4; x += value >= 1 ? x << 2 : x >> 1; }\n    boolean b = x != 1 && x == 2 || !(x-- < 3) | y <= 4;\n    Runnable r = () -> { x |= 1; x -= 1; x++; y = ~x ^ 2 & 1_000; };\n    String t = \"\"\"\n        block \\\" \\\\\n        \"\"\";\n    char n = '\\n';\n    /* block\n       comment */ int y = 0; // line\n}\n";
+        let relex_matches_lex = |offset: usize, old: usize, new: &str| {
+            let changed = format!("{}{new}{}", &content[..offset], &content[offset + old..]);
+            let edit = lexer::LexEdit {
+                start: point(content, offset),
+                old_end: point(content, offset + old),
+                new_end: point(&changed, offset + new.len()),
+            };
+            let mut tokens = lexer::lex(content.as_bytes()).unwrap();
+            let relexed = lexer::relex(changed.as_bytes(), &mut tokens, &edit);
+            match lexer::lex(changed.as_bytes()) {
+                Ok(lexed) => {
+                    assert_eq!(relexed, Ok(()), "{changed}");
+                    assert_eq!(tokens, lexed, "{changed}");
+                }
+                Err(e) => assert_eq!(relexed, Err(e), "{changed}"),
+            }
+        };
+        // Each token starts at its first byte
+        let byte = |point: crate::types::AstPoint| {
+            content.lines().nth(point.line).unwrap().as_bytes()[point.col]
+        };
+        for token in lexer::lex(content.as_bytes()).unwrap() {
+            let first = match &token.token {
+                lexer::Token::Identifier(s) | lexer::Token::Number(s) => s.as_bytes()[0],
+                lexer::Token::StringLiteral(..) | lexer::Token::StringLiteralMulti(..) => b'"',
+                lexer::Token::CharLiteral(_) => b'\'',
+                lexer::Token::LeftParen => b'(',
+                t => format!("{t:?}").as_bytes()[0],
+            };
+            assert_eq!(byte(token.start_point()), first, "{token:?}");
+        }
+        for offset in 0..=content.len() {
+            for new in ["0", " ", "=", "*", "\n", "'", "\"", "/*", "x(2)"] {
+                relex_matches_lex(offset, 0, new);
+            }
+            if offset < content.len() {
+                relex_matches_lex(offset, 1, "");
+            }
+        }
+        relex_matches_lex(content.find("int value").unwrap(), 16, "");
+        relex_matches_lex(
+            content.find("Test {").unwrap(),
+            7,
+            "Test {\n    void b() {}\n",
+        );
+    }
+
+    #[test]
+    fn string_literal_end() {
+        let content = "String s = \"äöü\";\nString t = \"\"\"\n    ä\n    \"\"\";\nint i;\n";
+        let tokens = lexer::lex(content.as_bytes()).unwrap();
+        let ends: Vec<_> = tokens
+            .iter()
+            .filter(|t| {
+                matches!(
+                    t.token,
+                    lexer::Token::StringLiteral(..) | lexer::Token::StringLiteralMulti(..)
+                )
+            })
+            .map(lexer::PositionToken::end_point)
+            .collect();
+        let semicolons: Vec<_> = content.match_indices(';').map(|(i, _)| i).collect();
+        assert_eq!(
+            ends,
+            [point(content, semicolons[0]), point(content, semicolons[1])]
+        );
+        let int = tokens
+            .iter()
+            .find(|t| t.token == lexer::Token::Int)
+            .unwrap();
+        assert_eq!(
+            int.start_point(),
+            point(content, content.find("int").unwrap())
+        );
+
+        let changed = content.replacen("int i", "long i", 1);
+        let offset = content.find("int").unwrap();
+        let edit = lexer::LexEdit {
+            start: point(content, offset),
+            old_end: point(content, offset + 3),
+            new_end: point(&changed, offset + 4),
+        };
+        let mut relexed = tokens;
+        lexer::relex(changed.as_bytes(), &mut relexed, &edit).unwrap();
+        assert_eq!(relexed, lexer::lex(changed.as_bytes()).unwrap());
+    }
 }
@@ -10,7 +10,7 @@ use annotation::parse_annotation;
 use bitflags::bitflags;
 use class::parse_class;
 use enumeration::parse_enumeration;
-use error::{AstError, ExpectedToken, InvalidToken, assert_semicolon, assert_token};
+use error::{Alternatives, AstError, ExpectedToken, InvalidToken, assert_semicolon, assert_token};
 use interface::parse_interface;
 use lexer::{PositionToken, Token};
 use my_string::smol_str::ToSmolStr;
@@ -56,12 +56,13 @@ pub mod module;
 pub mod range;
 pub mod record;
 pub mod types;
+pub mod visit;
 
 ///` package ch.emilycares; import .... public class ...`
 pub fn parse_file(tokens: &[PositionToken]) -> Result<AstFile, AstError> {
     let mut pos = 0;
     let mut top = Vec::new();
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     while tokens.get(pos).is_some() {
         errors.clear();
         match assert_token(tokens, pos, Token::Semicolon) {
@@ -70,7 +71,7 @@ pub fn parse_file(tokens: &[PositionToken]) -> Result<AstFile, AstError> {
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("semicolon"), e));
+                errors.push("semicolon", e);
             }
         }
         match parse_package(tokens, pos) {
@@ -80,7 +81,7 @@ pub fn parse_file(tokens: &[PositionToken]) -> Result<AstFile, AstError> {
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("package"), e));
+                errors.push("package", e);
             }
         }
         match parse_import(tokens, pos) {
@@ -90,7 +91,7 @@ pub fn parse_file(tokens: &[PositionToken]) -> Result<AstFile, AstError> {
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("import"), e));
+                errors.push("import", e);
             }
         }
         match parse_thing(tokens, pos) {
@@ -100,7 +101,7 @@ pub fn parse_file(tokens: &[PositionToken]) -> Result<AstFile, AstError> {
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("thing"), e));
+                errors.push("thing", e);
             }
         }
         match parse_class_method(tokens, pos) {
@@ -110,7 +111,7 @@ pub fn parse_file(tokens: &[PositionToken]) -> Result<AstFile, AstError> {
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("method"), e));
+                errors.push("method", e);
             }
         }
         match parse_module(tokens, pos) {
@@ -120,13 +121,10 @@ pub fn parse_file(tokens: &[PositionToken]) -> Result<AstFile, AstError> {
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("module"), e));
+                errors.push("module", e);
             }
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("file"),
-            errors,
-        });
+        return Err(errors.fail("file").into_tree());
     }
 
     Ok(AstFile { top })
@@ -309,7 +307,7 @@ pub fn parse_annotated(
     let (name, pos) = parse_name_dot_logical(tokens, pos)?;
     let mut parameters = AstAnnotatedParameterKind::None;
     let mut pos = pos;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     if assert_token(tokens, pos, Token::LeftParen).is_ok() {
         'parameters: {
             match parse_annotated_parameters(tokens, pos, &expression_options) {
@@ -318,7 +316,7 @@ pub fn parse_annotated(
                     pos = npos;
                     break 'parameters;
                 }
-                Err(e) => errors.push((SmolStr::new_inline("parameters"), e)),
+                Err(e) => errors.push("parameters", e),
             }
             match parse_annotated_array(tokens, pos, &expression_options) {
                 Ok((array, npos)) => {
@@ -326,14 +324,9 @@ pub fn parse_annotated(
                     pos = npos;
                     break 'parameters;
                 }
-                Err(e) => errors.push((SmolStr::new_inline("array"), e)),
-            }
-            if errors.len() == 2 {
-                return Err(AstError::AllChildrenFailed {
-                    parent: SmolStr::new_inline("annotated"),
-                    errors,
-                });
+                Err(e) => errors.push("array", e),
             }
+            return Err(errors.fail("annotated"));
         }
     }
     let end = tokens.end(pos)?;
@@ -358,7 +351,7 @@ pub fn parse_lambda(
     let mut pos = pos;
     let parameters;
     'params: {
-        let mut errors = vec![];
+        let mut errors = Alternatives::default();
         match parse_lambda_parameters(tokens, pos) {
             Ok((lparams, npos)) => {
                 parameters = lparams;
@@ -366,7 +359,7 @@ pub fn parse_lambda(
                 break 'params;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("lambda parameter"), e));
+                errors.push("lambda parameter", e);
             }
         }
         match parse_name(tokens, pos) {
@@ -383,13 +376,10 @@ pub fn parse_lambda(
                 break 'params;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("lambda name"), e));
+                errors.push("lambda name", e);
             }
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("lambda parameters"),
-            errors,
-        });
+        return Err(errors.fail("lambda parameters"));
     }
     let mut pos = assert_token(tokens, pos, Token::Arrow)?;
     let mut rhs = AstLambdaRhs::None;
@@ -503,7 +493,7 @@ fn parse_array_with_annotated(
     let start = tokens.start(pos)?;
     let mut pos = assert_token(tokens, pos, Token::LeftParenCurly)?;
     let mut values = vec![];
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     loop {
         errors.clear();
         if let Ok(npos) = assert_token(tokens, pos, Token::RightParenCurly) {
@@ -520,7 +510,7 @@ fn parse_array_with_annotated(
                 values.push(AstExpressionOrAnnotated::Annotated(an));
                 continue;
             }
-            Err(e) => errors.push((SmolStr::new_inline("annotated"), e)),
+            Err(e) => errors.push("annotated", e),
         }
         match parse_expression(tokens, pos, expression_options) {
             Ok((value, npos)) => {
@@ -528,12 +518,9 @@ fn parse_array_with_annotated(
                 values.push(AstExpressionOrAnnotated::Expression(value));
                 continue;
             }
-            Err(e) => errors.push((SmolStr::new_inline("expression"), e)),
+            Err(e) => errors.push("expression", e),
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("array with annotated"),
-            errors,
-        });
+        return Err(errors.fail("array with annotated"));
     }
     let end = tokens.end(pos)?;
     Ok((
@@ -654,7 +641,7 @@ pub fn parse_value_nuget(
                 pos + 1,
             ))
         }
-        Token::StringLiteral(_) | Token::StringLiteralMulti(_) => {
+        Token::StringLiteral(..) | Token::StringLiteralMulti(..) => {
             parse_string_literal(tokens, pos).map(|i| (AstValue::Nuget(i.0), i.1))
         }
         Token::CharLiteral(_) => {
@@ -688,32 +675,26 @@ pub fn parse_string_literal(
 ) -> Result<(AstValueNuget, usize), AstError> {
     let start = tokens.start(pos)?;
     match &start.token {
-        Token::StringLiteral(str) => {
-            let end = tokens.end(pos)?;
-            Ok((
-                AstValueNuget::StringLiteral {
-                    value: AstIdentifier {
-                        range: AstRange::from_position_token(start, end),
-                        value: str.clone(),
-                    },
-                    multi_line: false,
+        Token::StringLiteral(str, _) => Ok((
+            AstValueNuget::StringLiteral {
+                value: AstIdentifier {
+                    range: AstRange::from_single(start),
+                    value: str.clone(),
                 },
-                pos + 1,
-            ))
-        }
-        Token::StringLiteralMulti(str) => {
-            let end = tokens.end(pos)?;
-            Ok((
-                AstValueNuget::StringLiteral {
-                    value: AstIdentifier {
-                        range: AstRange::from_position_token(start, end),
-                        value: str.clone(),
-                    },
-                    multi_line: true,
+                multi_line: false,
+            },
+            pos + 1,
+        )),
+        Token::StringLiteralMulti(str, ..) => Ok((
+            AstValueNuget::StringLiteral {
+                value: AstIdentifier {
+                    range: AstRange::from_single(start),
+                    value: str.clone(),
                 },
-                pos + 1,
-            ))
-        }
+                multi_line: true,
+            },
+            pos + 1,
+        )),
         _ => Err(AstError::InvalidString(InvalidToken(pos))),
     }
 }
@@ -724,16 +705,13 @@ pub fn parse_char_literal(
 ) -> Result<(AstValueNuget, usize), AstError> {
     let start = tokens.start(pos)?;
     match &start.token {
-        Token::CharLiteral(str) => {
-            let end = tokens.end(pos)?;
-            Ok((
-                AstValueNuget::CharLiteral(AstIdentifier {
-                    range: AstRange::from_position_token(start, end),
-                    value: str.clone(),
-                }),
-                pos + 1,
-            ))
-        }
+        Token::CharLiteral(str) => Ok((
+            AstValueNuget::CharLiteral(AstIdentifier {
+                range: AstRange::from_single(start),
+                value: str.clone(),
+            }),
+            pos + 1,
+        )),
         _ => Err(AstError::InvalidString(InvalidToken(pos))),
     }
 }
@@ -922,7 +900,7 @@ fn parse_annotated_parameters(
 ) -> Result<(Vec<AstAnnotatedParameter>, usize), AstError> {
     let pos = assert_token(tokens, pos, Token::LeftParen)?;
     let mut out = vec![];
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     let mut pos = pos;
     loop {
         if let Ok(npos) = assert_token(tokens, pos, Token::RightParen) {
@@ -949,7 +927,7 @@ fn parse_annotated_parameters(
                     });
                     continue;
                 }
-                Err(e) => errors.push((SmolStr::new_inline("annotated"), e)),
+                Err(e) => errors.push("annotated", e),
             }
             match parse_expression(tokens, npos, expression_options) {
                 Ok((expression, npos)) => {
@@ -963,7 +941,7 @@ fn parse_annotated_parameters(
                     continue;
                 }
                 Err(e) => {
-                    errors.push((SmolStr::new_inline("named expression"), e));
+                    errors.push("named expression", e);
                 }
             }
             match parse_annotated(tokens, npos) {
@@ -978,13 +956,10 @@ fn parse_annotated_parameters(
                     continue;
                 }
                 Err(e) => {
-                    errors.push((SmolStr::new_inline("named annotated"), e));
+                    errors.push("named annotated", e);
                 }
             }
-            return Err(AstError::AllChildrenFailed {
-                parent: SmolStr::new_inline("named annotation"),
-                errors,
-            });
+            return Err(errors.fail("named annotation"));
         }
         match parse_expression(tokens, pos, expression_options) {
             Ok((expression, npos)) => {
@@ -993,7 +968,7 @@ fn parse_annotated_parameters(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("expression"), e));
+                errors.push("expression", e);
             }
         }
         match parse_annotated(tokens, pos) {
@@ -1003,13 +978,10 @@ fn parse_annotated_parameters(
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("annotated"), e));
+                errors.push("annotated", e);
             }
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("annotated parameters"),
-            errors,
-        });
+        return Err(errors.fail("annotated parameters"));
     }
     Ok((out, pos))
 }
@@ -1076,7 +1048,7 @@ pub fn parse_new_class(
     let (jtype, pos) = parse_jtype(tokens, pos)?;
     let mut pos = pos;
     let mut rhs = AstNewRhs::None;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     match parse_array_parameters(tokens, pos, expression_options) {
         Ok((array_parameters, npos)) => {
             pos = npos;
@@ -1084,7 +1056,7 @@ pub fn parse_new_class(
                 rhs = AstNewRhs::ArrayParameters(array_parameters);
             }
         }
-        Err(e) => errors.push((SmolStr::new_inline("array_parameters"), e)),
+        Err(e) => errors.push("array_parameters", e),
     }
     let pstart = tokens.end(pos)?;
     match parse_expression_parameters(tokens, pos) {
@@ -1093,7 +1065,7 @@ pub fn parse_new_class(
             let pend = tokens.end(pos)?;
             rhs = AstNewRhs::Parameters(AstRange::from_position_token(pstart, pend), nrhs);
         }
-        Err(e) => errors.push((SmolStr::new_inline("expression_parameters"), e)),
+        Err(e) => errors.push("expression_parameters", e),
     }
     if jtype.value.is_array() {
         match parse_array(tokens, pos, expression_options) {
@@ -1101,7 +1073,7 @@ pub fn parse_new_class(
                 pos = npos;
                 rhs = AstNewRhs::Array(nrhs);
             }
-            Err(e) => errors.push((SmolStr::new_inline("array"), e)),
+            Err(e) => errors.push("array", e),
         }
     } else {
         match parse_class_block(tokens, pos) {
@@ -1113,14 +1085,11 @@ pub fn parse_new_class(
                     rhs = AstNewRhs::Block(b);
                 }
             }
-            Err(e) => errors.push((SmolStr::new_inline("array"), e)),
+            Err(e) => errors.push("array", e),
         }
     }
     if matches!(rhs, AstNewRhs::None) {
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("new_class"),
-            errors,
-        });
+        return Err(errors.fail("new_class"));
     }
 
     let end = tokens.end(pos)?;
@@ -1187,30 +1156,30 @@ fn parse_expression_inner(
     pos: usize,
     expression_options: &ExpressionOptions,
 ) -> Result<(AstExpressionKind, usize), AstError> {
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     let current = tokens.start(pos)?;
     match &current.token {
         Token::LeftParenCurly => match parse_array(tokens, pos, expression_options) {
             Ok((v, pos)) => return Ok((AstExpressionKind::Array(v), pos)),
-            Err(e) => errors.push((SmolStr::new_inline("array"), e)),
+            Err(e) => errors.push("array", e),
         },
         Token::Switch => match parse_switch(tokens, pos, expression_options) {
             Ok((casted, pos)) => {
                 return Ok((AstExpressionKind::InlineSwitch(casted), pos));
             }
-            Err(e) => errors.push((SmolStr::new_inline("inline switch"), e)),
+            Err(e) => errors.push("inline switch", e),
         },
         Token::New => match parse_new_class(tokens, pos, expression_options) {
             Ok((new, pos)) => return Ok((AstExpressionKind::NewClass(new), pos)),
-            Err(e) => errors.push((SmolStr::new_inline("new class"), e)),
+            Err(e) => errors.push("new class", e),
         },
         Token::Lt => match parse_jtype_generics(tokens, pos) {
             Ok((a, pos)) => return Ok((AstExpressionKind::Generics(a), pos)),
-            Err(e) => errors.push((SmolStr::new_inline("type generics"), e)),
+            Err(e) => errors.push("type generics", e),
         },
         Token::InstanceOf => match parse_instnceof(tokens, pos) {
             Ok((a, pos)) => return Ok((AstExpressionKind::InstanceOf(a), pos)),
-            Err(e) => errors.push((SmolStr::new_inline("instanceof"), e)),
+            Err(e) => errors.push("instanceof", e),
         },
         _ => (),
     }
@@ -1219,7 +1188,7 @@ fn parse_expression_inner(
             Ok((lambda, pos)) => {
                 return Ok((AstExpressionKind::Lambda(lambda), pos));
             }
-            Err(e) => errors.push((SmolStr::new_inline("lambda"), e)),
+            Err(e) => errors.push("lambda", e),
         }
     }
 
@@ -1227,12 +1196,9 @@ fn parse_expression_inner(
         Ok((exp, pos)) => {
             return Ok((AstExpressionKind::Base(exp), pos));
         }
-        Err(e) => errors.push((SmolStr::new_inline("base"), e)),
+        Err(e) => errors.push("base", e),
     }
-    Err(AstError::AllChildrenFailed {
-        parent: SmolStr::new_inline("expression"),
-        errors,
-    })
+    Err(errors.fail("expression"))
 }
 
 fn parse_instnceof(
@@ -1348,7 +1314,7 @@ pub fn parse_base_expression(
             });
         }
         _ => {
-            let mut errors = vec![];
+            let mut errors = Alternatives::default();
             'others: {
                 match parse_value_operator_options(tokens, pos, expression_options) {
                     Ok((op, npos)) => {
@@ -1356,7 +1322,7 @@ pub fn parse_base_expression(
                         out.operator = op;
                         break 'others;
                     }
-                    Err(e) => errors.push((SmolStr::new_inline("operator"), e)),
+                    Err(e) => errors.push("operator", e),
                 }
                 match parse_value_nuget(tokens, pos) {
                     Ok((value, npos)) => {
@@ -1364,12 +1330,9 @@ pub fn parse_base_expression(
                         out.ident = Some(AstExpressionIdentifier::Value(value));
                         break 'others;
                     }
-                    Err(e) => errors.push((SmolStr::new_inline("value"), e)),
+                    Err(e) => errors.push("value", e),
                 }
-                return Err(AstError::AllChildrenFailed {
-                    parent: SmolStr::new_inline("expression"),
-                    errors,
-                });
+                return Err(errors.fail("expression"));
             }
         }
     }
@@ -2027,7 +1990,7 @@ fn parse_block_entry_options(
     block_entry_options: &BlockEntryOptions,
 ) -> Result<(AstBlockEntry, usize), AstError> {
     let current = tokens.start(pos)?;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     match &current.token {
         Token::Semicolon => {
             let start = tokens.start(pos - 1)?;
@@ -2041,7 +2004,7 @@ fn parse_block_entry_options(
                 return Ok((AstBlockEntry::Return(nret), pos));
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("block return"), e));
+                errors.push("block return", e);
             }
         },
         Token::Yield => match parse_block_yield(tokens, pos) {
@@ -2049,7 +2012,7 @@ fn parse_block_entry_options(
                 return Ok((AstBlockEntry::Yield(nret), pos));
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("block yield"), e));
+                errors.push("block yield", e);
             }
         },
         Token::Break => match parse_block_break(tokens, pos) {
@@ -2057,7 +2020,7 @@ fn parse_block_entry_options(
                 return Ok((AstBlockEntry::Break(nret), pos));
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("block break"), e));
+                errors.push("block break", e);
             }
         },
         Token::Assert => match parse_block_assert(tokens, pos) {
@@ -2065,7 +2028,7 @@ fn parse_block_entry_options(
                 return Ok((AstBlockEntry::Assert(nret), pos));
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("block assert"), e));
+                errors.push("block assert", e);
             }
         },
         Token::Continue => match parse_block_continue(tokens, pos) {
@@ -2073,7 +2036,7 @@ fn parse_block_entry_options(
                 return Ok((AstBlockEntry::Continue(nret), pos));
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("block continue"), e));
+                errors.push("block continue", e);
             }
         },
         Token::If => match parse_if(tokens, pos) {
@@ -2081,7 +2044,7 @@ fn parse_block_entry_options(
                 return Ok((AstBlockEntry::If(nret), pos));
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("block if"), e));
+                errors.push("block if", e);
             }
         },
         Token::Switch => match parse_switch(tokens, pos, &ExpressionOptions::empty()) {
@@ -2089,7 +2052,7 @@ fn parse_block_entry_options(
                 return Ok((AstBlockEntry::Switch(nret), pos));
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("block switch"), e));
+                errors.push("block switch", e);
             }
         },
         Token::Try => match parse_try_catch(tokens, pos) {
@@ -2097,7 +2060,7 @@ fn parse_block_entry_options(
                 return Ok((AstBlockEntry::TryCatch(nret), pos));
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("block try catch"), e));
+                errors.push("block try catch", e);
             }
         },
         Token::Throw => match parse_throw(tokens, pos) {
@@ -2105,7 +2068,7 @@ fn parse_block_entry_options(
                 return Ok((AstBlockEntry::Throw(nret), pos));
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("block throw"), e));
+                errors.push("block throw", e);
             }
         },
         Token::Synchronized => match parse_synchronised_block(tokens, pos) {
@@ -2113,7 +2076,7 @@ fn parse_block_entry_options(
                 return Ok((AstBlockEntry::SynchronizedBlock(synchronized_block), pos));
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("static block"), e));
+                errors.push("static block", e);
             }
         },
         Token::Else => {
@@ -2122,7 +2085,7 @@ fn parse_block_entry_options(
                     return Ok((AstBlockEntry::If(nret), pos));
                 }
                 Err(e) => {
-                    errors.push((SmolStr::new_inline("block if"), e));
+                    errors.push("block if", e);
                 }
             }
             match parse_else(tokens, pos) {
@@ -2130,7 +2093,7 @@ fn parse_block_entry_options(
                     return Ok((AstBlockEntry::If(nret), pos));
                 }
                 Err(e) => {
-                    errors.push((SmolStr::new_inline("block if"), e));
+                    errors.push("block if", e);
                 }
             }
         }
@@ -2141,7 +2104,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::InlineBlock(block), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block block"), e));
+            errors.push("block block", e);
         }
     }
     match parse_block_variable_options(tokens, pos, block_entry_options) {
@@ -2149,7 +2112,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::Variable(vars), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block variable"), e));
+            errors.push("block variable", e);
         }
     }
     match parse_while(tokens, pos) {
@@ -2157,7 +2120,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::While(nret), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block while"), e));
+            errors.push("block while", e);
         }
     }
     match parse_do_while(tokens, pos) {
@@ -2165,7 +2128,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::While(nret), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block do while"), e));
+            errors.push("block do while", e);
         }
     }
     match parse_for(tokens, pos) {
@@ -2173,7 +2136,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::For(Box::new(nret)), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block for"), e));
+            errors.push("block for", e);
         }
     }
     match parse_for_enhanced(tokens, pos) {
@@ -2181,7 +2144,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::ForEnhanced(Box::new(nret)), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block for enhanced"), e));
+            errors.push("block for enhanced", e);
         }
     }
     match parse_switch_case(tokens, pos) {
@@ -2189,7 +2152,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::SwitchCase(nret), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block switch case"), e));
+            errors.push("block switch case", e);
         }
     }
     match parse_switch_default(tokens, pos) {
@@ -2197,7 +2160,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::SwitchDefault(nret), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block switch default"), e));
+            errors.push("block switch default", e);
         }
     }
     match parse_switch_case_arrow_type(tokens, pos) {
@@ -2205,7 +2168,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::SwitchCaseArrowType(nret), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("bl sw case ar ty"), e));
+            errors.push("bl sw case ar ty", e);
         }
     }
     match parse_switch_case_arrow_value(tokens, pos) {
@@ -2213,7 +2176,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::SwitchCaseArrowValues(nret), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block switch case arrow"), e));
+            errors.push("block switch case arrow", e);
         }
     }
     match parse_switch_case_arrow_default(tokens, pos) {
@@ -2221,7 +2184,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::SwitchCaseArrowDefault(nret), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block switch case arrow"), e));
+            errors.push("block switch case arrow", e);
         }
     }
     match parse_block_assign(tokens, pos, block_entry_options) {
@@ -2229,7 +2192,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::Assign(Box::new(nret)), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block assign"), e));
+            errors.push("block assign", e);
         }
     }
     match parse_thing(tokens, pos) {
@@ -2237,7 +2200,7 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::Thing(Box::new(thing)), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("class thing"), e));
+            errors.push("class thing", e);
         }
     }
     match parse_block_expression_options(tokens, pos, block_entry_options) {
@@ -2245,13 +2208,10 @@ fn parse_block_entry_options(
             return Ok((AstBlockEntry::Expression(nret), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block expression"), e));
+            errors.push("block expression", e);
         }
     }
-    Err(AstError::AllChildrenFailed {
-        parent: SmolStr::new_inline("block"),
-        errors,
-    })
+    Err(errors.fail("block"))
 }
 
 fn parse_inline_block(
@@ -2283,13 +2243,13 @@ fn parse_block_entry_minimal_options(
     pos: usize,
     block_entry_options: &BlockEntryOptions,
 ) -> Result<(AstBlockEntry, usize), AstError> {
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     match parse_block_variable_options(tokens, pos, block_entry_options) {
         Ok((variable, pos)) => {
             return Ok((AstBlockEntry::Variable(variable), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block variable"), e));
+            errors.push("block variable", e);
         }
     }
     match parse_block_assign(tokens, pos, block_entry_options) {
@@ -2297,7 +2257,7 @@ fn parse_block_entry_minimal_options(
             return Ok((AstBlockEntry::Assign(Box::new(nret)), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block assign"), e));
+            errors.push("block assign", e);
         }
     }
     match parse_block_expression_options(tokens, pos, block_entry_options) {
@@ -2305,13 +2265,10 @@ fn parse_block_entry_minimal_options(
             return Ok((AstBlockEntry::Expression(nret), pos));
         }
         Err(e) => {
-            errors.push((SmolStr::new_inline("block expression"), e));
+            errors.push("block expression", e);
         }
     }
-    Err(AstError::AllChildrenFailed {
-        parent: SmolStr::new_inline("block"),
-        errors,
-    })
+    Err(errors.fail("block"))
 }
 
 fn parse_while(tokens: &[PositionToken], pos: usize) -> Result<(AstWhile, usize), AstError> {
@@ -2329,14 +2286,14 @@ fn parse_while(tokens: &[PositionToken], pos: usize) -> Result<(AstWhile, usize)
     let (control, pos) = parse_expression(tokens, pos, &ExpressionOptions::empty())?;
     let mut pos = assert_token(tokens, pos, Token::RightParen)?;
     let mut content = AstWhileContent::None;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     'while_content: {
         match assert_token(tokens, pos, Token::Semicolon) {
             Ok(npos) => {
                 pos = npos;
                 break 'while_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("semicolon"), e)),
+            Err(e) => errors.push("semicolon", e),
         }
         match parse_block(tokens, pos) {
             Ok((block, npos)) => {
@@ -2344,7 +2301,7 @@ fn parse_while(tokens: &[PositionToken], pos: usize) -> Result<(AstWhile, usize)
                 pos = npos;
                 break 'while_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block"), e)),
+            Err(e) => errors.push("block", e),
         }
         match parse_block_entry(tokens, pos) {
             Ok((entry, npos)) => {
@@ -2352,12 +2309,9 @@ fn parse_while(tokens: &[PositionToken], pos: usize) -> Result<(AstWhile, usize)
                 pos = npos;
                 break 'while_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block entry"), e)),
+            Err(e) => errors.push("block entry", e),
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("while"),
-            errors,
-        });
+        return Err(errors.fail("while"));
     }
     let end = tokens.end(pos)?;
     Ok((
@@ -2382,7 +2336,7 @@ fn parse_do_while(tokens: &[PositionToken], pos: usize) -> Result<(AstWhile, usi
     }
     let mut pos = assert_token(tokens, pos, Token::Do)?;
     let content;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     'do_while_content: {
         match parse_block(tokens, pos) {
             Ok((block, npos)) => {
@@ -2390,7 +2344,7 @@ fn parse_do_while(tokens: &[PositionToken], pos: usize) -> Result<(AstWhile, usi
                 pos = npos;
                 break 'do_while_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block"), e)),
+            Err(e) => errors.push("block", e),
         }
         match parse_block_entry(tokens, pos) {
             Ok((entry, npos)) => {
@@ -2398,12 +2352,9 @@ fn parse_do_while(tokens: &[PositionToken], pos: usize) -> Result<(AstWhile, usi
                 pos = npos;
                 break 'do_while_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block entry"), e)),
+            Err(e) => errors.push("block entry", e),
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("do while"),
-            errors,
-        });
+        return Err(errors.fail("do while"));
     }
 
     let pos = assert_token(tokens, pos, Token::While)?;
@@ -2464,14 +2415,14 @@ pub fn parse_for(tokens: &[PositionToken], pos: usize) -> Result<(AstFor, usize)
         pos = npos;
     }
     let mut content = AstForContent::None;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     'for_content: {
         match assert_token(tokens, pos, Token::Semicolon) {
             Ok(npos) => {
                 pos = npos;
                 break 'for_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("semicolon"), e)),
+            Err(e) => errors.push("semicolon", e),
         }
         match parse_block(tokens, pos) {
             Ok((block, npos)) => {
@@ -2479,7 +2430,7 @@ pub fn parse_for(tokens: &[PositionToken], pos: usize) -> Result<(AstFor, usize)
                 pos = npos;
                 break 'for_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block"), e)),
+            Err(e) => errors.push("block", e),
         }
         match parse_block_entry(tokens, pos) {
             Ok((entry, npos)) => {
@@ -2487,12 +2438,9 @@ pub fn parse_for(tokens: &[PositionToken], pos: usize) -> Result<(AstFor, usize)
                 pos = npos;
                 break 'for_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block entry"), e)),
+            Err(e) => errors.push("block entry", e),
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("for"),
-            errors,
-        });
+        return Err(errors.fail("for"));
     }
     let end = tokens.end(pos)?;
     Ok((
@@ -2726,7 +2674,7 @@ fn parse_for_enhanced(
     let (rhs, pos) = parse_expression(tokens, pos, &ExpressionOptions::empty())?;
     let pos = assert_token(tokens, pos, Token::RightParen)?;
     let content;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     let mut pos = pos;
     'for_content: {
         match parse_block(tokens, pos) {
@@ -2735,7 +2683,7 @@ fn parse_for_enhanced(
                 pos = npos;
                 break 'for_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block"), e)),
+            Err(e) => errors.push("block", e),
         }
         match parse_block_entry(tokens, pos) {
             Ok((entry, npos)) => {
@@ -2743,12 +2691,9 @@ fn parse_for_enhanced(
                 pos = npos;
                 break 'for_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block entry"), e)),
+            Err(e) => errors.push("block entry", e),
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("for"),
-            errors,
-        });
+        return Err(errors.fail("for"));
     }
     let end = tokens.end(pos)?;
     Ok((
@@ -2773,7 +2718,7 @@ fn parse_if(tokens: &[PositionToken], pos: usize) -> Result<(AstIf, usize), AstE
     let end_control = tokens.end(pos)?;
     let mut pos = pos;
     let content;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     'if_content: {
         match parse_block(tokens, pos) {
             Ok((block, npos)) => {
@@ -2781,7 +2726,7 @@ fn parse_if(tokens: &[PositionToken], pos: usize) -> Result<(AstIf, usize), AstE
                 pos = npos;
                 break 'if_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block"), e)),
+            Err(e) => errors.push("block", e),
         }
         match parse_block_entry(tokens, pos) {
             Ok((entry, npos)) => {
@@ -2789,12 +2734,9 @@ fn parse_if(tokens: &[PositionToken], pos: usize) -> Result<(AstIf, usize), AstE
                 pos = npos;
                 break 'if_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block expression"), e)),
+            Err(e) => errors.push("block expression", e),
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("if"),
-            errors,
-        });
+        return Err(errors.fail("if"));
     }
     let end = tokens.end(pos)?;
     Ok((
@@ -2818,7 +2760,7 @@ fn parse_else_if(tokens: &[PositionToken], pos: usize) -> Result<(AstIf, usize),
     let end_control = tokens.end(pos)?;
     let mut pos = pos;
     let content;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     'if_content: {
         match parse_block(tokens, pos) {
             Ok((block, npos)) => {
@@ -2826,7 +2768,7 @@ fn parse_else_if(tokens: &[PositionToken], pos: usize) -> Result<(AstIf, usize),
                 pos = npos;
                 break 'if_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block"), e)),
+            Err(e) => errors.push("block", e),
         }
         match parse_block_entry(tokens, pos) {
             Ok((entry, npos)) => {
@@ -2834,12 +2776,9 @@ fn parse_else_if(tokens: &[PositionToken], pos: usize) -> Result<(AstIf, usize),
                 pos = npos;
                 break 'if_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block entry"), e)),
+            Err(e) => errors.push("block entry", e),
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("if"),
-            errors,
-        });
+        return Err(errors.fail("if"));
     }
     let end = tokens.end(pos)?;
     Ok((
@@ -2857,7 +2796,7 @@ fn parse_else(tokens: &[PositionToken], pos: usize) -> Result<(AstIf, usize), As
     let pos = assert_token(tokens, pos, Token::Else)?;
     let mut pos = pos;
     let content;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     'if_content: {
         match parse_block(tokens, pos) {
             Ok((block, npos)) => {
@@ -2865,7 +2804,7 @@ fn parse_else(tokens: &[PositionToken], pos: usize) -> Result<(AstIf, usize), As
                 pos = npos;
                 break 'if_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block"), e)),
+            Err(e) => errors.push("block", e),
         }
         match parse_block_entry(tokens, pos) {
             Ok((entry, npos)) => {
@@ -2873,12 +2812,9 @@ fn parse_else(tokens: &[PositionToken], pos: usize) -> Result<(AstIf, usize), As
                 pos = npos;
                 break 'if_content;
             }
-            Err(e) => errors.push((SmolStr::new_inline("block entry"), e)),
+            Err(e) => errors.push("block entry", e),
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("if"),
-            errors,
-        });
+        return Err(errors.fail("if"));
     }
     let end = tokens.end(pos)?;
     Ok((
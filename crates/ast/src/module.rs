@@ -1,8 +1,7 @@
 //! Parse functions for module-info.java
-use my_string::smol_str::SmolStr;
 
 use crate::{
-    error::{AstError, GetStartEnd, assert_semicolon, assert_token},
+    error::{Alternatives, AstError, GetStartEnd, assert_semicolon, assert_token},
     lexer::{PositionToken, Token},
     parse_annotated_list, parse_name_dot_logical,
     types::{
@@ -25,7 +24,7 @@ pub fn parse_module(tokens: &[PositionToken], pos: usize) -> Result<(AstModule,
     let pos = assert_token(tokens, pos, Token::Module)?;
     let (name, pos) = parse_name_dot_logical(tokens, pos)?;
     let pos = assert_token(tokens, pos, Token::LeftParenCurly)?;
-    let mut errors = vec![];
+    let mut errors = Alternatives::default();
     let mut pos = pos;
     let mut exports = vec![];
     let mut opens = vec![];
@@ -46,7 +45,7 @@ pub fn parse_module(tokens: &[PositionToken], pos: usize) -> Result<(AstModule,
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("exports"), e));
+                errors.push("exports", e);
             }
         }
         match parse_opens(tokens, pos) {
@@ -56,7 +55,7 @@ pub fn parse_module(tokens: &[PositionToken], pos: usize) -> Result<(AstModule,
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("opens"), e));
+                errors.push("opens", e);
             }
         }
         match parse_uses(tokens, pos) {
@@ -66,7 +65,7 @@ pub fn parse_module(tokens: &[PositionToken], pos: usize) -> Result<(AstModule,
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("uses"), e));
+                errors.push("uses", e);
             }
         }
         match parse_provides(tokens, pos) {
@@ -76,7 +75,7 @@ pub fn parse_module(tokens: &[PositionToken], pos: usize) -> Result<(AstModule,
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("provides"), e));
+                errors.push("provides", e);
             }
         }
         match parse_requires(tokens, pos) {
@@ -86,13 +85,10 @@ pub fn parse_module(tokens: &[PositionToken], pos: usize) -> Result<(AstModule,
                 continue;
             }
             Err(e) => {
-                errors.push((SmolStr::new_inline("requires"), e));
+                errors.push("requires", e);
             }
         }
-        return Err(AstError::AllChildrenFailed {
-            parent: SmolStr::new_inline("module"),
-            errors,
-        });
+        return Err(errors.fail("module"));
     }
 
     let end = tokens.end(pos)?;
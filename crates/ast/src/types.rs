@@ -5,10 +5,11 @@ use std::fmt::Debug;
 
 use bitflags::bitflags;
 use my_string::MyString;
+use serde::Serialize;
 
 use crate::lexer::PositionToken;
 
-#[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy, Serialize)]
 pub struct AstRange {
     pub start: AstPoint,
     pub end: AstPoint,
@@ -49,7 +50,7 @@ impl AstRange {
     }
 }
 
-#[derive(PartialEq, Eq, Default, Clone, Copy, PartialOrd)]
+#[derive(PartialEq, Eq, Default, Clone, Copy, PartialOrd, Serialize)]
 pub struct AstPoint {
     pub line: usize,
     pub col: usize,
@@ -69,11 +70,11 @@ impl AstPoint {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AstFile {
     pub top: Vec<AstTopLevel>,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum AstTopLevel {
     Package(AstPackage),
     Import(AstImport),
@@ -82,19 +83,19 @@ pub enum AstTopLevel {
     Module(AstModule),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AstPackage {
     pub range: AstRange,
     pub annotated: Vec<AstAnnotated>,
     pub name: AstIdentifier,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AstImport {
     pub range: AstRange,
     pub unit: AstImportUnit,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum AstImportUnit {
     Class(AstIdentifier),
     StaticClass(AstIdentifier),
@@ -104,7 +105,7 @@ pub enum AstImportUnit {
 }
 
 bitflags! {
-   #[derive(Debug, Clone, PartialEq, Eq)]
+   #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
    pub struct AstThingAttributes: u8 {
         const Sealed       = 0b0000_0001;
         const NonSealed    = 0b0000_0010;
@@ -112,7 +113,7 @@ bitflags! {
 }
 
 bitflags! {
-   #[derive(Debug, Clone, PartialEq, Eq)]
+   #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
    pub struct AstAvailability: u8 {
         const Public       = 0b0000_0001;
         const Synchronized = 0b0000_0010;
@@ -124,7 +125,7 @@ bitflags! {
         const Native       = 0b1000_0000;
     }
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AstModule {
     pub range: AstRange,
     pub annotated: Vec<AstAnnotated>,
@@ -136,44 +137,44 @@ pub struct AstModule {
     pub provides: Vec<AstModuleProvides>,
     pub requires: Vec<AstModuleRequires>,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AstModuleExports {
     pub range: AstRange,
     pub name: AstIdentifier,
     pub to: Vec<AstIdentifier>,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AstModuleOpens {
     pub range: AstRange,
     pub name: AstIdentifier,
     pub to: Vec<AstIdentifier>,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AstModuleUses {
     pub range: AstRange,
     pub name: AstIdentifier,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AstModuleRequires {
     pub range: AstRange,
     pub name: AstIdentifier,
     pub flags: AstModuleRequiresFlags,
 }
 bitflags! {
-   #[derive(Debug, Clone)]
+   #[derive(Debug, Clone, Serialize)]
    pub struct AstModuleRequiresFlags: u8 {
         const Transitive   = 0b0000_0001;
         const Static       = 0b0000_0010;
     }
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AstModuleProvides {
     pub range: AstRange,
     pub name: AstIdentifier,
     pub with: Vec<AstIdentifier>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstClass {
     pub range: AstRange,
     pub availability: AstAvailability,
@@ -186,7 +187,7 @@ pub struct AstClass {
     pub permits: Vec<AstJType>,
     pub block: AstClassBlock,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstRecord {
     pub range: AstRange,
     pub availability: AstAvailability,
@@ -199,12 +200,12 @@ pub struct AstRecord {
     pub implements: Vec<AstJType>,
     pub block: AstClassBlock,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstRecordEntries {
     pub range: AstRange,
     pub entries: Vec<AstRecordEntry>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstRecordEntry {
     pub range: AstRange,
     pub annotated: Vec<AstAnnotated>,
@@ -212,7 +213,7 @@ pub struct AstRecordEntry {
     pub variadic: bool,
     pub name: AstIdentifier,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstClassBlock {
     pub range: AstRange,
     pub variables: Vec<AstClassVariable>,
@@ -223,14 +224,14 @@ pub struct AstClassBlock {
     pub blocks: Vec<AstBlock>,
 }
 bitflags! {
-   #[derive(PartialEq, Eq, Debug, Clone)]
+   #[derive(PartialEq, Eq, Debug, Clone, Serialize)]
    pub struct AstVolatileTransient: u8 {
      const Volatile  = 0b0000_0001;
      const Transient = 0b0000_0010;
    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstClassVariable {
     pub range: AstRange,
     pub availability: AstAvailability,
@@ -241,18 +242,18 @@ pub struct AstClassVariable {
     pub volatile_transient: AstVolatileTransient,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstClassMethod {
     pub range: AstRange,
     pub header: AstMethodHeader,
     pub block: Option<AstBlock>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstStaticBlock {
     pub range: AstRange,
     pub block: AstBlock,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstMethodHeader {
     pub range: AstRange,
     pub availability: AstAvailability,
@@ -263,18 +264,18 @@ pub struct AstMethodHeader {
     pub type_parameters: Option<AstTypeParameters>,
     pub annotated: Vec<AstAnnotated>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstThrowsDeclaration {
     pub range: AstRange,
     pub parameters: Vec<AstJType>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstClassConstructor {
     pub range: AstRange,
     pub header: AstConstructorHeader,
     pub block: AstBlock,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstConstructorHeader {
     pub range: AstRange,
     pub availability: AstAvailability,
@@ -284,12 +285,12 @@ pub struct AstConstructorHeader {
     pub type_parameters: Option<AstTypeParameters>,
     pub annotated: Vec<AstAnnotated>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstMethodParameters {
     pub range: AstRange,
     pub parameters: Vec<AstMethodParameter>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstMethodParameter {
     pub range: AstRange,
     pub annotated: Vec<AstAnnotated>,
@@ -298,13 +299,13 @@ pub struct AstMethodParameter {
     pub flags: AstMethodParameterFlags,
 }
 bitflags! {
-   #[derive(Debug, Clone, PartialEq, Eq)]
+   #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
    pub struct AstMethodParameterFlags: u8 {
         const Fin       = 0b0000_0001;
         const Variatic  = 0b0000_0010;
     }
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstBlockEntry {
     Return(AstBlockReturn),
     Variable(Vec<AstBlockVariable>),
@@ -331,15 +332,60 @@ pub enum AstBlockEntry {
     Semicolon(AstRange),
     Assert(AstBlockAssert),
 }
+impl AstBlockEntry {
+    #[must_use]
+    pub fn range(&self) -> AstRange {
+        match self {
+            Self::Return(ret) => ret.range,
+            Self::Variable(variables) => match (variables.first(), variables.last()) {
+                (Some(first), Some(last)) => AstRange {
+                    start: first.range.start,
+                    end: last.range.end,
+                },
+                _ => AstRange::default(),
+            },
+            Self::Expression(expression) => expression.range,
+            Self::Assign(assign) => assign.range,
+            Self::If(
+                AstIf::If { range, .. } | AstIf::ElseIf { range, .. } | AstIf::Else { range, .. },
+            )
+            | Self::Semicolon(range) => *range,
+            Self::While(ast_while) => ast_while.range,
+            Self::For(ast_for) => ast_for.range,
+            Self::ForEnhanced(ast_for) => ast_for.range,
+            Self::Break(ast_break) => ast_break.range,
+            Self::Continue(ast_continue) => ast_continue.range,
+            Self::Switch(switch) => switch.range,
+            Self::SwitchCase(case) => case.range,
+            Self::SwitchDefault(default) => default.range,
+            Self::SwitchCaseArrowValues(case) => case.range,
+            Self::SwitchCaseArrowType(case) => case.range,
+            Self::SwitchCaseArrowDefault(case) => case.range,
+            Self::TryCatch(try_catch) => try_catch.range,
+            Self::Throw(throw) => throw.range,
+            Self::Yield(ast_yield) => ast_yield.range,
+            Self::SynchronizedBlock(synchronized) => synchronized.range,
+            Self::Thing(thing) => match &**thing {
+                AstThing::Class(class) => class.range,
+                AstThing::Record(record) => record.range,
+                AstThing::Interface(interface) => interface.range,
+                AstThing::Enumeration(enumeration) => enumeration.range,
+                AstThing::Annotation(annotation) => annotation.range,
+            },
+            Self::InlineBlock(inline) => inline.range,
+            Self::Assert(assert) => assert.range,
+        }
+    }
+}
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstWhile {
     pub range: AstRange,
     pub control: AstExpression,
     pub content: AstWhileContent,
     pub label: Option<AstIdentifier>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstFor {
     pub range: AstRange,
     pub vars: Vec<AstBlockEntry>,
@@ -348,55 +394,55 @@ pub struct AstFor {
     pub content: AstForContent,
     pub label: Option<AstIdentifier>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstSwitch {
     pub range: AstRange,
     pub check: AstExpression,
     pub block: AstBlock,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstSwitchCase {
     pub range: AstRange,
     pub expressions: Vec<AstExpressionOrDefault>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstExpressionOrDefault {
     Default,
     Expression(AstExpression),
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstSwitchCaseArrowValues {
     pub range: AstRange,
     pub values: Vec<AstExpressionOrDefault>,
     pub content: Box<AstSwitchCaseArrowContent>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstSwitchCaseArrowType {
     pub range: AstRange,
     pub var: AstSwitchCaseArrowVar,
     pub content: Box<AstSwitchCaseArrowContent>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstSwitchCaseArrowVar {
     pub range: AstRange,
     pub jtype: AstJType,
     pub name: AstIdentifier,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstSwitchCaseArrowDefault {
     pub range: AstRange,
     pub content: Box<AstSwitchCaseArrowContent>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstSwitchCaseArrowContent {
     Block(AstBlock),
     Entry(Box<AstBlockEntry>),
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstSwitchDefault {
     pub range: AstRange,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstForEnhanced {
     pub range: AstRange,
     pub var: Vec<AstBlockVariable>,
@@ -404,7 +450,7 @@ pub struct AstForEnhanced {
     pub content: AstForContent,
     pub label: Option<AstIdentifier>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstIf {
     If {
         range: AstRange,
@@ -423,35 +469,35 @@ pub enum AstIf {
         content: AstIfContent,
     },
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstWhileContent {
     None,
     Block(AstBlock),
     BlockEntry(Box<AstBlockEntry>),
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstIfContent {
     Block(AstBlock),
     BlockEntry(Box<AstBlockEntry>),
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstForContent {
     None,
     Block(AstBlock),
     BlockEntry(Box<AstBlockEntry>),
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstThrow {
     pub range: AstRange,
     pub expression: AstExpression,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstSynchronizedBlock {
     pub range: AstRange,
     pub expression: AstExpression,
     pub block: AstBlock,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstTryCatch {
     pub range: AstRange,
     pub resources_block: Option<AstBlock>,
@@ -459,35 +505,35 @@ pub struct AstTryCatch {
     pub cases: Vec<AstTryCatchCase>,
     pub finally_block: Option<AstBlock>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstTryCatchCase {
     pub range: AstRange,
     pub variable: AstBlockVariableMultiType,
     pub block: AstBlock,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBlockAssign {
     pub range: AstRange,
     pub key: AstExpression,
     pub expression: AstExpression,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBlockExpression {
     pub range: AstRange,
     pub value: AstExpression,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstInlineBlock {
     pub range: AstRange,
     pub label: Option<AstIdentifier>,
     pub block: AstBlock,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBlock {
     pub range: AstRange,
     pub entries: Vec<AstBlockEntry>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBlockVariable {
     pub range: AstRange,
     pub fin: bool,
@@ -497,7 +543,7 @@ pub struct AstBlockVariable {
     pub value: Option<AstExpression>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBlockVariableMultiType {
     pub range: AstRange,
     pub fin: bool,
@@ -507,40 +553,40 @@ pub struct AstBlockVariableMultiType {
     pub expression: Option<AstExpression>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBlockReturn {
     pub range: AstRange,
     pub expression: AstExpressionOrValue,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstExpressionOrValue {
     None,
     Expression(AstExpression),
     Value(AstValue),
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBlockYield {
     pub range: AstRange,
     pub expression: AstExpressionOrValue,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBlockBreak {
     pub range: AstRange,
     pub label: Option<AstIdentifier>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBlockAssert {
     pub range: AstRange,
     pub expression: AstExpression,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBlockContinue {
     pub range: AstRange,
     pub label: Option<AstIdentifier>,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
 pub struct AstIdentifier {
     pub range: AstRange,
     pub value: MyString,
@@ -569,41 +615,41 @@ impl From<&AstIdentifier> for MyString {
 //     }
 // }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstInt {
     pub range: AstRange,
     pub value: MyString,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstHexLiteral {
     pub range: AstRange,
     pub value: MyString,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBinaryLiteral {
     pub range: AstRange,
     pub value: MyString,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstDouble {
     pub range: AstRange,
     pub value: MyString,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstSuperClass {
     None,
     Name(AstIdentifier),
     JType(AstJType),
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 /// Usage of a Annotation
 pub struct AstAnnotated {
     pub range: AstRange,
     pub name: AstIdentifier,
     pub parameters: AstAnnotatedParameterKind,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstAnnotatedParameterKind {
     None,
     Parameter(Vec<AstAnnotatedParameter>),
@@ -611,7 +657,7 @@ pub enum AstAnnotatedParameterKind {
 }
 
 /// Definition of a new Annotation
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstAnnotation {
     pub range: AstRange,
     pub availability: AstAvailability,
@@ -621,7 +667,7 @@ pub struct AstAnnotation {
     pub fields: Vec<AstAnnotationField>,
     pub inner: Vec<AstThing>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstAnnotationField {
     pub range: AstRange,
     pub availability: AstAvailability,
@@ -631,7 +677,7 @@ pub struct AstAnnotationField {
     pub expression: Option<AstExpression>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstInterface {
     pub range: AstRange,
     pub availability: AstAvailability,
@@ -647,7 +693,7 @@ pub struct AstInterface {
     pub permits: Vec<AstJType>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstThing {
     Class(AstClass),
     Record(AstRecord),
@@ -656,13 +702,13 @@ pub enum AstThing {
     Annotation(AstAnnotation),
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
 pub struct AstJType {
     pub annotated: Vec<AstAnnotated>,
     pub range: AstRange,
     pub value: AstJTypeKind,
 }
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
 pub enum AstJTypeKind {
     #[default]
     Void,
@@ -740,12 +786,12 @@ impl fmt::Display for AstJTypeKind {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstValue {
     Variable(AstIdentifier),
     Nuget(AstValueNuget),
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstValueNuget {
     Int(AstInt),
     Long(AstInt),
@@ -762,7 +808,7 @@ pub enum AstValueNuget {
 }
 pub type AstExpression = Vec<AstExpressionKind>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstExpressionKind {
     Base(AstBaseExpression),
     Lambda(AstLambda),
@@ -787,13 +833,26 @@ impl AstExpressionKind {
             | Self::InstanceOf(_) => true,
         }
     }
+    #[must_use]
+    pub const fn range(&self) -> &AstRange {
+        match self {
+            Self::Base(base) => &base.range,
+            Self::Lambda(lambda) => &lambda.range,
+            Self::InlineSwitch(switch) => &switch.range,
+            Self::NewClass(new_class) => &new_class.range,
+            Self::Generics(generics) => &generics.range,
+            Self::Array(values) => &values.range,
+            Self::JType(jtype) => &jtype.range,
+            Self::InstanceOf(instance_of) => &instance_of.range,
+        }
+    }
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstJTypeExpression {
     pub range: AstRange,
     pub jtype: AstJType,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstInstanceOf {
     pub range: AstRange,
     pub annotated: Vec<AstAnnotated>,
@@ -801,14 +860,14 @@ pub struct AstInstanceOf {
     pub jtype: AstJType,
     pub variable: Option<AstIdentifier>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBaseExpression {
     pub range: AstRange,
     pub ident: Option<AstExpressionIdentifier>,
     pub values: Option<AstValues>,
     pub operator: AstExpressionOperator,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstExpressionIdentifier {
     Identifier(AstIdentifier),
     Nuget(AstValueNuget),
@@ -820,17 +879,17 @@ pub enum AstExpressionIdentifier {
     EmptyArrayAccess(AstRange),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstValues {
     pub range: AstRange,
     pub values: Vec<AstExpression>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstValuesWithAnnotated {
     pub range: AstRange,
     pub values: Vec<AstExpressionOrAnnotated>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstExpressionOrAnnotated {
     Expression(AstExpression),
     Annotated(AstAnnotated),
@@ -843,44 +902,44 @@ impl AstBaseExpression {
             || self.operator != AstExpressionOperator::None
     }
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstLambda {
     pub range: AstRange,
     pub parameters: AstLambdaParameters,
     pub rhs: AstLambdaRhs,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstLambdaRhs {
     None,
     Block(AstBlock),
     Expr(AstExpression),
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
 pub struct AstLambdaParameters {
     pub range: AstRange,
     pub values: Vec<AstLambdaParameter>,
 }
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
 pub struct AstLambdaParameter {
     pub range: AstRange,
     pub jtype: Option<AstJType>,
     pub name: AstIdentifier,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstNewClass {
     pub range: AstRange,
     pub jtype: AstJType,
     pub rhs: Box<AstNewRhs>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstGenerics {
     pub range: AstRange,
     pub jtypes: Vec<AstJType>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstNewRhs {
     None,
     ArrayParameters(Vec<Vec<AstExpression>>),
@@ -890,13 +949,13 @@ pub enum AstNewRhs {
     Array(AstValues),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstBoolean {
     pub range: AstRange,
     pub value: bool,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum AstExpressionOperator {
     None,
     Plus(AstRange),
@@ -935,24 +994,24 @@ pub enum AstExpressionOperator {
     Caret(AstRange),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstTypeParameters {
     pub range: AstRange,
     pub parameters: Vec<AstTypeParameter>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstTypeParameter {
     pub range: AstRange,
     pub annotated: Vec<AstAnnotated>,
     pub name: AstIdentifier,
     pub supperclass: Option<Vec<AstSuperClass>>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstExtends {
     pub range: AstRange,
     pub parameters: Vec<AstJType>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstInterfaceConstant {
     pub range: AstRange,
     pub annotated: Vec<AstAnnotated>,
@@ -961,13 +1020,13 @@ pub struct AstInterfaceConstant {
     pub jtype: AstJType,
     pub expression: Option<AstExpression>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstInterfaceMethod {
     pub range: AstRange,
     pub annotated: Vec<AstAnnotated>,
     pub header: AstMethodHeader,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstInterfaceMethodDefault {
     pub range: AstRange,
     pub annotated: Vec<AstAnnotated>,
@@ -975,7 +1034,7 @@ pub struct AstInterfaceMethodDefault {
     pub block: AstBlock,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstEnumeration {
     pub range: AstRange,
     pub availability: AstAvailability,
@@ -992,7 +1051,7 @@ pub struct AstEnumeration {
     pub static_blocks: Vec<AstStaticBlock>,
     pub inner: Vec<AstThing>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct AstEnumerationVariant {
     pub range: AstRange,
     pub annotated: Vec<AstAnnotated>,
@@ -1000,7 +1059,7 @@ pub struct AstEnumerationVariant {
     pub parameters: Vec<AstExpression>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AstAnnotatedParameter {
     Expression(AstExpression),
     NamedExpression {
@@ -0,0 +1,718 @@
+//! Walk the ast
+//!
+//! Implement [`Visitor`] and override only the nodes of interest. Every default
+//! method calls the matching `walk_*` function so an override can still descend
+//! into the children by calling it.
+use crate::types::{
+    AstAnnotated, AstAnnotatedParameter, AstAnnotatedParameterKind, AstAnnotation, AstBlock,
+    AstBlockEntry, AstBlockVariable, AstClass, AstClassBlock, AstClassConstructor, AstClassMethod,
+    AstClassVariable, AstEnumeration, AstEnumerationVariant, AstExpressionIdentifier,
+    AstExpressionKind, AstExpressionOrAnnotated, AstExpressionOrDefault, AstExpressionOrValue,
+    AstFile, AstForContent, AstIdentifier, AstIf, AstIfContent, AstInterface, AstInterfaceConstant,
+    AstInterfaceMethod, AstInterfaceMethodDefault, AstJType, AstJTypeKind, AstLambda, AstLambdaRhs,
+    AstMethodHeader, AstNewClass, AstNewRhs, AstRecord, AstSuperClass, AstSwitchCaseArrowContent,
+    AstThing, AstTopLevel, AstTryCatchCase, AstTypeParameters, AstValue, AstWhileContent,
+};
+
+/// Visit nodes of an [`AstFile`]
+pub trait Visitor<'a>: Sized {
+    /// Visit a file
+    fn visit_file(&mut self, file: &'a AstFile) {
+        walk_file(self, file);
+    }
+    /// Visit a class, record, interface, enumeration or annotation
+    fn visit_thing(&mut self, thing: &'a AstThing) {
+        walk_thing(self, thing);
+    }
+    /// Visit the body of a class or record
+    fn visit_class_block(&mut self, block: &'a AstClassBlock) {
+        walk_class_block(self, block);
+    }
+    /// Visit a field
+    fn visit_class_variable(&mut self, variable: &'a AstClassVariable) {
+        walk_class_variable(self, variable);
+    }
+    /// Visit a method with optional body
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        walk_class_method(self, method);
+    }
+    /// Visit a constructor
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        walk_class_constructor(self, constructor);
+    }
+    /// Visit a abstract interface method
+    fn visit_interface_method(&mut self, method: &'a AstInterfaceMethod) {
+        walk_method_header(self, &method.header);
+    }
+    /// Visit a default interface method
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        walk_interface_method_default(self, method);
+    }
+    /// Visit a interface constant
+    fn visit_interface_constant(&mut self, constant: &'a AstInterfaceConstant) {
+        walk_interface_constant(self, constant);
+    }
+    /// Visit a enum variant
+    fn visit_enumeration_variant(&mut self, variant: &'a AstEnumerationVariant) {
+        walk_enumeration_variant(self, variant);
+    }
+    /// Visit a block `{ ... }`
+    fn visit_block(&mut self, block: &'a AstBlock) {
+        walk_block(self, block);
+    }
+    /// Visit a statement
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        walk_block_entry(self, entry);
+    }
+    /// Visit a local variable declaration
+    fn visit_block_variable(&mut self, variable: &'a AstBlockVariable) {
+        walk_block_variable(self, variable);
+    }
+    /// Visit a catch clause
+    fn visit_try_catch_case(&mut self, case: &'a AstTryCatchCase) {
+        walk_try_catch_case(self, case);
+    }
+    /// Visit a expression
+    fn visit_expression(&mut self, expression: &'a [AstExpressionKind]) {
+        walk_expression(self, expression);
+    }
+    /// Visit a part of a expression
+    fn visit_expression_kind(&mut self, kind: &'a AstExpressionKind) {
+        walk_expression_kind(self, kind);
+    }
+    /// Visit a identifier that is used inside of a expression
+    fn visit_expression_identifier(&mut self, ident: &'a AstExpressionIdentifier) {
+        walk_expression_identifier(self, ident);
+    }
+    /// Visit a identifier that can be a variable or a class
+    fn visit_identifier(&mut self, _ident: &'a AstIdentifier) {}
+    /// Visit a lambda
+    fn visit_lambda(&mut self, lambda: &'a AstLambda) {
+        walk_lambda(self, lambda);
+    }
+    /// Visit `new ...`
+    fn visit_new_class(&mut self, new_class: &'a AstNewClass) {
+        walk_new_class(self, new_class);
+    }
+    /// Visit a type usage
+    fn visit_jtype(&mut self, jtype: &'a AstJType) {
+        walk_jtype(self, jtype);
+    }
+    /// Visit a annotation usage
+    fn visit_annotated(&mut self, annotated: &'a AstAnnotated) {
+        walk_annotated(self, annotated);
+    }
+}
+
+/// Walk all top level items
+pub fn walk_file<'a, V: Visitor<'a>>(v: &mut V, file: &'a AstFile) {
+    for top in &file.top {
+        match top {
+            AstTopLevel::Thing(thing) => v.visit_thing(thing),
+            AstTopLevel::Method(method) => v.visit_class_method(method),
+            AstTopLevel::Package(package) => walk_annotated_list(v, &package.annotated),
+            AstTopLevel::Import(_) | AstTopLevel::Module(_) => (),
+        }
+    }
+}
+
+/// Walk children of a thing
+pub fn walk_thing<'a, V: Visitor<'a>>(v: &mut V, thing: &'a AstThing) {
+    match thing {
+        AstThing::Class(class) => walk_class(v, class),
+        AstThing::Record(record) => walk_record(v, record),
+        AstThing::Interface(interface) => walk_interface(v, interface),
+        AstThing::Enumeration(enumeration) => walk_enumeration(v, enumeration),
+        AstThing::Annotation(annotation) => walk_annotation(v, annotation),
+    }
+}
+
+fn walk_class<'a, V: Visitor<'a>>(v: &mut V, class: &'a AstClass) {
+    walk_annotated_list(v, &class.annotated);
+    walk_type_parameters(v, class.type_parameters.as_ref());
+    walk_superclasses(v, &class.superclass);
+    walk_jtypes(v, &class.implements);
+    walk_jtypes(v, &class.permits);
+    v.visit_class_block(&class.block);
+}
+
+fn walk_record<'a, V: Visitor<'a>>(v: &mut V, record: &'a AstRecord) {
+    walk_annotated_list(v, &record.annotated);
+    walk_type_parameters(v, record.type_parameters.as_ref());
+    for entry in &record.record_entries.entries {
+        walk_annotated_list(v, &entry.annotated);
+        v.visit_jtype(&entry.jtype);
+    }
+    walk_superclasses(v, &record.superclass);
+    walk_jtypes(v, &record.implements);
+    v.visit_class_block(&record.block);
+}
+
+fn walk_interface<'a, V: Visitor<'a>>(v: &mut V, interface: &'a AstInterface) {
+    walk_annotated_list(v, &interface.annotated);
+    walk_type_parameters(v, interface.type_parameters.as_ref());
+    if let Some(extends) = &interface.extends {
+        walk_jtypes(v, &extends.parameters);
+    }
+    walk_jtypes(v, &interface.permits);
+    for constant in &interface.constants {
+        v.visit_interface_constant(constant);
+    }
+    for method in &interface.methods {
+        v.visit_interface_method(method);
+    }
+    for method in &interface.default_methods {
+        v.visit_interface_method_default(method);
+    }
+    for inner in &interface.inner {
+        v.visit_thing(inner);
+    }
+}
+
+fn walk_enumeration<'a, V: Visitor<'a>>(v: &mut V, enumeration: &'a AstEnumeration) {
+    walk_annotated_list(v, &enumeration.annotated);
+    walk_superclasses(v, &enumeration.superclass);
+    walk_jtypes(v, &enumeration.implements);
+    walk_jtypes(v, &enumeration.permits);
+    for variant in &enumeration.variants {
+        v.visit_enumeration_variant(variant);
+    }
+    for variable in &enumeration.variables {
+        v.visit_class_variable(variable);
+    }
+    for constructor in &enumeration.constructors {
+        v.visit_class_constructor(constructor);
+    }
+    for method in &enumeration.methods {
+        v.visit_class_method(method);
+    }
+    for static_block in &enumeration.static_blocks {
+        v.visit_block(&static_block.block);
+    }
+    for inner in &enumeration.inner {
+        v.visit_thing(inner);
+    }
+}
+
+fn walk_annotation<'a, V: Visitor<'a>>(v: &mut V, annotation: &'a AstAnnotation) {
+    walk_annotated_list(v, &annotation.annotated);
+    for field in &annotation.fields {
+        walk_annotated_list(v, &field.annotated);
+        v.visit_jtype(&field.jtype);
+        if let Some(expression) = &field.expression {
+            v.visit_expression(expression);
+        }
+    }
+    for inner in &annotation.inner {
+        v.visit_thing(inner);
+    }
+}
+
+/// Walk members of a class body
+pub fn walk_class_block<'a, V: Visitor<'a>>(v: &mut V, block: &'a AstClassBlock) {
+    for variable in &block.variables {
+        v.visit_class_variable(variable);
+    }
+    for constructor in &block.constructors {
+        v.visit_class_constructor(constructor);
+    }
+    for method in &block.methods {
+        v.visit_class_method(method);
+    }
+    for static_block in &block.static_blocks {
+        v.visit_block(&static_block.block);
+    }
+    for b in &block.blocks {
+        v.visit_block(b);
+    }
+    for inner in &block.inner {
+        v.visit_thing(inner);
+    }
+}
+
+/// Walk type and initializer of a field
+pub fn walk_class_variable<'a, V: Visitor<'a>>(v: &mut V, variable: &'a AstClassVariable) {
+    walk_annotated_list(v, &variable.annotated);
+    v.visit_jtype(&variable.jtype);
+    if let Some(expression) = &variable.expression {
+        v.visit_expression(expression);
+    }
+}
+
+/// Walk header and body of a method
+pub fn walk_class_method<'a, V: Visitor<'a>>(v: &mut V, method: &'a AstClassMethod) {
+    walk_method_header(v, &method.header);
+    if let Some(block) = &method.block {
+        v.visit_block(block);
+    }
+}
+
+/// Walk types used in a method header
+pub fn walk_method_header<'a, V: Visitor<'a>>(v: &mut V, header: &'a AstMethodHeader) {
+    walk_annotated_list(v, &header.annotated);
+    walk_type_parameters(v, header.type_parameters.as_ref());
+    v.visit_jtype(&header.jtype);
+    for parameter in &header.parameters.parameters {
+        walk_annotated_list(v, &parameter.annotated);
+        v.visit_jtype(&parameter.jtype);
+    }
+    if let Some(throws) = &header.throws {
+        walk_jtypes(v, &throws.parameters);
+    }
+}
+
+/// Walk header and body of a constructor
+pub fn walk_class_constructor<'a, V: Visitor<'a>>(v: &mut V, constructor: &'a AstClassConstructor) {
+    let header = &constructor.header;
+    walk_annotated_list(v, &header.annotated);
+    walk_type_parameters(v, header.type_parameters.as_ref());
+    for parameter in &header.parameters.parameters {
+        walk_annotated_list(v, &parameter.annotated);
+        v.visit_jtype(&parameter.jtype);
+    }
+    if let Some(throws) = &header.throws {
+        walk_jtypes(v, &throws.parameters);
+    }
+    v.visit_block(&constructor.block);
+}
+
+/// Walk header and body of a default method
+pub fn walk_interface_method_default<'a, V: Visitor<'a>>(
+    v: &mut V,
+    method: &'a AstInterfaceMethodDefault,
+) {
+    walk_method_header(v, &method.header);
+    v.visit_block(&method.block);
+}
+
+/// Walk type and value of a interface constant
+pub fn walk_interface_constant<'a, V: Visitor<'a>>(v: &mut V, constant: &'a AstInterfaceConstant) {
+    walk_annotated_list(v, &constant.annotated);
+    v.visit_jtype(&constant.jtype);
+    if let Some(expression) = &constant.expression {
+        v.visit_expression(expression);
+    }
+}
+
+/// Walk arguments of a enum variant
+pub fn walk_enumeration_variant<'a, V: Visitor<'a>>(v: &mut V, variant: &'a AstEnumerationVariant) {
+    walk_annotated_list(v, &variant.annotated);
+    for parameter in &variant.parameters {
+        v.visit_expression(parameter);
+    }
+}
+
+/// Walk all statements of a block
+pub fn walk_block<'a, V: Visitor<'a>>(v: &mut V, block: &'a AstBlock) {
+    for entry in &block.entries {
+        v.visit_block_entry(entry);
+    }
+}
+
+/// Walk children of a statement
+pub fn walk_block_entry<'a, V: Visitor<'a>>(v: &mut V, entry: &'a AstBlockEntry) {
+    match entry {
+        AstBlockEntry::Return(ret) => walk_expression_or_value(v, &ret.expression),
+        AstBlockEntry::Yield(y) => walk_expression_or_value(v, &y.expression),
+        AstBlockEntry::Variable(variables) => {
+            for variable in variables {
+                v.visit_block_variable(variable);
+            }
+        }
+        AstBlockEntry::Expression(expression) => v.visit_expression(&expression.value),
+        AstBlockEntry::Assign(assign) => {
+            v.visit_expression(&assign.key);
+            v.visit_expression(&assign.expression);
+        }
+        AstBlockEntry::If(ast_if) => match ast_if {
+            AstIf::If {
+                control, content, ..
+            }
+            | AstIf::ElseIf {
+                control, content, ..
+            } => {
+                v.visit_expression(control);
+                walk_if_content(v, content);
+            }
+            AstIf::Else { content, .. } => walk_if_content(v, content),
+        },
+        AstBlockEntry::While(ast_while) => {
+            v.visit_expression(&ast_while.control);
+            match &ast_while.content {
+                AstWhileContent::None => (),
+                AstWhileContent::Block(block) => v.visit_block(block),
+                AstWhileContent::BlockEntry(entry) => v.visit_block_entry(entry),
+            }
+        }
+        AstBlockEntry::For(ast_for) => {
+            for entry in &ast_for.vars {
+                v.visit_block_entry(entry);
+            }
+            for entry in &ast_for.check {
+                v.visit_block_entry(entry);
+            }
+            for entry in &ast_for.changes {
+                v.visit_block_entry(entry);
+            }
+            walk_for_content(v, &ast_for.content);
+        }
+        AstBlockEntry::ForEnhanced(ast_for) => {
+            for variable in &ast_for.var {
+                v.visit_block_variable(variable);
+            }
+            v.visit_expression(&ast_for.rhs);
+            walk_for_content(v, &ast_for.content);
+        }
+        AstBlockEntry::Switch(switch) => {
+            v.visit_expression(&switch.check);
+            v.visit_block(&switch.block);
+        }
+        AstBlockEntry::SwitchCase(case) => walk_expression_or_defaults(v, &case.expressions),
+        AstBlockEntry::SwitchCaseArrowValues(case) => {
+            walk_expression_or_defaults(v, &case.values);
+            walk_switch_case_arrow_content(v, &case.content);
+        }
+        AstBlockEntry::SwitchCaseArrowType(case) => {
+            v.visit_jtype(&case.var.jtype);
+            walk_switch_case_arrow_content(v, &case.content);
+        }
+        AstBlockEntry::SwitchCaseArrowDefault(case) => {
+            walk_switch_case_arrow_content(v, &case.content);
+        }
+        AstBlockEntry::TryCatch(try_catch) => {
+            if let Some(resources) = &try_catch.resources_block {
+                v.visit_block(resources);
+            }
+            v.visit_block(&try_catch.block);
+            for case in &try_catch.cases {
+                v.visit_try_catch_case(case);
+            }
+            if let Some(finally) = &try_catch.finally_block {
+                v.visit_block(finally);
+            }
+        }
+        AstBlockEntry::Throw(throw) => v.visit_expression(&throw.expression),
+        AstBlockEntry::SynchronizedBlock(synchronized) => {
+            v.visit_expression(&synchronized.expression);
+            v.visit_block(&synchronized.block);
+        }
+        AstBlockEntry::Thing(thing) => v.visit_thing(thing),
+        AstBlockEntry::InlineBlock(inline) => v.visit_block(&inline.block),
+        AstBlockEntry::Assert(assert) => v.visit_expression(&assert.expression),
+        AstBlockEntry::Break(_)
+        | AstBlockEntry::Continue(_)
+        | AstBlockEntry::SwitchDefault(_)
+        | AstBlockEntry::Semicolon(_) => (),
+    }
+}
+
+/// Walk type and initializer of a local variable
+pub fn walk_block_variable<'a, V: Visitor<'a>>(v: &mut V, variable: &'a AstBlockVariable) {
+    walk_annotated_list(v, &variable.annotated);
+    v.visit_jtype(&variable.jtype);
+    if let Some(value) = &variable.value {
+        v.visit_expression(value);
+    }
+}
+
+/// Walk types and block of a catch clause
+pub fn walk_try_catch_case<'a, V: Visitor<'a>>(v: &mut V, case: &'a AstTryCatchCase) {
+    walk_annotated_list(v, &case.variable.annotated);
+    walk_jtypes(v, &case.variable.jtypes);
+    v.visit_block(&case.block);
+}
+
+/// Walk all parts of a expression
+pub fn walk_expression<'a, V: Visitor<'a>>(v: &mut V, expression: &'a [AstExpressionKind]) {
+    for kind in expression {
+        v.visit_expression_kind(kind);
+    }
+}
+
+/// Walk children of a expression part
+pub fn walk_expression_kind<'a, V: Visitor<'a>>(v: &mut V, kind: &'a AstExpressionKind) {
+    match kind {
+        AstExpressionKind::Base(base) => {
+            if let Some(ident) = &base.ident {
+                v.visit_expression_identifier(ident);
+            }
+            if let Some(values) = &base.values {
+                for value in &values.values {
+                    v.visit_expression(value);
+                }
+            }
+        }
+        AstExpressionKind::Lambda(lambda) => v.visit_lambda(lambda),
+        AstExpressionKind::InlineSwitch(switch) => {
+            v.visit_expression(&switch.check);
+            v.visit_block(&switch.block);
+        }
+        AstExpressionKind::NewClass(new_class) => v.visit_new_class(new_class),
+        AstExpressionKind::Generics(generics) => walk_jtypes(v, &generics.jtypes),
+        AstExpressionKind::Array(values) => {
+            for value in &values.values {
+                v.visit_expression(value);
+            }
+        }
+        AstExpressionKind::JType(jtype) => v.visit_jtype(&jtype.jtype),
+        AstExpressionKind::InstanceOf(instance_of) => {
+            walk_annotated_list(v, &instance_of.annotated);
+            v.visit_jtype(&instance_of.jtype);
+        }
+    }
+}
+
+/// Walk identifiers and array access of a expression identifier
+pub fn walk_expression_identifier<'a, V: Visitor<'a>>(
+    v: &mut V,
+    ident: &'a AstExpressionIdentifier,
+) {
+    match ident {
+        AstExpressionIdentifier::Identifier(identifier)
+        | AstExpressionIdentifier::Value(AstValue::Variable(identifier)) => {
+            v.visit_identifier(identifier);
+        }
+        AstExpressionIdentifier::ArrayAccess { expr, .. } => v.visit_expression(expr),
+        AstExpressionIdentifier::Nuget(_)
+        | AstExpressionIdentifier::Value(AstValue::Nuget(_))
+        | AstExpressionIdentifier::EmptyArrayAccess(_) => (),
+    }
+}
+
+/// Walk parameters and body of a lambda
+pub fn walk_lambda<'a, V: Visitor<'a>>(v: &mut V, lambda: &'a AstLambda) {
+    for parameter in &lambda.parameters.values {
+        if let Some(jtype) = &parameter.jtype {
+            v.visit_jtype(jtype);
+        }
+    }
+    match &lambda.rhs {
+        AstLambdaRhs::None => (),
+        AstLambdaRhs::Block(block) => v.visit_block(block),
+        AstLambdaRhs::Expr(expression) => v.visit_expression(expression),
+    }
+}
+
+/// Walk type, arguments and anonymous body of `new ...`
+pub fn walk_new_class<'a, V: Visitor<'a>>(v: &mut V, new_class: &'a AstNewClass) {
+    v.visit_jtype(&new_class.jtype);
+    match &*new_class.rhs {
+        AstNewRhs::None => (),
+        AstNewRhs::ArrayParameters(parameters) => {
+            for dimension in parameters {
+                for expression in dimension {
+                    v.visit_expression(expression);
+                }
+            }
+        }
+        AstNewRhs::Parameters(_, parameters) => {
+            for expression in parameters {
+                v.visit_expression(expression);
+            }
+        }
+        AstNewRhs::Block(block) => v.visit_class_block(block),
+        AstNewRhs::ParametersAndBlock(_, parameters, block) => {
+            for expression in parameters {
+                v.visit_expression(expression);
+            }
+            v.visit_class_block(block);
+        }
+        AstNewRhs::Array(values) => {
+            for value in &values.values {
+                v.visit_expression(value);
+            }
+        }
+    }
+}
+
+/// Walk nested types like generic arguments
+pub fn walk_jtype<'a, V: Visitor<'a>>(v: &mut V, jtype: &'a AstJType) {
+    walk_annotated_list(v, &jtype.annotated);
+    match &jtype.value {
+        AstJTypeKind::Array(inner) => v.visit_jtype(inner),
+        AstJTypeKind::Generic(_, args) => walk_jtypes(v, args),
+        AstJTypeKind::Access { base, inner } => {
+            v.visit_jtype(base);
+            v.visit_jtype(inner);
+        }
+        AstJTypeKind::Void
+        | AstJTypeKind::Byte
+        | AstJTypeKind::Char
+        | AstJTypeKind::Double
+        | AstJTypeKind::Float
+        | AstJTypeKind::Int
+        | AstJTypeKind::Long
+        | AstJTypeKind::Short
+        | AstJTypeKind::Boolean
+        | AstJTypeKind::Wildcard
+        | AstJTypeKind::Class(_)
+        | AstJTypeKind::ClassOrPackage(_)
+        | AstJTypeKind::Var => (),
+    }
+}
+
+/// Walk parameters of a annotation usage
+pub fn walk_annotated<'a, V: Visitor<'a>>(v: &mut V, annotated: &'a AstAnnotated) {
+    match &annotated.parameters {
+        AstAnnotatedParameterKind::None => (),
+        AstAnnotatedParameterKind::Parameter(parameters) => {
+            for parameter in parameters {
+                match parameter {
+                    AstAnnotatedParameter::Expression(expression)
+                    | AstAnnotatedParameter::NamedExpression { expression, .. } => {
+                        v.visit_expression(expression);
+                    }
+                    AstAnnotatedParameter::Annotated(annotated)
+                    | AstAnnotatedParameter::NamedAnnotated { annotated, .. } => {
+                        v.visit_annotated(annotated);
+                    }
+                    AstAnnotatedParameter::NamedArray { values, .. } => {
+                        walk_values_with_annotated(v, &values.values);
+                    }
+                }
+            }
+        }
+        AstAnnotatedParameterKind::Array(values) => {
+            walk_values_with_annotated(v, &values.values);
+        }
+    }
+}
+
+fn walk_values_with_annotated<'a, V: Visitor<'a>>(
+    v: &mut V,
+    values: &'a [AstExpressionOrAnnotated],
+) {
+    for value in values {
+        match value {
+            AstExpressionOrAnnotated::Expression(expression) => v.visit_expression(expression),
+            AstExpressionOrAnnotated::Annotated(annotated) => v.visit_annotated(annotated),
+        }
+    }
+}
+
+fn walk_annotated_list<'a, V: Visitor<'a>>(v: &mut V, annotated: &'a [AstAnnotated]) {
+    for a in annotated {
+        v.visit_annotated(a);
+    }
+}
+
+fn walk_jtypes<'a, V: Visitor<'a>>(v: &mut V, jtypes: &'a [AstJType]) {
+    for jtype in jtypes {
+        v.visit_jtype(jtype);
+    }
+}
+
+fn walk_superclasses<'a, V: Visitor<'a>>(v: &mut V, superclasses: &'a [AstSuperClass]) {
+    for superclass in superclasses {
+        match superclass {
+            AstSuperClass::None | AstSuperClass::Name(_) => (),
+            AstSuperClass::JType(jtype) => v.visit_jtype(jtype),
+        }
+    }
+}
+
+fn walk_type_parameters<'a, V: Visitor<'a>>(
+    v: &mut V,
+    type_parameters: Option<&'a AstTypeParameters>,
+) {
+    let Some(type_parameters) = type_parameters else {
+        return;
+    };
+    for parameter in &type_parameters.parameters {
+        walk_annotated_list(v, &parameter.annotated);
+        if let Some(superclasses) = &parameter.supperclass {
+            walk_superclasses(v, superclasses);
+        }
+    }
+}
+
+fn walk_expression_or_value<'a, V: Visitor<'a>>(v: &mut V, value: &'a AstExpressionOrValue) {
+    match value {
+        AstExpressionOrValue::None | AstExpressionOrValue::Value(AstValue::Nuget(_)) => (),
+        AstExpressionOrValue::Expression(expression) => v.visit_expression(expression),
+        AstExpressionOrValue::Value(AstValue::Variable(identifier)) => {
+            v.visit_identifier(identifier);
+        }
+    }
+}
+
+fn walk_expression_or_defaults<'a, V: Visitor<'a>>(
+    v: &mut V,
+    values: &'a [AstExpressionOrDefault],
+) {
+    for value in values {
+        match value {
+            AstExpressionOrDefault::Default => (),
+            AstExpressionOrDefault::Expression(expression) => v.visit_expression(expression),
+        }
+    }
+}
+
+fn walk_if_content<'a, V: Visitor<'a>>(v: &mut V, content: &'a AstIfContent) {
+    match content {
+        AstIfContent::Block(block) => v.visit_block(block),
+        AstIfContent::BlockEntry(entry) => v.visit_block_entry(entry),
+    }
+}
+
+fn walk_for_content<'a, V: Visitor<'a>>(v: &mut V, content: &'a AstForContent) {
+    match content {
+        AstForContent::None => (),
+        AstForContent::Block(block) => v.visit_block(block),
+        AstForContent::BlockEntry(entry) => v.visit_block_entry(entry),
+    }
+}
+
+fn walk_switch_case_arrow_content<'a, V: Visitor<'a>>(
+    v: &mut V,
+    content: &'a AstSwitchCaseArrowContent,
+) {
+    match content {
+        AstSwitchCaseArrowContent::Block(block) => v.visit_block(block),
+        AstSwitchCaseArrowContent::Entry(entry) => v.visit_block_entry(entry),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer, parse_file};
+
+    #[derive(Default)]
+    struct Lambdas {
+        count: usize,
+        identifiers: Vec<String>,
+    }
+    impl<'a> Visitor<'a> for Lambdas {
+        fn visit_lambda(&mut self, lambda: &'a AstLambda) {
+            self.count += 1;
+            walk_lambda(self, lambda);
+        }
+        fn visit_identifier(&mut self, ident: &'a AstIdentifier) {
+            self.identifiers.push(ident.value.to_string());
+        }
+    }
+
+    #[test]
+    fn nested_lambdas() {
+        let content = r"
+class A {
+    Runnable r = () -> other(x -> y);
+    void m() {
+        if (a) {
+            list.forEach(i -> { run(() -> i); });
+        }
+    }
+}
+";
+        let tokens = lexer::lex(content.as_bytes()).expect("lex");
+        let file = parse_file(&tokens).expect("parse");
+        let mut v = Lambdas::default();
+        v.visit_file(&file);
+        assert_eq!(v.count, 4);
+        assert_eq!(
+            v.identifiers,
+            vec!["other", "y", "a", "list", "forEach", "run", "i"]
+        );
+    }
+}
@@ -357,8 +357,8 @@ fn everything() {
                                                                     Base(
                                                                         AstBaseExpression {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 20:16 },
-                                                                                end: AstPoint { 20:17 },
+                                                                                start: AstPoint { 20:15 },
+                                                                                end: AstPoint { 20:16 },
                                                                             },
                                                                             ident: Some(
                                                                                 Value(
@@ -366,8 +366,8 @@ fn everything() {
                                                                                         Int(
                                                                                             AstInt {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 20:16 },
-                                                                                                    end: AstPoint { 20:17 },
+                                                                                                    start: AstPoint { 20:15 },
+                                                                                                    end: AstPoint { 20:16 },
                                                                                                 },
                                                                                                 value: "0",
                                                                                             },
@@ -1392,8 +1392,8 @@ fn locale_variable_table() {
                                                                                         Base(
                                                                                             AstBaseExpression {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 8:11 },
-                                                                                                    end: AstPoint { 8:12 },
+                                                                                                    start: AstPoint { 8:10 },
+                                                                                                    end: AstPoint { 8:11 },
                                                                                                 },
                                                                                                 ident: Some(
                                                                                                     Value(
@@ -1401,8 +1401,8 @@ fn locale_variable_table() {
                                                                                                             Int(
                                                                                                                 AstInt {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 8:11 },
-                                                                                                                        end: AstPoint { 8:12 },
+                                                                                                                        start: AstPoint { 8:10 },
+                                                                                                                        end: AstPoint { 8:11 },
                                                                                                                     },
                                                                                                                     value: "1",
                                                                                                                 },
@@ -1419,8 +1419,8 @@ fn locale_variable_table() {
                                                                                         Base(
                                                                                             AstBaseExpression {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 8:14 },
-                                                                                                    end: AstPoint { 8:14 },
+                                                                                                    start: AstPoint { 8:13 },
+                                                                                                    end: AstPoint { 8:15 },
                                                                                                 },
                                                                                                 ident: Some(
                                                                                                     Value(
@@ -1428,8 +1428,8 @@ fn locale_variable_table() {
                                                                                                             StringLiteral {
                                                                                                                 value: AstIdentifier {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 8:14 },
-                                                                                                                        end: AstPoint { 8:12 },
+                                                                                                                        start: AstPoint { 8:13 },
+                                                                                                                        end: AstPoint { 8:15 },
                                                                                                                     },
                                                                                                                     value: "",
                                                                                                                 },
@@ -1690,8 +1690,8 @@ fn locale_variable_table() {
                                                                     Base(
                                                                         AstBaseExpression {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 12:16 },
-                                                                                end: AstPoint { 12:17 },
+                                                                                start: AstPoint { 12:15 },
+                                                                                end: AstPoint { 12:16 },
                                                                             },
                                                                             ident: Some(
                                                                                 Value(
@@ -1699,8 +1699,8 @@ fn locale_variable_table() {
                                                                                         Int(
                                                                                             AstInt {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 12:16 },
-                                                                                                    end: AstPoint { 12:17 },
+                                                                                                    start: AstPoint { 12:15 },
+                                                                                                    end: AstPoint { 12:16 },
                                                                                                 },
                                                                                                 value: "1",
                                                                                             },
@@ -1970,7 +1970,7 @@ fn constants() {
                                             Base(
                                                 AstBaseExpression {
                                                     range: AstRange {
-                                                        start: AstPoint { 8:33 },
+                                                        start: AstPoint { 8:31 },
                                                         end: AstPoint { 8:34 },
                                                     },
                                                     ident: Some(
@@ -1979,8 +1979,8 @@ fn constants() {
                                                                 StringLiteral {
                                                                     value: AstIdentifier {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 8:33 },
-                                                                            end: AstPoint { 8:31 },
+                                                                            start: AstPoint { 8:31 },
+                                                                            end: AstPoint { 8:34 },
                                                                         },
                                                                         value: "A",
                                                                     },
@@ -2033,7 +2033,7 @@ fn constants() {
                                             Base(
                                                 AstBaseExpression {
                                                     range: AstRange {
-                                                        start: AstPoint { 9:26 },
+                                                        start: AstPoint { 9:24 },
                                                         end: AstPoint { 9:27 },
                                                     },
                                                     ident: Some(
@@ -2042,8 +2042,8 @@ fn constants() {
                                                                 StringLiteral {
                                                                     value: AstIdentifier {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 9:26 },
-                                                                            end: AstPoint { 9:24 },
+                                                                            start: AstPoint { 9:24 },
+                                                                            end: AstPoint { 9:27 },
                                                                         },
                                                                         value: "B",
                                                                     },
@@ -2096,7 +2096,7 @@ fn constants() {
                                             Base(
                                                 AstBaseExpression {
                                                     range: AstRange {
-                                                        start: AstPoint { 11:26 },
+                                                        start: AstPoint { 11:24 },
                                                         end: AstPoint { 11:27 },
                                                     },
                                                     ident: Some(
@@ -2105,8 +2105,8 @@ fn constants() {
                                                                 StringLiteral {
                                                                     value: AstIdentifier {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 11:26 },
-                                                                            end: AstPoint { 11:24 },
+                                                                            start: AstPoint { 11:24 },
+                                                                            end: AstPoint { 11:27 },
                                                                         },
                                                                         value: "C",
                                                                     },
@@ -3212,7 +3212,7 @@ fn variants() {
                                             Base(
                                                 AstBaseExpression {
                                                     range: AstRange {
-                                                        start: AstPoint { 2:8 },
+                                                        start: AstPoint { 2:6 },
                                                         end: AstPoint { 2:9 },
                                                     },
                                                     ident: Some(
@@ -3221,8 +3221,8 @@ fn variants() {
                                                                 StringLiteral {
                                                                     value: AstIdentifier {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 2:8 },
-                                                                            end: AstPoint { 2:6 },
+                                                                            start: AstPoint { 2:6 },
+                                                                            end: AstPoint { 2:9 },
                                                                         },
                                                                         value: "a",
                                                                     },
@@ -3256,7 +3256,7 @@ fn variants() {
                                             Base(
                                                 AstBaseExpression {
                                                     range: AstRange {
-                                                        start: AstPoint { 3:8 },
+                                                        start: AstPoint { 3:6 },
                                                         end: AstPoint { 3:9 },
                                                     },
                                                     ident: Some(
@@ -3265,8 +3265,8 @@ fn variants() {
                                                                 StringLiteral {
                                                                     value: AstIdentifier {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 3:8 },
-                                                                            end: AstPoint { 3:6 },
+                                                                            start: AstPoint { 3:6 },
+                                                                            end: AstPoint { 3:9 },
                                                                         },
                                                                         value: "b",
                                                                     },
@@ -3300,7 +3300,7 @@ fn variants() {
                                             Base(
                                                 AstBaseExpression {
                                                     range: AstRange {
-                                                        start: AstPoint { 4:8 },
+                                                        start: AstPoint { 4:6 },
                                                         end: AstPoint { 4:9 },
                                                     },
                                                     ident: Some(
@@ -3309,8 +3309,8 @@ fn variants() {
                                                                 StringLiteral {
                                                                     value: AstIdentifier {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 4:8 },
-                                                                            end: AstPoint { 4:6 },
+                                                                            start: AstPoint { 4:6 },
+                                                                            end: AstPoint { 4:9 },
                                                                         },
                                                                         value: "c",
                                                                     },
@@ -3599,15 +3599,15 @@ fn variants() {
                                                         Base(
                                                             AstBaseExpression {
                                                                 range: AstRange {
-                                                                    start: AstPoint { 8:18 },
-                                                                    end: AstPoint { 8:19 },
+                                                                    start: AstPoint { 8:17 },
+                                                                    end: AstPoint { 8:18 },
                                                                 },
                                                                 ident: None,
                                                                 values: None,
                                                                 operator: Assign(
                                                                     AstRange {
-                                                                        start: AstPoint { 8:18 },
-                                                                        end: AstPoint { 8:19 },
+                                                                        start: AstPoint { 8:17 },
+                                                                        end: AstPoint { 8:18 },
                                                                     },
                                                                 ),
                                                             },
@@ -3945,7 +3945,7 @@ fn types() {
                                     AstClassVariable {
                                         range: AstRange {
                                             start: AstPoint { 4:2 },
-                                            end: AstPoint { 4:20 },
+                                            end: AstPoint { 4:19 },
                                         },
                                         availability: AstAvailability(
                                             0x0,
@@ -3971,8 +3971,8 @@ fn types() {
                                                 Base(
                                                     AstBaseExpression {
                                                         range: AstRange {
-                                                            start: AstPoint { 4:19 },
-                                                            end: AstPoint { 4:20 },
+                                                            start: AstPoint { 4:18 },
+                                                            end: AstPoint { 4:19 },
                                                         },
                                                         ident: Some(
                                                             Value(
@@ -3980,8 +3980,8 @@ fn types() {
                                                                     Int(
                                                                         AstInt {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 4:19 },
-                                                                                end: AstPoint { 4:20 },
+                                                                                start: AstPoint { 4:18 },
+                                                                                end: AstPoint { 4:19 },
                                                                             },
                                                                             value: "0",
                                                                         },
@@ -4002,7 +4002,7 @@ fn types() {
                                     AstClassVariable {
                                         range: AstRange {
                                             start: AstPoint { 5:2 },
-                                            end: AstPoint { 5:18 },
+                                            end: AstPoint { 5:17 },
                                         },
                                         availability: AstAvailability(
                                             0x0,
@@ -4028,8 +4028,8 @@ fn types() {
                                                 Base(
                                                     AstBaseExpression {
                                                         range: AstRange {
-                                                            start: AstPoint { 5:17 },
-                                                            end: AstPoint { 5:18 },
+                                                            start: AstPoint { 5:16 },
+                                                            end: AstPoint { 5:17 },
                                                         },
                                                         ident: Some(
                                                             Value(
@@ -4037,8 +4037,8 @@ fn types() {
                                                                     Int(
                                                                         AstInt {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 5:17 },
-                                                                                end: AstPoint { 5:18 },
+                                                                                start: AstPoint { 5:16 },
+                                                                                end: AstPoint { 5:17 },
                                                                             },
                                                                             value: "0",
                                                                         },
@@ -4059,7 +4059,7 @@ fn types() {
                                     AstClassVariable {
                                         range: AstRange {
                                             start: AstPoint { 6:2 },
-                                            end: AstPoint { 6:22 },
+                                            end: AstPoint { 6:21 },
                                         },
                                         availability: AstAvailability(
                                             0x0,
@@ -4085,8 +4085,8 @@ fn types() {
                                                 Base(
                                                     AstBaseExpression {
                                                         range: AstRange {
-                                                            start: AstPoint { 6:21 },
-                                                            end: AstPoint { 6:22 },
+                                                            start: AstPoint { 6:20 },
+                                                            end: AstPoint { 6:21 },
                                                         },
                                                         ident: Some(
                                                             Value(
@@ -4094,8 +4094,8 @@ fn types() {
                                                                     Int(
                                                                         AstInt {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 6:21 },
-                                                                                end: AstPoint { 6:22 },
+                                                                                start: AstPoint { 6:20 },
+                                                                                end: AstPoint { 6:21 },
                                                                             },
                                                                             value: "0",
                                                                         },
@@ -4142,7 +4142,7 @@ fn types() {
                                                 Base(
                                                     AstBaseExpression {
                                                         range: AstRange {
-                                                            start: AstPoint { 7:21 },
+                                                            start: AstPoint { 7:18 },
                                                             end: AstPoint { 7:22 },
                                                         },
                                                         ident: Some(
@@ -4151,8 +4151,8 @@ fn types() {
                                                                     Long(
                                                                         AstInt {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 7:21 },
-                                                                                end: AstPoint { 7:24 },
+                                                                                start: AstPoint { 7:18 },
+                                                                                end: AstPoint { 7:21 },
                                                                             },
                                                                             value: "111",
                                                                         },
@@ -4199,7 +4199,7 @@ fn types() {
                                                 Base(
                                                     AstBaseExpression {
                                                         range: AstRange {
-                                                            start: AstPoint { 8:23 },
+                                                            start: AstPoint { 8:22 },
                                                             end: AstPoint { 8:26 },
                                                         },
                                                         ident: Some(
@@ -4208,8 +4208,8 @@ fn types() {
                                                                     Double(
                                                                         AstDouble {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 8:23 },
-                                                                                end: AstPoint { 8:24 },
+                                                                                start: AstPoint { 8:22 },
+                                                                                end: AstPoint { 8:23 },
                                                                             },
                                                                             value: "0.0",
                                                                         },
@@ -4256,7 +4256,7 @@ fn types() {
                                                 Base(
                                                     AstBaseExpression {
                                                         range: AstRange {
-                                                            start: AstPoint { 9:21 },
+                                                            start: AstPoint { 9:20 },
                                                             end: AstPoint { 9:25 },
                                                         },
                                                         ident: Some(
@@ -4265,8 +4265,8 @@ fn types() {
                                                                     Float(
                                                                         AstDouble {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 9:21 },
-                                                                                end: AstPoint { 9:22 },
+                                                                                start: AstPoint { 9:20 },
+                                                                                end: AstPoint { 9:21 },
                                                                             },
                                                                             value: "1.11",
                                                                         },
@@ -4287,7 +4287,7 @@ fn types() {
                                     AstClassVariable {
                                         range: AstRange {
                                             start: AstPoint { 10:2 },
-                                            end: AstPoint { 10:20 },
+                                            end: AstPoint { 10:21 },
                                         },
                                         availability: AstAvailability(
                                             0x0,
@@ -4313,8 +4313,8 @@ fn types() {
                                                 Base(
                                                     AstBaseExpression {
                                                         range: AstRange {
-                                                            start: AstPoint { 10:19 },
-                                                            end: AstPoint { 10:20 },
+                                                            start: AstPoint { 10:18 },
+                                                            end: AstPoint { 10:21 },
                                                         },
                                                         ident: Some(
                                                             Value(
@@ -4322,8 +4322,8 @@ fn types() {
                                                                     CharLiteral(
                                                                         AstIdentifier {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 10:19 },
-                                                                                end: AstPoint { 10:18 },
+                                                                                start: AstPoint { 10:18 },
+                                                                                end: AstPoint { 10:21 },
                                                                             },
                                                                             value: "a",
                                                                         },
@@ -4344,7 +4344,7 @@ fn types() {
                                     AstClassVariable {
                                         range: AstRange {
                                             start: AstPoint { 11:2 },
-                                            end: AstPoint { 11:31 },
+                                            end: AstPoint { 11:28 },
                                         },
                                         availability: AstAvailability(
                                             0x0,
@@ -4378,8 +4378,8 @@ fn types() {
                                                 Base(
                                                     AstBaseExpression {
                                                         range: AstRange {
-                                                            start: AstPoint { 11:27 },
-                                                            end: AstPoint { 11:31 },
+                                                            start: AstPoint { 11:22 },
+                                                            end: AstPoint { 11:28 },
                                                         },
                                                         ident: Some(
                                                             Value(
@@ -4387,8 +4387,8 @@ fn types() {
                                                                     StringLiteral {
                                                                         value: AstIdentifier {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 11:27 },
-                                                                                end: AstPoint { 11:22 },
+                                                                                start: AstPoint { 11:22 },
+                                                                                end: AstPoint { 11:28 },
                                                                             },
                                                                             value: "hihi",
                                                                         },
@@ -4535,8 +4535,8 @@ fn types() {
                                                                         Base(
                                                                             AstBaseExpression {
                                                                                 range: AstRange {
-                                                                                    start: AstPoint { 12:39 },
-                                                                                    end: AstPoint { 12:43 },
+                                                                                    start: AstPoint { 12:34 },
+                                                                                    end: AstPoint { 12:40 },
                                                                                 },
                                                                                 ident: Some(
                                                                                     Value(
@@ -4544,8 +4544,8 @@ fn types() {
                                                                                             StringLiteral {
                                                                                                 value: AstIdentifier {
                                                                                                     range: AstRange {
-                                                                                                        start: AstPoint { 12:39 },
-                                                                                                        end: AstPoint { 12:34 },
+                                                                                                        start: AstPoint { 12:34 },
+                                                                                                        end: AstPoint { 12:40 },
                                                                                                     },
                                                                                                     value: "haha",
                                                                                                 },
@@ -4919,8 +4919,8 @@ fn annotated() {
                                                     Base(
                                                         AstBaseExpression {
                                                             range: AstRange {
-                                                                start: AstPoint { 8:13 },
-                                                                end: AstPoint { 8:19 },
+                                                                start: AstPoint { 8:6 },
+                                                                end: AstPoint { 8:14 },
                                                             },
                                                             ident: Some(
                                                                 Value(
@@ -4928,8 +4928,8 @@ fn annotated() {
                                                                         StringLiteral {
                                                                             value: AstIdentifier {
                                                                                 range: AstRange {
-                                                                                    start: AstPoint { 8:13 },
-                                                                                    end: AstPoint { 8:6 },
+                                                                                    start: AstPoint { 8:6 },
+                                                                                    end: AstPoint { 8:14 },
                                                                                 },
                                                                                 value: "/hello",
                                                                             },
@@ -5103,8 +5103,8 @@ fn annotated() {
                                                                     Base(
                                                                         AstBaseExpression {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 12:17 },
-                                                                                end: AstPoint { 12:23 },
+                                                                                start: AstPoint { 12:10 },
+                                                                                end: AstPoint { 12:18 },
                                                                             },
                                                                             ident: Some(
                                                                                 Value(
@@ -5112,8 +5112,8 @@ fn annotated() {
                                                                                         StringLiteral {
                                                                                             value: AstIdentifier {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 12:17 },
-                                                                                                    end: AstPoint { 12:10 },
+                                                                                                    start: AstPoint { 12:10 },
+                                                                                                    end: AstPoint { 12:18 },
                                                                                                 },
                                                                                                 value: "{name}",
                                                                                             },
@@ -5387,8 +5387,8 @@ fn annotated() {
                                                                     Base(
                                                                         AstBaseExpression {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 17:39 },
-                                                                                end: AstPoint { 17:62 },
+                                                                                start: AstPoint { 17:15 },
+                                                                                end: AstPoint { 17:40 },
                                                                             },
                                                                             ident: Some(
                                                                                 Value(
@@ -5396,8 +5396,8 @@ fn annotated() {
                                                                                         StringLiteral {
                                                                                             value: AstIdentifier {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 17:39 },
-                                                                                                    end: AstPoint { 17:14 },
+                                                                                                    start: AstPoint { 17:15 },
+                                                                                                    end: AstPoint { 17:40 },
                                                                                                 },
                                                                                                 value: "Hello from Quarkus REST",
                                                                                             },
@@ -5677,8 +5677,8 @@ fn expression_array_access() {
                                     Base(
                                         AstBaseExpression {
                                             range: AstRange {
-                                                start: AstPoint { 0:9 },
-                                                end: AstPoint { 0:10 },
+                                                start: AstPoint { 0:8 },
+                                                end: AstPoint { 0:9 },
                                             },
                                             ident: Some(
                                                 Value(
@@ -5686,8 +5686,8 @@ fn expression_array_access() {
                                                         Int(
                                                             AstInt {
                                                                 range: AstRange {
-                                                                    start: AstPoint { 0:9 },
-                                                                    end: AstPoint { 0:10 },
+                                                                    start: AstPoint { 0:8 },
+                                                                    end: AstPoint { 0:9 },
                                                                 },
                                                                 value: "0",
                                                             },
@@ -5760,8 +5760,8 @@ fn expression_multi_array_access() {
                                     Base(
                                         AstBaseExpression {
                                             range: AstRange {
-                                                start: AstPoint { 0:9 },
-                                                end: AstPoint { 0:10 },
+                                                start: AstPoint { 0:8 },
+                                                end: AstPoint { 0:9 },
                                             },
                                             ident: Some(
                                                 Value(
@@ -5769,8 +5769,8 @@ fn expression_multi_array_access() {
                                                         Int(
                                                             AstInt {
                                                                 range: AstRange {
-                                                                    start: AstPoint { 0:9 },
-                                                                    end: AstPoint { 0:10 },
+                                                                    start: AstPoint { 0:8 },
+                                                                    end: AstPoint { 0:9 },
                                                                 },
                                                                 value: "0",
                                                             },
@@ -5805,8 +5805,8 @@ fn expression_multi_array_access() {
                                     Base(
                                         AstBaseExpression {
                                             range: AstRange {
-                                                start: AstPoint { 0:12 },
-                                                end: AstPoint { 0:13 },
+                                                start: AstPoint { 0:11 },
+                                                end: AstPoint { 0:12 },
                                             },
                                             ident: Some(
                                                 Value(
@@ -5814,8 +5814,8 @@ fn expression_multi_array_access() {
                                                         Int(
                                                             AstInt {
                                                                 range: AstRange {
-                                                                    start: AstPoint { 0:12 },
-                                                                    end: AstPoint { 0:13 },
+                                                                    start: AstPoint { 0:11 },
+                                                                    end: AstPoint { 0:12 },
                                                                 },
                                                                 value: "0",
                                                             },
@@ -5850,8 +5850,8 @@ fn expression_multi_array_access() {
                                     Base(
                                         AstBaseExpression {
                                             range: AstRange {
-                                                start: AstPoint { 0:15 },
-                                                end: AstPoint { 0:16 },
+                                                start: AstPoint { 0:14 },
+                                                end: AstPoint { 0:15 },
                                             },
                                             ident: Some(
                                                 Value(
@@ -5859,8 +5859,8 @@ fn expression_multi_array_access() {
                                                         Int(
                                                             AstInt {
                                                                 range: AstRange {
-                                                                    start: AstPoint { 0:15 },
-                                                                    end: AstPoint { 0:16 },
+                                                                    start: AstPoint { 0:14 },
+                                                                    end: AstPoint { 0:15 },
                                                                 },
                                                                 value: "0",
                                                             },
@@ -5903,7 +5903,7 @@ fn equasion_method_call() {
                 Base(
                     AstBaseExpression {
                         range: AstRange {
-                            start: AstPoint { 0:2 },
+                            start: AstPoint { 0:0 },
                             end: AstPoint { 0:3 },
                         },
                         ident: Some(
@@ -5912,7 +5912,7 @@ fn equasion_method_call() {
                                     StringLiteral {
                                         value: AstIdentifier {
                                             range: AstRange {
-                                                start: AstPoint { 0:2 },
+                                                start: AstPoint { 0:0 },
                                                 end: AstPoint { 0:3 },
                                             },
                                             value: "z",
@@ -6101,8 +6101,8 @@ fn annotation() {
                                             Base(
                                                 AstBaseExpression {
                                                     range: AstRange {
-                                                        start: AstPoint { 2:25 },
-                                                        end: AstPoint { 2:26 },
+                                                        start: AstPoint { 2:24 },
+                                                        end: AstPoint { 2:25 },
                                                     },
                                                     ident: Some(
                                                         Value(
@@ -6110,8 +6110,8 @@ fn annotation() {
                                                                 Int(
                                                                     AstInt {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 2:25 },
-                                                                            end: AstPoint { 2:26 },
+                                                                            start: AstPoint { 2:24 },
+                                                                            end: AstPoint { 2:25 },
                                                                         },
                                                                         value: "0",
                                                                     },
@@ -6163,8 +6163,8 @@ fn annotation() {
                                             Base(
                                                 AstBaseExpression {
                                                     range: AstRange {
-                                                        start: AstPoint { 3:32 },
-                                                        end: AstPoint { 3:37 },
+                                                        start: AstPoint { 3:26 },
+                                                        end: AstPoint { 3:33 },
                                                     },
                                                     ident: Some(
                                                         Value(
@@ -6172,8 +6172,8 @@ fn annotation() {
                                                                 StringLiteral {
                                                                     value: AstIdentifier {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 3:32 },
-                                                                            end: AstPoint { 3:25 },
+                                                                            start: AstPoint { 3:26 },
+                                                                            end: AstPoint { 3:33 },
                                                                         },
                                                                         value: "Hello",
                                                                     },
@@ -6229,7 +6229,7 @@ fn more_syntax() {
                         AstClass {
                             range: AstRange {
                                 start: AstPoint { 1:0 },
-                                end: AstPoint { 125:1 },
+                                end: AstPoint { 127:1 },
                             },
                             availability: AstAvailability(
                                 Public,
@@ -6252,14 +6252,14 @@ fn more_syntax() {
                             block: AstClassBlock {
                                 range: AstRange {
                                     start: AstPoint { 1:20 },
-                                    end: AstPoint { 125:1 },
+                                    end: AstPoint { 127:1 },
                                 },
                                 variables: [],
                                 methods: [
                                     AstClassMethod {
                                         range: AstRange {
                                             start: AstPoint { 2:4 },
-                                            end: AstPoint { 124:5 },
+                                            end: AstPoint { 126:5 },
                                         },
                                         header: AstMethodHeader {
                                             range: AstRange {
@@ -6342,7 +6342,7 @@ fn more_syntax() {
                                             AstBlock {
                                                 range: AstRange {
                                                     start: AstPoint { 2:43 },
-                                                    end: AstPoint { 124:5 },
+                                                    end: AstPoint { 126:5 },
                                                 },
                                                 entries: [
                                                     If(
@@ -6430,15 +6430,15 @@ fn more_syntax() {
                                                                 Expression(
                                                                     AstBlockExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 6:11 },
+                                                                            start: AstPoint { 6:10 },
                                                                             end: AstPoint { 6:12 },
                                                                         },
                                                                         value: [
                                                                             Base(
                                                                                 AstBaseExpression {
                                                                                     range: AstRange {
-                                                                                        start: AstPoint { 6:11 },
-                                                                                        end: AstPoint { 6:12 },
+                                                                                        start: AstPoint { 6:10 },
+                                                                                        end: AstPoint { 6:11 },
                                                                                     },
                                                                                     ident: Some(
                                                                                         Value(
@@ -6446,8 +6446,8 @@ fn more_syntax() {
                                                                                                 Int(
                                                                                                     AstInt {
                                                                                                         range: AstRange {
-                                                                                                            start: AstPoint { 6:11 },
-                                                                                                            end: AstPoint { 6:12 },
+                                                                                                            start: AstPoint { 6:10 },
+                                                                                                            end: AstPoint { 6:11 },
                                                                                                         },
                                                                                                         value: "1",
                                                                                                     },
@@ -6583,7 +6583,7 @@ fn more_syntax() {
                                                                         AstBlockVariable {
                                                                             range: AstRange {
                                                                                 start: AstPoint { 13:12 },
-                                                                                end: AstPoint { 13:20 },
+                                                                                end: AstPoint { 13:19 },
                                                                             },
                                                                             fin: false,
                                                                             annotated: [],
@@ -6607,8 +6607,8 @@ fn more_syntax() {
                                                                                     Base(
                                                                                         AstBaseExpression {
                                                                                             range: AstRange {
-                                                                                                start: AstPoint { 13:19 },
-                                                                                                end: AstPoint { 13:20 },
+                                                                                                start: AstPoint { 13:18 },
+                                                                                                end: AstPoint { 13:19 },
                                                                                             },
                                                                                             ident: Some(
                                                                                                 Value(
@@ -6616,8 +6616,8 @@ fn more_syntax() {
                                                                                                         Int(
                                                                                                             AstInt {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 13:19 },
-                                                                                                                    end: AstPoint { 13:20 },
+                                                                                                                    start: AstPoint { 13:18 },
+                                                                                                                    end: AstPoint { 13:19 },
                                                                                                                 },
                                                                                                                 value: "1",
                                                                                                             },
@@ -6640,7 +6640,7 @@ fn more_syntax() {
                                                                     AstBlockExpression {
                                                                         range: AstRange {
                                                                             start: AstPoint { 13:20 },
-                                                                            end: AstPoint { 13:25 },
+                                                                            end: AstPoint { 13:24 },
                                                                         },
                                                                         value: [
                                                                             Base(
@@ -6683,8 +6683,8 @@ fn more_syntax() {
                                                                             Base(
                                                                                 AstBaseExpression {
                                                                                     range: AstRange {
-                                                                                        start: AstPoint { 13:24 },
-                                                                                        end: AstPoint { 13:25 },
+                                                                                        start: AstPoint { 13:23 },
+                                                                                        end: AstPoint { 13:24 },
                                                                                     },
                                                                                     ident: Some(
                                                                                         Value(
@@ -6692,8 +6692,8 @@ fn more_syntax() {
                                                                                                 Int(
                                                                                                     AstInt {
                                                                                                         range: AstRange {
-                                                                                                            start: AstPoint { 13:24 },
-                                                                                                            end: AstPoint { 13:25 },
+                                                                                                            start: AstPoint { 13:23 },
+                                                                                                            end: AstPoint { 13:24 },
                                                                                                         },
                                                                                                         value: "5",
                                                                                                     },
@@ -6782,7 +6782,7 @@ fn more_syntax() {
                                                                         AstBlockVariable {
                                                                             range: AstRange {
                                                                                 start: AstPoint { 15:19 },
-                                                                                end: AstPoint { 15:27 },
+                                                                                end: AstPoint { 15:26 },
                                                                             },
                                                                             fin: false,
                                                                             annotated: [],
@@ -6806,8 +6806,8 @@ fn more_syntax() {
                                                                                     Base(
                                                                                         AstBaseExpression {
                                                                                             range: AstRange {
-                                                                                                start: AstPoint { 15:26 },
-                                                                                                end: AstPoint { 15:27 },
+                                                                                                start: AstPoint { 15:25 },
+                                                                                                end: AstPoint { 15:26 },
                                                                                             },
                                                                                             ident: Some(
                                                                                                 Value(
@@ -6815,8 +6815,8 @@ fn more_syntax() {
                                                                                                         Int(
                                                                                                             AstInt {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 15:26 },
-                                                                                                                    end: AstPoint { 15:27 },
+                                                                                                                    start: AstPoint { 15:25 },
+                                                                                                                    end: AstPoint { 15:26 },
                                                                                                                 },
                                                                                                                 value: "1",
                                                                                                             },
@@ -6839,7 +6839,7 @@ fn more_syntax() {
                                                                     AstBlockExpression {
                                                                         range: AstRange {
                                                                             start: AstPoint { 15:27 },
-                                                                            end: AstPoint { 15:32 },
+                                                                            end: AstPoint { 15:31 },
                                                                         },
                                                                         value: [
                                                                             Base(
@@ -6882,8 +6882,8 @@ fn more_syntax() {
                                                                             Base(
                                                                                 AstBaseExpression {
                                                                                     range: AstRange {
-                                                                                        start: AstPoint { 15:31 },
-                                                                                        end: AstPoint { 15:32 },
+                                                                                        start: AstPoint { 15:30 },
+                                                                                        end: AstPoint { 15:31 },
                                                                                     },
                                                                                     ident: Some(
                                                                                         Value(
@@ -6891,8 +6891,8 @@ fn more_syntax() {
                                                                                                 Int(
                                                                                                     AstInt {
                                                                                                         range: AstRange {
-                                                                                                            start: AstPoint { 15:31 },
-                                                                                                            end: AstPoint { 15:32 },
+                                                                                                            start: AstPoint { 15:30 },
+                                                                                                            end: AstPoint { 15:31 },
                                                                                                         },
                                                                                                         value: "5",
                                                                                                     },
@@ -7031,8 +7031,8 @@ fn more_syntax() {
                                                                                         Base(
                                                                                             AstBaseExpression {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 17:31 },
-                                                                                                    end: AstPoint { 17:36 },
+                                                                                                    start: AstPoint { 17:25 },
+                                                                                                    end: AstPoint { 17:32 },
                                                                                                 },
                                                                                                 ident: Some(
                                                                                                     Value(
@@ -7040,8 +7040,8 @@ fn more_syntax() {
                                                                                                             StringLiteral {
                                                                                                                 value: AstIdentifier {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 17:31 },
-                                                                                                                        end: AstPoint { 17:25 },
+                                                                                                                        start: AstPoint { 17:25 },
+                                                                                                                        end: AstPoint { 17:32 },
                                                                                                                     },
                                                                                                                     value: "Volvo",
                                                                                                                 },
@@ -7059,8 +7059,8 @@ fn more_syntax() {
                                                                                         Base(
                                                                                             AstBaseExpression {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 17:38 },
-                                                                                                    end: AstPoint { 17:41 },
+                                                                                                    start: AstPoint { 17:34 },
+                                                                                                    end: AstPoint { 17:39 },
                                                                                                 },
                                                                                                 ident: Some(
                                                                                                     Value(
@@ -7068,8 +7068,8 @@ fn more_syntax() {
                                                                                                             StringLiteral {
                                                                                                                 value: AstIdentifier {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 17:38 },
-                                                                                                                        end: AstPoint { 17:33 },
+                                                                                                                        start: AstPoint { 17:34 },
+                                                                                                                        end: AstPoint { 17:39 },
                                                                                                                     },
                                                                                                                     value: "BMW",
                                                                                                                 },
@@ -7087,8 +7087,8 @@ fn more_syntax() {
                                                                                         Base(
                                                                                             AstBaseExpression {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 17:46 },
-                                                                                                    end: AstPoint { 17:50 },
+                                                                                                    start: AstPoint { 17:41 },
+                                                                                                    end: AstPoint { 17:47 },
                                                                                                 },
                                                                                                 ident: Some(
                                                                                                     Value(
@@ -7096,8 +7096,8 @@ fn more_syntax() {
                                                                                                             StringLiteral {
                                                                                                                 value: AstIdentifier {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 17:46 },
-                                                                                                                        end: AstPoint { 17:40 },
+                                                                                                                        start: AstPoint { 17:41 },
+                                                                                                                        end: AstPoint { 17:47 },
                                                                                                                     },
                                                                                                                     value: "Ford",
                                                                                                                 },
@@ -7115,8 +7115,8 @@ fn more_syntax() {
                                                                                         Base(
                                                                                             AstBaseExpression {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 17:55 },
-                                                                                                    end: AstPoint { 17:60 },
+                                                                                                    start: AstPoint { 17:49 },
+                                                                                                    end: AstPoint { 17:56 },
                                                                                                 },
                                                                                                 ident: Some(
                                                                                                     Value(
@@ -7124,8 +7124,8 @@ fn more_syntax() {
                                                                                                             StringLiteral {
                                                                                                                 value: AstIdentifier {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 17:55 },
-                                                                                                                        end: AstPoint { 17:48 },
+                                                                                                                        start: AstPoint { 17:49 },
+                                                                                                                        end: AstPoint { 17:56 },
                                                                                                                     },
                                                                                                                     value: "Mazda",
                                                                                                                 },
@@ -7297,8 +7297,8 @@ fn more_syntax() {
                                                                                                             Base(
                                                                                                                 AstBaseExpression {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 18:33 },
-                                                                                                                        end: AstPoint { 18:34 },
+                                                                                                                        start: AstPoint { 18:32 },
+                                                                                                                        end: AstPoint { 18:33 },
                                                                                                                     },
                                                                                                                     ident: Some(
                                                                                                                         Value(
@@ -7306,8 +7306,8 @@ fn more_syntax() {
                                                                                                                                 Int(
                                                                                                                                     AstInt {
                                                                                                                                         range: AstRange {
-                                                                                                                                            start: AstPoint { 18:33 },
-                                                                                                                                            end: AstPoint { 18:34 },
+                                                                                                                                            start: AstPoint { 18:32 },
+                                                                                                                                            end: AstPoint { 18:33 },
                                                                                                                                         },
                                                                                                                                         value: "0",
                                                                                                                                     },
@@ -7403,8 +7403,8 @@ fn more_syntax() {
                                                                                                         Base(
                                                                                                             AstBaseExpression {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 19:32 },
-                                                                                                                    end: AstPoint { 19:33 },
+                                                                                                                    start: AstPoint { 19:31 },
+                                                                                                                    end: AstPoint { 19:32 },
                                                                                                                 },
                                                                                                                 ident: Some(
                                                                                                                     Value(
@@ -7412,8 +7412,8 @@ fn more_syntax() {
                                                                                                                             Int(
                                                                                                                                 AstInt {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 19:32 },
-                                                                                                                                        end: AstPoint { 19:33 },
+                                                                                                                                        start: AstPoint { 19:31 },
+                                                                                                                                        end: AstPoint { 19:32 },
                                                                                                                                     },
                                                                                                                                     value: "1",
                                                                                                                                 },
@@ -7430,8 +7430,8 @@ fn more_syntax() {
                                                                                                         Base(
                                                                                                             AstBaseExpression {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 19:35 },
-                                                                                                                    end: AstPoint { 19:36 },
+                                                                                                                    start: AstPoint { 19:34 },
+                                                                                                                    end: AstPoint { 19:35 },
                                                                                                                 },
                                                                                                                 ident: Some(
                                                                                                                     Value(
@@ -7439,8 +7439,8 @@ fn more_syntax() {
                                                                                                                             Int(
                                                                                                                                 AstInt {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 19:35 },
-                                                                                                                                        end: AstPoint { 19:36 },
+                                                                                                                                        start: AstPoint { 19:34 },
+                                                                                                                                        end: AstPoint { 19:35 },
                                                                                                                                     },
                                                                                                                                     value: "2",
                                                                                                                                 },
@@ -7457,8 +7457,8 @@ fn more_syntax() {
                                                                                                         Base(
                                                                                                             AstBaseExpression {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 19:38 },
-                                                                                                                    end: AstPoint { 19:39 },
+                                                                                                                    start: AstPoint { 19:37 },
+                                                                                                                    end: AstPoint { 19:38 },
                                                                                                                 },
                                                                                                                 ident: Some(
                                                                                                                     Value(
@@ -7466,8 +7466,8 @@ fn more_syntax() {
                                                                                                                             Int(
                                                                                                                                 AstInt {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 19:38 },
-                                                                                                                                        end: AstPoint { 19:39 },
+                                                                                                                                        start: AstPoint { 19:37 },
+                                                                                                                                        end: AstPoint { 19:38 },
                                                                                                                                     },
                                                                                                                                     value: "3",
                                                                                                                                 },
@@ -7484,8 +7484,8 @@ fn more_syntax() {
                                                                                                         Base(
                                                                                                             AstBaseExpression {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 19:41 },
-                                                                                                                    end: AstPoint { 19:42 },
+                                                                                                                    start: AstPoint { 19:40 },
+                                                                                                                    end: AstPoint { 19:41 },
                                                                                                                 },
                                                                                                                 ident: Some(
                                                                                                                     Value(
@@ -7493,8 +7493,8 @@ fn more_syntax() {
                                                                                                                             Int(
                                                                                                                                 AstInt {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 19:41 },
-                                                                                                                                        end: AstPoint { 19:42 },
+                                                                                                                                        start: AstPoint { 19:40 },
+                                                                                                                                        end: AstPoint { 19:41 },
                                                                                                                                     },
                                                                                                                                     value: "4",
                                                                                                                                 },
@@ -7523,8 +7523,8 @@ fn more_syntax() {
                                                                                                         Base(
                                                                                                             AstBaseExpression {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 19:46 },
-                                                                                                                    end: AstPoint { 19:47 },
+                                                                                                                    start: AstPoint { 19:45 },
+                                                                                                                    end: AstPoint { 19:46 },
                                                                                                                 },
                                                                                                                 ident: Some(
                                                                                                                     Value(
@@ -7532,8 +7532,8 @@ fn more_syntax() {
                                                                                                                             Int(
                                                                                                                                 AstInt {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 19:46 },
-                                                                                                                                        end: AstPoint { 19:47 },
+                                                                                                                                        start: AstPoint { 19:45 },
+                                                                                                                                        end: AstPoint { 19:46 },
                                                                                                                                     },
                                                                                                                                     value: "5",
                                                                                                                                 },
@@ -7550,8 +7550,8 @@ fn more_syntax() {
                                                                                                         Base(
                                                                                                             AstBaseExpression {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 19:49 },
-                                                                                                                    end: AstPoint { 19:50 },
+                                                                                                                    start: AstPoint { 19:48 },
+                                                                                                                    end: AstPoint { 19:49 },
                                                                                                                 },
                                                                                                                 ident: Some(
                                                                                                                     Value(
@@ -7559,8 +7559,8 @@ fn more_syntax() {
                                                                                                                             Int(
                                                                                                                                 AstInt {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 19:49 },
-                                                                                                                                        end: AstPoint { 19:50 },
+                                                                                                                                        start: AstPoint { 19:48 },
+                                                                                                                                        end: AstPoint { 19:49 },
                                                                                                                                     },
                                                                                                                                     value: "6",
                                                                                                                                 },
@@ -7577,8 +7577,8 @@ fn more_syntax() {
                                                                                                         Base(
                                                                                                             AstBaseExpression {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 19:52 },
-                                                                                                                    end: AstPoint { 19:53 },
+                                                                                                                    start: AstPoint { 19:51 },
+                                                                                                                    end: AstPoint { 19:52 },
                                                                                                                 },
                                                                                                                 ident: Some(
                                                                                                                     Value(
@@ -7586,8 +7586,8 @@ fn more_syntax() {
                                                                                                                             Int(
                                                                                                                                 AstInt {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 19:52 },
-                                                                                                                                        end: AstPoint { 19:53 },
+                                                                                                                                        start: AstPoint { 19:51 },
+                                                                                                                                        end: AstPoint { 19:52 },
                                                                                                                                     },
                                                                                                                                     value: "7",
                                                                                                                                 },
@@ -7652,8 +7652,8 @@ fn more_syntax() {
                                                                                     Base(
                                                                                         AstBaseExpression {
                                                                                             range: AstRange {
-                                                                                                start: AstPoint { 20:19 },
-                                                                                                end: AstPoint { 20:20 },
+                                                                                                start: AstPoint { 20:18 },
+                                                                                                end: AstPoint { 20:19 },
                                                                                             },
                                                                                             ident: Some(
                                                                                                 Value(
@@ -7661,8 +7661,8 @@ fn more_syntax() {
                                                                                                         Int(
                                                                                                             AstInt {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 20:19 },
-                                                                                                                    end: AstPoint { 20:20 },
+                                                                                                                    start: AstPoint { 20:18 },
+                                                                                                                    end: AstPoint { 20:19 },
                                                                                                                 },
                                                                                                                 value: "1",
                                                                                                             },
@@ -7697,8 +7697,8 @@ fn more_syntax() {
                                                                                     Base(
                                                                                         AstBaseExpression {
                                                                                             range: AstRange {
-                                                                                                start: AstPoint { 20:22 },
-                                                                                                end: AstPoint { 20:23 },
+                                                                                                start: AstPoint { 20:21 },
+                                                                                                end: AstPoint { 20:22 },
                                                                                             },
                                                                                             ident: Some(
                                                                                                 Value(
@@ -7706,8 +7706,8 @@ fn more_syntax() {
                                                                                                         Int(
                                                                                                             AstInt {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 20:22 },
-                                                                                                                    end: AstPoint { 20:23 },
+                                                                                                                    start: AstPoint { 20:21 },
+                                                                                                                    end: AstPoint { 20:22 },
                                                                                                                 },
                                                                                                                 value: "2",
                                                                                                             },
@@ -7733,15 +7733,15 @@ fn more_syntax() {
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 20:25 },
-                                                                            end: AstPoint { 20:26 },
+                                                                            start: AstPoint { 20:24 },
+                                                                            end: AstPoint { 20:25 },
                                                                         },
                                                                         ident: None,
                                                                         values: None,
                                                                         operator: Assign(
                                                                             AstRange {
-                                                                                start: AstPoint { 20:25 },
-                                                                                end: AstPoint { 20:26 },
+                                                                                start: AstPoint { 20:24 },
+                                                                                end: AstPoint { 20:25 },
                                                                             },
                                                                         ),
                                                                     },
@@ -7749,8 +7749,8 @@ fn more_syntax() {
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 20:27 },
-                                                                            end: AstPoint { 20:28 },
+                                                                            start: AstPoint { 20:26 },
+                                                                            end: AstPoint { 20:27 },
                                                                         },
                                                                         ident: Some(
                                                                             Value(
@@ -7758,8 +7758,8 @@ fn more_syntax() {
                                                                                     Int(
                                                                                         AstInt {
                                                                                             range: AstRange {
-                                                                                                start: AstPoint { 20:27 },
-                                                                                                end: AstPoint { 20:28 },
+                                                                                                start: AstPoint { 20:26 },
+                                                                                                end: AstPoint { 20:27 },
                                                                                             },
                                                                                             value: "9",
                                                                                         },
@@ -8027,7 +8027,7 @@ fn more_syntax() {
                                                                         Base(
                                                                             AstBaseExpression {
                                                                                 range: AstRange {
-                                                                                    start: AstPoint { 24:27 },
+                                                                                    start: AstPoint { 24:26 },
                                                                                     end: AstPoint { 24:31 },
                                                                                 },
                                                                                 ident: Some(
@@ -8036,8 +8036,8 @@ fn more_syntax() {
                                                                                             Double(
                                                                                                 AstDouble {
                                                                                                     range: AstRange {
-                                                                                                        start: AstPoint { 24:27 },
-                                                                                                        end: AstPoint { 24:28 },
+                                                                                                        start: AstPoint { 24:26 },
+                                                                                                        end: AstPoint { 24:27 },
                                                                                                     },
                                                                                                     value: "9.78",
                                                                                                 },
@@ -9271,7 +9271,7 @@ fn more_syntax() {
                                                             AstBlockVariable {
                                                                 range: AstRange {
                                                                     start: AstPoint { 39:8 },
-                                                                    end: AstPoint { 39:36 },
+                                                                    end: AstPoint { 39:33 },
                                                                 },
                                                                 fin: false,
                                                                 annotated: [],
@@ -9303,8 +9303,8 @@ fn more_syntax() {
                                                                         Base(
                                                                             AstBaseExpression {
                                                                                 range: AstRange {
-                                                                                    start: AstPoint { 39:32 },
-                                                                                    end: AstPoint { 39:36 },
+                                                                                    start: AstPoint { 39:27 },
+                                                                                    end: AstPoint { 39:33 },
                                                                                 },
                                                                                 ident: Some(
                                                                                     Value(
@@ -9312,8 +9312,8 @@ fn more_syntax() {
                                                                                             StringLiteral {
                                                                                                 value: AstIdentifier {
                                                                                                     range: AstRange {
-                                                                                                        start: AstPoint { 39:32 },
-                                                                                                        end: AstPoint { 39:27 },
+                                                                                                        start: AstPoint { 39:27 },
+                                                                                                        end: AstPoint { 39:33 },
                                                                                                     },
                                                                                                     value: "John",
                                                                                                 },
@@ -9336,7 +9336,7 @@ fn more_syntax() {
                                                             AstBlockVariable {
                                                                 range: AstRange {
                                                                     start: AstPoint { 40:8 },
-                                                                    end: AstPoint { 40:33 },
+                                                                    end: AstPoint { 40:31 },
                                                                 },
                                                                 fin: false,
                                                                 annotated: [],
@@ -9368,8 +9368,8 @@ fn more_syntax() {
                                                                         Base(
                                                                             AstBaseExpression {
                                                                                 range: AstRange {
-                                                                                    start: AstPoint { 40:30 },
-                                                                                    end: AstPoint { 40:33 },
+                                                                                    start: AstPoint { 40:26 },
+                                                                                    end: AstPoint { 40:31 },
                                                                                 },
                                                                                 ident: Some(
                                                                                     Value(
@@ -9377,8 +9377,8 @@ fn more_syntax() {
                                                                                             StringLiteral {
                                                                                                 value: AstIdentifier {
                                                                                                     range: AstRange {
-                                                                                                        start: AstPoint { 40:30 },
-                                                                                                        end: AstPoint { 40:26 },
+                                                                                                        start: AstPoint { 40:26 },
+                                                                                                        end: AstPoint { 40:31 },
                                                                                                     },
                                                                                                     value: "Doe",
                                                                                                 },
@@ -9553,7 +9553,7 @@ fn more_syntax() {
                                                                                         Base(
                                                                                             AstBaseExpression {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 41:41 },
+                                                                                                    start: AstPoint { 41:39 },
                                                                                                     end: AstPoint { 41:42 },
                                                                                                 },
                                                                                                 ident: Some(
@@ -9562,8 +9562,8 @@ fn more_syntax() {
                                                                                                             StringLiteral {
                                                                                                                 value: AstIdentifier {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 41:41 },
-                                                                                                                        end: AstPoint { 41:38 },
+                                                                                                                        start: AstPoint { 41:39 },
+                                                                                                                        end: AstPoint { 41:42 },
                                                                                                                     },
                                                                                                                     value: " ",
                                                                                                                 },
@@ -9685,8 +9685,8 @@ fn more_syntax() {
                                                                                         Base(
                                                                                             AstBaseExpression {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 42:18 },
-                                                                                                    end: AstPoint { 42:20 },
+                                                                                                    start: AstPoint { 42:16 },
+                                                                                                    end: AstPoint { 42:18 },
                                                                                                 },
                                                                                                 ident: Some(
                                                                                                     Value(
@@ -9694,8 +9694,8 @@ fn more_syntax() {
                                                                                                             Int(
                                                                                                                 AstInt {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 42:18 },
-                                                                                                                        end: AstPoint { 42:20 },
+                                                                                                                        start: AstPoint { 42:16 },
+                                                                                                                        end: AstPoint { 42:18 },
                                                                                                                     },
                                                                                                                     value: "18",
                                                                                                                 },
@@ -9733,8 +9733,8 @@ fn more_syntax() {
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 42:32 },
-                                                                            end: AstPoint { 42:41 },
+                                                                            start: AstPoint { 42:22 },
+                                                                            end: AstPoint { 42:33 },
                                                                         },
                                                                         ident: Some(
                                                                             Value(
@@ -9742,8 +9742,8 @@ fn more_syntax() {
                                                                                     StringLiteral {
                                                                                         value: AstIdentifier {
                                                                                             range: AstRange {
-                                                                                                start: AstPoint { 42:32 },
-                                                                                                end: AstPoint { 42:21 },
+                                                                                                start: AstPoint { 42:22 },
+                                                                                                end: AstPoint { 42:33 },
                                                                                             },
                                                                                             value: "Good day.",
                                                                                         },
@@ -9775,8 +9775,8 @@ fn more_syntax() {
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 42:50 },
-                                                                            end: AstPoint { 42:63 },
+                                                                            start: AstPoint { 42:36 },
+                                                                            end: AstPoint { 42:51 },
                                                                         },
                                                                         ident: Some(
                                                                             Value(
@@ -9784,8 +9784,8 @@ fn more_syntax() {
                                                                                     StringLiteral {
                                                                                         value: AstIdentifier {
                                                                                             range: AstRange {
-                                                                                                start: AstPoint { 42:50 },
-                                                                                                end: AstPoint { 42:35 },
+                                                                                                start: AstPoint { 42:36 },
+                                                                                                end: AstPoint { 42:51 },
                                                                                             },
                                                                                             value: "Good evening.",
                                                                                         },
@@ -10120,8 +10120,8 @@ fn more_syntax() {
                                                                                                         Base(
                                                                                                             AstBaseExpression {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 66:30 },
-                                                                                                                    end: AstPoint { 66:31 },
+                                                                                                                    start: AstPoint { 66:29 },
+                                                                                                                    end: AstPoint { 66:30 },
                                                                                                                 },
                                                                                                                 ident: Some(
                                                                                                                     Value(
@@ -10129,8 +10129,8 @@ fn more_syntax() {
                                                                                                                             Int(
                                                                                                                                 AstInt {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 66:30 },
-                                                                                                                                        end: AstPoint { 66:31 },
+                                                                                                                                        start: AstPoint { 66:29 },
+                                                                                                                                        end: AstPoint { 66:30 },
                                                                                                                                     },
                                                                                                                                     value: "1",
                                                                                                                                 },
@@ -10147,8 +10147,8 @@ fn more_syntax() {
                                                                                                         Base(
                                                                                                             AstBaseExpression {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 66:33 },
-                                                                                                                    end: AstPoint { 66:34 },
+                                                                                                                    start: AstPoint { 66:32 },
+                                                                                                                    end: AstPoint { 66:33 },
                                                                                                                 },
                                                                                                                 ident: Some(
                                                                                                                     Value(
@@ -10156,8 +10156,8 @@ fn more_syntax() {
                                                                                                                             Int(
                                                                                                                                 AstInt {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 66:33 },
-                                                                                                                                        end: AstPoint { 66:34 },
+                                                                                                                                        start: AstPoint { 66:32 },
+                                                                                                                                        end: AstPoint { 66:33 },
                                                                                                                                     },
                                                                                                                                     value: "2",
                                                                                                                                 },
@@ -10174,8 +10174,8 @@ fn more_syntax() {
                                                                                                         Base(
                                                                                                             AstBaseExpression {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 66:36 },
-                                                                                                                    end: AstPoint { 66:37 },
+                                                                                                                    start: AstPoint { 66:35 },
+                                                                                                                    end: AstPoint { 66:36 },
                                                                                                                 },
                                                                                                                 ident: Some(
                                                                                                                     Value(
@@ -10183,8 +10183,8 @@ fn more_syntax() {
                                                                                                                             Int(
                                                                                                                                 AstInt {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 66:36 },
-                                                                                                                                        end: AstPoint { 66:37 },
+                                                                                                                                        start: AstPoint { 66:35 },
+                                                                                                                                        end: AstPoint { 66:36 },
                                                                                                                                     },
                                                                                                                                     value: "3",
                                                                                                                                 },
@@ -10355,8 +10355,8 @@ fn more_syntax() {
                                                                                                                             Base(
                                                                                                                                 AstBaseExpression {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 67:41 },
-                                                                                                                                        end: AstPoint { 67:43 },
+                                                                                                                                        start: AstPoint { 67:39 },
+                                                                                                                                        end: AstPoint { 67:41 },
                                                                                                                                     },
                                                                                                                                     ident: Some(
                                                                                                                                         Value(
@@ -10364,8 +10364,8 @@ fn more_syntax() {
                                                                                                                                                 Int(
                                                                                                                                                     AstInt {
                                                                                                                                                         range: AstRange {
-                                                                                                                                                            start: AstPoint { 67:41 },
-                                                                                                                                                            end: AstPoint { 67:43 },
+                                                                                                                                                            start: AstPoint { 67:39 },
+                                                                                                                                                            end: AstPoint { 67:41 },
                                                                                                                                                         },
                                                                                                                                                         value: "10",
                                                                                                                                                     },
@@ -10566,8 +10566,8 @@ fn more_syntax() {
                                                                                                                 Base(
                                                                                                                     AstBaseExpression {
                                                                                                                         range: AstRange {
-                                                                                                                            start: AstPoint { 69:51 },
-                                                                                                                            end: AstPoint { 69:72 },
+                                                                                                                            start: AstPoint { 69:29 },
+                                                                                                                            end: AstPoint { 69:52 },
                                                                                                                         },
                                                                                                                         ident: Some(
                                                                                                                             Value(
@@ -10575,8 +10575,8 @@ fn more_syntax() {
                                                                                                                                     StringLiteral {
                                                                                                                                         value: AstIdentifier {
                                                                                                                                             range: AstRange {
-                                                                                                                                                start: AstPoint { 69:51 },
-                                                                                                                                                end: AstPoint { 69:29 },
+                                                                                                                                                start: AstPoint { 69:29 },
+                                                                                                                                                end: AstPoint { 69:52 },
                                                                                                                                             },
                                                                                                                                             value: "Something went wrong.",
                                                                                                                                         },
@@ -10730,8 +10730,8 @@ fn more_syntax() {
                                                                                                             Base(
                                                                                                                 AstBaseExpression {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 71:58 },
-                                                                                                                        end: AstPoint { 71:86 },
+                                                                                                                        start: AstPoint { 71:29 },
+                                                                                                                        end: AstPoint { 71:59 },
                                                                                                                     },
                                                                                                                     ident: Some(
                                                                                                                         Value(
@@ -10739,8 +10739,8 @@ fn more_syntax() {
                                                                                                                                 StringLiteral {
                                                                                                                                     value: AstIdentifier {
                                                                                                                                         range: AstRange {
-                                                                                                                                            start: AstPoint { 71:58 },
-                                                                                                                                            end: AstPoint { 71:29 },
+                                                                                                                                            start: AstPoint { 71:29 },
+                                                                                                                                            end: AstPoint { 71:59 },
                                                                                                                                         },
                                                                                                                                         value: "The 'try catch' is finished.",
                                                                                                                                     },
@@ -10818,7 +10818,7 @@ fn more_syntax() {
                                                                                         Base(
                                                                                             AstBaseExpression {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 74:29 },
+                                                                                                    start: AstPoint { 74:27 },
                                                                                                     end: AstPoint { 74:30 },
                                                                                                 },
                                                                                                 ident: Some(
@@ -10827,8 +10827,8 @@ fn more_syntax() {
                                                                                                             StringLiteral {
                                                                                                                 value: AstIdentifier {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 74:29 },
-                                                                                                                        end: AstPoint { 74:27 },
+                                                                                                                        start: AstPoint { 74:27 },
+                                                                                                                        end: AstPoint { 74:30 },
                                                                                                                     },
                                                                                                                     value: "s",
                                                                                                                 },
@@ -10947,7 +10947,7 @@ fn more_syntax() {
                                                                                                 Base(
                                                                                                     AstBaseExpression {
                                                                                                         range: AstRange {
-                                                                                                            start: AstPoint { 76:30 },
+                                                                                                            start: AstPoint { 76:28 },
                                                                                                             end: AstPoint { 76:31 },
                                                                                                         },
                                                                                                         ident: Some(
@@ -10956,8 +10956,8 @@ fn more_syntax() {
                                                                                                                     StringLiteral {
                                                                                                                         value: AstIdentifier {
                                                                                                                             range: AstRange {
-                                                                                                                                start: AstPoint { 76:30 },
-                                                                                                                                end: AstPoint { 76:28 },
+                                                                                                                                start: AstPoint { 76:28 },
+                                                                                                                                end: AstPoint { 76:31 },
                                                                                                                             },
                                                                                                                             value: "o",
                                                                                                                         },
@@ -11060,7 +11060,7 @@ fn more_syntax() {
                                                                                                 Base(
                                                                                                     AstBaseExpression {
                                                                                                         range: AstRange {
-                                                                                                            start: AstPoint { 78:30 },
+                                                                                                            start: AstPoint { 78:28 },
                                                                                                             end: AstPoint { 78:31 },
                                                                                                         },
                                                                                                         ident: Some(
@@ -11069,8 +11069,8 @@ fn more_syntax() {
                                                                                                                     StringLiteral {
                                                                                                                         value: AstIdentifier {
                                                                                                                             range: AstRange {
-                                                                                                                                start: AstPoint { 78:30 },
-                                                                                                                                end: AstPoint { 78:28 },
+                                                                                                                                start: AstPoint { 78:28 },
+                                                                                                                                end: AstPoint { 78:31 },
                                                                                                                             },
                                                                                                                             value: "o",
                                                                                                                         },
@@ -11136,7 +11136,7 @@ fn more_syntax() {
                                                                                             Base(
                                                                                                 AstBaseExpression {
                                                                                                     range: AstRange {
-                                                                                                        start: AstPoint { 80:28 },
+                                                                                                        start: AstPoint { 80:26 },
                                                                                                         end: AstPoint { 80:29 },
                                                                                                     },
                                                                                                     ident: Some(
@@ -11145,8 +11145,8 @@ fn more_syntax() {
                                                                                                                 StringLiteral {
                                                                                                                     value: AstIdentifier {
                                                                                                                         range: AstRange {
-                                                                                                                            start: AstPoint { 80:28 },
-                                                                                                                            end: AstPoint { 80:26 },
+                                                                                                                            start: AstPoint { 80:26 },
+                                                                                                                            end: AstPoint { 80:29 },
                                                                                                                         },
                                                                                                                         value: "a",
                                                                                                                     },
@@ -11273,8 +11273,8 @@ fn more_syntax() {
                                                                                                                         Base(
                                                                                                                             AstBaseExpression {
                                                                                                                                 range: AstRange {
-                                                                                                                                    start: AstPoint { 83:52 },
-                                                                                                                                    end: AstPoint { 83:60 },
+                                                                                                                                    start: AstPoint { 83:43 },
+                                                                                                                                    end: AstPoint { 83:53 },
                                                                                                                                 },
                                                                                                                                 ident: Some(
                                                                                                                                     Value(
@@ -11282,8 +11282,8 @@ fn more_syntax() {
                                                                                                                                             StringLiteral {
                                                                                                                                                 value: AstIdentifier {
                                                                                                                                                     range: AstRange {
-                                                                                                                                                        start: AstPoint { 83:52 },
-                                                                                                                                                        end: AstPoint { 83:43 },
+                                                                                                                                                        start: AstPoint { 83:43 },
+                                                                                                                                                        end: AstPoint { 83:53 },
                                                                                                                                                     },
                                                                                                                                                     value: "test.txt",
                                                                                                                                                 },
@@ -12312,8 +12312,8 @@ fn more_syntax() {
                                                                                     Base(
                                                                                         AstBaseExpression {
                                                                                             range: AstRange {
-                                                                                                start: AstPoint { 100:52 },
-                                                                                                end: AstPoint { 100:65 },
+                                                                                                start: AstPoint { 100:38 },
+                                                                                                end: AstPoint { 100:53 },
                                                                                             },
                                                                                             ident: Some(
                                                                                                 Value(
@@ -12321,8 +12321,8 @@ fn more_syntax() {
                                                                                                         StringLiteral {
                                                                                                             value: AstIdentifier {
                                                                                                                 range: AstRange {
-                                                                                                                    start: AstPoint { 100:52 },
-                                                                                                                    end: AstPoint { 100:38 },
+                                                                                                                    start: AstPoint { 100:38 },
+                                                                                                                    end: AstPoint { 100:53 },
                                                                                                                 },
                                                                                                                 value: "Access denied",
                                                                                                             },
@@ -12741,8 +12741,8 @@ fn more_syntax() {
                                                                                                                             Base(
                                                                                                                                 AstBaseExpression {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 104:30 },
-                                                                                                                                        end: AstPoint { 104:37 },
+                                                                                                                                        start: AstPoint { 104:22 },
+                                                                                                                                        end: AstPoint { 104:31 },
                                                                                                                                     },
                                                                                                                                     ident: Some(
                                                                                                                                         Value(
@@ -12750,8 +12750,8 @@ fn more_syntax() {
                                                                                                                                                 StringLiteral {
                                                                                                                                                     value: AstIdentifier {
                                                                                                                                                         range: AstRange {
-                                                                                                                                                            start: AstPoint { 104:30 },
-                                                                                                                                                            end: AstPoint { 104:21 },
+                                                                                                                                                            start: AstPoint { 104:22 },
+                                                                                                                                                            end: AstPoint { 104:31 },
                                                                                                                                                         },
                                                                                                                                                         value: "Got a 1",
                                                                                                                                                     },
@@ -12824,8 +12824,8 @@ fn more_syntax() {
                                                                                                                             Base(
                                                                                                                                 AstBaseExpression {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 107:30 },
-                                                                                                                                        end: AstPoint { 107:37 },
+                                                                                                                                        start: AstPoint { 107:22 },
+                                                                                                                                        end: AstPoint { 107:31 },
                                                                                                                                     },
                                                                                                                                     ident: Some(
                                                                                                                                         Value(
@@ -12833,8 +12833,8 @@ fn more_syntax() {
                                                                                                                                                 StringLiteral {
                                                                                                                                                     value: AstIdentifier {
                                                                                                                                                         range: AstRange {
-                                                                                                                                                            start: AstPoint { 107:30 },
-                                                                                                                                                            end: AstPoint { 107:21 },
+                                                                                                                                                            start: AstPoint { 107:22 },
+                                                                                                                                                            end: AstPoint { 107:31 },
                                                                                                                                                         },
                                                                                                                                                         value: "Got a 2",
                                                                                                                                                     },
@@ -12932,8 +12932,8 @@ fn more_syntax() {
                                                                                                                             Base(
                                                                                                                                 AstBaseExpression {
                                                                                                                                     range: AstRange {
-                                                                                                                                        start: AstPoint { 110:34 },
-                                                                                                                                        end: AstPoint { 110:45 },
+                                                                                                                                        start: AstPoint { 110:22 },
+                                                                                                                                        end: AstPoint { 110:35 },
                                                                                                                                     },
                                                                                                                                     ident: Some(
                                                                                                                                         Value(
@@ -12941,8 +12941,8 @@ fn more_syntax() {
                                                                                                                                                 StringLiteral {
                                                                                                                                                     value: AstIdentifier {
                                                                                                                                                         range: AstRange {
-                                                                                                                                                            start: AstPoint { 110:34 },
-                                                                                                                                                            end: AstPoint { 110:21 },
+                                                                                                                                                            start: AstPoint { 110:22 },
+                                                                                                                                                            end: AstPoint { 110:35 },
                                                                                                                                                         },
                                                                                                                                                         value: "More than 2",
                                                                                                                                                     },
@@ -13223,8 +13223,8 @@ fn more_syntax() {
                                                                                                                                                 Base(
                                                                                                                                                     AstBaseExpression {
                                                                                                                                                         range: AstRange {
-                                                                                                                                                            start: AstPoint { 118:41 },
-                                                                                                                                                            end: AstPoint { 118:48 },
+                                                                                                                                                            start: AstPoint { 118:33 },
+                                                                                                                                                            end: AstPoint { 118:42 },
                                                                                                                                                         },
                                                                                                                                                         ident: Some(
                                                                                                                                                             Value(
@@ -13232,8 +13232,8 @@ fn more_syntax() {
                                                                                                                                                                     StringLiteral {
                                                                                                                                                                         value: AstIdentifier {
                                                                                                                                                                             range: AstRange {
-                                                                                                                                                                                start: AstPoint { 118:41 },
-                                                                                                                                                                                end: AstPoint { 118:33 },
+                                                                                                                                                                                start: AstPoint { 118:33 },
+                                                                                                                                                                                end: AstPoint { 118:42 },
                                                                                                                                                                             },
                                                                                                                                                                             value: "Age is ",
                                                                                                                                                                         },
@@ -13542,15 +13542,15 @@ fn more_syntax() {
                                                     Expression(
                                                         AstBlockExpression {
                                                             range: AstRange {
-                                                                start: AstPoint { 122:51 },
-                                                                end: AstPoint { 122:53 },
+                                                                start: AstPoint { 122:8 },
+                                                                end: AstPoint { 124:21 },
                                                             },
                                                             value: [
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 122:51 },
-                                                                            end: AstPoint { 122:92 },
+                                                                            start: AstPoint { 122:8 },
+                                                                            end: AstPoint { 124:20 },
                                                                         },
                                                                         ident: Some(
                                                                             Value(
@@ -13558,8 +13558,8 @@ fn more_syntax() {
                                                                                     StringLiteral {
                                                                                         value: AstIdentifier {
                                                                                             range: AstRange {
-                                                                                                start: AstPoint { 122:51 },
-                                                                                                end: AstPoint { 121:51 },
+                                                                                                start: AstPoint { 122:8 },
+                                                                                                end: AstPoint { 124:20 },
                                                                                             },
                                                                                             value: "\n        Java is better\n        --Thorben",
                                                                                         },
@@ -13578,8 +13578,8 @@ fn more_syntax() {
                                                     Return(
                                                         AstBlockReturn {
                                                             range: AstRange {
-                                                                start: AstPoint { 123:8 },
-                                                                end: AstPoint { 123:15 },
+                                                                start: AstPoint { 125:8 },
+                                                                end: AstPoint { 125:15 },
                                                             },
                                                             expression: None,
                                                         },
@@ -13601,8 +13601,8 @@ fn more_syntax() {
                     Class(
                         AstClass {
                             range: AstRange {
-                                start: AstPoint { 126:0 },
-                                end: AstPoint { 132:1 },
+                                start: AstPoint { 128:0 },
+                                end: AstPoint { 134:1 },
                             },
                             availability: AstAvailability(
                                 0x0,
@@ -13613,8 +13613,8 @@ fn more_syntax() {
                             annotated: [],
                             name: AstIdentifier {
                                 range: AstRange {
-                                    start: AstPoint { 126:6 },
-                                    end: AstPoint { 126:16 },
+                                    start: AstPoint { 128:6 },
+                                    end: AstPoint { 128:16 },
                                 },
                                 value: "OuterClass",
                             },
@@ -13624,14 +13624,14 @@ fn more_syntax() {
                             permits: [],
                             block: AstClassBlock {
                                 range: AstRange {
-                                    start: AstPoint { 126:17 },
-                                    end: AstPoint { 132:1 },
+                                    start: AstPoint { 128:17 },
+                                    end: AstPoint { 134:1 },
                                 },
                                 variables: [
                                     AstClassVariable {
                                         range: AstRange {
-                                            start: AstPoint { 127:2 },
-                                            end: AstPoint { 127:14 },
+                                            start: AstPoint { 129:2 },
+                                            end: AstPoint { 129:12 },
                                         },
                                         availability: AstAvailability(
                                             0x0,
@@ -13639,16 +13639,16 @@ fn more_syntax() {
                                         annotated: [],
                                         name: AstIdentifier {
                                             range: AstRange {
-                                                start: AstPoint { 127:6 },
-                                                end: AstPoint { 127:7 },
+                                                start: AstPoint { 129:6 },
+                                                end: AstPoint { 129:7 },
                                             },
                                             value: "x",
                                         },
                                         jtype: AstJType {
                                             annotated: [],
                                             range: AstRange {
-                                                start: AstPoint { 127:2 },
-                                                end: AstPoint { 127:5 },
+                                                start: AstPoint { 129:2 },
+                                                end: AstPoint { 129:5 },
                                             },
                                             value: Int,
                                         },
@@ -13657,8 +13657,8 @@ fn more_syntax() {
                                                 Base(
                                                     AstBaseExpression {
                                                         range: AstRange {
-                                                            start: AstPoint { 127:12 },
-                                                            end: AstPoint { 127:14 },
+                                                            start: AstPoint { 129:10 },
+                                                            end: AstPoint { 129:12 },
                                                         },
                                                         ident: Some(
                                                             Value(
@@ -13666,8 +13666,8 @@ fn more_syntax() {
                                                                     Int(
                                                                         AstInt {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 127:12 },
-                                                                                end: AstPoint { 127:14 },
+                                                                                start: AstPoint { 129:10 },
+                                                                                end: AstPoint { 129:12 },
                                                                             },
                                                                             value: "10",
                                                                         },
@@ -13693,8 +13693,8 @@ fn more_syntax() {
                                     Class(
                                         AstClass {
                                             range: AstRange {
-                                                start: AstPoint { 129:2 },
-                                                end: AstPoint { 131:3 },
+                                                start: AstPoint { 131:2 },
+                                                end: AstPoint { 133:3 },
                                             },
                                             availability: AstAvailability(
                                                 0x0,
@@ -13705,8 +13705,8 @@ fn more_syntax() {
                                             annotated: [],
                                             name: AstIdentifier {
                                                 range: AstRange {
-                                                    start: AstPoint { 129:8 },
-                                                    end: AstPoint { 129:18 },
+                                                    start: AstPoint { 131:8 },
+                                                    end: AstPoint { 131:18 },
                                                 },
                                                 value: "InnerClass",
                                             },
@@ -13716,14 +13716,14 @@ fn more_syntax() {
                                             permits: [],
                                             block: AstClassBlock {
                                                 range: AstRange {
-                                                    start: AstPoint { 129:19 },
-                                                    end: AstPoint { 131:3 },
+                                                    start: AstPoint { 131:19 },
+                                                    end: AstPoint { 133:3 },
                                                 },
                                                 variables: [
                                                     AstClassVariable {
                                                         range: AstRange {
-                                                            start: AstPoint { 130:4 },
-                                                            end: AstPoint { 130:14 },
+                                                            start: AstPoint { 132:4 },
+                                                            end: AstPoint { 132:13 },
                                                         },
                                                         availability: AstAvailability(
                                                             0x0,
@@ -13731,16 +13731,16 @@ fn more_syntax() {
                                                         annotated: [],
                                                         name: AstIdentifier {
                                                             range: AstRange {
-                                                                start: AstPoint { 130:8 },
-                                                                end: AstPoint { 130:9 },
+                                                                start: AstPoint { 132:8 },
+                                                                end: AstPoint { 132:9 },
                                                             },
                                                             value: "y",
                                                         },
                                                         jtype: AstJType {
                                                             annotated: [],
                                                             range: AstRange {
-                                                                start: AstPoint { 130:4 },
-                                                                end: AstPoint { 130:7 },
+                                                                start: AstPoint { 132:4 },
+                                                                end: AstPoint { 132:7 },
                                                             },
                                                             value: Int,
                                                         },
@@ -13749,8 +13749,8 @@ fn more_syntax() {
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 130:13 },
-                                                                            end: AstPoint { 130:14 },
+                                                                            start: AstPoint { 132:12 },
+                                                                            end: AstPoint { 132:13 },
                                                                         },
                                                                         ident: Some(
                                                                             Value(
@@ -13758,8 +13758,8 @@ fn more_syntax() {
                                                                                     Int(
                                                                                         AstInt {
                                                                                             range: AstRange {
-                                                                                                start: AstPoint { 130:13 },
-                                                                                                end: AstPoint { 130:14 },
+                                                                                                start: AstPoint { 132:12 },
+                                                                                                end: AstPoint { 132:13 },
                                                                                             },
                                                                                             value: "5",
                                                                                         },
@@ -13796,8 +13796,8 @@ fn more_syntax() {
                     Class(
                         AstClass {
                             range: AstRange {
-                                start: AstPoint { 133:0 },
-                                end: AstPoint { 138:1 },
+                                start: AstPoint { 135:0 },
+                                end: AstPoint { 140:1 },
                             },
                             availability: AstAvailability(
                                 Abstract,
@@ -13808,8 +13808,8 @@ fn more_syntax() {
                             annotated: [],
                             name: AstIdentifier {
                                 range: AstRange {
-                                    start: AstPoint { 133:15 },
-                                    end: AstPoint { 133:21 },
+                                    start: AstPoint { 135:15 },
+                                    end: AstPoint { 135:21 },
                                 },
                                 value: "Animal",
                             },
@@ -13819,43 +13819,43 @@ fn more_syntax() {
                             permits: [],
                             block: AstClassBlock {
                                 range: AstRange {
-                                    start: AstPoint { 133:22 },
-                                    end: AstPoint { 138:1 },
+                                    start: AstPoint { 135:22 },
+                                    end: AstPoint { 140:1 },
                                 },
                                 variables: [],
                                 methods: [
                                     AstClassMethod {
                                         range: AstRange {
-                                            start: AstPoint { 134:2 },
-                                            end: AstPoint { 134:37 },
+                                            start: AstPoint { 136:2 },
+                                            end: AstPoint { 136:37 },
                                         },
                                         header: AstMethodHeader {
                                             range: AstRange {
-                                                start: AstPoint { 134:2 },
-                                                end: AstPoint { 134:36 },
+                                                start: AstPoint { 136:2 },
+                                                end: AstPoint { 136:36 },
                                             },
                                             availability: AstAvailability(
                                                 Public | Abstract,
                                             ),
                                             name: AstIdentifier {
                                                 range: AstRange {
-                                                    start: AstPoint { 134:23 },
-                                                    end: AstPoint { 134:34 },
+                                                    start: AstPoint { 136:23 },
+                                                    end: AstPoint { 136:34 },
                                                 },
                                                 value: "animalSound",
                                             },
                                             jtype: AstJType {
                                                 annotated: [],
                                                 range: AstRange {
-                                                    start: AstPoint { 134:18 },
-                                                    end: AstPoint { 134:22 },
+                                                    start: AstPoint { 136:18 },
+                                                    end: AstPoint { 136:22 },
                                                 },
                                                 value: Void,
                                             },
                                             parameters: AstMethodParameters {
                                                 range: AstRange {
-                                                    start: AstPoint { 134:34 },
-                                                    end: AstPoint { 134:36 },
+                                                    start: AstPoint { 136:34 },
+                                                    end: AstPoint { 136:36 },
                                                 },
                                                 parameters: [],
                                             },
@@ -13867,36 +13867,36 @@ fn more_syntax() {
                                     },
                                     AstClassMethod {
                                         range: AstRange {
-                                            start: AstPoint { 135:2 },
-                                            end: AstPoint { 137:3 },
+                                            start: AstPoint { 137:2 },
+                                            end: AstPoint { 139:3 },
                                         },
                                         header: AstMethodHeader {
                                             range: AstRange {
-                                                start: AstPoint { 135:2 },
-                                                end: AstPoint { 135:21 },
+                                                start: AstPoint { 137:2 },
+                                                end: AstPoint { 137:21 },
                                             },
                                             availability: AstAvailability(
                                                 Public,
                                             ),
                                             name: AstIdentifier {
                                                 range: AstRange {
-                                                    start: AstPoint { 135:14 },
-                                                    end: AstPoint { 135:19 },
+                                                    start: AstPoint { 137:14 },
+                                                    end: AstPoint { 137:19 },
                                                 },
                                                 value: "sleep",
                                             },
                                             jtype: AstJType {
                                                 annotated: [],
                                                 range: AstRange {
-                                                    start: AstPoint { 135:9 },
-                                                    end: AstPoint { 135:13 },
+                                                    start: AstPoint { 137:9 },
+                                                    end: AstPoint { 137:13 },
                                                 },
                                                 value: Void,
                                             },
                                             parameters: AstMethodParameters {
                                                 range: AstRange {
-                                                    start: AstPoint { 135:19 },
-                                                    end: AstPoint { 135:21 },
+                                                    start: AstPoint { 137:19 },
+                                                    end: AstPoint { 137:21 },
                                                 },
                                                 parameters: [],
                                             },
@@ -13907,29 +13907,29 @@ fn more_syntax() {
                                         block: Some(
                                             AstBlock {
                                                 range: AstRange {
-                                                    start: AstPoint { 135:22 },
-                                                    end: AstPoint { 137:3 },
+                                                    start: AstPoint { 137:22 },
+                                                    end: AstPoint { 139:3 },
                                                 },
                                                 entries: [
                                                     Expression(
                                                         AstBlockExpression {
                                                             range: AstRange {
-                                                                start: AstPoint { 136:4 },
-                                                                end: AstPoint { 136:30 },
+                                                                start: AstPoint { 138:4 },
+                                                                end: AstPoint { 138:30 },
                                                             },
                                                             value: [
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 136:4 },
-                                                                            end: AstPoint { 136:10 },
+                                                                            start: AstPoint { 138:4 },
+                                                                            end: AstPoint { 138:10 },
                                                                         },
                                                                         ident: Some(
                                                                             Identifier(
                                                                                 AstIdentifier {
                                                                                     range: AstRange {
-                                                                                        start: AstPoint { 136:4 },
-                                                                                        end: AstPoint { 136:10 },
+                                                                                        start: AstPoint { 138:4 },
+                                                                                        end: AstPoint { 138:10 },
                                                                                     },
                                                                                     value: "System",
                                                                                 },
@@ -13942,15 +13942,15 @@ fn more_syntax() {
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 136:10 },
-                                                                            end: AstPoint { 136:11 },
+                                                                            start: AstPoint { 138:10 },
+                                                                            end: AstPoint { 138:11 },
                                                                         },
                                                                         ident: None,
                                                                         values: None,
                                                                         operator: Dot(
                                                                             AstRange {
-                                                                                start: AstPoint { 136:10 },
-                                                                                end: AstPoint { 136:11 },
+                                                                                start: AstPoint { 138:10 },
+                                                                                end: AstPoint { 138:11 },
                                                                             },
                                                                         ),
                                                                     },
@@ -13958,15 +13958,15 @@ fn more_syntax() {
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 136:11 },
-                                                                            end: AstPoint { 136:14 },
+                                                                            start: AstPoint { 138:11 },
+                                                                            end: AstPoint { 138:14 },
                                                                         },
                                                                         ident: Some(
                                                                             Identifier(
                                                                                 AstIdentifier {
                                                                                     range: AstRange {
-                                                                                        start: AstPoint { 136:11 },
-                                                                                        end: AstPoint { 136:14 },
+                                                                                        start: AstPoint { 138:11 },
+                                                                                        end: AstPoint { 138:14 },
                                                                                     },
                                                                                     value: "out",
                                                                                 },
@@ -13979,15 +13979,15 @@ fn more_syntax() {
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 136:14 },
-                                                                            end: AstPoint { 136:15 },
+                                                                            start: AstPoint { 138:14 },
+                                                                            end: AstPoint { 138:15 },
                                                                         },
                                                                         ident: None,
                                                                         values: None,
                                                                         operator: Dot(
                                                                             AstRange {
-                                                                                start: AstPoint { 136:14 },
-                                                                                end: AstPoint { 136:15 },
+                                                                                start: AstPoint { 138:14 },
+                                                                                end: AstPoint { 138:15 },
                                                                             },
                                                                         ),
                                                                     },
@@ -13995,15 +13995,15 @@ fn more_syntax() {
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 136:15 },
-                                                                            end: AstPoint { 136:22 },
+                                                                            start: AstPoint { 138:15 },
+                                                                            end: AstPoint { 138:22 },
                                                                         },
                                                                         ident: Some(
                                                                             Identifier(
                                                                                 AstIdentifier {
                                                                                     range: AstRange {
-                                                                                        start: AstPoint { 136:15 },
-                                                                                        end: AstPoint { 136:22 },
+                                                                                        start: AstPoint { 138:15 },
+                                                                                        end: AstPoint { 138:22 },
                                                                                     },
                                                                                     value: "println",
                                                                                 },
@@ -14016,23 +14016,23 @@ fn more_syntax() {
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 136:22 },
-                                                                            end: AstPoint { 136:29 },
+                                                                            start: AstPoint { 138:22 },
+                                                                            end: AstPoint { 138:29 },
                                                                         },
                                                                         ident: None,
                                                                         values: Some(
                                                                             AstValues {
                                                                                 range: AstRange {
-                                                                                    start: AstPoint { 136:22 },
-                                                                                    end: AstPoint { 136:29 },
+                                                                                    start: AstPoint { 138:22 },
+                                                                                    end: AstPoint { 138:29 },
                                                                                 },
                                                                                 values: [
                                                                                     [
                                                                                         Base(
                                                                                             AstBaseExpression {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 136:27 },
-                                                                                                    end: AstPoint { 136:30 },
+                                                                                                    start: AstPoint { 138:23 },
+                                                                                                    end: AstPoint { 138:28 },
                                                                                                 },
                                                                                                 ident: Some(
                                                                                                     Value(
@@ -14040,8 +14040,8 @@ fn more_syntax() {
                                                                                                             StringLiteral {
                                                                                                                 value: AstIdentifier {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 136:27 },
-                                                                                                                        end: AstPoint { 136:23 },
+                                                                                                                        start: AstPoint { 138:23 },
+                                                                                                                        end: AstPoint { 138:28 },
                                                                                                                     },
                                                                                                                     value: "Zzz",
                                                                                                                 },
@@ -14144,8 +14144,8 @@ fn variable_array() {
                                             Base(
                                                 AstBaseExpression {
                                                     range: AstRange {
-                                                        start: AstPoint { 0:23 },
-                                                        end: AstPoint { 0:28 },
+                                                        start: AstPoint { 0:17 },
+                                                        end: AstPoint { 0:24 },
                                                     },
                                                     ident: Some(
                                                         Value(
@@ -14153,8 +14153,8 @@ fn variable_array() {
                                                                 StringLiteral {
                                                                     value: AstIdentifier {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 0:23 },
-                                                                            end: AstPoint { 0:17 },
+                                                                            start: AstPoint { 0:17 },
+                                                                            end: AstPoint { 0:24 },
                                                                         },
                                                                         value: "Volvo",
                                                                     },
@@ -14172,8 +14172,8 @@ fn variable_array() {
                                             Base(
                                                 AstBaseExpression {
                                                     range: AstRange {
-                                                        start: AstPoint { 0:30 },
-                                                        end: AstPoint { 0:33 },
+                                                        start: AstPoint { 0:26 },
+                                                        end: AstPoint { 0:31 },
                                                     },
                                                     ident: Some(
                                                         Value(
@@ -14181,8 +14181,8 @@ fn variable_array() {
                                                                 StringLiteral {
                                                                     value: AstIdentifier {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 0:30 },
-                                                                            end: AstPoint { 0:25 },
+                                                                            start: AstPoint { 0:26 },
+                                                                            end: AstPoint { 0:31 },
                                                                         },
                                                                         value: "BMW",
                                                                     },
@@ -14200,8 +14200,8 @@ fn variable_array() {
                                             Base(
                                                 AstBaseExpression {
                                                     range: AstRange {
-                                                        start: AstPoint { 0:38 },
-                                                        end: AstPoint { 0:42 },
+                                                        start: AstPoint { 0:33 },
+                                                        end: AstPoint { 0:39 },
                                                     },
                                                     ident: Some(
                                                         Value(
@@ -14209,8 +14209,8 @@ fn variable_array() {
                                                                 StringLiteral {
                                                                     value: AstIdentifier {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 0:38 },
-                                                                            end: AstPoint { 0:32 },
+                                                                            start: AstPoint { 0:33 },
+                                                                            end: AstPoint { 0:39 },
                                                                         },
                                                                         value: "Ford",
                                                                     },
@@ -14228,8 +14228,8 @@ fn variable_array() {
                                             Base(
                                                 AstBaseExpression {
                                                     range: AstRange {
-                                                        start: AstPoint { 0:47 },
-                                                        end: AstPoint { 0:52 },
+                                                        start: AstPoint { 0:41 },
+                                                        end: AstPoint { 0:48 },
                                                     },
                                                     ident: Some(
                                                         Value(
@@ -14237,8 +14237,8 @@ fn variable_array() {
                                                                 StringLiteral {
                                                                     value: AstIdentifier {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 0:47 },
-                                                                            end: AstPoint { 0:40 },
+                                                                            start: AstPoint { 0:41 },
+                                                                            end: AstPoint { 0:48 },
                                                                         },
                                                                         value: "Mazda",
                                                                     },
@@ -14283,7 +14283,7 @@ fn variable_var_no_value() {
                             AstBlockVariable {
                                 range: AstRange {
                                     start: AstPoint { 0:1 },
-                                    end: AstPoint { 0:9 },
+                                    end: AstPoint { 0:8 },
                                 },
                                 fin: false,
                                 annotated: [],
@@ -14330,8 +14330,8 @@ fn multi_line_string() {
             StringLiteral {
                 value: AstIdentifier {
                     range: AstRange {
-                        start: AstPoint { 1:72 },
-                        end: AstPoint { 1:136 },
+                        start: AstPoint { 1:8 },
+                        end: AstPoint { 5:8 },
                     },
                     value: "\n        Here is a muilti\n        line\n        string\"\"\n        ",
                 },
@@ -15381,8 +15381,8 @@ fn new_array() {
                                                                 Base(
                                                                     AstBaseExpression {
                                                                         range: AstRange {
-                                                                            start: AstPoint { 2:29 },
-                                                                            end: AstPoint { 2:43 },
+                                                                            start: AstPoint { 2:14 },
+                                                                            end: AstPoint { 2:30 },
                                                                         },
                                                                         ident: Some(
                                                                             Value(
@@ -15390,8 +15390,8 @@ fn new_array() {
                                                                                     StringLiteral {
                                                                                         value: AstIdentifier {
                                                                                             range: AstRange {
-                                                                                                start: AstPoint { 2:29 },
-                                                                                                end: AstPoint { 2:13 },
+                                                                                                start: AstPoint { 2:14 },
+                                                                                                end: AstPoint { 2:30 },
                                                                                             },
                                                                                             value: "NumberPatterns",
                                                                                         },
@@ -15448,8 +15448,8 @@ fn new_array() {
                                                                                         Base(
                                                                                             AstBaseExpression {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 4:20 },
-                                                                                                    end: AstPoint { 4:20 },
+                                                                                                    start: AstPoint { 4:19 },
+                                                                                                    end: AstPoint { 4:21 },
                                                                                                 },
                                                                                                 ident: Some(
                                                                                                     Value(
@@ -15457,8 +15457,8 @@ fn new_array() {
                                                                                                             StringLiteral {
                                                                                                                 value: AstIdentifier {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 4:20 },
-                                                                                                                        end: AstPoint { 3:30 },
+                                                                                                                        start: AstPoint { 4:19 },
+                                                                                                                        end: AstPoint { 4:21 },
                                                                                                                     },
                                                                                                                     value: "",
                                                                                                                 },
@@ -15476,8 +15476,8 @@ fn new_array() {
                                                                                         Base(
                                                                                             AstBaseExpression {
                                                                                                 range: AstRange {
-                                                                                                    start: AstPoint { 5:20 },
-                                                                                                    end: AstPoint { 5:20 },
+                                                                                                    start: AstPoint { 5:19 },
+                                                                                                    end: AstPoint { 5:21 },
                                                                                                 },
                                                                                                 ident: Some(
                                                                                                     Value(
@@ -15485,8 +15485,8 @@ fn new_array() {
                                                                                                             StringLiteral {
                                                                                                                 value: AstIdentifier {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 5:20 },
-                                                                                                                        end: AstPoint { 4:22 },
+                                                                                                                        start: AstPoint { 5:19 },
+                                                                                                                        end: AstPoint { 5:21 },
                                                                                                                     },
                                                                                                                     value: "",
                                                                                                                 },
@@ -15664,8 +15664,8 @@ fn long_expr() {
                                         Base(
                                             AstBaseExpression {
                                                 range: AstRange {
-                                                    start: AstPoint { 0:40 },
-                                                    end: AstPoint { 0:49 },
+                                                    start: AstPoint { 0:30 },
+                                                    end: AstPoint { 0:41 },
                                                 },
                                                 ident: Some(
                                                     Value(
@@ -15673,8 +15673,8 @@ fn long_expr() {
                                                             StringLiteral {
                                                                 value: AstIdentifier {
                                                                     range: AstRange {
-                                                                        start: AstPoint { 0:40 },
-                                                                        end: AstPoint { 0:30 },
+                                                                        start: AstPoint { 0:30 },
+                                                                        end: AstPoint { 0:41 },
                                                                     },
                                                                     value: "localhost",
                                                                 },
@@ -15692,8 +15692,8 @@ fn long_expr() {
                                         Base(
                                             AstBaseExpression {
                                                 range: AstRange {
-                                                    start: AstPoint { 0:47 },
-                                                    end: AstPoint { 0:51 },
+                                                    start: AstPoint { 0:43 },
+                                                    end: AstPoint { 0:47 },
                                                 },
                                                 ident: Some(
                                                     Value(
@@ -15701,8 +15701,8 @@ fn long_expr() {
                                                             Int(
                                                                 AstInt {
                                                                     range: AstRange {
-                                                                        start: AstPoint { 0:47 },
-                                                                        end: AstPoint { 0:51 },
+                                                                        start: AstPoint { 0:43 },
+                                                                        end: AstPoint { 0:47 },
                                                                     },
                                                                     value: "1344",
                                                                 },
@@ -15845,7 +15845,7 @@ fn cast() {
             AstNewClass {
                 range: AstRange {
                     start: AstPoint { 0:0 },
-                    end: AstPoint { 0:21 },
+                    end: AstPoint { 0:22 },
                 },
                 jtype: AstJType {
                     annotated: [],
@@ -15868,7 +15868,7 @@ fn cast() {
                     AstValues {
                         range: AstRange {
                             start: AstPoint { 0:11 },
-                            end: AstPoint { 0:21 },
+                            end: AstPoint { 0:22 },
                         },
                         values: [
                             [
@@ -15918,8 +15918,8 @@ fn cast() {
                                 Base(
                                     AstBaseExpression {
                                         range: AstRange {
-                                            start: AstPoint { 0:19 },
-                                            end: AstPoint { 0:20 },
+                                            start: AstPoint { 0:18 },
+                                            end: AstPoint { 0:21 },
                                         },
                                         ident: Some(
                                             Value(
@@ -15927,8 +15927,8 @@ fn cast() {
                                                     CharLiteral(
                                                         AstIdentifier {
                                                             range: AstRange {
-                                                                start: AstPoint { 0:19 },
-                                                                end: AstPoint { 0:18 },
+                                                                start: AstPoint { 0:18 },
+                                                                end: AstPoint { 0:21 },
                                                             },
                                                             value: "a",
                                                         },
@@ -16620,8 +16620,8 @@ fn new_casted_parameter() {
                             Base(
                                 AstBaseExpression {
                                     range: AstRange {
-                                        start: AstPoint { 0:19 },
-                                        end: AstPoint { 0:21 },
+                                        start: AstPoint { 0:17 },
+                                        end: AstPoint { 0:19 },
                                     },
                                     ident: Some(
                                         Value(
@@ -16629,8 +16629,8 @@ fn new_casted_parameter() {
                                                 Int(
                                                     AstInt {
                                                         range: AstRange {
-                                                            start: AstPoint { 0:19 },
-                                                            end: AstPoint { 0:21 },
+                                                            start: AstPoint { 0:17 },
+                                                            end: AstPoint { 0:19 },
                                                         },
                                                         value: "10",
                                                     },
@@ -16690,8 +16690,8 @@ fn new_casted_parameter() {
                             Base(
                                 AstBaseExpression {
                                     range: AstRange {
-                                        start: AstPoint { 0:30 },
-                                        end: AstPoint { 0:35 },
+                                        start: AstPoint { 0:29 },
+                                        end: AstPoint { 0:33 },
                                     },
                                     ident: Some(
                                         Value(
@@ -16699,8 +16699,8 @@ fn new_casted_parameter() {
                                                 Float(
                                                     AstDouble {
                                                         range: AstRange {
-                                                            start: AstPoint { 0:30 },
-                                                            end: AstPoint { 0:31 },
+                                                            start: AstPoint { 0:29 },
+                                                            end: AstPoint { 0:30 },
                                                         },
                                                         value: "3.00",
                                                     },
@@ -17004,14 +17004,14 @@ fn multiline_string_arg() {
             AstBlock {
                 range: AstRange {
                     start: AstPoint { 0:0 },
-                    end: AstPoint { 2:43 },
+                    end: AstPoint { 7:43 },
                 },
                 entries: [
                     Expression(
                         AstBlockExpression {
                             range: AstRange {
                                 start: AstPoint { 1:24 },
-                                end: AstPoint { 1:512 },
+                                end: AstPoint { 6:47 },
                             },
                             value: [
                                 Base(
@@ -17113,22 +17113,22 @@ fn multiline_string_arg() {
                                     AstBaseExpression {
                                         range: AstRange {
                                             start: AstPoint { 1:42 },
-                                            end: AstPoint { 1:511 },
+                                            end: AstPoint { 6:46 },
                                         },
                                         ident: None,
                                         values: Some(
                                             AstValues {
                                                 range: AstRange {
                                                     start: AstPoint { 1:42 },
-                                                    end: AstPoint { 1:511 },
+                                                    end: AstPoint { 6:46 },
                                                 },
                                                 values: [
                                                     [
                                                         Base(
                                                             AstBaseExpression {
                                                                 range: AstRange {
-                                                                    start: AstPoint { 1:509 },
-                                                                    end: AstPoint { 1:973 },
+                                                                    start: AstPoint { 1:43 },
+                                                                    end: AstPoint { 6:45 },
                                                                 },
                                                                 ident: Some(
                                                                     Value(
@@ -17136,8 +17136,8 @@ fn multiline_string_arg() {
                                                                             StringLiteral {
                                                                                 value: AstIdentifier {
                                                                                     range: AstRange {
-                                                                                        start: AstPoint { 1:509 },
-                                                                                        end: AstPoint { 1:43 },
+                                                                                        start: AstPoint { 1:43 },
+                                                                                        end: AstPoint { 6:45 },
                                                                                     },
                                                                                     value: "\n                                          The VFORK launch mechanism has been deprecated for being dangerous.\n                                          It will be removed in a future java version. Either remove the\n                                          jdk.lang.Process.launchMechanism property (preferred) or use FORK mode\n                                          instead (-Djdk.lang.Process.launchMechanism=FORK).\n                                          ",
                                                                                 },
@@ -18286,8 +18286,8 @@ fn casted_calculation() {
                                                         Base(
                                                             AstBaseExpression {
                                                                 range: AstRange {
-                                                                    start: AstPoint { 0:52 },
-                                                                    end: AstPoint { 0:60 },
+                                                                    start: AstPoint { 0:44 },
+                                                                    end: AstPoint { 0:52 },
                                                                 },
                                                                 ident: Some(
                                                                     Value(
@@ -18295,8 +18295,8 @@ fn casted_calculation() {
                                                                             Int(
                                                                                 AstInt {
                                                                                     range: AstRange {
-                                                                                        start: AstPoint { 0:52 },
-                                                                                        end: AstPoint { 0:60 },
+                                                                                        start: AstPoint { 0:44 },
+                                                                                        end: AstPoint { 0:52 },
                                                                                     },
                                                                                     value: "1000_000",
                                                                                 },
@@ -18428,8 +18428,8 @@ fn annotated_named() {
                                                             Base(
                                                                 AstBaseExpression {
                                                                     range: AstRange {
-                                                                        start: AstPoint { 0:70 },
-                                                                        end: AstPoint { 0:79 },
+                                                                        start: AstPoint { 0:60 },
+                                                                        end: AstPoint { 0:71 },
                                                                     },
                                                                     ident: Some(
                                                                         Value(
@@ -18437,8 +18437,8 @@ fn annotated_named() {
                                                                                 StringLiteral {
                                                                                     value: AstIdentifier {
                                                                                         range: AstRange {
-                                                                                            start: AstPoint { 0:70 },
-                                                                                            end: AstPoint { 0:60 },
+                                                                                            start: AstPoint { 0:60 },
+                                                                                            end: AstPoint { 0:71 },
                                                                                         },
                                                                                         value: "otherUuid",
                                                                                     },
@@ -18458,8 +18458,8 @@ fn annotated_named() {
                                                             Base(
                                                                 AstBaseExpression {
                                                                     range: AstRange {
-                                                                        start: AstPoint { 0:82 },
-                                                                        end: AstPoint { 0:90 },
+                                                                        start: AstPoint { 0:73 },
+                                                                        end: AstPoint { 0:83 },
                                                                     },
                                                                     ident: Some(
                                                                         Value(
@@ -18467,8 +18467,8 @@ fn annotated_named() {
                                                                                 StringLiteral {
                                                                                     value: AstIdentifier {
                                                                                         range: AstRange {
-                                                                                            start: AstPoint { 0:82 },
-                                                                                            end: AstPoint { 0:72 },
+                                                                                            start: AstPoint { 0:73 },
+                                                                                            end: AstPoint { 0:83 },
                                                                                         },
                                                                                         value: "thing_id",
                                                                                     },
@@ -18535,8 +18535,8 @@ fn annotated_array() {
                                                 Base(
                                                     AstBaseExpression {
                                                         range: AstRange {
-                                                            start: AstPoint { 0:29 },
-                                                            end: AstPoint { 0:38 },
+                                                            start: AstPoint { 0:19 },
+                                                            end: AstPoint { 0:30 },
                                                         },
                                                         ident: Some(
                                                             Value(
@@ -18544,8 +18544,8 @@ fn annotated_array() {
                                                                     StringLiteral {
                                                                         value: AstIdentifier {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 0:29 },
-                                                                                end: AstPoint { 0:19 },
+                                                                                start: AstPoint { 0:19 },
+                                                                                end: AstPoint { 0:30 },
                                                                             },
                                                                             value: "unchecked",
                                                                         },
@@ -18563,8 +18563,8 @@ fn annotated_array() {
                                                 Base(
                                                     AstBaseExpression {
                                                         range: AstRange {
-                                                            start: AstPoint { 0:41 },
-                                                            end: AstPoint { 0:49 },
+                                                            start: AstPoint { 0:32 },
+                                                            end: AstPoint { 0:42 },
                                                         },
                                                         ident: Some(
                                                             Value(
@@ -18572,8 +18572,8 @@ fn annotated_array() {
                                                                     StringLiteral {
                                                                         value: AstIdentifier {
                                                                             range: AstRange {
-                                                                                start: AstPoint { 0:41 },
-                                                                                end: AstPoint { 0:31 },
+                                                                                start: AstPoint { 0:32 },
+                                                                                end: AstPoint { 0:42 },
                                                                             },
                                                                             value: "rawtypes",
                                                                         },
@@ -18782,8 +18782,8 @@ fn nuget_long_underscore() {
                 Long(
                     AstInt {
                         range: AstRange {
-                            start: AstPoint { 0:14 },
-                            end: AstPoint { 0:28 },
+                            start: AstPoint { 0:0 },
+                            end: AstPoint { 0:14 },
                         },
                         value: "10_000_000_000",
                     },
@@ -19111,8 +19111,8 @@ fn inline_switch() {
                                                                                                                                 Base(
                                                                                                                                     AstBaseExpression {
                                                                                                                                         range: AstRange {
-                                                                                                                                            start: AstPoint { 3:29 },
-                                                                                                                                            end: AstPoint { 3:30 },
+                                                                                                                                            start: AstPoint { 3:28 },
+                                                                                                                                            end: AstPoint { 3:29 },
                                                                                                                                         },
                                                                                                                                         ident: Some(
                                                                                                                                             Value(
@@ -19120,8 +19120,8 @@ fn inline_switch() {
                                                                                                                                                     Int(
                                                                                                                                                         AstInt {
                                                                                                                                                             range: AstRange {
-                                                                                                                                                                start: AstPoint { 3:29 },
-                                                                                                                                                                end: AstPoint { 3:30 },
+                                                                                                                                                                start: AstPoint { 3:28 },
+                                                                                                                                                                end: AstPoint { 3:29 },
                                                                                                                                                             },
                                                                                                                                                             value: "1",
                                                                                                                                                         },
@@ -19170,8 +19170,8 @@ fn inline_switch() {
                                                                                                             Base(
                                                                                                                 AstBaseExpression {
                                                                                                                     range: AstRange {
-                                                                                                                        start: AstPoint { 3:40 },
-                                                                                                                        end: AstPoint { 3:41 },
+                                                                                                                        start: AstPoint { 3:39 },
+                                                                                                                        end: AstPoint { 3:40 },
                                                                                                                     },
                                                                                                                     ident: Some(
                                                                                                                         Value(
@@ -19179,8 +19179,8 @@ fn inline_switch() {
                                                                                                                                 Int(
                                                                                                                                     AstInt {
                                                                                                                                         range: AstRange {
-                                                                                                                                            start: AstPoint { 3:40 },
-                                                                                                                                            end: AstPoint { 3:41 },
+                                                                                                                                            start: AstPoint { 3:39 },
+                                                                                                                                            end: AstPoint { 3:40 },
                                                                                                                                         },
                                                                                                                                         value: "1",
                                                                                                                                     },
@@ -19597,8 +19597,8 @@ fn top_level_function() {
                                                                         Base(
                                                                             AstBaseExpression {
                                                                                 range: AstRange {
-                                                                                    start: AstPoint { 1:31 },
-                                                                                    end: AstPoint { 1:34 },
+                                                                                    start: AstPoint { 1:27 },
+                                                                                    end: AstPoint { 1:32 },
                                                                                 },
                                                                                 ident: Some(
                                                                                     Value(
@@ -19606,8 +19606,8 @@ fn top_level_function() {
                                                                                             StringLiteral {
                                                                                                 value: AstIdentifier {
                                                                                                     range: AstRange {
-                                                                                                        start: AstPoint { 1:31 },
-                                                                                                        end: AstPoint { 1:27 },
+                                                                                                        start: AstPoint { 1:27 },
+                                                                                                        end: AstPoint { 1:32 },
                                                                                                     },
                                                                                                     value: "Hey",
                                                                                                 },
@@ -0,0 +1,396 @@
+//! Java targets of a bazel workspace
+//!
+//! The targets that own an opened file are found with `bazel query`. Their source roots and the
+//! compile time jars of their dependencies are loaded once per target. The result of a target is
+//! cached until the build file of its package or the module of the workspace changes.
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    fs,
+    hash::{BuildHasher, DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    process::Command,
+    str::{Utf8Error, from_utf8},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use dto::{Class, SourceDestination};
+use my_string::MyString;
+
+#[derive(Debug)]
+pub enum BazelError {
+    IO(std::io::Error),
+    Utf8(Utf8Error),
+    Query(String),
+    NotInPackage,
+}
+
+const WORKSPACE_FILES: [&str; 3] = ["MODULE.bazel", "WORKSPACE.bazel", "WORKSPACE"];
+const BUILD_FILES: [&str; 2] = ["BUILD.bazel", "BUILD"];
+
+/// Prints the compile time jars of the dependencies, paths are relative to the execution root
+const COMPILE_JARS_EXPR: &str = r#""\n".join([j.path for k in providers(target) if k.endswith("JavaInfo") for j in providers(target)[k].transitive_compile_time_jars.to_list()])"#;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TargetInfo {
+    pub source_roots: Vec<PathBuf>,
+    pub jars: Vec<PathBuf>,
+}
+
+impl TargetInfo {
+    fn parse(content: &str) -> Self {
+        let mut out = Self::default();
+        for line in content.lines() {
+            if let Some(root) = line.strip_prefix("SRC:") {
+                out.source_roots.push(PathBuf::from(root));
+            } else if let Some(jar) = line.strip_prefix("JAR:") {
+                out.jars.push(PathBuf::from(jar));
+            }
+        }
+        out
+    }
+
+    fn serialize(&self) -> String {
+        let sources = self.source_roots.iter().map(|p| ("SRC:", p));
+        let jars = self.jars.iter().map(|p| ("JAR:", p));
+        sources
+            .chain(jars)
+            .fold(String::new(), |mut out, (prefix, p)| {
+                let _ = writeln!(out, "{prefix}{}", p.display());
+                out
+            })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Loaded {
+    targets: HashSet<String>,
+    source_roots: HashSet<PathBuf>,
+    jars: HashSet<PathBuf>,
+}
+
+/// Targets whose source roots and jars are in the class map
+#[derive(Debug, Default)]
+pub struct BazelTargets {
+    loaded: Mutex<Loaded>,
+}
+
+impl BazelTargets {
+    /// Load the targets that own the file, targets that were loaded before are skipped
+    pub fn index_file<S: BuildHasher>(
+        &self,
+        class_map: &Arc<RwLock<HashMap<MyString, Class, S>>>,
+        executable: &str,
+        workspace: &Path,
+        file: &Path,
+    ) -> Result<(), BazelError> {
+        let label = file_label(workspace, file).ok_or(BazelError::NotInPackage)?;
+        for target in owning_targets(executable, workspace, &label)? {
+            if self
+                .loaded
+                .lock()
+                .is_ok_and(|loaded| loaded.targets.contains(&target))
+            {
+                continue;
+            }
+            let info = load_target(executable, workspace, &target)?;
+            let (source_roots, jars) = {
+                let Ok(mut loaded) = self.loaded.lock() else {
+                    return Ok(());
+                };
+                loaded.targets.insert(target);
+                let source_roots: Vec<PathBuf> = info
+                    .source_roots
+                    .into_iter()
+                    .filter(|r| loaded.source_roots.insert(r.clone()))
+                    .collect();
+                let jars: Vec<PathBuf> = info
+                    .jars
+                    .into_iter()
+                    .filter(|j| loaded.jars.insert(j.clone()))
+                    .collect();
+                (source_roots, jars)
+            };
+            let mut classes = loader::load_java_folders(source_roots, |_, _| {});
+            classes.extend(
+                loader::parallel::parallel_map(
+                    &jars,
+                    |jar| match loader::load_classes_jar(jar, SourceDestination::None) {
+                        Ok(classes) => Some(classes.classes),
+                        Err(e) => {
+                            eprintln!("Failed to load jar: {}, {e:?}", jar.display());
+                            None
+                        }
+                    },
+                    |_, _| {},
+                )
+                .into_iter()
+                .flatten(),
+            );
+            if let Ok(mut cm) = class_map.write() {
+                for class in classes {
+                    cm.insert(class.class_path.clone(), class);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A build file changed, targets are loaded again when their files are opened
+    pub fn clear(&self) {
+        if let Ok(mut loaded) = self.loaded.lock() {
+            *loaded = Loaded::default();
+        }
+    }
+}
+
+/// Nearest folder of the file that has a build file
+fn package_dir<'a>(workspace: &Path, file: &'a Path) -> Option<&'a Path> {
+    file.ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(workspace))
+        .find(|dir| BUILD_FILES.iter().any(|b| dir.join(b).exists()))
+}
+
+/// Label of a source file, `//pkg:path/in/pkg.java`
+#[must_use]
+pub fn file_label(workspace: &Path, file: &Path) -> Option<String> {
+    let package = package_dir(workspace, file)?;
+    let package_name = package.strip_prefix(workspace).ok()?;
+    let name = file.strip_prefix(package).ok()?;
+    Some(format!(
+        "//{}:{}",
+        to_label_path(package_name)?,
+        to_label_path(name)?
+    ))
+}
+
+fn to_label_path(path: &Path) -> Option<String> {
+    let parts: Vec<&str> = path.iter().map(|p| p.to_str()).collect::<Option<_>>()?;
+    Some(parts.join("/"))
+}
+
+fn bazel(executable: &str, workspace: &Path, args: &[&str]) -> Result<String, BazelError> {
+    let out = Command::new(executable)
+        .current_dir(workspace)
+        .args(args)
+        .output()
+        .map_err(BazelError::IO)?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(BazelError::Query(stderr.to_string()));
+    }
+    from_utf8(&out.stdout)
+        .map(ToOwned::to_owned)
+        .map_err(BazelError::Utf8)
+}
+
+/// Java rules of the package that have the file in their sources
+pub fn owning_targets(
+    executable: &str,
+    workspace: &Path,
+    label: &str,
+) -> Result<Vec<String>, BazelError> {
+    let query = format!("kind(\"java_.* rule\", same_pkg_direct_rdeps({label}))");
+    let out = bazel(
+        executable,
+        workspace,
+        &["query", "--output=label", "--keep_going", &query],
+    )?;
+    Ok(out.lines().map(ToOwned::to_owned).collect())
+}
+
+/// Source roots and jars of the target, from the cache when the build files did not change
+pub fn load_target(
+    executable: &str,
+    workspace: &Path,
+    target: &str,
+) -> Result<TargetInfo, BazelError> {
+    let cache_path = get_target_cache_path(workspace, target, &common::project_cache_dir());
+    if cache_valid(&cache_path, workspace, target)
+        && let Ok(content) = fs::read_to_string(&cache_path)
+    {
+        return Ok(TargetInfo::parse(&content));
+    }
+    let info = query_target(executable, workspace, target)?;
+    if let Err(e) = fs::write(&cache_path, info.serialize()) {
+        eprintln!("Failed to save {} because: {e:?}", cache_path.display());
+    }
+    Ok(info)
+}
+
+fn query_target(
+    executable: &str,
+    workspace: &Path,
+    target: &str,
+) -> Result<TargetInfo, BazelError> {
+    let query = format!("labels(srcs, kind(\"java_.* rule\", deps({target})))");
+    let locations = bazel(
+        executable,
+        workspace,
+        &["query", "--output=location", "--keep_going", &query],
+    )?;
+    let mut source_roots: Vec<PathBuf> = vec![];
+    let mut seen_dirs = HashSet::new();
+    for file in parse_locations(&locations, workspace) {
+        // Files of a folder share the root
+        if !file
+            .parent()
+            .is_some_and(|d| seen_dirs.insert(d.to_path_buf()))
+        {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        if let Some(root) = source_root(&file, &content)
+            && !source_roots.contains(&root)
+        {
+            source_roots.push(root);
+        }
+    }
+
+    let execution_root = bazel(executable, workspace, &["info", "execution_root"])?;
+    let execution_root = PathBuf::from(execution_root.trim());
+    let expr = format!("--starlark:expr={COMPILE_JARS_EXPR}");
+    let jars = bazel(
+        executable,
+        workspace,
+        &["cquery", "--output=starlark", &expr, target],
+    )?;
+    let jars = jars
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(|l| execution_root.join(l))
+        .collect();
+    Ok(TargetInfo { source_roots, jars })
+}
+
+#[must_use]
+pub fn get_target_cache_path(workspace: &Path, target: &str, project_cache_dir: &Path) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    workspace.hash(&mut hasher);
+    target.hash(&mut hasher);
+    project_cache_dir.join(format!("{}.bazel.txt", hasher.finish()))
+}
+
+fn cache_valid(cache_path: &Path, workspace: &Path, target: &str) -> bool {
+    let Ok(cached) = fs::metadata(cache_path).and_then(|m| m.modified()) else {
+        return false;
+    };
+    let package = target
+        .trim_start_matches("//")
+        .split(':')
+        .next()
+        .unwrap_or_default();
+    let package = workspace.join(package);
+    WORKSPACE_FILES
+        .iter()
+        .map(|f| workspace.join(f))
+        .chain(BUILD_FILES.iter().map(|f| package.join(f)))
+        .filter_map(|f| fs::metadata(f).and_then(|m| m.modified()).ok())
+        .all(|modified| modified <= cached)
+}
+
+/// Java files of `bazel query --output=location` that are in the workspace
+#[must_use]
+pub fn parse_locations(output: &str, workspace: &Path) -> Vec<PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(": source file ").map(|(l, _)| l))
+        .filter_map(|location| {
+            // Strip `:line:column`
+            let mut parts = location.rsplitn(3, ':');
+            parts.next()?;
+            parts.next()?;
+            parts.next()
+        })
+        .map(PathBuf::from)
+        .filter(|p| p.starts_with(workspace))
+        .filter(|p| p.extension().is_some_and(|e| e == "java"))
+        .collect()
+}
+
+/// Folder of the file without the folders of its package
+#[must_use]
+pub fn source_root(file: &Path, content: &str) -> Option<PathBuf> {
+    let mut root = file.parent()?.to_path_buf();
+    let package = content
+        .lines()
+        .map(str::trim)
+        .find_map(|l| l.strip_prefix("package "))
+        .and_then(|l| l.split(';').next())
+        .map(str::trim);
+    let Some(package) = package else {
+        return Some(root);
+    };
+    for part in package.rsplit('.') {
+        if root.file_name().and_then(|n| n.to_str()) != Some(part) {
+            return None;
+        }
+        root.pop();
+    }
+    Some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{TargetInfo, file_label, parse_locations, source_root};
+
+    #[test]
+    fn labels_of_files() {
+        let dir = std::env::temp_dir().join(format!("bazel-label-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("app/src/main/java/a")).unwrap();
+        fs::write(dir.join("MODULE.bazel"), "").unwrap();
+        fs::write(dir.join("app/BUILD.bazel"), "").unwrap();
+        let file = dir.join("app/src/main/java/a/A.java");
+        assert_eq!(
+            file_label(&dir, &file),
+            Some("//app:src/main/java/a/A.java".to_string())
+        );
+        assert_eq!(file_label(&dir, &dir.join("other/B.java")), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn locations() {
+        let output = "/ws/app/src/a/A.java:1:1: source file //app:src/a/A.java
+/ws/app/src/a/B.java:1:1: source file //app:src/a/B.java
+/cache/external/lib/C.java:1:1: source file @lib//:C.java
+/ws/app/res/config.txt:1:1: source file //app:res/config.txt
+";
+        assert_eq!(
+            parse_locations(output, &PathBuf::from("/ws")),
+            vec![
+                PathBuf::from("/ws/app/src/a/A.java"),
+                PathBuf::from("/ws/app/src/a/B.java"),
+            ]
+        );
+    }
+
+    #[test]
+    fn source_roots() {
+        let file = PathBuf::from("/ws/app/src/ch/emilycares/A.java");
+        assert_eq!(
+            source_root(&file, "// license\npackage ch.emilycares;\nclass A {}"),
+            Some(PathBuf::from("/ws/app/src"))
+        );
+        assert_eq!(
+            source_root(&file, "class A {}"),
+            Some(PathBuf::from("/ws/app/src/ch/emilycares"))
+        );
+        assert_eq!(source_root(&file, "package other;"), None);
+    }
+
+    #[test]
+    fn target_info_cache() {
+        let info = TargetInfo {
+            source_roots: vec![PathBuf::from("/ws/app/src")],
+            jars: vec![PathBuf::from("/root/execroot/external/guava.jar")],
+        };
+        assert_eq!(TargetInfo::parse(&info.serialize()), info);
+    }
+}
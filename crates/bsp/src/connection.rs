@@ -0,0 +1,294 @@
+//! Connection files of build servers and the json-rpc messages that are sent to them
+//!
+//! A build server writes a connection file into the `.bsp` folder of the workspace. Its `argv`
+//! starts the server, which talks the build server protocol over stdio with the same framing as
+//! the language server protocol.
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+pub const BSP_DIR: &str = ".bsp";
+/// Version of the build server protocol that is implemented
+pub const BSP_VERSION: &str = "2.1.0";
+const PUBLISH_DIAGNOSTICS: &str = "build/publishDiagnostics";
+
+#[derive(Debug)]
+pub enum BspError {
+    IO(std::io::Error),
+    Json(serde_json::Error),
+    /// The connection file has no command
+    NoCommand,
+    /// Content-Length header is missing
+    Header,
+    /// The server answered with an error
+    Response(String),
+    /// The server exited
+    Closed,
+}
+
+/// Content of `.bsp/<name>.json`
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionDetails {
+    pub name: String,
+    pub argv: Vec<String>,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub bsp_version: String,
+    /// A server without languages is asked for its targets anyway
+    #[serde(default)]
+    pub languages: Vec<String>,
+}
+
+impl ConnectionDetails {
+    pub fn load(path: &Path) -> Result<Self, BspError> {
+        let content = fs::read_to_string(path).map_err(BspError::IO)?;
+        serde_json::from_str(&content).map_err(BspError::Json)
+    }
+
+    fn java(&self) -> bool {
+        self.languages.is_empty() || self.languages.iter().any(|l| l == "java")
+    }
+}
+
+/// First connection file of the workspace, by name, of a server that builds java
+#[must_use]
+pub fn find_connection(workspace: &Path) -> Option<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(workspace.join(BSP_DIR))
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .collect();
+    files.sort();
+    files
+        .into_iter()
+        .find(|p| ConnectionDetails::load(p).is_ok_and(|d| d.java()))
+}
+
+/// Running build server of a workspace
+#[derive(Debug)]
+pub struct BspClient {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: i64,
+    /// Params of `build/publishDiagnostics` that arrived while waiting for a response
+    pub diagnostics: Vec<Value>,
+}
+
+impl BspClient {
+    /// Start the server of the connection file and initialize the build session
+    pub fn start(details: &ConnectionDetails, workspace: &Path) -> Result<Self, BspError> {
+        let (program, args) = details.argv.split_first().ok_or(BspError::NoCommand)?;
+        let mut child = Command::new(program)
+            .args(args)
+            .current_dir(workspace)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(BspError::IO)?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            let _ = child.kill();
+            return Err(BspError::Closed);
+        };
+        let mut client = Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+            next_id: 0,
+            diagnostics: Vec::new(),
+        };
+        client.request(
+            "build/initialize",
+            &json!({
+                "displayName": "java_lsp",
+                "version": env!("CARGO_PKG_VERSION"),
+                "bspVersion": BSP_VERSION,
+                "rootUri": path_to_uri(workspace),
+                "capabilities": { "languageIds": ["java"] },
+            }),
+        )?;
+        client.notify("build/initialized", &Value::Null)?;
+        Ok(client)
+    }
+
+    /// Send the request and wait for its response, diagnostics that are published in between are
+    /// kept
+    pub fn request(&mut self, method: &str, params: &Value) -> Result<Value, BspError> {
+        self.next_id += 1;
+        let id = self.next_id;
+        write_message(
+            &mut self.stdin,
+            &json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }),
+        )?;
+        loop {
+            let mut message = read_message(&mut self.stdout)?;
+            if message.get("method").and_then(Value::as_str) == Some(PUBLISH_DIAGNOSTICS) {
+                self.diagnostics.push(message["params"].take());
+                continue;
+            }
+            if message.get("id").and_then(Value::as_i64) != Some(id)
+                || message.get("method").is_some()
+            {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                return Err(BspError::Response(error.to_string()));
+            }
+            return Ok(message["result"].take());
+        }
+    }
+
+    pub fn notify(&mut self, method: &str, params: &Value) -> Result<(), BspError> {
+        write_message(
+            &mut self.stdin,
+            &json!({ "jsonrpc": "2.0", "method": method, "params": params }),
+        )
+    }
+
+    /// Ask the server to exit, it is killed when it does not
+    pub fn shutdown(mut self) {
+        let _ = self.request("build/shutdown", &Value::Null);
+        let _ = self.notify("build/exit", &Value::Null);
+        if !matches!(self.child.try_wait(), Ok(Some(_))) {
+            let _ = self.child.kill();
+        }
+        let _ = self.child.wait();
+    }
+}
+
+/// Write the message with its `Content-Length` header
+pub fn write_message(out: &mut impl Write, message: &Value) -> Result<(), BspError> {
+    let body = serde_json::to_vec(message).map_err(BspError::Json)?;
+    write!(out, "Content-Length: {}\r\n\r\n", body.len()).map_err(BspError::IO)?;
+    out.write_all(&body).map_err(BspError::IO)?;
+    out.flush().map_err(BspError::IO)
+}
+
+/// Read the headers and the body of the next message
+pub fn read_message(input: &mut impl BufRead) -> Result<Value, BspError> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line).map_err(BspError::IO)? == 0 {
+            return Err(BspError::Closed);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let mut body = vec![0; length.ok_or(BspError::Header)?];
+    input.read_exact(&mut body).map_err(BspError::IO)?;
+    serde_json::from_slice(&body).map_err(BspError::Json)
+}
+
+#[must_use]
+pub fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let path = path.replace('%', "%25").replace(' ', "%20");
+    if path.starts_with('/') {
+        format!("file://{path}")
+    } else {
+        format!("file:///{path}")
+    }
+}
+
+/// Path of a `file:` uri, other schemes have none
+#[must_use]
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut it = path.bytes();
+    while let Some(b) = it.next() {
+        if b == b'%' {
+            let hex = [it.next()?, it.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(b);
+        }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // `file:///C:/dir`
+    let path = match path.as_bytes() {
+        [b'/', _, b':', ..] => path[1..].to_string(),
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Cursor, path::PathBuf};
+
+    use serde_json::json;
+
+    use super::{ConnectionDetails, find_connection, read_message, uri_to_path, write_message};
+
+    #[test]
+    fn messages() {
+        let mut out = Vec::new();
+        let message = json!({ "jsonrpc": "2.0", "id": 1, "result": { "targets": [] } });
+        write_message(&mut out, &message).unwrap();
+        write_message(
+            &mut out,
+            &json!({ "jsonrpc": "2.0", "method": "build/exit" }),
+        )
+        .unwrap();
+        let mut input = Cursor::new(out);
+        assert_eq!(read_message(&mut input).unwrap(), message);
+        assert_eq!(read_message(&mut input).unwrap()["method"], "build/exit");
+        assert!(read_message(&mut input).is_err());
+    }
+
+    #[test]
+    fn connection_files() {
+        let dir = std::env::temp_dir().join(format!("bsp-connection-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join(".bsp")).unwrap();
+        fs::write(
+            dir.join(".bsp/a-scala.json"),
+            r#"{"name":"sbt","argv":["sbt","-bsp"],"languages":["scala"]}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join(".bsp/bloop.json"),
+            r#"{"name":"Bloop","version":"2.0.0","bspVersion":"2.1.0","languages":["scala","java"],"argv":["bloop","bsp"]}"#,
+        )
+        .unwrap();
+        let connection = find_connection(&dir).unwrap();
+        assert_eq!(connection, dir.join(".bsp/bloop.json"));
+        let details = ConnectionDetails::load(&connection).unwrap();
+        assert_eq!(details.name, "Bloop");
+        assert_eq!(details.argv, vec!["bloop", "bsp"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn uris() {
+        assert_eq!(
+            uri_to_path("file:///ws/my%20app/src"),
+            Some(PathBuf::from("/ws/my app/src"))
+        );
+        assert_eq!(
+            uri_to_path("file:///C:/ws/src"),
+            Some(PathBuf::from("C:/ws/src"))
+        );
+        assert_eq!(uri_to_path("jar:file:///a.jar!/"), None);
+    }
+}
@@ -0,0 +1,440 @@
+//! Java targets of a workspace that a build server builds
+//!
+//! The server of the connection file is started once per workspace and kept running. The source
+//! roots and the classpath of all java targets are loaded when the first file of the workspace is
+//! opened. A saved file compiles the targets that own it, the diagnostics that the server
+//! publishes are the compile errors.
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use compile::CompileErrorMessage;
+use dto::{Class, SourceDestination};
+use my_string::{MyString, smol_str::ToSmolStr};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use crate::connection::{BspClient, BspError, ConnectionDetails, path_to_uri, uri_to_path};
+
+/// Files of a source item
+const SOURCE_FILE: u8 = 1;
+const ERROR: u8 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct BuildTargetIdentifier {
+    pub uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildTarget {
+    id: BuildTargetIdentifier,
+    #[serde(default)]
+    language_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkspaceBuildTargetsResult {
+    targets: Vec<BuildTarget>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SourcesResult {
+    items: Vec<SourcesItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SourcesItem {
+    sources: Vec<SourceItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SourceItem {
+    uri: String,
+    kind: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct JavacOptionsResult {
+    items: Vec<JavacOptionsItem>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JavacOptionsItem {
+    #[serde(default)]
+    classpath: Vec<String>,
+    #[serde(default)]
+    class_directory: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InverseSourcesResult {
+    targets: Vec<BuildTargetIdentifier>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublishDiagnosticsParams {
+    text_document: TextDocumentIdentifier,
+    diagnostics: Vec<BspDiagnostic>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextDocumentIdentifier {
+    uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BspDiagnostic {
+    range: BspRange,
+    severity: Option<u8>,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BspRange {
+    start: BspPosition,
+}
+
+#[derive(Debug, Deserialize)]
+struct BspPosition {
+    line: usize,
+    character: usize,
+}
+
+/// Sources and classpath of the java targets of a workspace
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BspWorkspace {
+    pub targets: Vec<BuildTargetIdentifier>,
+    /// Folders with sources
+    pub source_dirs: Vec<PathBuf>,
+    /// Sources that the server lists one by one
+    pub source_files: Vec<PathBuf>,
+    /// Jars and class folders of the dependencies, without the outputs of the targets
+    pub classpath: Vec<PathBuf>,
+}
+
+/// Running build servers by workspace
+#[derive(Debug, Default)]
+pub struct BspServers {
+    clients: Mutex<HashMap<PathBuf, BspClient>>,
+    /// Workspaces whose targets are in the class map
+    loaded: Mutex<HashSet<PathBuf>>,
+}
+
+impl BspServers {
+    /// Run `f` with the server of the workspace, it is started when it does not run yet. A server
+    /// that failed is stopped and started again by the next call.
+    fn with_client<T>(
+        &self,
+        connection: &Path,
+        workspace: &Path,
+        f: impl FnOnce(&mut BspClient) -> Result<T, BspError>,
+    ) -> Result<T, BspError> {
+        let Ok(mut clients) = self.clients.lock() else {
+            return Err(BspError::Closed);
+        };
+        let client = match clients.entry(workspace.to_path_buf()) {
+            std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
+            std::collections::hash_map::Entry::Vacant(e) => {
+                let details = ConnectionDetails::load(connection)?;
+                e.insert(BspClient::start(&details, workspace)?)
+            }
+        };
+        let out = f(client);
+        if matches!(out, Err(BspError::Closed | BspError::IO(_)))
+            && let Some(client) = clients.remove(workspace)
+        {
+            client.shutdown();
+        }
+        out
+    }
+
+    /// Source folders and classpath of the java targets of the workspace
+    pub fn workspace(&self, connection: &Path, workspace: &Path) -> Result<BspWorkspace, BspError> {
+        self.with_client(connection, workspace, load_workspace)
+    }
+
+    /// Load the targets of the workspace, a workspace that was loaded before is skipped
+    pub fn index_workspace<S: BuildHasher>(
+        &self,
+        class_map: &Arc<RwLock<HashMap<MyString, Class, S>>>,
+        connection: &Path,
+        workspace: &Path,
+    ) -> Result<(), BspError> {
+        if !self
+            .loaded
+            .lock()
+            .is_ok_and(|mut loaded| loaded.insert(workspace.to_path_buf()))
+        {
+            return Ok(());
+        }
+        match self.workspace(connection, workspace) {
+            Ok(loaded) => {
+                index_workspace(class_map, &loaded);
+                Ok(())
+            }
+            Err(e) => {
+                if let Ok(mut loaded) = self.loaded.lock() {
+                    loaded.remove(workspace);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Compile the targets of the file, the errors of all files that the server reported
+    pub fn compile(
+        &self,
+        connection: &Path,
+        workspace: &Path,
+        file: &Path,
+    ) -> Result<Vec<CompileErrorMessage>, BspError> {
+        self.with_client(connection, workspace, |client| compile_file(client, file))
+    }
+
+    /// Stop the servers, a changed build is loaded by new ones
+    pub fn clear(&self) {
+        if let Ok(mut loaded) = self.loaded.lock() {
+            loaded.clear();
+        }
+        let clients = self
+            .clients
+            .lock()
+            .map(|mut clients| std::mem::take(&mut *clients))
+            .unwrap_or_default();
+        for client in clients.into_values() {
+            client.shutdown();
+        }
+    }
+}
+
+fn request<T: DeserializeOwned>(
+    client: &mut BspClient,
+    method: &str,
+    params: &Value,
+) -> Result<T, BspError> {
+    serde_json::from_value(client.request(method, params)?).map_err(BspError::Json)
+}
+
+fn load_workspace(client: &mut BspClient) -> Result<BspWorkspace, BspError> {
+    let targets: WorkspaceBuildTargetsResult =
+        request(client, "workspace/buildTargets", &Value::Null)?;
+    let targets: Vec<BuildTargetIdentifier> = targets
+        .targets
+        .into_iter()
+        .filter(|t| t.language_ids.iter().any(|l| l == "java"))
+        .map(|t| t.id)
+        .collect();
+    let sources: SourcesResult = request(
+        client,
+        "buildTarget/sources",
+        &json!({ "targets": targets }),
+    )?;
+    let options: JavacOptionsResult = request(
+        client,
+        "buildTarget/javacOptions",
+        &json!({ "targets": targets }),
+    )?;
+    Ok(workspace_of(targets, sources, &options))
+}
+
+fn workspace_of(
+    targets: Vec<BuildTargetIdentifier>,
+    sources: SourcesResult,
+    options: &JavacOptionsResult,
+) -> BspWorkspace {
+    let mut source_dirs = Vec::new();
+    let mut source_files = Vec::new();
+    for source in sources.items.into_iter().flat_map(|i| i.sources) {
+        let Some(path) = uri_to_path(&source.uri) else {
+            continue;
+        };
+        let list = if source.kind == SOURCE_FILE {
+            &mut source_files
+        } else {
+            &mut source_dirs
+        };
+        if !list.contains(&path) {
+            list.push(path);
+        }
+    }
+    // The sources of the targets are loaded instead of their classes
+    let outputs: HashSet<PathBuf> = options
+        .items
+        .iter()
+        .filter_map(|i| i.class_directory.as_deref().and_then(uri_to_path))
+        .collect();
+    let mut classpath = Vec::new();
+    for entry in options.items.iter().flat_map(|i| &i.classpath) {
+        if let Some(path) = uri_to_path(entry)
+            && !outputs.contains(&path)
+            && !classpath.contains(&path)
+        {
+            classpath.push(path);
+        }
+    }
+    BspWorkspace {
+        targets,
+        source_dirs,
+        source_files,
+        classpath,
+    }
+}
+
+fn compile_file(client: &mut BspClient, file: &Path) -> Result<Vec<CompileErrorMessage>, BspError> {
+    let inverse: InverseSourcesResult = request(
+        client,
+        "buildTarget/inverseSources",
+        &json!({ "textDocument": { "uri": path_to_uri(file) } }),
+    )?;
+    if inverse.targets.is_empty() {
+        return Ok(Vec::new());
+    }
+    client.diagnostics.clear();
+    client.request(
+        "buildTarget/compile",
+        &json!({ "targets": inverse.targets }),
+    )?;
+    Ok(std::mem::take(&mut client.diagnostics)
+        .into_iter()
+        .filter_map(|params| serde_json::from_value(params).ok())
+        .flat_map(compile_errors)
+        .collect())
+}
+
+/// Errors of published diagnostics, rows start at 1 like the ones of javac
+fn compile_errors(params: PublishDiagnosticsParams) -> Vec<CompileErrorMessage> {
+    let Some(path) = uri_to_path(&params.text_document.uri) else {
+        return Vec::new();
+    };
+    let path = path.to_string_lossy().to_string();
+    params
+        .diagnostics
+        .into_iter()
+        .filter(|d| d.severity.is_none_or(|s| s == ERROR))
+        .map(|d| CompileErrorMessage {
+            path: path.clone(),
+            message: d.message,
+            row: d.range.start.line + 1,
+            col: d.range.start.character,
+        })
+        .collect()
+}
+
+/// Parse the sources and load the jars of the workspace into the class map
+pub fn index_workspace<S: BuildHasher>(
+    class_map: &Arc<RwLock<HashMap<MyString, Class, S>>>,
+    workspace: &BspWorkspace,
+) {
+    let mut classes = loader::load_java_folders(workspace.source_dirs.clone(), |_, _| {});
+    classes.extend(workspace.source_files.iter().filter_map(|file| {
+        let source = SourceDestination::Here(file.to_str()?.to_smolstr());
+        loader::load_java_fs(file, source).ok()
+    }));
+    let jars: Vec<&PathBuf> = workspace
+        .classpath
+        .iter()
+        .filter(|p| p.extension().is_some_and(|e| e == "jar"))
+        .collect();
+    classes.extend(
+        loader::parallel::parallel_map(
+            &jars,
+            |jar| match loader::load_classes_jar(jar, SourceDestination::None) {
+                Ok(classes) => Some(classes.classes),
+                Err(e) => {
+                    eprintln!("Failed to load jar: {}, {e:?}", jar.display());
+                    None
+                }
+            },
+            |_, _| {},
+        )
+        .into_iter()
+        .flatten(),
+    );
+    if let Ok(mut cm) = class_map.write() {
+        for class in classes {
+            cm.insert(class.class_path.clone(), class);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serde_json::json;
+
+    use super::{BuildTargetIdentifier, compile_errors, workspace_of};
+
+    #[test]
+    fn workspace() {
+        let target = BuildTargetIdentifier {
+            uri: "file:///ws/app?id=app".to_string(),
+        };
+        let sources = serde_json::from_value(json!({ "items": [{
+            "target": { "uri": "file:///ws/app?id=app" },
+            "sources": [
+                { "uri": "file:///ws/app/src/main/java/", "kind": 2, "generated": false },
+                { "uri": "file:///ws/app/Single.java", "kind": 1, "generated": false },
+            ],
+        }]}))
+        .unwrap();
+        let options = serde_json::from_value(json!({ "items": [{
+            "target": { "uri": "file:///ws/app?id=app" },
+            "options": ["-parameters"],
+            "classpath": ["file:///m2/guava.jar", "file:///ws/lib/out/"],
+            "classDirectory": "file:///ws/app/out/",
+        }, {
+            "target": { "uri": "file:///ws/lib?id=lib" },
+            "options": [],
+            "classpath": ["file:///m2/guava.jar"],
+            "classDirectory": "file:///ws/lib/out/",
+        }]}))
+        .unwrap();
+        let workspace = workspace_of(vec![target], sources, &options);
+        assert_eq!(
+            workspace.source_dirs,
+            vec![PathBuf::from("/ws/app/src/main/java/")]
+        );
+        assert_eq!(
+            workspace.source_files,
+            vec![PathBuf::from("/ws/app/Single.java")]
+        );
+        assert_eq!(workspace.classpath, vec![PathBuf::from("/m2/guava.jar")]);
+    }
+
+    #[test]
+    fn published_errors() {
+        let params = serde_json::from_value(json!({
+            "textDocument": { "uri": "file:///ws/app/A.java" },
+            "buildTarget": { "uri": "file:///ws/app?id=app" },
+            "reset": true,
+            "diagnostics": [{
+                "range": {
+                    "start": { "line": 3, "character": 8 },
+                    "end": { "line": 3, "character": 9 },
+                },
+                "severity": 1,
+                "message": "cannot find symbol",
+            }, {
+                "range": {
+                    "start": { "line": 1, "character": 0 },
+                    "end": { "line": 1, "character": 1 },
+                },
+                "severity": 2,
+                "message": "deprecated",
+            }],
+        }))
+        .unwrap();
+        let errors = compile_errors(params);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "/ws/app/A.java");
+        assert_eq!(errors[0].row, 4);
+        assert_eq!(errors[0].col, 8);
+    }
+}
@@ -3,7 +3,7 @@
 #![deny(clippy::nursery)]
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::too_many_lines)]
-use std::cmp::{self, max, min};
+use std::cmp;
 
 use ast::range::{AstInRange, GetRange, add_ranges};
 use ast::types::{
@@ -62,6 +62,11 @@ pub enum CallItem {
     ArrayAccess {
         range: AstRange,
     },
+    /// Explicit type arguments of the following method call `Collections.<String>emptyList()`
+    TypeArguments {
+        range: AstRange,
+        args: Vec<JType>,
+    },
 }
 
 impl CallItem {
@@ -76,6 +81,7 @@ impl CallItem {
             | Self::ClassGeneric { range, .. }
             | Self::ClassOrVariable { range, .. }
             | Self::ArrayAccess { range }
+            | Self::TypeArguments { range, .. }
             | Self::ArgumentList {
                 prev: _,
                 active_param: _,
@@ -574,6 +580,7 @@ pub fn validate(call_chain: &[CallItem], point: &AstPoint) -> (usize, Vec<CallIt
             | CallItem::This { range }
             | CallItem::ClassOrVariable { range, .. }
             | CallItem::ArrayAccess { range }
+            | CallItem::TypeArguments { range, .. }
             | CallItem::ClassGeneric { range, .. }
             | CallItem::Class { range, .. } => range.is_in_range(point),
             CallItem::ArgumentList {
@@ -623,7 +630,7 @@ fn cc_block(block: &AstBlock, point: &AstPoint, out: &mut Vec<CallItem>) {
     }
 }
 
-fn dist_block_entry(point: &AstPoint, entry: &AstBlockEntry) -> usize {
+fn dist_block_entry(point: &AstPoint, entry: &AstBlockEntry) -> Distance {
     dist(*point, entry.get_range())
 }
 
@@ -980,8 +987,15 @@ pub fn cc_expr(
         AstExpressionKind::NewClass(ast_new_class) => cc_new_class(ast_new_class, point, out),
         AstExpressionKind::Array(ast_values) => cc_array(ast_values, point, out),
         AstExpressionKind::Generics(ast_generics) => {
-            for j in &ast_generics.jtypes {
-                cc_jtype(j, out);
+            if ast_generics.range.is_in_range(point) {
+                for j in &ast_generics.jtypes {
+                    cc_jtype(j, out);
+                }
+            } else {
+                out.push(CallItem::TypeArguments {
+                    range: ast_generics.range,
+                    args: ast_generics.jtypes.iter().map(Into::into).collect(),
+                });
             }
         }
         AstExpressionKind::JType(c) => cc_jtype_expression(c, point, out),
@@ -1334,15 +1348,17 @@ fn cc_arguments_base(
     }
 }
 
-fn cc_arguments_for_method_call(args: AstValues) -> Vec<Vec<CallItem>> {
+/// Call chain of each argument of a call
+#[must_use]
+pub fn argument_call_chains(args: &[AstExpression]) -> Vec<Vec<CallItem>> {
     let mut out = Vec::new();
 
-    for arg in args.values {
+    for arg in args {
         let mut v = Vec::new();
 
         let mut point = arg.get_range().end;
         point.col += 1;
-        cc_expr(&arg, &point, false, &mut v);
+        cc_expr(arg, &point, false, &mut v);
 
         out.push(v);
     }
@@ -1359,20 +1375,38 @@ fn get_active_param(expressions: &[AstExpression], point: &AstPoint) -> usize {
         .unwrap_or_default()
 }
 
-fn dist(point: AstPoint, range: AstRange) -> usize {
-    if point < range.start {
+/// Distance of a point to a range, lines are compared before columns so that a long line never
+/// outweighs a range that is lines away. Of the ranges that contain the point the smaller one is
+/// nearer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Distance {
+    lines: usize,
+    cols: usize,
+    size: (usize, usize),
+}
+
+/// A range that contains the point is nearest, the innermost one first
+fn dist(point: AstPoint, range: AstRange) -> Distance {
+    let (lines, cols) = if point < range.start {
         line_col_diff(&point, &range.start)
     } else if point > range.end {
         line_col_diff(&point, &range.end)
     } else {
-        0
+        return Distance {
+            lines: 0,
+            cols: 0,
+            size: line_col_diff(&range.start, &range.end),
+        };
+    };
+    Distance {
+        lines,
+        cols,
+        size: (0, 0),
     }
 }
 
-fn line_col_diff(a: &AstPoint, b: &AstPoint) -> usize {
-    let line_diff = max(a.line, b.line) - min(a.line, b.line);
-    let col_diff = max(a.col, b.col) - min(a.col, b.col);
-    line_diff * 1000 + col_diff
+const fn line_col_diff(a: &AstPoint, b: &AstPoint) -> (usize, usize) {
+    (a.line.abs_diff(b.line), a.col.abs_diff(b.col))
 }
 
 fn cc_expr_ident(
@@ -1389,7 +1423,7 @@ fn cc_expr_ident(
                 out.push(CallItem::MethodCall {
                     name: ast_identifier.into(),
                     range: ast_identifier.range,
-                    args: cc_arguments_for_method_call(args),
+                    args: argument_call_chains(&args.values),
                 });
             } else if has_parent && !is_empty {
                 out.push(CallItem::FieldAccess {
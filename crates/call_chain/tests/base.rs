@@ -114,8 +114,8 @@ fn call_chain_method_a() {
                         Class {
                             name: "String",
                             range: AstRange {
-                                start: AstPoint { 8:35 },
-                                end: AstPoint { 8:30 },
+                                start: AstPoint { 8:30 },
+                                end: AstPoint { 8:36 },
                             },
                         },
                     ],
@@ -148,8 +148,8 @@ public class Test {
             Class {
                 name: "String",
                 range: AstRange {
-                    start: AstPoint { 5:16 },
-                    end: AstPoint { 5:14 },
+                    start: AstPoint { 5:15 },
+                    end: AstPoint { 5:17 },
                 },
             },
         ]
@@ -230,8 +230,8 @@ public class GreetingResource {
                         Class {
                             name: "String",
                             range: AstRange {
-                                start: AstPoint { 5:18 },
-                                end: AstPoint { 5:17 },
+                                start: AstPoint { 5:17 },
+                                end: AstPoint { 5:19 },
                             },
                         },
                     ],
@@ -278,8 +278,8 @@ public class GreetingResource {
                         Class {
                             name: "String",
                             range: AstRange {
-                                start: AstPoint { 4:18 },
-                                end: AstPoint { 4:17 },
+                                start: AstPoint { 4:17 },
+                                end: AstPoint { 4:19 },
                             },
                         },
                     ],
@@ -1823,7 +1823,7 @@ public class Test {
     let tokens = ast::lexer::lex(content.as_bytes()).unwrap();
     let ast = ast::parse_file(&tokens).unwrap();
 
-    let out = get_call_chain(&ast, &AstPoint::new(4, 15));
+    let out = get_call_chain(&ast, &AstPoint::new(4, 14));
     assert_eq!(
         vec![
             CallItem::This {
@@ -2092,3 +2092,369 @@ return a[0]. ;
         out,
     );
 }
+
+#[test]
+fn call_chain_type_arguments() {
+    let content = "
+package ch.emilycares;
+public class Test {
+    public void hello() {
+        var a = Collections.<String>emptyList();
+        return;
+    }
+}
+        ";
+    let tokens = ast::lexer::lex(content.as_bytes()).unwrap();
+    let ast = ast::parse_file(&tokens);
+    ast.print_err(content, &tokens);
+    let ast = ast.unwrap();
+
+    let out = get_call_chain(&ast, &AstPoint::new(4, 49));
+    let expected = expect![[r#"
+        [
+            ClassOrVariable {
+                name: "Collections",
+                range: AstRange {
+                    start: AstPoint { 4:16 },
+                    end: AstPoint { 4:27 },
+                },
+            },
+            TypeArguments {
+                range: AstRange {
+                    start: AstPoint { 4:28 },
+                    end: AstPoint { 4:36 },
+                },
+                args: [
+                    Class(
+                        "String",
+                    ),
+                ],
+            },
+            MethodCall {
+                name: "emptyList",
+                range: AstRange {
+                    start: AstPoint { 4:36 },
+                    end: AstPoint { 4:45 },
+                },
+                args: [],
+            },
+        ]
+    "#]];
+    expected.assert_debug_eq(&out);
+}
+
+#[test]
+fn call_chain_long_lines() {
+    // The entry on the line above is nearer than the one two lines below, even though the
+    // columns differ by more than a thousand
+    let content = format!(
+        "
+package ch.emilycares;
+public class Test {{
+    public void hello(String a, String b) {{
+        a.length();
+{}
+
+{}b.length();
+    }}
+}}
+        ",
+        " ".repeat(1300),
+        " ".repeat(1298)
+    );
+    let tokens = ast::lexer::lex(content.as_bytes()).unwrap();
+    let ast = ast::parse_file(&tokens);
+    ast.print_err(&content, &tokens);
+    let ast = ast.unwrap();
+
+    let out = get_call_chain(&ast, &AstPoint::new(5, 1300));
+    let expected = expect![[r#"
+        [
+            ClassOrVariable {
+                name: "a",
+                range: AstRange {
+                    start: AstPoint { 4:8 },
+                    end: AstPoint { 4:9 },
+                },
+            },
+            MethodCall {
+                name: "length",
+                range: AstRange {
+                    start: AstPoint { 4:10 },
+                    end: AstPoint { 4:16 },
+                },
+                args: [],
+            },
+        ]
+    "#]];
+    expected.assert_debug_eq(&out);
+}
+
+#[test]
+fn call_chain_nested_calls() {
+    let content = "
+package ch.emilycares;
+public class Test {
+    public void hello(String a) {
+        a.concat(a.trim().concat(a.strip()));
+    }
+}
+        ";
+    let tokens = ast::lexer::lex(content.as_bytes()).unwrap();
+    let ast = ast::parse_file(&tokens);
+    ast.print_err(content, &tokens);
+    let ast = ast.unwrap();
+
+    let out = get_call_chain(&ast, &AstPoint::new(4, 38));
+    let expected = expect![[r#"
+        [
+            ArgumentList {
+                prev: [
+                    ClassOrVariable {
+                        name: "a",
+                        range: AstRange {
+                            start: AstPoint { 4:8 },
+                            end: AstPoint { 4:9 },
+                        },
+                    },
+                    MethodCall {
+                        name: "concat",
+                        range: AstRange {
+                            start: AstPoint { 4:10 },
+                            end: AstPoint { 4:16 },
+                        },
+                        args: [
+                            [
+                                ClassOrVariable {
+                                    name: "a",
+                                    range: AstRange {
+                                        start: AstPoint { 4:17 },
+                                        end: AstPoint { 4:18 },
+                                    },
+                                },
+                                MethodCall {
+                                    name: "trim",
+                                    range: AstRange {
+                                        start: AstPoint { 4:19 },
+                                        end: AstPoint { 4:23 },
+                                    },
+                                    args: [],
+                                },
+                                MethodCall {
+                                    name: "concat",
+                                    range: AstRange {
+                                        start: AstPoint { 4:26 },
+                                        end: AstPoint { 4:32 },
+                                    },
+                                    args: [
+                                        [
+                                            ClassOrVariable {
+                                                name: "a",
+                                                range: AstRange {
+                                                    start: AstPoint { 4:33 },
+                                                    end: AstPoint { 4:34 },
+                                                },
+                                            },
+                                            MethodCall {
+                                                name: "strip",
+                                                range: AstRange {
+                                                    start: AstPoint { 4:35 },
+                                                    end: AstPoint { 4:40 },
+                                                },
+                                                args: [],
+                                            },
+                                        ],
+                                    ],
+                                },
+                            ],
+                        ],
+                    },
+                ],
+                active_param: Some(
+                    0,
+                ),
+                filled_params: [
+                    [
+                        ArgumentList {
+                            prev: [
+                                ClassOrVariable {
+                                    name: "a",
+                                    range: AstRange {
+                                        start: AstPoint { 4:17 },
+                                        end: AstPoint { 4:18 },
+                                    },
+                                },
+                                MethodCall {
+                                    name: "trim",
+                                    range: AstRange {
+                                        start: AstPoint { 4:19 },
+                                        end: AstPoint { 4:23 },
+                                    },
+                                    args: [],
+                                },
+                                MethodCall {
+                                    name: "concat",
+                                    range: AstRange {
+                                        start: AstPoint { 4:26 },
+                                        end: AstPoint { 4:32 },
+                                    },
+                                    args: [
+                                        [
+                                            ClassOrVariable {
+                                                name: "a",
+                                                range: AstRange {
+                                                    start: AstPoint { 4:33 },
+                                                    end: AstPoint { 4:34 },
+                                                },
+                                            },
+                                            MethodCall {
+                                                name: "strip",
+                                                range: AstRange {
+                                                    start: AstPoint { 4:35 },
+                                                    end: AstPoint { 4:40 },
+                                                },
+                                                args: [],
+                                            },
+                                        ],
+                                    ],
+                                },
+                            ],
+                            active_param: Some(
+                                0,
+                            ),
+                            filled_params: [
+                                [
+                                    ClassOrVariable {
+                                        name: "a",
+                                        range: AstRange {
+                                            start: AstPoint { 4:33 },
+                                            end: AstPoint { 4:34 },
+                                        },
+                                    },
+                                    MethodCall {
+                                        name: "strip",
+                                        range: AstRange {
+                                            start: AstPoint { 4:35 },
+                                            end: AstPoint { 4:40 },
+                                        },
+                                        args: [],
+                                    },
+                                ],
+                            ],
+                            range: AstRange {
+                                start: AstPoint { 4:32 },
+                                end: AstPoint { 4:43 },
+                            },
+                        },
+                        ClassOrVariable {
+                            name: "a",
+                            range: AstRange {
+                                start: AstPoint { 4:33 },
+                                end: AstPoint { 4:34 },
+                            },
+                        },
+                        MethodCall {
+                            name: "strip",
+                            range: AstRange {
+                                start: AstPoint { 4:35 },
+                                end: AstPoint { 4:40 },
+                            },
+                            args: [],
+                        },
+                    ],
+                ],
+                range: AstRange {
+                    start: AstPoint { 4:16 },
+                    end: AstPoint { 4:44 },
+                },
+            },
+            ArgumentList {
+                prev: [
+                    ClassOrVariable {
+                        name: "a",
+                        range: AstRange {
+                            start: AstPoint { 4:17 },
+                            end: AstPoint { 4:18 },
+                        },
+                    },
+                    MethodCall {
+                        name: "trim",
+                        range: AstRange {
+                            start: AstPoint { 4:19 },
+                            end: AstPoint { 4:23 },
+                        },
+                        args: [],
+                    },
+                    MethodCall {
+                        name: "concat",
+                        range: AstRange {
+                            start: AstPoint { 4:26 },
+                            end: AstPoint { 4:32 },
+                        },
+                        args: [
+                            [
+                                ClassOrVariable {
+                                    name: "a",
+                                    range: AstRange {
+                                        start: AstPoint { 4:33 },
+                                        end: AstPoint { 4:34 },
+                                    },
+                                },
+                                MethodCall {
+                                    name: "strip",
+                                    range: AstRange {
+                                        start: AstPoint { 4:35 },
+                                        end: AstPoint { 4:40 },
+                                    },
+                                    args: [],
+                                },
+                            ],
+                        ],
+                    },
+                ],
+                active_param: Some(
+                    0,
+                ),
+                filled_params: [
+                    [
+                        ClassOrVariable {
+                            name: "a",
+                            range: AstRange {
+                                start: AstPoint { 4:33 },
+                                end: AstPoint { 4:34 },
+                            },
+                        },
+                        MethodCall {
+                            name: "strip",
+                            range: AstRange {
+                                start: AstPoint { 4:35 },
+                                end: AstPoint { 4:40 },
+                            },
+                            args: [],
+                        },
+                    ],
+                ],
+                range: AstRange {
+                    start: AstPoint { 4:32 },
+                    end: AstPoint { 4:43 },
+                },
+            },
+            ClassOrVariable {
+                name: "a",
+                range: AstRange {
+                    start: AstPoint { 4:33 },
+                    end: AstPoint { 4:34 },
+                },
+            },
+            MethodCall {
+                name: "strip",
+                range: AstRange {
+                    start: AstPoint { 4:35 },
+                    end: AstPoint { 4:40 },
+                },
+                args: [],
+            },
+        ]
+    "#]];
+    expected.assert_debug_eq(&out);
+}
@@ -41,6 +41,7 @@ pub fn load_class(
     let mut deprecated = false;
     let mut class_signature = None;
     let mut source = source;
+    let mut access = c.access_flags.clone();
 
     for a in &c.attributes {
         if a.name == 0 {
@@ -63,6 +64,25 @@ pub fn load_class(
                 return Err(ClassParserError::Ignoring);
             }
             deprecated = true;
+        } else if attribute_name == "InnerClasses" {
+            let info = a.lookup(data)?;
+            if let Some(inner) = parse_inner_classes_attribute(info, 0)?
+                .0
+                .into_iter()
+                .find(|i| i.class_info == c.this_class)
+            {
+                // Anonymous and local classes can not be referenced by name
+                let named = inner.outer_class_info != 0 && inner.inner_name != 0;
+                if filter
+                    && (!named
+                        || inner
+                            .access_flags
+                            .intersects(Access::Private | Access::Protected))
+                {
+                    return Err(ClassParserError::Ignoring);
+                }
+                access = inner.access_flags;
+            }
         } else if attribute_name == "SourceFile" {
             let info = a.lookup(data)?;
             let (name, _) = get_u16(info, 0)?;
@@ -97,7 +117,7 @@ pub fn load_class(
         methods.push(method);
     }
     for f in &c.fields {
-        let field = parse_field(&c, f, filter);
+        let field = parse_field(&c, data, f, filter);
         if matches!(field, Err(ClassParserError::Ignoring)) {
             continue;
         }
@@ -144,7 +164,7 @@ pub fn load_class(
         super_interfaces,
         super_class,
         imports,
-        access: parse_class_access(c.access_flags, deprecated),
+        access: parse_class_access(access, deprecated),
         signature: class_signature,
         name,
         methods,
@@ -163,19 +183,54 @@ fn lookup_class_name(c: &Base, index: usize) -> Result<MyString, ClassParserErro
     }
 }
 
-fn parse_field(c: &Base, field: &Field, filter: bool) -> Result<dto::Field, ClassParserError> {
+fn parse_field(
+    c: &Base,
+    data: &[u8],
+    field: &Field,
+    filter: bool,
+) -> Result<dto::Field, ClassParserError> {
     if filter
         && field
             .access_flags
-            .intersects(Access::Private | Access::Protected)
+            .intersects(Access::Private | Access::Protected | Access::Synthetic)
     {
         return Err(ClassParserError::Ignoring);
     }
+    let mut access = field.access_flags.clone();
+    let mut descriptor = lookup_string(c, field.descriptor)?;
+    let mut annotations = Vec::new();
+    for attribute in &field.attributes {
+        let name = lookup_string(c, attribute.name)?;
+        if name == "Signature" {
+            let info = attribute.lookup(data)?;
+            let (sig, _) = get_u16(info, 0)?;
+            descriptor = lookup_string(c, sig)?;
+        } else if name == "RuntimeVisibleAnnotations" || name == "RuntimeInvisibleAnnotations" {
+            parse_annotations_attribute(c, attribute.lookup(data)?, 0, &mut annotations)?;
+        } else if name == "RuntimeVisibleTypeAnnotations"
+            || name == "RuntimeInvisibleTypeAnnotations"
+        {
+            let (types, _) = parse_type_annotations_attribute(c, attribute.lookup(data)?, 0)?;
+            for (target, annotation) in types {
+                if matches!(target, TypeAnnotationTarget::Field)
+                    && !annotations.contains(&annotation)
+                {
+                    annotations.push(annotation);
+                }
+            }
+        } else if name == "Deprecated" {
+            if filter {
+                return Err(ClassParserError::Ignoring);
+            }
+            access.insert(Access::Deprecated);
+        }
+    }
     Ok(dto::Field {
-        access: field.access_flags.clone(),
+        access,
         name: lookup_string(c, field.name)?.to_smolstr(),
-        jtype: parse_field_type(lookup_string(c, field.descriptor)?.as_bytes(), 0)?.0,
+        jtype: parse_field_type(descriptor.as_bytes(), 0)?.0,
         source: None,
+        annotations,
     })
 }
 
@@ -188,7 +243,7 @@ fn parse_method(
     if filter
         && method
             .access_flags
-            .intersects(Access::Private | Access::Protected)
+            .intersects(Access::Private | Access::Protected | Access::Synthetic)
     {
         return Err(ClassParserError::Ignoring);
     }
@@ -202,17 +257,42 @@ fn parse_method(
 
     let mut parameters = Vec::new();
     let mut parameter_names = Vec::new();
+    let mut type_parameters = Vec::new();
     let mut throws = Vec::new();
     let mut deprecated = false;
     let mut signature_index = None;
     let mut method_parameter_index = None;
     let mut exception_index = None;
     let mut ret = JType::Void;
+    let mut annotations = Vec::new();
+    let mut parameter_annotations: Vec<Vec<MyString>> = Vec::new();
+    let mut type_annotations = Vec::new();
 
     for (index, attribute) in method.attributes.iter().enumerate() {
         let name = lookup_string(c, attribute.name)?;
         if name == "Signature" {
             signature_index = Some(index);
+        } else if name == "RuntimeVisibleAnnotations" || name == "RuntimeInvisibleAnnotations" {
+            parse_annotations_attribute(c, attribute.lookup(data)?, 0, &mut annotations)?;
+        } else if name == "RuntimeVisibleParameterAnnotations"
+            || name == "RuntimeInvisibleParameterAnnotations"
+        {
+            let (found, _) = parse_parameter_annotations_attribute(c, attribute.lookup(data)?, 0)?;
+            if parameter_annotations.len() < found.len() {
+                parameter_annotations.resize(found.len(), Vec::new());
+            }
+            for (all, found) in parameter_annotations.iter_mut().zip(found) {
+                for annotation in found {
+                    if !all.contains(&annotation) {
+                        all.push(annotation);
+                    }
+                }
+            }
+        } else if name == "RuntimeVisibleTypeAnnotations"
+            || name == "RuntimeInvisibleTypeAnnotations"
+        {
+            let (found, _) = parse_type_annotations_attribute(c, attribute.lookup(data)?, 0)?;
+            type_annotations.extend(found);
         } else if name == "MethodParameters" {
             method_parameter_index = Some(index);
         } else if name == "Exceptions" {
@@ -238,6 +318,7 @@ fn parse_method(
             parameters.push(Parameter {
                 name: None,
                 jtype: p,
+                annotations: vec![],
             });
         }
     }
@@ -264,13 +345,21 @@ fn parse_method(
             for p in info {
                 let jtype = params.next().ok_or(ClassParserError::NotEnogthParams)?;
                 if p.name_index == 0 {
-                    parameters.push(Parameter { name: None, jtype });
+                    parameters.push(Parameter {
+                        name: None,
+                        jtype,
+                        annotations: vec![],
+                    });
                 } else {
                     let name = lookup_string(c, p.name_index)
                         .ok()
                         .filter(|i| !i.is_empty())
                         .map(SmolStr::new);
-                    parameters.push(Parameter { name, jtype });
+                    parameters.push(Parameter {
+                        name,
+                        jtype,
+                        annotations: vec![],
+                    });
                 }
             }
         }
@@ -290,12 +379,17 @@ fn parse_method(
         parameters.extend(sig.params.into_iter().map(|jtype| Parameter {
             name: name_iter.next().flatten().map(SmolStr::new),
             jtype,
+            annotations: vec![],
         }));
 
         ret = sig.ret;
+        throws = sig.throws;
+        type_parameters = sig.args;
     }
 
-    if let Some(index) = exception_index {
+    if let Some(index) = exception_index
+        && throws.is_empty()
+    {
         let attribute = method
             .attributes
             .get(index)
@@ -309,6 +403,42 @@ fn parse_method(
         }
     }
 
+    if parameters.iter().all(|p| p.name.is_none())
+        && let Some(code_attribute) = &code_attribute
+    {
+        let is_static = method.access_flags.contains(Access::Static);
+        let names =
+            local_variable_parameter_names(c, data, code_attribute, &parameters, is_static)?;
+        for (p, name) in parameters.iter_mut().zip(names) {
+            p.name = name;
+        }
+    }
+
+    // Parameter annotations can leave out synthetic parameters at the start, like the outer
+    // instance of inner class constructors
+    let offset = parameters.len().saturating_sub(parameter_annotations.len());
+    for (p, found) in parameters
+        .iter_mut()
+        .skip(offset)
+        .zip(parameter_annotations)
+    {
+        p.annotations = found;
+    }
+    for (target, annotation) in type_annotations {
+        let target = match target {
+            TypeAnnotationTarget::Return => Some(&mut annotations),
+            TypeAnnotationTarget::Parameter(index) => parameters
+                .get_mut(index as usize)
+                .map(|p| &mut p.annotations),
+            TypeAnnotationTarget::Field | TypeAnnotationTarget::Other => None,
+        };
+        if let Some(target) = target
+            && !target.contains(&annotation)
+        {
+            target.push(annotation);
+        }
+    }
+
     let name = if lname == "<init>" {
         None
     } else {
@@ -318,10 +448,12 @@ fn parse_method(
         dto::Method {
             access: parse_method_access(method, deprecated),
             name,
+            type_parameters,
             parameters,
             ret,
             throws,
             source: None,
+            annotations,
         },
         code_attribute,
     ))
@@ -342,6 +474,163 @@ fn parse_exceptions_attribute(
     Ok((out, pos))
 }
 
+/// Descriptor of an annotation as class path, internal annotations of the JDK are skipped
+fn push_annotation(
+    c: &Base,
+    type_index: u16,
+    out: &mut Vec<MyString>,
+) -> Result<(), ClassParserError> {
+    let descriptor = lookup_string(c, type_index)?;
+    let name = descriptor
+        .trim_start_matches('L')
+        .trim_end_matches(';')
+        .replace_smolstr("/", ".");
+    if !name.starts_with("jdk.internal.") && !out.contains(&name) {
+        out.push(name);
+    }
+    Ok(())
+}
+
+/// `RuntimeVisibleAnnotations` and `RuntimeInvisibleAnnotations`
+fn parse_annotations_attribute(
+    c: &Base,
+    data: &[u8],
+    pos: usize,
+    out: &mut Vec<MyString>,
+) -> Result<usize, ClassParserError> {
+    let (count, mut pos) = get_u16(data, pos)?;
+    for _ in 0..count {
+        let (type_index, npos) = get_u16(data, pos)?;
+        pos = skip_element_value_pairs(data, npos)?;
+        push_annotation(c, type_index, out)?;
+    }
+    Ok(pos)
+}
+
+/// `RuntimeVisibleParameterAnnotations` and `RuntimeInvisibleParameterAnnotations`
+fn parse_parameter_annotations_attribute(
+    c: &Base,
+    data: &[u8],
+    pos: usize,
+) -> Result<(Vec<Vec<MyString>>, usize), ClassParserError> {
+    let (count, mut pos) = get_u8(data, pos)?;
+    let mut out = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let mut annotations = Vec::new();
+        pos = parse_annotations_attribute(c, data, pos, &mut annotations)?;
+        out.push(annotations);
+    }
+    Ok((out, pos))
+}
+
+#[derive(Clone, Copy)]
+enum TypeAnnotationTarget {
+    Field,
+    Return,
+    Parameter(u8),
+    Other,
+}
+
+/// `RuntimeVisibleTypeAnnotations` and `RuntimeInvisibleTypeAnnotations`, only annotations
+/// of a whole type are returned and not the ones of type arguments or array elements
+fn parse_type_annotations_attribute(
+    c: &Base,
+    data: &[u8],
+    pos: usize,
+) -> Result<(Vec<(TypeAnnotationTarget, MyString)>, usize), ClassParserError> {
+    let (count, mut pos) = get_u16(data, pos)?;
+    let mut out = Vec::new();
+    for _ in 0..count {
+        let (target_type, npos) = get_u8(data, pos)?;
+        let (target, npos) = match target_type {
+            0x13 => (TypeAnnotationTarget::Field, npos),
+            0x14 => (TypeAnnotationTarget::Return, npos),
+            0x16 => {
+                let (index, npos) = get_u8(data, npos)?;
+                (TypeAnnotationTarget::Parameter(index), npos)
+            }
+            0x15 => (TypeAnnotationTarget::Other, npos),
+            0x00 | 0x01 => (TypeAnnotationTarget::Other, npos + U8_LEN),
+            0x10 | 0x11 | 0x12 | 0x17 | 0x42..=0x46 => {
+                (TypeAnnotationTarget::Other, npos + U16_LEN)
+            }
+            0x47..=0x4B => (TypeAnnotationTarget::Other, npos + U16_LEN + U8_LEN),
+            0x40 | 0x41 => {
+                let (table_length, npos) = get_u16(data, npos)?;
+                let table = (table_length as usize).saturating_mul(3 * U16_LEN);
+                (TypeAnnotationTarget::Other, npos.saturating_add(table))
+            }
+            _ => return Err(ClassParserError::NotAsExpected),
+        };
+        let (path_length, npos) = get_u8(data, npos)?;
+        let npos = npos + path_length as usize * 2 * U8_LEN;
+        let (type_index, npos) = get_u16(data, npos)?;
+        pos = skip_element_value_pairs(data, npos)?;
+        if path_length == 0 && !matches!(target, TypeAnnotationTarget::Other) {
+            let mut name = Vec::with_capacity(1);
+            push_annotation(c, type_index, &mut name)?;
+            out.extend(name.into_iter().map(|name| (target, name)));
+        }
+    }
+    Ok((out, pos))
+}
+
+fn skip_element_value_pairs(data: &[u8], pos: usize) -> Result<usize, ClassParserError> {
+    let (count, mut pos) = get_u16(data, pos)?;
+    for _ in 0..count {
+        // element_name_index
+        pos = skip_element_value(data, pos + U16_LEN)?;
+    }
+    Ok(pos)
+}
+
+fn skip_element_value(data: &[u8], pos: usize) -> Result<usize, ClassParserError> {
+    let (tag, pos) = get_u8(data, pos)?;
+    match tag {
+        b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' | b's' | b'c' => Ok(pos + U16_LEN),
+        b'e' => Ok(pos + 2 * U16_LEN),
+        b'@' => skip_element_value_pairs(data, pos + U16_LEN),
+        b'[' => {
+            let (count, mut pos) = get_u16(data, pos)?;
+            for _ in 0..count {
+                pos = skip_element_value(data, pos)?;
+            }
+            Ok(pos)
+        }
+        _ => Err(ClassParserError::NotAsExpected),
+    }
+}
+
+struct InnerClass {
+    class_info: u16,
+    outer_class_info: u16,
+    inner_name: u16,
+    access_flags: Access,
+}
+
+fn parse_inner_classes_attribute(
+    data: &[u8],
+    pos: usize,
+) -> Result<(Vec<InnerClass>, usize), ClassParserError> {
+    let (count, pos) = get_u16(data, pos)?;
+    let mut out = Vec::with_capacity(count as usize);
+    let mut pos = pos;
+    for _ in 0..count {
+        let (class_info, npos) = get_u16(data, pos)?;
+        let (outer_class_info, npos) = get_u16(data, npos)?;
+        let (inner_name, npos) = get_u16(data, npos)?;
+        let (access_flags, npos) = parse_inner_class_access_flags(data, npos)?;
+        out.push(InnerClass {
+            class_info,
+            outer_class_info,
+            inner_name,
+            access_flags,
+        });
+        pos = npos;
+    }
+    Ok((out, pos))
+}
+
 struct MethodParametersAttribute {
     name_index: u16,
 }
@@ -370,48 +659,16 @@ fn parse_method_parameters_attribute_inner(
 }
 
 #[derive(Debug)]
-#[allow(dead_code)]
 struct MethodSignature {
     pub args: Vec<MyString>,
     pub params: Vec<JType>,
     pub ret: JType,
+    pub throws: Vec<JType>,
 }
 fn parse_method_signature_info(sig: &str) -> Result<(MethodSignature, usize), ClassParserError> {
     let content = sig.as_bytes();
-    let mut pos = 0;
-    let mut args = Vec::new();
+    let (args, pos) = parse_type_parameters(content, 0)?;
     let mut params = Vec::new();
-    if let Ok(npos) = assert_char(content, pos, b'<') {
-        pos = npos;
-        loop {
-            if let Ok(npos) = assert_char(content, pos, b';') {
-                pos = npos;
-            }
-            if let Ok(npos) = assert_char(content, pos, b'>') {
-                pos = npos;
-                break;
-            }
-            let mut arg = SmolStrBuilder::new();
-            loop {
-                let v = content.get(pos).ok_or(ClassParserError::EOF)?;
-                if *v == b':' {
-                    break;
-                }
-                let i = u32::from(*v);
-                let c = char::from_u32(i).ok_or(ClassParserError::GenericParameterName)?;
-                arg.push(c);
-                pos += 1;
-            }
-            args.push(arg.finish());
-            let npos = assert_char(content, pos, b':')?;
-            pos = npos;
-            if let Ok(npos) = assert_char(content, pos, b':') {
-                pos = npos;
-            }
-            let (_, npos) = parse_field_type(content, pos)?;
-            pos = npos;
-        }
-    }
     let mut pos = assert_char(content, pos, b'(')?;
     if let Ok(npos) = assert_char(content, pos, b')') {
         pos = npos;
@@ -429,44 +686,65 @@ fn parse_method_signature_info(sig: &str) -> Result<(MethodSignature, usize), Cl
             pos = npos;
         }
     }
+    // Type variables like `TE;` end before their semicolon
     let (ret, pos) = parse_field_type(content, pos)?;
-    Ok((MethodSignature { args, params, ret }, pos))
+    let mut pos = assert_char(content, pos, b';').unwrap_or(pos);
+    let mut throws = Vec::new();
+    while let Ok(npos) = assert_char(content, pos, b'^') {
+        let (ty, npos) = parse_field_type(content, npos)?;
+        throws.push(ty);
+        pos = assert_char(content, npos, b';').unwrap_or(npos);
+    }
+    Ok((
+        MethodSignature {
+            args,
+            params,
+            ret,
+            throws,
+        },
+        pos,
+    ))
 }
-fn parse_class_signature_info(sig: &str) -> Result<(ClassSignature, usize), ClassParserError> {
-    let content = sig.as_bytes();
-    let mut pos = 0;
+
+/// Names of `<T:Ljava/lang/Object;U::Ljava/lang/Comparable<TU;>;:Ljava/lang/Cloneable;>`,
+/// the class bound can be empty and is followed by any number of interface bounds
+fn parse_type_parameters(
+    content: &[u8],
+    pos: usize,
+) -> Result<(Vec<MyString>, usize), ClassParserError> {
     let mut args = Vec::new();
-    if let Ok(npos) = assert_char(content, pos, b'<') {
-        pos = npos;
+    let Ok(mut pos) = assert_char(content, pos, b'<') else {
+        return Ok((args, pos));
+    };
+    loop {
+        if let Ok(npos) = assert_char(content, pos, b'>') {
+            return Ok((args, npos));
+        }
+        let mut arg = SmolStrBuilder::new();
         loop {
-            if let Ok(npos) = assert_char(content, pos, b';') {
-                pos = npos;
-            }
-            if let Ok(npos) = assert_char(content, pos, b'>') {
-                pos = npos;
+            let v = content.get(pos).ok_or(ClassParserError::EOF)?;
+            if *v == b':' {
                 break;
             }
-            let mut arg = SmolStrBuilder::new();
-            loop {
-                let v = content.get(pos).ok_or(ClassParserError::EOF)?;
-                if *v == b':' {
-                    break;
-                }
-                let c =
-                    char::from_u32(u32::from(*v)).ok_or(ClassParserError::GenericParameterName)?;
-                arg.push(c);
-                pos += 1;
-            }
-            args.push(arg.finish());
-            let npos = assert_char(content, pos, b':')?;
+            let c = char::from_u32(u32::from(*v)).ok_or(ClassParserError::GenericParameterName)?;
+            arg.push(c);
+            pos += 1;
+        }
+        args.push(arg.finish());
+        while let Ok(npos) = assert_char(content, pos, b':') {
             pos = npos;
-            if let Ok(npos) = assert_char(content, pos, b':') {
-                pos = npos;
+            if matches!(content.get(pos), Some(b':')) {
+                continue;
             }
             let (_, npos) = parse_field_type(content, pos)?;
-            pos = npos;
+            pos = assert_char(content, npos, b';').unwrap_or(npos);
         }
     }
+}
+
+fn parse_class_signature_info(sig: &str) -> Result<(ClassSignature, usize), ClassParserError> {
+    let content = sig.as_bytes();
+    let (args, mut pos) = parse_type_parameters(content, 0)?;
     let mut init = false;
     let mut ret = JType::default();
     let end = sig.len();
@@ -543,27 +821,74 @@ fn parse_code_attribute(
     ))
 }
 
-/// Returns list of descriptors
+struct LocalVariableEntry {
+    start_pc: u16,
+    name_index: u16,
+    descriptor_index: u16,
+    index: u16,
+}
+
 fn parse_local_variable_table_attribute(
     data: &[u8],
     pos: usize,
-) -> Result<(Vec<u16>, usize), ClassParserError> {
+) -> Result<(Vec<LocalVariableEntry>, usize), ClassParserError> {
     let (table_length, pos) = get_u16(data, pos)?;
     let mut out = Vec::with_capacity(table_length as usize);
     let mut pos = pos;
 
     for _ in 0..table_length {
-        pos += 3 * U16_LEN;
-        let (descriptor_index, npos) = get_u16(data, pos)?;
+        let (start_pc, npos) = get_u16(data, pos)?;
+        // skip length
+        let (name_index, npos) = get_u16(data, npos + U16_LEN)?;
+        let (descriptor_index, npos) = get_u16(data, npos)?;
+        let (index, npos) = get_u16(data, npos)?;
         pos = npos;
-        out.push(descriptor_index);
-        // skip u16
-        pos += U16_LEN;
+        out.push(LocalVariableEntry {
+            start_pc,
+            name_index,
+            descriptor_index,
+            index,
+        });
     }
 
     Ok((out, pos))
 }
 
+/// Names of the parameters from the local variables that start with the method. Used for
+/// classes compiled with `-g` but without `-parameters`.
+fn local_variable_parameter_names(
+    c: &Base,
+    data: &[u8],
+    code_attribute: &CodeAttribute,
+    parameters: &[Parameter],
+    is_static: bool,
+) -> Result<Vec<Option<MyString>>, ClassParserError> {
+    let info = code_attribute.lookup(data)?;
+    let mut variables = Vec::new();
+    for attribute in &code_attribute.attributes {
+        if lookup_string(c, attribute.name)? == "LocalVariableTable" {
+            let (table, _) = parse_local_variable_table_attribute(attribute.lookup(info)?, 0)?;
+            variables.extend(table.into_iter().filter(|v| v.start_pc == 0));
+        }
+    }
+    let mut slot = u16::from(!is_static);
+    let mut out = Vec::with_capacity(parameters.len());
+    for p in parameters {
+        let name = variables
+            .iter()
+            .find(|v| v.index == slot)
+            .and_then(|v| lookup_string(c, v.name_index).ok())
+            .map(SmolStr::new);
+        out.push(name);
+        slot += if matches!(p.jtype, JType::Long | JType::Double) {
+            2
+        } else {
+            1
+        };
+    }
+    Ok(out)
+}
+
 fn parse_used_classes(
     c: &Base,
     data: &[u8],
@@ -576,9 +901,9 @@ fn parse_used_classes(
         let attribute_name = lookup_string(c, attribute.name)?;
         if attribute_name == "LocalVariableTable" {
             let info = attribute.lookup(info)?;
-            let (descriptors, _) = parse_local_variable_table_attribute(info, 0)?;
-            for f in descriptors {
-                let field_desc = lookup_string(c, f)?;
+            let (variables, _) = parse_local_variable_table_attribute(info, 0)?;
+            for v in variables {
+                let field_desc = lookup_string(c, v.descriptor_index)?;
                 let (field_desc, _) = parse_field_type(field_desc.as_bytes(), 0)?;
                 jtype_class_names(field_desc, used_classes);
             }
@@ -917,6 +1242,7 @@ struct Field {
     pub access_flags: Access,
     pub name: u16,
     pub descriptor: u16,
+    pub attributes: Vec<Attribute>,
 }
 struct Method {
     pub access_flags: Access,
@@ -991,6 +1317,12 @@ fn parse_class_access_flags(data: &[u8], pos: usize) -> Result<(Access, usize),
     if (flags & 0x0400) != 0 {
         out |= Access::Abstract;
     }
+    if (flags & 0x1000) != 0 {
+        out |= Access::Synthetic;
+    }
+    if (flags & 0x2000) != 0 {
+        out |= Access::Annotation;
+    }
     if (flags & 0x4000) != 0 {
         out |= Access::Enum;
     }
@@ -998,6 +1330,26 @@ fn parse_class_access_flags(data: &[u8], pos: usize) -> Result<(Access, usize),
     Ok((out, pos))
 }
 
+/// Flags of a nested class, unlike the flags of the class file they keep private, protected
+/// and static
+fn parse_inner_class_access_flags(
+    data: &[u8],
+    pos: usize,
+) -> Result<(Access, usize), ClassParserError> {
+    let (flags, npos) = get_u16(data, pos)?;
+    let (mut out, _) = parse_class_access_flags(data, pos)?;
+    if (flags & 0x0002) != 0 {
+        out |= Access::Private;
+    }
+    if (flags & 0x0004) != 0 {
+        out |= Access::Protected;
+    }
+    if (flags & 0x0008) != 0 {
+        out |= Access::Static;
+    }
+    Ok((out, npos))
+}
+
 fn parse_fields(data: &[u8], pos: usize) -> Result<(Vec<Field>, usize), ClassParserError> {
     let (size, pos) = get_u16(data, pos)?;
     let mut pos = pos;
@@ -1016,12 +1368,13 @@ fn parse_class_field(data: &[u8], pos: usize) -> Result<(Field, usize), ClassPar
     let (access_flags, pos) = parse_field_access_flags(data, pos)?;
     let (name, pos) = get_u16(data, pos)?;
     let (descriptor, pos) = get_u16(data, pos)?;
-    let (_, pos) = parse_attributes(data, pos)?;
+    let (attributes, pos) = parse_attributes(data, pos)?;
     Ok((
         Field {
             access_flags,
             name,
             descriptor,
+            attributes,
         },
         pos,
     ))
@@ -1107,9 +1460,16 @@ fn parse_method_access_flags(data: &[u8], pos: usize) -> Result<(Access, usize),
     if (flags & 0x0010) != 0 {
         out |= Access::Final;
     }
+    if (flags & 0x0080) != 0 {
+        out |= Access::Varargs;
+    }
     if (flags & 0x0400) != 0 {
         out |= Access::Abstract;
     }
+    // Bridge methods are generated by the compiler like synthetic methods
+    if (flags & 0x1040) != 0 {
+        out |= Access::Synthetic;
+    }
 
     Ok((out, pos))
 }
@@ -1359,7 +1719,7 @@ fn expect_data(data: &[u8], pos: usize, expected: &[u8]) -> Result<usize, ClassP
 #[cfg(test)]
 mod tests {
     use crate::{load_class, load_module, parse_class_signature_info, parse_field_type};
-    use dto::SourceDestination;
+    use dto::{Access, ClassParserError, SourceDestination};
     use expect_test::expect;
     use my_string::smol_str::SmolStr;
     // #[test]
@@ -1411,10 +1771,12 @@ mod tests {
                             Public,
                         ),
                         name: None,
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1423,10 +1785,12 @@ mod tests {
                         name: Some(
                             "method",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1435,10 +1799,12 @@ mod tests {
                         name: Some(
                             "public_method",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1447,10 +1813,12 @@ mod tests {
                         name: Some(
                             "private_method",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1459,10 +1827,12 @@ mod tests {
                         name: Some(
                             "out",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Int,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1471,23 +1841,27 @@ mod tests {
                         name: Some(
                             "add",
                         ),
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
                                     "a",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                             Parameter {
                                 name: Some(
                                     "b",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                         ],
                         throws: [],
                         ret: Int,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1496,23 +1870,27 @@ mod tests {
                         name: Some(
                             "sadd",
                         ),
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
                                     "a",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                             Parameter {
                                 name: Some(
                                     "b",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                         ],
                         throws: [],
                         ret: Int,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [
@@ -1523,6 +1901,7 @@ mod tests {
                         name: "noprop",
                         jtype: Int,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -1531,6 +1910,7 @@ mod tests {
                         name: "publicproperty",
                         jtype: Int,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -1539,6 +1919,7 @@ mod tests {
                         name: "privateproperty",
                         jtype: Int,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 super_class: None,
@@ -1577,10 +1958,12 @@ mod tests {
                             Public,
                         ),
                         name: None,
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1589,10 +1972,12 @@ mod tests {
                         name: Some(
                             "method",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1601,10 +1986,12 @@ mod tests {
                         name: Some(
                             "public_method",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1613,10 +2000,12 @@ mod tests {
                         name: Some(
                             "private_method",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1625,10 +2014,12 @@ mod tests {
                         name: Some(
                             "out",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Int,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1637,23 +2028,27 @@ mod tests {
                         name: Some(
                             "add",
                         ),
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
                                     "a",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                             Parameter {
                                 name: Some(
                                     "b",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                         ],
                         throws: [],
                         ret: Int,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1662,23 +2057,27 @@ mod tests {
                         name: Some(
                             "sadd",
                         ),
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
                                     "a",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                             Parameter {
                                 name: Some(
                                     "b",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                         ],
                         throws: [],
                         ret: Int,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [
@@ -1689,6 +2088,7 @@ mod tests {
                         name: "noprop",
                         jtype: Int,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -1697,6 +2097,7 @@ mod tests {
                         name: "publicproperty",
                         jtype: Int,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -1705,6 +2106,7 @@ mod tests {
                         name: "privateproperty",
                         jtype: Int,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 super_class: None,
@@ -1742,10 +2144,12 @@ mod tests {
                             Public,
                         ),
                         name: None,
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [],
@@ -1785,10 +2189,12 @@ mod tests {
                             Public,
                         ),
                         name: None,
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1797,6 +2203,7 @@ mod tests {
                         name: Some(
                             "ioThrower",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [
                             Class(
@@ -1805,6 +2212,7 @@ mod tests {
                         ],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1813,12 +2221,14 @@ mod tests {
                         name: Some(
                             "ioThrower",
                         ),
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
                                     "a",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                         ],
                         throws: [
@@ -1831,6 +2241,7 @@ mod tests {
                         ],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [],
@@ -1893,6 +2304,7 @@ mod tests {
                         name: Some(
                             "stream",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Generic(
@@ -1904,6 +2316,7 @@ mod tests {
                             ],
                         ),
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [],
@@ -1943,6 +2356,9 @@ mod tests {
                     Class(
                         "java.util.HashMap",
                     ),
+                    Class(
+                        "java.lang.String",
+                    ),
                     Class(
                         "java.util.HashSet",
                     ),
@@ -1955,10 +2371,12 @@ mod tests {
                             Public,
                         ),
                         name: None,
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1967,10 +2385,12 @@ mod tests {
                         name: Some(
                             "hereIsCode",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1979,23 +2399,27 @@ mod tests {
                         name: Some(
                             "hereIsCode",
                         ),
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
                                     "a",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                             Parameter {
                                 name: Some(
                                     "b",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                         ],
                         throws: [],
                         ret: Int,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [
@@ -2004,10 +2428,16 @@ mod tests {
                             Private,
                         ),
                         name: "a",
-                        jtype: Class(
+                        jtype: Generic(
                             "java.util.HashSet",
+                            [
+                                Class(
+                                    "java.lang.String",
+                                ),
+                            ],
                         ),
                         source: None,
+                        annotations: [],
                     },
                 ],
                 super_class: None,
@@ -2062,6 +2492,7 @@ mod tests {
                         name: Some(
                             "values",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Array(
@@ -2070,6 +2501,7 @@ mod tests {
                             ),
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -2078,6 +2510,7 @@ mod tests {
                         name: Some(
                             "valueOf",
                         ),
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
@@ -2086,6 +2519,7 @@ mod tests {
                                 jtype: Class(
                                     "java.lang.String",
                                 ),
+                                annotations: [],
                             },
                         ],
                         throws: [],
@@ -2093,12 +2527,14 @@ mod tests {
                             "ch.emilycares.Variants",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
                             Private,
                         ),
                         name: None,
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
@@ -2107,11 +2543,13 @@ mod tests {
                                 jtype: Class(
                                     "java.lang.String",
                                 ),
+                                annotations: [],
                             },
                         ],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -2120,20 +2558,23 @@ mod tests {
                         name: Some(
                             "getTag",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Class(
                             "java.lang.String",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
-                            Private | Static,
+                            Private | Static | Synthetic,
                         ),
                         name: Some(
                             "$values",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Array(
@@ -2142,6 +2583,7 @@ mod tests {
                             ),
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -2150,10 +2592,12 @@ mod tests {
                         name: Some(
                             "<clinit>",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [
@@ -2166,6 +2610,7 @@ mod tests {
                             "ch.emilycares.Variants",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -2176,6 +2621,7 @@ mod tests {
                             "ch.emilycares.Variants",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -2186,6 +2632,7 @@ mod tests {
                             "ch.emilycares.Variants",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -2196,6 +2643,7 @@ mod tests {
                             "java.lang.String",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -2208,6 +2656,7 @@ mod tests {
                             ),
                         ),
                         source: None,
+                        annotations: [],
                     },
                 ],
                 super_class: ClassPath(
@@ -2327,6 +2776,312 @@ mod tests {
         expected.assert_debug_eq(&result.0);
     }
 
+    #[test]
+    fn generics() {
+        let result = load_class(
+            include_bytes!("../../parser/test/Generics.class"),
+            SmolStr::new("ch.emilycares.Generics"),
+            SourceDestination::None,
+            true,
+        );
+        let expected = expect![[r#"
+            Class {
+                class_path: "ch.emilycares.Generics",
+                source: None,
+                access: Access(
+                    Public | Super,
+                ),
+                imports: [
+                    Package(
+                        "ch.emilycares",
+                    ),
+                    Class(
+                        "java.util.List",
+                    ),
+                    Class(
+                        "java.lang.Runnable",
+                    ),
+                    Class(
+                        "java.lang.String",
+                    ),
+                    Class(
+                        "java.util.List",
+                    ),
+                    Class(
+                        "java.lang.String",
+                    ),
+                    Class(
+                        "java.util.List",
+                    ),
+                    Class(
+                        "java.util.Map",
+                    ),
+                ],
+                signature: Some(
+                    ClassSignature {
+                        args: [
+                            "T",
+                        ],
+                        ret: Class(
+                            "java.lang.Object",
+                        ),
+                    },
+                ),
+                name: "Generics",
+                methods: [
+                    Method {
+                        access: Access(
+                            Public,
+                        ),
+                        name: None,
+                        type_parameters: [],
+                        parameters: [],
+                        throws: [],
+                        ret: Void,
+                        source: None,
+                        annotations: [],
+                    },
+                    Method {
+                        access: Access(
+                            Public,
+                        ),
+                        name: Some(
+                            "run",
+                        ),
+                        type_parameters: [
+                            "E",
+                        ],
+                        parameters: [
+                            Parameter {
+                                name: Some(
+                                    "items",
+                                ),
+                                jtype: Generic(
+                                    "java.util.List",
+                                    [
+                                        Parameter(
+                                            "T",
+                                        ),
+                                    ],
+                                ),
+                                annotations: [],
+                            },
+                        ],
+                        throws: [
+                            Parameter(
+                                "E",
+                            ),
+                        ],
+                        ret: Void,
+                        source: None,
+                        annotations: [],
+                    },
+                    Method {
+                        access: Access(
+                            Public,
+                        ),
+                        name: Some(
+                            "orThrow",
+                        ),
+                        type_parameters: [
+                            "X",
+                        ],
+                        parameters: [
+                            Parameter {
+                                name: Some(
+                                    "x",
+                                ),
+                                jtype: Parameter(
+                                    "X",
+                                ),
+                                annotations: [],
+                            },
+                        ],
+                        throws: [
+                            Parameter(
+                                "X",
+                            ),
+                            Class(
+                                "java.io.IOException",
+                            ),
+                        ],
+                        ret: Parameter(
+                            "T",
+                        ),
+                        source: None,
+                        annotations: [],
+                    },
+                    Method {
+                        access: Access(
+                            Public,
+                        ),
+                        name: Some(
+                            "anonymous",
+                        ),
+                        type_parameters: [],
+                        parameters: [],
+                        throws: [],
+                        ret: Class(
+                            "java.lang.Runnable",
+                        ),
+                        source: None,
+                        annotations: [],
+                    },
+                ],
+                fields: [
+                    Field {
+                        access: Access(
+                            Public,
+                        ),
+                        name: "names",
+                        jtype: Generic(
+                            "java.util.List",
+                            [
+                                Class(
+                                    "java.lang.String",
+                                ),
+                            ],
+                        ),
+                        source: None,
+                        annotations: [],
+                    },
+                    Field {
+                        access: Access(
+                            Public,
+                        ),
+                        name: "groups",
+                        jtype: Generic(
+                            "java.util.Map",
+                            [
+                                Class(
+                                    "java.lang.String",
+                                ),
+                                Generic(
+                                    "java.util.List",
+                                    [
+                                        Parameter(
+                                            "T",
+                                        ),
+                                    ],
+                                ),
+                            ],
+                        ),
+                        source: None,
+                        annotations: [],
+                    },
+                ],
+                super_class: None,
+                super_interfaces: [],
+            }
+        "#]];
+        expected.assert_debug_eq(&result.unwrap());
+    }
+
+    #[test]
+    fn annotations() {
+        let class = load_class(
+            include_bytes!("../../parser/test/Annotations.class"),
+            SmolStr::new("ch.emilycares.Annotations"),
+            SourceDestination::None,
+            true,
+        )
+        .unwrap();
+        let field = class.fields.iter().find(|f| f.name == "name").unwrap();
+        assert_eq!(
+            field.annotations,
+            [
+                "ch.emilycares.Annotations$Checked",
+                "ch.emilycares.Annotations$Nullable"
+            ]
+        );
+        let get = class
+            .methods
+            .iter()
+            .find(|m| m.name.as_deref() == Some("get"))
+            .unwrap();
+        assert_eq!(
+            get.annotations,
+            [
+                "ch.emilycares.Annotations$Checked",
+                "ch.emilycares.Annotations$Nullable"
+            ]
+        );
+        let params = get
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_deref(), p.annotations.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            params,
+            [
+                (
+                    Some("key"),
+                    vec![SmolStr::new("ch.emilycares.Annotations$Checked")]
+                ),
+                (Some("offset"), vec![]),
+                (
+                    Some("fallback"),
+                    vec![SmolStr::new("ch.emilycares.Annotations$Nullable")]
+                ),
+            ]
+        );
+        let sum = class
+            .methods
+            .iter()
+            .find(|m| m.name.as_deref() == Some("sum"))
+            .unwrap();
+        let names = sum
+            .parameters
+            .iter()
+            .map(|p| p.name.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(names, [Some("a"), Some("b")]);
+    }
+
+    #[test]
+    fn inner_classes() {
+        let entry = load_class(
+            include_bytes!("../../parser/test/Generics$Entry.class"),
+            SmolStr::new("ch.emilycares.Generics$Entry"),
+            SourceDestination::None,
+            true,
+        )
+        .unwrap();
+        assert!(entry.access.contains(Access::Public | Access::Static));
+        let compare_to = entry
+            .methods
+            .iter()
+            .filter(|m| m.name.as_deref() == Some("compareTo"))
+            .count();
+        assert_eq!(compare_to, 1);
+        let inner = load_class(
+            include_bytes!("../../parser/test/Generics$Inner.class"),
+            SmolStr::new("ch.emilycares.Generics$Inner"),
+            SourceDestination::None,
+            true,
+        )
+        .unwrap();
+        assert!(!inner.access.contains(Access::Static));
+        for (bytes, class_path) in [
+            (
+                include_bytes!("../../parser/test/Generics$Hidden.class").as_slice(),
+                "ch.emilycares.Generics$Hidden",
+            ),
+            (
+                include_bytes!("../../parser/test/Generics$1.class").as_slice(),
+                "ch.emilycares.Generics$1",
+            ),
+        ] {
+            let out = load_class(
+                bytes,
+                SmolStr::new(class_path),
+                SourceDestination::None,
+                true,
+            );
+            assert!(matches!(out, Err(ClassParserError::Ignoring)));
+        }
+    }
+
     #[test]
     fn class_signature() {
         let content =
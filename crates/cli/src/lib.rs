@@ -3,12 +3,16 @@
 #![deny(clippy::nursery)]
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::unused_async)]
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write as _;
 use std::path::Path;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::{fs::canonicalize, path::PathBuf};
 
-use ast::error::PrintErr;
+use ast::error::{PrintErr, failing_construct};
 use jdk::{test_load_jdk_jmod, test_load_jdk_modules_executable, test_load_jdk_modules_own};
+use serde_json::Value;
 #[derive(Debug)]
 pub enum CheckError {
     IO(std::io::Error),
@@ -25,6 +29,12 @@ no arg : Starts lsp over stdio
 
 server-tcp <port> : Open tcp lsp socket
 
+--listen <tcp://host:port | unix://path> : Wait for a client on a tcp address or unix socket
+
+--pipe <name> : Connect to the named pipe or unix socket of the editor
+
+--socket <port> : Connect to the tcp port of the editor
+
 reload-deps : Reload dependencies of current project
 
 update-deps : Update dependencies of current project
@@ -33,12 +43,20 @@ lex <file path to java file> : Print tokens from file
 
 lex-pos <file path to java file> <index> : Print token at position
 
+ast <file path to java file> <Optional --line <line> --col <column>> : Print the ast as json, only the node at the 1-based position when given
+
 ast-check <file path to java file> : Check for ast errors in java file
 
 ast-check-dir <directory path> <Optional ignore pattern> : Check for ast errors in directory
 
 ast-check-jdk : Check for ast errors in current jdk in path
 
+parse-check <directory path> : Parse every java file in the directory and report the parsed percentage, the constructs that fail most and the parse times
+
+check <directory path> <Optional --format <human | json | sarif>> : Print the diagnostics of the java files in the directory, exits with 1 on errors
+
+symbols <query> <Optional --dir <directory path>> : Print the classes, methods and fields that match the query with their file:line, exits with 1 when none match
+
 index-jdk <variant> : Index jdk in path with variant jimage-own/jimage-executable/jmod
 "
     );
@@ -53,11 +71,33 @@ pub fn parse(args: &[String]) -> Option<Command> {
         Some("update-deps") => Some(Command::UpdateDependencies),
         Some("lex") => parse_lex(&args[1..]),
         Some("lex-pos") => parse_lex_pos(&args[1..]),
+        Some("ast") => parse_ast(&args[1..]),
         Some("ast-check") => parse_ast_check(&args[1..]),
         Some("ast-check-dir") => parse_ast_check_dir(&args[1..]),
         Some("ast-check-jdk") => Some(Command::AstCheckJdk),
+        Some("parse-check") => parse_parse_check(&args[1..]),
+        Some("check") => parse_check(&args[1..]),
+        Some("symbols") => parse_symbols(&args[1..]),
         Some("index-jdk") => parse_index_jdk(&args[1..]),
         Some("--help") => Some(Command::Help),
+        Some(arg) if option(arg, "--listen").is_some() => option_value(arg, "--listen", &args[1..])
+            .map(|address| Command::ServerListen {
+                address: address.to_string(),
+            }),
+        Some(arg) if option(arg, "--pipe").is_some() => option_value(arg, "--pipe", &args[1..])
+            .map(|name| Command::ServerPipe {
+                name: name.to_string(),
+            }),
+        Some(arg) if option(arg, "--socket").is_some() => option_value(arg, "--socket", &args[1..])
+            .and_then(|port| {
+                port.parse().map_or_else(
+                    |_| {
+                        println!("Port must be a number");
+                        None
+                    },
+                    |port| Some(Command::ServerSocket { port }),
+                )
+            }),
         // for vscode
         Some("--stdio") => None,
         Some(u) => {
@@ -85,6 +125,89 @@ fn parse_index_jdk(args: &[String]) -> Option<Command> {
     }
 }
 
+fn parse_ast(args: &[String]) -> Option<Command> {
+    let Some(path) = args.first() else {
+        println!("Expected file path");
+        return None;
+    };
+    let mut line = None;
+    let mut col = None;
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        let (target, name) = if option(arg, "--line").is_some() {
+            (&mut line, "--line")
+        } else if option(arg, "--col").is_some() {
+            (&mut col, "--col")
+        } else {
+            println!("unknown option {arg}");
+            return None;
+        };
+        let value = match option(arg, name) {
+            Some("") => rest.next().map(String::as_str),
+            value => value,
+        };
+        let Some(Ok(value)) = value.map(str::parse::<usize>) else {
+            println!("{name} must be a number");
+            return None;
+        };
+        *target = Some(value);
+    }
+    let position = match (line, col) {
+        (Some(line), Some(col)) => Some((line, col)),
+        (None, None) => None,
+        _ => {
+            println!("Expected --line and --col");
+            return None;
+        }
+    };
+    Some(Command::Ast {
+        file: PathBuf::from(path),
+        position,
+    })
+}
+
+fn parse_check(args: &[String]) -> Option<Command> {
+    let Some(folder) = args.first() else {
+        println!("Expected directory path");
+        return None;
+    };
+    let format = match args.get(1) {
+        Some(arg) if option(arg, "--format").is_some() => {
+            Some(option_value(arg, "--format", &args[2..])?.to_string())
+        }
+        Some(arg) => {
+            println!("unknown option {arg}");
+            return None;
+        }
+        None => None,
+    };
+    Some(Command::Check {
+        folder: PathBuf::from(folder),
+        format,
+    })
+}
+
+fn parse_symbols(args: &[String]) -> Option<Command> {
+    let Some(query) = args.first() else {
+        println!("Expected query");
+        return None;
+    };
+    let folder = match args.get(1) {
+        Some(arg) if option(arg, "--dir").is_some() => {
+            Some(PathBuf::from(option_value(arg, "--dir", &args[2..])?))
+        }
+        Some(arg) => {
+            println!("unknown option {arg}");
+            return None;
+        }
+        None => None,
+    };
+    Some(Command::Symbols {
+        query: query.clone(),
+        folder,
+    })
+}
+
 fn parse_ast_check(args: &[String]) -> Option<Command> {
     args.first().map_or_else(
         || {
@@ -121,6 +244,20 @@ fn parse_ast_check_dir(args: &[String]) -> Option<Command> {
     )
 }
 
+fn parse_parse_check(args: &[String]) -> Option<Command> {
+    args.first().map_or_else(
+        || {
+            println!("Expected directory path");
+            None
+        },
+        |path| {
+            Some(Command::ParseCheck {
+                folder: PathBuf::from(path),
+            })
+        },
+    )
+}
+
 fn parse_lex(args: &[String]) -> Option<Command> {
     args.first().map_or_else(
         || {
@@ -157,6 +294,26 @@ fn parse_lex_pos(args: &[String]) -> Option<Command> {
     )
 }
 
+/// Matches `--name` and `--name=value`
+fn option<'a>(arg: &'a str, name: &str) -> Option<&'a str> {
+    let rest = arg.strip_prefix(name)?;
+    if rest.is_empty() {
+        return Some(rest);
+    }
+    rest.strip_prefix('=')
+}
+
+/// Value of `--name=value` or `--name value`
+fn option_value<'a>(arg: &'a str, name: &str, rest: &'a [String]) -> Option<&'a str> {
+    match option(arg, name) {
+        Some("") => rest.first().map(String::as_str).or_else(|| {
+            println!("Expected value for {name}");
+            None
+        }),
+        value => value,
+    }
+}
+
 fn parse_server_tcp(args: &[String]) -> Option<Command> {
     args.first().map_or_else(
         || {
@@ -185,6 +342,18 @@ pub enum Command {
     ServerTcp {
         port: u16,
     },
+    /// Start the lsp server on a `tcp://` or `unix://` address
+    ServerListen {
+        address: String,
+    },
+    /// Start the lsp server connected to the pipe of the editor
+    ServerPipe {
+        name: String,
+    },
+    /// Start the lsp server connected to the tcp port of the editor
+    ServerSocket {
+        port: u16,
+    },
     /// Reloads the dependencies of project
     ReloadDependencies,
     /// Update the dependencies of project
@@ -198,6 +367,11 @@ pub enum Command {
         file: PathBuf,
         pos: usize,
     },
+    /// Print the ast of the file as json, or the innermost node at the 1-based line and column
+    Ast {
+        file: PathBuf,
+        position: Option<(usize, usize)>,
+    },
     /// Check for errors in file
     AstCheck {
         file: PathBuf,
@@ -209,6 +383,21 @@ pub enum Command {
     },
     /// Check jdk in path
     AstCheckJdk,
+    /// Report how many java files of a directory parse
+    ParseCheck {
+        folder: PathBuf,
+    },
+    /// Print the diagnostics of the java files in the folder
+    Check {
+        folder: PathBuf,
+        format: Option<String>,
+    },
+    /// Print the symbols of the workspace that match the query, the folder is the current one
+    /// when not given
+    Symbols {
+        query: String,
+        folder: Option<PathBuf>,
+    },
     IndexJdk {
         variant: IndexJdkOptions,
     },
@@ -244,6 +433,77 @@ pub fn ast_check(path: &PathBuf) {
     }
 }
 
+/// Print the ast of the file as json
+///
+/// # Panics
+/// When the file can not be read
+pub fn ast_json(file: &PathBuf, position: Option<(usize, usize)>) {
+    let bytes = std::fs::read(file).expect("File should exist");
+    let tokens = match ast::lexer::lex(&bytes) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            eprintln!("Lexer error: {e:?}");
+            std::process::exit(2);
+        }
+    };
+    let ast = ast::parse_file(&tokens);
+    let Ok(ast) = ast else {
+        ast.print_err(&String::from_utf8_lossy(&bytes), &tokens);
+        std::process::exit(3);
+    };
+    let Ok(value) = serde_json::to_value(&ast) else {
+        eprintln!("Unable to serialize ast");
+        std::process::exit(4);
+    };
+    let node = match position {
+        Some((line, col)) => {
+            let point = (line.saturating_sub(1), col.saturating_sub(1));
+            let Some(node) = node_at(&value, point) else {
+                eprintln!("No node at {line}:{col}");
+                std::process::exit(5);
+            };
+            node
+        }
+        None => &value,
+    };
+    match serde_json::to_string_pretty(node) {
+        Ok(json) => println!("{json}"),
+        Err(e) => eprintln!("Unable to print ast: {e:?}"),
+    }
+}
+
+/// Innermost json object with a `range` that contains the 0-based point
+fn node_at(value: &Value, point: (usize, usize)) -> Option<&Value> {
+    let mut children: Box<dyn Iterator<Item = &Value>> = match value {
+        Value::Object(map) => Box::new(map.iter().filter(|(k, _)| *k != "range").map(|(_, v)| v)),
+        Value::Array(values) => Box::new(values.iter()),
+        _ => return None,
+    };
+    if let Some(inner) = children.find_map(|child| node_at(child, point)) {
+        return Some(inner);
+    }
+    value
+        .get("range")
+        .is_some_and(|range| range_contains(range, point))
+        .then_some(value)
+}
+
+fn range_contains(range: &Value, point: (usize, usize)) -> bool {
+    let as_point = |p: &Value| {
+        Some((
+            usize::try_from(p.get("line")?.as_u64()?).ok()?,
+            usize::try_from(p.get("col")?.as_u64()?).ok()?,
+        ))
+    };
+    let (Some(start), Some(end)) = (
+        range.get("start").and_then(as_point),
+        range.get("end").and_then(as_point),
+    ) else {
+        return false;
+    };
+    start <= point && point <= end
+}
+
 fn lex_and_ast(file: &Path, text: &[u8]) {
     // eprintln!("Here: {:?}", file);
     match ast::lexer::lex(text) {
@@ -325,6 +585,139 @@ pub async fn ast_check_dir_ignore(folder: PathBuf, ignore: &[String]) -> Result<
     Ok(())
 }
 
+/// Result of lexing and parsing a file
+#[derive(Debug)]
+pub enum ParseOutcome {
+    Parsed,
+    LexError,
+    /// The construct that failed to parse
+    ParseError(String),
+    Panic,
+}
+
+#[derive(Debug)]
+pub struct ParseResult {
+    pub path: PathBuf,
+    pub time: Duration,
+    pub outcome: ParseOutcome,
+}
+
+/// Parse all java files in the folder and print a report of the parsed percentage, the
+/// constructs that fail most and the parse times
+pub fn parse_check_dir(folder: PathBuf) -> Result<(), CheckError> {
+    let time = Instant::now();
+    let dir = canonicalize(folder).map_err(CheckError::IO)?;
+    let files = RefCell::new(Vec::new());
+    let mut dirs = std::collections::VecDeque::new();
+    dirs.push_back(dir.clone());
+    while let Some(dir) = dirs.pop_front() {
+        visit_java_fies(&dir, &mut dirs, |file| {
+            files.borrow_mut().push(file.clone());
+        })?;
+    }
+    let results: Vec<ParseResult> = files
+        .into_inner()
+        .into_iter()
+        .filter_map(|path| match std::fs::read(&path) {
+            Ok(bytes) => Some(parse_timed(path, &bytes)),
+            Err(e) => {
+                eprintln!("Unable to read {}: {e:?}", path.display());
+                None
+            }
+        })
+        .collect();
+    print!("{}", parse_report(&results, &dir));
+    println!("Checked all files. in: {:.2?}", time.elapsed());
+    Ok(())
+}
+
+fn parse_timed(path: PathBuf, bytes: &[u8]) -> ParseResult {
+    let start = Instant::now();
+    let outcome = std::panic::catch_unwind(|| {
+        ast::lexer::lex(bytes).map_or(ParseOutcome::LexError, |tokens| {
+            match ast::parse_file(&tokens) {
+                Ok(_) => ParseOutcome::Parsed,
+                Err(e) => ParseOutcome::ParseError(failing_construct(&e).to_string()),
+            }
+        })
+    })
+    .unwrap_or(ParseOutcome::Panic);
+    ParseResult {
+        path,
+        time: start.elapsed(),
+        outcome,
+    }
+}
+
+/// Report of the parse results, paths are relative to the folder
+#[must_use]
+pub fn parse_report(results: &[ParseResult], folder: &Path) -> String {
+    const TOP: usize = 10;
+    const SLOWEST: usize = 5;
+    let mut out = String::new();
+    if results.is_empty() {
+        out.push_str("No java files found\n");
+        return out;
+    }
+    let relative = |path: &Path| {
+        path.strip_prefix(folder)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    };
+    let count = |f: fn(&ParseOutcome) -> bool| results.iter().filter(|r| f(&r.outcome)).count();
+    let parsed = count(|o| matches!(o, ParseOutcome::Parsed));
+    #[allow(clippy::cast_precision_loss)]
+    let percent = parsed as f64 * 100.0 / results.len() as f64;
+    let _ = writeln!(
+        out,
+        "Parsed {parsed} of {} files ({percent:.2}%)",
+        results.len()
+    );
+    let lex_errors = count(|o| matches!(o, ParseOutcome::LexError));
+    if lex_errors > 0 {
+        let _ = writeln!(out, "Lexer errors: {lex_errors}");
+    }
+    let panics = count(|o| matches!(o, ParseOutcome::Panic));
+    if panics > 0 {
+        let _ = writeln!(out, "Panics: {panics}");
+    }
+
+    // Construct with the number of files and the first file
+    let mut constructs: HashMap<&str, (usize, &Path)> = HashMap::new();
+    for result in results {
+        if let ParseOutcome::ParseError(construct) = &result.outcome {
+            constructs.entry(construct).or_insert((0, &result.path)).0 += 1;
+        }
+    }
+    let mut constructs: Vec<_> = constructs.into_iter().collect();
+    constructs.sort_by(|(a_name, (a, _)), (b_name, (b, _))| b.cmp(a).then(a_name.cmp(b_name)));
+    if !constructs.is_empty() {
+        out.push_str("Top failing constructs:\n");
+        for (construct, (count, example)) in constructs.iter().take(TOP) {
+            let _ = writeln!(out, "{count:>6} {construct} (e.g. {})", relative(example));
+        }
+    }
+
+    let mut times: Vec<&ParseResult> = results.iter().collect();
+    times.sort_by_key(|r| std::cmp::Reverse(r.time));
+    let total: Duration = results.iter().map(|r| r.time).sum();
+    let at = |percentile: usize| times[(times.len() - 1) * (100 - percentile) / 100].time;
+    let _ = writeln!(
+        out,
+        "Parse time: total {total:.2?}, mean {:.2?}, median {:.2?}, p95 {:.2?}, max {:.2?}",
+        total / u32::try_from(results.len()).unwrap_or(u32::MAX),
+        at(50),
+        at(95),
+        times[0].time
+    );
+    out.push_str("Slowest files:\n");
+    for result in times.iter().take(SLOWEST) {
+        let _ = writeln!(out, "{:>10.2?} {}", result.time, relative(&result.path));
+    }
+    out
+}
+
 /// # Panics
 /// When lexer fails or file issue
 pub fn lex(file: &PathBuf) {
@@ -347,3 +740,30 @@ pub async fn index_jdk(variant: IndexJdkOptions) {
         IndexJdkOptions::Jmod => test_load_jdk_jmod().await,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_at_position() {
+        let content = "package ch.emilycares;\npublic class Test {\n    int value = 1;\n}\n";
+        let tokens = ast::lexer::lex(content.as_bytes()).unwrap();
+        let ast = ast::parse_file(&tokens).unwrap();
+        let value = serde_json::to_value(&ast).unwrap();
+
+        let node = node_at(&value, (2, 9)).unwrap();
+        assert_eq!(node.get("value").and_then(Value::as_str), Some("value"));
+        assert_eq!(
+            node.get("range"),
+            Some(&serde_json::json!({
+                "start": { "line": 2, "col": 8 },
+                "end": { "line": 2, "col": 13 },
+            }))
+        );
+        let range = &node["range"];
+        assert!(range_contains(range, (2, 13)));
+        assert!(!range_contains(range, (2, 14)));
+        assert!(node_at(&value, (10, 0)).is_none());
+    }
+}
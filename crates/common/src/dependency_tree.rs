@@ -0,0 +1,80 @@
+//! Resolved dependency graph of a build tool
+//!
+//! Maven and gradle print the graph as a tree, the depth of a dependency is the width of the
+//! prefix of its line.
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyNode {
+    pub group_id: String,
+    pub artifact_id: String,
+    /// Requested version
+    pub version: String,
+    /// Scope of maven or configuration of gradle
+    pub scope: Option<String>,
+    pub optional: bool,
+    /// Version that won the conflict resolution when it is not the requested one
+    pub resolved_version: Option<String>,
+    /// Reason why the build tool left the dependency out, example:
+    /// `omitted for conflict with 2.0`
+    pub omitted: Option<String>,
+    pub children: Vec<Self>,
+}
+
+/// Tree of dependencies with their depth, a dependency is a child of the last dependency
+/// before it with a smaller depth
+#[must_use]
+pub fn build_tree(lines: impl IntoIterator<Item = (usize, DependencyNode)>) -> Vec<DependencyNode> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<(usize, DependencyNode)> = Vec::new();
+    for (depth, node) in lines {
+        while stack.last().is_some_and(|(d, _)| *d >= depth) {
+            pop(&mut stack, &mut roots);
+        }
+        stack.push((depth, node));
+    }
+    while !stack.is_empty() {
+        pop(&mut stack, &mut roots);
+    }
+    roots
+}
+
+fn pop(stack: &mut Vec<(usize, DependencyNode)>, roots: &mut Vec<DependencyNode>) {
+    let Some((_, node)) = stack.pop() else {
+        return;
+    };
+    match stack.last_mut() {
+        Some((_, parent)) => parent.children.push(node),
+        None => roots.push(node),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DependencyNode, build_tree};
+
+    #[test]
+    fn tree() {
+        let node = |artifact_id: &str| DependencyNode {
+            artifact_id: artifact_id.to_string(),
+            ..Default::default()
+        };
+        let tree = build_tree([
+            (0, node("app")),
+            (1, node("a")),
+            (2, node("b")),
+            (1, node("c")),
+            (0, node("lib")),
+        ]);
+        let names = |nodes: &[DependencyNode]| {
+            nodes
+                .iter()
+                .map(|n| n.artifact_id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&tree), vec!["app", "lib"]);
+        assert_eq!(names(&tree[0].children), vec!["a", "c"]);
+        assert_eq!(names(&tree[0].children[0].children), vec!["b"]);
+    }
+}
@@ -8,6 +8,34 @@ pub fn deps_get_source(deps_bas: &DepsBas) -> DepsSource {
     deps_bas.join("source")
 }
 
+/// Dependency and the path inside of its sources jar of a file in the source folder
+/// `deps/org/example/lib/1.0/source/org/example/A.java`
+#[must_use]
+pub fn deps_source_dependency(deps_path: &Path, file: &Path) -> Option<(Dependency, String)> {
+    let relative = file.strip_prefix(deps_path).ok()?;
+    let parts = relative
+        .components()
+        .map(|c| c.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    let source = parts.iter().position(|p| *p == "source")?;
+    if source < 3 {
+        return None;
+    }
+    let entry = parts[source + 1..].join("/");
+    if entry.is_empty() {
+        return None;
+    }
+    Some((
+        Dependency {
+            group_id: parts[..source - 2].join("."),
+            artivact_id: parts[source - 2].to_string(),
+            version: parts[source - 1].to_string(),
+            version_suffix: None,
+        },
+        entry,
+    ))
+}
+
 type DepsHash = PathBuf;
 #[must_use]
 pub fn deps_get_hash(deps_bas: &DepsBas, pom: &Dependency) -> DepsHash {
@@ -45,3 +73,30 @@ pub fn deps_base(pom: &Dependency, deps_path: &Path) -> DepsBas {
     p = p.join(&pom.artivact_id).join(&pom.version);
     p
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn source_dependency() {
+        let deps = PathBuf::from("/cache/deps");
+        let dep = Dependency {
+            group_id: "org.junit.jupiter".to_string(),
+            artivact_id: "junit-jupiter-api".to_string(),
+            version: "5.9.0".to_string(),
+            version_suffix: None,
+        };
+        let file = deps_get_source(&deps_base(&dep, &deps)).join("org/junit/jupiter/api/Test.java");
+        assert_eq!(
+            deps_source_dependency(&deps, &file),
+            Some((dep, "org/junit/jupiter/api/Test.java".to_string()))
+        );
+        assert_eq!(
+            deps_source_dependency(&deps, &PathBuf::from("/project/src/A.java")),
+            None
+        );
+    }
+}
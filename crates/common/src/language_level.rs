@@ -0,0 +1,47 @@
+//! Java release and preview features that the build tool compiles a project with
+use std::fmt::Display;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LanguageLevel {
+    /// Unknown when the build does not set it
+    pub release: Option<u32>,
+    /// `--enable-preview`
+    pub preview: bool,
+}
+
+impl Display for LanguageLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.release {
+            Some(release) if self.preview => write!(f, "Java {release} with preview features"),
+            Some(release) => write!(f, "Java {release}"),
+            None => write!(f, "the default Java release"),
+        }
+    }
+}
+
+/// Release of a version like `1.8`, `17`, `VERSION_17` or `JavaVersion.VERSION_1_8`
+#[must_use]
+pub fn parse_release(version: &str) -> Option<u32> {
+    let version = version.trim().trim_matches(['"', '\'']);
+    let version = version.rsplit_once("VERSION_").map_or(version, |(_, v)| v);
+    let version = version
+        .strip_prefix("1.")
+        .or_else(|| version.strip_prefix("1_"))
+        .unwrap_or(version);
+    version.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_release;
+
+    #[test]
+    fn releases() {
+        assert_eq!(parse_release("1.8"), Some(8));
+        assert_eq!(parse_release(" 17 "), Some(17));
+        assert_eq!(parse_release("'11'"), Some(11));
+        assert_eq!(parse_release("JavaVersion.VERSION_1_8"), Some(8));
+        assert_eq!(parse_release("VERSION_21"), Some(21));
+        assert_eq!(parse_release("${java.version}"), None);
+    }
+}
@@ -1,8 +1,11 @@
 #![deny(clippy::redundant_clone)]
 
 use std::{fs, path::PathBuf, sync::LazyLock};
+pub mod dependency_tree;
 pub mod deps;
+pub mod language_level;
 pub mod project_kind;
+pub mod timings;
 
 #[derive(Debug, Default, Clone)]
 pub struct TaskProgress {
@@ -12,6 +12,17 @@ pub enum ProjectKind {
         executable: String,
         path_build_gradle: PathBuf,
     },
+    /// Project with the `.classpath` of eclipse
+    Eclipse {
+        path_classpath: PathBuf,
+    },
+    Bazel {
+        executable: String,
+    },
+    /// Project of a build server, `path_connection` is its file in the `.bsp` folder
+    Bsp {
+        path_connection: PathBuf,
+    },
     Unknown,
 }
 
@@ -20,6 +31,7 @@ pub enum ProjectKindError {
     PathToString,
     MvnNotInPath,
     GradleNotInPath,
+    BazelNotInPath,
     ExecutableNotFound(std::io::Error),
     ExecutableNoMetadata(std::io::Error),
     NoPermissionToExecute(String),
@@ -31,6 +43,9 @@ impl Display for ProjectKind {
         match self {
             ProjectKind::Maven { .. } => write!(f, "maven"),
             ProjectKind::Gradle { .. } => write!(f, "gradle"),
+            ProjectKind::Eclipse { .. } => write!(f, "eclipse"),
+            ProjectKind::Bazel { .. } => write!(f, "bazel"),
+            ProjectKind::Bsp { .. } => write!(f, "bsp"),
             ProjectKind::Unknown => write!(f, "unknown"),
         }
     }
@@ -55,6 +70,26 @@ pub fn get_project_kind(
         return get_gradle_executable(build_gradle, path);
     }
 
+    // The root of a composite build may only have a settings script
+    for settings in ["settings.gradle", "settings.gradle.kts"] {
+        let settings = project_dir.join(settings);
+        if settings.exists() {
+            return get_gradle_executable(settings, path);
+        }
+    }
+
+    if ["MODULE.bazel", "WORKSPACE.bazel", "WORKSPACE"]
+        .iter()
+        .any(|f| project_dir.join(f).exists())
+    {
+        return get_bazel_executable(path);
+    }
+
+    let path_classpath = project_dir.join(".classpath");
+    if path_classpath.exists() {
+        return Ok(ProjectKind::Eclipse { path_classpath });
+    }
+
     Ok(ProjectKind::Unknown)
 }
 
@@ -132,6 +167,21 @@ fn get_gradle_executable(
     })
 }
 
+/// Bazelisk is preferred, it runs the bazel version of the workspace
+fn get_bazel_executable(path: &OsString) -> Result<ProjectKind, ProjectKindError> {
+    #[cfg(target_os = "windows")]
+    let names = ["bazelisk.exe", "bazel.exe"];
+    #[cfg(not(target_os = "windows"))]
+    let names = ["bazelisk", "bazel"];
+    let executable = names
+        .iter()
+        .find_map(|name| {
+            get_executable_from_path(name, ProjectKindError::BazelNotInPath, path).ok()
+        })
+        .ok_or(ProjectKindError::BazelNotInPath)?;
+    Ok(ProjectKind::Bazel { executable })
+}
+
 fn get_executable_from_path(
     executable: &str,
     e: ProjectKindError,
@@ -0,0 +1,99 @@
+//! Durations of recurring work, `java/status` reports their percentiles
+use std::{collections::VecDeque, sync::Mutex, time::Duration};
+
+use serde::Serialize;
+
+/// Durations that are kept of each kind, older ones are dropped
+const SAMPLES: usize = 1024;
+
+/// Lexing and parsing a source file
+pub static PARSE: Timings = Timings::new();
+/// Indexing the JDK or the classes of a project
+pub static INDEX: Timings = Timings::new();
+
+#[derive(Debug)]
+pub struct Timings {
+    samples: Mutex<VecDeque<Duration>>,
+}
+
+#[derive(Debug, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Percentiles {
+    pub count: usize,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+}
+
+impl Default for Timings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Timings {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            samples: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn record(&self, duration: Duration) {
+        let Ok(mut samples) = self.samples.lock() else {
+            return;
+        };
+        if samples.len() == SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(duration);
+    }
+
+    #[must_use]
+    pub fn percentiles(&self) -> Percentiles {
+        let Ok(samples) = self.samples.lock() else {
+            return Percentiles::default();
+        };
+        let mut sorted: Vec<Duration> = samples.iter().copied().collect();
+        drop(samples);
+        if sorted.is_empty() {
+            return Percentiles::default();
+        }
+        sorted.sort_unstable();
+        let at = |percentile: usize| {
+            sorted[(sorted.len() - 1) * percentile / 100].as_secs_f64() * 1000.0
+        };
+        Percentiles {
+            count: sorted.len(),
+            p50_ms: at(50),
+            p90_ms: at(90),
+            p99_ms: at(99),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Percentiles, SAMPLES, Timings};
+
+    #[test]
+    fn percentiles() {
+        let timings = Timings::new();
+        assert_eq!(timings.percentiles(), Percentiles::default());
+        for ms in (1..=100).rev() {
+            timings.record(Duration::from_millis(ms));
+        }
+        let percentiles = timings.percentiles();
+        assert_eq!(percentiles.count, 100);
+        assert_eq!(percentiles.p50_ms.round(), 50.0);
+        assert_eq!(percentiles.p90_ms.round(), 90.0);
+        assert_eq!(percentiles.p99_ms.round(), 99.0);
+        for _ in 0..SAMPLES {
+            timings.record(Duration::from_millis(1));
+        }
+        assert_eq!(timings.percentiles().count, SAMPLES);
+        assert_eq!(timings.percentiles().p99_ms.round(), 1.0);
+    }
+}
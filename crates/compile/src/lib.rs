@@ -3,7 +3,18 @@
 #![deny(clippy::nursery)]
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::too_many_lines)]
-use std::{process::Command, str::Utf8Error};
+use std::{path::Path, process::Command, str::Utf8Error};
+
+/// Separator of the entries of a classpath
+#[cfg(not(target_os = "windows"))]
+pub const CLASSPATH_SEPARATOR: char = ':';
+#[cfg(target_os = "windows")]
+pub const CLASSPATH_SEPARATOR: char = ';';
+
+#[cfg(not(target_os = "windows"))]
+const EXECUTABLE_JAVAC: &str = "javac";
+#[cfg(target_os = "windows")]
+const EXECUTABLE_JAVAC: &str = "javac.exe";
 
 #[derive(Debug)]
 pub enum CompileError {
@@ -19,18 +30,131 @@ pub struct CompileErrorMessage {
     pub col: usize,
 }
 
+/// `project` is the folder of the maven module, with a release javac checks against its API
 pub fn maven_compile_java_file(
     file_path: &str,
     classpath: &str,
     project: &str,
+    release: Option<u32>,
+) -> Result<Vec<CompileErrorMessage>, CompileError> {
+    classpath_compile_java_file(
+        file_path,
+        classpath,
+        project,
+        "target/classes",
+        release,
+        false,
+        None,
+    )
+}
+
+/// javac of the JDK home, the one on the PATH without a home
+fn javac(jdk: Option<&Path>) -> Command {
+    jdk.map_or_else(
+        || Command::new("javac"),
+        |home| Command::new(home.join("bin").join(EXECUTABLE_JAVAC)),
+    )
+}
+
+/// Preview features need the release
+fn release_args(command: &mut Command, release: Option<u32>, preview: bool) {
+    if let Some(release) = release {
+        command.arg("--release").arg(release.to_string());
+        if preview {
+            command.arg("--enable-preview");
+        }
+    }
+}
+
+/// Compile into the `output` folder of the project, it is relative to the project
+pub fn classpath_compile_java_file(
+    file_path: &str,
+    classpath: &str,
+    project: &str,
+    output: &str,
+    release: Option<u32>,
+    preview: bool,
+    jdk: Option<&Path>,
 ) -> Result<Vec<CompileErrorMessage>, CompileError> {
     // Compile the Java file using `javac` with the generated classpath
-    let out = Command::new("javac")
+    let mut command = javac(jdk);
+    command
         .current_dir(project)
         .arg("-cp")
         .arg(classpath)
         .arg("-d")
-        .arg("target/classes")
+        .arg(output);
+    release_args(&mut command, release, preview);
+    let out = command
+        .arg(file_path)
+        .output()
+        .map_err(CompileError::JavacIo)?;
+
+    let stdout = std::str::from_utf8(&out.stderr).map_err(CompileError::Utf8)?;
+    Ok(parse_compile_errors(stdout))
+}
+
+/// Compile a file of a java module, the entries of the `module_path` are modules. The other
+/// files of the module are looked up in the `source_root` of its `module-info.java`
+#[allow(clippy::too_many_arguments)]
+pub fn module_compile_java_file(
+    file_path: &str,
+    module_path: &str,
+    source_root: &str,
+    project: &str,
+    output: &str,
+    release: Option<u32>,
+    preview: bool,
+    jdk: Option<&Path>,
+) -> Result<Vec<CompileErrorMessage>, CompileError> {
+    let module_info = std::path::Path::new(source_root).join("module-info.java");
+    let mut command = javac(jdk);
+    command
+        .current_dir(project)
+        .arg("--module-path")
+        .arg(module_path)
+        .arg("-sourcepath")
+        .arg(source_root)
+        .arg("-d")
+        .arg(output);
+    release_args(&mut command, release, preview);
+    if !file_path.ends_with("module-info.java") {
+        command.arg(module_info);
+    }
+    let out = command
+        .arg(file_path)
+        .output()
+        .map_err(CompileError::JavacIo)?;
+
+    let stdout = std::str::from_utf8(&out.stderr).map_err(CompileError::Utf8)?;
+    Ok(parse_compile_errors(stdout))
+}
+
+/// Only run the annotation processors of the file, their sources go into the `generated` folder
+/// of the project. Without a `processor_path` the processors of the classpath run
+pub fn generate_sources(
+    file_path: &str,
+    classpath: &str,
+    processor_path: Option<&str>,
+    project: &str,
+    generated: &str,
+    jdk: Option<&Path>,
+) -> Result<Vec<CompileErrorMessage>, CompileError> {
+    std::fs::create_dir_all(std::path::Path::new(project).join(generated))
+        .map_err(CompileError::JavacIo)?;
+    let mut command = javac(jdk);
+    command
+        .current_dir(project)
+        .arg("-proc:only")
+        .arg("-implicit:none")
+        .arg("-cp")
+        .arg(classpath)
+        .arg("-s")
+        .arg(generated);
+    if let Some(processor_path) = processor_path {
+        command.arg("-processorpath").arg(processor_path);
+    }
+    let out = command
         .arg(file_path)
         .output()
         .map_err(CompileError::JavacIo)?;
@@ -97,9 +221,44 @@ pub fn parse_compile_errors(input: &str) -> Vec<CompileErrorMessage> {
     out
 }
 
+/// Errors of the maven compiler plugin, like `[ERROR] /p/App.java:[4,19] ';' expected`. Maven
+/// repeats them in the summary of the failed build, each error is kept once.
+#[must_use]
+pub fn parse_maven_compile_errors(input: &str) -> Vec<CompileErrorMessage> {
+    let mut out: Vec<CompileErrorMessage> = Vec::new();
+    for line in input.lines() {
+        let Some(line) = line.trim().strip_prefix("[ERROR] ") else {
+            continue;
+        };
+        let Some((path, rest)) = line.split_once(":[") else {
+            continue;
+        };
+        let Some((position, message)) = rest.split_once(']') else {
+            continue;
+        };
+        let Some((row, col)) = position.split_once(',') else {
+            continue;
+        };
+        let (Ok(row), Ok(col)) = (row.trim().parse::<usize>(), col.trim().parse::<usize>()) else {
+            continue;
+        };
+        // Maven counts the columns from one, javac marks them from zero
+        let error = CompileErrorMessage {
+            path: path.trim().to_string(),
+            message: message.trim().to_string(),
+            row,
+            col: col.saturating_sub(1),
+        };
+        if !out.contains(&error) {
+            out.push(error);
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::parse_compile_errors;
+    use crate::{parse_compile_errors, parse_maven_compile_errors};
     use expect_test::expect;
 
     #[test]
@@ -200,4 +359,30 @@ only showing the first 100 errors, of 115 total; use -Xmaxerrs if you would like
         "]];
         expected.assert_debug_eq(&out);
     }
+
+    #[test]
+    fn parse_maven_compile_errors_summary() {
+        let input = r"[INFO] -------------------------------------------------------------
+[ERROR] COMPILATION ERROR :
+[INFO] -------------------------------------------------------------
+[ERROR] /home/emily/tmp/app/src/main/java/org/example/App.java:[4,19] ';' expected
+[WARNING] /home/emily/tmp/app/src/main/java/org/example/Other.java:[7,5] deprecated
+[INFO] 1 error
+[ERROR] Failed to execute goal org.apache.maven.plugins:maven-compiler-plugin:3.13.0:compile (default-compile) on project app: Compilation failure
+[ERROR] /home/emily/tmp/app/src/main/java/org/example/App.java:[4,19] ';' expected
+[ERROR] -> [Help 1]
+";
+        let out = parse_maven_compile_errors(input);
+        let expected = expect![[r#"
+            [
+                CompileErrorMessage {
+                    path: "/home/emily/tmp/app/src/main/java/org/example/App.java",
+                    message: "';' expected",
+                    row: 4,
+                    col: 18,
+                },
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
 }
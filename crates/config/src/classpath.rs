@@ -0,0 +1,210 @@
+//! Classpath of a project without a build tool in `.java_lsp/classpath.toml` or `.jvmrc`
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::project::ProjectConfigError;
+
+pub const CLASSPATH_CONFIG: &str = ".java_lsp/classpath.toml";
+pub const JVMRC: &str = ".jvmrc";
+
+/// Paths are relative to the project root
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ClasspathConfig {
+    /// Source roots, the project root when empty
+    pub sources: Vec<PathBuf>,
+    /// Jar paths, `*` matches in a file name and `**` any number of folders
+    pub jars: Vec<String>,
+    /// Folder for the classes of javac, `out` when not set
+    pub output: Option<PathBuf>,
+    /// Folder for the sources of annotation processors, `generated` when not set
+    pub generated: Option<PathBuf>,
+    /// Java release of the sources
+    pub release: Option<u32>,
+    /// Preview features are enabled
+    pub preview: bool,
+}
+
+/// The `classpath.toml` or `.jvmrc` of the project
+#[must_use]
+pub fn classpath_config_path(root: &Path) -> Option<PathBuf> {
+    [CLASSPATH_CONFIG, JVMRC]
+        .iter()
+        .map(|f| root.join(f))
+        .find(|f| f.is_file())
+}
+
+impl ClasspathConfig {
+    /// None when the project has no classpath file
+    pub fn load(root: &Path) -> Result<Option<Self>, ProjectConfigError> {
+        let Some(path) = classpath_config_path(root) else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(path).map_err(ProjectConfigError::IO)?;
+        Self::parse(&content).map(Some)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, ProjectConfigError> {
+        toml::from_str(content).map_err(ProjectConfigError::Toml)
+    }
+
+    #[must_use]
+    pub fn source_dirs(&self, root: &Path) -> Vec<PathBuf> {
+        if self.sources.is_empty() {
+            return vec![root.to_path_buf()];
+        }
+        self.sources.iter().map(|s| root.join(s)).collect()
+    }
+
+    /// Jars that the patterns match, sorted and without duplicates
+    #[must_use]
+    pub fn jar_paths(&self, root: &Path) -> Vec<PathBuf> {
+        let mut out: Vec<PathBuf> = self
+            .jars
+            .iter()
+            .flat_map(|pattern| expand_glob(root, pattern))
+            .collect();
+        out.sort_unstable();
+        out.dedup();
+        out
+    }
+
+    #[must_use]
+    pub fn output_dir(&self, root: &Path) -> PathBuf {
+        root.join(self.output.as_deref().unwrap_or_else(|| Path::new("out")))
+    }
+
+    #[must_use]
+    pub fn generated_dir(&self, root: &Path) -> PathBuf {
+        root.join(
+            self.generated
+                .as_deref()
+                .unwrap_or_else(|| Path::new("generated")),
+        )
+    }
+}
+
+fn expand_glob(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let path = root.join(pattern);
+    let mut base = PathBuf::new();
+    let mut rest = vec![];
+    for component in &path {
+        let Some(part) = component.to_str() else {
+            return vec![];
+        };
+        if rest.is_empty() && !part.contains(['*', '?']) {
+            base.push(component);
+        } else {
+            rest.push(part);
+        }
+    }
+    if rest.is_empty() {
+        return if base.is_file() { vec![base] } else { vec![] };
+    }
+    let mut out = vec![];
+    walk(&base, &rest, &mut out);
+    out
+}
+
+fn walk(dir: &Path, parts: &[&str], out: &mut Vec<PathBuf>) {
+    let Some((first, rest)) = parts.split_first() else {
+        if dir.is_file() {
+            out.push(dir.to_path_buf());
+        }
+        return;
+    };
+    if *first == "**" {
+        // Zero folders or one more folder
+        walk(dir, rest, out);
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                walk(&entry.path(), parts, out);
+            }
+        }
+        return;
+    }
+    if !first.contains(['*', '?']) {
+        walk(&dir.join(first), rest, out);
+        return;
+    }
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        if let Some(name) = entry.file_name().to_str()
+            && wildcard_match(first.as_bytes(), name.as_bytes())
+        {
+            walk(&entry.path(), rest, out);
+        }
+    }
+}
+
+/// `*` matches any characters and `?` one character
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some((b'*', p)), _) => {
+            wildcard_match(p, name) || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        }
+        (Some((b'?', p)), Some((_, n))) => wildcard_match(p, n),
+        (Some((a, p)), Some((b, n))) if a == b => wildcard_match(p, n),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{ClasspathConfig, wildcard_match};
+
+    #[test]
+    fn parse_and_expand() {
+        let dir = std::env::temp_dir().join(format!("classpath-config-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for file in [
+            "lib/a.jar",
+            "lib/b.jar",
+            "lib/notes.txt",
+            "vendor/x/c.jar",
+            "vendor/x/y/d.jar",
+        ] {
+            let file = dir.join(file);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, "").unwrap();
+        }
+        let config = ClasspathConfig::parse(
+            r#"
+sources = ["src"]
+jars = ["lib/*.jar", "vendor/**/*.jar", "lib/a.jar", "missing.jar"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.source_dirs(&dir), vec![dir.join("src")]);
+        assert_eq!(config.output_dir(&dir), dir.join("out"));
+        assert_eq!(config.generated_dir(&dir), dir.join("generated"));
+        assert_eq!(
+            config.jar_paths(&dir),
+            vec![
+                dir.join("lib/a.jar"),
+                dir.join("lib/b.jar"),
+                dir.join("vendor/x/c.jar"),
+                dir.join("vendor/x/y/d.jar"),
+            ]
+        );
+        assert_eq!(
+            ClasspathConfig::default().source_dirs(&dir),
+            vec![dir.clone()]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_match(b"*.jar", b"a.jar"));
+        assert!(wildcard_match(b"guava-?.jar", b"guava-3.jar"));
+        assert!(!wildcard_match(b"*.jar", b"a.jar.txt"));
+        assert!(wildcard_match(b"*", b""));
+    }
+}
@@ -1,7 +1,121 @@
+use std::{collections::HashMap, ffi::OsString, path::PathBuf};
+
+pub mod classpath;
+pub mod project;
+
+/// Annotations of dependency injection, serialization and test frameworks
+pub const REFLECTION_ANNOTATIONS: [&str; 16] = [
+    "Autowired",
+    "Inject",
+    "Resource",
+    "Value",
+    "PersistenceContext",
+    "EJB",
+    "Mock",
+    "Spy",
+    "InjectMocks",
+    "Captor",
+    "FXML",
+    "JsonProperty",
+    "SerializedName",
+    "Id",
+    "Column",
+    "EventListener",
+];
+
+/// Annotations of frameworks that call the classes and methods, the dead code report skips them
+pub const ENTRY_POINT_ANNOTATIONS: [&str; 12] = [
+    "Override",
+    "Bean",
+    "Component",
+    "Service",
+    "Repository",
+    "Controller",
+    "RestController",
+    "Configuration",
+    "SpringBootApplication",
+    "PostConstruct",
+    "PreDestroy",
+    "Scheduled",
+];
+
+/// Annotations of JSR-305, JetBrains and the Checker Framework for values that can be null
+pub const NULLABLE_ANNOTATIONS: [&str; 2] = ["Nullable", "CheckForNull"];
+
+/// Annotations of JSR-305, JetBrains and the Checker Framework for values that are never null
+pub const NOT_NULL_ANNOTATIONS: [&str; 3] = ["NotNull", "Nonnull", "NonNull"];
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Configuration {
     pub formatter: FormatterConfig,
+    /// Column at which the internal formatter wraps long lines
+    pub format_line_width: usize,
     pub editor_runs_commands: bool,
+    /// Run javac with the project classpath on save and report its errors
+    pub javac_diagnostics: bool,
+    /// Annotations that mark members as used by reflection
+    pub reflection_annotations: Vec<String>,
+    /// Annotations that mark classes and members as entry points of the dead code report
+    pub entry_point_annotations: Vec<String>,
+    /// Annotations that mark parameters, fields and return values that can be null
+    pub nullable_annotations: Vec<String>,
+    /// Annotations that mark parameters, fields and return values that are never null
+    pub not_null_annotations: Vec<String>,
+    /// Levels of diagnostic codes that differ from their default severity
+    pub diagnostic_levels: HashMap<String, DiagnosticLevel>,
+    /// Import groups of the internal formatter when `java_lsp.toml` does not set them
+    pub import_order: Vec<String>,
+    /// Home of the JDK to index instead of the java found on the PATH
+    pub jdk_path: Option<PathBuf>,
+    /// Release of the platform classes, read from `lib/ct.sym` of the JDK
+    pub java_release: Option<u32>,
+    /// Folders whose java files are not indexed
+    pub excluded_dirs: Vec<PathBuf>,
+    /// Show the inferred type of `var` declarations
+    pub inlay_hints: bool,
+    /// Show the inferred type of lambda parameters that are declared without a type
+    pub lambda_parameter_hints: bool,
+    /// References of a method include the methods that override it and the calls through the
+    /// supertypes that declare it
+    pub references_include_overrides: bool,
+    /// Memory budget in bytes for documents read from disk and cached token lists
+    pub cache_memory: usize,
+    /// Report the status of the server every minute as progress, read at initialization
+    pub status_updates: bool,
+    /// Threads that parse the java files of the workspace, 0 uses all cores
+    pub parse_threads: usize,
+    /// Download dependencies and their sources that are missing in the local repository
+    pub download_dependencies: bool,
+    /// Console launcher of the JUnit platform, the newest one of the local maven repository is
+    /// used when it is not set
+    pub junit_launcher: Option<PathBuf>,
+    /// The client shows `jdt` uris of sources in the cache folder with `java/classContents`
+    pub class_file_contents: bool,
+    /// PMD on the saved sources
+    pub pmd: AnalyzerConfig,
+    /// `SpotBugs` on the classes that javac compiled of the saved file
+    pub spotbugs: AnalyzerConfig,
+}
+
+/// External analyzer, example: `{ "pmd": { "enabled": true, "rules": ["category/java/bestpractices.xml"] } }`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AnalyzerConfig {
+    pub enabled: bool,
+    /// Path of the executable, the name of the tool is looked up on the PATH when it is not set
+    pub executable: Option<PathBuf>,
+    /// Rulesets of PMD, filter files of `SpotBugs` that include the reported bugs
+    pub rules: Vec<String>,
+}
+
+impl AnalyzerConfig {
+    #[must_use]
+    pub fn disabled(rules: &[&str]) -> Self {
+        Self {
+            enabled: false,
+            executable: None,
+            rules: rules.iter().map(ToString::to_string).collect(),
+        }
+    }
 }
 
 impl Configuration {
@@ -11,6 +125,40 @@ impl Configuration {
             field
         )
     }
+
+    /// PATH used to find the JDK, the bin folder of `jdk_path` comes first
+    #[must_use]
+    pub fn java_path(&self, path: OsString) -> OsString {
+        let Some(jdk_path) = &self.jdk_path else {
+            return path;
+        };
+        let paths = std::iter::once(jdk_path.join("bin")).chain(std::env::split_paths(&path));
+        std::env::join_paths(paths).unwrap_or(path)
+    }
+}
+
+/// Level of a diagnostic code, example: `{ "diagnostics": { "unused": "off" } }`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DiagnosticLevel {
+    Off,
+    Hint,
+    Info,
+    Warning,
+    Error,
+}
+
+impl DiagnosticLevel {
+    #[must_use]
+    pub fn parse(level: &str) -> Option<Self> {
+        match level.to_lowercase().as_str() {
+            "off" | "none" => Some(Self::Off),
+            "hint" => Some(Self::Hint),
+            "info" | "information" => Some(Self::Info),
+            "warning" | "warn" => Some(Self::Warning),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -25,7 +173,41 @@ impl Default for Configuration {
     fn default() -> Self {
         Self {
             formatter: FormatterConfig::None,
+            format_line_width: 100,
             editor_runs_commands: false,
+            javac_diagnostics: false,
+            reflection_annotations: REFLECTION_ANNOTATIONS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            entry_point_annotations: ENTRY_POINT_ANNOTATIONS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            nullable_annotations: NULLABLE_ANNOTATIONS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            not_null_annotations: NOT_NULL_ANNOTATIONS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            diagnostic_levels: HashMap::new(),
+            import_order: Vec::new(),
+            jdk_path: None,
+            java_release: None,
+            excluded_dirs: Vec::new(),
+            inlay_hints: true,
+            lambda_parameter_hints: true,
+            references_include_overrides: false,
+            cache_memory: 512 * 1024 * 1024,
+            status_updates: false,
+            parse_threads: 0,
+            download_dependencies: true,
+            junit_launcher: None,
+            class_file_contents: false,
+            pmd: AnalyzerConfig::disabled(&["rulesets/java/quickstart.xml"]),
+            spotbugs: AnalyzerConfig::disabled(&[]),
         }
     }
 }
@@ -0,0 +1,386 @@
+//! Settings of a `java_lsp.toml` in the workspace root
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::Deserialize;
+
+use crate::{Configuration, DiagnosticLevel, FormatterConfig};
+
+pub const PROJECT_CONFIG: &str = "java_lsp.toml";
+
+#[derive(Debug)]
+pub enum ProjectConfigError {
+    IO(std::io::Error),
+    Toml(toml::de::Error),
+    Xml(serde_xml_rs::Error),
+}
+
+/// Settings of a `java_lsp.toml`, example:
+///
+/// ```toml
+/// [format]
+/// style = "internal"
+/// line_width = 120
+/// indent_size = 4
+/// eclipse_profile = "eclipse-formatter.xml"
+///
+/// [imports]
+/// order = ["java", "javax", "", "static"]
+///
+/// [members]
+/// order = ["fields", "constructors", "methods", "types"]
+///
+/// [processors]
+/// run = true
+/// path = ["tools/mapstruct-processor.jar"]
+///
+/// [diagnostics]
+/// javac = true
+/// levels = { unused = "off", unreachable = "warning" }
+///
+/// [jdk]
+/// path = "/usr/lib/jvm/java-21"
+/// release = 17
+///
+/// [sources]
+/// exclude = ["legacy", "build/generated"]
+/// ```
+///
+/// The file of the workspace root is merged under the settings of the client, see
+/// [`Self::apply`]. The formatter of a project uses the file of its own folder.
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ProjectConfig {
+    pub format: ProjectFormat,
+    pub imports: ProjectImports,
+    pub members: ProjectMembers,
+    pub processors: ProjectProcessors,
+    pub diagnostics: ProjectDiagnostics,
+    pub jdk: ProjectJdk,
+    pub sources: ProjectSources,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ProjectFormat {
+    /// One of none, internal, google and idea
+    pub style: Option<String>,
+    pub line_width: Option<usize>,
+    pub indent_size: Option<usize>,
+    pub indent_tabs: Option<bool>,
+    /// Eclipse formatter profile relative to the workspace root, its settings apply when they
+    /// are not set in this file
+    pub eclipse_profile: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ProjectImports {
+    /// Import prefixes, each is a group separated by a blank line. An empty prefix matches all
+    /// other imports and `static` matches static imports.
+    pub order: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ProjectMembers {
+    /// Members of a class are sorted by their kind in this order
+    pub order: Vec<MemberKind>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ProjectProcessors {
+    /// Run the annotation processors with javac when a file is saved
+    pub run: bool,
+    /// Processor path relative to the workspace root, the processors of the classpath are used
+    /// when it is empty
+    pub path: Vec<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ProjectDiagnostics {
+    /// Run javac on save
+    pub javac: Option<bool>,
+    /// Levels by diagnostic code like the `diagnostics` setting
+    pub levels: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ProjectJdk {
+    /// Home of the JDK, relative to the workspace root or absolute
+    pub path: Option<PathBuf>,
+    pub release: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ProjectSources {
+    /// Folders relative to the workspace root whose java files are not indexed
+    pub exclude: Vec<PathBuf>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum MemberKind {
+    Fields,
+    StaticInitializers,
+    Initializers,
+    Constructors,
+    Methods,
+    Types,
+}
+
+impl ProjectConfig {
+    pub fn load(root: &Path) -> Result<Self, ProjectConfigError> {
+        let content =
+            fs::read_to_string(root.join(PROJECT_CONFIG)).map_err(ProjectConfigError::IO)?;
+        let mut config = Self::parse(&content)?;
+        if let Some(profile) = &config.format.eclipse_profile {
+            let content = fs::read_to_string(root.join(profile)).map_err(ProjectConfigError::IO)?;
+            config.format.fill_eclipse(&content)?;
+        }
+        Ok(config)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, ProjectConfigError> {
+        toml::from_str(content).map_err(ProjectConfigError::Toml)
+    }
+
+    /// Set the settings of the file in the configuration, the client settings are applied
+    /// afterwards
+    pub fn apply(&self, root: &Path, config: &mut Configuration) {
+        if let Some(formatter) = self.formatter() {
+            config.formatter = formatter;
+        }
+        if let Some(width) = self.format.line_width {
+            config.format_line_width = width;
+        }
+        if !self.imports.order.is_empty() {
+            config.import_order.clone_from(&self.imports.order);
+        }
+        if let Some(javac) = self.diagnostics.javac {
+            config.javac_diagnostics = javac;
+        }
+        for (code, level) in &self.diagnostics.levels {
+            match DiagnosticLevel::parse(level) {
+                Some(level) => {
+                    config.diagnostic_levels.insert(code.clone(), level);
+                }
+                None => {
+                    eprintln!("Unknown level {level} for diagnostic {code} in {PROJECT_CONFIG}")
+                }
+            }
+        }
+        if let Some(path) = &self.jdk.path {
+            config.jdk_path = Some(root.join(path));
+        }
+        if let Some(release) = self.jdk.release {
+            config.java_release = Some(release);
+        }
+        config.excluded_dirs = self.sources.exclude.iter().map(|e| root.join(e)).collect();
+    }
+
+    #[must_use]
+    pub fn formatter(&self) -> Option<FormatterConfig> {
+        match self.format.style.as_deref()?.to_lowercase().as_str() {
+            "none" => Some(FormatterConfig::None),
+            "internal" => Some(FormatterConfig::Internal),
+            "google" => Some(FormatterConfig::Google),
+            "idea" => Some(FormatterConfig::Idea),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EclipseProfiles {
+    #[serde(default)]
+    profile: Vec<EclipseProfile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EclipseProfile {
+    #[serde(default)]
+    setting: Vec<EclipseSetting>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EclipseSetting {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(rename = "@value")]
+    value: String,
+}
+
+impl ProjectFormat {
+    /// Take the line width and indentation of the first profile in an Eclipse formatter export
+    pub fn fill_eclipse(&mut self, content: &str) -> Result<(), ProjectConfigError> {
+        let profiles: EclipseProfiles =
+            serde_xml_rs::from_str(content).map_err(ProjectConfigError::Xml)?;
+        let Some(profile) = profiles.profile.first() else {
+            return Ok(());
+        };
+        for setting in &profile.setting {
+            let Some(id) = setting.id.strip_prefix("org.eclipse.jdt.core.formatter.") else {
+                continue;
+            };
+            match id {
+                "lineSplit" => {
+                    self.line_width = self.line_width.or_else(|| setting.value.parse().ok());
+                }
+                "tabulation.char" => {
+                    self.indent_tabs = self.indent_tabs.or(Some(setting.value == "tab"));
+                }
+                "indentation.size" => {
+                    self.indent_size = self.indent_size.or_else(|| setting.value.parse().ok());
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Loaded `java_lsp.toml` files by workspace root, reloaded when they change on disk
+#[derive(Debug, Default)]
+pub struct ProjectConfigs {
+    loaded: HashMap<PathBuf, (Vec<Option<SystemTime>>, ProjectConfig)>,
+}
+
+impl ProjectConfigs {
+    pub fn get(&mut self, root: &Path) -> ProjectConfig {
+        if let Some((loaded, config)) = self.loaded.get(root)
+            && *loaded == stamp(root, config)
+        {
+            return config.clone();
+        }
+        let config = match ProjectConfig::load(root) {
+            Ok(config) => config,
+            Err(ProjectConfigError::IO(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                ProjectConfig::default()
+            }
+            Err(e) => {
+                eprintln!("Unable to load {PROJECT_CONFIG}: {e:?}");
+                ProjectConfig::default()
+            }
+        };
+        self.loaded
+            .insert(root.to_path_buf(), (stamp(root, &config), config.clone()));
+        config
+    }
+}
+
+/// Modification times of the config and its Eclipse profile
+fn stamp(root: &Path, config: &ProjectConfig) -> Vec<Option<SystemTime>> {
+    let mut files = vec![root.join(PROJECT_CONFIG)];
+    if let Some(profile) = &config.format.eclipse_profile {
+        files.push(root.join(profile));
+    }
+    files
+        .iter()
+        .map(|f| fs::metadata(f).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let content = r#"
+[format]
+style = "internal"
+line_width = 120
+
+[imports]
+order = ["java", "javax", "", "static"]
+
+[members]
+order = ["fields", "static_initializers", "constructors", "methods"]
+
+[processors]
+run = true
+path = ["tools/processor.jar"]
+"#;
+        let config = ProjectConfig::parse(content).unwrap();
+        assert_eq!(config.formatter(), Some(FormatterConfig::Internal));
+        assert_eq!(config.format.line_width, Some(120));
+        assert_eq!(config.imports.order, ["java", "javax", "", "static"]);
+        assert_eq!(
+            config.members.order,
+            [
+                MemberKind::Fields,
+                MemberKind::StaticInitializers,
+                MemberKind::Constructors,
+                MemberKind::Methods
+            ]
+        );
+        assert!(config.processors.run);
+        assert_eq!(
+            config.processors.path,
+            [PathBuf::from("tools/processor.jar")]
+        );
+    }
+
+    #[test]
+    fn apply() {
+        let content = r#"
+[format]
+style = "google"
+
+[diagnostics]
+javac = true
+levels = { unused = "off", unknown = "loud" }
+
+[jdk]
+path = "jdks/21"
+release = 17
+
+[sources]
+exclude = ["legacy"]
+"#;
+        let root = Path::new("/workspace");
+        let mut config = Configuration::default();
+        ProjectConfig::parse(content)
+            .unwrap()
+            .apply(root, &mut config);
+        assert_eq!(config.formatter, FormatterConfig::Google);
+        assert!(config.javac_diagnostics);
+        assert_eq!(
+            config.diagnostic_levels,
+            HashMap::from([("unused".to_string(), DiagnosticLevel::Off)])
+        );
+        assert_eq!(config.jdk_path, Some(PathBuf::from("/workspace/jdks/21")));
+        assert_eq!(config.java_release, Some(17));
+        assert_eq!(config.excluded_dirs, [PathBuf::from("/workspace/legacy")]);
+    }
+
+    #[test]
+    fn eclipse_profile() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<profiles version="21">
+<profile kind="CodeFormatterProfile" name="Company" version="21">
+<setting id="org.eclipse.jdt.core.formatter.lineSplit" value="140"/>
+<setting id="org.eclipse.jdt.core.formatter.tabulation.char" value="tab"/>
+<setting id="org.eclipse.jdt.core.formatter.indentation.size" value="4"/>
+</profile>
+</profiles>
+"#;
+        let mut format = ProjectFormat {
+            line_width: Some(100),
+            ..Default::default()
+        };
+        format.fill_eclipse(content).unwrap();
+        assert_eq!(format.line_width, Some(100));
+        assert_eq!(format.indent_tabs, Some(true));
+        assert_eq!(format.indent_size, Some(4));
+    }
+}
@@ -0,0 +1,137 @@
+//! Memory budget of the document map
+use std::{
+    collections::HashMap,
+    mem::size_of,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use ast::lexer::PositionToken;
+use my_string::MyString;
+
+use crate::Document;
+
+/// Rough size of the syntax tree per byte of source
+const AST_BYTES_PER_SOURCE_BYTE: usize = 6;
+
+static TICK: AtomicU64 = AtomicU64::new(0);
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// A source was found in the document map
+pub(crate) fn hit() {
+    HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A source had to be read from disk
+pub(crate) fn miss() {
+    MISSES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Hits and misses of the document map when reading sources
+#[must_use]
+pub fn hits_and_misses() -> (u64, u64) {
+    (HITS.load(Ordering::Relaxed), MISSES.load(Ordering::Relaxed))
+}
+
+impl Document {
+    /// Mark the document as used now
+    pub fn touch(&mut self) {
+        self.last_used = TICK.fetch_add(1, Ordering::Relaxed) + 1;
+    }
+
+    /// Estimated memory used by the text, tokens and syntax tree
+    #[must_use]
+    pub fn memory_size(&self) -> usize {
+        let text = self.rope.len_bytes();
+        text + text * AST_BYTES_PER_SOURCE_BYTE + self.tokens_size()
+    }
+
+    const fn tokens_size(&self) -> usize {
+        self.tokens.capacity() * size_of::<PositionToken>()
+    }
+}
+
+/// Drop documents until the estimated size is within the budget, returns the freed bytes
+pub fn evict(document_map: &Arc<RwLock<HashMap<MyString, Document>>>, budget: usize) -> usize {
+    let Ok(mut dm) = document_map.write() else {
+        return 0;
+    };
+    let total: usize = dm.values().map(Document::memory_size).sum();
+    if total <= budget {
+        return 0;
+    }
+    let mut used = total;
+    let mut closed: Vec<(u64, MyString)> = dm
+        .iter()
+        .filter(|(_, d)| !d.opened)
+        .map(|(k, d)| (d.last_used, k.clone()))
+        .collect();
+    closed.sort_unstable();
+    for (_, key) in closed {
+        if used <= budget {
+            break;
+        }
+        if let Some(document) = dm.remove(&key) {
+            used = used.saturating_sub(document.memory_size());
+        }
+    }
+    if used > budget {
+        let mut open: Vec<(u64, MyString)> = dm
+            .iter()
+            .filter(|(_, d)| d.opened && !d.tokens.is_empty())
+            .map(|(k, d)| (d.last_used, k.clone()))
+            .collect();
+        open.sort_unstable();
+        for (_, key) in open {
+            if used <= budget {
+                break;
+            }
+            if let Some(document) = dm.get_mut(&key) {
+                used = used.saturating_sub(document.tokens_size());
+                document.tokens = Vec::new();
+            }
+        }
+    }
+    total - used
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use my_string::smol_str::ToSmolStr;
+
+    use super::*;
+
+    #[test]
+    fn evict_closed_first() {
+        let content = "package a;\npublic class A {\n    int a;\n}\n";
+        let document_map = Arc::new(RwLock::new(HashMap::new()));
+        let mut size = 0;
+        for (name, opened) in [("Open", true), ("Old", false), ("New", false)] {
+            let mut document = Document::setup(content, PathBuf::from(name)).unwrap();
+            document.opened = opened;
+            document.touch();
+            size = document.memory_size();
+            document_map
+                .write()
+                .unwrap()
+                .insert(name.to_smolstr(), document);
+        }
+        assert_eq!(evict(&document_map, size * 3), 0);
+        assert!(evict(&document_map, size * 2) > 0);
+        let dm = document_map.read().unwrap();
+        assert!(dm.contains_key("Open"));
+        assert!(!dm.contains_key("Old"));
+        assert!(dm.contains_key("New"));
+        drop(dm);
+        evict(&document_map, 0);
+        let dm = document_map.read().unwrap();
+        assert_eq!(dm.keys().collect::<Vec<_>>(), ["Open"]);
+        assert!(dm["Open"].tokens.is_empty());
+        drop(dm);
+    }
+}
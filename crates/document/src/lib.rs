@@ -5,29 +5,46 @@
 #![allow(clippy::too_many_lines)]
 #![allow(clippy::unnecessary_wraps)]
 #![allow(clippy::implicit_hasher)]
+pub mod cache;
+pub mod source_set;
+
 use std::{
     collections::HashMap,
     fs,
     path::PathBuf,
     sync::{Arc, RwLock},
+    time::Instant,
 };
 
 use ast::{
     error::PrintErr,
-    types::{AstFile, AstThing, AstTopLevel},
+    lexer::{LexEdit, PositionToken},
+    types::{AstFile, AstPoint, AstRange, AstThing, AstTopLevel},
 };
-use lsp_types::{Diagnostic, TextDocumentContentChangeEvent};
+use common::timings;
+use lsp_types::{Diagnostic, Position, TextDocumentContentChangeEvent};
 use my_string::{
     MyString,
     smol_str::{ToSmolStr, format_smolstr},
 };
 use ropey::Rope;
+use source_set::SourceSet;
 
 #[derive(Debug, Clone)]
 pub struct Document {
     pub rope: Rope,
     pub ast: AstFile,
     pub path: PathBuf,
+    /// Main or test source, see [`SourceSet::of`]
+    pub source_set: SourceSet,
+    /// Tokens of the current text, edits only lex the changed region again
+    pub tokens: Vec<PositionToken>,
+    /// Version of the text from the client, files read from disk have version 0
+    pub version: i32,
+    /// Open in the client, documents read from disk are evicted first
+    pub opened: bool,
+    /// Tick of the last use, see [`cache::evict`]
+    pub last_used: u64,
 }
 
 #[derive(Debug)]
@@ -58,7 +75,12 @@ impl Document {
         let mut o = Self {
             rope,
             ast: AstFile { top: Vec::new() },
+            source_set: SourceSet::of(&path),
             path,
+            tokens: Vec::new(),
+            version: 0,
+            opened: false,
+            last_used: 0,
         };
 
         o.reparse(text.as_bytes())?;
@@ -69,7 +91,12 @@ impl Document {
         let mut o = Self {
             rope,
             ast: AstFile { top: Vec::new() },
+            source_set: SourceSet::of(&path),
             path,
+            tokens: Vec::new(),
+            version: 0,
+            opened: false,
+            last_used: 0,
         };
 
         o.reparse(text.as_bytes())?;
@@ -82,14 +109,21 @@ impl Document {
         text: &str,
         path: PathBuf,
         key: &MyString,
+        version: i32,
         document_map: &Arc<RwLock<HashMap<MyString, Self>>>,
     ) -> Result<(), DocumentError> {
         let rope = Rope::from_str(text);
         let mut o = Self {
             rope,
             ast: AstFile { top: Vec::new() },
+            source_set: SourceSet::of(&path),
             path,
+            tokens: Vec::new(),
+            version,
+            opened: true,
+            last_used: 0,
         };
+        o.touch();
 
         match o.reparse(text.as_bytes()) {
             Ok(()) => {
@@ -113,44 +147,93 @@ impl Document {
         &mut self,
         changes: &[TextDocumentContentChangeEvent],
     ) -> Result<(), DocumentError> {
+        let mut edit = None;
         for change in changes {
-            if let Some(range) = change.range {
-                let sp = range.start;
-                let ep = range.end;
-
-                // Get the start/end char indices of the line.
-                let start_idx = self
-                    .rope
-                    .line_to_char(sp.line.try_into().unwrap_or_default())
-                    + TryInto::<usize>::try_into(sp.character).unwrap_or_default();
-                let end_idx = self
-                    .rope
-                    .line_to_char(ep.line.try_into().unwrap_or_default())
-                    + TryInto::<usize>::try_into(ep.character).unwrap_or_default();
-
-                let do_insert = !change.text.is_empty();
-
-                if start_idx < end_idx {
-                    self.rope.remove(start_idx..end_idx);
-                    if do_insert {
-                        self.rope.insert(start_idx, &change.text);
-                    }
-                } else {
-                    self.rope.remove(end_idx..start_idx);
-                    if do_insert {
-                        self.rope.insert(end_idx, &change.text);
-                    }
-                }
-
+            let Some(range) = change.range else {
+                self.rope = Rope::from_str(&change.text);
+                edit = None;
                 continue;
+            };
+            let start_idx = self.position_to_char(range.start);
+            let end_idx = self.position_to_char(range.end).max(start_idx);
+            let start = self.char_to_point(start_idx);
+            let old_end = self.char_to_point(end_idx);
+            self.rope.remove(start_idx..end_idx);
+            self.rope.insert(start_idx, &change.text);
+            let new_end = self.char_to_point(start_idx + change.text.chars().count());
+            edit = Some(LexEdit {
+                start,
+                old_end,
+                new_end,
+            });
+        }
+        let text = self.rope.to_string();
+        let lexed = match edit {
+            // Only a single edit can be applied to the old tokens
+            Some(edit) if changes.len() == 1 => {
+                ast::lexer::relex(text.as_bytes(), &mut self.tokens, &edit)
             }
+            _ => ast::lexer::lex_mut::<false>(text.as_bytes(), &mut self.tokens),
+        };
+        if let Err(e) = lexed {
+            self.tokens.clear();
+            return Err(DocumentError::Diagnostic(Box::new(
+                lsp_extra::lexer_error_to_diagnostic(&e),
+            )));
+        }
+        self.parse_tokens(&text)
+    }
 
-            if change.range.is_none() && change.range_length.is_none() {
-                self.rope = Rope::from_str(&change.text);
-            }
+    /// Char index in the rope of a lsp position, the character is counted in UTF-16 code units
+    #[must_use]
+    pub fn position_to_char(&self, position: Position) -> usize {
+        let line = usize::try_from(position.line).unwrap_or(usize::MAX);
+        if line >= self.rope.len_lines() {
+            return self.rope.len_chars();
+        }
+        let line_start = self.rope.line_to_char(line);
+        let line_end = line_start + self.rope.line(line).len_chars();
+        let character = usize::try_from(position.character).unwrap_or(usize::MAX);
+        let utf16 = self
+            .rope
+            .char_to_utf16_cu(line_start)
+            .saturating_add(character);
+        let utf16 = utf16.min(self.rope.char_to_utf16_cu(line_end));
+        self.rope.utf16_cu_to_char(utf16).min(line_end)
+    }
+
+    /// Lexer position of a char index, the column is counted in bytes
+    fn char_to_point(&self, char_idx: usize) -> AstPoint {
+        let line = self.rope.char_to_line(char_idx);
+        AstPoint {
+            line,
+            col: self.rope.char_to_byte(char_idx) - self.rope.line_to_byte(line),
+        }
+    }
+    /// Source text covered by the ast range
+    #[must_use]
+    pub fn range_text(&self, range: &AstRange) -> String {
+        let start = self.point_to_char(&range.start);
+        let end = self.point_to_char(&range.end);
+        if start >= end {
+            return String::new();
         }
-        self.reparse(self.rope.to_string().as_bytes())
+        self.rope.slice(start..end).to_string()
     }
+
+    /// Char index in the rope of the ast point
+    #[must_use]
+    pub fn point_to_char(&self, point: &AstPoint) -> usize {
+        let lines = self.rope.len_lines();
+        if point.line >= lines {
+            return self.rope.len_chars();
+        }
+        // Columns of the lexer are bytes
+        let line_start = self.rope.line_to_byte(point.line);
+        let line_len = self.rope.line(point.line).len_bytes();
+        self.rope.byte_to_char(line_start + point.col.min(line_len))
+    }
+
     pub fn reparse_no_change(&self) -> Result<(), DocumentError> {
         let binding = self.rope.to_string();
         let bytes = binding.as_bytes();
@@ -159,9 +242,9 @@ impl Document {
                 let ast = ast::parse_file(&tokens);
                 if let Err(e) = ast {
                     e.print_err(&binding, &tokens);
-                    if let Ok(diag) = lsp_extra::ast_error_to_diagnostic(&e, &tokens) {
-                        return Err(DocumentError::Diagnostic(Box::new(diag)));
-                    }
+                    return Err(DocumentError::Diagnostic(Box::new(
+                        lsp_extra::ast_error_to_diagnostic(&e, &tokens),
+                    )));
                 }
             }
             Err(e) => {
@@ -174,29 +257,31 @@ impl Document {
         Ok(())
     }
     fn reparse(&mut self, bytes: &[u8]) -> Result<(), DocumentError> {
-        match ast::lexer::lex(bytes) {
-            Ok(tokens) => {
-                let ast = ast::parse_file(&tokens);
-                match ast {
-                    Ok(ast) => {
-                        self.ast = ast;
-                    }
-                    Err(e) => {
-                        e.print_err(&self.rope.to_string(), &tokens);
-                        if let Ok(diag) = lsp_extra::ast_error_to_diagnostic(&e, &tokens) {
-                            return Err(DocumentError::Diagnostic(Box::new(diag)));
-                        }
-                    }
-                }
+        if let Err(e) = ast::lexer::lex_mut::<false>(bytes, &mut self.tokens) {
+            self.tokens.clear();
+            return Err(DocumentError::Diagnostic(Box::new(
+                lsp_extra::lexer_error_to_diagnostic(&e),
+            )));
+        }
+        self.parse_tokens(&String::from_utf8_lossy(bytes))
+    }
+
+    fn parse_tokens(&mut self, text: &str) -> Result<(), DocumentError> {
+        let started = Instant::now();
+        let parsed = ast::parse_file(&self.tokens);
+        timings::PARSE.record(started.elapsed());
+        match parsed {
+            Ok(ast) => {
+                self.ast = ast;
+                Ok(())
             }
             Err(e) => {
-                return Err(DocumentError::Diagnostic(Box::new(
-                    lsp_extra::lexer_error_to_diagnostic(&e),
-                )));
+                e.print_err(text, &self.tokens);
+                Err(DocumentError::Diagnostic(Box::new(
+                    lsp_extra::ast_error_to_diagnostic(&e, &self.tokens),
+                )))
             }
         }
-
-        Ok(())
     }
 }
 
@@ -227,10 +312,11 @@ pub fn get_class_path(ast: &AstFile) -> Option<MyString> {
 pub fn open_document(
     key: &str,
     content: &str,
+    version: i32,
     document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
 ) -> Result<(), DocumentError> {
     let path = path_without_subclass(key);
-    Document::setup_insert(content, path, &key.to_smolstr(), document_map)?;
+    Document::setup_insert(content, path, &key.to_smolstr(), version, document_map)?;
     Ok(())
 }
 pub fn read_document_or_open_class(
@@ -240,12 +326,17 @@ pub fn read_document_or_open_class(
     let Ok(mut dm) = document_map.write() else {
         return Err(DocumentError::Locked);
     };
-    if let Some(document) = dm.get(source) {
+    if let Some(document) = dm.get_mut(source) {
+        cache::hit();
+        document.touch();
         return Ok(document.clone());
     }
+    cache::miss();
     let path = path_without_subclass(source);
-    Document::setup_read(path).inspect(|doc| {
+    Document::setup_read(path).map(|mut doc| {
+        doc.touch();
         dm.insert(source.to_smolstr(), doc.clone());
+        doc
     })
 }
 
@@ -255,7 +346,9 @@ pub fn get_ast(
 ) -> Result<AstFile, DocumentError> {
     read_document_or_open_class(source, document_map).map(|i| i.ast)
 }
-fn path_without_subclass(source: &str) -> PathBuf {
+/// Path of the file that contains the class, nested classes are in the file of the outer class
+#[must_use]
+pub fn path_without_subclass(source: &str) -> PathBuf {
     let mut path = PathBuf::from(source);
     {
         if let Some(file_name) = path.file_name()
@@ -269,3 +362,42 @@ fn path_without_subclass(source: &str) -> PathBuf {
     }
     path
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ast::{
+        lexer::{self, Token},
+        types::{AstPoint, AstRange},
+    };
+
+    use crate::Document;
+
+    #[test]
+    fn range_text_non_ascii() {
+        let content = "class A {\n    String s = \"äöü\" + name;\n}\n";
+        let document = Document::setup(content, PathBuf::from("A.java")).unwrap();
+        let tokens = lexer::lex(content.as_bytes()).unwrap();
+        let name = tokens
+            .iter()
+            .find(|t| t.token == Token::Identifier("name".into()))
+            .unwrap();
+        let start = AstPoint {
+            line: name.line,
+            col: name.col,
+        };
+        let end = AstPoint {
+            line: name.line,
+            col: name.col + 4,
+        };
+        assert_eq!(document.range_text(&AstRange { start, end }), "name");
+        assert_eq!(
+            document.point_to_char(&end),
+            content
+                .find(';')
+                .map(|b| content[..b].chars().count())
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,53 @@
+//! Main and test sources of a project
+//!
+//! Build tools keep test sources in a source set next to `main`, like `src/test/java` or
+//! `src/integrationTest/java`. Projects without that layout put them into a `test` folder.
+use std::path::Path;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SourceSet {
+    #[default]
+    Main,
+    Test,
+}
+
+impl SourceSet {
+    /// The folder after `src` names the source set, a package called `test` below it does not
+    /// matter
+    #[must_use]
+    pub fn of(path: &Path) -> Self {
+        let mut components = path.components().filter_map(|c| c.as_os_str().to_str());
+        let mut test_folder = false;
+        while let Some(component) = components.next() {
+            if component == "src" {
+                return match components.next() {
+                    Some(set) if set.to_lowercase().contains("test") => Self::Test,
+                    _ => Self::Main,
+                };
+            }
+            test_folder |= component == "test" || component == "tests";
+        }
+        if test_folder { Self::Test } else { Self::Main }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::SourceSet;
+
+    #[test]
+    fn source_sets() {
+        let of = |p: &str| SourceSet::of(Path::new(p));
+        assert_eq!(of("/p/src/main/java/ch/A.java"), SourceSet::Main);
+        assert_eq!(of("/p/src/test/java/ch/ATest.java"), SourceSet::Test);
+        assert_eq!(
+            of("/p/app/src/integrationTest/java/ch/AIT.java"),
+            SourceSet::Test
+        );
+        assert_eq!(of("/p/src/main/java/ch/test/Helper.java"), SourceSet::Main);
+        assert_eq!(of("/p/test/ch/ATest.java"), SourceSet::Test);
+        assert_eq!(of("/p/ch/A.java"), SourceSet::Main);
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 use bitflags::bitflags;
 use my_string::{MyString, smol_str::format_smolstr};
 
-pub const CFC_VERSION: usize = 19;
+pub const CFC_VERSION: usize = 23;
 
 #[derive(Debug)]
 pub enum ClassParserError {
@@ -162,6 +162,8 @@ bitflags! {
      const Abstract     = 0b0001_0000_0000_0000;
      const Synchronized = 0b0010_0000_0000_0000;
      const Deprecated   = 0b0100_0000_0000_0000;
+     /// Method with a variable number of arguments, its last parameter is an array
+     const Varargs      = 0b1000_0000_0000_0000;
    }
 }
 
@@ -169,26 +171,32 @@ bitflags! {
 pub struct Method {
     pub access: Access,
     pub name: Option<MyString>,
+    /// Names of the type parameters of a generic method
+    pub type_parameters: Vec<MyString>,
     pub parameters: Vec<Parameter>,
     pub throws: Vec<JType>,
     pub ret: JType,
     /// When None then it is in the class
     pub source: Option<MyString>,
+    /// Annotations of the method and its return type
+    pub annotations: Vec<MyString>,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct Field {
     pub access: Access,
     pub name: MyString,
     pub jtype: JType,
     /// When None then it is in the class
     pub source: Option<MyString>,
+    pub annotations: Vec<MyString>,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct Parameter {
     pub name: Option<MyString>,
     pub jtype: JType,
+    pub annotations: Vec<MyString>,
 }
 
 #[derive(Debug, PartialEq, Clone, Default)]
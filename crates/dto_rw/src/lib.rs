@@ -452,6 +452,7 @@ fn write_method(m: &Method, out: &mut Vec<u8>) {
     } else {
         write_u8(0, out);
     }
+    write_strings(&m.type_parameters, out);
     write_usize(m.parameters.len(), out);
     for p in &m.parameters {
         write_parameter(p, out);
@@ -467,6 +468,7 @@ fn write_method(m: &Method, out: &mut Vec<u8>) {
     } else {
         write_u8(0, out);
     }
+    write_strings(&m.annotations, out);
 }
 fn parse_method(data: &[u8], pos: usize) -> Result<(Method, usize), DtoRwError> {
     let (access, pos) = parse_access(data, pos)?;
@@ -478,6 +480,7 @@ fn parse_method(data: &[u8], pos: usize) -> Result<(Method, usize), DtoRwError>
     } else {
         (None, pos)
     };
+    let (type_parameters, pos) = parse_strings(data, pos)?;
 
     let (len, pos) = parse_usize(data, pos)?;
     let mut pos = pos;
@@ -510,14 +513,17 @@ fn parse_method(data: &[u8], pos: usize) -> Result<(Method, usize), DtoRwError>
     } else {
         (None, pos)
     };
+    let (annotations, pos) = parse_strings(data, pos)?;
     Ok((
         Method {
             access,
             name,
+            type_parameters,
             parameters,
             throws,
             ret,
             source,
+            annotations,
         },
         pos,
     ))
@@ -531,6 +537,7 @@ fn write_parameter(p: &Parameter, out: &mut Vec<u8>) {
         write_u8(0, out);
     }
     write_jtype(&p.jtype, out);
+    write_strings(&p.annotations, out);
 }
 fn parse_parameter(data: &[u8], pos: usize) -> Result<(Parameter, usize), DtoRwError> {
     let (variant, pos) = parse_u8(data, pos)?;
@@ -541,7 +548,33 @@ fn parse_parameter(data: &[u8], pos: usize) -> Result<(Parameter, usize), DtoRwE
         (None, pos)
     };
     let (jtype, pos) = parse_jtype(data, pos)?;
-    Ok((Parameter { name, jtype }, pos))
+    let (annotations, pos) = parse_strings(data, pos)?;
+    Ok((
+        Parameter {
+            name,
+            jtype,
+            annotations,
+        },
+        pos,
+    ))
+}
+
+fn write_strings(strings: &[MyString], out: &mut Vec<u8>) {
+    write_usize(strings.len(), out);
+    for s in strings {
+        write_string(s, out);
+    }
+}
+fn parse_strings(data: &[u8], pos: usize) -> Result<(Vec<MyString>, usize), DtoRwError> {
+    let (len, pos) = parse_usize(data, pos)?;
+    let mut pos = pos;
+    let mut out = Vec::with_capacity(len);
+    for _ in 0..len {
+        let (string, npos) = parse_string(data, pos)?;
+        pos = npos;
+        out.push(string);
+    }
+    Ok((out, pos))
 }
 
 fn write_fields(ms: &[Field], out: &mut Vec<u8>) {
@@ -574,6 +607,7 @@ fn write_field(f: &Field, out: &mut Vec<u8>) {
     } else {
         write_u8(0, out);
     }
+    write_strings(&f.annotations, out);
 }
 fn parse_field(data: &[u8], pos: usize) -> Result<(Field, usize), DtoRwError> {
     let (access, pos) = parse_access(data, pos)?;
@@ -587,12 +621,14 @@ fn parse_field(data: &[u8], pos: usize) -> Result<(Field, usize), DtoRwError> {
     } else {
         (None, pos)
     };
+    let (annotations, pos) = parse_strings(data, pos)?;
     Ok((
         Field {
             access,
             name,
             jtype,
             source,
+            annotations,
         },
         pos,
     ))
@@ -807,27 +843,33 @@ mod tests {
                     Method {
                         access: Access::Public,
                         name: None,
+                        type_parameters: vec![],
                         parameters: vec![
                             Parameter {
                                 name: Some(SmolStr::new_inline("a")),
                                 jtype: JType::Char,
+                                annotations: vec![],
                             },
                             Parameter {
                                 name: None,
                                 jtype: JType::Char,
+                                annotations: vec![],
                             },
                         ],
                         throws: vec![JType::Class(SmolStr::new_inline("IOException"))],
                         ret: JType::Void,
                         source: None,
+                        annotations: vec![],
                     },
                     Method {
                         access: Access::Public,
                         name: Some(SmolStr::new_inline("haha")),
+                        type_parameters: vec![SmolStr::new_inline("T")],
                         parameters: vec![],
                         throws: vec![],
-                        ret: JType::Void,
+                        ret: JType::Parameter(SmolStr::new_inline("T")),
                         source: Some(SmolStr::new_inline("/data/String.java")),
+                        annotations: vec![],
                     },
                 ],
                 fields: vec![
@@ -836,12 +878,14 @@ mod tests {
                         name: SmolStr::new_inline("a"),
                         jtype: JType::Int,
                         source: None,
+                        annotations: vec![],
                     },
                     Field {
                         access: Access::Public,
                         name: SmolStr::new_inline("a"),
                         jtype: JType::Int,
                         source: Some(SmolStr::new_inline("/data/String.java")),
+                        annotations: vec![],
                     },
                 ],
                 super_class: SuperClass::None,
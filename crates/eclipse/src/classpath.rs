@@ -0,0 +1,179 @@
+//! Entries of the eclipse `.classpath` and the name in `.project`
+//!
+//! Source folders and libraries are resolved relative to the project. Paths that start with a `/`
+//! and do not exist name a project of the eclipse workspace, the folder next to the project.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+#[derive(Debug)]
+pub enum EclipseError {
+    IO(std::io::Error),
+    Xml(serde_xml_rs::Error),
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename = "classpath")]
+struct ClasspathFile {
+    #[serde(rename = "classpathentry", default)]
+    entries: Vec<ClasspathEntry>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+struct ClasspathEntry {
+    #[serde(rename = "@kind")]
+    kind: String,
+    #[serde(rename = "@path")]
+    path: String,
+    #[serde(rename = "@sourcepath")]
+    source_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename = "projectDescription")]
+struct ProjectDescription {
+    name: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct EclipseClasspath {
+    pub sources: Vec<PathBuf>,
+    pub libraries: Vec<EclipseLibrary>,
+    /// Containers like the jre or junit, eclipse resolves them itself
+    pub containers: Vec<String>,
+    /// Other projects of the eclipse workspace
+    pub projects: Vec<PathBuf>,
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct EclipseLibrary {
+    pub jar: PathBuf,
+    /// Folder or archive with the sources of the jar
+    pub source: Option<PathBuf>,
+}
+
+const JRE_CONTAINER: &str = "org.eclipse.jdt.launching.JRE_CONTAINER";
+
+impl EclipseClasspath {
+    /// Release of the execution environment of the jre container, `JavaSE-1.8` is 8
+    #[must_use]
+    pub fn release(&self) -> Option<u32> {
+        self.containers
+            .iter()
+            .filter(|c| c.starts_with(JRE_CONTAINER))
+            .find_map(|c| {
+                let version = c.rsplit_once("JavaSE-")?.1;
+                let version = version.strip_prefix("1.").unwrap_or(version);
+                version.parse().ok()
+            })
+    }
+}
+
+pub fn load_classpath(project_dir: &Path) -> Result<EclipseClasspath, EclipseError> {
+    let content = fs::read_to_string(project_dir.join(".classpath")).map_err(EclipseError::IO)?;
+    parse_classpath(&content, project_dir)
+}
+
+pub fn parse_classpath(
+    content: &str,
+    project_dir: &Path,
+) -> Result<EclipseClasspath, EclipseError> {
+    let file: ClasspathFile = serde_xml_rs::from_str(content).map_err(EclipseError::Xml)?;
+    let mut out = EclipseClasspath::default();
+    for entry in file.entries {
+        match entry.kind.as_str() {
+            "src" if entry.path.starts_with('/') => {
+                out.projects.push(resolve(project_dir, &entry.path));
+            }
+            "src" => out.sources.push(resolve(project_dir, &entry.path)),
+            "lib" => out.libraries.push(EclipseLibrary {
+                jar: resolve(project_dir, &entry.path),
+                source: entry.source_path.map(|s| resolve(project_dir, &s)),
+            }),
+            "con" => out.containers.push(entry.path),
+            "output" => out.output = Some(resolve(project_dir, &entry.path)),
+            _ => (),
+        }
+    }
+    Ok(out)
+}
+
+/// Name of the project in `.project`
+pub fn load_project_name(project_dir: &Path) -> Result<String, EclipseError> {
+    let content = fs::read_to_string(project_dir.join(".project")).map_err(EclipseError::IO)?;
+    let description: ProjectDescription =
+        serde_xml_rs::from_str(&content).map_err(EclipseError::Xml)?;
+    Ok(description.name)
+}
+
+fn resolve(project_dir: &Path, path: &str) -> PathBuf {
+    let absolute = Path::new(path);
+    if let Some(workspace_path) = path.strip_prefix('/') {
+        if absolute.exists() {
+            return absolute.to_path_buf();
+        }
+        return project_dir
+            .parent()
+            .unwrap_or(project_dir)
+            .join(workspace_path);
+    }
+    if absolute.is_absolute() {
+        return absolute.to_path_buf();
+    }
+    project_dir.join(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{EclipseClasspath, EclipseLibrary, parse_classpath};
+
+    #[test]
+    fn classpath_entries() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<classpath>
+	<classpathentry kind="src" path="src"/>
+	<classpathentry kind="src" output="bin/test" path="test">
+		<attributes>
+			<attribute name="test" value="true"/>
+		</attributes>
+	</classpathentry>
+	<classpathentry combineaccessrules="false" kind="src" path="/common"/>
+	<classpathentry kind="con" path="org.eclipse.jdt.launching.JRE_CONTAINER/org.eclipse.jdt.internal.debug.ui.launcher.StandardVMType/JavaSE-17"/>
+	<classpathentry kind="con" path="org.eclipse.jdt.junit.JUNIT_CONTAINER/5"/>
+	<classpathentry kind="lib" path="lib/gson-2.10.jar" sourcepath="lib/gson-2.10-sources.jar"/>
+	<classpathentry kind="lib" path="/opt/libs/a.jar"/>
+	<classpathentry kind="output" path="bin"/>
+</classpath>"#;
+        let dir = PathBuf::from("/ws/app");
+        let classpath = parse_classpath(content, &dir).unwrap();
+        assert_eq!(
+            classpath,
+            EclipseClasspath {
+                sources: vec![dir.join("src"), dir.join("test")],
+                libraries: vec![
+                    EclipseLibrary {
+                        jar: dir.join("lib/gson-2.10.jar"),
+                        source: Some(dir.join("lib/gson-2.10-sources.jar")),
+                    },
+                    EclipseLibrary {
+                        jar: PathBuf::from("/ws/opt/libs/a.jar"),
+                        source: None,
+                    },
+                ],
+                containers: vec![
+                    "org.eclipse.jdt.launching.JRE_CONTAINER/org.eclipse.jdt.internal.debug.ui.launcher.StandardVMType/JavaSE-17".to_string(),
+                    "org.eclipse.jdt.junit.JUNIT_CONTAINER/5".to_string(),
+                ],
+                projects: vec![PathBuf::from("/ws/common")],
+                output: Some(dir.join("bin")),
+            }
+        );
+        assert_eq!(classpath.release(), Some(17));
+    }
+}
@@ -0,0 +1,9 @@
+#![deny(clippy::redundant_clone)]
+#![deny(clippy::pedantic)]
+#![deny(clippy::nursery)]
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::too_many_lines)]
+
+pub mod classpath;
+pub mod preferences;
+pub mod project;
@@ -0,0 +1,60 @@
+//! Compiler settings of the project in `.settings/org.eclipse.jdt.core.prefs`
+use std::{fs, path::Path};
+
+use common::language_level::{LanguageLevel, parse_release};
+
+use crate::classpath::load_classpath;
+
+const JDT_PREFS: &str = ".settings/org.eclipse.jdt.core.prefs";
+
+/// The compiler source of the preferences, otherwise the execution environment of the jre
+/// container
+#[must_use]
+pub fn language_level(project_dir: &Path) -> LanguageLevel {
+    let mut level = fs::read_to_string(project_dir.join(JDT_PREFS))
+        .map(|content| parse_preferences(&content))
+        .unwrap_or_default();
+    if level.release.is_none() {
+        level.release = load_classpath(project_dir).ok().and_then(|c| c.release());
+    }
+    level
+}
+
+fn parse_preferences(content: &str) -> LanguageLevel {
+    let mut level = LanguageLevel::default();
+    for (key, value) in content.lines().filter_map(|l| l.split_once('=')) {
+        match key.trim() {
+            "org.eclipse.jdt.core.compiler.source" => level.release = parse_release(value),
+            "org.eclipse.jdt.core.compiler.problem.enablePreviewFeatures" => {
+                level.preview = value.trim() == "enabled";
+            }
+            _ => (),
+        }
+    }
+    level
+}
+
+#[cfg(test)]
+mod tests {
+    use common::language_level::LanguageLevel;
+
+    use super::parse_preferences;
+
+    #[test]
+    fn preferences() {
+        let content = "eclipse.preferences.version=1
+org.eclipse.jdt.core.compiler.codegen.targetPlatform=21
+org.eclipse.jdt.core.compiler.compliance=21
+org.eclipse.jdt.core.compiler.problem.enablePreviewFeatures=enabled
+org.eclipse.jdt.core.compiler.release=enabled
+org.eclipse.jdt.core.compiler.source=21
+";
+        assert_eq!(
+            parse_preferences(content),
+            LanguageLevel {
+                release: Some(21),
+                preview: true
+            }
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use dto::{Class, ClassFolder, SourceDestination};
+use my_string::{MyString, smol_str::ToSmolStr};
+
+use crate::classpath::{EclipseClasspath, load_classpath};
+
+#[must_use]
+pub fn get_eclipse_cache_path(project_dir: &Path, project_cache_dir: &Path) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    project_dir.hash(&mut hasher);
+    let s = format!("{}.eclipse.cfc", hasher.finish());
+    project_cache_dir.join(s)
+}
+
+/// Source folders of the project and of the workspace projects it references, with the folders
+/// that annotation processing generated. `progress` gets the number of parsed files and the total
+#[must_use]
+pub fn load_project_folders(
+    project_dir: &Path,
+    progress: impl Fn(usize, usize) + Sync,
+) -> Vec<Class> {
+    let mut folders = match load_classpath(project_dir) {
+        Ok(classpath) => source_folders(&classpath),
+        Err(e) => {
+            eprintln!("Failed to load eclipse classpath: {e:?}");
+            Vec::new()
+        }
+    };
+    folders.extend(generated_source_folders(project_dir));
+    loader::load_java_folders(folders, progress)
+}
+
+/// Where eclipse puts the sources of annotation processors, `.apt_generated` is also the folder
+/// that the language server generates into
+pub const APT_GENERATED: &str = ".apt_generated";
+
+#[must_use]
+pub fn generated_source_folders(project_dir: &Path) -> Vec<PathBuf> {
+    [APT_GENERATED, ".apt_generated_tests"]
+        .iter()
+        .map(|f| project_dir.join(f))
+        .filter(|f| f.is_dir())
+        .collect()
+}
+
+fn source_folders(classpath: &EclipseClasspath) -> Vec<PathBuf> {
+    let referenced = classpath
+        .projects
+        .iter()
+        .filter_map(|p| load_classpath(p).ok())
+        .flat_map(|c| c.sources);
+    classpath
+        .sources
+        .iter()
+        .cloned()
+        .chain(referenced)
+        .collect()
+}
+
+/// Load the classes of the libraries into the class map
+pub fn index_project(
+    class_map: &Arc<RwLock<HashMap<MyString, Class, impl BuildHasher>>>,
+    use_cache: bool,
+    cache_path: &Path,
+    project_dir: &Path,
+) {
+    if use_cache
+        && cache_path.exists()
+        && let Ok(classes) = loader::load_class_folder(cache_path)
+    {
+        insert(class_map, classes);
+        return;
+    }
+    let classpath = match load_classpath(project_dir) {
+        Ok(classpath) => classpath,
+        Err(e) => {
+            eprintln!("Failed to load eclipse classpath: {e:?}");
+            return;
+        }
+    };
+    let loaded = loader::parallel::parallel_map(
+        &classpath.libraries,
+        |library| {
+            // Extracted sources can be used, archives are not extracted
+            let source = library
+                .source
+                .as_ref()
+                .filter(|s| s.is_dir())
+                .and_then(|s| s.to_str())
+                .map_or(SourceDestination::None, |s| {
+                    SourceDestination::RelativeInFolder(s.to_smolstr())
+                });
+            match loader::load_classes_jar(&library.jar, source) {
+                Ok(classes) => Some(classes),
+                Err(e) => {
+                    eprintln!("Failed to load jar: {}, {e:?}", library.jar.display());
+                    None
+                }
+            }
+        },
+        |_, _| {},
+    );
+    let class_folder = ClassFolder {
+        classes: loaded.into_iter().flat_map(|c| c.classes).collect(),
+    };
+    if let Err(e) = loader::save_class_folder(cache_path, &class_folder) {
+        eprintln!("Failed to save {} because: {e:?}", cache_path.display());
+    }
+    insert(class_map, class_folder);
+}
+
+fn insert(
+    class_map: &Arc<RwLock<HashMap<MyString, Class, impl BuildHasher>>>,
+    classes: ClassFolder,
+) {
+    if let Ok(mut cm) = class_map.write() {
+        for class in classes.classes {
+            cm.insert(class.class_path.clone(), class);
+        }
+    }
+}
+
+/// Libraries for the classpath of javac
+#[must_use]
+pub fn classpath_jars(classpath: &EclipseClasspath) -> Vec<&Path> {
+    classpath
+        .libraries
+        .iter()
+        .map(|l| l.jar.as_path())
+        .collect()
+}
@@ -0,0 +1,283 @@
+//! Formatting settings of `.editorconfig` files, <https://editorconfig.org>
+use std::{fs, path::Path};
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct EditorConfig {
+    pub indent_style: Option<IndentStyle>,
+    pub indent_size: Option<usize>,
+    pub max_line_length: Option<usize>,
+    pub insert_final_newline: Option<bool>,
+    pub end_of_line: Option<EndOfLine>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IndentStyle {
+    Tab,
+    Space,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EndOfLine {
+    Lf,
+    CrLf,
+    Cr,
+}
+
+impl EditorConfig {
+    /// Settings for a file from the `.editorconfig` files of its directory and all parents up to
+    /// the one with `root = true`. Closer files take precedence.
+    #[must_use]
+    pub fn load(file: &Path) -> Self {
+        let mut out = Self::default();
+        let mut dir = file.parent();
+        while let Some(d) = dir {
+            if let Ok(content) = fs::read_to_string(d.join(".editorconfig"))
+                && let Ok(relative) = file.strip_prefix(d)
+            {
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                let (config, root) = Self::parse(&content, &relative);
+                out.fill(config);
+                if root {
+                    break;
+                }
+            }
+            dir = d.parent();
+        }
+        out
+    }
+
+    /// Settings of one `.editorconfig` for a path relative to it, and whether it is the root
+    #[must_use]
+    pub fn parse(content: &str, relative: &str) -> (Self, bool) {
+        let mut out = Self::default();
+        let mut root = false;
+        let mut section: Option<bool> = None;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(glob) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = Some(section_matches(glob, relative));
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim().to_lowercase();
+            match section {
+                None if key == "root" => root = value == "true",
+                Some(true) => out.set(&key, &value),
+                _ => {}
+            }
+        }
+        (out, root)
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        match key {
+            "indent_style" => {
+                self.indent_style = match value {
+                    "tab" => Some(IndentStyle::Tab),
+                    "space" => Some(IndentStyle::Space),
+                    _ => None,
+                };
+            }
+            "indent_size" => self.indent_size = value.parse().ok(),
+            "max_line_length" => self.max_line_length = value.parse().ok(),
+            "insert_final_newline" => {
+                self.insert_final_newline = match value {
+                    "true" => Some(true),
+                    "false" => Some(false),
+                    _ => None,
+                };
+            }
+            "end_of_line" => {
+                self.end_of_line = match value {
+                    "lf" => Some(EndOfLine::Lf),
+                    "crlf" => Some(EndOfLine::CrLf),
+                    "cr" => Some(EndOfLine::Cr),
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    /// Take the settings of a file further up that are not set yet
+    fn fill(&mut self, other: Self) {
+        self.indent_style = self.indent_style.or(other.indent_style);
+        self.indent_size = self.indent_size.or(other.indent_size);
+        self.max_line_length = self.max_line_length.or(other.max_line_length);
+        self.insert_final_newline = self.insert_final_newline.or(other.insert_final_newline);
+        self.end_of_line = self.end_of_line.or(other.end_of_line);
+    }
+
+    /// Indentation unit, falls back to the editor's tab size
+    #[must_use]
+    pub fn space(&self, insert_spaces: bool, tab_size: usize) -> String {
+        let size = self.indent_size.unwrap_or(tab_size);
+        match self.indent_style {
+            Some(IndentStyle::Space) => " ".repeat(size),
+            None if insert_spaces => " ".repeat(size),
+            _ => "\t".to_string(),
+        }
+    }
+
+    /// Apply the final newline and line ending settings to formatted output
+    #[must_use]
+    pub fn finish(&self, mut out: Vec<u8>) -> Vec<u8> {
+        match self.insert_final_newline {
+            Some(true) if !out.ends_with(b"\n") => out.push(b'\n'),
+            Some(false) => {
+                while out.last().is_some_and(|b| *b == b'\n' || *b == b'\r') {
+                    out.pop();
+                }
+            }
+            _ => {}
+        }
+        let eol: &[u8] = match self.end_of_line {
+            None | Some(EndOfLine::Lf) => return out,
+            Some(EndOfLine::CrLf) => b"\r\n",
+            Some(EndOfLine::Cr) => b"\r",
+        };
+        let mut converted = Vec::with_capacity(out.len());
+        let mut bytes = out.iter().peekable();
+        while let Some(b) = bytes.next() {
+            match b {
+                b'\r' => {
+                    if bytes.peek() == Some(&&b'\n') {
+                        bytes.next();
+                    }
+                    converted.extend_from_slice(eol);
+                }
+                b'\n' => converted.extend_from_slice(eol),
+                _ => converted.push(*b),
+            }
+        }
+        converted
+    }
+}
+
+/// Without a slash the glob matches the file name in any directory
+fn section_matches(glob: &str, relative: &str) -> bool {
+    let glob = glob.strip_prefix('/').unwrap_or(glob);
+    expand_braces(glob).iter().any(|glob| {
+        if glob.contains('/') {
+            glob_matches(glob.as_bytes(), relative.as_bytes())
+        } else {
+            let name = relative.rsplit('/').next().unwrap_or(relative);
+            glob_matches(glob.as_bytes(), name.as_bytes())
+        }
+    })
+}
+
+/// `*.{java,kt}` becomes `*.java` and `*.kt`
+fn expand_braces(glob: &str) -> Vec<String> {
+    let Some(open) = glob.find('{') else {
+        return vec![glob.to_string()];
+    };
+    let Some(close) = glob[open..].find('}').map(|c| open + c) else {
+        return vec![glob.to_string()];
+    };
+    let (prefix, suffix) = (&glob[..open], &glob[close + 1..]);
+    glob[open + 1..close]
+        .split(',')
+        .flat_map(|alternative| expand_braces(&format!("{prefix}{alternative}{suffix}")))
+        .collect()
+}
+
+fn glob_matches(glob: &[u8], path: &[u8]) -> bool {
+    match glob {
+        [] => path.is_empty(),
+        [b'*', b'*', rest @ ..] => (0..=path.len()).any(|i| glob_matches(rest, &path[i..])),
+        [b'*', rest @ ..] => (0..=path.len())
+            .take_while(|i| !path[..*i].contains(&b'/'))
+            .any(|i| glob_matches(rest, &path[i..])),
+        [b'?', rest @ ..] => {
+            matches!(path.first(), Some(c) if *c != b'/') && glob_matches(rest, &path[1..])
+        }
+        [b'[', rest @ ..] => {
+            let Some(close) = rest.iter().position(|c| *c == b']') else {
+                return path.first() == Some(&b'[') && glob_matches(rest, &path[1..]);
+            };
+            let Some(c) = path.first() else {
+                return false;
+            };
+            let (negate, class) = match &rest[..close] {
+                [b'!', class @ ..] => (true, class),
+                class => (false, class),
+            };
+            class.contains(c) != negate && glob_matches(&rest[close + 1..], &path[1..])
+        }
+        [g, rest @ ..] => path.first() == Some(g) && glob_matches(rest, &path[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+
+    const CONTENT: &str = "
+root = true
+
+[*]
+indent_style = space
+indent_size = 2
+end_of_line = lf
+
+[*.{java,kt}]
+indent_size = 4
+max_line_length = 120
+
+[src/test/**.java]
+insert_final_newline = false
+";
+
+    #[test]
+    fn parse_sections() {
+        let (config, root) = EditorConfig::parse(CONTENT, "src/test/java/Test.java");
+        assert!(root);
+        let expected = expect![[r"
+            EditorConfig {
+                indent_style: Some(
+                    Space,
+                ),
+                indent_size: Some(
+                    4,
+                ),
+                max_line_length: Some(
+                    120,
+                ),
+                insert_final_newline: Some(
+                    false,
+                ),
+                end_of_line: Some(
+                    Lf,
+                ),
+            }
+        "]];
+        expected.assert_debug_eq(&config);
+        let (config, _) = EditorConfig::parse(CONTENT, "README.md");
+        assert_eq!(config.indent_size, Some(2));
+        assert_eq!(config.max_line_length, None);
+    }
+
+    #[test]
+    fn finish_output() {
+        let config = EditorConfig {
+            insert_final_newline: Some(true),
+            end_of_line: Some(EndOfLine::CrLf),
+            ..Default::default()
+        };
+        assert_eq!(config.finish(b"a\nb".to_vec()), b"a\r\nb\r\n");
+        let config = EditorConfig {
+            insert_final_newline: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(config.finish(b"a\n\n".to_vec()), b"a");
+    }
+}
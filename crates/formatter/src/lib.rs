@@ -12,7 +12,7 @@ use ast::{
     types::{
         AstAnnotated, AstAnnotatedParameter, AstAnnotatedParameterKind, AstAnnotation,
         AstAnnotationField, AstAvailability, AstBlock, AstBlockEntry, AstClass, AstClassBlock,
-        AstClassConstructor, AstClassMethod, AstClassVariable, AstEnumeration,
+        AstClassConstructor, AstClassMethod, AstClassVariable, AstEnumeration, AstExpression,
         AstExpressionIdentifier, AstExpressionKind, AstExpressionOperator,
         AstExpressionOrAnnotated, AstExpressionOrDefault, AstExpressionOrValue, AstForContent,
         AstIdentifier, AstIf, AstIfContent, AstImport, AstImportUnit, AstInterface,
@@ -24,8 +24,9 @@ use ast::{
         AstValuesWithAnnotated, AstVolatileTransient, AstWhileContent,
     },
 };
-use config::FormatterConfig;
+use config::{FormatterConfig, project::MemberKind};
 
+pub mod editorconfig;
 pub mod google;
 pub mod idea;
 
@@ -45,6 +46,30 @@ pub struct FormatLineError {
     pub col: u32,
     pub message: String,
 }
+/// Layout settings of the internal formatter
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    pub space: String,
+    /// Lines longer than this are wrapped
+    pub width: usize,
+    /// Import prefixes, each group is separated by a blank line
+    pub import_order: Vec<String>,
+    /// Members of a class are sorted by their kind in this order
+    pub member_order: Vec<MemberKind>,
+}
+
+impl FormatOptions {
+    #[must_use]
+    pub fn new(space: &str, width: usize) -> Self {
+        Self {
+            space: space.to_string(),
+            width,
+            import_order: Vec::new(),
+            member_order: Vec::new(),
+        }
+    }
+}
+
 #[must_use]
 pub fn get_formatter_name(formatter: &FormatterConfig) -> String {
     match formatter {
@@ -60,28 +85,37 @@ pub fn format(
     content: &[u8],
     path: &Path,
     project_dir: &Path,
-    space: &str,
+    options: &FormatOptions,
 ) -> Result<Option<Vec<u8>>, FormatError> {
     match formatter {
         FormatterConfig::None => Err(FormatError::NoFormatterSpecified),
-        FormatterConfig::Internal => internal(content, space),
+        FormatterConfig::Internal => internal(content, options),
         FormatterConfig::Google => google::google_java_format(content),
         FormatterConfig::Idea => idea::idea_java_format(path, project_dir),
     }
 }
 
-fn internal(content: &[u8], space: &str) -> Result<Option<Vec<u8>>, FormatError> {
-    let mut f = Formatter::new(content, space)?;
+fn internal(content: &[u8], options: &FormatOptions) -> Result<Option<Vec<u8>>, FormatError> {
+    let mut f = Formatter::new(content, options)?;
     let tokens = ast::lexer::lex_v::<false>(content).map_err(FormatError::Lexer)?;
     let ast = ast::parse_file(&tokens).map_err(FormatError::Ast)?;
 
     let mut top = ast.top.iter().peekable();
 
-    while let Some(t) = top.next() {
+    while let Some(mut t) = top.next() {
         match t {
             AstTopLevel::Package(p) => {
                 write_package(p, &mut f);
             }
+            AstTopLevel::Import(ast_import) if !options.import_order.is_empty() => {
+                let mut imports = vec![ast_import];
+                while let Some(next @ AstTopLevel::Import(import)) = top.peek() {
+                    imports.push(import);
+                    t = next;
+                    top.next();
+                }
+                write_imports(&imports, &options.import_order, &mut f);
+            }
             AstTopLevel::Import(ast_import) => write_import(ast_import, &mut f),
             AstTopLevel::Thing(ast_thing) => write_thing(ast_thing, &mut f),
             AstTopLevel::Method(ast_class_method) => {
@@ -103,20 +137,55 @@ struct Formatter {
     pub buf: Vec<u8>,
     pub indent: usize,
     pub space: String,
+    /// Lines longer than this are wrapped
+    pub width: usize,
+    pub member_order: Vec<MemberKind>,
+    /// Set while a line is written to check its width, nested checks then assume it fits
+    pub measuring: bool,
 }
 
 impl Formatter {
-    pub fn new(content: &[u8], space: &str) -> Result<Self, FormatError> {
+    pub fn new(content: &[u8], options: &FormatOptions) -> Result<Self, FormatError> {
         let with_comments = ast::lexer::lex_v::<true>(content).map_err(FormatError::Lexer)?;
         Ok(Self {
             with_comments,
             index: 0,
             buf: Vec::new(),
             indent: 0,
-            space: space.to_string(),
+            space: options.space.clone(),
+            width: options.width,
+            member_order: options.member_order.clone(),
+            measuring: false,
         })
     }
 
+    /// Write on the current line to check that it stays within the width, then undo the write
+    pub fn fits(&mut self, write: impl FnOnce(&mut Self)) -> bool {
+        if self.measuring {
+            return true;
+        }
+        let (len, index, indent) = (self.buf.len(), self.index, self.indent);
+        self.measuring = true;
+        write(self);
+        self.measuring = false;
+        let start = self.buf[..len]
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map_or(0, |i| i + 1);
+        let end = self.buf[len..]
+            .iter()
+            .position(|b| *b == b'\n')
+            .map_or(self.buf.len(), |i| len + i);
+        let width = self.buf[start..end]
+            .iter()
+            .map(|b| if *b == b'\t' { 4 } else { 1 })
+            .sum::<usize>();
+        self.buf.truncate(len);
+        self.index = index;
+        self.indent = indent;
+        width <= self.width
+    }
+
     fn write_indent(&mut self) {
         for _ in 0..self.indent {
             self.buf.extend_from_slice(self.space.as_bytes());
@@ -223,6 +292,11 @@ impl Formatter {
         }
     }
 
+    /// Index of the first token at or after a point, to write members out of source order
+    fn token_index(&self, at: AstPoint) -> usize {
+        self.with_comments.partition_point(|t| t.start_point() < at)
+    }
+
     #[inline]
     fn new_line(&mut self) {
         self.buf.push(b'\n');
@@ -240,6 +314,12 @@ fn write_package(p: &AstPackage, f: &mut Formatter) {
 }
 
 fn write_annotation(ann: &AstAnnotated, f: &mut Formatter) {
+    write_nested_annotation(ann, f);
+    f.insert_line_or_space();
+}
+
+/// Annotation used as a value of another annotation
+fn write_nested_annotation(ann: &AstAnnotated, f: &mut Formatter) {
     f.write(b"@");
     f.write_identifier(&ann.name);
     match &ann.parameters {
@@ -260,7 +340,6 @@ fn write_annotation(ann: &AstAnnotated, f: &mut Formatter) {
             f.write(b")");
         }
     }
-    f.insert_line_or_space();
 }
 
 fn write_annotation_parameter(param: &AstAnnotatedParameter, f: &mut Formatter) {
@@ -276,7 +355,7 @@ fn write_annotation_parameter(param: &AstAnnotatedParameter, f: &mut Formatter)
             write_expression(expression, f);
         }
         AstAnnotatedParameter::Annotated(ann) => {
-            write_annotation(ann, f);
+            write_nested_annotation(ann, f);
         }
         AstAnnotatedParameter::NamedArray { name, values, .. } => {
             f.write_identifier(name);
@@ -288,7 +367,7 @@ fn write_annotation_parameter(param: &AstAnnotatedParameter, f: &mut Formatter)
         } => {
             f.write_identifier(name);
             f.write(b" = ");
-            write_annotation(annotated, f);
+            write_nested_annotation(annotated, f);
         }
     }
 }
@@ -301,7 +380,7 @@ fn write_values_with_annotated(values: &AstValuesWithAnnotated, f: &mut Formatte
         }
         match v {
             AstExpressionOrAnnotated::Expression(expr) => write_expression(expr, f),
-            AstExpressionOrAnnotated::Annotated(ann) => write_annotation(ann, f),
+            AstExpressionOrAnnotated::Annotated(ann) => write_nested_annotation(ann, f),
         }
     }
     f.write(b"}");
@@ -331,6 +410,12 @@ fn write_expression(expr: &[AstExpressionKind], f: &mut Formatter) {
             })
             .count();
         is_large = ops != 1;
+    } else if !f.fits(|f| {
+        for (nth, k) in expr.iter().enumerate() {
+            write_expression_kind(k, f, false, false, nth);
+        }
+    }) {
+        is_large = true;
     }
 
     let dot = !expr
@@ -383,20 +468,17 @@ fn write_expression_kind(
                         nl = false;
                     }
                 }
-                f.write(b"(");
-                for (i, expr) in values.values.iter().enumerate() {
-                    if i > 0 {
-                        f.write(b",");
-                    }
-                    if nl {
-                        f.new_line();
-                        f.write_indent();
-                    } else if i > 0 {
-                        f.write(b" ");
-                    }
-                    write_expression(expr, f);
+                let wrap = !nl
+                    && !values.values.is_empty()
+                    && !f.fits(|f| write_arguments(&values.values, false, f));
+                if wrap {
+                    f.indent += 1;
                 }
-                if nl {
+                write_arguments(&values.values, nl || wrap, f);
+                if wrap {
+                    f.indent -= 1;
+                }
+                if nl || wrap {
                     f.new_line();
                     f.write_indent();
                 }
@@ -532,6 +614,23 @@ fn write_expression_kind(
     }
 }
 
+/// Opening parenthesis and arguments of a call, one argument per line with `nl`
+fn write_arguments(values: &[AstExpression], nl: bool, f: &mut Formatter) {
+    f.write(b"(");
+    for (i, expr) in values.iter().enumerate() {
+        if i > 0 {
+            f.write(b",");
+        }
+        if nl {
+            f.new_line();
+            f.write_indent();
+        } else if i > 0 {
+            f.write(b" ");
+        }
+        write_expression(expr, f);
+    }
+}
+
 fn write_jtype(jtype: &AstJType, f: &mut Formatter) {
     for ann in &jtype.annotated {
         write_annotation(ann, f);
@@ -1218,8 +1317,21 @@ fn write_class_block(block: &AstClassBlock, f: &mut Formatter) {
     if let Some(first) = members.first() {
         f.insert_new_lines(block.range.start.line, first.0.start.line);
     }
+    let source = members.clone();
+    if !f.member_order.is_empty() {
+        members.sort_by_key(|(_, type_id, _)| member_rank(*type_id, &f.member_order));
+    }
+    let reordered = members != source;
+    let start = f.index;
     let mut members = members.iter().peekable();
-    while let Some((range, type_id, idx)) = members.next() {
+    while let Some(member @ (range, type_id, idx)) = members.next() {
+        if reordered {
+            // Comments before a member in the source are written with it
+            let position = source.iter().position(|m| m == member).unwrap_or_default();
+            f.index = position
+                .checked_sub(1)
+                .map_or(start, |previous| f.token_index(source[previous].0.end));
+        }
         match type_id {
             0 => write_class_variable(&block.variables[*idx], f),
             1 => write_class_method(&block.methods[*idx], f),
@@ -1239,9 +1351,30 @@ fn write_class_block(block: &AstClassBlock, f: &mut Formatter) {
             _ => unreachable!(),
         }
         if let Some(next) = members.peek() {
-            f.insert_new_lines(range.end.line, next.0.start.line);
+            // Fields keep their grouping, everything else is separated by one blank line
+            if *type_id == 0 && next.1 == 0 {
+                f.insert_new_lines(range.end.line, next.0.start.line);
+            } else {
+                f.new_line();
+            }
         }
     }
+    if reordered && let Some(last) = source.last() {
+        f.index = f.token_index(last.0.end);
+    }
+}
+
+/// Position of a member kind of [`write_class_block`] in the configured order
+fn member_rank(type_id: u8, order: &[MemberKind]) -> usize {
+    let kind = match type_id {
+        0 => MemberKind::Fields,
+        1 => MemberKind::Methods,
+        2 => MemberKind::Constructors,
+        3 => MemberKind::StaticInitializers,
+        4 => MemberKind::Types,
+        _ => MemberKind::Initializers,
+    };
+    order.iter().position(|k| *k == kind).unwrap_or(order.len())
 }
 
 fn write_class_variable(v: &AstClassVariable, f: &mut Formatter) {
@@ -1325,7 +1458,20 @@ fn write_method_header(header: &AstMethodHeader, f: &mut Formatter) {
 }
 
 fn write_method_parameters(params: &AstMethodParameters, f: &mut Formatter) {
-    let nl = params.range.start.line != params.range.end.line;
+    let nl = params.range.start.line != params.range.end.line
+        || (!params.parameters.is_empty()
+            && !f.fits(|f| write_method_parameter_list(params, false, f)));
+    write_method_parameter_list(params, nl, f);
+    if nl {
+        f.indent -= 1;
+        f.new_line();
+        f.write_indent();
+    }
+    f.write(b")");
+}
+
+/// Opening parenthesis and parameters, one parameter per line with `nl`
+fn write_method_parameter_list(params: &AstMethodParameters, nl: bool, f: &mut Formatter) {
     f.write(b"(");
     if nl {
         f.indent += 1;
@@ -1355,12 +1501,6 @@ fn write_method_parameters(params: &AstMethodParameters, f: &mut Formatter) {
         f.buf.push(b' ');
         f.write_identifier(&param.name);
     }
-    if nl {
-        f.indent -= 1;
-        f.new_line();
-        f.write_indent();
-    }
-    f.write(b")");
 }
 
 fn write_throws(throws: &AstThrowsDeclaration, f: &mut Formatter) {
@@ -1500,7 +1640,11 @@ fn write_interface(iface: &AstInterface, f: &mut Formatter) {
         members.push((t.get_range().start, 3, i));
     }
     members.sort_by(|(a, _, _), (b, _, _)| a.line.cmp(&b.line).then(a.col.cmp(&b.col)));
-    for (_, type_id, idx) in &members {
+    for (i, (_, type_id, idx)) in members.iter().enumerate() {
+        // Members with a body are separated by one blank line
+        if i > 0 && (*type_id >= 2 || members[i - 1].1 >= 2) {
+            f.new_line();
+        }
         match type_id {
             0 => write_interface_constant(&iface.constants[*idx], f),
             1 => write_interface_method(&iface.methods[*idx], f),
@@ -1816,6 +1960,62 @@ fn write_expression_operator(
     f.skip_to(r.end);
 }
 
+/// Sorted into the configured groups, comments stay with the import that follows them
+fn write_imports(imports: &[&AstImport], order: &[String], f: &mut Formatter) {
+    let Some(last) = imports.last() else {
+        return;
+    };
+    let start = f.index;
+    let mut sorted: Vec<_> = imports
+        .iter()
+        .enumerate()
+        .map(|(i, import)| {
+            let name = import_name(import);
+            (import_group(&name, order), name, i)
+        })
+        .collect();
+    sorted.sort();
+    let mut group = None;
+    for (g, _, i) in sorted {
+        if group.is_some_and(|group| group != g) {
+            f.new_line();
+        }
+        group = Some(g);
+        f.index = i
+            .checked_sub(1)
+            .map_or(start, |previous| f.token_index(imports[previous].range.end));
+        write_import(imports[i], f);
+    }
+    f.index = f.token_index(last.range.end);
+}
+
+fn import_name(import: &AstImport) -> String {
+    match &import.unit {
+        AstImportUnit::Class(ident) => ident.value.to_string(),
+        AstImportUnit::StaticClass(ident) => format!("static {}", ident.value),
+        AstImportUnit::StaticClassMethod(class, method) => {
+            format!("static {}.{}", class.value, method.value)
+        }
+        AstImportUnit::Prefix(ident) => format!("{}.*", ident.value),
+        AstImportUnit::StaticPrefix(ident) => format!("static {}.*", ident.value),
+    }
+}
+
+/// Index of the longest matching prefix, imports without a match come last
+fn import_group(name: &str, order: &[String]) -> usize {
+    order
+        .iter()
+        .enumerate()
+        .filter(|(_, prefix)| {
+            prefix.is_empty()
+                || name
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', ' ']))
+        })
+        .max_by_key(|(_, prefix)| prefix.len())
+        .map_or(order.len(), |(i, _)| i)
+}
+
 fn write_import(import: &AstImport, f: &mut Formatter) {
     f.write(b"import ");
     match &import.unit {
@@ -1966,6 +2166,7 @@ mod tests {
     use super::*;
 
     const SPACE: &str = "    ";
+    const WIDTH: usize = 100;
 
     #[test]
     fn package() {
@@ -1978,7 +2179,7 @@ mod tests {
         // Now the imports
         ";
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r"
             // This is a cool file
             @Thing(Type.IMPORTANT)
@@ -2012,7 +2213,7 @@ mod tests {
         }
         "#;
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r#"
             package ch.emilycares;
 
@@ -2061,7 +2262,7 @@ mod tests {
         }
         ";
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r"
             package ch.emilycares;
             public class Application {
@@ -2110,7 +2311,7 @@ mod tests {
         }
         ";
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r"
             package ch.emilycares;
             public enum EType {
@@ -2150,7 +2351,7 @@ mod tests {
         }
         ";
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r"
             package ch.emilycares;
             public class Test {
@@ -2181,7 +2382,7 @@ mod tests {
         }
         "#;
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r#"
             package ch.emilycares;
             public class Test {
@@ -2210,7 +2411,7 @@ mod tests {
         }
         "#;
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r#"
             package ch.emilycares;
             public class Test {
@@ -2239,7 +2440,7 @@ mod tests {
         }
         ";
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r"
             package ch.emilycares;
 
@@ -2282,11 +2483,11 @@ public class ThingResource {
   }
         "#;
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r#"
             @Path("/api/v1/thing")
             @Consumes(MediaType.APPLICATION_JSON, MediaType.APPLICATION_JSON)
-            @Table(uniqueConstraints = @UniqueConstraint(columnNames = {"otherUuid", "thing_id"}) )
+            @Table(uniqueConstraints = @UniqueConstraint(columnNames = {"otherUuid", "thing_id"}))
             public class ThingResource {
 
                 @Inject
@@ -2329,7 +2530,7 @@ public class ThingResource {
         }
         ";
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r"
             package ch.emilycares;
 
@@ -2367,7 +2568,7 @@ public class ThingResource {
         }
         ";
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r"
             package ch.emilycares;
 
@@ -2403,7 +2604,7 @@ public class Test {
 }
         "#;
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r#"
             package ch.emilycares;
 
@@ -2451,7 +2652,7 @@ public class Test {
         }
         ";
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r"
             package ch.emilycares;
 
@@ -2501,7 +2702,7 @@ public class Test {
         }
         ";
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r"
             package ch.emilycares;
 
@@ -2528,7 +2729,7 @@ public class Test {
         }
         ";
 
-        let o = internal(content, SPACE).unwrap();
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
         let expected = expect![[r"
             public interface Test {
                 void a();
@@ -2538,4 +2739,148 @@ public class Test {
         "]];
         expected.assert_eq(str::from_utf8(&o.unwrap_or_default()).unwrap());
     }
+
+    #[test]
+    fn member_blank_lines() {
+        let content = br"
+        public class Test {
+            private int a;
+            private int b;
+            public Test() {
+            }
+            public int a() {
+                return a;
+            }
+
+
+            public int b() {
+                return b;
+            }
+        }
+        ";
+
+        let o = internal(content, &FormatOptions::new(SPACE, WIDTH)).unwrap();
+        let expected = expect![[r"
+            public class Test {
+                private int a;
+                private int b;
+
+                public Test() {
+                }
+
+                public int a() {
+                    return a;
+                }
+
+                public int b() {
+                    return b;
+                }
+            }
+        "]];
+        expected.assert_eq(str::from_utf8(&o.unwrap_or_default()).unwrap());
+    }
+
+    #[test]
+    fn line_width() {
+        let content = br"
+        public class Test {
+            public int calculate(int first, int second, int third) {
+                return combine(first, second, third) + first * second + third;
+            }
+        }
+        ";
+
+        let o = internal(content, &FormatOptions::new(SPACE, 50)).unwrap();
+        let expected = expect![[r"
+            public class Test {
+                public int calculate(
+                    int first,
+                    int second,
+                    int third
+                ) {
+                    return combine(first, second, third) + first * second
+                         + third;
+                }
+            }
+        "]];
+        expected.assert_eq(str::from_utf8(&o.unwrap_or_default()).unwrap());
+    }
+
+    #[test]
+    fn import_order() {
+        let content = br"
+        package ch.emilycares;
+        import static org.junit.Assert.assertEquals;
+        import ch.emilycares.Other;
+        // lists
+        import java.util.List;
+        import javax.inject.Inject;
+        import java.io.File;
+
+        public class Test {
+        }
+        ";
+
+        let mut options = FormatOptions::new(SPACE, WIDTH);
+        options.import_order = vec![
+            "java".to_string(),
+            "javax".to_string(),
+            String::new(),
+            "static".to_string(),
+        ];
+        let o = internal(content, &options).unwrap();
+        let expected = expect![[r"
+            package ch.emilycares;
+            import java.io.File;
+            // lists
+            import java.util.List;
+
+            import javax.inject.Inject;
+
+            import ch.emilycares.Other;
+
+            import static org.junit.Assert.assertEquals;
+
+            public class Test {
+            }
+        "]];
+        expected.assert_eq(str::from_utf8(&o.unwrap_or_default()).unwrap());
+    }
+
+    #[test]
+    fn member_order() {
+        let content = br"
+        public class Test {
+            public int a() {
+                return a;
+            }
+            /** the value */
+            private int a;
+            public Test() {
+            }
+        }
+        ";
+
+        let mut options = FormatOptions::new(SPACE, WIDTH);
+        options.member_order = vec![
+            MemberKind::Fields,
+            MemberKind::Constructors,
+            MemberKind::Methods,
+        ];
+        let o = internal(content, &options).unwrap();
+        let expected = expect![[r"
+            public class Test {
+                /** the value */
+                private int a;
+
+                public Test() {
+                }
+
+                public int a() {
+                    return a;
+                }
+            }
+        "]];
+        expected.assert_eq(str::from_utf8(&o.unwrap_or_default()).unwrap());
+    }
 }
@@ -1,21 +1,59 @@
-use std::process::{Command, Stdio};
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
 
 use compile::{CompileErrorMessage, parse_compile_errors};
 
+pub const COMPILE_JAVA: &str = "compileJava";
+
 #[must_use]
 pub fn compile_java(
     executable_gradle: &str,
     project_dir: &str,
 ) -> Option<Vec<CompileErrorMessage>> {
-    run_compile_java(executable_gradle, project_dir).map(|log| parse_compile_errors(&log))
+    compile_task(executable_gradle, project_dir, COMPILE_JAVA)
+}
+
+/// Run a compile task like `:app:compileJava`
+#[must_use]
+pub fn compile_task(
+    executable_gradle: &str,
+    project_dir: &str,
+    task: &str,
+) -> Option<Vec<CompileErrorMessage>> {
+    run_compile_java(executable_gradle, project_dir, task).map(|log| parse_compile_errors(&log))
+}
+
+/// `compileJava` of the subproject of the file, the subproject is the closest folder with a
+/// build script
+#[must_use]
+pub fn subproject_task(project_dir: &Path, file: &Path) -> String {
+    let subproject = file
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(project_dir) && *dir != project_dir)
+        .find(|dir| dir.join("build.gradle").exists() || dir.join("build.gradle.kts").exists())
+        .and_then(|dir| dir.strip_prefix(project_dir).ok());
+    let Some(subproject) = subproject else {
+        return COMPILE_JAVA.to_string();
+    };
+    let mut task = String::new();
+    for part in subproject.components() {
+        task.push(':');
+        task.push_str(&part.as_os_str().to_string_lossy());
+    }
+    task.push(':');
+    task.push_str(COMPILE_JAVA);
+    task
 }
 
 /// Telling gradle to give java compiler errors
-fn run_compile_java(executable_gradle: &str, project_dir: &str) -> Option<String> {
+fn run_compile_java(executable_gradle: &str, project_dir: &str, task: &str) -> Option<String> {
     // ./gradlew compileJava -q
     let child = Command::new(executable_gradle)
         .current_dir(project_dir)
-        .arg("compileJava")
+        .arg(task)
         .arg("-q")
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -30,9 +68,26 @@ fn run_compile_java(executable_gradle: &str, project_dir: &str) -> Option<String
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use compile::parse_compile_errors;
     use expect_test::expect;
 
+    use crate::compile::subproject_task;
+
+    #[test]
+    fn task_of_subproject() {
+        let dir = std::env::temp_dir().join(format!("gradle-task-{}", std::process::id()));
+        let source = dir.join("libs/core/src/main/java/org");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(dir.join("build.gradle"), "").unwrap();
+        let file = source.join("Core.java");
+        assert_eq!(subproject_task(&dir, &file), "compileJava");
+        fs::write(dir.join("libs/core/build.gradle.kts"), "").unwrap();
+        assert_eq!(subproject_task(&dir, &file), ":libs:core:compileJava");
+        let _ = fs::remove_dir_all(dir);
+    }
+
     #[test]
     fn gradle_compile() {
         let inp = include_str!("../tests/compile_basic.txt");
@@ -6,3 +6,4 @@
 
 pub mod compile;
 pub mod project;
+pub mod tree;
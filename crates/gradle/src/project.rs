@@ -1,11 +1,15 @@
 use common::deps::{deps_base, deps_get_source};
+use common::language_level::{LanguageLevel, parse_release};
 use common::{Dependency, TaskProgress, deps_dir};
 use dto::{Class, ClassFolder, SourceDestination};
+use loader::LoaderError;
+use loader::classpath::{Classpath, classpath_path};
 use maven::m2::{self, pom_m2, pom_sources_jar};
 use maven::update::{CurlClient, pom_source_jar_url};
 use my_string::MyString;
 use my_string::smol_str::ToSmolStr;
 use std::collections::HashSet;
+use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
 use std::str::{Utf8Error, from_utf8};
@@ -18,16 +22,64 @@ use std::{
 };
 use tokio::task::JoinSet;
 
+/// Source sets of the projects of the build when the init script ran before, otherwise the
+/// default source folders. `progress` gets the number of parsed files and the total
 #[must_use]
-pub fn load_project_folders(project_dir: &Path) -> Vec<Class> {
-    let mut out = vec![];
-
-    out.extend(loader::load_java_files(project_dir.join("src/main/java")));
-    out.extend(loader::load_java_files(project_dir.join("src/test/java")));
+pub fn load_project_folders(
+    project_dir: &Path,
+    progress: impl Fn(usize, usize) + Sync,
+) -> Vec<Class> {
+    let folders = cached_init_script_output(project_dir).map_or_else(
+        || {
+            let mut folders = default_source_dirs(project_dir);
+            folders.extend(generated_source_dirs(project_dir));
+            folders
+        },
+        |output| {
+            let generated = output
+                .projects
+                .iter()
+                .flat_map(|p| generated_source_dirs(Path::new(&p.dir)));
+            output
+                .source_dirs()
+                .map(PathBuf::from)
+                .chain(generated)
+                .collect()
+        },
+    );
+    loader::load_java_folders(folders, progress)
+}
 
+/// Folders that the annotation processors of the compile tasks generated sources into
+#[must_use]
+pub fn generated_source_dirs(project_dir: &Path) -> Vec<PathBuf> {
+    let mut out: Vec<PathBuf> =
+        fs::read_dir(project_dir.join("build/generated/sources/annotationProcessor/java"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|e| e.path())
+            .collect();
+    out.sort_unstable();
     out
 }
 
+fn default_source_dirs(project_dir: &Path) -> Vec<PathBuf> {
+    vec![
+        project_dir.join("src/main/java"),
+        project_dir.join("src/test/java"),
+    ]
+}
+
+/// Shard that caches the classes of a jar of the classpath
+#[must_use]
+pub fn get_jar_shard_path(jar: &Path, shards_dir: &Path) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    jar.hash(&mut hasher);
+    shards_dir.join(format!("{}.cfc", hasher.finish()))
+}
+
 #[must_use]
 pub fn get_gradle_cache_path(project_dir: &Path, project_cache_dir: &Path) -> PathBuf {
     let mut hasher = DefaultHasher::new();
@@ -46,8 +98,16 @@ pub fn ensure_init_script() -> Result<PathBuf, GradleProjectError> {
         br#"
 allprojects {
     tasks.register("printLspClasspath") {
-        doLast {        
-            project.sourceSets.each { ss ->
+        doLast {
+            println("JAVA_LSP_PROJECT:" + project.projectDir.absolutePath)
+            def sourceSets = project.extensions.findByName("sourceSets")
+            if (sourceSets == null) {
+                return
+            }
+            sourceSets.each { ss ->
+                ss.java.srcDirs.each {
+                    println("JAVA_LSP_SOURCE:" + project.projectDir.absolutePath + "\t" + it.absolutePath)
+                }
                 ss.compileClasspath.each {
                     println("JAVA_LSP_CLASSPATH:" + it.absolutePath)
                 }
@@ -64,36 +124,254 @@ allprojects {
     Ok(file)
 }
 
-#[derive(Debug)]
+/// Files that change the output of the init script, version catalogs are checked separately
+const BUILD_SCRIPTS: [&str; 5] = [
+    "build.gradle",
+    "build.gradle.kts",
+    "settings.gradle",
+    "settings.gradle.kts",
+    "gradle.properties",
+];
+
+const SETTINGS_SCRIPTS: [&str; 2] = ["settings.gradle", "settings.gradle.kts"];
+
+/// Build scripts and the version catalogs in the `gradle` folder of the directory
+fn build_scripts(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    let catalogs = fs::read_dir(dir.join("gradle"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.ends_with(".versions.toml"))
+        });
+    BUILD_SCRIPTS.iter().map(|s| dir.join(s)).chain(catalogs)
+}
+
+/// Builds that the settings script includes with `includeBuild`, nested included builds are
+/// included too
+#[must_use]
+pub fn included_builds(project_dir: &Path) -> Vec<PathBuf> {
+    let mut out: Vec<PathBuf> = vec![];
+    let mut queue = vec![project_dir.to_path_buf()];
+    while let Some(dir) = queue.pop() {
+        for settings in SETTINGS_SCRIPTS {
+            let Ok(content) = fs::read_to_string(dir.join(settings)) else {
+                continue;
+            };
+            for include in parse_include_builds(&content) {
+                let include = dir.join(include);
+                let include = fs::canonicalize(&include).unwrap_or(include);
+                if include != project_dir && !out.contains(&include) {
+                    out.push(include.clone());
+                    queue.push(include);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Paths of `includeBuild("..")` and `includeBuild '..'` in a groovy or kotlin settings script
+fn parse_include_builds(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.starts_with("//"))
+        .filter_map(|l| l.strip_prefix("includeBuild"))
+        .filter_map(|l| {
+            let l = l.trim_start().trim_start_matches('(').trim_start();
+            let quote = l.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+            let l = &l[1..];
+            l.find(quote).map(|end| &l[..end])
+        })
+        .collect()
+}
+
+/// Release and preview features of the `build.gradle` or `build.gradle.kts` of the project
+#[must_use]
+pub fn language_level(project_dir: &Path) -> LanguageLevel {
+    ["build.gradle", "build.gradle.kts"]
+        .iter()
+        .filter_map(|s| fs::read_to_string(project_dir.join(s)).ok())
+        .map(|content| parse_language_level(&content))
+        .find(|level| level.release.is_some() || level.preview)
+        .unwrap_or_default()
+}
+
+/// `options.release` wins over `sourceCompatibility` and the `languageVersion` of the toolchain
+fn parse_language_level(content: &str) -> LanguageLevel {
+    let lines: Vec<&str> = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.starts_with("//"))
+        .collect();
+    let release = ["options.release", "sourceCompatibility", "languageVersion"]
+        .iter()
+        .find_map(|key| lines.iter().find_map(|l| setting(l, key)));
+    LanguageLevel {
+        release,
+        preview: lines.iter().any(|l| l.contains("--enable-preview")),
+    }
+}
+
+/// Release of `key = 17`, `key.set(17)` or `key = JavaLanguageVersion.of(17)`
+fn setting(line: &str, key: &str) -> Option<u32> {
+    let (_, value) = line.split_once(key)?;
+    let value = value.trim_start();
+    let value = value.strip_prefix(".set").unwrap_or(value).trim_start();
+    let value = value.trim_start_matches(['=', '(']).trim_start();
+    let value = value
+        .strip_prefix("JavaLanguageVersion.of(")
+        .unwrap_or(value);
+    let end = value
+        .find(|c: char| !(c.is_alphanumeric() || matches!(c, '.' | '_' | '"' | '\'')))
+        .unwrap_or(value.len());
+    parse_release(&value[..end])
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
 pub struct InitScriptOutput {
     class_path: HashSet<String>,
+    pub projects: Vec<GradleProject>,
 }
 
-pub fn run_init_script(executable_gradle: String) -> Result<InitScriptOutput, GradleProjectError> {
-    let script = ensure_init_script()?;
-    let out = Command::new(executable_gradle)
-        .arg("--init-script")
-        .arg(script)
-        .arg("printLspClasspath")
-        .output()
-        .map_err(GradleProjectError::IO)?;
-
-    let content = from_utf8(&out.stdout).map_err(GradleProjectError::Utf8)?;
-    let mut class_path = HashSet::new();
-    for p in content.lines() {
-        if p.starts_with("JAVA_LSP_CLASSPATH:") {
-            let value = p
-                .trim_start_matches("JAVA_LSP_CLASSPATH:")
-                .replace('\\', "/");
-            if std::path::Path::new(&value)
+/// Project of a gradle build with the folders of its source sets
+#[derive(Debug, PartialEq, Eq)]
+pub struct GradleProject {
+    pub dir: String,
+    pub source_dirs: Vec<String>,
+}
+
+impl InitScriptOutput {
+    /// Jars of the classpath, jars that other projects of the build produce are resolved to
+    /// their sources
+    fn jars(&self) -> Vec<String> {
+        let outputs: Vec<String> = self
+            .projects
+            .iter()
+            .map(|p| format!("{}/build/", p.dir))
+            .collect();
+        let mut jars: Vec<String> = self
+            .class_path
+            .iter()
+            .filter(|jar| !outputs.iter().any(|o| jar.starts_with(o)))
+            .cloned()
+            .collect();
+        jars.sort_unstable();
+        jars
+    }
+
+    pub fn source_dirs(&self) -> impl Iterator<Item = &String> {
+        self.projects.iter().flat_map(|p| &p.source_dirs)
+    }
+}
+
+/// Lines of the init script that start with `JAVA_LSP_`
+#[must_use]
+pub fn parse_init_script_output(content: &str) -> InitScriptOutput {
+    let mut out = InitScriptOutput::default();
+    for line in content.lines() {
+        let line = line.replace('\\', "/");
+        if let Some(value) = line.strip_prefix("JAVA_LSP_CLASSPATH:") {
+            if Path::new(value)
                 .extension()
                 .is_some_and(|ext| ext.eq_ignore_ascii_case("jar"))
             {
-                class_path.insert(value);
+                out.class_path.insert(value.to_string());
             }
+        } else if let Some(dir) = line.strip_prefix("JAVA_LSP_PROJECT:") {
+            project(&mut out.projects, dir);
+        } else if let Some(value) = line.strip_prefix("JAVA_LSP_SOURCE:")
+            && let Some((dir, source_dir)) = value.split_once('\t')
+        {
+            let project = project(&mut out.projects, dir);
+            if !project.source_dirs.iter().any(|s| s == source_dir) {
+                project.source_dirs.push(source_dir.to_string());
+            }
+        }
+    }
+    out
+}
+
+fn project<'a>(projects: &'a mut Vec<GradleProject>, dir: &str) -> &'a mut GradleProject {
+    let index = projects
+        .iter()
+        .position(|p| p.dir == dir)
+        .unwrap_or_else(|| {
+            projects.push(GradleProject {
+                dir: dir.to_string(),
+                source_dirs: Vec::new(),
+            });
+            projects.len() - 1
+        });
+    &mut projects[index]
+}
+
+#[must_use]
+pub fn get_init_script_cache_path(project_dir: &Path, project_cache_dir: &Path) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    project_dir.hash(&mut hasher);
+    project_cache_dir.join(format!("{}.gradle.txt", hasher.finish()))
+}
+
+/// Output of the last run of the init script, none when a build script changed since
+#[must_use]
+pub fn cached_init_script_output(project_dir: &Path) -> Option<InitScriptOutput> {
+    let cache_path = get_init_script_cache_path(project_dir, &common::project_cache_dir());
+    let cached = fs::metadata(&cache_path).and_then(|m| m.modified()).ok()?;
+    let output = parse_init_script_output(&fs::read_to_string(&cache_path).ok()?);
+    let dirs = std::iter::once(project_dir.to_path_buf())
+        .chain(output.projects.iter().map(|p| PathBuf::from(&p.dir)));
+    for script in dirs.flat_map(|dir| build_scripts(&dir).collect::<Vec<_>>()) {
+        if let Ok(modified) = fs::metadata(script).and_then(|m| m.modified())
+            && modified > cached
+        {
+            return None;
         }
     }
-    Ok(InitScriptOutput { class_path })
+    Some(output)
+}
+
+/// Run the init script in the project and in its included builds, the output is cached until a
+/// build script changes
+pub fn run_init_script(
+    executable_gradle: &str,
+    project_dir: &Path,
+) -> Result<InitScriptOutput, GradleProjectError> {
+    if let Some(output) = cached_init_script_output(project_dir) {
+        return Ok(output);
+    }
+    let script = ensure_init_script()?;
+    let mut lines = String::new();
+    let mut success = true;
+    for dir in std::iter::once(project_dir.to_path_buf()).chain(included_builds(project_dir)) {
+        let out = Command::new(executable_gradle)
+            .current_dir(&dir)
+            .arg("--init-script")
+            .arg(&script)
+            .arg("printLspClasspath")
+            .output()
+            .map_err(GradleProjectError::IO)?;
+        success &= out.status.success();
+
+        let content = from_utf8(&out.stdout).map_err(GradleProjectError::Utf8)?;
+        lines.extend(
+            content
+                .lines()
+                .filter(|l| l.starts_with("JAVA_LSP_"))
+                .flat_map(|l| [l, "\n"]),
+        );
+    }
+    if success {
+        let cache_path = get_init_script_cache_path(project_dir, &common::project_cache_dir());
+        if let Err(e) = fs::write(&cache_path, &lines) {
+            eprintln!("Failed to save {} because: {e:?}", cache_path.display());
+        }
+    }
+    Ok(parse_init_script_output(&lines))
 }
 
 #[derive(Debug)]
@@ -109,8 +387,18 @@ pub async fn index_project(
     use_cache: bool,
     cache_path: PathBuf,
     executable_gradle: String,
+    project_dir: &Path,
 ) {
-    match index(class_map, sender, use_cache, cache_path, executable_gradle).await {
+    match index(
+        class_map,
+        sender,
+        use_cache,
+        cache_path,
+        &executable_gradle,
+        project_dir,
+    )
+    .await
+    {
         Ok(()) => (),
         Err(e) => {
             eprintln!("Got error while loading gradle project: {e:?}");
@@ -123,7 +411,8 @@ async fn index(
     sender: tokio::sync::watch::Sender<TaskProgress>,
     use_cache: bool,
     cache_path: PathBuf,
-    executable_gradle: String,
+    executable_gradle: &str,
+    project_dir: &Path,
 ) -> Result<(), GradleProjectError> {
     if use_cache
         && cache_path.exists()
@@ -140,14 +429,48 @@ async fn index(
     let deps_path = deps_dir();
     let deps_path = deps_path.as_path();
 
-    let inits = run_init_script(executable_gradle)?;
-    let tasks_number = u32::try_from(inits.class_path.len()).unwrap_or(1);
+    let source_sets_known = cached_init_script_output(project_dir).is_some();
+    let inits = run_init_script(executable_gradle, project_dir)?;
+    if !source_sets_known {
+        // Only the default source folders of the root project were loaded
+        let defaults = default_source_dirs(project_dir);
+        let folders = inits
+            .source_dirs()
+            .map(PathBuf::from)
+            .filter(|d| !defaults.contains(d))
+            .collect();
+        let classes = loader::load_java_folders(folders, |_, _| {});
+        if let Ok(mut cm) = class_map.write() {
+            for class in classes {
+                cm.insert(class.class_path.clone(), class);
+            }
+        }
+    }
+    let jars = inits.jars();
+    let tasks_number = u32::try_from(jars.len()).unwrap_or(1);
     let completed_number = Arc::new(AtomicU32::new(0));
     let m2 = m2::get_maven_m2_folder().map_err(GradleProjectError::MTwo)?;
     let client = Arc::new(CurlClient::new());
     let repo = Arc::new(maven::repository::central());
 
-    for p in inits.class_path {
+    let shards_dir = deps_path.join("jars");
+    let _ = fs::create_dir_all(&shards_dir);
+    let shards: Vec<(PathBuf, PathBuf)> = jars
+        .iter()
+        .map(|jar| {
+            (
+                PathBuf::from(jar),
+                get_jar_shard_path(Path::new(jar), &shards_dir),
+            )
+        })
+        .collect();
+    let classpath_path = classpath_path(&cache_path);
+    let old_classpath = Classpath::load(&classpath_path);
+    let diff = old_classpath.diff(&Classpath::new(shards.iter().map(|(j, s)| (j, s))));
+    old_classpath.drop_stale(&diff, &class_map);
+
+    for (p, (_, shard)) in jars.into_iter().zip(&shards) {
+        let shard = shard.clone();
         let sender = sender.clone();
         let completed_number = completed_number.clone();
         let source = get_dependency(&p).map_or(SourceDestination::None, |dep| {
@@ -157,12 +480,12 @@ async fn index(
             let source_file = Arc::new(pom_sources_jar(&dep, &pom_mtwo));
             let source_url = pom_source_jar_url(&dep, &repo.url);
 
-            {
+            if !source_file.exists() && cached_sources_jar(&dep).is_none() {
                 let deps_source = deps_source.clone();
                 let client = client.clone();
                 let repo = repo.clone();
                 handles.spawn(async move {
-                    maven::update::fetch_extract_source(
+                    maven::update::fetch_sources_jar(
                         source_file,
                         pom_mtwo,
                         deps_source,
@@ -183,7 +506,15 @@ async fn index(
         });
 
         handles.spawn(async move {
-            match loader::load_classes_jar(&p, source).await {
+            // Jars that did not change since the last load have a shard
+            let classes = loader::load_class_folder(&shard).or_else(|_| {
+                let classes = loader::load_classes_jar(&p, source)?;
+                if let Err(e) = loader::save_class_folder(&shard, &classes) {
+                    eprintln!("Failed to save shard of {p}, {e:?}");
+                }
+                Ok::<_, LoaderError>(classes)
+            });
+            match classes {
                 Ok(classes) => {
                     let a = completed_number.fetch_add(1, Ordering::Relaxed);
                     let message = p.clone();
@@ -211,6 +542,9 @@ async fn index(
     if let Err(e) = loader::save_class_folder(&cache_path, &class_folder) {
         eprintln!("Failed to save {} because: {e:?}", cache_path.display());
     }
+    if let Err(e) = Classpath::new(shards).save(&classpath_path) {
+        eprintln!("Failed to save {} because: {e:?}", classpath_path.display());
+    }
     if let Ok(mut cm) = class_map.write() {
         for class in class_folder.classes {
             cm.insert(class.class_path.clone(), class);
@@ -219,6 +553,28 @@ async fn index(
     Ok(())
 }
 
+/// Sources jar that gradle downloaded into its cache
+/// `~/.gradle/caches/modules-2/files-2.1/GROUP/ARTIFACT/VERSION/HASH/ARTIFACT-VERSION-sources.jar`
+#[must_use]
+pub fn cached_sources_jar(dep: &Dependency) -> Option<PathBuf> {
+    let gradle_home = std::env::var_os("GRADLE_USER_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".gradle")))?;
+    let version_dir = gradle_home
+        .join("caches")
+        .join("modules-2")
+        .join("files-2.1")
+        .join(&dep.group_id)
+        .join(&dep.artivact_id)
+        .join(&dep.version);
+    let name = format!("{}-{}-sources.jar", dep.artivact_id, dep.version);
+    std::fs::read_dir(version_dir)
+        .ok()?
+        .flatten()
+        .map(|hash| hash.path().join(&name))
+        .find(|jar| jar.exists())
+}
+
 fn get_dependency(p: &str) -> Option<Dependency> {
     let (_, p) = p.split_once(".gradle/caches/modules-2/files-2.1/")?;
     let mut spl = p.splitn(4, '/');
@@ -255,4 +611,99 @@ mod tests {
         "#]];
         expected.assert_debug_eq(&out);
     }
+
+    #[test]
+    fn language_levels() {
+        let groovy = "java {
+    sourceCompatibility = JavaVersion.VERSION_1_8
+    targetCompatibility = JavaVersion.VERSION_1_8
+}";
+        assert_eq!(
+            parse_language_level(groovy),
+            LanguageLevel {
+                release: Some(8),
+                preview: false
+            }
+        );
+        let kotlin = "java {
+    toolchain {
+        languageVersion.set(JavaLanguageVersion.of(21))
+    }
+}
+tasks.withType<JavaCompile> {
+    options.release = 17
+    options.compilerArgs.add(\"--enable-preview\")
+}";
+        assert_eq!(
+            parse_language_level(kotlin),
+            LanguageLevel {
+                release: Some(17),
+                preview: true
+            }
+        );
+        let toolchain = "java { toolchain { languageVersion = JavaLanguageVersion.of(21) } }";
+        assert_eq!(parse_language_level(toolchain).release, Some(21));
+        assert_eq!(
+            parse_language_level("sourceCompatibility = '11'").release,
+            Some(11)
+        );
+        assert_eq!(
+            parse_language_level("plugins { id 'java' }"),
+            LanguageLevel::default()
+        );
+    }
+
+    #[test]
+    fn include_builds() {
+        let groovy = "rootProject.name = 'app'
+includeBuild '../shared'
+// includeBuild 'old'
+includeBuild('plugins') {
+    name = 'build-logic'
+}
+include 'core'";
+        assert_eq!(parse_include_builds(groovy), vec!["../shared", "plugins"]);
+        let kotlin = "pluginManagement {
+    includeBuild(\"build-logic\")
+}
+includeBuild( \"libs/json\" )";
+        assert_eq!(
+            parse_include_builds(kotlin),
+            vec!["build-logic", "libs/json"]
+        );
+    }
+
+    #[test]
+    fn init_script_output() {
+        let content = "> Task :printLspClasspath
+JAVA_LSP_PROJECT:/p
+JAVA_LSP_SOURCE:/p\t/p/src/main/java
+JAVA_LSP_CLASSPATH:/p/build/classes/java/main
+JAVA_LSP_CLASSPATH:/home/u/.gradle/caches/lib-1.0.jar
+JAVA_LSP_PROJECT:/p/app
+JAVA_LSP_SOURCE:/p/app\t/p/app/src/main/java
+JAVA_LSP_SOURCE:/p/app\t/p/app/src/generated/java
+JAVA_LSP_SOURCE:/p/app\t/p/app/src/main/java
+JAVA_LSP_CLASSPATH:/p/build/libs/p.jar
+JAVA_LSP_CLASSPATH:/home/u/.gradle/caches/lib-1.0.jar
+";
+        let out = parse_init_script_output(content);
+        assert_eq!(
+            out.projects,
+            vec![
+                GradleProject {
+                    dir: "/p".to_string(),
+                    source_dirs: vec!["/p/src/main/java".to_string()],
+                },
+                GradleProject {
+                    dir: "/p/app".to_string(),
+                    source_dirs: vec![
+                        "/p/app/src/main/java".to_string(),
+                        "/p/app/src/generated/java".to_string(),
+                    ],
+                },
+            ]
+        );
+        assert_eq!(out.jars(), vec!["/home/u/.gradle/caches/lib-1.0.jar"]);
+    }
 }
@@ -0,0 +1,142 @@
+//! Dependency graph of the `dependencies` task
+//!
+//! Only the classpath configurations of the main and test source sets are read, a dependency
+//! has the configuration as its scope.
+use std::{path::Path, process::Command, str::from_utf8};
+
+use common::dependency_tree::{DependencyNode, build_tree};
+
+use crate::project::GradleProjectError;
+
+/// Configurations of the report that are read
+const CONFIGURATIONS: [&str; 4] = [
+    "compileClasspath",
+    "runtimeClasspath",
+    "testCompileClasspath",
+    "testRuntimeClasspath",
+];
+
+/// Dependency graph of the root project with the versions that won a conflict
+pub fn load_graph(
+    executable_gradle: &str,
+    project_dir: &Path,
+) -> Result<Vec<DependencyNode>, GradleProjectError> {
+    let out = Command::new(executable_gradle)
+        .current_dir(project_dir)
+        .arg("--console=plain")
+        .arg("dependencies")
+        .output()
+        .map_err(GradleProjectError::IO)?;
+    let content = from_utf8(&out.stdout).map_err(GradleProjectError::Utf8)?;
+    Ok(parse_graph(content))
+}
+
+fn parse_graph(content: &str) -> Vec<DependencyNode> {
+    let mut lines = Vec::new();
+    let mut configuration: Option<&str> = None;
+    for line in content.lines() {
+        let tree_line = ["+--- ", "\\--- ", "|    ", "     "]
+            .iter()
+            .any(|prefix| line.starts_with(prefix));
+        if !tree_line {
+            let name = line.split(" - ").next().unwrap_or_default();
+            configuration = CONFIGURATIONS.iter().copied().find(|c| *c == name);
+            continue;
+        }
+        let Some(configuration) = configuration else {
+            continue;
+        };
+        let content = line.trim_start_matches(['|', '+', '\\', '-', ' ']);
+        if let Some(node) = graph_node(content, configuration) {
+            lines.push(((line.len() - content.len()) / 5, node));
+        }
+    }
+    build_tree(lines)
+}
+
+/// `org.slf4j:slf4j-api:1.7.30 -> 2.0.9`, `project :lib` or `org.slf4j:slf4j-api:2.0.9 (*)`
+fn graph_node(content: &str, configuration: &str) -> Option<DependencyNode> {
+    let (content, omitted) = match content.rsplit_once(' ') {
+        // Constraints are not dependencies
+        Some((_, "(c)")) => return None,
+        Some((content, "(*)")) => (content, Some("omitted for duplicate")),
+        Some((content, "(n)")) => (content, Some("not resolved")),
+        Some((content, "FAILED")) => (content, Some("failed")),
+        _ => (content, None),
+    };
+    let scope = Some(configuration.to_string());
+    let omitted = omitted.map(ToString::to_string);
+    if let Some(path) = content.strip_prefix("project ") {
+        return Some(DependencyNode {
+            artifact_id: path.to_string(),
+            scope,
+            omitted,
+            ..Default::default()
+        });
+    }
+    let (requested, resolved) = content
+        .split_once(" -> ")
+        .map_or((content, None), |(requested, resolved)| {
+            (requested, Some(resolved))
+        });
+    let mut parts = requested.splitn(3, ':');
+    let group_id = parts.next()?;
+    let artifact_id = parts.next()?;
+    let version = parts.next().unwrap_or_default();
+    let (version, resolved_version) = match resolved {
+        Some(resolved) if version.is_empty() => (resolved, None),
+        Some(resolved) if resolved != version => (version, Some(resolved.to_string())),
+        _ => (version, None),
+    };
+    Some(DependencyNode {
+        group_id: group_id.to_string(),
+        artifact_id: artifact_id.to_string(),
+        version: version.to_string(),
+        scope,
+        optional: false,
+        resolved_version,
+        omitted,
+        children: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_graph;
+
+    #[test]
+    fn parse_dependencies_report() {
+        let graph = parse_graph(include_str!("../tests/dependencies_basic.txt"));
+        let names: Vec<(&str, &str)> = graph
+            .iter()
+            .map(|n| {
+                (
+                    n.artifact_id.as_str(),
+                    n.scope.as_deref().unwrap_or_default(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("slf4j-simple", "compileClasspath"),
+                ("slf4j-api", "compileClasspath"),
+                (":lib", "compileClasspath"),
+                ("slf4j-api", "testCompileClasspath"),
+                ("junit-jupiter", "testCompileClasspath"),
+            ]
+        );
+        let conflict = &graph[0].children[0];
+        assert_eq!(conflict.version, "1.7.30");
+        assert_eq!(conflict.resolved_version.as_deref(), Some("2.0.9"));
+        assert_eq!(
+            graph[2].children[0].omitted.as_deref(),
+            Some("omitted for duplicate")
+        );
+        assert_eq!(graph[4].version, "5.10.0");
+        assert_eq!(
+            graph[4].children[0].omitted.as_deref(),
+            Some("not resolved")
+        );
+    }
+}
@@ -24,6 +24,24 @@ async fn main() {
             };
             let _ = server::listen(port);
         }
+        Some(Command::ServerListen { address }) => {
+            unsafe {
+                std::env::set_var("RUST_BACKTRACE", "1");
+            };
+            let _ = server::listen_address(&address);
+        }
+        Some(Command::ServerPipe { name }) => {
+            unsafe {
+                std::env::set_var("RUST_BACKTRACE", "1");
+            };
+            let _ = server::pipe(&name);
+        }
+        Some(Command::ServerSocket { port }) => {
+            unsafe {
+                std::env::set_var("RUST_BACKTRACE", "1");
+            };
+            let _ = server::socket(port);
+        }
         Some(Command::ReloadDependencies) => reload_dependencies_cli().await,
         Some(Command::UpdateDependencies) => update_dependencies_cli().await,
         Some(Command::Lex { file }) => {
@@ -32,6 +50,9 @@ async fn main() {
         Some(Command::LexPos { file, pos }) => {
             cli::lex_pos(&file, pos);
         }
+        Some(Command::Ast { file, position }) => {
+            cli::ast_json(&file, position);
+        }
         Some(Command::AstCheck { file }) => {
             cli::ast_check(&file);
         }
@@ -54,6 +75,18 @@ async fn main() {
                 .unwrap();
             cli::ast_check_dir(op_dir.join("src")).unwrap();
         }
+        Some(Command::ParseCheck { folder }) => {
+            cli::parse_check_dir(folder).unwrap();
+        }
+        Some(Command::Check { folder, format }) => {
+            let code = server::check::check_cli(folder, format.as_deref()).await;
+            std::process::exit(code);
+        }
+        Some(Command::Symbols { query, folder }) => {
+            let folder = folder.unwrap_or_else(|| std::path::PathBuf::from("."));
+            let code = server::symbol_search::symbols_cli(&query, folder).await;
+            std::process::exit(code);
+        }
         Some(Command::IndexJdk { variant }) => {
             cli::index_jdk(variant).await;
         }
@@ -0,0 +1,278 @@
+//! JDKs that are installed on the machine and the one that is picked for a release
+//!
+//! The JDK of the `jdk_path` setting comes first, then `JAVA_HOME`, the java on the PATH, the
+//! jdks of the maven `toolchains.xml`, the toolchains that gradle provisioned or was pointed to
+//! and the java candidates of SDKMAN. The release of a JDK is read from its `release` file.
+//! A module gets the JDK of the setting, otherwise the oldest JDK that has its release.
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+use serde::Serialize;
+
+use crate::java_executable_location;
+
+/// JDKs of the last [`discover`]
+static INSTALLED: RwLock<Vec<JdkInstall>> = RwLock::new(Vec::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JdkSource {
+    Setting,
+    JavaHome,
+    Path,
+    MavenToolchains,
+    GradleToolchains,
+    Sdkman,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JdkInstall {
+    pub home: PathBuf,
+    /// Unknown when the JDK has no `release` file
+    pub release: Option<u32>,
+    pub source: JdkSource,
+}
+
+/// Find the installed JDKs and keep them for [`home`] and [`java_path`]. `toolchains` are the
+/// homes of the maven `toolchains.xml`
+pub fn discover(
+    setting: Option<&Path>,
+    path: &OsString,
+    toolchains: Vec<PathBuf>,
+) -> Vec<JdkInstall> {
+    let mut homes: Vec<(PathBuf, JdkSource)> = Vec::new();
+    if let Some(setting) = setting {
+        homes.push((setting.to_path_buf(), JdkSource::Setting));
+    }
+    if let Some(java_home) = env::var_os("JAVA_HOME").filter(|h| !h.is_empty()) {
+        homes.push((PathBuf::from(java_home), JdkSource::JavaHome));
+    }
+    if let Some(home) = java_executable_location(path)
+        .and_then(|java| fs::canonicalize(java).ok())
+        .and_then(|java| Some(java.parent()?.parent()?.to_path_buf()))
+    {
+        homes.push((home, JdkSource::Path));
+    }
+    homes.extend(
+        toolchains
+            .into_iter()
+            .map(|h| (h, JdkSource::MavenToolchains)),
+    );
+    homes.extend(
+        gradle_homes()
+            .into_iter()
+            .map(|h| (h, JdkSource::GradleToolchains)),
+    );
+    homes.extend(sdkman_homes().into_iter().map(|h| (h, JdkSource::Sdkman)));
+
+    let mut out: Vec<JdkInstall> = Vec::new();
+    let mut seen = Vec::new();
+    for (home, source) in homes {
+        let Ok(canonical) = fs::canonicalize(&home) else {
+            continue;
+        };
+        if seen.contains(&canonical) {
+            continue;
+        }
+        seen.push(canonical);
+        out.push(JdkInstall {
+            release: release_of(&home),
+            home,
+            source,
+        });
+    }
+    if let Ok(mut installed) = INSTALLED.write() {
+        installed.clone_from(&out);
+    }
+    out
+}
+
+/// JDKs of the last [`discover`]
+#[must_use]
+pub fn installed() -> Vec<JdkInstall> {
+    INSTALLED.read().map(|i| i.clone()).unwrap_or_default()
+}
+
+/// The JDK of the setting, otherwise the oldest JDK that compiles the release. Without a release
+/// or a JDK for it the first one is used
+#[must_use]
+pub fn select(installs: &[JdkInstall], release: Option<u32>) -> Option<&JdkInstall> {
+    if let Some(setting) = installs.iter().find(|i| i.source == JdkSource::Setting) {
+        return Some(setting);
+    }
+    release
+        .and_then(|release| {
+            installs
+                .iter()
+                .filter(|i| i.release.is_some_and(|r| r >= release))
+                .min_by_key(|i| i.release)
+        })
+        .or_else(|| installs.first())
+}
+
+/// Home of the installed JDK for the release
+#[must_use]
+pub fn home(release: Option<u32>) -> Option<PathBuf> {
+    let installed = INSTALLED.read().ok()?;
+    select(&installed, release).map(|i| i.home.clone())
+}
+
+/// PATH with the bin folder of the JDK for the release first
+#[must_use]
+pub fn java_path(release: Option<u32>, path: OsString) -> OsString {
+    let Some(home) = home(release) else {
+        return path;
+    };
+    let paths = std::iter::once(home.join("bin")).chain(env::split_paths(&path));
+    env::join_paths(paths).unwrap_or(path)
+}
+
+/// Feature release of the `JAVA_VERSION` in the `release` file of the JDK
+#[must_use]
+pub fn release_of(home: &Path) -> Option<u32> {
+    let content = fs::read_to_string(home.join("release")).ok()?;
+    parse_release_file(&content)
+}
+
+fn parse_release_file(content: &str) -> Option<u32> {
+    let version = content
+        .lines()
+        .find_map(|line| line.strip_prefix("JAVA_VERSION="))?
+        .trim()
+        .trim_matches('"');
+    let mut parts = version.split(['.', '_', '-', '+']);
+    match parts.next()? {
+        "1" => parts.next()?.parse().ok(),
+        major => major.parse().ok(),
+    }
+}
+
+/// JDKs that gradle downloaded into `jdks` of its user home and the folders of
+/// `org.gradle.java.installations.paths` in its `gradle.properties`
+fn gradle_homes() -> Vec<PathBuf> {
+    let Some(gradle_home) = env::var_os("GRADLE_USER_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".gradle")))
+    else {
+        return vec![];
+    };
+    let mut out = fs::read_to_string(gradle_home.join("gradle.properties"))
+        .map(|content| installation_paths(&content))
+        .unwrap_or_default();
+    out.extend(
+        sub_folders(&gradle_home.join("jdks"))
+            .into_iter()
+            .map(|jdk| {
+                // The archives of macOS have the home in the bundle
+                let bundle = jdk.join("Contents").join("Home");
+                if bundle.is_dir() { bundle } else { jdk }
+            }),
+    );
+    out
+}
+
+fn installation_paths(properties: &str) -> Vec<PathBuf> {
+    properties
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == "org.gradle.java.installations.paths").then_some(value)
+        })
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// `candidates/java` of `SDKMAN_DIR`, the `current` link is the same as one of the others
+fn sdkman_homes() -> Vec<PathBuf> {
+    let Some(sdkman) = env::var_os("SDKMAN_DIR")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".sdkman")))
+    else {
+        return vec![];
+    };
+    sub_folders(&sdkman.join("candidates").join("java"))
+        .into_iter()
+        .filter(|p| p.file_name().is_some_and(|n| n != "current"))
+        .collect()
+}
+
+fn sub_folders(dir: &Path) -> Vec<PathBuf> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut out: Vec<PathBuf> = read_dir
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    out.sort();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{JdkInstall, JdkSource, installation_paths, parse_release_file, select};
+
+    #[test]
+    fn release_file() {
+        assert_eq!(
+            parse_release_file("IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"17.0.9\"\n"),
+            Some(17)
+        );
+        assert_eq!(parse_release_file("JAVA_VERSION=\"1.8.0_392\""), Some(8));
+        assert_eq!(parse_release_file("JAVA_VERSION=\"21\""), Some(21));
+        assert_eq!(parse_release_file("JAVA_VERSION=\"22-ea\""), Some(22));
+        assert_eq!(parse_release_file("OS_NAME=\"Linux\""), None);
+    }
+
+    #[test]
+    fn gradle_installation_paths() {
+        let properties = "
+org.gradle.jvmargs=-Xmx2g
+org.gradle.java.installations.paths=/opt/jdk-11, /opt/jdk-17
+";
+        assert_eq!(
+            installation_paths(properties),
+            [PathBuf::from("/opt/jdk-11"), PathBuf::from("/opt/jdk-17")]
+        );
+    }
+
+    #[test]
+    fn selected() {
+        let jdk = |release, source| JdkInstall {
+            home: PathBuf::from(format!("/opt/jdk-{release}")),
+            release: Some(release),
+            source,
+        };
+        let installs = vec![
+            jdk(21, JdkSource::JavaHome),
+            jdk(11, JdkSource::Sdkman),
+            jdk(17, JdkSource::Sdkman),
+        ];
+        let release = |release| select(&installs, release).and_then(|i| i.release);
+        assert_eq!(release(None), Some(21));
+        assert_eq!(release(Some(11)), Some(11));
+        assert_eq!(release(Some(16)), Some(17));
+        assert_eq!(release(Some(25)), Some(21));
+        assert_eq!(release(Some(8)), Some(11));
+
+        let mut with_setting = installs.clone();
+        with_setting.push(jdk(8, JdkSource::Setting));
+        assert_eq!(
+            select(&with_setting, Some(21)).map(|i| i.source),
+            Some(JdkSource::Setting)
+        );
+        assert_eq!(select(&[], Some(17)), None);
+    }
+}
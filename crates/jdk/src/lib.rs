@@ -23,6 +23,8 @@ use loader::{LoaderError, load_class_files};
 use my_string::{MyString, smol_str::ToSmolStr};
 use tokio::task::JoinSet;
 
+pub mod installations;
+
 #[cfg(not(target_os = "windows"))]
 const EXECUTABLE_JAVA: &str = "java";
 #[cfg(target_os = "windows")]
@@ -50,34 +52,85 @@ pub enum JdkError {
     Loader(LoaderError),
 }
 
+/// Index the JDK found in the PATH. With a release the platform classes of that release are
+/// read from `lib/ct.sym`, the full JDK is used when it has no signatures for the release.
 pub async fn load_classes(
     class_map: Arc<RwLock<HashMap<MyString, Class>>>,
     sender: tokio::sync::watch::Sender<TaskProgress>,
     path: &OsString,
-) -> Result<(), JdkError> {
+    release: Option<u32>,
+) -> Result<usize, JdkError> {
     let (java_path, op_dir) = get_work_dirs(path)?;
+    if let Some(release) = release {
+        match load_release(&java_path, &op_dir, release).await {
+            Ok(Some(class_folder)) => return Ok(insert_classes(&class_map, class_folder)),
+            Ok(None) => (),
+            Err(e) => eprintln!("Failed to load release {release} from ct.sym: {e:?}"),
+        }
+    }
     let cache_path = op_dir.join(JDK_CFC);
 
     if cache_path.exists()
         && let Ok(classes) = loader::load_class_folder(&cache_path)
     {
-        if let Ok(mut cm) = class_map.write() {
-            for class in classes.classes {
-                cm.insert(class.class_path.clone(), class);
-            }
-        }
-        return Ok(());
+        return Ok(insert_classes(&class_map, classes));
     }
     let class_folder = load_jdk(&java_path, &op_dir, ForceLoader::None, sender).await?;
     if let Err(e) = loader::save_class_folder(cache_path, &class_folder) {
         eprintln!("Failed to save {JDK_CFC} because: {e:?}");
     }
+    Ok(insert_classes(&class_map, class_folder))
+}
+
+fn insert_classes(
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    class_folder: ClassFolder,
+) -> usize {
+    let loaded = class_folder.classes.len();
     if let Ok(mut cm) = class_map.write() {
         for class in class_folder.classes {
             cm.insert(class.class_path.clone(), class);
         }
     }
-    Ok(())
+    loaded
+}
+
+/// Classes of a release from `lib/ct.sym`, cached in `jdk_<release>.cfc`. None when the JDK
+/// has no `ct.sym` or no signatures for the release, the release of the JDK itself is not
+/// part of `ct.sym`.
+pub async fn load_release(
+    java_path: &Path,
+    op_dir: &Path,
+    release: u32,
+) -> Result<Option<ClassFolder>, JdkError> {
+    let cache_path = op_dir.join(format!("jdk_{release}.cfc"));
+    if cache_path.exists()
+        && let Ok(classes) = loader::load_class_folder(&cache_path)
+    {
+        return Ok(Some(classes));
+    }
+    let ct_sym = get_ct_sym(java_path);
+    if !ct_sym.exists() {
+        return Ok(None);
+    }
+    extract_source_zip(java_path, op_dir).await?;
+    let source_dir = op_dir.join("src");
+    let source_dir = source_dir.to_str().ok_or(JdkError::Str)?;
+    let class_folder =
+        loader::load_classes_ct_sym(&ct_sym, release, source_dir).map_err(JdkError::Loader)?;
+    if class_folder.classes.is_empty() {
+        return Ok(None);
+    }
+    if let Err(e) = loader::save_class_folder(cache_path, &class_folder) {
+        eprintln!("Failed to save jdk_{release}.cfc because: {e:?}");
+    }
+    Ok(Some(class_folder))
+}
+
+fn get_ct_sym(java_path: &Path) -> PathBuf {
+    let mut path = java_path.to_path_buf();
+    path.pop();
+    path.join("lib").join("ct.sym")
 }
 
 fn java_executable_location(path: &OsString) -> Option<PathBuf> {
@@ -139,6 +192,18 @@ pub async fn load_jdk(
         return load_modules_with_command(jimage_executable, modules_file, op_dir, source_dir);
     }
 
+    // The jmods are zip files that are read directly, JDKs that link their runtime image
+    // without them only have the modules file
+    if force_loader.is_jmod() {
+        let jmods_dir = get_jmods_dir(java_path);
+        if jmods_dir.exists() {
+            let out = load_jmods(jmods_dir, op_dir, sender).await;
+            if out.as_ref().is_ok_and(|c| !c.classes.is_empty()) {
+                return out;
+            }
+        }
+    }
+
     if modules_file.exists() && force_loader.is_modules() {
         let source_dir = op_dir.join("src");
         let source_dir = source_dir.to_str().ok_or(JdkError::Str)?;
@@ -147,20 +212,6 @@ pub async fn load_jdk(
             return out;
         }
     }
-
-    if force_loader.is_jmod() {
-        let jmods_dir = get_jmods_dir(java_path);
-        if jmods_dir.exists() {
-            let mut jmod_executable = java_path.to_path_buf();
-            jmod_executable.push("jmod");
-            if cfg!(windows) {
-                jmod_executable.set_extension("exe");
-            }
-            if jmod_executable.exists() {
-                return load_jmods(jmods_dir, op_dir, sender).await;
-            }
-        }
-    }
     if modules_file.exists() && force_loader.is_modules() {
         let jimage_executable = get_jimage_executable(java_path);
         let source_dir = op_dir.join("src");
@@ -236,7 +287,6 @@ async fn load_old(java_path: &Path, op_dir: &Path) -> Result<ClassFolder, JdkErr
                 .into(),
         ),
     )
-    .await
     .map_err(JdkError::ParserLoader)?;
 
     load_javafx(java_path, op_dir, jre_lib, &mut classes).await?;
@@ -266,7 +316,6 @@ async fn load_javafx(
                         .into(),
                 ),
             )
-            .await
             .map_err(JdkError::ParserLoader)?;
             classes.append(classes_jfx);
         }
@@ -314,7 +363,6 @@ async fn load_jmods(
                                         .into(),
                                 ),
                             )
-                            .await
                             .map_err(JdkError::ParserLoader);
                             let a = completed_number.fetch_add(1, Ordering::Relaxed);
                             let _ = sender.send(TaskProgress {
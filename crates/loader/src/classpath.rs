@@ -0,0 +1,253 @@
+//! Jars of a project classpath and the shards that cache their classes
+//!
+//! The classpath of the last load is saved next to the project cache. When the build file changes
+//! only the classes of removed or changed jars are dropped and only new or changed jars are
+//! indexed again, the shards of the other jars are reused.
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    fs,
+    hash::BuildHasher,
+    path::{Path, PathBuf},
+    sync::RwLock,
+    time::UNIX_EPOCH,
+};
+
+use dto::Class;
+use my_string::MyString;
+
+use crate::{LoaderError, load_class_folder};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ClasspathEntry {
+    len: u64,
+    modified: u64,
+    shard: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Classpath {
+    /// Jar path to its size, modification time and shard
+    jars: BTreeMap<String, ClasspathEntry>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ClasspathDiff {
+    pub removed: Vec<String>,
+    /// Jars that were rewritten, for example a snapshot
+    pub changed: Vec<String>,
+    pub added: Vec<String>,
+}
+
+impl ClasspathDiff {
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.changed.is_empty() && self.added.is_empty()
+    }
+}
+
+impl Classpath {
+    /// Jars with their shard, jars that do not exist are skipped
+    pub fn new<J: AsRef<Path>, S: AsRef<Path>>(jars: impl IntoIterator<Item = (J, S)>) -> Self {
+        let mut out = Self::default();
+        for (jar, shard) in jars {
+            let Ok(metadata) = fs::metadata(&jar) else {
+                continue;
+            };
+            let (Some(jar), Some(shard)) = (jar.as_ref().to_str(), shard.as_ref().to_str()) else {
+                continue;
+            };
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_secs());
+            out.jars.insert(
+                jar.to_owned(),
+                ClasspathEntry {
+                    len: metadata.len(),
+                    modified,
+                    shard: shard.to_owned(),
+                },
+            );
+        }
+        out
+    }
+
+    /// A missing or broken file is a empty classpath
+    #[must_use]
+    pub fn load(path: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        let jars = content
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.split('\t');
+                let len = parts.next()?.parse().ok()?;
+                let modified = parts.next()?.parse().ok()?;
+                let jar = parts.next()?.to_owned();
+                let shard = parts.next()?.to_owned();
+                Some((
+                    jar,
+                    ClasspathEntry {
+                        len,
+                        modified,
+                        shard,
+                    },
+                ))
+            })
+            .collect();
+        Self { jars }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), LoaderError> {
+        let mut content = String::new();
+        for (jar, entry) in &self.jars {
+            let _ = writeln!(
+                content,
+                "{}\t{}\t{jar}\t{}",
+                entry.len, entry.modified, entry.shard
+            );
+        }
+        fs::write(path, content).map_err(LoaderError::IO)
+    }
+
+    #[must_use]
+    pub fn diff(&self, new: &Self) -> ClasspathDiff {
+        let mut out = ClasspathDiff::default();
+        for (jar, entry) in &self.jars {
+            match new.jars.get(jar) {
+                None => out.removed.push(jar.clone()),
+                Some(new) if new != entry => out.changed.push(jar.clone()),
+                Some(_) => (),
+            }
+        }
+        out.added = new
+            .jars
+            .keys()
+            .filter(|jar| !self.jars.contains_key(*jar))
+            .cloned()
+            .collect();
+        out
+    }
+
+    /// Remove the classes of removed and changed jars from the class map. The shards of changed
+    /// jars are deleted so that they are indexed again.
+    pub fn drop_stale<S: BuildHasher>(
+        &self,
+        diff: &ClasspathDiff,
+        class_map: &RwLock<HashMap<MyString, Class, S>>,
+    ) {
+        let stale = diff.removed.iter().chain(&diff.changed);
+        let mut class_paths = vec![];
+        for jar in stale {
+            let Some(entry) = self.jars.get(jar) else {
+                continue;
+            };
+            if let Ok(classes) = load_class_folder(&entry.shard) {
+                class_paths.extend(classes.classes.into_iter().map(|c| c.class_path));
+            }
+        }
+        for jar in &diff.changed {
+            if let Some(entry) = self.jars.get(jar) {
+                let _ = fs::remove_file(&entry.shard);
+            }
+        }
+        if class_paths.is_empty() {
+            return;
+        }
+        if let Ok(mut class_map) = class_map.write() {
+            for class_path in class_paths {
+                class_map.remove(&class_path);
+            }
+        }
+    }
+}
+
+/// Path of the classpath that belongs to a project cache
+#[must_use]
+pub fn classpath_path(cache_path: &Path) -> PathBuf {
+    cache_path.with_extension("classpath")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs, sync::RwLock};
+
+    use dto::{Class, ClassFolder};
+    use my_string::smol_str::SmolStr;
+
+    use super::{Classpath, ClasspathDiff, classpath_path};
+    use crate::save_class_folder;
+
+    fn class(class_path: &str) -> Class {
+        Class {
+            class_path: SmolStr::new(class_path),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn drop_classes_of_removed_and_changed_jars() {
+        let dir = std::env::temp_dir().join(format!("classpath-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let jar = |name: &str, content: &str| {
+            let path = dir.join(format!("{name}.jar"));
+            fs::write(&path, content).unwrap();
+            (path, dir.join(format!("{name}.cfc")))
+        };
+        let (kept, kept_shard) = jar("kept", "a");
+        let (removed, removed_shard) = jar("removed", "b");
+        let (changed, changed_shard) = jar("changed", "c");
+        for (shard, class_path) in [
+            (&kept_shard, "a.Kept"),
+            (&removed_shard, "a.Removed"),
+            (&changed_shard, "a.Changed"),
+        ] {
+            let classes = ClassFolder {
+                classes: vec![class(class_path)],
+            };
+            save_class_folder(shard, &classes).unwrap();
+        }
+        let old = Classpath::new([
+            (&kept, &kept_shard),
+            (&removed, &removed_shard),
+            (&changed, &changed_shard),
+        ]);
+        let path = classpath_path(&dir.join("project.maven.cfc"));
+        old.save(&path).unwrap();
+        let old = Classpath::load(&path);
+
+        fs::write(&changed, "changed").unwrap();
+        let (added, added_shard) = jar("added", "d");
+        let new = Classpath::new([
+            (&kept, &kept_shard),
+            (&changed, &changed_shard),
+            (&added, &added_shard),
+        ]);
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff,
+            ClasspathDiff {
+                removed: vec![removed.to_str().unwrap().to_owned()],
+                changed: vec![changed.to_str().unwrap().to_owned()],
+                added: vec![added.to_str().unwrap().to_owned()],
+            }
+        );
+        assert!(new.diff(&new).is_empty());
+
+        let class_map = RwLock::new(HashMap::from(
+            ["a.Kept", "a.Removed", "a.Changed", "b.Project"].map(|c| (SmolStr::new(c), class(c))),
+        ));
+        old.drop_stale(&diff, &class_map);
+        let mut left: Vec<_> = class_map.read().unwrap().keys().cloned().collect();
+        left.sort_unstable();
+        assert_eq!(left, vec!["a.Kept", "b.Project"]);
+        assert!(kept_shard.exists());
+        assert!(removed_shard.exists());
+        assert!(!changed_shard.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
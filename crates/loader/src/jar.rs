@@ -0,0 +1,241 @@
+//! Classes of a jar that are parsed when they are needed
+//!
+//! The archive is memory-mapped and only its central directory is read when it is opened. A
+//! class file is decompressed and parsed on the first request, the last parsed classes are kept.
+use std::{collections::HashMap, fmt::Debug, path::Path, sync::Mutex};
+
+use class::{ModuleInfo, load_class, load_module};
+use dto::{Class, ClassFolder, ClassParserError, SourceDestination};
+use my_string::{MyString, smol_str::ToSmolStr};
+use zip_util::{Entry, EntryKind, MappedArchive, ZipUtilError};
+
+use crate::{LoaderError, lru::Lru, parallel};
+
+/// Parsed classes that are kept for the next request
+const PARSED_CLASSES: usize = 256;
+
+pub struct LazyJar {
+    path: String,
+    archive: MappedArchive,
+    source: SourceDestination,
+    /// Class files in the order of the archive
+    classes: Vec<(MyString, Entry)>,
+    index: HashMap<MyString, usize>,
+    /// Jars inside of the jar
+    jars: Vec<Entry>,
+    parsed: Mutex<Lru<MyString, Class>>,
+}
+
+impl LazyJar {
+    pub fn open<P: AsRef<Path> + Debug>(
+        path: P,
+        source: SourceDestination,
+    ) -> Result<Self, LoaderError> {
+        let display = format!("{path:?}");
+        let archive = MappedArchive::open(path.as_ref());
+        Self::new(display, archive, source, None)
+    }
+
+    /// A jmod has a header of 4 bytes and its classes are in the `classes` folder
+    pub fn open_jmod<P: AsRef<Path> + Debug>(
+        path: P,
+        source: SourceDestination,
+    ) -> Result<Self, LoaderError> {
+        let display = format!("{path:?}");
+        let archive = MappedArchive::open_at(path.as_ref(), 4);
+        Self::new(display, archive, source, Some("classes."))
+    }
+
+    fn new(
+        path: String,
+        archive: Result<MappedArchive, ZipUtilError>,
+        source: SourceDestination,
+        trim_prefix: Option<&str>,
+    ) -> Result<Self, LoaderError> {
+        let archive = archive.map_err(|e| LoaderError::Zip {
+            e,
+            path: path.clone(),
+        })?;
+
+        // Prefix for module info
+        let mut rules: Vec<(String, ModuleInfo)> = Vec::new();
+        for entry in archive.entries() {
+            if !matches!(entry.kind(), EntryKind::Directory)
+                && let Some(file_name) = entry.sanitized_name()
+                && file_name.ends_with("module-info.class")
+            {
+                let prefix = file_name.trim_end_matches("module-info.class");
+                let buf = archive.read(entry).map_err(|e| LoaderError::Zip {
+                    e,
+                    path: path.clone(),
+                })?;
+                rules.push((
+                    prefix.to_string(),
+                    load_module(&buf).map_err(LoaderError::Module)?,
+                ));
+            }
+        }
+
+        let trim_prefix_path = trim_prefix.map(|i| i.replace('.', "/"));
+        let mut classes = Vec::new();
+        let mut jars = Vec::new();
+        'entries: for entry in archive.entries() {
+            if matches!(entry.kind(), EntryKind::Directory) {
+                continue;
+            }
+            let Some(file_name) = entry.sanitized_name() else {
+                continue;
+            };
+            let ext = Path::new(file_name).extension();
+            if ext.is_some_and(|e| e.eq_ignore_ascii_case("jar")) {
+                jars.push(entry.clone());
+                continue;
+            }
+            if !ext.is_some_and(|e| e.eq_ignore_ascii_case("class"))
+                || file_name.ends_with("module-info.class")
+            {
+                continue;
+            }
+            for r in &rules {
+                let p = trim_prefix_path
+                    .as_ref()
+                    .map_or(file_name, |prefix| file_name.trim_start_matches(prefix));
+                if file_name.starts_with(&r.0)
+                    && !r.1.exports.iter().any(|e| p.starts_with(e.as_str()))
+                {
+                    continue 'entries;
+                }
+            }
+            let class_path = file_name.trim_start_matches('/');
+            let class_path = class_path.trim_end_matches(".class");
+            let mut class_path = class_path.replace('/', ".").to_smolstr();
+            if let Some(trim_prefix) = trim_prefix {
+                class_path = class_path.replace(trim_prefix, "").to_smolstr();
+            }
+            classes.push((class_path, entry.clone()));
+        }
+        let index = classes
+            .iter()
+            .enumerate()
+            .map(|(i, (class_path, _))| (class_path.clone(), i))
+            .collect();
+
+        Ok(Self {
+            path,
+            archive,
+            source,
+            classes,
+            index,
+            jars,
+            parsed: Mutex::new(Lru::new(PARSED_CLASSES)),
+        })
+    }
+
+    /// Class paths of the class files, the classes are not parsed
+    pub fn class_paths(&self) -> impl Iterator<Item = &MyString> {
+        self.classes.iter().map(|(class_path, _)| class_path)
+    }
+
+    #[must_use]
+    pub fn contains(&self, class_path: &str) -> bool {
+        self.index.contains_key(class_path)
+    }
+
+    /// Parse the class file of the class path, classes that are ignored by the class parser are
+    /// `None`
+    pub fn class(&self, class_path: &str) -> Result<Option<Class>, LoaderError> {
+        let Some(index) = self.index.get(class_path) else {
+            return Ok(None);
+        };
+        let (class_path, entry) = &self.classes[*index];
+        if let Ok(mut parsed) = self.parsed.lock()
+            && let Some(class) = parsed.get(class_path)
+        {
+            return Ok(Some(class.clone()));
+        }
+        let Some(class) = self.parse(class_path, entry)? else {
+            return Ok(None);
+        };
+        if let Ok(mut parsed) = self.parsed.lock() {
+            parsed.insert(class_path.clone(), class.clone());
+        }
+        Ok(Some(class))
+    }
+
+    /// Parse all classes on all cores, including the classes of nested jars
+    pub fn load_all(&self) -> Result<ClassFolder, LoaderError> {
+        let mut classes = vec![];
+        for entry in &self.jars {
+            let buf = self.read(entry)?;
+            let nested = Self::new(
+                entry.name.clone(),
+                MappedArchive::from_bytes(buf),
+                SourceDestination::None,
+                None,
+            )?;
+            classes.extend(nested.load_all()?.classes);
+        }
+        let parsed = parallel::parallel_map(
+            &self.classes,
+            |(class_path, entry)| self.parse(class_path, entry).transpose(),
+            |_, _| {},
+        );
+        for class in parsed {
+            classes.push(class?);
+        }
+        Ok(ClassFolder { classes })
+    }
+
+    fn parse(&self, class_path: &MyString, entry: &Entry) -> Result<Option<Class>, LoaderError> {
+        let buf = self.read(entry)?;
+        match load_class(&buf, class_path.clone(), self.source.clone(), true) {
+            Ok(class) => Ok(Some(class)),
+            Err(ClassParserError::Ignoring | ClassParserError::NotAClass) => Ok(None),
+            Err(e) => Err(LoaderError::ClassParser(e)),
+        }
+    }
+
+    fn read(&self, entry: &Entry) -> Result<Vec<u8>, LoaderError> {
+        self.archive.read(entry).map_err(|e| LoaderError::Zip {
+            e,
+            path: self.path.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use dto::SourceDestination;
+
+    use super::LazyJar;
+
+    #[test]
+    fn parse_on_request() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/annotations.jar");
+        let jar = LazyJar::open(&path, SourceDestination::None).unwrap();
+        assert_eq!(
+            jar.class_paths()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "ch.emilycares.Annotations$Checked",
+                "ch.emilycares.Annotations$Nullable",
+                "ch.emilycares.Annotations",
+            ]
+        );
+        assert!(jar.parsed.lock().unwrap().is_empty());
+
+        let class = jar.class("ch.emilycares.Annotations").unwrap().unwrap();
+        assert_eq!(class.name, "Annotations");
+        assert_eq!(jar.parsed.lock().unwrap().len(), 1);
+        let cached = jar.class("ch.emilycares.Annotations").unwrap().unwrap();
+        assert_eq!(cached, class);
+        assert_eq!(jar.parsed.lock().unwrap().len(), 1);
+        assert!(jar.class("ch.emilycares.Other").unwrap().is_none());
+
+        let all = jar.load_all().unwrap();
+        assert!(all.classes.contains(&class));
+    }
+}
@@ -6,9 +6,14 @@
 use std::collections::VecDeque;
 use std::fs::read;
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io::Write,
     path::{Path, PathBuf},
+    sync::{
+        RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
 };
 
 use class::{ModuleInfo, load_class, load_module};
@@ -19,18 +24,41 @@ use my_string::smol_str::SmolStr;
 use my_string::smol_str::StrExt;
 use my_string::{MyString, smol_str::ToSmolStr};
 use parser::java::{self, ParseJavaError};
-use rc_zip_tokio::{ReadZip, rc_zip::parse::EntryKind};
 use std::fmt::Debug;
+use zip_util::{EntryKind, MappedArchive, ZipUtilError};
+
+pub mod classpath;
+pub mod jar;
+pub mod lru;
+pub mod parallel;
+
+pub use jar::LazyJar;
 
 pub const DEBUGGING: bool = false;
 
+/// Files that take longer to parse are reported
+const SLOW_PARSE: Duration = Duration::from_millis(500);
+
+/// Folders whose java files are not loaded
+static EXCLUDED_DIRS: RwLock<Vec<PathBuf>> = RwLock::new(Vec::new());
+
+/// Skip the java files in the folders and their subfolders
+pub fn set_excluded_dirs(dirs: Vec<PathBuf>) {
+    if let Ok(mut excluded) = EXCLUDED_DIRS.write() {
+        *excluded = dirs;
+    }
+}
+
+fn excluded(dir: &Path) -> bool {
+    EXCLUDED_DIRS
+        .read()
+        .is_ok_and(|excluded| excluded.iter().any(|e| dir.starts_with(e)))
+}
+
 #[derive(Debug)]
 pub enum LoaderError {
     IO(std::io::Error),
-    Zip {
-        e: rc_zip_tokio::rc_zip::error::Error,
-        path: String,
-    },
+    Zip { e: ZipUtilError, path: String },
     EmptyClassFolder,
     Module(ClassParserError),
     ClassParser(ClassParserError),
@@ -59,23 +87,39 @@ where
     class::load_class(&buf, class_path, source, filter).map_err(LoaderError::ClassParser)
 }
 
+/// The caches of the JDK and the dependencies are shared by all projects and servers. The file is
+/// written next to the cache and renamed, so that a reader never sees a partial cache.
 pub fn save_class_folder<P: AsRef<Path> + Debug>(
     path: P,
     class_folder: &ClassFolder,
 ) -> Result<(), LoaderError> {
+    static WRITES: AtomicU64 = AtomicU64::new(0);
     if class_folder.classes.is_empty() {
         return Err(LoaderError::EmptyClassFolder);
     }
-    let mut file = OpenOptions::new()
+    let path = path.as_ref();
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tmp = PathBuf::from(tmp);
+    let data = dto_rw::write(class_folder);
+    let written = OpenOptions::new()
         .create(true)
         .truncate(true)
         .write(true)
-        .open(path)
-        .map_err(LoaderError::IO)?;
-    let data = dto_rw::write(class_folder);
-
-    file.write_all(&data).map_err(LoaderError::IO)?;
-    file.flush().map_err(LoaderError::IO)?;
+        .open(&tmp)
+        .and_then(|mut file| {
+            file.write_all(&data)?;
+            file.flush()
+        })
+        .and_then(|()| fs::rename(&tmp, path));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(LoaderError::IO(e));
+    }
     Ok(())
 }
 
@@ -90,39 +134,73 @@ pub fn load_class_folder<P: AsRef<Path> + Debug>(path: P) -> Result<ClassFolder,
 
 #[must_use]
 pub fn load_java_files(dir: PathBuf) -> Vec<Class> {
-    let mut dirs = VecDeque::new();
-    dirs.push_back(dir);
-    let mut out = Vec::new();
-    while let Some(dir) = dirs.pop_front() {
-        if let Ok(o) = visit_java_files(&dir, &mut dirs, |p| {
-            if let Some(s) = p.to_str() {
-                return load_java_fs(p, SourceDestination::Here(s.to_smolstr())).ok();
+    load_java_folders(vec![dir], |_, _| {})
+}
+
+/// Parse the java files of the folders on the worker pool
+///
+/// The number of workers is limited by [`parallel::set_max_workers`]. `progress` gets the number
+/// of parsed files and the total. Files that are slow to parse are reported with their time.
+#[must_use]
+pub fn load_java_folders(
+    folders: Vec<PathBuf>,
+    progress: impl Fn(usize, usize) + Sync,
+) -> Vec<Class> {
+    let files = java_files(folders);
+    let started = Instant::now();
+    let classes = parallel::parallel_map(
+        &files,
+        |p| {
+            let s = p.to_str()?;
+            let file_started = Instant::now();
+            let class = load_java_fs(p, SourceDestination::Here(s.to_smolstr()));
+            let elapsed = file_started.elapsed();
+            if elapsed >= SLOW_PARSE {
+                eprintln!("Parsing {s} took {} ms", elapsed.as_millis());
             }
-            None
-        }) {
-            out.extend(o);
+            class.ok()
+        },
+        progress,
+    );
+    if !files.is_empty() {
+        eprintln!(
+            "Parsed {} java files in {} ms with up to {} workers",
+            files.len(),
+            started.elapsed().as_millis(),
+            parallel::max_workers()
+        );
+    }
+    classes
+}
+/// Java files in the folders and their subfolders, see [`set_excluded_dirs`]
+#[must_use]
+pub fn java_files(folders: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut dirs = VecDeque::from(folders);
+    let mut files = Vec::new();
+    while let Some(dir) = dirs.pop_front() {
+        if let Ok(o) = visit_java_files(&dir, &mut dirs) {
+            files.extend(o);
         }
     }
-    out
+    files
 }
+
 fn visit_java_files(
     dir: &PathBuf,
     dirs: &mut VecDeque<PathBuf>,
-    cb: impl Fn(&PathBuf) -> Option<Class>,
-) -> Result<Vec<Class>, LoaderError> {
+) -> Result<Vec<PathBuf>, LoaderError> {
     let read_dir = std::fs::read_dir(dir)
         .map_err(LoaderError::IO)?
         .map(|res| res.map(|e| e.path()))
         .filter_map(Result::ok);
-    let mut out: Vec<Class> = Vec::new();
+    let mut out = Vec::new();
     for entry in read_dir {
         if entry.is_dir() {
-            dirs.push_back(entry);
-        } else if let Some(e) = entry.extension()
-            && e == "java"
-            && let Some(o) = cb(&entry)
-        {
-            out.push(o);
+            if !excluded(&entry) {
+                dirs.push_back(entry);
+            }
+        } else if entry.extension().is_some_and(|e| e == "java") {
+            out.push(entry);
         }
     }
     Ok(out)
@@ -204,102 +282,110 @@ pub fn load_class_files(
         }
     }
 
-    let mut out = Vec::new();
-
-    'outer: for p in files.iter().filter(|i| !i.ends_with("module-info.class")) {
-        use my_string::smol_str::{StrExt, format_smolstr};
+    let files: Vec<&SmolStr> = files
+        .iter()
+        .filter(|p| !p.ends_with("module-info.class"))
+        .filter(|p| {
+            let prefix = p.trim_start_matches(root_prefix).trim_start_matches('/');
+            !rules.iter().any(|r| {
+                prefix.starts_with(&r.0) && !r.1.exports.iter().any(|e| p.contains(e.as_str()))
+            })
+        })
+        .collect();
 
-        let prefix = p.trim_start_matches(root_prefix).trim_start_matches('/');
+    let out = parallel::parallel_map(
+        &files,
+        |p| {
+            use my_string::smol_str::{StrExt, format_smolstr};
 
-        for r in &rules {
-            if prefix.starts_with(&r.0) && !r.1.exports.iter().any(|e| p.contains(e.as_str())) {
-                continue 'outer;
-            }
-        }
-        let mut class_path = prefix.trim_end_matches(".class").replace_smolstr("/", ".");
-        if trim_prefix > 1 {
-            let spl = class_path.splitn(trim_prefix, '.');
-            if let Some(p) = spl.last() {
-                class_path = p.to_smolstr();
-            }
-        }
-        let sr = p
-            .trim_start_matches(root_prefix)
-            .replacen_smolstr(".class", ".java", 1);
-
-        let smol_str = format_smolstr!("{source}{sr}");
-        match load_class_fs(
-            p,
-            SourceDestination::Here(smol_str.clone()),
-            class_path,
-            filter,
-        ) {
-            Ok(c) => {
-                out.push(c);
+            let prefix = p.trim_start_matches(root_prefix).trim_start_matches('/');
+            let mut class_path = prefix.trim_end_matches(".class").replace_smolstr("/", ".");
+            if trim_prefix > 1 {
+                let spl = class_path.splitn(trim_prefix, '.');
+                if let Some(p) = spl.last() {
+                    class_path = p.to_smolstr();
+                }
             }
-            Err(LoaderError::ClassParser(
-                ClassParserError::NotAClass | ClassParserError::Ignoring,
-            )) => (),
-            Err(e) => {
-                return Err(e);
+            let sr = p
+                .trim_start_matches(root_prefix)
+                .replacen_smolstr(".class", ".java", 1);
+
+            let smol_str = format_smolstr!("{source}{sr}");
+            match load_class_fs(
+                p.as_str(),
+                SourceDestination::Here(smol_str),
+                class_path,
+                filter,
+            ) {
+                Err(LoaderError::ClassParser(
+                    ClassParserError::NotAClass | ClassParserError::Ignoring,
+                )) => None,
+                out => Some(out),
             }
-        }
-    }
-    Ok(out)
+        },
+        |_, _| {},
+    );
+    out.into_iter().collect()
 }
-pub async fn load_classes_jar<P: AsRef<Path> + Debug + Clone>(
+/// Parse all classes of the jar, use [`LazyJar`] to parse classes when they are needed
+pub fn load_classes_jar<P: AsRef<Path> + Debug>(
     path: P,
     source: SourceDestination,
 ) -> Result<ClassFolder, LoaderError> {
-    let src_zip = format!("{path:?}");
-    let buf = read(path).map_err(LoaderError::IO)?;
-
-    base_load_classes_zip(src_zip, source, buf, None).await
+    LazyJar::open(path, source)?.load_all()
 }
-pub async fn load_classes_jmod<P: AsRef<Path> + Debug>(
+pub fn load_classes_jmod<P: AsRef<Path> + Debug>(
     path: P,
     source: SourceDestination,
 ) -> Result<ClassFolder, LoaderError> {
-    let src_zip = format!("{path:?}");
-    let mut buf = read(path).map_err(LoaderError::IO)?;
-    buf.drain(0..4);
-
-    base_load_classes_zip(src_zip, source, buf, Some("classes.")).await
+    LazyJar::open_jmod(path, source)?.load_all()
 }
 
-async fn base_load_classes_zip(
-    path: String,
-    source: SourceDestination,
-    buf: Vec<u8>,
-    trim_prefix: Option<&str>,
+/// Load the signatures of the platform classes of a java release from `lib/ct.sym`
+///
+/// The entries are `<releases>/<module>/<package>/<Class>.sig` where `<releases>` lists every
+/// release that shares the signature as base 36 digits, for example `9ABC` is java 9 to 12.
+/// Java 8 and older have no module folder. Sources are looked up in the module folders of
+/// `source_dir`.
+pub fn load_classes_ct_sym<P: AsRef<Path> + Debug>(
+    path: P,
+    release: u32,
+    source_dir: &str,
 ) -> Result<ClassFolder, LoaderError> {
-    let zip = buf.read_zip().await.map_err(|e| LoaderError::Zip {
+    let src_zip = format!("{path:?}");
+    let zip = MappedArchive::open(path.as_ref()).map_err(|e| LoaderError::Zip {
         e,
-        path: path.clone(),
+        path: src_zip.clone(),
     })?;
-    let mut classes = vec![];
+    let read = |entry| {
+        zip.read(entry).map_err(|e| LoaderError::Zip {
+            e,
+            path: src_zip.clone(),
+        })
+    };
+    let Some(release) = ct_sym_release(release) else {
+        return Ok(ClassFolder { classes: vec![] });
+    };
 
     // Prefix for module info
     let mut rules: Vec<(String, ModuleInfo)> = Vec::new();
-
     for entry in zip.entries() {
         if !matches!(entry.kind(), EntryKind::Directory)
             && let Some(file_name) = entry.sanitized_name()
-            && file_name.ends_with("module-info.class")
+            && let Some((releases, _)) = file_name.split_once('/')
+            && releases.contains(release)
+            && file_name.ends_with("module-info.sig")
         {
-            let prefix = file_name.trim_end_matches("module-info.class");
-            let buf = entry.bytes().await.map_err(LoaderError::IO)?;
-            match load_module(buf.as_slice()) {
-                Ok(c) => {
-                    rules.push((prefix.to_string(), c));
-                }
-                Err(e) => {
-                    return Err(LoaderError::Module(e));
-                }
+            let prefix = file_name.trim_end_matches("module-info.sig");
+            let buf = read(entry)?;
+            match load_module(&buf) {
+                Ok(c) => rules.push((prefix.to_string(), c)),
+                Err(e) => return Err(LoaderError::Module(e)),
             }
         }
     }
-    let trim_prefix_path = trim_prefix.map(|i| i.replace('.', "/"));
+
+    let mut classes = vec![];
     'entries: for entry in zip.entries() {
         if matches!(entry.kind(), EntryKind::Directory) {
             continue;
@@ -307,58 +393,63 @@ async fn base_load_classes_zip(
         let Some(file_name) = entry.sanitized_name() else {
             continue;
         };
-        let ext = Path::new(file_name).extension();
-        if ext.is_some_and(|e| e.eq_ignore_ascii_case("jar")) {
-            let buf = entry.bytes().await.map_err(LoaderError::IO)?;
-            let o = Box::pin(base_load_classes_zip(
-                file_name.to_string(),
-                SourceDestination::None,
-                buf,
-                None,
-            ))
-            .await?;
-            classes.extend(o.classes);
-            continue;
-        }
-        if !ext.is_some_and(|e| e.eq_ignore_ascii_case("class")) {
+        let Some((releases, class_file)) = file_name.split_once('/') else {
             continue;
-        }
-        if file_name.ends_with("module-info.class") {
+        };
+        if !releases.contains(release)
+            || !Path::new(class_file)
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("sig"))
+            || class_file.ends_with("module-info.sig")
+        {
             continue;
         }
+        let (module, class_file) = match class_file.split_once('/') {
+            Some((module, rest)) if module.contains('.') => (Some(module), rest),
+            _ => (None, class_file),
+        };
         for r in &rules {
-            let p = trim_prefix_path
-                .as_ref()
-                .map_or(file_name, |prefix| file_name.trim_start_matches(prefix));
-            if file_name.starts_with(&r.0) && !r.1.exports.iter().any(|e| p.starts_with(e.as_str()))
+            if file_name.starts_with(&r.0)
+                && !r
+                    .1
+                    .exports
+                    .iter()
+                    .any(|e| class_file.starts_with(e.as_str()))
             {
                 continue 'entries;
             }
         }
-        let class_path = file_name.trim_start_matches('/');
-        let class_path = class_path.trim_end_matches(".class");
-        let mut class_path = class_path.replace('/', ".").to_smolstr();
-        if let Some(trim_prefix) = trim_prefix {
-            class_path = class_path.replace(trim_prefix, "").to_smolstr();
-        }
-
-        let buf = entry.bytes().await.map_err(LoaderError::IO)?;
-
-        match load_class(buf.as_slice(), class_path.clone(), source.clone(), true) {
+        let class_path = class_file.trim_end_matches(".sig").replace('/', ".");
+        let source = module.map_or(SourceDestination::None, |module| {
+            SourceDestination::RelativeInFolder(format!("{source_dir}/{module}").to_smolstr())
+        });
+        let buf = read(entry)?;
+        match load_class(&buf, class_path.to_smolstr(), source, true) {
             Ok(c) => classes.push(c),
             Err(ClassParserError::Ignoring | ClassParserError::NotAClass) => (),
-            Err(e) => {
-                return Err(LoaderError::ClassParser(e));
-            }
+            Err(e) => return Err(LoaderError::ClassParser(e)),
         }
     }
-
     Ok(ClassFolder { classes })
 }
 
+/// Digit of a release in the folder names of `ct.sym`
+#[must_use]
+pub fn ct_sym_release(release: u32) -> Option<char> {
+    char::from_digit(release, 36).map(|c| c.to_ascii_uppercase())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::DEBUGGING;
+    use std::fs;
+
+    use dto::{Class, ClassFolder};
+    use my_string::smol_str::SmolStr;
+
+    use crate::{
+        DEBUGGING, ct_sym_release, java_files, load_class_folder, save_class_folder,
+        set_excluded_dirs,
+    };
 
     #[test]
     fn not_debugging() {
@@ -366,4 +457,46 @@ mod tests {
             assert!(!DEBUGGING);
         }
     }
+
+    #[test]
+    fn ct_sym_release_digit() {
+        assert_eq!(ct_sym_release(8), Some('8'));
+        assert_eq!(ct_sym_release(11), Some('B'));
+        assert_eq!(ct_sym_release(21), Some('L'));
+        assert_eq!(ct_sym_release(36), None);
+    }
+
+    #[test]
+    fn replace_shared_cache() {
+        let dir = std::env::temp_dir().join(format!("loader-cache-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("dep.cfc");
+        for class_path in ["a.First", "a.Second"] {
+            let class_folder = ClassFolder {
+                classes: vec![Class {
+                    class_path: SmolStr::new(class_path),
+                    ..Default::default()
+                }],
+            };
+            save_class_folder(&path, &class_folder).unwrap();
+            assert_eq!(load_class_folder(&path).unwrap(), class_folder);
+        }
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn excluded_java_files() {
+        let dir = std::env::temp_dir().join(format!("loader-exclude-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src/legacy/old")).unwrap();
+        fs::write(dir.join("src/A.java"), "class A {}").unwrap();
+        fs::write(dir.join("src/legacy/old/B.java"), "class B {}").unwrap();
+        set_excluded_dirs(vec![dir.join("src/legacy")]);
+        assert_eq!(java_files(vec![dir.clone()]), [dir.join("src/A.java")]);
+        set_excluded_dirs(vec![]);
+        assert_eq!(java_files(vec![dir.clone()]).len(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,77 @@
+//! Least recently used cache
+use std::{collections::HashMap, hash::Hash};
+
+/// Keeps the last `capacity` used values, the least recently used value is dropped first
+#[derive(Debug)]
+pub struct Lru<K, V> {
+    capacity: usize,
+    tick: u64,
+    values: HashMap<K, (u64, V)>,
+}
+
+impl<K: Hash + Eq + Clone, V> Lru<K, V> {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            tick: 0,
+            values: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Value of the key, it becomes the most recently used
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.values.get_mut(key).map(|(used, value)| {
+            *used = tick;
+            &*value
+        })
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.tick += 1;
+        if self.values.len() >= self.capacity
+            && !self.values.contains_key(&key)
+            && let Some(oldest) = self
+                .values
+                .iter()
+                .min_by_key(|(_, (used, _))| *used)
+                .map(|(key, _)| key.clone())
+        {
+            self.values.remove(&oldest);
+        }
+        self.values.insert(key, (self.tick, value));
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lru;
+
+    #[test]
+    fn drop_least_recently_used() {
+        let mut lru = Lru::new(2);
+        lru.insert("a", 1);
+        lru.insert("b", 2);
+        assert_eq!(lru.get(&"a"), Some(&1));
+        lru.insert("c", 3);
+        assert_eq!(lru.len(), 2);
+        assert_eq!(lru.get(&"b"), None);
+        assert_eq!(lru.get(&"a"), Some(&1));
+        assert_eq!(lru.get(&"c"), Some(&3));
+        lru.insert("c", 4);
+        assert_eq!(lru.get(&"c"), Some(&4));
+        assert_eq!(lru.get(&"a"), Some(&1));
+    }
+}
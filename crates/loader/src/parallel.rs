@@ -0,0 +1,141 @@
+//! Parse many files on all cores
+use std::{
+    num::NonZeroUsize,
+    panic,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+/// Items per worker below which starting another thread is not worth it
+const MIN_ITEMS_PER_WORKER: usize = 64;
+
+/// Upper limit of workers, 0 uses all cores
+static MAX_WORKERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Limit the number of worker threads, 0 uses all cores
+pub fn set_max_workers(workers: usize) {
+    MAX_WORKERS.store(workers, Ordering::Relaxed);
+}
+
+/// Number of workers that are started for many items
+#[must_use]
+pub fn max_workers() -> usize {
+    let cores = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    match MAX_WORKERS.load(Ordering::Relaxed) {
+        0 => cores,
+        limit => limit.min(cores),
+    }
+}
+
+/// Map the items on a pool of worker threads, the results keep the order of the items
+///
+/// Workers take the next item until all are done and keep their results in their own shard, the
+/// shards are merged at the end. `progress` is called with the number of finished items and the
+/// total. A panic of a worker is raised again once all workers are done.
+pub fn parallel_map<T, R>(
+    items: &[T],
+    work: impl Fn(&T) -> Option<R> + Sync,
+    progress: impl Fn(usize, usize) + Sync,
+) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let total = items.len();
+    let workers = max_workers()
+        .min(total.div_ceil(MIN_ITEMS_PER_WORKER))
+        .max(1);
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let run = || {
+        let mut shard = Vec::new();
+        loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(item) = items.get(index) else {
+                break;
+            };
+            if let Some(out) = work(item) {
+                shard.push((index, out));
+            }
+            progress(done.fetch_add(1, Ordering::Relaxed) + 1, total);
+        }
+        shard
+    };
+    if workers == 1 {
+        return run().into_iter().map(|(_, out)| out).collect();
+    }
+    let mut merged: Vec<(usize, R)> = thread::scope(|scope| {
+        // Spawn all workers before joining the first
+        let handles: Vec<_> = (0..workers).map(|_| scope.spawn(run)).collect();
+        let mut merged = Vec::with_capacity(total);
+        let mut panicked = None;
+        for handle in handles {
+            match handle.join() {
+                Ok(shard) => merged.extend(shard),
+                Err(payload) => {
+                    eprintln!("A parse worker panicked, its results are lost");
+                    panicked.get_or_insert(payload);
+                }
+            }
+        }
+        // Without the shard items would be missing without a trace
+        if let Some(payload) = panicked {
+            panic::resume_unwind(payload);
+        }
+        merged
+    });
+    merged.sort_unstable_by_key(|(index, _)| *index);
+    merged.into_iter().map(|(_, out)| out).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::{max_workers, parallel_map, set_max_workers};
+
+    #[test]
+    fn ordered_results() {
+        let items: Vec<usize> = (0..1000).collect();
+        let calls = AtomicUsize::new(0);
+        let out = parallel_map(
+            &items,
+            |i| (i % 3 != 0).then_some(i * 2),
+            |_, total| {
+                assert_eq!(total, 1000);
+                calls.fetch_add(1, Ordering::Relaxed);
+            },
+        );
+        let expected: Vec<usize> = items
+            .iter()
+            .filter(|i| *i % 3 != 0)
+            .map(|i| i * 2)
+            .collect();
+        assert_eq!(out, expected);
+        assert_eq!(calls.load(Ordering::Relaxed), 1000);
+    }
+
+    #[test]
+    fn worker_panic() {
+        let items: Vec<usize> = (0..1000).collect();
+        let out = std::panic::catch_unwind(|| {
+            parallel_map(
+                &items,
+                |i| {
+                    assert_ne!(*i, 500, "bad item");
+                    Some(*i)
+                },
+                |_, _| {},
+            )
+        });
+        assert!(out.is_err());
+    }
+
+    #[test]
+    fn worker_limit() {
+        set_max_workers(1);
+        assert_eq!(max_workers(), 1);
+        set_max_workers(0);
+        assert!(max_workers() >= 1);
+    }
+}
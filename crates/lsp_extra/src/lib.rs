@@ -7,7 +7,7 @@ use std::{num::TryFromIntError, str::FromStr};
 
 use ast::{
     error::{AstError, get_pos},
-    lexer::{LexerError, PositionToken},
+    lexer::{LexerError, PositionToken, Token},
     types::{AstPoint, AstRange},
 };
 use lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range, Uri};
@@ -53,15 +53,24 @@ pub fn to_ast_point(position: lsp_types::Position) -> AstPoint {
     )
 }
 
+#[must_use]
+pub fn to_ast_range(range: &Range) -> AstRange {
+    AstRange {
+        start: to_ast_point(range.start),
+        end: to_ast_point(range.end),
+    }
+}
+
 #[must_use]
 pub fn lexer_error_to_diagnostic(error: &LexerError) -> Diagnostic {
     match error {
-        LexerError::EOF(line, col) => {
-            Diagnostic::new_simple(line_col_to_range(*line, *col), String::from("EOF"))
-        }
-        LexerError::UnknownChar(char, line, col) => Diagnostic::new_simple(
+        LexerError::EOF(line, col) => diag(
+            String::from("unexpected end of file"),
+            line_col_to_range(*line, *col),
+        ),
+        LexerError::UnknownChar(char, line, col) => diag(
+            format!("unexpected character '{char}'"),
             line_col_to_range(*line, *col),
-            format!("Unexpected char: '{char}'"),
         ),
     }
 }
@@ -123,128 +132,199 @@ fn path_without_subclass(source: &str) -> String {
     source.to_owned()
 }
 
-pub enum AstDiagnosticError {
-    TokensModified,
-    Empty,
-    ChildrenNotFound,
+/// Convert a parser error to a diagnostic at the range of the offending token
+#[must_use]
+pub fn ast_error_to_diagnostic(err: &AstError, tokens: &[PositionToken]) -> Diagnostic {
+    let (message, pos) = ast_error_message(err, tokens);
+    let range = pos
+        .and_then(|pos| tokens.get(pos))
+        .map_or_else(|| end_of_file_range(tokens), token_range);
+    diag(message, range)
 }
 
-pub fn ast_error_to_diagnostic(
-    err: &AstError,
-    tokens: &[PositionToken],
-) -> Result<Diagnostic, AstDiagnosticError> {
+/// Human readable message and the position of the offending token.
+/// No position means the error is at the end of the file.
+fn ast_error_message(err: &AstError, tokens: &[PositionToken]) -> (String, Option<usize>) {
+    let found = |pos: usize| {
+        tokens
+            .get(pos)
+            .map_or_else(|| "end of file".to_string(), |t| format!("'{}'", t.token))
+    };
     match err {
         AstError::ExpectedToken(expected_token) => {
-            let found = tokens
-                .get(expected_token.pos)
-                .ok_or(AstDiagnosticError::TokensModified)?;
-            Ok(diag(
-                format!(
-                    "Expected token {:?} found: {:?}",
-                    expected_token.expected, found.token
-                ),
-                found,
-            ))
-        }
-        AstError::UnexpectedEOF => {
-            if let Some(last) = tokens.last() {
-                return Ok(diag("Unexpected end of File".to_string(), last));
-            }
-            Err(AstDiagnosticError::Empty)
-        }
-        AstError::InvalidJtype(invalid_token) => {
-            let found = tokens
-                .get(invalid_token.0)
-                .ok_or(AstDiagnosticError::TokensModified)?;
-            Ok(diag(
-                format!(
-                    "Invalid Type token found: {:?} valid onese ar Int, String",
-                    found.token
-                ),
-                found,
-            ))
-        }
-        AstError::IdentifierEmpty(invalid_token) => {
-            let found = tokens
-                .get(invalid_token.0)
-                .ok_or(AstDiagnosticError::TokensModified)?;
-            Ok(diag(
-                format!("Identifier empty found: {:?}", found.token),
-                found,
-            ))
-        }
-        AstError::InvalidName(invalid_token) => {
-            let found = tokens
-                .get(invalid_token.0)
-                .ok_or(AstDiagnosticError::TokensModified)?;
-            Ok(diag(
-                format!("Token not allowed in name: {:?}", found.token),
-                found,
-            ))
-        }
-        AstError::InvalidNuget(invalid_token) => {
-            let found = tokens
-                .get(invalid_token.0)
-                .ok_or(AstDiagnosticError::TokensModified)?;
-            Ok(diag(
-                format!("Token not allowed in nuget: {:?}", found.token),
-                found,
-            ))
-        }
-        AstError::InvalidString(invalid_token) => {
-            let found = tokens
-                .get(invalid_token.0)
-                .ok_or(AstDiagnosticError::TokensModified)?;
-            Ok(diag(
-                format!("Not a string literal: {:?}", found.token),
-                found,
-            ))
-        }
-        AstError::AllChildrenFailed { parent: _, errors } => {
-            if let Some(e) = errors
-                .iter()
-                .map(|i| (get_pos(&i.1), i))
-                .max_by(|a, b| a.0.0.cmp(&b.0.0))
-            {
-                // e.1.1.print_err(content, tokens);
-                return ast_error_to_diagnostic(&e.1.1, tokens);
-            }
-            Err(AstDiagnosticError::ChildrenNotFound)
-        }
-        AstError::EmptyExpression(invalid_token) => {
-            let found = tokens
-                .get(invalid_token.0)
-                .ok_or(AstDiagnosticError::TokensModified)?;
-            Ok(diag(
-                format!("Invalid expression it is empty {:?}", found.token),
-                found,
-            ))
-        }
-        AstError::FordbidenExpressionCall(invalid_token) => {
-            let found = tokens
-                .get(invalid_token.0)
-                .ok_or(AstDiagnosticError::TokensModified)?;
-            Ok(diag(
-                format!("Method call now allowed {:?}", found.token),
-                found,
-            ))
+            let pos = expected_token.pos;
+            let previous = pos.checked_sub(1).and_then(|p| tokens.get(p));
+            let message = match (&expected_token.expected, previous) {
+                (Token::Semicolon, Some(previous)) => {
+                    format!("expected ';' after '{}'", previous.token)
+                }
+                (expected, _) => format!("expected '{expected}' but found {}", found(pos)),
+            };
+            (message, Some(pos))
         }
+        AstError::UnexpectedEOF => ("unexpected end of file".to_string(), None),
+        AstError::InvalidJtype(invalid_token) => (
+            format!("expected a type but found {}", found(invalid_token.0)),
+            Some(invalid_token.0),
+        ),
+        AstError::IdentifierEmpty(invalid_token) => (
+            format!(
+                "expected an identifier but found {}",
+                found(invalid_token.0)
+            ),
+            Some(invalid_token.0),
+        ),
+        AstError::InvalidName(invalid_token) => (
+            format!("{} is not allowed in a name", found(invalid_token.0)),
+            Some(invalid_token.0),
+        ),
+        AstError::InvalidNuget(invalid_token) => (
+            format!("expected a literal but found {}", found(invalid_token.0)),
+            Some(invalid_token.0),
+        ),
+        AstError::InvalidString(invalid_token) => (
+            format!(
+                "expected a string literal but found {}",
+                found(invalid_token.0)
+            ),
+            Some(invalid_token.0),
+        ),
+        AstError::EmptyExpression(invalid_token) => (
+            format!(
+                "expected an expression but found {}",
+                found(invalid_token.0)
+            ),
+            Some(invalid_token.0),
+        ),
+        AstError::FordbidenExpressionCall(invalid_token) => (
+            "method call is not allowed here".to_string(),
+            Some(invalid_token.0),
+        ),
+        AstError::AlternativeFailed { error, .. } => ast_error_message(error, tokens),
+        AstError::AllChildrenFailed { parent, errors } => errors
+            .iter()
+            // The alternative that got the furthest is the most likely one,
+            // on a tie the one that was tried first
+            .rev()
+            .max_by_key(|(_, e)| get_pos(e).1)
+            .map_or_else(
+                || (format!("could not parse {parent}"), Some(0)),
+                |(_, e)| ast_error_message(e, tokens),
+            ),
     }
 }
 
-fn diag(message: String, found: &PositionToken) -> Diagnostic {
-    let p = Position {
-        line: u32::try_from(found.line).unwrap_or_default(),
-        character: u32::try_from(found.col).unwrap_or_default(),
+fn token_range(token: &PositionToken) -> Range {
+    let start = Position {
+        line: u32::try_from(token.line).unwrap_or_default(),
+        character: u32::try_from(token.col).unwrap_or_default(),
     };
-    let range = Range::new(p, p);
+    // Tokens spanning multiple lines are marked at their start
+    if matches!(
+        token.token,
+        Token::BlockComment(..) | Token::StringLiteralMulti(..)
+    ) {
+        return Range::new(start, start);
+    }
+    let len = u32::try_from(token.token.len()).unwrap_or_default();
+    Range::new(
+        start,
+        Position {
+            line: start.line,
+            character: start.character + len,
+        },
+    )
+}
+
+fn end_of_file_range(tokens: &[PositionToken]) -> Range {
+    let end = tokens.last().map(token_range).unwrap_or_default().end;
+    Range::new(end, end)
+}
+
+fn diag(message: String, range: Range) -> Diagnostic {
     Diagnostic::new(
         range,
         Some(DiagnosticSeverity::ERROR),
         None,
-        None,
+        Some(SERVER_NAME.to_string()),
         message,
         None,
         None,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Position, Range};
+
+    use crate::ast_error_to_diagnostic;
+
+    fn parse_error(content: &str) -> (String, Range) {
+        let tokens = ast::lexer::lex(content.as_bytes()).unwrap();
+        let error = ast::parse_file(&tokens).unwrap_err();
+        let diagnostic = ast_error_to_diagnostic(&error, &tokens);
+        (diagnostic.message, diagnostic.range)
+    }
+
+    #[test]
+    fn missing_paren() {
+        let content = "class A {
+    void m() {
+        if (a { }
+    }
+}";
+        assert_eq!(
+            parse_error(content),
+            (
+                "expected ')' but found '}'".to_string(),
+                Range::new(Position::new(3, 4), Position::new(3, 5))
+            )
+        );
+    }
+
+    #[test]
+    fn missing_argument() {
+        let content = "class A {
+    void m() {
+        x.y(;
+    }
+}";
+        assert_eq!(
+            parse_error(content),
+            (
+                "expected an expression but found ';'".to_string(),
+                Range::new(Position::new(2, 12), Position::new(2, 13))
+            )
+        );
+    }
+
+    #[test]
+    fn missing_semicolon() {
+        let content = "class A {
+    void m() {
+        break
+    }
+}";
+        assert_eq!(
+            parse_error(content),
+            (
+                "expected ';' after 'break'".to_string(),
+                Range::new(Position::new(3, 4), Position::new(3, 5))
+            )
+        );
+    }
+
+    #[test]
+    fn unexpected_end_of_file() {
+        let content = "class A {
+    void m() {}
+";
+        assert_eq!(
+            parse_error(content),
+            (
+                "unexpected end of file".to_string(),
+                Range::new(Position::new(1, 15), Position::new(1, 15))
+            )
+        );
+    }
+}
@@ -6,6 +6,7 @@ use std::{
 };
 
 use common::Dependency;
+use compile::{CompileErrorMessage, parse_maven_compile_errors};
 use tokio::sync::OnceCell;
 
 use crate::config::overwrite_settings_xml;
@@ -57,6 +58,26 @@ pub fn generate_classpath(maven_executable: &str) -> Result<String, MavenClasspa
     Ok(full_classpath)
 }
 
+/// Compile the project with maven, with a `module` folder relative to the project only that
+/// module and the modules it depends on
+#[must_use]
+pub fn compile(
+    maven_executable: &str,
+    project_dir: &Path,
+    module: Option<&Path>,
+) -> Option<Vec<CompileErrorMessage>> {
+    // mvn -q compile -pl module -am
+    let mut command = Command::new(maven_executable);
+    command.current_dir(project_dir).args(["-q", "compile"]);
+    if let Some(module) = module {
+        command.arg("-pl").arg(module).arg("-am");
+    }
+    let output = overwrite_settings_xml(&mut command).output().ok()?;
+    Some(parse_maven_compile_errors(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
 fn should_load_existing_classpath() -> bool {
     let classpath_mtime = fs::metadata(CLASSPATH_FILE).and_then(|m| m.modified()).ok();
     let pom_mtime = fs::metadata("./pom.xml").and_then(|m| m.modified()).ok();
@@ -12,10 +12,12 @@ pub mod compile;
 pub mod config;
 pub mod m2;
 pub mod metadata;
+pub mod modules;
 pub mod pom;
 pub mod project;
 pub mod repository;
 pub mod settings;
+pub mod toolchains;
 pub mod tree;
 pub mod update;
 
@@ -0,0 +1,205 @@
+//! Modules of a maven reactor build
+//!
+//! Each module has its own source roots and compiler release. Dependencies on other modules of
+//! the reactor are resolved to their sources instead of the jars in the local repository.
+use std::path::{Path, PathBuf};
+
+use common::language_level::LanguageLevel;
+
+use crate::pom::{Pom, load_pom_xml};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MavenModule {
+    pub artifact_id: String,
+    pub dir: PathBuf,
+    pub source_roots: Vec<PathBuf>,
+    /// Artifact ids of the modules of the reactor that this module depends on
+    pub dependencies: Vec<String>,
+    /// Inherited from the aggregator when the module does not set it
+    pub release: Option<u32>,
+    /// Preview features are enabled, inherited like the release
+    pub preview: bool,
+}
+
+impl MavenModule {
+    #[must_use]
+    pub const fn language_level(&self) -> LanguageLevel {
+        LanguageLevel {
+            release: self.release,
+            preview: self.preview,
+        }
+    }
+}
+
+/// The project and all of its modules, the project itself comes first
+#[must_use]
+pub fn load_modules(project_dir: &Path) -> Vec<MavenModule> {
+    let mut poms = vec![];
+    collect(project_dir, LanguageLevel::default(), &mut poms);
+    let artifact_ids: Vec<String> = poms.iter().map(|(p, ..)| p.artifact_id.clone()).collect();
+    poms.into_iter()
+        .map(|(pom, dir, level)| {
+            let dependencies = pom
+                .dependencies
+                .iter()
+                .flat_map(|d| d.dependency.iter().flatten())
+                .filter(|d| d.artifact_id != pom.artifact_id)
+                .filter(|d| artifact_ids.contains(&d.artifact_id))
+                .map(|d| d.artifact_id.clone())
+                .collect();
+            MavenModule {
+                source_roots: source_roots(&pom, &dir),
+                artifact_id: pom.artifact_id,
+                dir,
+                dependencies,
+                release: level.release,
+                preview: level.preview,
+            }
+        })
+        .collect()
+}
+
+fn collect(dir: &Path, parent: LanguageLevel, out: &mut Vec<(Pom, PathBuf, LanguageLevel)>) {
+    let Ok(pom) = load_pom_xml(dir) else {
+        return;
+    };
+    if out.iter().any(|(_, d, _)| d == dir) {
+        return;
+    }
+    let level = LanguageLevel {
+        release: pom.release().or(parent.release),
+        preview: pom.preview().unwrap_or(parent.preview),
+    };
+    let modules: Vec<PathBuf> = pom
+        .modules
+        .iter()
+        .flat_map(|m| m.module.iter().flatten())
+        .map(|m| dir.join(m.trim()))
+        .collect();
+    out.push((pom, dir.to_path_buf(), level));
+    for module in modules {
+        collect(&module, level, out);
+    }
+}
+
+fn source_roots(pom: &Pom, dir: &Path) -> Vec<PathBuf> {
+    let build = pom.build.as_ref();
+    let main = build
+        .and_then(|b| b.source_directory.as_deref())
+        .unwrap_or("src/main/java");
+    let test = build
+        .and_then(|b| b.test_source_directory.as_deref())
+        .unwrap_or("src/test/java");
+    vec![dir.join(main), dir.join(test)]
+}
+
+/// Module that contains the file, the most nested module wins
+#[must_use]
+pub fn module_of<'a>(modules: &'a [MavenModule], file: &Path) -> Option<&'a MavenModule> {
+    modules
+        .iter()
+        .filter(|m| file.starts_with(&m.dir))
+        .max_by_key(|m| m.dir.components().count())
+}
+
+/// Folders that annotation processors and plugins generated sources into, the subfolders of
+/// `target/generated-sources` and `target/generated-test-sources` of the module
+#[must_use]
+pub fn generated_source_roots(module_dir: &Path) -> Vec<PathBuf> {
+    let mut out: Vec<PathBuf> = ["generated-sources", "generated-test-sources"]
+        .iter()
+        .flat_map(|d| std::fs::read_dir(module_dir.join("target").join(d)).into_iter())
+        .flatten()
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.path())
+        .collect();
+    out.sort_unstable();
+    out
+}
+
+/// Output folders of the modules that the module depends on, for the classpath of javac
+#[must_use]
+pub fn dependency_outputs(modules: &[MavenModule], module: &MavenModule) -> Vec<PathBuf> {
+    modules
+        .iter()
+        .filter(|m| module.dependencies.contains(&m.artifact_id))
+        .map(|m| m.dir.join("target").join("classes"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{dependency_outputs, generated_source_roots, load_modules, module_of};
+
+    #[test]
+    fn reactor_modules() {
+        let dir = std::env::temp_dir().join(format!("maven-modules-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let write = |path: &str, content: &str| {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write(
+            "pom.xml",
+            "<project><artifactId>app</artifactId>
+                <properties><maven.compiler.release>17</maven.compiler.release></properties>
+                <modules><module>core</module><module>web</module></modules>
+            </project>",
+        );
+        write(
+            "core/pom.xml",
+            "<project><artifactId>core</artifactId>
+                <properties><maven.compiler.release>11</maven.compiler.release></properties>
+            </project>",
+        );
+        write(
+            "web/pom.xml",
+            "<project><artifactId>web</artifactId>
+                <dependencies>
+                    <dependency><artifactId>core</artifactId></dependency>
+                    <dependency><artifactId>junit</artifactId></dependency>
+                </dependencies>
+                <build><sourceDirectory>src</sourceDirectory></build>
+            </project>",
+        );
+
+        let modules = load_modules(&dir);
+        let ids: Vec<_> = modules.iter().map(|m| m.artifact_id.as_str()).collect();
+        assert_eq!(ids, vec!["app", "core", "web"]);
+        let web = &modules[2];
+        assert_eq!(web.dependencies, vec!["core"]);
+        assert_eq!(web.release, Some(17));
+        assert_eq!(modules[1].release, Some(11));
+        assert_eq!(
+            web.source_roots,
+            vec![dir.join("web/src"), dir.join("web/src/test/java")]
+        );
+        assert_eq!(
+            dependency_outputs(&modules, web),
+            vec![dir.join("core/target/classes")]
+        );
+        let file = dir.join("web/src/ch/emilycares/A.java");
+        assert_eq!(module_of(&modules, &file), Some(web));
+        assert_eq!(module_of(&modules, &PathBuf::from("/other/A.java")), None);
+
+        fs::create_dir_all(dir.join("web/target/generated-sources/annotations")).unwrap();
+        fs::create_dir_all(dir.join("web/target/generated-test-sources/test-annotations")).unwrap();
+        write("web/target/generated-sources/readme.txt", "");
+        assert_eq!(
+            generated_source_roots(&web.dir),
+            vec![
+                dir.join("web/target/generated-sources/annotations"),
+                dir.join("web/target/generated-test-sources/test-annotations"),
+            ]
+        );
+        assert_eq!(
+            generated_source_roots(&modules[1].dir),
+            Vec::<PathBuf>::new()
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use common::language_level::parse_release;
 use serde::Deserialize;
 
 #[derive(Debug)]
@@ -14,6 +15,126 @@ pub struct Pom {
     #[serde(rename = "artifactId")]
     pub artifact_id: String,
     pub repositories: Option<PomRepositories>,
+    pub modules: Option<PomModules>,
+    pub properties: Option<PomProperties>,
+    pub dependencies: Option<PomDependencies>,
+    pub build: Option<PomBuild>,
+}
+
+/// Modules of a reactor build, relative folders of the module poms
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct PomModules {
+    pub module: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct PomProperties {
+    #[serde(rename = "maven.compiler.release")]
+    pub release: Option<String>,
+    #[serde(rename = "maven.compiler.source")]
+    pub source: Option<String>,
+    #[serde(rename = "maven.compiler.enablePreview")]
+    pub enable_preview: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct PomDependencies {
+    pub dependency: Option<Vec<PomDependency>>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct PomDependency {
+    #[serde(rename = "groupId")]
+    pub group_id: Option<String>,
+    #[serde(rename = "artifactId")]
+    pub artifact_id: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct PomBuild {
+    #[serde(rename = "sourceDirectory")]
+    pub source_directory: Option<String>,
+    #[serde(rename = "testSourceDirectory")]
+    pub test_source_directory: Option<String>,
+    pub plugins: Option<PomPlugins>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct PomPlugins {
+    pub plugin: Option<Vec<PomPlugin>>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct PomPlugin {
+    #[serde(rename = "artifactId")]
+    pub artifact_id: String,
+    pub configuration: Option<PomPluginConfiguration>,
+}
+
+/// Configuration of the `maven-compiler-plugin`
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct PomPluginConfiguration {
+    pub release: Option<String>,
+    pub source: Option<String>,
+    #[serde(rename = "enablePreview")]
+    pub enable_preview: Option<String>,
+    #[serde(rename = "compilerArgs")]
+    pub compiler_args: Option<PomCompilerArgs>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct PomCompilerArgs {
+    pub arg: Option<Vec<String>>,
+}
+
+impl Pom {
+    /// Release of the configuration of the compiler plugin, otherwise of `maven.compiler.release`
+    /// or `maven.compiler.source`. `1.8` is 8.
+    #[must_use]
+    pub fn release(&self) -> Option<u32> {
+        let configuration = self.compiler_configuration();
+        let properties = self.properties.as_ref();
+        let release = configuration
+            .and_then(|c| c.release.as_deref().or(c.source.as_deref()))
+            .or_else(|| properties.and_then(|p| p.release.as_deref()))
+            .or_else(|| properties.and_then(|p| p.source.as_deref()))?;
+        parse_release(release)
+    }
+
+    /// Preview features are enabled with `enablePreview` or the `--enable-preview` argument of
+    /// the compiler plugin. None when the pom does not configure it
+    #[must_use]
+    pub fn preview(&self) -> Option<bool> {
+        let configuration = self.compiler_configuration();
+        if configuration
+            .and_then(|c| c.compiler_args.as_ref())
+            .and_then(|a| a.arg.as_ref())
+            .is_some_and(|args| args.iter().any(|a| a.trim() == "--enable-preview"))
+        {
+            return Some(true);
+        }
+        configuration
+            .and_then(|c| c.enable_preview.as_deref())
+            .or_else(|| {
+                self.properties
+                    .as_ref()
+                    .and_then(|p| p.enable_preview.as_deref())
+            })
+            .map(|preview| preview.trim() == "true")
+    }
+
+    fn compiler_configuration(&self) -> Option<&PomPluginConfiguration> {
+        self.build
+            .as_ref()?
+            .plugins
+            .as_ref()?
+            .plugin
+            .iter()
+            .flatten()
+            .find(|p| p.artifact_id == "maven-compiler-plugin")?
+            .configuration
+            .as_ref()
+    }
 }
 
 #[derive(Debug, Deserialize, PartialEq, Eq)]
@@ -35,7 +156,10 @@ pub fn load_pom_xml(project_dir: &Path) -> Result<Pom, PomError> {
 
 #[cfg(test)]
 mod tests {
-    use crate::pom::{Pom, PomRepositories, PomRepository};
+    use crate::pom::{
+        Pom, PomBuild, PomDependencies, PomDependency, PomModules, PomProperties, PomRepositories,
+        PomRepository,
+    };
 
     #[test]
     fn load() {
@@ -68,6 +192,10 @@ mod tests {
                     },
                 ]),
             }),
+            modules: None,
+            properties: None,
+            dependencies: None,
+            build: None,
         };
 
         let out: Pom = serde_xml_rs::from_str(content).unwrap();
@@ -85,6 +213,10 @@ mod tests {
         let expect = Pom {
             artifact_id: String::from("some-service"),
             repositories: None,
+            modules: None,
+            properties: None,
+            dependencies: None,
+            build: None,
         };
 
         let out: Pom = serde_xml_rs::from_str(content).unwrap();
@@ -103,10 +235,102 @@ mod tests {
         let expect = Pom {
             artifact_id: String::from("some-service"),
             repositories: Some(PomRepositories { repository: None }),
+            modules: None,
+            properties: None,
+            dependencies: None,
+            build: None,
+        };
+
+        let out: Pom = serde_xml_rs::from_str(content).unwrap();
+
+        assert_eq!(out, expect);
+    }
+
+    #[test]
+    fn reactor() {
+        let content = "
+        <project>
+            <artifactId>app</artifactId>
+            <properties>
+                <maven.compiler.source>1.8</maven.compiler.source>
+                <project.build.sourceEncoding>UTF-8</project.build.sourceEncoding>
+            </properties>
+            <modules>
+                <module>core</module>
+                <module>web</module>
+            </modules>
+            <dependencies>
+                <dependency>
+                    <groupId>ch.emilycares</groupId>
+                    <artifactId>core</artifactId>
+                    <version>1.0</version>
+                </dependency>
+            </dependencies>
+            <build>
+                <sourceDirectory>src</sourceDirectory>
+            </build>
+        </project>
+        ";
+        let expect = Pom {
+            artifact_id: String::from("app"),
+            repositories: None,
+            modules: Some(PomModules {
+                module: Some(vec!["core".to_string(), "web".to_string()]),
+            }),
+            properties: Some(PomProperties {
+                release: None,
+                source: Some("1.8".to_string()),
+                enable_preview: None,
+            }),
+            dependencies: Some(PomDependencies {
+                dependency: Some(vec![PomDependency {
+                    group_id: Some("ch.emilycares".to_string()),
+                    artifact_id: "core".to_string(),
+                }]),
+            }),
+            build: Some(PomBuild {
+                source_directory: Some("src".to_string()),
+                test_source_directory: None,
+                plugins: None,
+            }),
         };
 
         let out: Pom = serde_xml_rs::from_str(content).unwrap();
 
         assert_eq!(out, expect);
+        assert_eq!(out.release(), Some(8));
+        assert_eq!(out.preview(), None);
+    }
+
+    #[test]
+    fn compiler_plugin() {
+        let content = "
+        <project>
+            <artifactId>app</artifactId>
+            <properties>
+                <maven.compiler.release>17</maven.compiler.release>
+            </properties>
+            <build>
+                <plugins>
+                    <plugin>
+                        <artifactId>maven-surefire-plugin</artifactId>
+                    </plugin>
+                    <plugin>
+                        <artifactId>maven-compiler-plugin</artifactId>
+                        <configuration>
+                            <release>21</release>
+                            <compilerArgs>
+                                <arg>-Xlint:all</arg>
+                                <arg>--enable-preview</arg>
+                            </compilerArgs>
+                        </configuration>
+                    </plugin>
+                </plugins>
+            </build>
+        </project>
+        ";
+        let out: Pom = serde_xml_rs::from_str(content).unwrap();
+        assert_eq!(out.release(), Some(21));
+        assert_eq!(out.preview(), Some(true));
     }
 }
@@ -15,12 +15,16 @@ use common::{
     deps_dir,
 };
 use dto::{Class, ClassFolder, SourceDestination};
-use loader::{DtoRwError, LoaderError};
+use loader::{
+    DtoRwError, LoaderError,
+    classpath::{Classpath, classpath_path},
+};
 use my_string::{MyString, smol_str::ToSmolStr};
 use tokio::task::JoinSet;
 
 use crate::{
     m2::{self, MTwoError},
+    modules,
     repository::Repository,
     update::{self},
 };
@@ -58,6 +62,20 @@ pub async fn project_deps(
     let m2 = m2::get_maven_m2_folder().map_err(MavenProjectError::MTwo)?;
     let m2 = Arc::new(m2);
 
+    // The cfc of a dependency is the shard of its jar
+    let shards: Vec<(PathBuf, PathBuf)> = tree
+        .iter()
+        .filter(|dep| !project_artifacts.contains(&dep.artivact_id))
+        .map(|dep| {
+            let jar = m2::pom_classes_jar(dep, &m2::pom_m2(dep, &m2));
+            (jar, deps_get_cfc(&deps_base(dep, &deps_path), dep))
+        })
+        .collect();
+    let classpath_path = classpath_path(&cache_path);
+    let old_classpath = Classpath::load(&classpath_path);
+    let diff = old_classpath.diff(&Classpath::new(shards.iter().map(|(j, s)| (j, s))));
+    old_classpath.drop_stale(&diff, &class_map);
+
     let mut update_tree = Vec::new();
 
     for dep in tree {
@@ -82,10 +100,15 @@ pub async fn project_deps(
             let _ = fs::create_dir_all(&pom_mtwo);
         }
         let jar = m2::pom_classes_jar(dep, &pom_mtwo);
-        let source = deps_get_source(&deps_bas);
+        let sources_jar = m2::pom_sources_jar(dep, &pom_mtwo);
 
-        if !jar.exists() || !source.exists() {
-            update_tree.push(dep.to_owned());
+        if update::download_dependencies() {
+            if !jar.exists() || !sources_jar.exists() {
+                update_tree.push(dep.to_owned());
+                continue;
+            }
+        } else if !jar.exists() {
+            eprintln!("Not in the local repository: {dep:?}");
             continue;
         }
         let dep = Arc::new(dep.to_owned());
@@ -105,14 +128,13 @@ pub async fn project_deps(
                 Err(LoaderError::DtoRw(DtoRwError::InvalidCfcCache) | LoaderError::IO(_)) => {
                     reindex(
                         tasks_number,
-                        completed_number,
+                        &completed_number,
                         &dep,
-                        deps_bas,
-                        cfc,
-                        jar,
-                        sender,
+                        &deps_bas,
+                        &cfc,
+                        &jar,
+                        &sender,
                     )
-                    .await
                 }
                 Err(e) => {
                     eprintln!("Parse error in {dep:?}, {e:?}");
@@ -138,14 +160,13 @@ pub async fn project_deps(
             handles.spawn(async move {
                 reindex(
                     tasks_number,
-                    completed_number,
+                    &completed_number,
                     &dep,
-                    deps_bas,
-                    cfc,
-                    jar,
-                    sender,
+                    &deps_bas,
+                    &cfc,
+                    &jar,
+                    &sender,
                 )
-                .await
             });
         }
     }
@@ -159,6 +180,9 @@ pub async fn project_deps(
     if let Err(e) = loader::save_class_folder(&cache_path, &maven_class_folder) {
         eprintln!("Failed to save {} because: {e:?}", cache_path.display());
     }
+    if let Err(e) = Classpath::new(shards).save(&classpath_path) {
+        eprintln!("Failed to save {} because: {e:?}", classpath_path.display());
+    }
     if let Ok(mut cm) = class_map.write() {
         for class in maven_class_folder.classes {
             cm.insert(class.class_path.clone(), class);
@@ -167,23 +191,21 @@ pub async fn project_deps(
     Ok(())
 }
 
-async fn reindex(
+fn reindex(
     tasks_number: u32,
-    completed_number: Arc<AtomicU32>,
-    dep: &Arc<Dependency>,
-    deps_bas: PathBuf,
-    cfc: PathBuf,
-    jar: PathBuf,
-    sender: Arc<tokio::sync::watch::Sender<TaskProgress>>,
+    completed_number: &AtomicU32,
+    dep: &Dependency,
+    deps_bas: &PathBuf,
+    cfc: &Path,
+    jar: &Path,
+    sender: &tokio::sync::watch::Sender<TaskProgress>,
 ) -> Option<ClassFolder> {
-    let source = deps_get_source(&deps_bas);
+    let source = deps_get_source(deps_bas);
     if let Some(source) = source.as_path().to_str() {
         match loader::load_classes_jar(
-            &jar,
+            jar,
             SourceDestination::RelativeInFolder(source.to_smolstr()),
-        )
-        .await
-        {
+        ) {
             Ok(classes) => {
                 let a = completed_number.fetch_add(1, Ordering::Relaxed);
                 let _ = sender.send(TaskProgress {
@@ -191,7 +213,7 @@ async fn reindex(
                     error: false,
                     message: dep.artivact_id.clone(),
                 });
-                if let Err(e) = loader::save_class_folder(&cfc, &classes) {
+                if let Err(e) = loader::save_class_folder(cfc, &classes) {
                     eprintln!("Failed to save cache for {}, {e:?}", cfc.display());
                 }
 
@@ -213,14 +235,25 @@ pub fn get_maven_cache_path(project_dir: &Path, project_cache_dir: &Path) -> Pat
     project_cache_dir.join(s)
 }
 
+/// Sources of the project and its modules, `progress` gets the number of parsed files and the
+/// total
 #[must_use]
-pub fn load_project_folders(project_dir: &Path) -> Vec<Class> {
-    let mut out = vec![];
-
-    out.extend(loader::load_java_files(project_dir.join("src/main/java")));
-    out.extend(loader::load_java_files(project_dir.join("src/test/java")));
-
-    // list modules
-    // mvn help:evaluate -Dexpression=project.modules
-    out
+pub fn load_project_folders(
+    project_dir: &Path,
+    progress: impl Fn(usize, usize) + Sync,
+) -> Vec<Class> {
+    let mut folders: Vec<PathBuf> = modules::load_modules(project_dir)
+        .into_iter()
+        .flat_map(|m| {
+            let generated = modules::generated_source_roots(&m.dir);
+            m.source_roots.into_iter().chain(generated)
+        })
+        .collect();
+    if folders.is_empty() {
+        folders = vec![
+            project_dir.join("src/main/java"),
+            project_dir.join("src/test/java"),
+        ];
+    }
+    loader::load_java_folders(folders, progress)
 }
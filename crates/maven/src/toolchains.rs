@@ -0,0 +1,93 @@
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum M2ToolchainsError {
+    IO(std::io::Error),
+    Xml(serde_xml_rs::Error),
+}
+
+/// `~/.m2/toolchains.xml` of the maven toolchains plugin
+#[derive(Debug, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename = "toolchains")]
+pub struct M2Toolchains {
+    #[serde(default)]
+    pub toolchain: Vec<M2Toolchain>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct M2Toolchain {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub configuration: Option<M2ToolchainConfiguration>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct M2ToolchainConfiguration {
+    #[serde(rename = "jdkHome")]
+    pub jdk_home: Option<String>,
+}
+
+impl M2Toolchains {
+    /// Homes of the jdk toolchains
+    #[must_use]
+    pub fn jdk_homes(&self) -> Vec<PathBuf> {
+        self.toolchain
+            .iter()
+            .filter(|t| t.kind == "jdk")
+            .filter_map(|t| t.configuration.as_ref()?.jdk_home.as_ref())
+            .map(|home| PathBuf::from(home.trim()))
+            .collect()
+    }
+}
+
+pub fn load_toolchains_xml(m2_folder: &Path) -> Result<M2Toolchains, M2ToolchainsError> {
+    let path = m2_folder.join("toolchains.xml");
+    if !path.exists() {
+        return Ok(M2Toolchains::default());
+    }
+    let file = std::fs::File::open(path).map_err(M2ToolchainsError::IO)?;
+    serde_xml_rs::from_reader(file).map_err(M2ToolchainsError::Xml)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::toolchains::M2Toolchains;
+
+    #[test]
+    fn jdk_homes() {
+        let content = "
+        <toolchains>
+          <toolchain>
+            <type>jdk</type>
+            <provides>
+              <version>17</version>
+              <vendor>temurin</vendor>
+            </provides>
+            <configuration>
+              <jdkHome>/opt/jdk-17</jdkHome>
+            </configuration>
+          </toolchain>
+          <toolchain>
+            <type>netbeans</type>
+            <configuration>
+              <installDir>/opt/netbeans</installDir>
+            </configuration>
+          </toolchain>
+          <toolchain>
+            <type>jdk</type>
+            <configuration>
+              <jdkHome>/opt/jdk-21</jdkHome>
+            </configuration>
+          </toolchain>
+        </toolchains>
+        ";
+        let toolchains: M2Toolchains = serde_xml_rs::from_str(content).unwrap();
+        assert_eq!(
+            toolchains.jdk_homes(),
+            [PathBuf::from("/opt/jdk-17"), PathBuf::from("/opt/jdk-21")]
+        );
+    }
+}
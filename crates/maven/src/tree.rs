@@ -1,9 +1,16 @@
-use std::str::{Utf8Error, from_utf8};
+use std::{
+    path::Path,
+    str::{Utf8Error, from_utf8},
+    sync::RwLock,
+};
 
-use common::Dependency;
+use common::{
+    Dependency,
+    dependency_tree::{DependencyNode, build_tree},
+};
 use tokio::process::Command;
 
-use crate::config::overwrite_settings_xml_tokio;
+use crate::config::{overwrite_settings_xml, overwrite_settings_xml_tokio};
 
 #[derive(Debug)]
 pub enum MavenTreeError {
@@ -13,11 +20,54 @@ pub enum MavenTreeError {
     GotError(String),
 }
 
+/// Dependencies of the loaded trees that only the tests see
+static TEST_DEPENDENCIES: RwLock<Vec<Dependency>> = RwLock::new(Vec::new());
+
 pub async fn load(maven_executable: &str) -> Result<Vec<Dependency>, MavenTreeError> {
     let log: String = get_cli_output(maven_executable).await?;
     let cut: String = cut_output(&log);
 
-    Ok(parser(&cut))
+    let tree = parser(&cut);
+    let test = test_scoped(&cut);
+    if let Ok(mut dependencies) = TEST_DEPENDENCIES.write() {
+        // A dependency of another scope in this tree is visible to the main sources
+        dependencies.retain(|d| !tree.contains(d) || test.contains(d));
+        for dep in test {
+            if !dependencies.contains(&dep) {
+                dependencies.push(dep);
+            }
+        }
+    }
+    Ok(tree)
+}
+
+/// Dependencies with the test scope of all trees that were loaded
+#[must_use]
+pub fn test_dependencies() -> Vec<Dependency> {
+    TEST_DEPENDENCIES
+        .read()
+        .map(|dependencies| dependencies.clone())
+        .unwrap_or_default()
+}
+
+/// Dependencies that the tree lists with the test scope
+fn test_scoped(cut: &str) -> Vec<Dependency> {
+    let lines = cut.lines().filter(|line| scope(line) == Some("test")).fold(
+        String::new(),
+        |mut out, line| {
+            out.push_str(line);
+            out.push('\n');
+            out
+        },
+    );
+    parser(&lines)
+}
+
+/// Last part of the dependency of an edge `"a:b:jar:1.0" -> "c:d:jar:2.0:test" ;`
+fn scope(line: &str) -> Option<&str> {
+    let (_, dependency) = line.split_once("-> \"")?;
+    let (dependency, _) = dependency.split_once('"')?;
+    dependency.rsplit(':').next()
 }
 
 fn parser(cut: &str) -> Vec<Dependency> {
@@ -70,6 +120,91 @@ async fn get_cli_output(maven_executable: &str) -> Result<String, MavenTreeError
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Dependency graph of the projects of the folder with the versions that lost a conflict
+pub fn load_graph(
+    maven_executable: &str,
+    project_dir: &Path,
+) -> Result<Vec<DependencyNode>, MavenTreeError> {
+    // mvn dependency:tree -Dverbose
+    let mut command = std::process::Command::new(maven_executable);
+    let command = command
+        .current_dir(project_dir)
+        .arg("dependency:tree")
+        .arg("-Dverbose");
+    let output = overwrite_settings_xml(command)
+        .output()
+        .map_err(MavenTreeError::Cli)?;
+    if !output.status.success() {
+        let err = from_utf8(&output.stdout).map_err(MavenTreeError::Utf8)?;
+        return Err(MavenTreeError::GotError(err.to_owned()));
+    }
+    Ok(parse_graph(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Trees of the text output, every project of the reactor is a root
+fn parse_graph(output: &str) -> Vec<DependencyNode> {
+    let mut lines = Vec::new();
+    let mut capture = false;
+    for line in output.lines() {
+        let Some(line) = line.strip_prefix("[INFO] ") else {
+            capture = false;
+            continue;
+        };
+        if line.starts_with("--- ") && line.contains(":tree ") {
+            capture = true;
+            continue;
+        }
+        if !capture || line.trim().is_empty() || line.starts_with("---") {
+            capture = false;
+            continue;
+        }
+        let content = line.trim_start_matches(['|', '+', '\\', '-', ' ']);
+        if let Some(node) = graph_node(content) {
+            lines.push(((line.len() - content.len()) / 3, node));
+        }
+    }
+    build_tree(lines)
+}
+
+/// `org.slf4j:slf4j-api:jar:2.0.9:compile (optional)` or
+/// `(org.slf4j:slf4j-api:jar:1.7.30:compile - omitted for conflict with 2.0.9)`
+fn graph_node(content: &str) -> Option<DependencyNode> {
+    let (coordinates, omitted) = content
+        .strip_prefix('(')
+        .and_then(|c| c.strip_suffix(')'))
+        .map_or((content, None), |inner| {
+            inner
+                .split_once(" - ")
+                .map_or((inner, None), |(c, reason)| (c, Some(reason.to_string())))
+        });
+    let (coordinates, rest) = coordinates.split_once(' ').unwrap_or((coordinates, ""));
+    let parts: Vec<&str> = coordinates.split(':').collect();
+    let (group_id, artifact_id, version, scope) = match parts.as_slice() {
+        // The project itself
+        [group_id, artifact_id, _, version] => (group_id, artifact_id, version, None),
+        [group_id, artifact_id, _, version, scope]
+        | [group_id, artifact_id, _, _, version, scope] => {
+            (group_id, artifact_id, version, Some(scope.to_string()))
+        }
+        _ => return None,
+    };
+    let resolved_version = omitted.as_deref().and_then(|reason| {
+        reason
+            .strip_prefix("omitted for conflict with ")
+            .map(ToString::to_string)
+    });
+    Some(DependencyNode {
+        group_id: (*group_id).to_string(),
+        artifact_id: (*artifact_id).to_string(),
+        version: (*version).to_string(),
+        scope,
+        optional: rest.contains("(optional)"),
+        resolved_version,
+        omitted,
+        children: Vec::new(),
+    })
+}
+
 fn cut_output(inp: &str) -> String {
     let mut out = String::new();
 
@@ -95,9 +230,44 @@ fn cut_output(inp: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::tree::{cut_output, parser};
+    use crate::tree::{cut_output, parse_graph, parser, test_scoped};
     use common::Dependency;
 
+    #[test]
+    fn parse_verbose_graph() {
+        let graph = parse_graph(include_str!("../tests/tree-verbose.txt"));
+        assert_eq!(graph.len(), 1);
+        let app = &graph[0];
+        assert_eq!(app.artifact_id, "app");
+        assert_eq!(app.scope, None);
+        let names: Vec<&str> = app
+            .children
+            .iter()
+            .map(|c| c.artifact_id.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "slf4j-simple",
+                "slf4j-api",
+                "jsr305",
+                "querydsl-apt",
+                "junit-jupiter"
+            ]
+        );
+        let omitted = &app.children[0].children[0];
+        assert_eq!(omitted.version, "1.7.30");
+        assert_eq!(omitted.resolved_version.as_deref(), Some("2.0.9"));
+        assert_eq!(
+            omitted.omitted.as_deref(),
+            Some("omitted for conflict with 2.0.9")
+        );
+        assert!(app.children[2].optional);
+        assert_eq!(app.children[3].version, "5.1.0");
+        assert_eq!(app.children[4].scope.as_deref(), Some("test"));
+        assert_eq!(app.children[4].children[0].artifact_id, "junit-jupiter-api");
+    }
+
     #[test]
     fn cut_basic() {
         let inp = include_str!("../tests/tverify.basic.txt");
@@ -164,6 +334,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_scope() {
+        let inp = include_str!("../tests/tverify.basic.txt");
+        let cut = cut_output(inp);
+        let test: Vec<_> = test_scoped(&cut)
+            .into_iter()
+            .map(|d| d.artivact_id)
+            .collect();
+        assert!(test.contains(&"quarkus-junit5".to_string()));
+        assert!(test.contains(&"junit-jupiter".to_string()));
+        assert!(test.contains(&"rest-assured".to_string()));
+        assert!(!test.contains(&"quarkus-rest".to_string()));
+    }
+
     #[test]
     fn parse_diagram() {
         let inp = include_str!("../tests/tverify.basic.txt");
@@ -4,7 +4,7 @@ use std::{
     path::PathBuf,
     sync::{
         Arc, Mutex,
-        atomic::{AtomicU32, Ordering},
+        atomic::{AtomicBool, AtomicU32, Ordering},
     },
     time::Duration,
 };
@@ -20,7 +20,7 @@ use curl::easy::{Easy, List};
 use curl::multi::{EasyHandle, Multi};
 use dto::SourceDestination;
 use my_string::smol_str::ToSmolStr;
-use tokio::{fs::remove_dir, task::JoinSet};
+use tokio::{fs::remove_dir_all, task::JoinSet};
 use tokio::{
     fs::{create_dir_all, write},
     sync::{mpsc, oneshot},
@@ -45,6 +45,20 @@ pub enum MavenUpdateError {
     WriteJar(std::io::Error),
     CreateDir(std::io::Error),
     WriteEtag(std::io::Error),
+    /// The download does not match the published sha1, the url of the download
+    Checksum(String),
+}
+
+static DOWNLOAD_DEPENDENCIES: AtomicBool = AtomicBool::new(true);
+
+/// Whether dependencies that are missing in the local repository are downloaded
+pub fn set_download_dependencies(download: bool) {
+    DOWNLOAD_DEPENDENCIES.store(download, Ordering::Relaxed);
+}
+
+#[must_use]
+pub fn download_dependencies() -> bool {
+    DOWNLOAD_DEPENDENCIES.load(Ordering::Relaxed)
 }
 
 pub struct CurlResponse {
@@ -359,7 +373,7 @@ pub async fn update(
         }
         if !found && matches!(one, UpdateStateOne::WasUpdated) {
             handles.spawn(async move {
-                index_jar(pom, &deps_bas, &jar, &d_source).await;
+                index_jar(&pom, &deps_bas, &jar, &d_source);
             });
         }
     }
@@ -390,7 +404,7 @@ fn handle_repo_retry(
                 let deps_bas = deps_bas.clone();
                 let client = client.clone();
                 handles.spawn(async move {
-                    fetch_extract_source(
+                    fetch_sources_jar(
                         f_source,
                         pom_mtwo,
                         d_source,
@@ -403,14 +417,14 @@ fn handle_repo_retry(
                 });
             }
             handles.spawn(async move {
-                index_jar(pom, &deps_bas, &jar, &d_source).await;
+                index_jar(&pom, &deps_bas, &jar, &d_source);
             });
         }
         Ok(UpdateStateTwo::AlreadyLatest) => {
             let cfc = deps_get_cfc(&deps_bas, &pom);
             if !cfc.exists() {
                 handles.spawn(async move {
-                    index_jar(pom, &deps_bas, &jar, &d_source).await;
+                    index_jar(&pom, &deps_bas, &jar, &d_source);
                 });
             }
         }
@@ -420,7 +434,8 @@ fn handle_repo_retry(
     false
 }
 
-pub async fn fetch_extract_source(
+/// Download the sources jar, its files are extracted on first use
+pub async fn fetch_sources_jar(
     f_source: Arc<PathBuf>,
     pom_mtwo: Arc<PathBuf>,
     d_source: Arc<PathBuf>,
@@ -431,12 +446,8 @@ pub async fn fetch_extract_source(
 ) -> bool {
     match fetch_source(&pom_mtwo, &repo, &f_source, &deps_bas, &client, url).await {
         Ok(UpdateStateSource::Updated) => {
-            let _ = remove_dir(&d_source.as_path()).await;
-            eprintln!("Extract: {}", f_source.display());
-            match zip_util::extract_jar(&f_source, &d_source).await {
-                Ok(()) => (),
-                Err(e) => eprintln!("unable to extract jar {e:?}"),
-            }
+            // Files extracted from the previous sources jar are outdated
+            let _ = remove_dir_all(d_source.as_path()).await;
             return true;
         }
         Ok(UpdateStateSource::NotFound) => {
@@ -447,18 +458,16 @@ pub async fn fetch_extract_source(
     false
 }
 
-async fn index_jar(pom: Arc<Dependency>, deps_bas: &PathBuf, jar: &PathBuf, d_source: &DepsSource) {
+fn index_jar(pom: &Dependency, deps_bas: &PathBuf, jar: &PathBuf, d_source: &DepsSource) {
     let Some(source) = d_source.as_path().to_str() else {
         return;
     };
     match loader::load_classes_jar(
         jar,
         SourceDestination::RelativeInFolder(source.to_smolstr()),
-    )
-    .await
-    {
+    ) {
         Ok(classes) => {
-            let cfc = deps_get_cfc(deps_bas, &pom);
+            let cfc = deps_get_cfc(deps_bas, pom);
             if let Err(e) = loader::save_class_folder(&cfc, &classes) {
                 eprintln!("Failed to save cache for {}, {e:?}", cfc.display());
             }
@@ -485,9 +494,7 @@ pub fn stage_one(pom: &Dependency, deps_bas: &DepsBas, pom_mtwo: &PomMTwo) -> Up
     if !own_hash.exists() {
         return UpdateStateOne::NoOwnHash;
     }
-    let source = deps_get_source(deps_bas);
-
-    if !source.exists() {
+    if !pom_sources_jar(pom, pom_mtwo).exists() {
         return UpdateStateOne::SourceNotFound;
     }
     let jar = pom_classes_jar(pom, pom_mtwo);
@@ -576,30 +583,13 @@ pub async fn stage_two(
 
     let hash_path = deps_get_hash(deps_bas, &pom);
     let sha1_path = pom_m2_sha1(&pom, &pom_mtwo);
-
-    if let Some(sha) = resp.headers.get("x-checksum-sha1") {
-        let sha_bytes = sha.as_bytes().to_vec();
-        write(&hash_path, &sha_bytes)
-            .await
-            .map_err(MavenUpdateError::WriteHash)?;
-        write(&sha1_path, &sha_bytes)
-            .await
-            .map_err(MavenUpdateError::WriteHash)?;
-    } else {
-        let sha_resp = client
-            .get(
-                &pom_jar_sha_url(&pom, &repo.url),
-                repo.credentials.as_ref(),
-                None,
-            )
-            .await?;
-        write(&hash_path, &sha_resp.body)
-            .await
-            .map_err(MavenUpdateError::WriteHash)?;
-        write(&sha1_path, &sha_resp.body)
-            .await
-            .map_err(MavenUpdateError::WriteHash)?;
-    }
+    let sha = verify_checksum(&resp, repo, client, jar_url).await?;
+    write(&hash_path, &sha)
+        .await
+        .map_err(MavenUpdateError::WriteHash)?;
+    write(&sha1_path, &sha)
+        .await
+        .map_err(MavenUpdateError::WriteHash)?;
 
     write(jar.as_ref(), &resp.body)
         .await
@@ -634,6 +624,7 @@ pub async fn fetch_source(
     if resp.status == 404 || !resp.status_is_success() {
         return Ok(UpdateStateSource::NotFound);
     }
+    verify_checksum(&resp, repo, client, url).await?;
     write(source, &resp.body)
         .await
         .map_err(MavenUpdateError::WriteJar)?;
@@ -680,15 +671,43 @@ fn pom_url_base(pom: &Dependency, repo: &str, suffix: &str) -> String {
         suffix
     )
 }
-fn pom_jar_sha_url(pom: &Dependency, repo: &str) -> String {
-    format!(
-        "{repo}{}/{}/{}/{}-{}.jar.sha1",
-        pom.group_id.replace('.', "/"),
-        pom.artivact_id,
-        pom.version,
-        pom.artivact_id,
-        pom.version
-    )
+/// Sha1 of the download, it has to match the sha1 that the repository publishes in a header or
+/// in a `.sha1` file next to it. Without a published sha1 the download is accepted.
+async fn verify_checksum(
+    resp: &CurlResponse,
+    repo: &Repository,
+    client: &Arc<CurlClient>,
+    url: &str,
+) -> Result<String, MavenUpdateError> {
+    let expected = if let Some(sha) = resp.headers.get("x-checksum-sha1") {
+        parse_sha1(sha)
+    } else {
+        let sha_resp = client
+            .get(&format!("{url}.sha1"), repo.credentials.as_ref(), None)
+            .await?;
+        if sha_resp.status_is_success() {
+            std::str::from_utf8(&sha_resp.body)
+                .ok()
+                .and_then(parse_sha1)
+        } else {
+            None
+        }
+    };
+    let actual = jar_sha1(&resp.body);
+    match expected {
+        Some(expected) if expected != actual => Err(MavenUpdateError::Checksum(url.to_owned())),
+        Some(_) => Ok(actual),
+        None => {
+            eprintln!("No sha1 published for {url}");
+            Ok(actual)
+        }
+    }
+}
+
+/// A `.sha1` file has the hash and sometimes the file name
+fn parse_sha1(content: &str) -> Option<String> {
+    let sha = content.split_whitespace().next()?.to_lowercase();
+    (sha.len() == 40 && sha.bytes().all(|b| b.is_ascii_hexdigit())).then_some(sha)
 }
 
 fn jar_sha1(jar: &[u8]) -> String {
@@ -696,3 +715,20 @@ fn jar_sha1(jar: &[u8]) -> String {
     m.update(jar);
     m.digest().to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{jar_sha1, parse_sha1};
+
+    #[test]
+    fn sha1_files() {
+        let sha = jar_sha1(b"jar");
+        assert_eq!(parse_sha1(&sha), Some(sha.clone()));
+        assert_eq!(
+            parse_sha1(&format!("{}  guava-33.0.jar\n", sha.to_uppercase())),
+            Some(sha)
+        );
+        assert_eq!(parse_sha1("<html>not found</html>"), None);
+        assert_eq!(parse_sha1(""), None);
+    }
+}
@@ -0,0 +1,611 @@
+//! Selection of the methods that a call invokes, following the phases of JLS 15.12.2
+#![deny(clippy::redundant_clone)]
+#![deny(clippy::pedantic)]
+#![deny(clippy::nursery)]
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::implicit_hasher)]
+use std::collections::{HashMap, HashSet};
+
+use dto::{Access, Class, JType, Method, SuperClass};
+use my_string::MyString;
+
+pub const OBJECT: &str = "java.lang.Object";
+
+/// Type of an argument of a call
+#[derive(Debug, Clone, PartialEq)]
+pub enum Argument {
+    /// The type is not known
+    Unknown,
+    Null,
+    Typed(JType),
+}
+
+/// Parameter types of a method or constructor. None for types that are not known, the last
+/// parameter of a variable arity method is an array
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    pub parameters: Vec<Option<JType>>,
+    pub variadic: bool,
+}
+
+impl Signature {
+    #[must_use]
+    pub fn of(method: &Method) -> Self {
+        Self {
+            parameters: method
+                .parameters
+                .iter()
+                .map(|p| Some(p.jtype.clone()))
+                .collect(),
+            variadic: method.access.contains(Access::Varargs),
+        }
+    }
+
+    /// Can be called with `count` arguments
+    #[must_use]
+    pub const fn accepts(&self, count: usize) -> bool {
+        if self.variadic {
+            return count + 1 >= self.parameters.len();
+        }
+        count == self.parameters.len()
+    }
+
+    /// The last parameter is an array or not known
+    fn is_varargs(&self) -> bool {
+        self.variadic && matches!(self.parameters.last(), Some(Some(JType::Array(_)) | None))
+    }
+
+    /// Element type of the varargs parameter, None when it is not known
+    fn varargs_element(&self) -> Option<&JType> {
+        match self.parameters.last()? {
+            Some(JType::Array(element)) => Some(element),
+            _ => None,
+        }
+    }
+
+    /// Parameter types for `count` arguments, the varargs parameter is repeated
+    fn expanded(&self, count: usize, phase: Phase) -> Vec<Option<&JType>> {
+        if phase != Phase::VariableArity || !self.is_varargs() {
+            return self.parameters.iter().map(Option::as_ref).collect();
+        }
+        let element = self.varargs_element();
+        let fixed = self.parameters.len() - 1;
+        let mut out: Vec<Option<&JType>> = self.parameters[..fixed]
+            .iter()
+            .map(Option::as_ref)
+            .collect();
+        out.resize(count.max(fixed + 1), element);
+        out
+    }
+}
+
+/// Phases of JLS 15.12.2.2 to 15.12.2.4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Identity and widening
+    Strict,
+    /// Boxing and unboxing
+    Loose,
+    VariableArity,
+}
+
+/// Most specific applicable methods of the first phase that found one. There is more than
+/// one when it is ambiguous or arguments are not known.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Selection {
+    pub phase: Phase,
+    /// Indexes of the signatures
+    pub methods: Vec<usize>,
+}
+
+/// Methods that a call with the arguments invokes. None when no method is applicable.
+#[must_use]
+pub fn select(
+    signatures: &[Signature],
+    arguments: &[Argument],
+    class_map: &HashMap<MyString, Class>,
+) -> Option<Selection> {
+    [Phase::Strict, Phase::Loose, Phase::VariableArity]
+        .into_iter()
+        .find_map(|phase| {
+            let found: Vec<usize> = signatures
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| applicable(s, arguments, phase, class_map))
+                .map(|(i, _)| i)
+                .collect();
+            if found.is_empty() {
+                return None;
+            }
+            let count = arguments.len();
+            let strictly = |a: usize, b: usize| {
+                more_specific(&signatures[a], &signatures[b], count, phase, class_map)
+                    && !more_specific(&signatures[b], &signatures[a], count, phase, class_map)
+            };
+            let methods = found
+                .iter()
+                .copied()
+                .filter(|i| !found.iter().any(|j| j != i && strictly(*j, *i)))
+                .collect();
+            Some(Selection { phase, methods })
+        })
+}
+
+/// Methods that a call with the arguments invokes, in the order of `methods`
+#[must_use]
+pub fn select_methods<'a>(
+    methods: impl IntoIterator<Item = &'a Method>,
+    arguments: &[Argument],
+    class_map: &HashMap<MyString, Class>,
+) -> Vec<&'a Method> {
+    let methods: Vec<&Method> = methods.into_iter().collect();
+    let signatures: Vec<Signature> = methods.iter().map(|m| Signature::of(m)).collect();
+    select(&signatures, arguments, class_map).map_or_else(Vec::new, |selection| {
+        selection.methods.into_iter().map(|i| methods[i]).collect()
+    })
+}
+
+/// Arguments of the parameter types of the method, to find the overload of another class
+/// that has the same parameters
+#[must_use]
+pub fn parameter_arguments(method: &Method) -> Vec<Argument> {
+    method
+        .parameters
+        .iter()
+        .map(|p| Argument::Typed(p.jtype.clone()))
+        .collect()
+}
+
+/// Can the arguments so far start a call of the method, for calls that are typed
+#[must_use]
+pub fn accepts_prefix(
+    signature: &Signature,
+    arguments: &[Argument],
+    class_map: &HashMap<MyString, Class>,
+) -> bool {
+    if !signature.variadic && arguments.len() > signature.parameters.len() {
+        return false;
+    }
+    let parameters = signature.expanded(arguments.len(), Phase::VariableArity);
+    parameters
+        .iter()
+        .zip(arguments)
+        .all(|(p, a)| p.is_none_or(|p| compatible(a, p, true, class_map) != Some(false)))
+}
+
+fn applicable(
+    signature: &Signature,
+    arguments: &[Argument],
+    phase: Phase,
+    class_map: &HashMap<MyString, Class>,
+) -> bool {
+    let loose = phase != Phase::Strict;
+    if phase == Phase::VariableArity {
+        if !signature.is_varargs() || !signature.accepts(arguments.len()) {
+            return false;
+        }
+    } else if signature.parameters.len() != arguments.len() {
+        return false;
+    }
+    signature
+        .expanded(arguments.len(), phase)
+        .iter()
+        .zip(arguments)
+        .all(|(p, a)| p.is_none_or(|p| compatible(a, p, loose, class_map) != Some(false)))
+}
+
+/// Every parameter of `a` can be passed to the one of `b`
+fn more_specific(
+    a: &Signature,
+    b: &Signature,
+    count: usize,
+    phase: Phase,
+    class_map: &HashMap<MyString, Class>,
+) -> bool {
+    let mut a = a.expanded(count, phase);
+    let mut b = b.expanded(count, phase);
+    let len = a.len().max(b.len());
+    if let Some(last) = a.last().copied() {
+        a.resize(len, last);
+    }
+    if let Some(last) = b.last().copied() {
+        b.resize(len, last);
+    }
+    a.iter().zip(&b).all(|(a, b)| match (a, b) {
+        (Some(a), Some(b)) => a == b || convertible(a, b, false, class_map) == Some(true),
+        (None, None) => true,
+        _ => false,
+    })
+}
+
+/// Can the argument be passed to the parameter. None when it is not known
+fn compatible(
+    argument: &Argument,
+    parameter: &JType,
+    loose: bool,
+    class_map: &HashMap<MyString, Class>,
+) -> Option<bool> {
+    match argument {
+        Argument::Unknown => None,
+        Argument::Null => Some(boxed(parameter).is_none()),
+        Argument::Typed(from) => convertible(from, parameter, loose, class_map),
+    }
+}
+
+/// Can a value of type `from` be converted to `to` in an invocation context, with boxing
+/// and unboxing when `loose`. None when it is not known
+#[must_use]
+pub fn convertible(
+    from: &JType,
+    to: &JType,
+    loose: bool,
+    class_map: &HashMap<MyString, Class>,
+) -> Option<bool> {
+    if from == to {
+        return Some(true);
+    }
+    let from_primitive = boxed(from).is_some();
+    let to_primitive = boxed(to).is_some();
+    match (from, to) {
+        (_, JType::Parameter(_) | JType::Wildcard | JType::Var | JType::Extends { .. }) => {
+            Some(loose || !from_primitive)
+        }
+        _ if from_primitive && to_primitive => Some(widens(from, to)),
+        (JType::Array(_), _) if to_primitive => Some(false),
+        (_, JType::Array(_)) if from_primitive => Some(false),
+        _ if from_primitive => {
+            if !loose {
+                return Some(false);
+            }
+            is_subtype(class_map, boxed(from)?, erasure(to)?)
+        }
+        _ if to_primitive => {
+            if !loose {
+                return Some(false);
+            }
+            Some(unboxed(erasure(from)?).is_some_and(|p| widens(&p, to)))
+        }
+        (JType::Array(a), JType::Array(b)) => {
+            if boxed(a).is_some() || boxed(b).is_some() {
+                return Some(a == b);
+            }
+            convertible(a, b, false, class_map)
+        }
+        (JType::Array(_), _) => {
+            let to = erasure(to)?;
+            Some(
+                same_class(to, OBJECT)
+                    || same_class(to, "java.lang.Cloneable")
+                    || same_class(to, "java.io.Serializable"),
+            )
+        }
+        (_, JType::Array(_)) => erasure(from).map(|_| false),
+        _ if erasure(to).is_some_and(|to| same_class(to, OBJECT)) => Some(true),
+        _ => is_subtype(class_map, erasure(from)?, erasure(to)?),
+    }
+}
+
+/// Is `class_path` the class `target` or does it extend or implement it. None when a class
+/// of the hierarchy is not known
+#[must_use]
+pub fn is_subtype(
+    class_map: &HashMap<MyString, Class>,
+    class_path: &str,
+    target: &str,
+) -> Option<bool> {
+    if same_class(class_path, target) || same_class(target, OBJECT) {
+        return Some(true);
+    }
+    let mut pending = vec![class_path];
+    let mut visited = HashSet::new();
+    let mut known = true;
+    while let Some(class_path) = pending.pop() {
+        if !visited.insert(class_path) {
+            continue;
+        }
+        let class = class_map.get(class_path).or_else(|| {
+            // Names of the source without an import
+            (!class_path.contains('.'))
+                .then(|| class_map.get(format!("java.lang.{class_path}").as_str()))
+                .flatten()
+        });
+        let Some(class) = class else {
+            known = false;
+            continue;
+        };
+        for s in std::iter::once(&class.super_class).chain(&class.super_interfaces) {
+            match s {
+                SuperClass::None => (),
+                SuperClass::Name(name) | SuperClass::ClassPath(name) => {
+                    if same_class(name, target) {
+                        return Some(true);
+                    }
+                    pending.push(name);
+                }
+            }
+        }
+    }
+    known.then_some(false)
+}
+
+/// Types of the type parameters of the method from the arguments of the call, primitives
+/// are boxed
+#[must_use]
+pub fn infer(signature: &Signature, arguments: &[Argument]) -> HashMap<MyString, JType> {
+    let mut out = HashMap::new();
+    let parameters = signature.expanded(arguments.len(), Phase::VariableArity);
+    for (parameter, argument) in parameters.iter().zip(arguments) {
+        if let (Some(parameter), Argument::Typed(argument)) = (parameter, argument) {
+            bind(parameter, argument, &mut out);
+        }
+    }
+    out
+}
+
+fn bind(parameter: &JType, argument: &JType, out: &mut HashMap<MyString, JType>) {
+    match (parameter, argument) {
+        (JType::Parameter(name), _) => {
+            let argument = boxed(argument).map_or_else(
+                || argument.clone(),
+                |class_path| JType::Class(class_path.into()),
+            );
+            out.entry(name.clone()).or_insert(argument);
+        }
+        (JType::Array(parameter), JType::Array(argument)) => bind(parameter, argument, out),
+        (JType::Generic(name, parameters), JType::Generic(argument_name, arguments))
+            if same_class(name, argument_name) =>
+        {
+            for (parameter, argument) in parameters.iter().zip(arguments) {
+                bind(parameter, argument, out);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Replace the inferred type parameters in a type
+#[must_use]
+pub fn substitute(jtype: &JType, bindings: &HashMap<MyString, JType>) -> JType {
+    match jtype {
+        JType::Parameter(name) => bindings.get(name).cloned().unwrap_or_else(|| jtype.clone()),
+        JType::Array(inner) => JType::Array(Box::new(substitute(inner, bindings))),
+        JType::Generic(name, arguments) => JType::Generic(
+            name.clone(),
+            arguments.iter().map(|a| substitute(a, bindings)).collect(),
+        ),
+        _ => jtype.clone(),
+    }
+}
+
+/// Class of a reference type
+fn erasure(jtype: &JType) -> Option<&str> {
+    match jtype {
+        JType::Class(c) | JType::ClassOrPackage(c) | JType::Generic(c, _) => Some(c),
+        _ => None,
+    }
+}
+
+/// Types of the source are not always qualified, `String` is `java.lang.String`
+fn same_class(a: &str, b: &str) -> bool {
+    let simple =
+        |a: &str, b: &str| !a.contains('.') && b.strip_suffix(a).is_some_and(|p| p.ends_with('.'));
+    a == b || simple(a, b) || simple(b, a)
+}
+
+/// Primitive widening
+#[must_use]
+pub fn widens(from: &JType, to: &JType) -> bool {
+    from == to
+        || matches!(
+            (from, to),
+            (
+                JType::Byte,
+                JType::Short | JType::Int | JType::Long | JType::Float | JType::Double
+            ) | (
+                JType::Short | JType::Char,
+                JType::Int | JType::Long | JType::Float | JType::Double
+            ) | (JType::Int, JType::Long | JType::Float | JType::Double)
+                | (JType::Long, JType::Float | JType::Double)
+                | (JType::Float, JType::Double)
+        )
+}
+
+/// Box type of a primitive
+#[must_use]
+pub const fn boxed(jtype: &JType) -> Option<&'static str> {
+    match jtype {
+        JType::Byte => Some("java.lang.Byte"),
+        JType::Short => Some("java.lang.Short"),
+        JType::Char => Some("java.lang.Character"),
+        JType::Int => Some("java.lang.Integer"),
+        JType::Long => Some("java.lang.Long"),
+        JType::Float => Some("java.lang.Float"),
+        JType::Double => Some("java.lang.Double"),
+        JType::Boolean => Some("java.lang.Boolean"),
+        _ => None,
+    }
+}
+
+/// Primitive of a box type
+#[must_use]
+pub fn unboxed(class_path: &str) -> Option<JType> {
+    [
+        JType::Byte,
+        JType::Short,
+        JType::Char,
+        JType::Int,
+        JType::Long,
+        JType::Float,
+        JType::Double,
+        JType::Boolean,
+    ]
+    .into_iter()
+    .find(|p| boxed(p).is_some_and(|b| same_class(class_path, b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dto::{Class, JType, SuperClass};
+    use my_string::MyString;
+
+    use super::*;
+
+    fn class_map() -> HashMap<MyString, Class> {
+        let class = |class_path: &str, super_class: &str, interfaces: &[&str]| {
+            (
+                MyString::from(class_path),
+                Class {
+                    class_path: class_path.into(),
+                    super_class: if super_class.is_empty() {
+                        SuperClass::None
+                    } else {
+                        SuperClass::ClassPath(super_class.into())
+                    },
+                    super_interfaces: interfaces
+                        .iter()
+                        .map(|i| SuperClass::ClassPath((*i).into()))
+                        .collect(),
+                    ..Default::default()
+                },
+            )
+        };
+        HashMap::from([
+            class(OBJECT, "", &[]),
+            class(
+                "java.lang.String",
+                OBJECT,
+                &["java.lang.CharSequence", "java.lang.Comparable"],
+            ),
+            class("java.lang.CharSequence", OBJECT, &[]),
+            class("java.lang.Comparable", OBJECT, &[]),
+            class("java.lang.Integer", "java.lang.Number", &[]),
+            class("java.lang.Long", "java.lang.Number", &[]),
+            class("java.lang.Number", OBJECT, &[]),
+        ])
+    }
+
+    fn class(name: &str) -> JType {
+        JType::Class(name.into())
+    }
+
+    fn fixed(parameters: Vec<JType>) -> Signature {
+        Signature {
+            parameters: parameters.into_iter().map(Some).collect(),
+            variadic: false,
+        }
+    }
+
+    fn varargs(parameters: Vec<JType>) -> Signature {
+        Signature {
+            variadic: true,
+            ..fixed(parameters)
+        }
+    }
+
+    fn typed(jtype: JType) -> Argument {
+        Argument::Typed(jtype)
+    }
+
+    #[test]
+    fn phases() {
+        let class_map = class_map();
+        let signatures = [
+            fixed(vec![JType::Long]),
+            fixed(vec![class("java.lang.Integer")]),
+            varargs(vec![JType::Array(Box::new(JType::Int))]),
+        ];
+        // Widening before boxing
+        let selection = select(&signatures, &[typed(JType::Int)], &class_map);
+        assert_eq!(
+            selection,
+            Some(Selection {
+                phase: Phase::Strict,
+                methods: vec![0],
+            })
+        );
+        // Boxing before varargs
+        let selection = select(
+            &signatures,
+            &[typed(class("java.lang.Integer"))],
+            &class_map,
+        );
+        assert_eq!(selection.map(|s| s.methods), Some(vec![1]));
+        let selection = select(
+            &signatures,
+            &[typed(JType::Int), typed(JType::Int)],
+            &class_map,
+        );
+        assert_eq!(
+            selection,
+            Some(Selection {
+                phase: Phase::VariableArity,
+                methods: vec![2],
+            })
+        );
+        assert_eq!(
+            select(&signatures, &[], &class_map).map(|s| s.methods),
+            Some(vec![2])
+        );
+        assert_eq!(
+            select(&signatures, &[typed(class("java.lang.String"))], &class_map),
+            None
+        );
+        let strings = JType::Array(Box::new(class("java.lang.String")));
+        assert_eq!(
+            convertible(&JType::Int, &strings, true, &class_map),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn most_specific() {
+        let class_map = class_map();
+        let signatures = [
+            fixed(vec![class(OBJECT)]),
+            fixed(vec![class("java.lang.CharSequence")]),
+            fixed(vec![class("java.lang.String")]),
+            fixed(vec![class("java.lang.Number")]),
+        ];
+        let select = |argument| {
+            select(&signatures, &[argument], &class_map)
+                .map(|s| s.methods)
+                .unwrap_or_default()
+        };
+        assert_eq!(select(typed(class("java.lang.String"))), vec![2]);
+        assert_eq!(select(typed(class("java.lang.Integer"))), vec![3]);
+        assert_eq!(select(typed(JType::Int)), vec![3]);
+        // Ambiguous
+        assert_eq!(select(Argument::Null), vec![2, 3]);
+        // Not known arguments match every overload
+        assert_eq!(select(Argument::Unknown), vec![2, 3]);
+        // Simple names of the source
+        assert_eq!(select(typed(class("String"))), vec![2]);
+    }
+
+    #[test]
+    fn prefix_and_inference() {
+        let class_map = class_map();
+        let signature = fixed(vec![JType::Parameter("T".into()), JType::Int]);
+        assert!(accepts_prefix(&signature, &[], &class_map));
+        assert!(accepts_prefix(
+            &signature,
+            &[typed(class("java.lang.String"))],
+            &class_map
+        ));
+        assert!(!accepts_prefix(
+            &signature,
+            &[Argument::Unknown, typed(class("java.lang.String"))],
+            &class_map
+        ));
+        let bindings = infer(&signature, &[typed(JType::Long), typed(JType::Int)]);
+        assert_eq!(
+            substitute(
+                &JType::Generic("java.util.List".into(), vec![JType::Parameter("T".into())]),
+                &bindings
+            ),
+            JType::Generic("java.util.List".into(), vec![class("java.lang.Long")])
+        );
+    }
+}
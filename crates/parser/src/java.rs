@@ -4,8 +4,9 @@ use ast::{
     types::{
         AstAnnotated, AstAnnotationField, AstClassConstructor, AstClassMethod, AstClassVariable,
         AstEnumerationVariant, AstExtends, AstFile, AstInterfaceConstant, AstInterfaceMethod,
-        AstInterfaceMethodDefault, AstJTypeKind, AstSuperClass, AstThing, AstTopLevel,
-        AstTypeParameter, AstTypeParameters,
+        AstInterfaceMethodDefault, AstJType, AstJTypeKind, AstMethodParameterFlags,
+        AstMethodParameters, AstSuperClass, AstThing, AstTopLevel, AstTypeParameter,
+        AstTypeParameters,
     },
 };
 use my_string::{
@@ -72,6 +73,7 @@ pub fn load_java_tree(ast: &AstFile, source: SourceDestination) -> Class {
                                 .map(|i| convert_class_method(i, class.type_parameters.as_ref())),
                         );
                         fields.extend(class.block.variables.iter().map(convert_class_field));
+                        super_interfaces.extend(super_types(&class.implements, &imports));
                         super_class = match &class.superclass.first() {
                             None | Some(AstSuperClass::None | AstSuperClass::JType(_)) => {
                                 SuperClass::None
@@ -92,6 +94,7 @@ pub fn load_java_tree(ast: &AstFile, source: SourceDestination) -> Class {
                                 .map(|i| convert_class_method(i, record.type_parameters.as_ref())),
                         );
                         fields.extend(record.block.variables.iter().map(convert_class_field));
+                        super_interfaces.extend(super_types(&record.implements, &imports));
                         super_class = match &record.superclass.first() {
                             None | Some(AstSuperClass::None | AstSuperClass::JType(_)) => {
                                 SuperClass::None
@@ -105,6 +108,7 @@ pub fn load_java_tree(ast: &AstFile, source: SourceDestination) -> Class {
                             name: (&i.name).into(),
                             jtype: (&i.jtype).into(),
                             source: None,
+                            annotations: annotation_names(&i.annotated, &i.jtype),
                         }));
                     }
                     AstThing::Enumeration(enumeration) => {
@@ -180,8 +184,24 @@ fn load_deprecated(access: &mut Access, annotated: &[AstAnnotated]) {
     }
 }
 
+/// Names of the annotations of a declaration and its type as written in the source
+fn annotation_names(annotated: &[AstAnnotated], jtype: &AstJType) -> Vec<MyString> {
+    let mut out: Vec<MyString> = Vec::new();
+    for a in annotated.iter().chain(&jtype.annotated) {
+        if !out.contains(&a.name.value) {
+            out.push(a.name.value.clone());
+        }
+    }
+    out
+}
+
 fn fun_name(ext: &AstExtends, imports: &[ImportUnit]) -> impl Iterator<Item = SuperClass> {
-    ext.parameters.iter().filter_map(|i| {
+    super_types(&ext.parameters, imports)
+}
+
+/// Extended interfaces or implemented interfaces
+fn super_types(types: &[AstJType], imports: &[ImportUnit]) -> impl Iterator<Item = SuperClass> {
+    types.iter().filter_map(|i| {
         if let AstJTypeKind::Class(c) = &i.value {
             return imports
                 .iter()
@@ -203,16 +223,7 @@ fn convert_class_method(
     load_deprecated(&mut access, &m.header.annotated);
     let type_parameters =
         merge_type_parameters(class_type_parameters, m.header.type_parameters.as_ref());
-    let parameters = m
-        .header
-        .parameters
-        .parameters
-        .iter()
-        .map(|p| Parameter {
-            name: Some((&p.name).into()),
-            jtype: check_type_parameters(&p.jtype, &type_parameters),
-        })
-        .collect();
+    let parameters = convert_parameters(&m.header.parameters, &type_parameters, &mut access);
     let throws = m
         .header
         .throws
@@ -221,13 +232,39 @@ fn convert_class_method(
     Method {
         access,
         name: Some((&m.header.name).into()),
+        type_parameters: type_parameter_names(m.header.type_parameters.as_ref()),
         parameters,
         throws,
         ret: check_type_parameters(&m.header.jtype, &type_parameters),
         source: None,
+        annotations: annotation_names(&m.header.annotated, &m.header.jtype),
     }
 }
 
+/// Parameters of a method, the varargs parameter is an array like in class files
+fn convert_parameters(
+    parameters: &AstMethodParameters,
+    type_parameters: &[AstTypeParameter],
+    access: &mut Access,
+) -> Vec<Parameter> {
+    parameters
+        .parameters
+        .iter()
+        .map(|p| {
+            let mut jtype = check_type_parameters(&p.jtype, type_parameters);
+            if p.flags.contains(AstMethodParameterFlags::Variatic) {
+                access.insert(Access::Varargs);
+                jtype = JType::Array(Box::new(jtype));
+            }
+            Parameter {
+                name: Some((&p.name).into()),
+                jtype,
+                annotations: annotation_names(&p.annotated, &p.jtype),
+            }
+        })
+        .collect()
+}
+
 fn merge_type_parameters(
     class_type_parameters: Option<&AstTypeParameters>,
     type_parameters: Option<&AstTypeParameters>,
@@ -241,23 +278,20 @@ fn merge_type_parameters(
     }
     out
 }
+/// Names of the type parameters that a method declares
+fn type_parameter_names(type_parameters: Option<&AstTypeParameters>) -> Vec<MyString> {
+    type_parameters.map_or_else(Vec::new, |t| {
+        t.parameters.iter().map(|p| p.name.value.clone()).collect()
+    })
+}
 fn convert_class_constructor(
     m: &AstClassConstructor,
     class_type_parameters: Option<&AstTypeParameters>,
 ) -> Method {
-    let access = access_from_availability(&m.header.availability, Access::Public);
+    let mut access = access_from_availability(&m.header.availability, Access::Public);
     let type_parameters =
         merge_type_parameters(class_type_parameters, m.header.type_parameters.as_ref());
-    let parameters = m
-        .header
-        .parameters
-        .parameters
-        .iter()
-        .map(|p| Parameter {
-            name: Some((&p.name).into()),
-            jtype: check_type_parameters(&p.jtype, &type_parameters),
-        })
-        .collect();
+    let parameters = convert_parameters(&m.header.parameters, &type_parameters, &mut access);
     let throws = m
         .header
         .throws
@@ -266,29 +300,31 @@ fn convert_class_constructor(
     Method {
         access,
         name: None,
+        type_parameters: type_parameter_names(m.header.type_parameters.as_ref()),
         parameters,
         throws,
         ret: JType::Void,
         source: None,
+        annotations: m
+            .header
+            .annotated
+            .iter()
+            .map(|a| a.name.value.clone())
+            .collect(),
     }
 }
 fn convert_interface_method(
     m: &AstInterfaceMethod,
     interface_type_parameters: Option<&AstTypeParameters>,
 ) -> Method {
-    let access = access_from_availability(&m.header.availability, Access::Public);
+    let mut access = access_from_availability(&m.header.availability, Access::Public);
+    // Interface methods without a body are implicitly abstract
+    if !access.contains(Access::Static) {
+        access.insert(Access::Abstract);
+    }
     let type_parameters =
         merge_type_parameters(interface_type_parameters, m.header.type_parameters.as_ref());
-    let parameters = m
-        .header
-        .parameters
-        .parameters
-        .iter()
-        .map(|p| Parameter {
-            name: Some((&p.name).into()),
-            jtype: check_type_parameters(&p.jtype, &type_parameters),
-        })
-        .collect();
+    let parameters = convert_parameters(&m.header.parameters, &type_parameters, &mut access);
     let throws = m.header.throws.as_ref().map_or_else(Vec::new, |t| {
         t.parameters
             .iter()
@@ -298,29 +334,22 @@ fn convert_interface_method(
     Method {
         access,
         name: Some((&m.header.name).into()),
+        type_parameters: type_parameter_names(m.header.type_parameters.as_ref()),
         parameters,
         throws,
         ret: check_type_parameters(&m.header.jtype, &type_parameters),
         source: None,
+        annotations: annotation_names(&m.header.annotated, &m.header.jtype),
     }
 }
 fn convert_interface_default_method(
     m: &AstInterfaceMethodDefault,
     interface_type_parameters: Option<&AstTypeParameters>,
 ) -> Method {
-    let access = access_from_availability(&m.header.availability, Access::Public);
+    let mut access = access_from_availability(&m.header.availability, Access::Public);
     let type_parameters =
         merge_type_parameters(interface_type_parameters, m.header.type_parameters.as_ref());
-    let parameters = m
-        .header
-        .parameters
-        .parameters
-        .iter()
-        .map(|p| Parameter {
-            name: Some((&p.name).into()),
-            jtype: check_type_parameters(&p.jtype, &type_parameters),
-        })
-        .collect();
+    let parameters = convert_parameters(&m.header.parameters, &type_parameters, &mut access);
     let throws = m.header.throws.as_ref().map_or_else(Vec::new, |t| {
         t.parameters
             .iter()
@@ -330,10 +359,12 @@ fn convert_interface_default_method(
     Method {
         access,
         name: Some((&m.header.name).into()),
+        type_parameters: type_parameter_names(m.header.type_parameters.as_ref()),
         parameters,
         throws,
         ret: check_type_parameters(&m.header.jtype, &type_parameters),
         source: None,
+        annotations: annotation_names(&m.header.annotated, &m.header.jtype),
     }
 }
 
@@ -345,6 +376,7 @@ fn convert_interface_constant(c: &AstInterfaceConstant) -> Field {
         name: (&c.name).into(),
         jtype: (&c.jtype).into(),
         source: None,
+        annotations: annotation_names(&c.annotated, &c.jtype),
     }
 }
 fn convert_annotation_field(c: &AstAnnotationField) -> Field {
@@ -355,6 +387,7 @@ fn convert_annotation_field(c: &AstAnnotationField) -> Field {
         name: (&c.name).into(),
         jtype: (&c.jtype).into(),
         source: None,
+        annotations: annotation_names(&c.annotated, &c.jtype),
     }
 }
 fn convert_class_field(c: &AstClassVariable) -> Field {
@@ -367,6 +400,7 @@ fn convert_class_field(c: &AstClassVariable) -> Field {
         jtype,
         name: c.name.value.clone(),
         source: None,
+        annotations: annotation_names(&c.annotated, &c.jtype),
     }
 }
 
@@ -376,6 +410,7 @@ fn convert_enum_variant(c: &AstEnumerationVariant, jtype: &JType) -> Field {
         jtype: jtype.clone(),
         name: c.name.value.clone(),
         source: None,
+        annotations: c.annotated.iter().map(|a| a.name.value.clone()).collect(),
     }
 }
 
@@ -443,6 +478,7 @@ pub mod tests {
                         name: Some(
                             "main",
                         ),
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
@@ -453,11 +489,13 @@ pub mod tests {
                                         "String",
                                     ),
                                 ),
+                                annotations: [],
                             },
                         ],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [
@@ -470,6 +508,7 @@ pub mod tests {
                             "Logger",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -478,6 +517,7 @@ pub mod tests {
                         name: "IS_ACTIVE",
                         jtype: Boolean,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -486,6 +526,7 @@ pub mod tests {
                         name: "one_byte",
                         jtype: Byte,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -494,6 +535,7 @@ pub mod tests {
                         name: "one_int",
                         jtype: Int,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -502,6 +544,7 @@ pub mod tests {
                         name: "one_short",
                         jtype: Short,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -510,6 +553,7 @@ pub mod tests {
                         name: "one_long",
                         jtype: Long,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -518,6 +562,7 @@ pub mod tests {
                         name: "one_double",
                         jtype: Double,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -526,6 +571,7 @@ pub mod tests {
                         name: "one_float",
                         jtype: Float,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -534,6 +580,7 @@ pub mod tests {
                         name: "one_char",
                         jtype: Char,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -544,6 +591,7 @@ pub mod tests {
                             "String",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -559,6 +607,7 @@ pub mod tests {
                             ],
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -575,6 +624,7 @@ pub mod tests {
                             ],
                         ),
                         source: None,
+                        annotations: [],
                     },
                 ],
                 super_class: None,
@@ -647,6 +697,9 @@ public class Test {
                         name: Some(
                             "add",
                         ),
+                        type_parameters: [
+                            "T",
+                        ],
                         parameters: [
                             Parameter {
                                 name: Some(
@@ -660,6 +713,7 @@ public class Test {
                                         ),
                                     ],
                                 ),
+                                annotations: [],
                             },
                             Parameter {
                                 name: Some(
@@ -668,11 +722,13 @@ public class Test {
                                 jtype: Parameter(
                                     "T",
                                 ),
+                                annotations: [],
                             },
                         ],
                         throws: [],
                         ret: Int,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [],
@@ -712,6 +768,7 @@ public class Test {
                         name: Some(
                             "ioThrower",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [
                             Class(
@@ -720,6 +777,7 @@ public class Test {
                         ],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -728,12 +786,14 @@ public class Test {
                         name: Some(
                             "ioThrower",
                         ),
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
                                     "a",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                         ],
                         throws: [
@@ -746,6 +806,7 @@ public class Test {
                         ],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [],
@@ -789,23 +850,26 @@ public class Test {
                 methods: [
                     Method {
                         access: Access(
-                            Public,
+                            Public | Abstract,
                         ),
                         name: Some(
                             "display",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
-                            Public,
+                            Public | Abstract,
                         ),
                         name: Some(
                             "createSocket",
                         ),
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
@@ -814,12 +878,14 @@ public class Test {
                                 jtype: Class(
                                     "String",
                                 ),
+                                annotations: [],
                             },
                             Parameter {
                                 name: Some(
                                     "port",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                         ],
                         throws: [
@@ -831,6 +897,7 @@ public class Test {
                             "Socket",
                         ),
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [
@@ -843,6 +910,7 @@ public class Test {
                             "String",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -853,6 +921,7 @@ public class Test {
                             "String",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -863,6 +932,7 @@ public class Test {
                             "String",
                         ),
                         source: None,
+                        annotations: [],
                     },
                 ],
                 super_class: None,
@@ -902,11 +972,14 @@ public class Test {
                 methods: [
                     Method {
                         access: Access(
-                            Public,
+                            Public | Abstract,
                         ),
                         name: Some(
                             "mapToObj",
                         ),
+                        type_parameters: [
+                            "U",
+                        ],
                         parameters: [
                             Parameter {
                                 name: Some(
@@ -921,6 +994,7 @@ public class Test {
                                         ),
                                     ],
                                 ),
+                                annotations: [],
                             },
                         ],
                         throws: [],
@@ -933,6 +1007,7 @@ public class Test {
                             ],
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -941,6 +1016,9 @@ public class Test {
                         name: Some(
                             "a",
                         ),
+                        type_parameters: [
+                            "A",
+                        ],
                         parameters: [
                             Parameter {
                                 name: Some(
@@ -949,6 +1027,7 @@ public class Test {
                                 jtype: Parameter(
                                     "A",
                                 ),
+                                annotations: [],
                             },
                         ],
                         throws: [],
@@ -956,6 +1035,7 @@ public class Test {
                             "A",
                         ),
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [],
@@ -994,12 +1074,14 @@ public class Test {
                         name: Some(
                             "getTag",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Class(
                             "String",
                         ),
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [
@@ -1012,6 +1094,7 @@ public class Test {
                             "Variants",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -1022,6 +1105,7 @@ public class Test {
                             "Variants",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -1032,6 +1116,7 @@ public class Test {
                             "Variants",
                         ),
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -1042,6 +1127,7 @@ public class Test {
                             "String",
                         ),
                         source: None,
+                        annotations: [],
                     },
                 ],
                 super_class: None,
@@ -1080,6 +1166,7 @@ public class Test {
                         name: "value",
                         jtype: Int,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -1090,6 +1177,7 @@ public class Test {
                             "String",
                         ),
                         source: None,
+                        annotations: [],
                     },
                 ],
                 super_class: None,
@@ -1125,10 +1213,12 @@ public class Test {
                             Public,
                         ),
                         name: None,
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1137,10 +1227,12 @@ public class Test {
                         name: Some(
                             "method",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1149,10 +1241,12 @@ public class Test {
                         name: Some(
                             "public_method",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1161,10 +1255,12 @@ public class Test {
                         name: Some(
                             "private_method",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Void,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1173,10 +1269,12 @@ public class Test {
                         name: Some(
                             "out",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Int,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1185,23 +1283,27 @@ public class Test {
                         name: Some(
                             "add",
                         ),
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
                                     "a",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                             Parameter {
                                 name: Some(
                                     "b",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                         ],
                         throws: [],
                         ret: Int,
                         source: None,
+                        annotations: [],
                     },
                     Method {
                         access: Access(
@@ -1210,23 +1312,27 @@ public class Test {
                         name: Some(
                             "sadd",
                         ),
+                        type_parameters: [],
                         parameters: [
                             Parameter {
                                 name: Some(
                                     "a",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                             Parameter {
                                 name: Some(
                                     "b",
                                 ),
                                 jtype: Int,
+                                annotations: [],
                             },
                         ],
                         throws: [],
                         ret: Int,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [
@@ -1237,6 +1343,7 @@ public class Test {
                         name: "noprop",
                         jtype: Int,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -1245,6 +1352,7 @@ public class Test {
                         name: "publicproperty",
                         jtype: Int,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -1253,6 +1361,7 @@ public class Test {
                         name: "privateproperty",
                         jtype: Int,
                         source: None,
+                        annotations: [],
                     },
                 ],
                 super_class: None,
@@ -1348,6 +1457,7 @@ public class Test {
                         name: Some(
                             "stream",
                         ),
+                        type_parameters: [],
                         parameters: [],
                         throws: [],
                         ret: Generic(
@@ -1359,6 +1469,7 @@ public class Test {
                             ],
                         ),
                         source: None,
+                        annotations: [],
                     },
                 ],
                 fields: [],
@@ -1404,6 +1515,7 @@ public class Test {
                         name: "value",
                         jtype: Int,
                         source: None,
+                        annotations: [],
                     },
                     Field {
                         access: Access(
@@ -1414,6 +1526,7 @@ public class Test {
                             "String",
                         ),
                         source: None,
+                        annotations: [],
                     },
                 ],
                 super_class: None,
@@ -0,0 +1,37 @@
+package ch.emilycares;
+
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+import java.lang.annotation.Target;
+
+public class Annotations {
+    @Retention(RetentionPolicy.RUNTIME)
+    @Target({ElementType.METHOD, ElementType.PARAMETER, ElementType.FIELD})
+    public @interface Checked {
+        String value() default "";
+    }
+
+    @Retention(RetentionPolicy.CLASS)
+    @Target(ElementType.TYPE_USE)
+    public @interface Nullable {}
+
+    @Checked("field")
+    public @Nullable String name;
+
+    /**
+     * Value of the key
+     *
+     * @param key name of the value
+     * @return the value or {@code null}
+     */
+    @Checked
+    public @Nullable String get(@Checked("key") String key, long offset, @Nullable Object fallback) {
+        String local = key;
+        return local;
+    }
+
+    public static int sum(int a, double b) {
+        return a + (int) b;
+    }
+}
@@ -0,0 +1,34 @@
+package ch.emilycares;
+
+import java.io.IOException;
+import java.util.List;
+import java.util.Map;
+
+public class Generics<T extends Comparable<T> & Cloneable> {
+  public List<String> names;
+  public Map<String, List<T>> groups;
+
+  public <E extends Exception> void run(List<? extends T> items) throws E {}
+
+  public <X extends Throwable> T orThrow(X x) throws X, IOException {
+    return null;
+  }
+
+  public static class Entry<K> implements Comparable<Entry<K>> {
+    public K key;
+
+    public int compareTo(Entry<K> other) {
+      return 0;
+    }
+  }
+
+  public class Inner {}
+
+  protected static class Hidden {}
+
+  public Runnable anonymous() {
+    return new Runnable() {
+      public void run() {}
+    };
+  }
+}
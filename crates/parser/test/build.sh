@@ -7,5 +7,8 @@ javac -parameters Constants.java
 javac -parameters Super.java
 javac -parameters SuperInterface.java
 javac -parameters InterfaceBase.java 
+javac -parameters Generics.java
 # -g is with Debug info
 javac -parameters -g LocalVariableTable.java
+# Without -parameters the names come from the LocalVariableTable
+javac -g Annotations.java
@@ -0,0 +1,647 @@
+//! Diagnostics of the external analyzers PMD and `SpotBugs`, they run in the background after a
+//! save and are published with the diagnostics of the server
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+    sync::Mutex,
+};
+
+use config::{AnalyzerConfig, Configuration};
+use lsp_extra::source_to_uri;
+use lsp_types::{
+    CodeDescription, Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range, TextEdit, Uri,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::javac::Javac;
+
+#[cfg(not(target_os = "windows"))]
+const PMD: &str = "pmd";
+#[cfg(target_os = "windows")]
+const PMD: &str = "pmd.bat";
+#[cfg(not(target_os = "windows"))]
+const SPOTBUGS: &str = "spotbugs";
+#[cfg(target_os = "windows")]
+const SPOTBUGS: &str = "spotbugs.bat";
+
+/// Exit code of PMD when it found violations
+const PMD_VIOLATIONS: i32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Analyzer {
+    Pmd,
+    SpotBugs,
+}
+
+impl Analyzer {
+    pub const ALL: [Self; 2] = [Self::Pmd, Self::SpotBugs];
+
+    /// Source of the diagnostics
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Pmd => "pmd",
+            Self::SpotBugs => "spotbugs",
+        }
+    }
+
+    /// Analyzer that reported the diagnostic
+    #[must_use]
+    pub fn of(diagnostic: &Diagnostic) -> Option<Self> {
+        let source = diagnostic.source.as_deref()?;
+        Self::ALL.into_iter().find(|tool| tool.name() == source)
+    }
+
+    #[must_use]
+    pub const fn config(self, config: &Configuration) -> &AnalyzerConfig {
+        match self {
+            Self::Pmd => &config.pmd,
+            Self::SpotBugs => &config.spotbugs,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AnalyzerError {
+    Io(std::io::Error),
+    /// Exit code and error output of the tool
+    Failed(Option<i32>, String),
+    Json(serde_json::Error),
+}
+
+/// Replacement that an analyzer suggests for a finding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuggestedFix {
+    pub title: String,
+    pub edits: Vec<TextEdit>,
+}
+
+/// Fixes in the `data` of the diagnostic
+#[must_use]
+pub fn suggested_fixes(diagnostic: &Diagnostic) -> Vec<SuggestedFix> {
+    diagnostic
+        .data
+        .clone()
+        .and_then(|data| serde_json::from_value(data).ok())
+        .unwrap_or_default()
+}
+
+/// Findings of the analyzers by file
+pub type FileFindings = HashMap<Uri, Vec<Diagnostic>>;
+
+/// Diagnostics of the server and of the analyzers of each file
+#[derive(Debug, Default)]
+pub struct Findings {
+    files: Mutex<HashMap<Uri, Published>>,
+}
+
+#[derive(Debug, Default)]
+struct Published {
+    /// Last diagnostics of the server
+    own: Vec<Diagnostic>,
+    tools: HashMap<Analyzer, Vec<Diagnostic>>,
+}
+
+impl Published {
+    fn merged(&self) -> Vec<Diagnostic> {
+        let mut out = self.own.clone();
+        for tool in Analyzer::ALL {
+            out.extend(self.tools.get(&tool).into_iter().flatten().cloned());
+        }
+        out
+    }
+}
+
+impl Findings {
+    /// The diagnostics of the server with the findings of the file
+    pub fn merge(&self, uri: &Uri, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        let Ok(mut files) = self.files.lock() else {
+            return diagnostics;
+        };
+        let published = files.entry(uri.clone()).or_default();
+        published.own = diagnostics;
+        published.merged()
+    }
+
+    /// Replace the findings of the tool in the analyzed file and in the files it found
+    /// something in. Returns the diagnostics of these files.
+    #[allow(clippy::mutable_key_type)]
+    pub fn update(
+        &self,
+        tool: Analyzer,
+        analyzed: &Uri,
+        mut found: FileFindings,
+    ) -> Vec<(Uri, Vec<Diagnostic>)> {
+        let Ok(mut files) = self.files.lock() else {
+            return Vec::new();
+        };
+        found.entry(analyzed.clone()).or_default();
+        found
+            .into_iter()
+            .map(|(uri, diagnostics)| {
+                let published = files.entry(uri.clone()).or_default();
+                published.tools.insert(tool, diagnostics);
+                (uri, published.merged())
+            })
+            .collect()
+    }
+
+    /// Forget the findings of a disabled tool. Returns the diagnostics of the files that had
+    /// findings of it.
+    pub fn clear(&self, tool: Analyzer) -> Vec<(Uri, Vec<Diagnostic>)> {
+        let Ok(mut files) = self.files.lock() else {
+            return Vec::new();
+        };
+        files
+            .iter_mut()
+            .filter_map(|(uri, published)| {
+                published
+                    .tools
+                    .remove(&tool)
+                    .filter(|d| !d.is_empty())
+                    .map(|_| (uri.clone(), published.merged()))
+            })
+            .collect()
+    }
+}
+
+/// Violations of PMD in the source file
+pub fn pmd(config: &AnalyzerConfig, path: &Path) -> Result<FileFindings, AnalyzerError> {
+    let executable = config
+        .executable
+        .as_deref()
+        .unwrap_or_else(|| Path::new(PMD));
+    let output = Command::new(executable)
+        .args(["check", "--no-progress", "--no-cache", "--format", "json"])
+        .arg("--rulesets")
+        .arg(config.rules.join(","))
+        .arg("--dir")
+        .arg(path)
+        .output()
+        .map_err(AnalyzerError::Io)?;
+    if !matches!(output.status.code(), Some(0 | PMD_VIOLATIONS)) {
+        return Err(AnalyzerError::Failed(
+            output.status.code(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+    parse_pmd(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Bugs that `SpotBugs` found in the compiled classes of the source file
+pub fn spotbugs(
+    config: &AnalyzerConfig,
+    javac: &Javac,
+    class_path: &str,
+    path: &Path,
+) -> Result<FileFindings, AnalyzerError> {
+    let executable = config
+        .executable
+        .as_deref()
+        .unwrap_or_else(|| Path::new(SPOTBUGS));
+    let mut command = Command::new(executable);
+    command
+        .current_dir(&javac.dir)
+        .args(["-textui", "-quiet", "-sarif"])
+        .arg("-onlyAnalyze")
+        .arg(class_path);
+    if !javac.classpath.is_empty() {
+        command.arg("-auxclasspath").arg(&javac.classpath);
+    }
+    for filter in &config.rules {
+        command.arg("-include").arg(filter);
+    }
+    let output = command
+        .arg(&javac.output)
+        .output()
+        .map_err(AnalyzerError::Io)?;
+    if !output.status.success() {
+        return Err(AnalyzerError::Failed(
+            output.status.code(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+    let Some(source_root) = source_root(path, class_path) else {
+        return Ok(FileFindings::new());
+    };
+    parse_sarif(&String::from_utf8_lossy(&output.stdout), source_root)
+}
+
+/// `/p/src/main/java` of the file of `ch.emilycares.App`
+fn source_root<'a>(path: &'a Path, class_path: &str) -> Option<&'a Path> {
+    path.ancestors().nth(class_path.matches('.').count() + 1)
+}
+
+#[derive(Debug, Deserialize)]
+struct PmdReport {
+    #[serde(default)]
+    files: Vec<PmdFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PmdFile {
+    filename: String,
+    #[serde(default)]
+    violations: Vec<PmdViolation>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PmdViolation {
+    beginline: u32,
+    begincolumn: u32,
+    endline: u32,
+    endcolumn: u32,
+    description: String,
+    rule: String,
+    priority: u8,
+    external_info_url: Option<String>,
+}
+
+#[allow(clippy::mutable_key_type)]
+fn parse_pmd(json: &str) -> Result<FileFindings, AnalyzerError> {
+    let report: PmdReport = serde_json::from_str(json).map_err(AnalyzerError::Json)?;
+    let mut out = FileFindings::new();
+    for file in report.files {
+        let Ok(uri) = source_to_uri(&file.filename) else {
+            tracing::debug!("PMD reported a file without uri: {}", file.filename);
+            continue;
+        };
+        out.entry(uri)
+            .or_default()
+            .extend(file.violations.into_iter().map(|v| {
+                // Lines and columns of PMD start at 1, the end column is inclusive
+                let range = Range::new(
+                    Position::new(
+                        v.beginline.saturating_sub(1),
+                        v.begincolumn.saturating_sub(1),
+                    ),
+                    Position::new(v.endline.saturating_sub(1), v.endcolumn),
+                );
+                let severity = match v.priority {
+                    1 | 2 => DiagnosticSeverity::WARNING,
+                    3 => DiagnosticSeverity::INFORMATION,
+                    _ => DiagnosticSeverity::HINT,
+                };
+                finding(
+                    Analyzer::Pmd,
+                    range,
+                    severity,
+                    v.rule,
+                    v.description,
+                    v.external_info_url.as_deref(),
+                )
+            }));
+    }
+    Ok(out)
+}
+
+#[derive(Debug, Deserialize)]
+struct Sarif {
+    #[serde(default)]
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SarifRun {
+    #[serde(default)]
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    level: Option<String>,
+    message: SarifMessage,
+    #[serde(default)]
+    locations: Vec<SarifLocation>,
+    #[serde(default)]
+    fixes: Vec<SarifFix>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifFix {
+    description: Option<SarifMessage>,
+    #[serde(default)]
+    artifact_changes: Vec<SarifArtifactChange>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactChange {
+    artifact_location: SarifArtifactLocation,
+    #[serde(default)]
+    replacements: Vec<SarifReplacement>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifReplacement {
+    deleted_region: SarifRegion,
+    inserted_content: Option<SarifMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SarifMessage {
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: Option<SarifPhysicalLocation>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    region: Option<SarifRegion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SarifArtifactLocation {
+    uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: u32,
+    end_line: Option<u32>,
+    start_column: Option<u32>,
+    end_column: Option<u32>,
+}
+
+impl SarifRegion {
+    /// Lines and columns start at 1 and the end column is exclusive, a region without columns
+    /// covers whole lines
+    fn range(&self) -> Range {
+        let start_line = self.start_line.saturating_sub(1);
+        let end_line = self.end_line.unwrap_or(self.start_line);
+        match (self.start_column, self.end_column) {
+            (Some(start), Some(end)) => Range::new(
+                Position::new(start_line, start.saturating_sub(1)),
+                Position::new(end_line.saturating_sub(1), end.saturating_sub(1)),
+            ),
+            (Some(start), None) => Range::new(
+                Position::new(start_line, start.saturating_sub(1)),
+                Position::new(end_line, 0),
+            ),
+            _ => Range::new(Position::new(start_line, 0), Position::new(end_line, 0)),
+        }
+    }
+}
+
+#[allow(clippy::mutable_key_type)]
+fn parse_sarif(json: &str, source_root: &Path) -> Result<FileFindings, AnalyzerError> {
+    let sarif: Sarif = serde_json::from_str(json).map_err(AnalyzerError::Json)?;
+    let mut out = FileFindings::new();
+    for result in sarif.runs.into_iter().flat_map(|r| r.results) {
+        let Some(location) = result
+            .locations
+            .into_iter()
+            .find_map(|l| l.physical_location)
+        else {
+            continue;
+        };
+        let source = source_path(&location.artifact_location.uri, source_root);
+        let Some(uri) = source.to_str().and_then(|s| source_to_uri(s).ok()) else {
+            continue;
+        };
+        let range = location
+            .region
+            .as_ref()
+            .map_or_else(Range::default, SarifRegion::range);
+        let severity = match result.level.as_deref() {
+            Some("error") => DiagnosticSeverity::ERROR,
+            Some("note") => DiagnosticSeverity::INFORMATION,
+            Some("none") => DiagnosticSeverity::HINT,
+            _ => DiagnosticSeverity::WARNING,
+        };
+        let fixes: Vec<SuggestedFix> = result
+            .fixes
+            .into_iter()
+            .filter_map(|fix| {
+                // Only the changes of the file that has the finding
+                let edits: Vec<TextEdit> = fix
+                    .artifact_changes
+                    .into_iter()
+                    .filter(|c| source_path(&c.artifact_location.uri, source_root) == source)
+                    .flat_map(|c| c.replacements)
+                    .map(|r| TextEdit {
+                        range: r.deleted_region.range(),
+                        new_text: r.inserted_content.map(|c| c.text).unwrap_or_default(),
+                    })
+                    .collect();
+                if edits.is_empty() {
+                    return None;
+                }
+                let title = fix.description.map_or_else(
+                    || format!("Apply the fix for {}", result.rule_id),
+                    |d| d.text,
+                );
+                Some(SuggestedFix { title, edits })
+            })
+            .collect();
+        let mut diagnostic = finding(
+            Analyzer::SpotBugs,
+            range,
+            severity,
+            result.rule_id,
+            result.message.text,
+            None,
+        );
+        if !fixes.is_empty() {
+            diagnostic.data = serde_json::to_value(fixes).ok();
+        }
+        out.entry(uri).or_default().push(diagnostic);
+    }
+    Ok(out)
+}
+
+/// Path of a SARIF artifact, relative ones are below the source root
+fn source_path(artifact: &str, source_root: &Path) -> PathBuf {
+    let path = Path::new(artifact.strip_prefix("file://").unwrap_or(artifact));
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    source_root.join(path)
+}
+
+fn finding(
+    tool: Analyzer,
+    range: Range,
+    severity: DiagnosticSeverity,
+    rule: String,
+    message: String,
+    url: Option<&str>,
+) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        code: Some(NumberOrString::String(rule)),
+        code_description: url
+            .and_then(|url| Uri::from_str(url).ok())
+            .map(|href| CodeDescription { href }),
+        source: Some(tool.name().to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, str::FromStr};
+
+    use lsp_types::{
+        Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range, TextEdit, Uri,
+    };
+
+    use super::{
+        Analyzer, Findings, SuggestedFix, parse_pmd, parse_sarif, source_root, suggested_fixes,
+    };
+
+    #[test]
+    #[allow(clippy::mutable_key_type)]
+    fn pmd_report() {
+        let json = r#"{
+  "formatVersion": 1,
+  "pmdVersion": "7.0.0",
+  "files": [{
+    "filename": "/p/src/main/java/ch/emilycares/App.java",
+    "violations": [{
+      "beginline": 5,
+      "begincolumn": 9,
+      "endline": 5,
+      "endcolumn": 20,
+      "description": "Avoid unused local variables such as 'a'.",
+      "rule": "UnusedLocalVariable",
+      "ruleset": "Best Practices",
+      "priority": 3,
+      "externalInfoUrl": "https://docs.pmd-code.org/latest/pmd_rules_java_bestpractices.html#unusedlocalvariable"
+    }]
+  }],
+  "suppressedViolations": [],
+  "processingErrors": [],
+  "configurationErrors": []
+}"#;
+        let findings = parse_pmd(json).unwrap();
+        let uri = Uri::from_str("file:///p/src/main/java/ch/emilycares/App.java").unwrap();
+        let [diagnostic] = findings[&uri].as_slice() else {
+            panic!("Expected one violation");
+        };
+        assert_eq!(
+            diagnostic.range,
+            Range::new(Position::new(4, 8), Position::new(4, 20))
+        );
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::INFORMATION));
+        assert_eq!(
+            diagnostic.code,
+            Some(NumberOrString::String("UnusedLocalVariable".to_string()))
+        );
+        assert_eq!(diagnostic.source.as_deref(), Some("pmd"));
+        assert!(diagnostic.code_description.is_some());
+    }
+
+    #[test]
+    #[allow(clippy::mutable_key_type)]
+    fn spotbugs_report() {
+        let json = r#"{
+  "version": "2.1.0",
+  "runs": [{
+    "tool": { "driver": { "name": "SpotBugs" } },
+    "results": [{
+      "ruleId": "NP_ALWAYS_NULL",
+      "level": "error",
+      "message": { "text": "Null pointer dereference of s in ch.emilycares.App.run()" },
+      "locations": [{
+        "physicalLocation": {
+          "artifactLocation": { "uri": "ch/emilycares/App.java", "uriBaseId": "SRCROOT" },
+          "region": { "startLine": 7 }
+        },
+        "logicalLocations": [{ "name": "run", "kind": "function" }]
+      }],
+      "fixes": [{
+        "description": { "text": "Check s for null" },
+        "artifactChanges": [{
+          "artifactLocation": { "uri": "ch/emilycares/App.java", "uriBaseId": "SRCROOT" },
+          "replacements": [{
+            "deletedRegion": { "startLine": 7, "startColumn": 9, "endColumn": 9 },
+            "insertedContent": { "text": "if (s != null) " }
+          }]
+        }]
+      }]
+    }]
+  }]
+}"#;
+        let path = Path::new("/p/src/main/java/ch/emilycares/App.java");
+        let root = source_root(path, "ch.emilycares.App").unwrap();
+        assert_eq!(root, Path::new("/p/src/main/java"));
+        let findings = parse_sarif(json, root).unwrap();
+        let uri = Uri::from_str("file:///p/src/main/java/ch/emilycares/App.java").unwrap();
+        let [diagnostic] = findings[&uri].as_slice() else {
+            panic!("Expected one bug");
+        };
+        assert_eq!(
+            diagnostic.range,
+            Range::new(Position::new(6, 0), Position::new(7, 0))
+        );
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(diagnostic.source.as_deref(), Some("spotbugs"));
+        let position = Position::new(6, 8);
+        assert_eq!(
+            suggested_fixes(diagnostic),
+            [SuggestedFix {
+                title: "Check s for null".to_string(),
+                edits: vec![TextEdit {
+                    range: Range::new(position, position),
+                    new_text: "if (s != null) ".to_string(),
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn findings_are_merged() {
+        let uri = Uri::from_str("file:///p/App.java").unwrap();
+        let diagnostic = |message: &str| Diagnostic {
+            message: message.to_string(),
+            ..Default::default()
+        };
+        let messages = |diagnostics: &[Diagnostic]| {
+            diagnostics
+                .iter()
+                .map(|d| d.message.clone())
+                .collect::<Vec<_>>()
+        };
+        let findings = Findings::default();
+        findings.merge(&uri, vec![diagnostic("own")]);
+        let updated = findings.update(
+            Analyzer::Pmd,
+            &uri,
+            [(uri.clone(), vec![diagnostic("pmd")])].into(),
+        );
+        assert_eq!(messages(&updated[0].1), ["own", "pmd"]);
+        assert_eq!(
+            messages(&findings.merge(&uri, vec![diagnostic("edited")])),
+            ["edited", "pmd"]
+        );
+        // A run without findings removes the old ones
+        let updated = findings.update(Analyzer::Pmd, &uri, [].into());
+        assert_eq!(messages(&updated[0].1), ["edited"]);
+
+        findings.update(
+            Analyzer::SpotBugs,
+            &uri,
+            [(uri.clone(), vec![diagnostic("spotbugs")])].into(),
+        );
+        let cleared = findings.clear(Analyzer::SpotBugs);
+        assert_eq!(messages(&cleared[0].1), ["edited"]);
+        assert!(findings.clear(Analyzer::SpotBugs).is_empty());
+    }
+}
@@ -3,61 +3,121 @@ use std::{
     ffi::OsString,
     fs,
     path::{Path, PathBuf},
-    sync::{Arc, Mutex, RwLock},
+    str::FromStr,
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicU32, AtomicU64, Ordering},
+    },
+    time::Instant,
 };
 
-use ast::types::AstFile;
-use call_chain::get_call_chain;
-use common::{Dependency, TaskProgress, project_kind::ProjectKind};
+use ast::types::{AstFile, AstPoint};
+use bazel::project::BazelTargets;
+use bsp::project::BspServers;
+use call_chain::{CallItem, get_call_chain};
+use common::{
+    Dependency, TaskProgress,
+    deps::{deps_base, deps_get_cfc},
+    deps_dir,
+    language_level::LanguageLevel,
+    project_cache_dir,
+    project_kind::{ProjectKind, ProjectKindError},
+    timings,
+};
 use compile::CompileErrorMessage;
-use config::{Configuration, FormatterConfig};
+use config::{
+    AnalyzerConfig, Configuration, DiagnosticLevel, FormatterConfig,
+    classpath::classpath_config_path, project::ProjectConfigs,
+};
 use document::{Document, DocumentError, get_class_path, open_document};
-use dto::Class;
-use formatter::{FormatError, FormatLineError};
+use dto::{Class, ClassParserError, ImportUnit, Method, SourceDestination};
+use eclipse::project::get_eclipse_cache_path;
+use formatter::{FormatError, FormatLineError, FormatOptions, editorconfig::EditorConfig};
 use gradle::project::get_gradle_cache_path;
-use lsp_extra::{SERVER_NAME, source_to_uri, to_ast_point};
-use lsp_server::{Connection, Message};
+use loader::LoaderError;
+use local_variable::LocalVariable;
+use lsp_extra::{SERVER_NAME, source_to_uri, to_ast_point, to_ast_range};
+use lsp_server::{Connection, Message, RequestId};
 use lsp_types::{
-    ClientCapabilities, CodeActionOrCommand, CodeActionParams, CodeActionResponse, CodeLens,
-    CodeLensParams, Command, CompletionItem, CompletionItemKind, CompletionList, CompletionParams,
-    CompletionResponse, Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams,
-    DidCloseTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
-    DocumentFormattingParams, DocumentLink, DocumentLinkParams, DocumentSymbolParams,
-    DocumentSymbolResponse, ExecuteCommandParams, FoldingRange, GotoDefinitionParams,
-    GotoDefinitionResponse, Hover, HoverParams, InlayHint, InlayHintParams, InsertTextFormat,
-    Location, Position, ProgressParams, ProgressParamsValue, ProgressToken,
-    PublishDiagnosticsParams, Range, ReferenceParams, ShowDocumentParams, SignatureHelp,
-    SignatureHelpParams, TextEdit, Uri, WorkDoneProgress, WorkDoneProgressBegin,
-    WorkDoneProgressEnd, WorkDoneProgressReport, WorkspaceFolder,
+    ApplyWorkspaceEditParams, ClientCapabilities, CodeActionOrCommand, CodeActionParams,
+    CodeActionResponse, CodeLens, CodeLensParams, Command, CompletionItem, CompletionItemKind,
+    CompletionList, CompletionParams, CompletionResponse, ConfigurationItem, ConfigurationParams,
+    Diagnostic, DiagnosticSeverity, DidChangeConfigurationParams, DidChangeTextDocumentParams,
+    DidChangeWatchedFilesParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, DocumentDiagnosticParams, DocumentDiagnosticReport,
+    DocumentDiagnosticReportResult, DocumentFormattingParams, DocumentLink, DocumentLinkParams,
+    DocumentSymbolParams, DocumentSymbolResponse, ExecuteCommandParams, FileChangeType,
+    FoldingRange, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverParams, InlayHint,
+    InlayHintParams, InsertTextFormat, Location, OneOf, Position, ProgressParams,
+    ProgressParamsValue, ProgressToken, PublishDiagnosticsParams, Range, ReferenceParams,
+    RegistrationParams, RelatedFullDocumentDiagnosticReport, ShowDocumentParams, SignatureHelp,
+    SignatureHelpParams, SymbolKind, SymbolTag, TextDocumentPositionParams, TextEdit,
+    TypeHierarchyItem, TypeHierarchyPrepareParams, TypeHierarchySubtypesParams,
+    TypeHierarchySupertypesParams, Uri, WorkDoneProgress, WorkDoneProgressBegin,
+    WorkDoneProgressEnd, WorkDoneProgressReport, WorkspaceDiagnosticParams,
+    WorkspaceDiagnosticReport, WorkspaceDiagnosticReportPartialResult,
+    WorkspaceDiagnosticReportResult, WorkspaceFolder, WorkspaceLocation, WorkspaceSymbol,
+    WorkspaceSymbolParams, WorkspaceSymbolResponse,
     notification::{Notification, Progress, PublishDiagnostics},
-    request::{Request, ShowDocument},
+    request::{
+        ApplyWorkspaceEdit, GotoImplementationParams, GotoImplementationResponse,
+        InlayHintRefreshRequest, RegisterCapability, Request, ShowDocument, WorkspaceConfiguration,
+        WorkspaceDiagnosticRefresh,
+    },
 };
 use maven::{
+    modules::{self, MavenModule},
     pom::load_pom_xml,
     project::get_maven_cache_path,
     repository::Repository,
     update::{self, MavenUpdateError},
 };
-use my_string::{MyString, smol_str::ToSmolStr};
+use my_string::{
+    MyString,
+    smol_str::{ToSmolStr, format_smolstr},
+};
 use serde_json::Value;
 use tokio::task::JoinSet;
 use variables::VariableContext;
 
 use crate::{
+    analyzers::{self, Analyzer, Findings},
+    call_graph,
+    cancel::Cancellation,
+    class_contents,
     code_lens::{self, CodeLensError},
     codeaction::{self, CodeActionContext},
     command::{
-        self, COMMAND_CMD, COMMAND_RELOAD_DEPENDENCIES, COMMAND_UPDATE_DEPENDENCIES,
-        reload_gradle_project, reload_maven_project,
+        self, COMMAND_CMD, COMMAND_COMPILE, COMMAND_RELOAD_DEPENDENCIES,
+        COMMAND_UPDATE_DEPENDENCIES, reload_eclipse_project, reload_gradle_project,
+        reload_maven_project, reload_plain_project,
     },
-    completion,
+    completion, dead_code, debug,
     definition::{self, DefinitionContext},
+    diagnostic_report::DiagnosticReports,
+    diagnostics,
     document_link::get_document_link,
     folding_range,
     hover::{self, class_action},
+    implementation,
+    import_rank::{self, ImportChoices},
     inlay_hint::get_inlay_hint,
+    javac, junit,
+    language_level::{self, Feature},
+    lint_fix,
+    logging::{self, LogLevels, ServerStatus},
+    main_class,
+    name_index::NameIndex,
+    plain_project,
+    queries::Queries,
+    quickfix, refactor,
     references::{self, ReferenceUnit, ReferencesContext},
-    signature, snipptes,
+    scratch, signature, snipptes,
+    status::{STATUS_INTERVAL, STATUS_TASK, Status},
+    subtypes::Subtypes,
+    switch_cases,
+    symbol_index::Symbols,
+    watched_files::{self, WatchedKind},
 };
 
 #[derive(Debug, Clone)]
@@ -65,8 +125,22 @@ pub struct Project {
     pub artifact_id: String,
     pub dir: String,
     pub kind: ProjectKind,
+    /// Modules of a maven reactor build, including the project itself
+    pub modules: Vec<MavenModule>,
+    /// Language level of the build, maven modules have their own
+    pub language_level: LanguageLevel,
+}
+
+impl Project {
+    /// Language level of the maven module of the file, otherwise of the project
+    #[must_use]
+    pub fn language_level(&self, file: &Path) -> LanguageLevel {
+        modules::module_of(&self.modules, file)
+            .map_or(self.language_level, MavenModule::language_level)
+    }
 }
 
+#[derive(Clone)]
 pub struct Backend {
     pub error_files: Arc<Mutex<HashSet<String>>>,
     pub projects: Arc<RwLock<Vec<Project>>>,
@@ -76,6 +150,49 @@ pub struct Backend {
     pub client_capabilities: Arc<Option<ClientCapabilities>>,
     pub connection: Arc<Connection>,
     pub config: Configuration,
+    /// Settings of the client, they are merged over the `java_lsp.toml` of the workspace root
+    pub client_settings: serde_json::Map<String, Value>,
+    pub diagnostic_reports: Arc<Mutex<DiagnosticReports>>,
+    pub findings: Arc<Findings>,
+    pub project_configs: Arc<Mutex<ProjectConfigs>>,
+    pub cancellation: Arc<Cancellation>,
+    pub symbols: Arc<Symbols>,
+    pub subtypes: Arc<Subtypes>,
+    pub names: Arc<NameIndex>,
+    pub queries: Arc<Queries>,
+    pub import_choices: Arc<ImportChoices>,
+    pub bazel_targets: Arc<BazelTargets>,
+    pub bsp_servers: Arc<BspServers>,
+    pub configuration_requests: Arc<ConfigurationRequests>,
+}
+
+/// Ids of the `workspace/configuration` requests that the client did not answer yet
+#[derive(Debug, Default)]
+pub struct ConfigurationRequests {
+    next: AtomicU64,
+    pending: Mutex<HashSet<RequestId>>,
+}
+
+impl ConfigurationRequests {
+    /// Id of a new request, it is pending until it is answered
+    fn start(&self) -> RequestId {
+        let id = RequestId::from(format!(
+            "{}/{}",
+            WorkspaceConfiguration::METHOD,
+            self.next.fetch_add(1, Ordering::Relaxed)
+        ));
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(id.clone());
+        }
+        id
+    }
+
+    /// Is the id one of a pending request, it is no longer pending after
+    pub fn answered(&self, id: &RequestId) -> bool {
+        self.pending
+            .lock()
+            .is_ok_and(|mut pending| pending.remove(id))
+    }
 }
 
 impl Backend {
@@ -89,10 +206,65 @@ impl Backend {
             reference_map: Arc::new(Mutex::new(HashMap::new())),
             client_capabilities: Arc::new(None),
             config: Configuration::default(),
+            client_settings: serde_json::Map::new(),
             projects: Arc::new(RwLock::new(Vec::new())),
+            diagnostic_reports: Arc::new(Mutex::new(DiagnosticReports::default())),
+            findings: Arc::new(Findings::default()),
+            project_configs: Arc::new(Mutex::new(ProjectConfigs::default())),
+            cancellation: Arc::new(Cancellation::default()),
+            symbols: Arc::new(Symbols::default()),
+            subtypes: Arc::new(Subtypes::default()),
+            names: Arc::new(NameIndex::default()),
+            queries: Arc::new(Queries::default()),
+            import_choices: Arc::new(ImportChoices::default()),
+            bazel_targets: Arc::new(BazelTargets::default()),
+            bsp_servers: Arc::new(BspServers::default()),
+            configuration_requests: Arc::new(ConfigurationRequests::default()),
+        }
+    }
+
+    /// Whether the client cancelled the running request
+    #[must_use]
+    pub fn cancelled(&self) -> bool {
+        self.cancellation.is_cancelled(&self.connection)
+    }
+
+    /// Locks that were held by a panicking handler stay usable, a handler only replaces whole
+    /// entries
+    pub fn clear_poison(&self) {
+        self.error_files.clear_poison();
+        self.projects.clear_poison();
+        self.document_map.clear_poison();
+        self.class_map.clear_poison();
+        self.reference_map.clear_poison();
+        self.diagnostic_reports.clear_poison();
+        self.project_configs.clear_poison();
+    }
+
+    /// Keep the document map within the configured memory budget
+    pub fn evict_documents(&self) {
+        let freed = document::cache::evict(&self.document_map, self.config.cache_memory);
+        if freed > 0 {
+            tracing::debug!("Evicted {} KiB of cached documents", freed / 1024);
         }
     }
 
+    /// Version of an open document
+    #[must_use]
+    pub fn document_version(&self, uri: &Uri) -> Option<i32> {
+        let dm = self.document_map.read().ok()?;
+        dm.get(&get_document_map_key(uri)).map(|d| d.version)
+    }
+
+    /// Whether the document changed since it had the version
+    #[must_use]
+    pub fn content_modified(&self, uri: &Uri, version: Option<i32>) -> bool {
+        self.document_version(uri) != version
+            || self
+                .cancellation
+                .pending_change(&self.connection, uri.as_str())
+    }
+
     pub fn send_diagnostic(con: &Arc<Connection>, uri: Uri, diagnostics: Vec<Diagnostic>) {
         if let Ok(params) = serde_json::to_value(PublishDiagnosticsParams {
             uri,
@@ -107,6 +279,76 @@ impl Backend {
                 }));
         }
     }
+    /// The client requests diagnostics with `textDocument/diagnostic`
+    fn pull_diagnostics(&self) -> bool {
+        self.client_capabilities
+            .as_ref()
+            .as_ref()
+            .and_then(|c| c.text_document.as_ref())
+            .is_some_and(|t| t.diagnostic.is_some())
+    }
+
+    /// Push the diagnostics or store them for the next pull of the client
+    fn publish_diagnostics(&self, uri: Uri, diagnostics: Vec<Diagnostic>) {
+        let diagnostics = self.findings.merge(&uri, diagnostics);
+        if !self.pull_diagnostics() {
+            Self::send_diagnostic(&self.connection, uri, diagnostics);
+            return;
+        }
+        if let Ok(mut reports) = self.diagnostic_reports.lock() {
+            reports.update(uri, diagnostics);
+        } else {
+            tracing::error!("diagnostic_reports mutex poisoned");
+        }
+    }
+
+    /// The client pulls the diagnostics of all files again when it is asked to
+    fn refresh_support(&self) -> bool {
+        self.client_capabilities
+            .as_ref()
+            .as_ref()
+            .and_then(|c| c.workspace.as_ref())
+            .and_then(|w| w.diagnostic.as_ref())
+            .and_then(|d| d.refresh_support)
+            .unwrap_or_default()
+    }
+
+    /// Ask the client to pull the diagnostics of all files again
+    fn refresh_diagnostics(&self) {
+        if self.refresh_support() {
+            Self::send_refresh(&self.connection);
+        }
+    }
+
+    fn send_refresh(con: &Arc<Connection>) {
+        let _ = con.sender.send(Message::Request(lsp_server::Request {
+            id: WorkspaceDiagnosticRefresh::METHOD.to_string().into(),
+            method: WorkspaceDiagnosticRefresh::METHOD.to_string(),
+            params: Value::Null,
+        }));
+    }
+
+    /// Like [`Self::publish_diagnostics`] for diagnostics that a background task computed, the
+    /// findings of the analyzers are already merged
+    fn diagnostics_publisher(&self) -> impl Fn(Uri, Vec<Diagnostic>) + Send + 'static {
+        let con = self.connection.clone();
+        let reports = self.diagnostic_reports.clone();
+        let pull = self.pull_diagnostics();
+        let refresh = self.refresh_support();
+        move |uri, diagnostics| {
+            if !pull {
+                Self::send_diagnostic(&con, uri, diagnostics);
+                return;
+            }
+            let changed = reports
+                .lock()
+                .is_ok_and(|mut reports| reports.update(uri, diagnostics));
+            if changed && refresh {
+                Self::send_refresh(&con);
+            }
+        }
+    }
+
     pub fn progress_start_option_token(
         con: &Arc<Connection>,
         token: &Arc<Option<ProgressToken>>,
@@ -119,7 +361,7 @@ impl Backend {
         Self::progress_start(con, title);
     }
     fn progress_start_token(con: &Arc<Connection>, token: &ProgressToken, title: &str) {
-        eprintln!("Start progress on: {title}");
+        tracing::debug!("Start progress on: {title}");
         if let Ok(params) = serde_json::to_value(ProgressParams {
             token: token.to_owned(),
             value: ProgressParamsValue::WorkDone(WorkDoneProgress::Begin(WorkDoneProgressBegin {
@@ -171,7 +413,7 @@ impl Backend {
         message: String,
         percentage: u32,
     ) {
-        eprintln!("Report progress on: {task} {percentage:?} status: {message}");
+        tracing::debug!("Report progress on: {task} {percentage:?} status: {message}");
         if let Ok(params) = serde_json::to_value(ProgressParams {
             token: token.to_owned(),
             value: ProgressParamsValue::WorkDone(WorkDoneProgress::Report(
@@ -190,23 +432,61 @@ impl Backend {
                 }));
         }
     }
+    /// Progress without a percentage, false when the client is gone
+    fn progress_report(con: &Arc<Connection>, task: &str, message: String) -> bool {
+        let Ok(params) = serde_json::to_value(ProgressParams {
+            token: ProgressToken::String(task.to_owned()),
+            value: ProgressParamsValue::WorkDone(WorkDoneProgress::Report(
+                WorkDoneProgressReport {
+                    cancellable: Some(false),
+                    message: Some(message),
+                    percentage: None,
+                },
+            )),
+        }) else {
+            return true;
+        };
+        con.sender
+            .send(Message::Notification(lsp_server::Notification {
+                method: Progress::METHOD.to_string(),
+                params,
+            }))
+            .is_ok()
+    }
     pub fn progress_end_option_token(
         con: &Arc<Connection>,
         token: &Arc<Option<ProgressToken>>,
         task: &str,
+    ) {
+        Self::progress_end_option_token_message(con, token, task, None);
+    }
+    /// End progress with a final message that the client shows once the task is done
+    pub fn progress_end_option_token_message(
+        con: &Arc<Connection>,
+        token: &Arc<Option<ProgressToken>>,
+        task: &str,
+        message: Option<String>,
     ) {
         if let Some(token) = token.as_ref() {
-            Self::progress_end_token(con, token, task);
+            Self::progress_end_token(con, token, task, message);
             return;
         }
-        Self::progress_end(con, task);
+        Self::progress_end(con, task, message);
     }
-    fn progress_end_token(con: &Arc<Connection>, token: &ProgressToken, task: &str) {
-        eprintln!("End progress on: {task}");
+    fn progress_end_token(
+        con: &Arc<Connection>,
+        token: &ProgressToken,
+        task: &str,
+        message: Option<String>,
+    ) {
+        tracing::info!(
+            "End progress on: {task} {}",
+            message.as_deref().unwrap_or_default()
+        );
         if let Ok(params) = serde_json::to_value(ProgressParams {
             token: token.to_owned(),
             value: ProgressParamsValue::WorkDone(WorkDoneProgress::End(WorkDoneProgressEnd {
-                message: None,
+                message,
             })),
         }) {
             let _ = con
@@ -218,9 +498,9 @@ impl Backend {
         }
     }
 
-    fn progress_end(con: &Arc<Connection>, task: &str) {
+    fn progress_end(con: &Arc<Connection>, task: &str, message: Option<String>) {
         let token = ProgressToken::String(task.to_owned());
-        Self::progress_end_token(con, &token, task);
+        Self::progress_end_token(con, &token, task, message);
     }
 
     fn compile(&self, path: &str, uri: &Uri) -> Option<Vec<CompileErrorMessage>> {
@@ -230,28 +510,32 @@ impl Backend {
         }
         let project = self.get_project(uri)?;
         match &project.kind {
-            ProjectKind::Maven { executable } => {
-                match maven::compile::generate_classpath(executable) {
-                    Ok(classpath) => {
-                        match compile::maven_compile_java_file(path, &classpath, &project.dir) {
-                            Ok(errors) => return Some(errors),
-                            Err(e) => eprintln!("Compile error: {e:?}"),
-                        }
-                    }
-                    e => eprintln!("Failed to load classpath {e:?}"),
-                }
-            }
             ProjectKind::Gradle { executable, .. } => {
-                if let Some(errors) = gradle::compile::compile_java(executable, &project.dir) {
-                    return Some(errors);
-                }
+                return gradle::compile::compile_java(executable, &project.dir);
+            }
+            // Bazel builds the targets itself
+            ProjectKind::Bazel { .. } => return None,
+            ProjectKind::Bsp { path_connection } => {
+                return self
+                    .bsp_servers
+                    .compile(path_connection, Path::new(&project.dir), Path::new(path))
+                    .inspect_err(|e| tracing::warn!("Build server failed to compile: {e:?}"))
+                    .ok();
+            }
+            _ => (),
+        }
+        let compiled = match javac::javac(&project, Path::new(path)) {
+            Some(javac) => javac.compile(path),
+            None if project.kind == ProjectKind::Unknown => compile::compile_java_file(path),
+            None => return None,
+        };
+        match compiled {
+            Ok(errors) => Some(errors),
+            Err(e) => {
+                tracing::warn!("Compile error: {e:?}");
+                None
             }
-            ProjectKind::Unknown => match compile::compile_java_file(path) {
-                Ok(errors) => return Some(errors),
-                Err(e) => eprintln!("Compile error: {e:?}"),
-            },
         }
-        None
     }
 
     fn publish_compile_errors(
@@ -262,7 +546,7 @@ impl Backend {
         let mut out = Vec::new();
         let mut emap = HashMap::<String, Vec<CompileErrorMessage>>::new();
         let Ok(mut error_files) = self.error_files.lock() else {
-            eprintln!("error_files mutex poisoned");
+            tracing::error!("error_files mutex poisoned");
             return Vec::new();
         };
         for e in errors {
@@ -274,6 +558,7 @@ impl Backend {
             }
         }
 
+        let mut other_files = false;
         for path in error_files.iter() {
             let Some(path) = path.get(..) else {
                 continue;
@@ -281,17 +566,21 @@ impl Backend {
             if let Ok(uri) = source_to_uri(path) {
                 if let Some(errs) = emap.get(path) {
                     if &uri == current_file {
-                        out.extend(errs.iter().map(compile_error_to_diagnostic));
+                        out.extend(errs.iter().map(javac::diagnostic));
                     } else {
-                        let errs: Vec<Diagnostic> =
-                            errs.iter().map(compile_error_to_diagnostic).collect();
-                        Self::send_diagnostic(&self.connection.clone(), uri, errs);
+                        let errs: Vec<Diagnostic> = errs.iter().map(javac::diagnostic).collect();
+                        self.publish_diagnostics(uri, errs);
+                        other_files = true;
                     }
                 } else {
-                    Self::send_diagnostic(&self.connection.clone(), uri, vec![]);
+                    self.publish_diagnostics(uri, vec![]);
+                    other_files = true;
                 }
             }
         }
+        if other_files && self.pull_diagnostics() {
+            self.refresh_diagnostics();
+        }
         out
     }
 
@@ -301,33 +590,20 @@ impl Backend {
         class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
         reference_map: Arc<Mutex<HashMap<MyString, Vec<ReferenceUnit>>>>,
         path: &OsString,
+        release: Option<u32>,
         projects: Arc<RwLock<Vec<Project>>>,
     ) {
+        let started = Instant::now();
+        logging::server_status(&con, ServerStatus::Indexing, None);
         let progress = Arc::new(progress);
         Self::progress_start_option_token(&con, &progress, "Init");
         let mut handles = JoinSet::new();
-        {
-            let con = con.clone();
-            let class_map = class_map.clone();
-            let path = path.to_owned();
-            handles.spawn(async move {
-                let task = "Load jdk";
-                let progress = Arc::new(Option::Some(ProgressToken::String(task.to_owned())));
-                let (sender, receiver) =
-                    tokio::sync::watch::channel::<TaskProgress>(TaskProgress {
-                        percentage: 0,
-                        error: false,
-                        message: "...".to_string(),
-                    });
-
-                Self::progress_start_option_token(&con.clone(), &progress, task);
-                tokio::select! {
-                    () = read_forward(receiver, con.clone(), task.to_owned(), progress.clone())  => {},
-                    _ = jdk::load_classes(class_map, sender, &path) => {}
-                }
-                Self::progress_end_option_token(&con.clone(), &progress, task);
-            });
-        }
+        handles.spawn(Self::load_jdk(
+            con.clone(),
+            class_map.clone(),
+            path.to_owned(),
+            release,
+        ));
         {
             let Ok(projs) = projects.read() else {
                 return;
@@ -357,7 +633,21 @@ impl Backend {
                         executable,
                         &mut handles,
                     ),
-                    ProjectKind::Unknown => (),
+                    ProjectKind::Eclipse { .. } => reload_eclipse_project(
+                        &con,
+                        class_map,
+                        PathBuf::from(p.dir.clone()).as_path(),
+                        &mut handles,
+                    ),
+                    ProjectKind::Unknown => reload_plain_project(
+                        &con,
+                        class_map,
+                        PathBuf::from(p.dir.clone()).as_path(),
+                        false,
+                        &mut handles,
+                    ),
+                    // Targets are loaded when their files are opened
+                    ProjectKind::Bazel { .. } | ProjectKind::Bsp { .. } => (),
                 }
 
                 {
@@ -368,6 +658,7 @@ impl Backend {
                     handles.spawn(async move {
                         let task = format!("Load project files {}", p.artifact_id);
                         let project_dir = project_dir.as_path();
+                        let started = Instant::now();
                         let progress = Arc::new(Option::Some(ProgressToken::String(task.clone())));
                         Self::progress_start_option_token(&con, &progress, &task);
                         Self::progress_update_percentage_option_token(
@@ -377,14 +668,36 @@ impl Backend {
                             "Load project paths".to_string(),
                             1,
                         );
+                        // Parsing the files takes the first half of the progress
+                        let reported = AtomicU32::new(1);
+                        let parse_progress = |done: usize, total: usize| {
+                            let percentage =
+                                u32::try_from(done * 49 / total.max(1)).unwrap_or(49) + 1;
+                            if reported.fetch_max(percentage, Ordering::Relaxed) < percentage {
+                                Self::progress_update_percentage_option_token(
+                                    &con,
+                                    &progress,
+                                    &task,
+                                    format!("Parsed {done} of {total} files"),
+                                    percentage,
+                                );
+                            }
+                        };
                         let project_classes = match p.kind {
                             ProjectKind::Maven { .. } => {
-                                maven::project::load_project_folders(project_dir)
+                                maven::project::load_project_folders(project_dir, parse_progress)
                             }
                             ProjectKind::Gradle { .. } => {
-                                gradle::project::load_project_folders(project_dir)
+                                gradle::project::load_project_folders(project_dir, parse_progress)
+                            }
+                            ProjectKind::Eclipse { .. } => {
+                                eclipse::project::load_project_folders(project_dir, parse_progress)
+                            }
+                            // The sources of a target are loaded when one of its files is opened
+                            ProjectKind::Bazel { .. } | ProjectKind::Bsp { .. } => Vec::new(),
+                            ProjectKind::Unknown => {
+                                plain_project::load_project_folders(project_dir, parse_progress)
                             }
-                            ProjectKind::Unknown => loader::load_java_files(PathBuf::from("./")),
                         };
                         Self::progress_update_percentage_option_token(
                             &con.clone(),
@@ -399,7 +712,7 @@ impl Backend {
                             &reference_map,
                         ) {
                             Ok(()) => (),
-                            Err(e) => eprintln!("Got reference error: {e:?}"),
+                            Err(e) => tracing::warn!("Got reference error: {e:?}"),
                         }
                         Self::progress_update_percentage_option_token(
                             &con.clone(),
@@ -408,14 +721,21 @@ impl Backend {
                             format!("Populating class map number: {}", project_classes.len()),
                             90,
                         );
+                        let loaded = project_classes.len();
+                        timings::INDEX.record(started.elapsed());
                         if let Ok(mut cm) = class_map.write() {
                             for class in project_classes {
                                 cm.insert(class.class_path.clone(), class);
                             }
                         } else {
-                            eprintln!("class_map mutex poisoned");
+                            tracing::error!("class_map mutex poisoned");
                         }
-                        Self::progress_end_option_token(&con.clone(), &progress, &task);
+                        Self::progress_end_option_token_message(
+                            &con.clone(),
+                            &progress,
+                            &task,
+                            Some(progress_summary(Some(loaded), started)),
+                        );
                     });
                 }
             }
@@ -423,93 +743,212 @@ impl Backend {
 
         let _ = handles.join_all().await;
 
-        Self::progress_end_option_token(&con, &progress, "Init");
+        let loaded = class_map.read().map(|cm| cm.len()).ok();
+        let summary = progress_summary(loaded, started);
+        Self::progress_end_option_token_message(&con, &progress, "Init", Some(summary.clone()));
+        logging::server_status(&con, ServerStatus::Ready, Some(&summary));
+    }
+
+    /// Index the classes of the JDK that is found on the PATH, with a release its platform
+    /// classes come from `ct.sym`
+    pub async fn load_jdk(
+        con: Arc<Connection>,
+        class_map: Arc<RwLock<HashMap<MyString, Class>>>,
+        path: OsString,
+        release: Option<u32>,
+    ) {
+        let task = "Load jdk";
+        let progress = Arc::new(Option::Some(ProgressToken::String(task.to_owned())));
+        let (sender, receiver) = tokio::sync::watch::channel::<TaskProgress>(TaskProgress {
+            percentage: 0,
+            error: false,
+            message: "...".to_string(),
+        });
+
+        let started = Instant::now();
+        Self::progress_start_option_token(&con.clone(), &progress, task);
+        let mut loaded = None;
+        tokio::select! {
+            () = read_forward(receiver, con.clone(), task.to_owned(), progress.clone())  => {},
+            out = jdk::load_classes(class_map, sender, &path, release) => {
+                match out {
+                    Ok(count) => loaded = Some(count),
+                    Err(e) => {
+                        tracing::error!("Failed to load jdk: {e:?}");
+                        logging::server_status(
+                            &con,
+                            ServerStatus::Error,
+                            Some(&format!("Failed to load jdk: {e:?}")),
+                        );
+                    }
+                }
+            }
+        }
+        if loaded.is_some() {
+            timings::INDEX.record(started.elapsed());
+        }
+        Self::progress_end_option_token_message(
+            &con.clone(),
+            &progress,
+            task,
+            Some(progress_summary(loaded, started)),
+        );
     }
 
     pub fn did_open(&self, params: &DidOpenTextDocumentParams) {
-        if !params
-            .text_document
-            .uri
-            .path()
-            .as_str()
-            .to_lowercase()
-            .ends_with(".java")
-        {
+        let uri = &params.text_document.uri;
+        let is_java = if scratch::is_scratch(uri) {
+            params.text_document.language_id == scratch::LANGUAGE_ID
+        } else {
+            uri.path().as_str().to_lowercase().ends_with(".java")
+        };
+        if !is_java {
+            return;
+        }
+        if class_contents::is_virtual(&params.text_document.uri) {
+            // Read only, neither compiled nor checked
+            let document_map_key = get_document_map_key(&params.text_document.uri);
+            if let Err(e) = open_document(
+                &document_map_key,
+                &params.text_document.text,
+                params.text_document.version,
+                &self.document_map,
+            ) {
+                tracing::debug!("Error while on_open: {e:?}");
+            }
             return;
         }
         let path = params.text_document.uri.path();
         let path_str = path.as_str();
 
         let mut current_file_diagnostics = Vec::new();
-        self.compile_project_file(
-            &params.text_document.uri,
-            path_str,
-            &mut current_file_diagnostics,
-        );
         let document_map_key = get_document_map_key(&params.text_document.uri);
         match open_document(
             &document_map_key,
             &params.text_document.text,
+            params.text_document.version,
             &self.document_map,
         ) {
-            Ok(()) => {}
+            Ok(()) => {
+                if let Ok(dm) = self.document_map.read()
+                    && let Some(document) = dm.get(&document_map_key)
+                {
+                    current_file_diagnostics.extend(self.queries.diagnostics(
+                        &document_map_key,
+                        document,
+                        &params.text_document.uri,
+                        &self.class_map,
+                        &self.config,
+                        self.language_level(&params.text_document.uri),
+                    ));
+                }
+            }
             Err(DocumentError::Diagnostic(diag)) => {
-                current_file_diagnostics.push(*diag);
+                current_file_diagnostics.extend(language_level::syntax_error(
+                    *diag,
+                    &params.text_document.text,
+                    self.language_level(&params.text_document.uri),
+                ));
             }
             Err(e) => {
-                eprintln!("Error while on_open: {e:?}");
+                tracing::debug!("Error while on_open: {e:?}");
             }
         }
-        Self::send_diagnostic(
-            &self.connection,
-            params.text_document.uri.clone(),
-            current_file_diagnostics,
-        );
+        self.publish_diagnostics(params.text_document.uri.clone(), current_file_diagnostics);
+        self.index_bazel_file(&params.text_document.uri, path_str);
+        self.index_bsp_workspace(&params.text_document.uri);
+    }
+
+    /// Load the bazel targets that own the file in the background
+    fn index_bazel_file(&self, uri: &Uri, path: &str) {
+        let Some(Project {
+            dir,
+            kind: ProjectKind::Bazel { executable },
+            ..
+        }) = self.get_project(uri)
+        else {
+            return;
+        };
+        let bazel_targets = self.bazel_targets.clone();
+        let class_map = self.class_map.clone();
+        let file = PathBuf::from(path);
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) =
+                bazel_targets.index_file(&class_map, &executable, Path::new(&dir), &file)
+            {
+                tracing::warn!("Failed to load bazel targets of {}: {e:?}", file.display());
+            }
+        });
+    }
+
+    /// Load the java targets of the build server in the background, once per workspace
+    fn index_bsp_workspace(&self, uri: &Uri) {
+        let Some(Project {
+            dir,
+            kind: ProjectKind::Bsp { path_connection },
+            ..
+        }) = self.get_project(uri)
+        else {
+            return;
+        };
+        let bsp_servers = self.bsp_servers.clone();
+        let class_map = self.class_map.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) =
+                bsp_servers.index_workspace(&class_map, &path_connection, Path::new(&dir))
+            {
+                tracing::warn!("Failed to load build server targets of {dir}: {e:?}");
+            }
+        });
     }
+
     pub fn did_close(&self, params: &DidCloseTextDocumentParams) {
         let key = get_document_map_key(&params.text_document.uri);
-        eprintln!("Closing file: {key}");
+        tracing::debug!("Closing file: {key}");
 
         let Ok(mut dm) = self.document_map.write() else {
-            eprintln!("document_map mutex poisoned");
+            tracing::error!("document_map mutex poisoned");
             return;
         };
         dm.remove(&key.to_smolstr());
+        drop(dm);
+        self.queries.remove(&key);
     }
 
     pub fn did_change(&self, params: &DidChangeTextDocumentParams) {
-        if !params
-            .text_document
-            .uri
-            .path()
-            .as_str()
-            .to_lowercase()
-            .ends_with(".java")
-        {
+        if !self.is_java(&params.text_document.uri) {
             return;
         }
 
         let Ok(mut dm) = self.document_map.write() else {
-            eprintln!("document_map mutex poisoned");
+            tracing::error!("document_map mutex poisoned");
             return;
         };
-        let Some(document) =
-            dm.get_mut(&get_document_map_key(&params.text_document.uri).to_smolstr())
-        else {
-            eprintln!("on_change document not found");
+        let key = get_document_map_key(&params.text_document.uri);
+        let Some(document) = dm.get_mut(&key) else {
+            tracing::debug!("on_change document not found");
             return;
         };
+        document.version = params.text_document.version;
+        document.touch();
         let mut errors = Vec::new();
-        if let Err(DocumentError::Diagnostic(diag)) =
-            document.apply_text_changes(&params.content_changes)
-        {
-            errors.push(*diag);
+        match document.apply_text_changes(&params.content_changes) {
+            Ok(()) => errors.extend(self.queries.diagnostics(
+                &key,
+                document,
+                &params.text_document.uri,
+                &self.class_map,
+                &self.config,
+                self.language_level(&params.text_document.uri),
+            )),
+            Err(DocumentError::Diagnostic(diag)) => errors.extend(language_level::syntax_error(
+                *diag,
+                &document.rope.to_string(),
+                self.language_level(&params.text_document.uri),
+            )),
+            Err(_) => (),
         }
-        Self::send_diagnostic(
-            &self.connection.clone(),
-            params.text_document.uri.clone(),
-            errors,
-        );
+        self.publish_diagnostics(params.text_document.uri.clone(), errors);
     }
 
     pub fn did_save(&self, params: &DidSaveTextDocumentParams) {
@@ -524,35 +963,107 @@ impl Backend {
             path_str,
             &mut current_file_diagnostics,
         );
+        self.generate_sources(&params.text_document.uri, path_str);
 
         let Ok(dm) = self.document_map.read() else {
-            eprintln!("document_map mutex poisoned");
+            tracing::error!("document_map mutex poisoned");
             return;
         };
-        let Some(document) = dm.get(&get_document_map_key(&params.text_document.uri)) else {
-            eprintln!("on_change document not found");
+        let key = get_document_map_key(&params.text_document.uri);
+        let Some(document) = dm.get(&key) else {
+            tracing::debug!("on_change document not found");
             return;
         };
-        if let Err(DocumentError::Diagnostic(diag)) = document.reparse_no_change() {
-            current_file_diagnostics.push(*diag);
-        }
         let class = parser::update_project_java_file(PathBuf::from(path.as_str()), &document.ast);
-        let class_path = class.class_path.clone();
-        match references::reference_update_class(&class, &self.class_map, &self.reference_map) {
-            Ok(()) => {}
-            Err(e) => eprintln!("Got reference error: {e:?}"),
-        }
-        if let Ok(mut class_map) = self.class_map.write() {
-            class_map.insert(class_path, class);
-        } else {
-            eprintln!("class_map mutex poisoned");
+        // Only a changed signature affects other files, edits in bodies keep the class
+        let changed = self.class_map.read().map_or(true, |class_map| {
+            class_map.get(&class.class_path) != Some(&class)
+        });
+        if changed {
+            self.queries.classes_changed();
+        }
+        match document.reparse_no_change() {
+            Ok(()) => current_file_diagnostics.extend(self.queries.diagnostics(
+                &key,
+                document,
+                &params.text_document.uri,
+                &self.class_map,
+                &self.config,
+                self.language_level(&params.text_document.uri),
+            )),
+            Err(DocumentError::Diagnostic(diag)) => {
+                current_file_diagnostics.extend(language_level::syntax_error(
+                    *diag,
+                    &document.rope.to_string(),
+                    self.language_level(&params.text_document.uri),
+                ));
+            }
+            Err(_) => (),
+        }
+        self.names.update(path_str, &document.tokens);
+        if changed {
+            let class_path = class.class_path.clone();
+            match references::reference_update_class(&class, &self.class_map, &self.reference_map) {
+                Ok(()) => {}
+                Err(e) => tracing::warn!("Got reference error: {e:?}"),
+            }
+            if let Ok(mut class_map) = self.class_map.write() {
+                class_map.insert(class_path, class.clone());
+            } else {
+                tracing::error!("class_map mutex poisoned");
+            }
+            self.subtypes.update(&class, &self.class_map);
         }
 
-        Self::send_diagnostic(
-            &self.connection,
-            params.text_document.uri.clone(),
-            current_file_diagnostics,
-        );
+        self.publish_diagnostics(params.text_document.uri.clone(), current_file_diagnostics);
+        self.analyze(&params.text_document.uri, path_str, &class.class_path);
+    }
+
+    /// Run the enabled analyzers on the saved file in the background
+    fn analyze(&self, uri: &Uri, path_str: &str, class_path: &str) {
+        if path_str.starts_with(common::CACHE_DIR.as_str()) {
+            return;
+        }
+        let pmd = self.config.pmd.clone();
+        let spotbugs = self.config.spotbugs.clone();
+        if !pmd.enabled && !spotbugs.enabled {
+            return;
+        }
+        let project = self.get_project(uri);
+        let findings = self.findings.clone();
+        let publish = self.diagnostics_publisher();
+        let uri = uri.clone();
+        let path = PathBuf::from(path_str);
+        let class_path = class_path.to_string();
+        tokio::task::spawn_blocking(move || {
+            let run = |tool: Analyzer, config: &AnalyzerConfig| {
+                if !config.enabled {
+                    return;
+                }
+                let found = match tool {
+                    Analyzer::Pmd => analyzers::pmd(config, &path),
+                    // SpotBugs needs the classes that javac compiled
+                    Analyzer::SpotBugs => {
+                        let Some(javac) = project.as_ref().and_then(|p| javac::javac(p, &path))
+                        else {
+                            tracing::debug!("No classes of {} for spotbugs", path.display());
+                            return;
+                        };
+                        analyzers::spotbugs(config, &javac, &class_path, &path)
+                    }
+                };
+                match found {
+                    Ok(found) => {
+                        for (uri, diagnostics) in findings.update(tool, &uri, found) {
+                            publish(uri, diagnostics);
+                        }
+                    }
+                    Err(e) => tracing::warn!("Failed to run {}: {e:?}", tool.name()),
+                }
+            };
+            run(Analyzer::Pmd, &pmd);
+            run(Analyzer::SpotBugs, &spotbugs);
+        });
     }
 
     /// Only run javac on project files
@@ -565,33 +1076,58 @@ impl Backend {
         path_str: &str,
         current_file_diagnostics: &mut Vec<Diagnostic>,
     ) {
+        if !self.config.javac_diagnostics {
+            return;
+        }
         if let Some(errors) = self.compile(path_str, uri) {
             current_file_diagnostics.extend(self.publish_compile_errors(errors, uri));
         }
     }
 
+    /// Run the annotation processors of the file and load the classes they generated
+    fn generate_sources(&self, uri: &Uri, path_str: &str) {
+        if path_str.starts_with(common::CACHE_DIR.as_str()) {
+            return;
+        }
+        let Some(project) = self.get_project(uri) else {
+            return;
+        };
+        let project_config = self
+            .project_configs
+            .lock()
+            .map(|mut configs| configs.get(Path::new(&project.dir)))
+            .unwrap_or_default();
+        let classes = javac::generate(&project, path_str, &project_config.processors);
+        if classes.is_empty() {
+            return;
+        }
+        if let Ok(mut class_map) = self.class_map.write() {
+            for class in &classes {
+                class_map.insert(class.class_path.clone(), class.clone());
+            }
+        } else {
+            tracing::error!("class_map mutex poisoned");
+        }
+        self.queries.classes_changed();
+        for class in &classes {
+            self.subtypes.update(class, &self.class_map);
+        }
+    }
+
     pub fn hover(&self, params: HoverParams) -> Option<Hover> {
-        if !params
-            .text_document_position_params
-            .text_document
-            .uri
-            .path()
-            .as_str()
-            .to_lowercase()
-            .ends_with(".java")
-        {
+        if !self.is_java(&params.text_document_position_params.text_document.uri) {
             return None;
         }
         let uri = params.text_document_position_params.text_document.uri;
         let Ok(dm) = self.document_map.read() else {
-            eprintln!("document_map mutex poisoned");
+            tracing::error!("document_map mutex poisoned");
             return None;
         };
         let document = dm.get(&get_document_map_key(&uri).to_smolstr())?;
         let point = to_ast_point(params.text_document_position_params.position);
         let imports = imports::imports(&document.ast);
 
-        let class = self.get_class(&document.ast)?;
+        let class = self.get_class(&uri, &document.ast)?;
 
         let vars = match variables::get_vars(
             &document.ast,
@@ -604,7 +1140,7 @@ impl Backend {
         ) {
             Ok(v) => Some(v),
             Err(e) => {
-                eprintln!("Could not get vars: {e:?}");
+                tracing::debug!("Could not get vars: {e:?}");
                 None
             }
         }?;
@@ -612,7 +1148,7 @@ impl Backend {
         match hover::base(&document.ast, &point, &vars, &imports, &self.class_map) {
             Ok(hover) => Some(hover),
             Err(e) => {
-                eprintln!("Error while hover: {e:?}");
+                tracing::debug!("Error while hover: {e:?}");
                 None
             }
         }
@@ -629,35 +1165,67 @@ impl Backend {
         {
             return None;
         }
-        if matches!(&self.config.formatter, config::FormatterConfig::None) {
+        let uri = params.text_document.uri;
+        let project = self.get_project(&uri)?;
+        let project_config = self
+            .project_configs
+            .lock()
+            .map(|mut configs| configs.get(Path::new(&project.dir)))
+            .unwrap_or_default();
+        // Settings of the client win over the java_lsp.toml
+        let formatter = project_config
+            .formatter()
+            .filter(|_| !self.client_settings.contains_key("formatter"))
+            .unwrap_or_else(|| self.config.formatter.clone());
+        if matches!(formatter, config::FormatterConfig::None) {
             Configuration::missing("formatter");
             return None;
         }
-        let uri = params.text_document.uri;
         let Ok(mut dm) = self.document_map.write() else {
-            eprintln!("document_map mutex poisoned");
+            tracing::error!("document_map mutex poisoned");
             return None;
         };
         let Some(document) = dm.get_mut(&get_document_map_key(&uri).to_smolstr()) else {
-            eprintln!("Document is not opened.");
+            tracing::debug!("Document is not opened.");
             return None;
         };
         let lines = document.rope.lines().len();
-        let space = if params.options.insert_spaces {
-            " ".repeat(params.options.tab_size as usize)
-        } else {
-            "\t".to_string()
+        let editorconfig = EditorConfig::load(&document.path);
+        let format = &project_config.format;
+        let space = match (format.indent_tabs, format.indent_size) {
+            (Some(true), _) => "\t".to_string(),
+            (_, Some(size)) => " ".repeat(size),
+            _ => editorconfig.space(
+                params.options.insert_spaces,
+                params.options.tab_size as usize,
+            ),
         };
-        let name = formatter::get_formatter_name(&self.config.formatter);
-        let project = self.get_project(&uri)?;
+        let options = FormatOptions {
+            space,
+            width: format
+                .line_width
+                .filter(|_| !self.client_settings.contains_key("format_line_width"))
+                .or(editorconfig.max_line_length)
+                .unwrap_or(self.config.format_line_width),
+            import_order: if project_config.imports.order.is_empty()
+                || self.client_settings.contains_key("import_order")
+            {
+                self.config.import_order.clone()
+            } else {
+                project_config.imports.order
+            },
+            member_order: project_config.members.order,
+        };
+        let name = formatter::get_formatter_name(&formatter);
         match formatter::format(
-            &self.config.formatter,
+            &formatter,
             document.rope.to_string().as_bytes(),
             &document.path,
             PathBuf::from(project.dir).as_path(),
-            &space,
+            &options,
         ) {
             Ok(Some(o)) => {
+                let o = editorconfig.finish(o);
                 let out = String::from_utf8_lossy(&o);
                 let lines = u32::try_from(lines).unwrap_or_default();
                 Some(vec![TextEdit::new(
@@ -678,7 +1246,7 @@ impl Backend {
                 None
             }
             Err(e) => {
-                eprintln!("Got formatter error {e:?}");
+                tracing::debug!("Got formatter error {e:?}");
                 None
             }
         }
@@ -687,13 +1255,16 @@ impl Backend {
     pub fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
         let params = params.text_document_position;
         let uri = params.text_document.uri;
-        if !uri.path().as_str().to_lowercase().ends_with(".java") {
+        if !self.is_java(&uri) {
             return None;
         }
         let document = self.get_document(&uri)?;
 
         let mut out = vec![];
         let point = to_ast_point(params.position);
+        if let Some(javadoc) = completion::javadoc(&document, &point) {
+            return Some(CompletionResponse::Array(vec![javadoc]));
+        }
         let imports = imports::imports(&document.ast);
 
         if let Some(imp) = completion::imports(&document, &point, &self.class_map) {
@@ -717,7 +1288,7 @@ impl Backend {
         out.push(snippet_completion("if", snipptes::IF));
         out.push(snippet_completion("switch", snipptes::SWITCH));
 
-        let class = self.get_class(&document.ast)?;
+        let class = self.get_class(&uri, &document.ast)?;
 
         let vars = match variables::get_vars(
             &document.ast,
@@ -730,10 +1301,13 @@ impl Backend {
         ) {
             Ok(v) => Some(v),
             Err(e) => {
-                eprintln!("Could not get vars: {e:?}");
+                tracing::debug!("Could not get vars: {e:?}");
                 None
             }
         }?;
+        if self.cancelled() {
+            return None;
+        }
 
         match completion::complete_call_chain(
             &document,
@@ -747,10 +1321,13 @@ impl Backend {
                 out.extend(call_chain);
             }
             Err(e) => {
-                eprintln!("Error while completion: {e:?}");
+                tracing::debug!("Error while completion: {e:?}");
             }
         }
-        out.extend(completion::static_methods(
+        if self.cancelled() {
+            return None;
+        }
+        out.extend(completion::static_members(
             &document.ast,
             &imports,
             &self.class_map,
@@ -770,14 +1347,14 @@ impl Backend {
         let params = params.text_document_position_params;
         let uri = params.text_document.uri;
 
-        if !uri.path().as_str().to_lowercase().ends_with(".java") {
+        if !self.is_java(&uri) {
             return None;
         }
         let document = self.get_document(&uri)?;
 
         let point = to_ast_point(params.position);
         let imports = imports::imports(&document.ast);
-        let class = self.get_class(&document.ast)?;
+        let class = self.get_class(&uri, &document.ast)?;
 
         let vars = match variables::get_vars(
             &document.ast,
@@ -790,7 +1367,7 @@ impl Backend {
         ) {
             Ok(v) => Some(v),
             Err(e) => {
-                eprintln!("Could not get vars: {e:?}");
+                tracing::debug!("Could not get vars: {e:?}");
                 None
             }
         }?;
@@ -806,33 +1383,245 @@ impl Backend {
         };
 
         match definition::class(&document.ast, &context, &self.document_map) {
-            Ok(definition) => return Some(definition),
+            Ok(definition) => return Some(self.definition_response(definition)),
             Err(e) => {
-                eprintln!("Error while class definition: {e:?}");
+                tracing::debug!("Error while class definition: {e:?}");
             }
         }
         let call_chain = get_call_chain(&document.ast, &point);
         match definition::call_chain_definition(&call_chain, &context) {
-            Ok(definition) => return Some(definition),
+            Ok(definition) => return Some(self.definition_response(definition)),
             Err(e) => {
-                eprintln!("Error while call_chain definition: {e:?}");
+                tracing::debug!("Error while call_chain definition: {e:?}");
             }
         }
         None
     }
 
-    pub fn references(&self, params: ReferenceParams) -> Option<Vec<Location>> {
-        let params = params.text_document_position;
+    /// Definitions into the cache folder as `jdt` uris for clients that show class contents
+    fn definition_response(&self, response: GotoDefinitionResponse) -> GotoDefinitionResponse {
+        if !self.config.class_file_contents {
+            return response;
+        }
+        class_contents::virtual_definition(response, &common::cache_dir_no_create())
+    }
+
+    pub fn class_contents(params: &class_contents::ClassContentsParams) -> Option<String> {
+        class_contents::class_contents(&params.uri, &common::cache_dir_no_create())
+    }
+
+    pub fn implementation(
+        &self,
+        params: GotoImplementationParams,
+    ) -> Option<GotoImplementationResponse> {
+        let params = params.text_document_position_params;
         let uri = params.text_document.uri;
-        if !uri.path().as_str().to_lowercase().ends_with(".java") {
+        if !self.is_java(&uri) {
             return None;
         }
-
+        let document = self.get_document(&uri)?;
+        let point = to_ast_point(params.position);
+        let imports = imports::imports(&document.ast);
+        let class = self.get_class(&uri, &document.ast)?;
+        let vars = match variables::get_vars(
+            &document.ast,
+            &VariableContext {
+                point: Some(point),
+                imports: &imports,
+                class: &class,
+                class_map: self.class_map.clone(),
+            },
+        ) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                tracing::debug!("Could not get vars: {e:?}");
+                None
+            }
+        }?;
+        let subtypes = self.subtypes.get(&self.class_map);
+
+        let locations = if let Ok((found, _range)) =
+            class_action(&document.ast, &point, &vars, &imports, &self.class_map)
+        {
+            implementation::class_implementations(
+                &found.class_path,
+                &subtypes,
+                &self.class_map,
+                &self.document_map,
+            )
+        } else {
+            let (class_path, name, method) =
+                self.method_at(&document.ast, &point, &vars, &imports, &class)?;
+            implementation::method_implementations(
+                &class_path,
+                &name,
+                method.as_ref(),
+                &subtypes,
+                &self.class_map,
+                &self.document_map,
+            )
+        };
+        Some(GotoImplementationResponse::Array(locations))
+    }
+
+    /// The methods that override the method declared or called at the position
+    pub fn find_overrides(&self, params: &TextDocumentPositionParams) -> Option<Vec<Location>> {
+        let uri = &params.text_document.uri;
+        if !self.is_java(uri) {
+            return None;
+        }
+        let document = self.get_document(uri)?;
+        let point = to_ast_point(params.position);
+        let imports = imports::imports(&document.ast);
+        let class = self.get_class(uri, &document.ast)?;
+        let vars = match variables::get_vars(
+            &document.ast,
+            &VariableContext {
+                point: Some(point),
+                imports: &imports,
+                class: &class,
+                class_map: self.class_map.clone(),
+            },
+        ) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                tracing::debug!("Could not get vars: {e:?}");
+                None
+            }
+        }?;
+        let (class_path, name, method) =
+            self.method_at(&document.ast, &point, &vars, &imports, &class)?;
+        let subtypes = self.subtypes.get(&self.class_map);
+        Some(implementation::method_implementations(
+            &class_path,
+            &name,
+            method.as_ref(),
+            &subtypes,
+            &self.class_map,
+            &self.document_map,
+        ))
+    }
+
+    /// Class path, name and selected overload of the method declared or called at the
+    /// position, the overload is None for a declaration
+    fn method_at(
+        &self,
+        ast: &AstFile,
+        point: &AstPoint,
+        vars: &[LocalVariable],
+        imports: &[ImportUnit],
+        class: &Class,
+    ) -> Option<(MyString, MyString, Option<Method>)> {
+        if let Some(name) = implementation::declared_method(ast, point) {
+            return Some((class.class_path.clone(), name, None));
+        }
+        let call_chain = call_chain::flatten_argument_lists(&get_call_chain(ast, point));
+        let (item, relevant) = call_chain::validate(&call_chain, point);
+        let Some(CallItem::MethodCall { name, args, .. }) = relevant.get(item) else {
+            return None;
+        };
+        match tyres::resolve_call_chain_to_point(
+            &relevant,
+            vars,
+            imports,
+            class,
+            &self.class_map,
+            point,
+        ) {
+            Ok(resolve_state) => {
+                let arguments =
+                    tyres::resolve_arguments(args, vars, imports, class, &self.class_map);
+                let method = tyres::select_methods(
+                    &resolve_state.class,
+                    Some(name),
+                    &arguments,
+                    &self.class_map,
+                )
+                .first()
+                .map(|m| (*m).clone());
+                Some((resolve_state.class.class_path, name.clone(), method))
+            }
+            Err(e) => {
+                tracing::debug!("Could not resolve method: {e:?}");
+                None
+            }
+        }
+    }
+
+    pub fn prepare_type_hierarchy(
+        &self,
+        params: TypeHierarchyPrepareParams,
+    ) -> Option<Vec<TypeHierarchyItem>> {
+        let params = params.text_document_position_params;
+        let uri = params.text_document.uri;
+        if !self.is_java(&uri) {
+            return None;
+        }
+        let document = self.get_document(&uri)?;
+        let point = to_ast_point(params.position);
+        let imports = imports::imports(&document.ast);
+        let class = self.get_class(&uri, &document.ast)?;
+        let vars = variables::get_vars(
+            &document.ast,
+            &VariableContext {
+                point: Some(point),
+                imports: &imports,
+                class: &class,
+                class_map: self.class_map.clone(),
+            },
+        )
+        .unwrap_or_default();
+        let class = class_action(&document.ast, &point, &vars, &imports, &self.class_map)
+            .map_or(class, |(found, _range)| found);
+        match implementation::type_hierarchy_item(&class, &self.class_map, &self.document_map) {
+            Ok(item) => Some(vec![item]),
+            Err(e) => {
+                tracing::debug!("Could not prepare type hierarchy: {e:?}");
+                None
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn type_hierarchy_supertypes(
+        &self,
+        params: &TypeHierarchySupertypesParams,
+    ) -> Option<Vec<TypeHierarchyItem>> {
+        let class_path = implementation::item_class_path(&params.item)?;
+        let subtypes = self.subtypes.get(&self.class_map);
+        Some(implementation::type_hierarchy_items(
+            subtypes.direct_supertypes(class_path),
+            &self.class_map,
+            &self.document_map,
+        ))
+    }
+
+    #[must_use]
+    pub fn type_hierarchy_subtypes(
+        &self,
+        params: &TypeHierarchySubtypesParams,
+    ) -> Option<Vec<TypeHierarchyItem>> {
+        let class_path = implementation::item_class_path(&params.item)?;
+        let subtypes = self.subtypes.get(&self.class_map);
+        Some(implementation::type_hierarchy_items(
+            subtypes.direct_subtypes(class_path),
+            &self.class_map,
+            &self.document_map,
+        ))
+    }
+
+    pub fn references(&self, params: ReferenceParams) -> Option<Vec<Location>> {
+        let params = params.text_document_position;
+        let uri = params.text_document.uri;
+        if !self.is_java(&uri) {
+            return None;
+        }
+
         let document = self.get_document(&uri)?;
 
         let point = to_ast_point(params.position);
         let imports = imports::imports(&document.ast);
-        let class = self.get_class(&document.ast)?;
+        let class = self.get_class(&uri, &document.ast)?;
         let vars = match variables::get_vars(
             &document.ast,
             &VariableContext {
@@ -844,10 +1633,16 @@ impl Backend {
         ) {
             Ok(v) => Some(v),
             Err(e) => {
-                eprintln!("Could not get vars: {e:?}");
+                tracing::debug!("Could not get vars: {e:?}");
                 None
             }
         }?;
+        if self.cancelled() {
+            return None;
+        }
+        if let Some((name, arity)) = references::constructor_at(&document.ast, &point) {
+            return self.constructor_calls(&name, arity, &imports, &class);
+        }
         match class_action(&document.ast, &point, &vars, &imports, &self.class_map) {
             Ok((class, _range)) => {
                 if let Some(value) = references::class_path(
@@ -855,14 +1650,37 @@ impl Backend {
                     &self.reference_map,
                     &self.class_map,
                     &self.document_map,
+                    &self.names,
+                    &|| self.cancelled(),
                 ) {
                     return Some(value);
                 }
             }
-            Err(e) => eprintln!("Got reference class error: {e:?}"),
+            Err(e) => tracing::debug!("Got reference class error: {e:?}"),
+        }
+        if self.cancelled() {
+            return None;
+        }
+        let subtypes = self.subtypes.get(&self.class_map);
+        let overrides = self.config.references_include_overrides;
+        if let Some(name) = implementation::declared_method(&document.ast, &point) {
+            let query = references::MethodQuery {
+                class_path: class.class_path,
+                name,
+                method: None,
+                overrides,
+            };
+            return Some(references::method(
+                &query,
+                &subtypes,
+                &self.reference_map,
+                &self.class_map,
+                &self.document_map,
+                &self.names,
+                &|| self.cancelled(),
+            ));
         }
         let call_chain = get_call_chain(&document.ast, &point);
-        let class = self.get_class(&document.ast)?;
         let context = ReferencesContext {
             point: &point,
             imports: &imports,
@@ -874,26 +1692,87 @@ impl Backend {
         match references::call_chain_references(
             &call_chain,
             &context,
+            overrides,
+            &subtypes,
             &self.reference_map,
             &self.document_map,
+            &self.names,
         ) {
             Ok(refs) => Some(refs),
             Err(e) => {
-                eprintln!("Got reference call_chain error: {e:?}");
+                tracing::debug!("Got reference call_chain error: {e:?}");
                 None
             }
         }
     }
 
+    /// Calls of the constructors of the class at the position, of only the constructor when the
+    /// position is on its declaration
+    pub fn constructor_references(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<Vec<Location>> {
+        let uri = &params.text_document.uri;
+        if !self.is_java(uri) {
+            return None;
+        }
+        let document = self.get_document(uri)?;
+        let point = to_ast_point(params.position);
+        let imports = imports::imports(&document.ast);
+        let class = self.get_class(uri, &document.ast)?;
+        if let Some((name, arity)) = references::constructor_at(&document.ast, &point) {
+            return self.constructor_calls(&name, arity, &imports, &class);
+        }
+        let (class, _range) =
+            match class_action(&document.ast, &point, &[], &imports, &self.class_map) {
+                Ok(found) => found,
+                Err(e) => {
+                    tracing::debug!("Got constructor reference class error: {e:?}");
+                    return None;
+                }
+            };
+        self.constructor_calls(&class.name, None, &imports, &class)
+    }
+
+    /// `name` is the simple name of the class, the class of the file when it declares it
+    fn constructor_calls(
+        &self,
+        name: &str,
+        arity: Option<usize>,
+        imports: &[ImportUnit],
+        class: &Class,
+    ) -> Option<Vec<Location>> {
+        let class_path = if class.name == name {
+            class.class_path.clone()
+        } else {
+            tyres::resolve(name, imports, &self.class_map).map_or_else(
+                |_| format_smolstr!("{}${name}", class.class_path),
+                |resolved| resolved.class.class_path,
+            )
+        };
+        references::constructor(
+            &references::Constructor { class_path, arity },
+            &self.reference_map,
+            &self.class_map,
+            &self.document_map,
+            &self.names,
+            &|| self.cancelled(),
+        )
+    }
+
     pub fn code_action(&self, params: CodeActionParams) -> Option<CodeActionResponse> {
         let path_str = params.text_document.uri.path().as_str().to_lowercase();
 
         // Is already lowercase
         #[allow(clippy::case_sensitive_file_extension_comparisons)]
-        if !path_str.ends_with(".java") {
+        if !self.is_java(&params.text_document.uri) {
             if path_str.ends_with("pom.xml")
                 || path_str.ends_with("build.gradle")
                 || path_str.ends_with("build.gradle.kts")
+                || path_str.ends_with("settings.gradle")
+                || path_str.ends_with("settings.gradle.kts")
+                || path_str.ends_with(".versions.toml")
+                || path_str.ends_with(".classpath")
             {
                 return Some(vec![
                     CodeActionOrCommand::Command(Command {
@@ -916,20 +1795,25 @@ impl Backend {
             Ok(None) => (),
             Ok(Some(e)) => return Some(vec![e]),
             Err(e) => {
-                eprintln!("Got error code_action generate class: {e:?}");
+                tracing::debug!("Got error code_action generate class: {e:?}");
             }
         }
         let point = to_ast_point(params.range.start);
 
         let imports = imports::imports(&document.ast);
 
-        let class = self.get_class(&document.ast)?;
+        let class = self.get_class(&current_file, &document.ast)?;
 
         if let Some(imps) = codeaction::import_jtype(
             &document.ast,
             &point,
             &imports,
             &self.class_map,
+            &self.symbols.get(&self.class_map),
+            &self
+                .get_project(&current_file)
+                .map(|p| self.import_choices.counts(Path::new(&p.dir)))
+                .unwrap_or_default(),
             &current_file,
         ) {
             return Some(imps);
@@ -945,7 +1829,7 @@ impl Backend {
         ) {
             Ok(v) => Some(v),
             Err(e) => {
-                eprintln!("Could not get vars: {e:?}");
+                tracing::debug!("Could not get vars: {e:?}");
                 None
             }
         }?;
@@ -963,16 +1847,102 @@ impl Backend {
             Ok(None) => (),
             Ok(Some(e)) => return Some(vec![e]),
             Err(e) => {
-                eprintln!("Got error code_action replace with value: {e:?}");
+                tracing::debug!("Got error code_action replace with value: {e:?}");
             }
         }
 
-        None
+        match quickfix::unhandled_exception(&document, &context, &params.context.diagnostics) {
+            Ok(e) if e.is_empty() => (),
+            Ok(e) => return Some(e),
+            Err(e) => {
+                tracing::debug!("Got error code_action unhandled exception: {e:?}");
+            }
+        }
+
+        match quickfix::create_method(&document, &context, &self.document_map) {
+            Ok(None) => (),
+            Ok(Some(e)) => return Some(vec![e]),
+            Err(e) => {
+                tracing::debug!("Got error code_action create method: {e:?}");
+            }
+        }
+
+        let mut actions = lint_fix::code_actions(&document, &context, &params.context.diagnostics);
+        match refactor::lambda_to_anonymous_class(&document, &context) {
+            Ok(None) => (),
+            Ok(Some(e)) => actions.push(e),
+            Err(e) => {
+                tracing::debug!("Got error code_action lambda to anonymous class: {e:?}");
+            }
+        }
+        match refactor::convert_for_loop(&document, &context) {
+            Ok(e) => actions.extend(e),
+            Err(e) => {
+                tracing::debug!("Got error code_action convert for loop: {e:?}");
+            }
+        }
+        match refactor::convert_switch(&document, &context) {
+            Ok(e) => actions.extend(e),
+            Err(e) => {
+                tracing::debug!("Got error code_action convert switch: {e:?}");
+            }
+        }
+        match refactor::convert_var(
+            &document,
+            &context,
+            Feature::Var.available(self.language_level(&current_file)),
+        ) {
+            Ok(e) => actions.extend(e),
+            Err(e) => {
+                tracing::debug!("Got error code_action convert var: {e:?}");
+            }
+        }
+        match refactor::split_join_declaration(&document, &context) {
+            Ok(e) => actions.extend(e),
+            Err(e) => {
+                tracing::debug!("Got error code_action split declaration: {e:?}");
+            }
+        }
+        match switch_cases::add_missing_cases(&document, &context) {
+            Ok(e) => actions.extend(e),
+            Err(e) => {
+                tracing::debug!("Got error code_action missing switch cases: {e:?}");
+            }
+        }
+        match refactor::invert_if(&document, &context) {
+            Ok(e) => actions.extend(e),
+            Err(e) => {
+                tracing::debug!("Got error code_action invert if: {e:?}");
+            }
+        }
+        match refactor::toggle_final(&document, &context) {
+            Ok(e) => actions.extend(e),
+            Err(e) => {
+                tracing::debug!("Got error code_action toggle final: {e:?}");
+            }
+        }
+        match refactor::move_class(&document, &context) {
+            Ok(e) => actions.extend(e),
+            Err(e) => {
+                tracing::debug!("Got error code_action move class: {e:?}");
+            }
+        }
+        match refactor::surround_with(&document, &context, &to_ast_range(&params.range)) {
+            Ok(e) => actions.extend(e),
+            Err(e) => {
+                tracing::debug!("Got error code_action surround with: {e:?}");
+            }
+        }
+
+        if actions.is_empty() {
+            return None;
+        }
+        Some(actions)
     }
 
     pub fn document_symbol(&self, params: DocumentSymbolParams) -> Option<DocumentSymbolResponse> {
         let uri = params.text_document.uri;
-        if !uri.path().as_str().to_lowercase().ends_with(".java") {
+        if !self.is_java(&uri) {
             return None;
         }
         let document = self.get_document(&uri)?;
@@ -985,28 +1955,101 @@ impl Backend {
         Some(DocumentSymbolResponse::Flat(symbols))
     }
 
+    #[must_use]
+    pub fn workspace_symbol(&self, params: &WorkspaceSymbolParams) -> WorkspaceSymbolResponse {
+        const LIMIT: usize = 100;
+        let index = self.symbols.get(&self.class_map);
+        let found: Vec<(String, SymbolKind, bool, Option<Class>)> = {
+            let Ok(class_map) = self.class_map.read() else {
+                return WorkspaceSymbolResponse::Nested(vec![]);
+            };
+            index
+                .search(&params.query, LIMIT)
+                .into_iter()
+                .map(|c| {
+                    (
+                        c.search_name().to_string(),
+                        c.kind,
+                        c.deprecated,
+                        class_map.get(&c.class_path).cloned(),
+                    )
+                })
+                .collect()
+        };
+        let resolve = self.workspace_symbol_resolve_support();
+        let symbols = found
+            .into_iter()
+            .filter_map(|(name, kind, deprecated, class)| {
+                let class = class?;
+                // Without resolve support the range is needed now
+                let location = match class.get_source() {
+                    Some(source) if resolve => OneOf::Right(WorkspaceLocation {
+                        uri: source_to_uri(&source).ok()?,
+                    }),
+                    _ => OneOf::Left(
+                        definition::class_location(&class, &self.class_map, &self.document_map)
+                            .ok()?,
+                    ),
+                };
+                let package = class
+                    .class_path
+                    .rsplit_once('.')
+                    .map(|(p, _)| p.to_string());
+                Some(WorkspaceSymbol {
+                    name,
+                    kind,
+                    tags: deprecated.then(|| vec![SymbolTag::DEPRECATED]),
+                    container_name: package,
+                    location,
+                    data: Some(Value::String(class.class_path.to_string())),
+                })
+            })
+            .collect();
+        WorkspaceSymbolResponse::Nested(symbols)
+    }
+
+    /// Add the range of the declaration to a symbol of `workspace/symbol`
+    #[must_use]
+    pub fn workspace_symbol_resolve(&self, mut symbol: WorkspaceSymbol) -> WorkspaceSymbol {
+        let class = symbol
+            .data
+            .as_ref()
+            .and_then(Value::as_str)
+            .and_then(|class_path| self.class_map.read().ok()?.get(class_path).cloned());
+        if let Some(class) = class {
+            match definition::class_location(&class, &self.class_map, &self.document_map) {
+                Ok(location) => symbol.location = OneOf::Left(location),
+                Err(e) => tracing::debug!("Unable to resolve symbol {}: {e:?}", symbol.name),
+            }
+        }
+        symbol
+    }
+
+    /// The client resolves the range of the locations of `workspace/symbol` later
+    fn workspace_symbol_resolve_support(&self) -> bool {
+        self.client_capabilities
+            .as_ref()
+            .as_ref()
+            .and_then(|c| c.workspace.as_ref())
+            .and_then(|w| w.symbol.as_ref())
+            .and_then(|s| s.resolve_support.as_ref())
+            .is_some_and(|r| r.properties.iter().any(|p| p == "location.range"))
+    }
+
     pub fn signature_help(&self, params: SignatureHelpParams) -> Option<SignatureHelp> {
-        if !params
-            .text_document_position_params
-            .text_document
-            .uri
-            .path()
-            .as_str()
-            .to_lowercase()
-            .ends_with(".java")
-        {
+        if !self.is_java(&params.text_document_position_params.text_document.uri) {
             return None;
         }
         let uri = params.text_document_position_params.text_document.uri;
         let document = self.get_document(&uri)?;
 
         let point = to_ast_point(params.text_document_position_params.position);
-        let class = self.get_class(&document.ast)?;
+        let class = self.get_class(&uri, &document.ast)?;
 
         match signature::signature_driver(&document, &point, &class, &self.class_map) {
             Ok(hover) => Some(hover),
             Err(e) => {
-                eprintln!("Error while signature_help: {e:?}");
+                tracing::debug!("Error while signature_help: {e:?}");
                 None
             }
         }
@@ -1033,17 +2076,338 @@ impl Backend {
             }
             COMMAND_CMD => {
                 if let Err(e) = command::cmd(&self.connection, &params.arguments, progress) {
-                    eprintln!("Got error in Command: {e:?}");
+                    tracing::error!("Got error in Command: {e:?}");
                 }
                 None
             }
+            COMMAND_COMPILE => {
+                self.build_compile(&params.arguments);
+                None
+            }
+            junit::COMMAND_RUN_TEST => {
+                self.run_test(&params.arguments);
+                None
+            }
+            debug::COMMAND_DEBUG_CONFIG => self.debug_config(&params.arguments),
+            main_class::COMMAND_MAIN_CLASSES => {
+                serde_json::to_value(main_class::main_classes(&self.class_map)).ok()
+            }
+            main_class::COMMAND_RUN_MAIN => {
+                self.run_main(&params.arguments);
+                None
+            }
+            import_rank::COMMAND_IMPORT_CHOSEN => {
+                self.import_chosen(&params.arguments);
+                None
+            }
             u => {
-                eprintln!("Unhandled command: {u}");
+                tracing::debug!("Unhandled command: {u}");
+                None
+            }
+        }
+    }
+
+    /// Arguments are the position of the type and the package to move it to. Without a package
+    /// the packages it can be moved to are returned, with one the edit is sent to the client
+    #[must_use]
+    pub fn move_class(&self, arguments: &[Value]) -> Option<Value> {
+        let params =
+            serde_json::from_value::<TextDocumentPositionParams>(arguments.first()?.clone())
+                .ok()?;
+        let uri = &params.text_document.uri;
+        let document = self.get_document(uri)?;
+        let point = to_ast_point(params.position);
+        let Some(Value::String(package)) = arguments.get(1) else {
+            let packages = refactor::move_class_packages(&document, &point, uri, &self.class_map);
+            return serde_json::to_value(packages).ok();
+        };
+        let edit = match refactor::move_class_edit(
+            &document,
+            &point,
+            uri,
+            package,
+            &self.class_map,
+            &self.document_map,
+            &self.names,
+        ) {
+            Ok(Some(edit)) => edit,
+            Ok(None) => return None,
+            Err(e) => {
+                tracing::error!("Got error move class: {e:?}");
+                return None;
+            }
+        };
+        let params = serde_json::to_value(ApplyWorkspaceEditParams {
+            label: Some(format!("Move to package {package}")),
+            edit,
+        })
+        .ok()?;
+        let _ = self
+            .connection
+            .sender
+            .send(Message::Request(lsp_server::Request {
+                id: ApplyWorkspaceEdit::METHOD.to_string().into(),
+                method: ApplyWorkspaceEdit::METHOD.to_string(),
+                params,
+            }));
+        None
+    }
+
+    /// Dead code of the workspace, the entry point and reflection annotations are skipped
+    #[must_use]
+    pub fn dead_code(&self) -> Option<Value> {
+        let annotations: Vec<String> = self
+            .config
+            .entry_point_annotations
+            .iter()
+            .chain(&self.config.reflection_annotations)
+            .cloned()
+            .collect();
+        let dead = dead_code::dead_code(
+            &self.class_map,
+            &self.document_map,
+            &self.names,
+            &annotations,
+            &|| self.cancelled(),
+        );
+        serde_json::to_value(dead).ok()
+    }
+
+    /// Arguments are the name of a package or the uri and position of a method, and optionally
+    /// the format `dot`
+    #[must_use]
+    pub fn call_graph(&self, arguments: &[Value]) -> Option<Value> {
+        let graph = match arguments.first()? {
+            Value::String(package) => {
+                call_graph::package(package, &self.class_map, &self.document_map, &|| {
+                    self.cancelled()
+                })
+            }
+            position => {
+                let params =
+                    serde_json::from_value::<TextDocumentPositionParams>(position.clone()).ok()?;
+                let uri = &params.text_document.uri;
+                let document = self.get_document(uri)?;
+                let point = to_ast_point(params.position);
+                let imports = imports::imports(&document.ast);
+                let class = self.get_class(uri, &document.ast)?;
+                let vars = match variables::get_vars(
+                    &document.ast,
+                    &VariableContext {
+                        point: Some(point),
+                        imports: &imports,
+                        class: &class,
+                        class_map: self.class_map.clone(),
+                    },
+                ) {
+                    Ok(v) => Some(v),
+                    Err(e) => {
+                        tracing::debug!("Could not get vars: {e:?}");
+                        None
+                    }
+                }?;
+                let (class_path, name, _) =
+                    self.method_at(&document.ast, &point, &vars, &imports, &class)?;
+                call_graph::method(
+                    &class_path,
+                    &name,
+                    &self.class_map,
+                    &self.document_map,
+                    &|| self.cancelled(),
+                )
+            }
+        };
+        if arguments.get(1).and_then(Value::as_str) == Some("dot") {
+            return Some(Value::String(graph.to_dot()));
+        }
+        serde_json::to_value(graph).ok()
+    }
+
+    #[must_use]
+    pub fn status(&self) -> Status {
+        Status::collect(
+            &self.class_map,
+            &self.document_map,
+            &self.reference_map,
+            self.config.cache_memory,
+            self.jdk_release(),
+        )
+    }
+
+    /// Report the status as progress until the client goes away
+    pub async fn status_updates(self) {
+        Self::progress_start(&self.connection, STATUS_TASK);
+        let mut interval = tokio::time::interval(STATUS_INTERVAL);
+        loop {
+            interval.tick().await;
+            if !Self::progress_report(&self.connection, STATUS_TASK, self.status().summary()) {
+                return;
+            }
+        }
+    }
+
+    pub fn tests(&self, params: &junit::TestsParams) -> Vec<junit::TestClass> {
+        let file = params
+            .uri
+            .as_ref()
+            .map(|uri| PathBuf::from(get_document_map_key(uri).as_str()));
+        junit::tests(&self.class_map, file.as_deref())
+    }
+
+    /// Argument is optionally the uri of the changed file. The build tool compiles its module,
+    /// or all projects, in the background and the errors are published for every file.
+    fn build_compile(&self, arguments: &[Value]) {
+        let uri = arguments
+            .first()
+            .and_then(Value::as_str)
+            .and_then(|uri| Uri::from_str(uri).ok());
+        let projects: Vec<Project> = uri.as_ref().map_or_else(
+            || self.projects.read().map(|p| p.clone()).unwrap_or_default(),
+            |uri| self.get_project(uri).into_iter().collect(),
+        );
+        let file = uri.map(|uri| PathBuf::from(get_document_map_key(&uri).as_str()));
+        let con = self.connection.clone();
+        let error_files = self.error_files.clone();
+        let findings = self.findings.clone();
+        let publish = self.diagnostics_publisher();
+        tokio::task::spawn_blocking(move || {
+            let task = format!("Command: {COMMAND_COMPILE}");
+            let started = Instant::now();
+            Self::progress_start(&con, &task);
+            let errors: Vec<CompileErrorMessage> = projects
+                .iter()
+                .filter_map(|p| command::build_compile(p, file.as_deref()))
+                .flatten()
+                .collect();
+            let count = errors.len();
+            let Ok(mut error_files) = error_files.lock() else {
+                tracing::error!("error_files mutex poisoned");
+                return;
+            };
+            let mut emap = HashMap::<String, Vec<Diagnostic>>::new();
+            for e in errors {
+                emap.entry(e.path.clone())
+                    .or_default()
+                    .push(javac::diagnostic(&e));
+            }
+            // Files of the last compile that have no errors anymore are cleared
+            let paths: HashSet<String> = error_files.drain().chain(emap.keys().cloned()).collect();
+            for path in paths {
+                let Ok(uri) = source_to_uri(&path) else {
+                    continue;
+                };
+                let diagnostics = emap.get(&path).cloned().unwrap_or_default();
+                publish(uri.clone(), findings.merge(&uri, diagnostics));
+            }
+            error_files.extend(emap.into_keys());
+            drop(error_files);
+            let duration = started.elapsed().as_secs_f32();
+            Self::progress_end(
+                &con,
+                &task,
+                Some(format!("{count} errors in {duration:.1}s")),
+            );
+        });
+    }
+
+    /// Arguments are the class path of the test class and optionally the name of a method
+    fn run_test(&self, arguments: &[Value]) {
+        let Some(class_path) = arguments.first().and_then(Value::as_str) else {
+            return;
+        };
+        let method = arguments.get(1).and_then(Value::as_str);
+        let Ok(projects) = self.projects.read().map(|p| p.clone()) else {
+            return;
+        };
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        if let Err(e) = junit::run_test(
+            &self.connection,
+            &self.class_map,
+            &projects,
+            self.config.junit_launcher.as_deref(),
+            self.config.java_path(path),
+            class_path,
+            method,
+        ) {
+            tracing::error!("Failed to run test {class_path}: {e:?}");
+        }
+    }
+
+    /// Argument is optionally the uri of a file, only the graph of its project is loaded
+    pub fn dependency_tree(&self, arguments: &[Value]) -> Option<Value> {
+        let projects = match arguments
+            .first()
+            .and_then(Value::as_str)
+            .and_then(|uri| Uri::from_str(uri).ok())
+        {
+            Some(uri) => vec![self.get_project(&uri)?],
+            None => self.projects.read().ok()?.clone(),
+        };
+        serde_json::to_value(command::dependency_tree(&projects)).ok()
+    }
+
+    /// Arguments are the class path of the main class or the uri of its file and optionally
+    /// `{ "start": true }`
+    fn debug_config(&self, arguments: &[Value]) -> Option<Value> {
+        let target = main_class_target(arguments.first()?)?;
+        let target = target.as_str();
+        let projects = self.projects.read().ok()?.clone();
+        let (config, release) = match main_class::resolve(&self.class_map, &projects, target) {
+            Ok(target) => (debug::launch_config(&target), target.javac.level.release),
+            Err(e) => {
+                tracing::error!("No debug configuration for {target}: {e:?}");
+                return None;
+            }
+        };
+        if !debug::start_requested(arguments.get(1)) {
+            return serde_json::to_value(config).ok();
+        }
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let java_path = jdk::installations::java_path(release, self.config.java_path(path));
+        match debug::start(&config, java_path) {
+            Ok(attach) => serde_json::to_value(attach).ok(),
+            Err(e) => {
+                tracing::error!("Failed to start {target} for debugging: {e:?}");
                 None
             }
         }
     }
 
+    /// Argument is the class path of the main class or the uri of its file
+    fn run_main(&self, arguments: &[Value]) {
+        let Some(target) = arguments.first().and_then(main_class_target) else {
+            return;
+        };
+        let Ok(projects) = self.projects.read().map(|p| p.clone()) else {
+            return;
+        };
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        if let Err(e) = main_class::run_main(
+            &self.connection,
+            &self.class_map,
+            &projects,
+            self.config.java_path(path),
+            &target,
+        ) {
+            tracing::error!("Failed to run {target}: {e:?}");
+        }
+    }
+
+    /// Arguments are the uri of the file and the imported class path
+    fn import_chosen(&self, arguments: &[Value]) {
+        let [Value::String(uri), Value::String(class_path), ..] = arguments else {
+            return;
+        };
+        let Some(project) = Uri::from_str(uri)
+            .ok()
+            .and_then(|uri| self.get_project(&uri))
+        else {
+            return;
+        };
+        self.import_choices
+            .choose(Path::new(&project.dir), class_path);
+    }
+
     pub fn code_lens(&self, params: CodeLensParams) -> Vec<CodeLens> {
         let uri = params.text_document.uri;
         let file = uri.path().as_str().to_lowercase();
@@ -1061,17 +2425,15 @@ impl Backend {
         match code_lens::tests(&document.ast, &file, &project.kind, &self.config, &mut out) {
             Ok(()) | Err(CodeLensError::SkipFile) => (),
             Err(e) => {
-                eprintln!("Got error running CodeLens: {e:?}");
+                tracing::debug!("Got error running CodeLens: {e:?}");
             }
         }
         out
     }
     pub fn folding_range(&self, params: lsp_types::FoldingRangeParams) -> Vec<FoldingRange> {
         let uri = params.text_document.uri;
-        let file = uri.path().as_str().to_lowercase();
         let mut out = Vec::new();
-        #[allow(clippy::case_sensitive_file_extension_comparisons)]
-        if !file.ends_with(".java") {
+        if !self.is_java(&uri) {
             return out;
         }
         let Some(document) = self.get_document(&uri) else {
@@ -1080,7 +2442,7 @@ impl Backend {
         match folding_range::fold(&document.ast, &mut out) {
             Ok(()) => (),
             Err(e) => {
-                eprintln!("Got error running FoldingRange: {e:?}");
+                tracing::debug!("Got error running FoldingRange: {e:?}");
             }
         }
         out
@@ -1090,6 +2452,222 @@ impl Backend {
         self.fill_projects(Some(params.event.added.clone()));
     }
 
+    /// Ask the client to watch sources, build files and jars when it supports dynamic
+    /// registration
+    pub fn register_file_watchers(&self) {
+        let dynamic_registration = self
+            .client_capabilities
+            .as_ref()
+            .as_ref()
+            .and_then(|c| c.workspace.as_ref())
+            .and_then(|w| w.did_change_watched_files.as_ref())
+            .and_then(|d| d.dynamic_registration)
+            .unwrap_or_default();
+        if !dynamic_registration {
+            return;
+        }
+        let Ok(params) = serde_json::to_value(RegistrationParams {
+            registrations: vec![watched_files::registration()],
+        }) else {
+            return;
+        };
+        let _ = self
+            .connection
+            .sender
+            .send(Message::Request(lsp_server::Request {
+                id: RegisterCapability::METHOD.to_string().into(),
+                method: RegisterCapability::METHOD.to_string(),
+                params,
+            }));
+    }
+
+    /// Sources that are not open are reparsed and class files of the build output are loaded.
+    /// Changed build files and jars invalidate the cached classes of the affected projects,
+    /// which are then loaded again.
+    pub fn did_change_watched_files(&mut self, params: &DidChangeWatchedFilesParams) {
+        let mut reload: Vec<Project> = Vec::new();
+        let mut jars = false;
+        let mut settings = false;
+        for change in &params.changes {
+            let path = get_document_map_key(&change.uri);
+            let file = PathBuf::from(path.as_str());
+            match watched_files::watched_kind(&file) {
+                Some(WatchedKind::Java) => self.reload_java_file(&path, &file, change.typ),
+                Some(WatchedKind::Class) => self.reload_class_file(&file, change.typ),
+                Some(WatchedKind::Build) => {
+                    if let Some(project) = self.get_project(&change.uri)
+                        && !reload.iter().any(|p| p.dir == project.dir)
+                    {
+                        reload.push(project);
+                    }
+                }
+                Some(WatchedKind::Jar) => {
+                    if let Some(dep) = watched_files::jar_dependency(&file) {
+                        let cfc = deps_get_cfc(&deps_base(&dep, &deps_dir()), &dep);
+                        if let Err(e) = fs::remove_file(&cfc)
+                            && e.kind() != std::io::ErrorKind::NotFound
+                        {
+                            tracing::error!("Unable to remove {}: {e:?}", cfc.display());
+                        }
+                    }
+                    jars = true;
+                }
+                Some(WatchedKind::Settings) => settings = true,
+                None => (),
+            }
+        }
+        if jars && let Ok(projects) = self.projects.read() {
+            for project in projects.iter() {
+                if !reload.iter().any(|p| p.dir == project.dir) {
+                    reload.push(project.clone());
+                }
+            }
+        }
+        if !reload.is_empty() {
+            self.reload_projects(reload);
+        }
+        if settings {
+            self.reload_project_config();
+        }
+    }
+
+    fn reload_java_file(&self, path: &MyString, file: &Path, typ: FileChangeType) {
+        let source = SourceDestination::Here(path.clone());
+        if typ == FileChangeType::DELETED {
+            if let Ok(mut class_map) = self.class_map.write() {
+                class_map.retain(|_, class| class.source != source);
+            } else {
+                tracing::error!("class_map mutex poisoned");
+            }
+            self.symbols.invalidate();
+            self.subtypes.invalidate();
+            self.names.remove(path);
+            return;
+        }
+        // Open documents are kept up to date by the client
+        if self
+            .document_map
+            .read()
+            .is_ok_and(|dm| dm.contains_key(path))
+        {
+            return;
+        }
+        self.names.remove(path);
+        let class = match loader::load_java_fs(file, source) {
+            Ok(class) => class,
+            Err(e) => {
+                tracing::error!("Unable to load changed file {}: {e:?}", file.display());
+                return;
+            }
+        };
+        if let Err(e) =
+            references::reference_update_class(&class, &self.class_map, &self.reference_map)
+        {
+            tracing::warn!("Got reference error: {e:?}");
+        }
+        if let Ok(mut class_map) = self.class_map.write() {
+            class_map.insert(class.class_path.clone(), class.clone());
+        } else {
+            tracing::error!("class_map mutex poisoned");
+        }
+        self.queries.classes_changed();
+        self.subtypes.update(&class, &self.class_map);
+    }
+
+    /// Classes of the build output that have a source in the workspace are kept, the class file
+    /// of a generated or separately compiled class replaces its entry.
+    fn reload_class_file(&self, file: &Path, typ: FileChangeType) {
+        let Some(class_path) = watched_files::output_class_path(file) else {
+            return;
+        };
+        let has_source = |class_map: &HashMap<MyString, Class>| {
+            class_map
+                .get(&class_path)
+                .is_some_and(|c| matches!(c.source, SourceDestination::Here(_)))
+        };
+        if typ == FileChangeType::DELETED {
+            if let Ok(mut class_map) = self.class_map.write()
+                && !has_source(&class_map)
+                && class_map.remove(&class_path).is_some()
+            {
+                drop(class_map);
+                self.symbols.invalidate();
+                self.subtypes.invalidate();
+            }
+            return;
+        }
+        if self.class_map.read().is_ok_and(|cm| has_source(&cm)) {
+            return;
+        }
+        let class =
+            match loader::load_class_fs(file, SourceDestination::None, class_path.clone(), true) {
+                Ok(class) => class,
+                Err(LoaderError::ClassParser(
+                    ClassParserError::Ignoring | ClassParserError::NotAClass,
+                )) => return,
+                Err(e) => {
+                    tracing::error!("Unable to load changed class {}: {e:?}", file.display());
+                    return;
+                }
+            };
+        if let Ok(mut class_map) = self.class_map.write() {
+            class_map.insert(class_path, class.clone());
+        } else {
+            tracing::error!("class_map mutex poisoned");
+        }
+        self.queries.classes_changed();
+        self.subtypes.update(&class, &self.class_map);
+    }
+
+    /// Drop the cached classes of the projects and load their dependencies again
+    fn reload_projects(&self, reload: Vec<Project>) {
+        let cache_dir = project_cache_dir();
+        let reload: Vec<Project> = reload
+            .into_iter()
+            .filter_map(|project| {
+                let dir = PathBuf::from(&project.dir);
+                let cache_path = match project.kind {
+                    ProjectKind::Maven { .. } => Some(get_maven_cache_path(&dir, &cache_dir)),
+                    ProjectKind::Gradle { .. } => Some(get_gradle_cache_path(&dir, &cache_dir)),
+                    ProjectKind::Eclipse { .. } => Some(get_eclipse_cache_path(&dir, &cache_dir)),
+                    ProjectKind::Bazel { .. } => {
+                        self.bazel_targets.clear();
+                        return None;
+                    }
+                    ProjectKind::Bsp { .. } => {
+                        self.bsp_servers.clear();
+                        return None;
+                    }
+                    // The jars of a classpath file are compared with their last load
+                    ProjectKind::Unknown if classpath_config_path(&dir).is_some() => None,
+                    ProjectKind::Unknown => return None,
+                };
+                if let Some(cache_path) = cache_path {
+                    let _ = fs::remove_file(cache_path);
+                }
+                project_kind_to_project(&project.dir, project.kind)
+            })
+            .collect();
+        let project_artifacts = self.projects.write().map_or_else(
+            |_| Arc::new(Vec::new()),
+            |mut projects| {
+                for project in &reload {
+                    if let Some(p) = projects.iter_mut().find(|p| p.dir == project.dir) {
+                        p.clone_from(project);
+                    }
+                }
+                get_project_artifacts(&projects)
+            },
+        );
+        command::reload_projects(
+            self.connection.clone(),
+            None,
+            self.class_map.clone(),
+            reload,
+            project_artifacts,
+        );
+    }
+
     pub fn document_link(&self, params: DocumentLinkParams) -> Option<Vec<DocumentLink>> {
         let uri = params.text_document.uri;
         if !uri.path().as_str().to_lowercase().ends_with(".java") {
@@ -1100,20 +2678,92 @@ impl Backend {
     }
 
     pub fn inlay_hint(&self, params: InlayHintParams) -> Option<Vec<InlayHint>> {
+        if !self.config.inlay_hints && !self.config.lambda_parameter_hints {
+            return None;
+        }
         let uri = params.text_document.uri;
-        if !uri.path().as_str().to_lowercase().ends_with(".java") {
+        if !self.is_java(&uri) {
             return None;
         }
         let document = self.get_document(&uri)?;
-        let class = self.get_class(&document.ast)?;
+        let class = self.get_class(&uri, &document.ast)?;
         let imports = imports::imports(&document.ast);
 
-        get_inlay_hint(&document, &class, &imports, self.class_map.clone())
+        get_inlay_hint(&document, &class, &imports, &self.class_map, &self.config)
+    }
+
+    #[must_use]
+    pub fn document_diagnostic(
+        &self,
+        params: &DocumentDiagnosticParams,
+    ) -> DocumentDiagnosticReportResult {
+        let uri = &params.text_document.uri;
+        let Ok(mut reports) = self.diagnostic_reports.lock() else {
+            tracing::error!("diagnostic_reports mutex poisoned");
+            return DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(
+                RelatedFullDocumentDiagnosticReport::default(),
+            ));
+        };
+        if !reports.contains(uri)
+            && let Some(document) = self.get_document(uri)
+        {
+            let diagnostics = self.queries.diagnostics(
+                &get_document_map_key(uri),
+                &document,
+                uri,
+                &self.class_map,
+                &self.config,
+                self.language_level(uri),
+            );
+            reports.update(uri.clone(), self.findings.merge(uri, diagnostics));
+        }
+        DocumentDiagnosticReportResult::Report(
+            reports.document(uri, params.previous_result_id.as_deref()),
+        )
+    }
+
+    #[must_use]
+    pub fn workspace_diagnostic(
+        &self,
+        params: &WorkspaceDiagnosticParams,
+    ) -> WorkspaceDiagnosticReportResult {
+        let Ok(reports) = self.diagnostic_reports.lock() else {
+            tracing::error!("diagnostic_reports mutex poisoned");
+            return WorkspaceDiagnosticReportResult::Report(WorkspaceDiagnosticReport::default());
+        };
+        let items = reports.workspace(&params.previous_result_ids);
+        drop(reports);
+        let Some(token) = &params.partial_result_params.partial_result_token else {
+            return WorkspaceDiagnosticReportResult::Report(WorkspaceDiagnosticReport { items });
+        };
+        // Stream the reports, the final response is empty
+        for item in items {
+            if self.cancelled() {
+                break;
+            }
+            let partial = WorkspaceDiagnosticReportPartialResult { items: vec![item] };
+            if let Ok(params) = serde_json::to_value(serde_json::json!({
+                "token": token,
+                "value": partial,
+            })) {
+                let _ =
+                    self.connection
+                        .sender
+                        .send(Message::Notification(lsp_server::Notification {
+                            method: Progress::METHOD.to_string(),
+                            params,
+                        }));
+            }
+        }
+        WorkspaceDiagnosticReportResult::Report(WorkspaceDiagnosticReport { items: vec![] })
     }
 
-    fn get_class(&self, ast: &AstFile) -> Option<Class> {
+    fn get_class(&self, uri: &Uri, ast: &AstFile) -> Option<Class> {
+        if scratch::is_scratch(uri) {
+            return Some(scratch::class(uri, ast));
+        }
         let Some(class_path) = get_class_path(ast) else {
-            eprintln!("Could not get class_path");
+            tracing::debug!("Could not get class_path");
             return None;
         };
         let class;
@@ -1122,30 +2772,210 @@ impl Backend {
         {
             class = cl.clone();
         } else {
-            eprintln!("Could not find class {class_path}");
+            tracing::debug!("Could not find class {class_path}");
             return None;
         }
         Some(class)
     }
 
+    /// Java files and the scratch documents that were opened as java
+    fn is_java(&self, uri: &Uri) -> bool {
+        if scratch::is_scratch(uri) {
+            return self
+                .document_map
+                .read()
+                .is_ok_and(|dm| dm.contains_key(&scratch::key(uri)));
+        }
+        uri.path().as_str().to_lowercase().ends_with(".java")
+    }
+
     fn get_document(&self, uri: &Uri) -> Option<Document> {
         if let Ok(dm) = self.document_map.read()
             && let Some(doc) = dm.get(&get_document_map_key(uri))
         {
             Some(doc.clone())
         } else {
-            eprintln!("Document is not opened.");
+            tracing::debug!("Document is not opened.");
             None
         }
     }
 
-    pub fn fill_config(&mut self, initialization_options: Option<Value>) {
-        let Some(Value::Object(init)) = initialization_options else {
-            return;
-        };
+    /// Remember the settings of the client and merge them again, see [`Self::merge_config`]
+    pub fn fill_config(&mut self, settings: Option<Value>) {
+        if let Some(Value::Object(settings)) = settings {
+            self.client_settings.extend(settings);
+        }
+        self.merge_config();
+    }
+
+    /// The defaults, then the `java_lsp.toml` of the workspace root, then the client settings
+    fn merge_config(&mut self) {
+        let mut config = Configuration::default();
+        if let Some(root) = self.workspace_root() {
+            let project_config = self
+                .project_configs
+                .lock()
+                .map(|mut configs| configs.get(&root))
+                .unwrap_or_default();
+            project_config.apply(&root, &mut config);
+        }
+        self.config = config;
+        let settings = std::mem::take(&mut self.client_settings);
+        self.fill_client_settings(&settings);
+        self.client_settings = settings;
+        loader::set_excluded_dirs(self.config.excluded_dirs.clone());
+        self.discover_jdks();
+    }
+
+    /// Find the installed JDKs for the releases of the modules, see [`jdk::installations`]
+    fn discover_jdks(&self) {
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let toolchains = maven::m2::get_maven_m2_folder()
+            .ok()
+            .and_then(|m2| maven::toolchains::load_toolchains_xml(&m2).ok())
+            .map(|toolchains| toolchains.jdk_homes())
+            .unwrap_or_default();
+        let installs =
+            jdk::installations::discover(self.config.jdk_path.as_deref(), &path, toolchains);
+        tracing::debug!("Found {} JDKs", installs.len());
+    }
+
+    /// Release the JDK of the index is selected for, the setting or the newest release of the
+    /// projects and their modules
+    #[must_use]
+    pub fn jdk_release(&self) -> Option<u32> {
+        if self.config.java_release.is_some() {
+            return self.config.java_release;
+        }
+        let projects = self.projects.read().ok()?;
+        projects
+            .iter()
+            .flat_map(|p| {
+                std::iter::once(p.language_level.release)
+                    .chain(p.modules.iter().map(|m| m.language_level().release))
+            })
+            .flatten()
+            .max()
+    }
+
+    /// PATH to index the classes of the JDK with
+    #[must_use]
+    pub fn index_java_path(&self, path: OsString) -> OsString {
+        jdk::installations::java_path(self.jdk_release(), self.config.java_path(path))
+    }
+
+    /// Folder of the first workspace project
+    fn workspace_root(&self) -> Option<PathBuf> {
+        let projects = self.projects.read().ok()?;
+        projects.first().map(|p| PathBuf::from(&p.dir))
+    }
+
+    fn fill_client_settings(&mut self, init: &serde_json::Map<String, Value>) {
         if let Some(Value::Bool(editor_runs_commands)) = init.get("editor_runs_commands") {
             self.config.editor_runs_commands = *editor_runs_commands;
         }
+        if let Some(width) = init.get("format_line_width").and_then(Value::as_u64) {
+            self.config.format_line_width = usize::try_from(width).unwrap_or(usize::MAX);
+        }
+        if let Some(Value::Bool(javac_diagnostics)) = init.get("javac_diagnostics") {
+            self.config.javac_diagnostics = *javac_diagnostics;
+        }
+        if let Some(Value::Array(annotations)) = init.get("reflection_annotations") {
+            self.config.reflection_annotations = annotations
+                .iter()
+                .filter_map(Value::as_str)
+                .map(ToString::to_string)
+                .collect();
+        }
+        if let Some(Value::Array(annotations)) = init.get("entry_point_annotations") {
+            self.config.entry_point_annotations = annotations
+                .iter()
+                .filter_map(Value::as_str)
+                .map(ToString::to_string)
+                .collect();
+        }
+        if let Some(Value::Array(annotations)) = init.get("nullable_annotations") {
+            self.config.nullable_annotations = annotations
+                .iter()
+                .filter_map(Value::as_str)
+                .map(ToString::to_string)
+                .collect();
+        }
+        if let Some(Value::Array(annotations)) = init.get("not_null_annotations") {
+            self.config.not_null_annotations = annotations
+                .iter()
+                .filter_map(Value::as_str)
+                .map(ToString::to_string)
+                .collect();
+        }
+        if let Some(levels) = init.get("diagnostics") {
+            self.fill_diagnostic_levels(levels);
+        }
+        if let Some(Value::Array(order)) = init.get("import_order") {
+            self.config.import_order = order
+                .iter()
+                .filter_map(Value::as_str)
+                .map(ToString::to_string)
+                .collect();
+        }
+        match init.get("jdk_path") {
+            Some(Value::String(jdk_path)) if !jdk_path.is_empty() => {
+                self.config.jdk_path = Some(PathBuf::from(jdk_path));
+            }
+            Some(Value::String(_) | Value::Null) => self.config.jdk_path = None,
+            _ => {}
+        }
+        if let Some(levels) = init.get("log") {
+            logging::set_levels(LogLevels::parse(levels));
+        }
+        match init.get("java_release") {
+            Some(Value::Number(release)) => {
+                self.config.java_release = release.as_u64().and_then(|r| u32::try_from(r).ok());
+            }
+            Some(Value::String(release)) => self.config.java_release = release.parse().ok(),
+            Some(Value::Null) => self.config.java_release = None,
+            _ => {}
+        }
+        if let Some(Value::Bool(inlay_hints)) = init.get("inlay_hints") {
+            self.config.inlay_hints = *inlay_hints;
+        }
+        if let Some(Value::Bool(lambda_parameter_hints)) = init.get("lambda_parameter_hints") {
+            self.config.lambda_parameter_hints = *lambda_parameter_hints;
+        }
+        if let Some(Value::Bool(include_overrides)) = init.get("references_include_overrides") {
+            self.config.references_include_overrides = *include_overrides;
+        }
+        if let Some(Value::Bool(status_updates)) = init.get("status_updates") {
+            self.config.status_updates = *status_updates;
+        }
+        if let Some(Value::Bool(download)) = init.get("download_dependencies") {
+            self.config.download_dependencies = *download;
+            update::set_download_dependencies(*download);
+        }
+        if let Some(Value::Bool(class_file_contents)) = init.get("class_file_contents") {
+            self.config.class_file_contents = *class_file_contents;
+        }
+        if let Some(Value::Object(pmd)) = init.get("pmd") {
+            fill_analyzer_config(&mut self.config.pmd, pmd);
+        }
+        if let Some(Value::Object(spotbugs)) = init.get("spotbugs") {
+            fill_analyzer_config(&mut self.config.spotbugs, spotbugs);
+        }
+        match init.get("junit_launcher") {
+            Some(Value::String(launcher)) if !launcher.is_empty() => {
+                self.config.junit_launcher = Some(PathBuf::from(launcher));
+            }
+            Some(Value::String(_) | Value::Null) => self.config.junit_launcher = None,
+            _ => {}
+        }
+        if let Some(megabytes) = init.get("cache_memory_mb").and_then(Value::as_u64) {
+            self.config.cache_memory =
+                usize::try_from(megabytes.saturating_mul(1024 * 1024)).unwrap_or(usize::MAX);
+        }
+        if let Some(threads) = init.get("parse_threads").and_then(Value::as_u64) {
+            self.config.parse_threads = usize::try_from(threads).unwrap_or(usize::MAX);
+            loader::parallel::set_max_workers(self.config.parse_threads);
+        }
         if let Some(Value::String(formatter)) = init.get("formatter") {
             match formatter.to_lowercase().as_str() {
                 "none" => {
@@ -1161,10 +2991,187 @@ impl Backend {
                     self.config.formatter = FormatterConfig::Idea;
                 }
                 _ => {
-                    eprintln!("Only formatters none and google and idea are supported");
+                    tracing::warn!("Only formatters none and google and idea are supported");
+                }
+            }
+        }
+    }
+
+    /// Levels by diagnostic code, example: `{ "unused": "off", "unreachable": "warning" }`
+    fn fill_diagnostic_levels(&mut self, levels: &Value) {
+        let Value::Object(levels) = levels else {
+            return;
+        };
+        self.config
+            .diagnostic_levels
+            .extend(levels.iter().filter_map(|(code, level)| {
+                let Some(parsed) = level.as_str().and_then(DiagnosticLevel::parse) else {
+                    tracing::warn!("Unknown level {level} for diagnostic {code}");
+                    return None;
+                };
+                Some((code.clone(), parsed))
+            }));
+    }
+
+    /// Settings pushed with the notification are applied right away, clients that only notify
+    /// about the change are asked for the `java_lsp` section
+    pub fn did_change_configuration(&mut self, params: &DidChangeConfigurationParams) {
+        let settings = params.settings.get(SERVER_NAME).unwrap_or(&params.settings);
+        if settings.as_object().is_none_or(serde_json::Map::is_empty) {
+            self.request_configuration();
+            return;
+        }
+        self.apply_configuration(settings.clone());
+    }
+
+    /// Ask the client for the `java_lsp` settings section with `workspace/configuration`
+    pub fn request_configuration(&self) {
+        let supported = self
+            .client_capabilities
+            .as_ref()
+            .as_ref()
+            .and_then(|c| c.workspace.as_ref())
+            .and_then(|w| w.configuration)
+            .unwrap_or_default();
+        if !supported {
+            return;
+        }
+        let Ok(params) = serde_json::to_value(ConfigurationParams {
+            items: vec![ConfigurationItem {
+                scope_uri: None,
+                section: Some(SERVER_NAME.to_string()),
+            }],
+        }) else {
+            return;
+        };
+        let _ = self
+            .connection
+            .sender
+            .send(Message::Request(lsp_server::Request {
+                id: self.configuration_requests.start(),
+                method: WorkspaceConfiguration::METHOD.to_string(),
+                params,
+            }));
+    }
+
+    /// Response to [`Self::request_configuration`]
+    pub fn configuration_response(&mut self, response: lsp_server::Response) {
+        if let Some(error) = response.error {
+            tracing::error!("Unable to get configuration: {error:?}");
+            return;
+        }
+        let Some(Value::Array(mut sections)) = response.result else {
+            return;
+        };
+        if sections.is_empty() {
+            return;
+        }
+        self.apply_configuration(sections.swap_remove(0));
+    }
+
+    /// Update the configuration and redo the work that depends on the changed settings
+    pub fn apply_configuration(&mut self, settings: Value) {
+        let old = self.config.clone();
+        self.fill_config(Some(settings));
+        self.configuration_changed(&old);
+    }
+
+    /// Merge the settings again after a `java_lsp.toml` changed
+    pub fn reload_project_config(&mut self) {
+        let old = self.config.clone();
+        self.merge_config();
+        self.configuration_changed(&old);
+    }
+
+    fn configuration_changed(&self, old: &Configuration) {
+        if (old.jdk_path != self.config.jdk_path || old.java_release != self.config.java_release)
+            && let Some(path) = std::env::var_os("PATH")
+        {
+            tokio::spawn(Self::load_jdk(
+                self.connection.clone(),
+                self.class_map.clone(),
+                self.index_java_path(path),
+                self.config.java_release,
+            ));
+        }
+        if old.inlay_hints != self.config.inlay_hints
+            || old.lambda_parameter_hints != self.config.lambda_parameter_hints
+        {
+            self.refresh_inlay_hints();
+        }
+        for tool in Analyzer::ALL {
+            if tool.config(old).enabled && !tool.config(&self.config).enabled {
+                let publish = self.diagnostics_publisher();
+                for (uri, diagnostics) in self.findings.clear(tool) {
+                    publish(uri, diagnostics);
                 }
             }
         }
+        if old.diagnostic_levels != self.config.diagnostic_levels
+            || old.javac_diagnostics != self.config.javac_diagnostics
+            || old.reflection_annotations != self.config.reflection_annotations
+            || old.nullable_annotations != self.config.nullable_annotations
+            || old.not_null_annotations != self.config.not_null_annotations
+        {
+            self.rediagnose();
+        }
+        if old.excluded_dirs != self.config.excluded_dirs
+            && let Ok(projects) = self.projects.read()
+        {
+            self.reload_projects(projects.clone());
+        }
+    }
+
+    /// Compute the diagnostics of all open documents again
+    fn rediagnose(&self) {
+        self.queries.clear();
+        if self.pull_diagnostics() {
+            if let Ok(mut reports) = self.diagnostic_reports.lock() {
+                reports.clear();
+            }
+            self.refresh_diagnostics();
+            return;
+        }
+        let Ok(dm) = self.document_map.read() else {
+            tracing::error!("document_map mutex poisoned");
+            return;
+        };
+        for document in dm.values() {
+            if let Some(path) = document.path.to_str()
+                && let Ok(uri) = source_to_uri(path)
+            {
+                let diagnostics = diagnostics::diagnostics(
+                    document,
+                    &uri,
+                    &self.class_map,
+                    &self.config,
+                    self.language_level(&uri),
+                );
+                Self::send_diagnostic(&self.connection, uri, diagnostics);
+            }
+        }
+    }
+
+    /// Ask the client to request the inlay hints of all files again
+    fn refresh_inlay_hints(&self) {
+        let refresh_support = self
+            .client_capabilities
+            .as_ref()
+            .as_ref()
+            .and_then(|c| c.workspace.as_ref())
+            .and_then(|w| w.inlay_hint.as_ref())
+            .and_then(|i| i.refresh_support)
+            .unwrap_or_default();
+        if refresh_support {
+            let _ = self
+                .connection
+                .sender
+                .send(Message::Request(lsp_server::Request {
+                    id: InlayHintRefreshRequest::METHOD.to_string().into(),
+                    method: InlayHintRefreshRequest::METHOD.to_string(),
+                    params: Value::Null,
+                }));
+        }
     }
 
     pub fn open_log(con: &Connection, path: &str) {
@@ -1198,8 +3205,7 @@ impl Backend {
                 if projects.iter().any(|i| i.dir == dir) {
                     continue;
                 }
-                if let Ok(kind) =
-                    common::project_kind::get_project_kind(&PathBuf::from(&dir), &path)
+                if let Ok(kind) = get_project_kind(&PathBuf::from(&dir), &path)
                     && let Some(p) = project_kind_to_project(&dir, kind)
                 {
                     projects.push(p);
@@ -1208,6 +3214,13 @@ impl Backend {
         }
     }
 
+    /// Language level of the project of the file, the default when it is in no project
+    fn language_level(&self, uri: &Uri) -> LanguageLevel {
+        self.get_project(uri)
+            .map(|p| p.language_level(Path::new(&get_document_map_key(uri))))
+            .unwrap_or_default()
+    }
+
     fn get_project(&self, uri: &Uri) -> Option<Project> {
         let path = get_document_map_key(uri);
         let Ok(projects) = self.projects.read() else {
@@ -1222,35 +3235,77 @@ impl Backend {
     }
 }
 
+/// Artifacts of the projects and their modules, dependencies on them are resolved to their
+/// sources instead of jars
 #[must_use]
 pub fn get_project_artifacts(projs: &[Project]) -> Arc<Vec<String>> {
     Arc::new(
         projs
             .iter()
-            .map(|i| i.artifact_id.clone())
+            .flat_map(|i| {
+                std::iter::once(i.artifact_id.clone())
+                    .chain(i.modules.iter().map(|m| m.artifact_id.clone()))
+            })
             .collect::<Vec<_>>(),
     )
 }
 
+/// A connection file of a build server is used instead of the files of the build tool
+pub fn get_project_kind(
+    project_dir: &PathBuf,
+    path: &OsString,
+) -> Result<ProjectKind, ProjectKindError> {
+    if let Some(path_connection) = bsp::connection::find_connection(project_dir) {
+        return Ok(ProjectKind::Bsp { path_connection });
+    }
+    common::project_kind::get_project_kind(project_dir, path)
+}
+
 #[must_use]
 pub fn project_kind_to_project(dir: &str, kind: ProjectKind) -> Option<Project> {
     match kind {
         ProjectKind::Maven { .. } => {
             if let Ok(pom) = load_pom_xml(PathBuf::from(dir).as_path()) {
+                let modules = modules::load_modules(Path::new(dir));
                 Some(Project {
                     artifact_id: pom.artifact_id,
                     dir: dir.to_string(),
                     kind,
+                    language_level: modules
+                        .first()
+                        .map(MavenModule::language_level)
+                        .unwrap_or_default(),
+                    modules,
                 })
             } else {
                 None
             }
         }
-        ProjectKind::Gradle { .. } | ProjectKind::Unknown => Some(Project {
-            artifact_id: String::from("default"),
+        ProjectKind::Eclipse { .. } => Some(Project {
+            artifact_id: eclipse::classpath::load_project_name(Path::new(dir))
+                .unwrap_or_else(|_| String::from("default")),
             dir: dir.to_string(),
             kind,
+            modules: Vec::new(),
+            language_level: eclipse::preferences::language_level(Path::new(dir)),
         }),
+        ProjectKind::Gradle { .. }
+        | ProjectKind::Bazel { .. }
+        | ProjectKind::Bsp { .. }
+        | ProjectKind::Unknown => {
+            let language_level = match kind {
+                ProjectKind::Gradle { .. } => gradle::project::language_level(Path::new(dir)),
+                ProjectKind::Unknown => plain_project::language_level(Path::new(dir)),
+                _ => LanguageLevel::default(),
+            };
+            Some(Project {
+                artifact_id: String::from("default"),
+                dir: dir.to_string(),
+                kind,
+                modules: Vec::new(),
+                language_level,
+            })
+        }
     }
 }
 
@@ -1264,27 +3319,45 @@ fn snippet_completion(name: &str, content: &str) -> CompletionItem {
     }
 }
 
-fn compile_error_to_diagnostic(e: &CompileErrorMessage) -> Diagnostic {
-    let r = u32::try_from(e.row).unwrap_or_default();
-    let c = u32::try_from(e.col).unwrap_or_default();
-    let p = Position::new(r.saturating_sub(1), c);
-    Diagnostic::new(
-        Range::new(p, p),
-        Some(DiagnosticSeverity::ERROR),
-        None,
-        Some(String::from(SERVER_NAME)),
-        e.message.clone(),
-        None,
-        None,
-    )
+/// Settings of an analyzer, example: `{ "enabled": true, "executable": "/opt/pmd/bin/pmd" }`
+fn fill_analyzer_config(config: &mut AnalyzerConfig, settings: &serde_json::Map<String, Value>) {
+    if let Some(Value::Bool(enabled)) = settings.get("enabled") {
+        config.enabled = *enabled;
+    }
+    match settings.get("executable") {
+        Some(Value::String(executable)) if !executable.is_empty() => {
+            config.executable = Some(PathBuf::from(executable));
+        }
+        Some(Value::String(_) | Value::Null) => config.executable = None,
+        _ => {}
+    }
+    if let Some(Value::Array(rules)) = settings.get("rules") {
+        config.rules = rules
+            .iter()
+            .filter_map(Value::as_str)
+            .map(ToString::to_string)
+            .collect();
+    }
 }
 
 #[cfg(not(target_os = "windows"))]
 pub fn get_document_map_key(uri: &Uri) -> MyString {
+    if let Some(source) = virtual_source(uri) {
+        return source;
+    }
+    if scratch::is_scratch(uri) {
+        return scratch::key(uri);
+    }
     uri.path().as_str().to_smolstr()
 }
 #[cfg(target_os = "windows")]
 pub fn get_document_map_key(uri: &Uri) -> MyString {
+    if let Some(source) = virtual_source(uri) {
+        return source;
+    }
+    if scratch::is_scratch(uri) {
+        return scratch::key(uri);
+    }
     uri.path()
         .as_str()
         // remove leading slash
@@ -1294,6 +3367,34 @@ pub fn get_document_map_key(uri: &Uri) -> MyString {
         .to_smolstr()
 }
 
+/// Documents of `jdt` uris are the sources in the cache folder
+fn virtual_source(uri: &Uri) -> Option<MyString> {
+    if !class_contents::is_virtual(uri) {
+        return None;
+    }
+    class_contents::source_path(uri, &common::cache_dir_no_create())
+        .map(|source| source.to_string_lossy().to_smolstr())
+}
+
+/// Class path or the path of the file of a `file:` uri
+fn main_class_target(argument: &Value) -> Option<String> {
+    let target = argument.as_str()?;
+    Some(match Uri::from_str(target) {
+        Ok(uri) if target.starts_with("file:") => get_document_map_key(&uri).to_string(),
+        _ => target.to_string(),
+    })
+}
+
+/// Final progress message like "Indexed 1234 classes in 2.5s"
+#[must_use]
+pub fn progress_summary(classes: Option<usize>, started: Instant) -> String {
+    let duration = started.elapsed().as_secs_f32();
+    classes.map_or_else(
+        || format!("Done in {duration:.1}s"),
+        |classes| format!("Indexed {classes} classes in {duration:.1}s"),
+    )
+}
+
 pub async fn read_forward(
     mut rx: tokio::sync::watch::Receiver<TaskProgress>,
     con: Arc<Connection>,
@@ -1330,7 +3431,8 @@ pub async fn project_deps(
     let cache_path = match project_kind {
         ProjectKind::Maven { .. } => Some(get_maven_cache_path(project_dir, project_cache_dir)),
         ProjectKind::Gradle { .. } => Some(get_gradle_cache_path(project_dir, project_cache_dir)),
-        ProjectKind::Unknown => None,
+        ProjectKind::Eclipse { .. } => Some(get_eclipse_cache_path(project_dir, project_cache_dir)),
+        ProjectKind::Bazel { .. } | ProjectKind::Bsp { .. } | ProjectKind::Unknown => None,
     };
     if let Some(cache_path) = cache_path {
         match maven::project::project_deps(
@@ -1346,7 +3448,7 @@ pub async fn project_deps(
         {
             Ok(()) => (),
             Err(e) => {
-                eprintln!("Got error while loading maven project: {e:?}");
+                tracing::error!("Got error while loading maven project: {e:?}");
             }
         }
     }
@@ -1391,6 +3493,17 @@ pub async fn update_report(
                 None,
             ));
         }
+        MavenUpdateError::Checksum(url) => {
+            diagnostics.push(Diagnostic::new(
+                range,
+                Some(DiagnosticSeverity::ERROR),
+                None,
+                Some(String::from(SERVER_NAME)),
+                format!("Checksum of {url} does not match"),
+                None,
+                None,
+            ));
+        }
         MavenUpdateError::MTwo(mtwo_error) => {
             let message = format!("m2 error while update: {mtwo_error:?}");
             diagnostics.push(Diagnostic::new(
@@ -1417,7 +3530,8 @@ pub fn report_maven_gradle_diagnostic(
         ProjectKind::Gradle {
             path_build_gradle, ..
         } => Some(path_build_gradle.clone()),
-        ProjectKind::Unknown => None,
+        ProjectKind::Eclipse { path_classpath } => Some(path_classpath.clone()),
+        ProjectKind::Bazel { .. } | ProjectKind::Bsp { .. } | ProjectKind::Unknown => None,
     };
     if let Some(source) = source
         && let Ok(source) = fs::canonicalize(source)
@@ -1450,3 +3564,100 @@ fn format_lines_to_diagnostic(e: FormatLineError, formatter: String) -> Diagnost
         data: None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use lsp_server::Connection;
+
+    use super::*;
+
+    #[test]
+    fn javac_diagnostics_setting() {
+        let dir = std::env::temp_dir().join(format!("javac-diagnostics-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("Broken.java");
+        fs::write(&file, "public class Broken {\n    int a = \"text\";\n}\n").unwrap();
+        let path = file.to_str().unwrap();
+        let uri = source_to_uri(path).unwrap();
+        let (con, _client) = Connection::memory();
+        let mut backend = Backend::new(con);
+        backend.projects = Arc::new(RwLock::new(vec![Project {
+            artifact_id: String::new(),
+            dir: dir.to_str().unwrap().to_string(),
+            kind: ProjectKind::Unknown,
+            modules: vec![],
+            language_level: LanguageLevel::default(),
+        }]));
+        let compile = |backend: &Backend| {
+            let mut diagnostics = vec![];
+            backend.compile_project_file(&uri, path, &mut diagnostics);
+            diagnostics
+        };
+        let disabled = compile(&backend);
+        backend.config.javac_diagnostics = true;
+        let enabled = compile(&backend);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(disabled.is_empty());
+        assert_eq!(enabled.len(), 1);
+    }
+
+    #[test]
+    fn configuration_request_ids() {
+        let (con, client) = Connection::memory();
+        let mut backend = Backend::new(con);
+        backend.client_capabilities = Arc::new(Some(ClientCapabilities {
+            workspace: Some(lsp_types::WorkspaceClientCapabilities {
+                configuration: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }));
+        backend.request_configuration();
+        backend.request_configuration();
+        let ids: Vec<RequestId> = client
+            .receiver
+            .try_iter()
+            .filter_map(|message| match message {
+                Message::Request(request) => Some(request.id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert_ne!(ids[0], ids[1]);
+        assert!(backend.configuration_requests.answered(&ids[1]));
+        assert!(!backend.configuration_requests.answered(&ids[1]));
+        assert!(
+            !backend
+                .configuration_requests
+                .answered(&RequestId::from(WorkspaceConfiguration::METHOD.to_string()))
+        );
+        assert!(backend.configuration_requests.answered(&ids[0]));
+    }
+
+    #[test]
+    fn progress_summary_messages() {
+        let started = Instant::now()
+            .checked_sub(std::time::Duration::from_millis(2500))
+            .unwrap();
+        assert_eq!(
+            progress_summary(Some(1234), started),
+            "Indexed 1234 classes in 2.5s"
+        );
+        assert_eq!(progress_summary(None, started), "Done in 2.5s");
+    }
+
+    #[tokio::test]
+    async fn apply_configuration() {
+        let (con, _client) = Connection::memory();
+        let mut backend = Backend::new(con);
+        assert!(backend.config.inlay_hints);
+        backend.apply_configuration(serde_json::json!({
+            "import_order": ["java", "", "static"],
+            "jdk_path": "/opt/jdk-21",
+            "inlay_hints": false,
+        }));
+        assert_eq!(backend.config.import_order, ["java", "", "static"]);
+        assert_eq!(backend.config.jdk_path, Some(PathBuf::from("/opt/jdk-21")));
+        assert!(!backend.config.inlay_hints);
+    }
+}
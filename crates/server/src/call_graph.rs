@@ -0,0 +1,326 @@
+//! Static call graph of a method or a package
+use std::{
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+    fmt::Write,
+    sync::{Arc, RwLock},
+};
+
+use ast::{
+    types::{AstBlock, AstFile, AstThing, AstTopLevel},
+    visit::Visitor,
+};
+use document::{Document, read_document_or_open_class};
+use dto::{Class, SourceDestination};
+use my_string::MyString;
+use serde::Serialize;
+
+use crate::references::{self, MethodCalls};
+
+/// Arguments are the method position or package name and optionally the format
+pub const COMMAND_CALL_GRAPH: &str = "java_lsp.callGraph";
+
+/// Methods that a graph of a method follows at most
+const MAX_METHODS: usize = 500;
+
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub struct CallGraph {
+    /// `ch.emilycares.Foo#run`
+    pub nodes: BTreeSet<String>,
+    pub edges: BTreeSet<CallEdge>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CallEdge {
+    pub from: String,
+    pub to: String,
+}
+
+impl CallGraph {
+    fn add(&mut self, from: String, to: String) {
+        self.nodes.insert(from.clone());
+        self.nodes.insert(to.clone());
+        self.edges.insert(CallEdge { from, to });
+    }
+
+    /// Graphviz digraph of the calls
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph calls {\n");
+        for node in &self.nodes {
+            let _ = writeln!(out, "  \"{node}\";");
+        }
+        for edge in &self.edges {
+            let _ = writeln!(out, "  \"{}\" -> \"{}\";", edge.from, edge.to);
+        }
+        out.push('}');
+        out
+    }
+}
+
+/// Method declared in a source file
+struct Declaration<'a> {
+    class_path: MyString,
+    name: &'a str,
+    block: &'a AstBlock,
+}
+
+/// Calls of the method and of the workspace methods that it calls
+#[must_use]
+pub fn method(
+    class_path: &str,
+    name: &str,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+    cancelled: &dyn Fn() -> bool,
+) -> CallGraph {
+    let mut graph = CallGraph::default();
+    let mut queue = VecDeque::from([(MyString::from(class_path), MyString::from(name))]);
+    let mut visited = HashSet::new();
+    while let Some((class_path, name)) = queue.pop_front() {
+        if visited.len() >= MAX_METHODS || cancelled() {
+            break;
+        }
+        if !visited.insert((class_path.clone(), name.clone())) {
+            continue;
+        }
+        let Some(source) = workspace_source(&class_path, class_map) else {
+            continue;
+        };
+        let Ok(document) = read_document_or_open_class(&source, document_map) else {
+            continue;
+        };
+        let from = node(&class_path, &name);
+        graph.nodes.insert(from.clone());
+        for declaration in declarations(&document.ast)
+            .iter()
+            .filter(|d| d.class_path == class_path && d.name == name)
+        {
+            for (receiver, callee) in calls(&document.ast, declaration.block, class_map) {
+                graph.add(from.clone(), node(&receiver, &callee));
+                queue.push_back((receiver, callee));
+            }
+        }
+    }
+    graph
+}
+
+/// Calls of all methods of the classes in the package
+#[must_use]
+pub fn package(
+    package: &str,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+    cancelled: &dyn Fn() -> bool,
+) -> CallGraph {
+    let mut sources: Vec<MyString> = class_map.read().map_or_else(
+        |_| vec![],
+        |class_map| {
+            class_map
+                .values()
+                .filter(|class| {
+                    class
+                        .class_path
+                        .rsplit_once('.')
+                        .is_some_and(|(p, _)| p == package)
+                })
+                .filter_map(|class| match &class.source {
+                    SourceDestination::Here(source) => Some(source.clone()),
+                    _ => None,
+                })
+                .collect()
+        },
+    );
+    sources.sort_unstable();
+    sources.dedup();
+    let mut graph = CallGraph::default();
+    for source in sources.iter().take_while(|_| !cancelled()) {
+        let Ok(document) = read_document_or_open_class(source, document_map) else {
+            continue;
+        };
+        for declaration in declarations(&document.ast) {
+            let from = node(&declaration.class_path, declaration.name);
+            graph.nodes.insert(from.clone());
+            for (receiver, callee) in calls(&document.ast, declaration.block, class_map) {
+                graph.add(from.clone(), node(&receiver, &callee));
+            }
+        }
+    }
+    graph
+}
+
+fn node(class_path: &str, name: &str) -> String {
+    format!("{class_path}#{name}")
+}
+
+fn workspace_source(
+    class_path: &str,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Option<MyString> {
+    let class_map = class_map.read().ok()?;
+    match &class_map.get(class_path)?.source {
+        SourceDestination::Here(source) => Some(source.clone()),
+        _ => None,
+    }
+}
+
+/// Class path and name of the methods called in the block
+fn calls(
+    ast: &AstFile,
+    block: &AstBlock,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<(MyString, MyString)> {
+    let mut found = MethodCalls {
+        name: None,
+        nargs: None,
+        found: vec![],
+    };
+    found.visit_block(block);
+    if found.found.is_empty() {
+        return vec![];
+    }
+    let class = references::file_class(ast, class_map);
+    let imports = imports::imports(ast);
+    found
+        .found
+        .into_iter()
+        .filter_map(|(ident, _)| {
+            let receiver =
+                references::call_receiver(ast, &ident.range.start, &imports, &class, class_map)?;
+            Some((receiver, ident.value.clone()))
+        })
+        .collect()
+}
+
+/// Methods with a body of the classes in the file, nested classes are separated with `$`
+fn declarations(ast: &AstFile) -> Vec<Declaration<'_>> {
+    fn collect<'a>(thing: &'a AstThing, prefix: &str, out: &mut Vec<Declaration<'a>>) {
+        let (name, inner) = match thing {
+            AstThing::Class(c) => (&c.name, &c.block.inner),
+            AstThing::Record(r) => (&r.name, &r.block.inner),
+            AstThing::Interface(i) => (&i.name, &i.inner),
+            AstThing::Enumeration(e) => (&e.name, &e.inner),
+            AstThing::Annotation(a) => (&a.name, &a.inner),
+        };
+        let class_path = MyString::from(format!("{prefix}{}", name.value));
+        let methods = match thing {
+            AstThing::Class(c) => c
+                .block
+                .methods
+                .iter()
+                .filter_map(|m| Some((&m.header.name, m.block.as_ref()?)))
+                .collect(),
+            AstThing::Record(r) => r
+                .block
+                .methods
+                .iter()
+                .filter_map(|m| Some((&m.header.name, m.block.as_ref()?)))
+                .collect(),
+            AstThing::Enumeration(e) => e
+                .methods
+                .iter()
+                .filter_map(|m| Some((&m.header.name, m.block.as_ref()?)))
+                .collect(),
+            AstThing::Interface(i) => i
+                .default_methods
+                .iter()
+                .map(|m| (&m.header.name, &m.block))
+                .collect(),
+            AstThing::Annotation(_) => vec![],
+        };
+        out.extend(methods.into_iter().map(|(name, block)| Declaration {
+            class_path: class_path.clone(),
+            name: &name.value,
+            block,
+        }));
+        let prefix = format!("{class_path}$");
+        for thing in inner {
+            collect(thing, &prefix, out);
+        }
+    }
+    let mut package = String::new();
+    let mut out = vec![];
+    for top in &ast.top {
+        match top {
+            AstTopLevel::Package(p) => package = format!("{}.", p.name.value),
+            AstTopLevel::Thing(thing) => collect(thing, &package, &mut out),
+            _ => (),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        path::PathBuf,
+        sync::{Arc, RwLock},
+    };
+
+    use document::Document;
+
+    use super::{CallGraph, calls, declarations};
+
+    #[test]
+    fn declared_methods() {
+        let content = "
+package ch.emilycares;
+public class Foo {
+    void run() { helper(); }
+    abstract void todo();
+    class Inner {
+        void deep() {}
+    }
+}
+interface Bar {
+    default void ok() {}
+}
+";
+        let document = Document::setup(content, PathBuf::new()).unwrap();
+        let found: Vec<String> = declarations(&document.ast)
+            .iter()
+            .map(|d| format!("{}#{}", d.class_path, d.name))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                "ch.emilycares.Foo#run",
+                "ch.emilycares.Foo$Inner#deep",
+                "ch.emilycares.Bar#ok"
+            ]
+        );
+    }
+
+    #[test]
+    fn resolved_calls() {
+        let content = "
+package ch.emilycares;
+public class Foo {
+    void run() { helper(); this.other(1); }
+    void helper() {}
+    void other(int a) {}
+}
+";
+        let document = Document::setup(content, PathBuf::new()).unwrap();
+        let class_map = Arc::new(RwLock::new(HashMap::new()));
+        let declarations = declarations(&document.ast);
+        let found: Vec<String> = calls(&document.ast, declarations[0].block, &class_map)
+            .iter()
+            .map(|(class_path, name)| format!("{class_path}#{name}"))
+            .collect();
+        assert_eq!(
+            found,
+            ["ch.emilycares.Foo#helper", "ch.emilycares.Foo#other"]
+        );
+    }
+
+    #[test]
+    fn dot() {
+        let mut graph = CallGraph::default();
+        graph.add("a.A#run".into(), "a.B#call".into());
+        assert_eq!(
+            graph.to_dot(),
+            "digraph calls {\n  \"a.A#run\";\n  \"a.B#call\";\n  \"a.A#run\" -> \"a.B#call\";\n}"
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! Cancellation of requests with `$/cancelRequest`
+use std::{
+    cell::RefCell,
+    collections::{HashSet, VecDeque},
+    sync::Mutex,
+};
+
+use lsp_server::{Connection, ErrorCode, Message, RequestId, Response};
+use lsp_types::{
+    CancelParams, NumberOrString,
+    notification::{Cancel, DidChangeTextDocument, Notification},
+};
+
+thread_local! {
+    /// Request handled by a background thread, it can not read ahead on the connection
+    static BACKGROUND: RefCell<Option<RequestId>> = const { RefCell::new(None) };
+}
+
+/// Messages that were read while a request was running and the requests the client cancelled
+#[derive(Debug, Default)]
+pub struct Cancellation {
+    pending: Mutex<VecDeque<Message>>,
+    cancelled: Mutex<HashSet<RequestId>>,
+    running: Mutex<Option<RequestId>>,
+    /// Requests that were handed to a background thread
+    background: Mutex<HashSet<RequestId>>,
+}
+
+impl Cancellation {
+    /// Next message to handle, messages read while a request was running come first
+    pub fn next(&self, connection: &Connection) -> Option<Message> {
+        if let Ok(mut pending) = self.pending.lock()
+            && let Some(message) = pending.pop_front()
+        {
+            return Some(message);
+        }
+        connection.receiver.recv().ok()
+    }
+
+    /// Mark a request as running, returns false when it was cancelled before it started
+    pub fn start(&self, id: &RequestId) -> bool {
+        let previous = self
+            .running
+            .lock()
+            .ok()
+            .and_then(|mut running| running.replace(id.clone()));
+        let Ok(mut cancelled) = self.cancelled.lock() else {
+            return true;
+        };
+        // The previous request of the main loop is done, also when it sent no response
+        if let Some(previous) = previous {
+            cancelled.remove(&previous);
+        }
+        !cancelled.remove(id)
+    }
+
+    /// The running request continues on a background thread
+    pub fn detach(&self, id: &RequestId) {
+        if let Ok(mut running) = self.running.lock()
+            && running.as_ref() == Some(id)
+        {
+            *running = None;
+        }
+        if let Ok(mut background) = self.background.lock() {
+            background.insert(id.clone());
+        }
+    }
+
+    /// The request is done, returns false when it was cancelled while running
+    pub fn finish(&self, id: &RequestId) -> bool {
+        if let Ok(mut running) = self.running.lock()
+            && running.as_ref() == Some(id)
+        {
+            *running = None;
+        }
+        if let Ok(mut background) = self.background.lock() {
+            background.remove(id);
+        }
+        self.cancelled
+            .lock()
+            .map_or(true, |mut cancelled| !cancelled.remove(id))
+    }
+
+    /// Run a request on the current background thread, [`Self::is_cancelled`] then checks
+    /// this request
+    pub fn background<T>(&self, id: &RequestId, handler: impl FnOnce() -> T) -> T {
+        /// Clears the request of the thread, also when the handler panics
+        struct Reset;
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                BACKGROUND.with(|b| *b.borrow_mut() = None);
+            }
+        }
+        BACKGROUND.with(|b| *b.borrow_mut() = Some(id.clone()));
+        let _reset = Reset;
+        handler()
+    }
+
+    /// Mark a request as cancelled, it is answered as cancelled when it finishes. Requests that
+    /// are done or unknown are ignored
+    pub fn cancel(&self, params: CancelParams) {
+        let id = match params.id {
+            NumberOrString::Number(n) => RequestId::from(n),
+            NumberOrString::String(s) => RequestId::from(s),
+        };
+        if !self.is_known(&id) {
+            return;
+        }
+        if let Ok(mut cancelled) = self.cancelled.lock() {
+            cancelled.insert(id);
+        }
+    }
+
+    /// Whether the request is running or waits in the pending messages
+    fn is_known(&self, id: &RequestId) -> bool {
+        self.running
+            .lock()
+            .is_ok_and(|running| running.as_ref() == Some(id))
+            || self
+                .background
+                .lock()
+                .is_ok_and(|background| background.contains(id))
+            || self.pending.lock().is_ok_and(|pending| {
+                pending
+                    .iter()
+                    .any(|message| matches!(message, Message::Request(r) if &r.id == id))
+            })
+    }
+
+    /// Whether the client cancelled the running request. Long running requests check this
+    /// between steps and stop early.
+    pub fn is_cancelled(&self, connection: &Connection) -> bool {
+        if let Some(id) = BACKGROUND.with(|b| b.borrow().clone()) {
+            return self
+                .cancelled
+                .lock()
+                .is_ok_and(|cancelled| cancelled.contains(&id));
+        }
+        self.read_ahead(connection);
+        let Ok(running) = self.running.lock() else {
+            return false;
+        };
+        let Some(id) = running.as_ref() else {
+            return false;
+        };
+        self.cancelled
+            .lock()
+            .is_ok_and(|cancelled| cancelled.contains(id))
+    }
+
+    /// Whether a change of the document arrived that is not handled yet
+    pub fn pending_change(&self, connection: &Connection, uri: &str) -> bool {
+        if BACKGROUND.with(|b| b.borrow().is_none()) {
+            self.read_ahead(connection);
+        }
+        self.pending.lock().is_ok_and(|pending| {
+            pending.iter().any(|message| {
+                matches!(message, Message::Notification(n)
+                    if n.method == DidChangeTextDocument::METHOD
+                        && n.params["textDocument"]["uri"].as_str() == Some(uri))
+            })
+        })
+    }
+
+    /// Read messages that already arrived, cancel notifications are handled right away and the
+    /// rest after the running request
+    fn read_ahead(&self, connection: &Connection) {
+        while let Ok(message) = connection.receiver.try_recv() {
+            match message {
+                Message::Notification(notification) if notification.method == Cancel::METHOD => {
+                    if let Ok(params) = serde_json::from_value::<CancelParams>(notification.params)
+                    {
+                        self.cancel(params);
+                    }
+                }
+                message => {
+                    if let Ok(mut pending) = self.pending.lock() {
+                        pending.push_back(message);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[must_use]
+pub fn cancelled_response(id: RequestId) -> Response {
+    Response::new_err(
+        id,
+        ErrorCode::RequestCanceled as i32,
+        "Request cancelled".to_string(),
+    )
+}
+
+#[must_use]
+pub fn content_modified_response(id: RequestId) -> Response {
+    Response::new_err(
+        id,
+        ErrorCode::ContentModified as i32,
+        "Content modified".to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_server::{Connection, Message, Notification, RequestId};
+    use lsp_types::{
+        CancelParams, NumberOrString,
+        notification::{Cancel, Notification as _},
+    };
+
+    use super::*;
+
+    #[test]
+    fn cancel_running_request() {
+        let (server, client) = Connection::memory();
+        let cancellation = Cancellation::default();
+        let id = RequestId::from(7);
+        assert!(cancellation.start(&id));
+        assert!(!cancellation.is_cancelled(&server));
+
+        let cancel = CancelParams {
+            id: NumberOrString::Number(7),
+        };
+        let notification = Notification::new(Cancel::METHOD.to_string(), cancel);
+        let other = Notification::new("other".to_string(), ());
+        client.sender.send(Message::Notification(other)).unwrap();
+        client
+            .sender
+            .send(Message::Notification(notification))
+            .unwrap();
+
+        assert!(cancellation.is_cancelled(&server));
+        assert!(!cancellation.finish(&id));
+        assert!(matches!(
+            cancellation.next(&server),
+            Some(Message::Notification(n)) if n.method == "other"
+        ));
+    }
+
+    #[test]
+    fn pending_document_change() {
+        let (server, client) = Connection::memory();
+        let cancellation = Cancellation::default();
+        let change = Notification::new(
+            DidChangeTextDocument::METHOD.to_string(),
+            serde_json::json!({
+                "textDocument": { "uri": "file:///A.java", "version": 2 },
+                "contentChanges": [],
+            }),
+        );
+        client.sender.send(Message::Notification(change)).unwrap();
+        assert!(!cancellation.pending_change(&server, "file:///B.java"));
+        assert!(cancellation.pending_change(&server, "file:///A.java"));
+    }
+
+    #[test]
+    fn cancel_background_request() {
+        let (server, _client) = Connection::memory();
+        let cancellation = Cancellation::default();
+        let main = RequestId::from(1);
+        let background = RequestId::from(2);
+        assert!(cancellation.start(&background));
+        cancellation.detach(&background);
+        assert!(cancellation.start(&main));
+        cancellation.cancel(CancelParams {
+            id: NumberOrString::Number(2),
+        });
+        assert!(!cancellation.is_cancelled(&server));
+        assert!(cancellation.background(&background, || cancellation.is_cancelled(&server)));
+        assert!(!cancellation.finish(&background));
+        assert!(cancellation.finish(&main));
+    }
+
+    #[test]
+    fn ignore_cancel_of_unknown_request() {
+        let (server, client) = Connection::memory();
+        let cancellation = Cancellation::default();
+        let cancel = |id| {
+            cancellation.cancel(CancelParams {
+                id: NumberOrString::Number(id),
+            });
+        };
+        let done = RequestId::from(1);
+        assert!(cancellation.start(&done));
+        assert!(cancellation.finish(&done));
+        cancel(1);
+        cancel(99);
+        assert!(cancellation.cancelled.lock().unwrap().is_empty());
+
+        // A request read while another one was running
+        let running = RequestId::from(2);
+        assert!(cancellation.start(&running));
+        let request = lsp_server::Request::new(RequestId::from(3), "other".to_string(), ());
+        client.sender.send(Message::Request(request)).unwrap();
+        cancellation.read_ahead(&server);
+        cancel(3);
+        assert!(cancellation.finish(&running));
+        assert!(matches!(
+            cancellation.next(&server),
+            Some(Message::Request(r)) if r.id == RequestId::from(3)
+        ));
+        assert!(!cancellation.start(&RequestId::from(3)));
+        assert!(cancellation.cancelled.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,361 @@
+//! Diagnostics of a folder without an editor, `java_lsp check <dir>`
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use config::Configuration;
+use document::{Document, DocumentError};
+use dto::Class;
+use lsp_extra::{SERVER_NAME, source_to_uri};
+use lsp_server::Connection;
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use my_string::MyString;
+use serde_json::{Value, json};
+
+use crate::{
+    backend::{self, Backend, project_kind_to_project},
+    diagnostics, language_level,
+};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckFormat {
+    Human,
+    Json,
+    Sarif,
+}
+
+impl CheckFormat {
+    #[must_use]
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.to_lowercase().as_str() {
+            "human" | "text" => Some(Self::Human),
+            "json" => Some(Self::Json),
+            "sarif" => Some(Self::Sarif),
+            _ => None,
+        }
+    }
+}
+
+/// Diagnostics of a file, the path is relative to the checked folder
+#[derive(Debug)]
+pub struct FileDiagnostics {
+    pub path: PathBuf,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Index the project of the folder, print the diagnostics of its files and return the exit code
+pub async fn check_cli(folder: PathBuf, format: Option<&str>) -> i32 {
+    let format = match format.map(CheckFormat::parse) {
+        None => CheckFormat::Human,
+        Some(Some(format)) => format,
+        Some(None) => {
+            eprintln!("Unknown format, expected human, json or sarif");
+            return 2;
+        }
+    };
+    let Ok(folder) = folder.canonicalize() else {
+        eprintln!("Folder {} does not exist", folder.display());
+        return 2;
+    };
+    let (backend, project) = match load_folder(&folder).await {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("{e}");
+            return 2;
+        }
+    };
+    let mut files = loader::java_files(vec![folder.clone()]);
+    files.sort();
+    let results: Vec<FileDiagnostics> = files
+        .into_iter()
+        .filter_map(|file| {
+            let diagnostics =
+                file_diagnostics(&file, &backend.class_map, &backend.config, &project);
+            if diagnostics.is_empty() {
+                return None;
+            }
+            Some(FileDiagnostics {
+                path: file.strip_prefix(&folder).unwrap_or(&file).to_path_buf(),
+                diagnostics,
+            })
+        })
+        .collect();
+    match format {
+        CheckFormat::Human => print!("{}", human(&results)),
+        CheckFormat::Json => println!("{:#}", json(&results)),
+        CheckFormat::Sarif => println!("{:#}", sarif(&results)),
+    }
+    i32::from(has_errors(&results))
+}
+
+/// Backend with the project of the folder indexed the way the server does on start. The
+/// current folder is changed to the folder, build tools are run in it.
+pub(crate) async fn load_folder(folder: &Path) -> Result<(Backend, backend::Project), String> {
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    let project = backend::get_project_kind(&folder.to_path_buf(), &path)
+        .ok()
+        .and_then(|kind| project_kind_to_project(&folder.to_string_lossy(), kind))
+        .ok_or_else(|| format!("Unable to load the project of {}", folder.display()))?;
+    std::env::set_current_dir(folder)
+        .map_err(|e| format!("Unable to enter {}: {e:?}", folder.display()))?;
+    let (con, _client) = Connection::memory();
+    let backend = Backend::new(con);
+    if let Ok(mut projects) = backend.projects.write() {
+        projects.push(project.clone());
+    }
+    Backend::initialized(
+        None,
+        backend.connection.clone(),
+        &backend.class_map,
+        backend.reference_map.clone(),
+        &backend.index_java_path(path),
+        backend.config.java_release,
+        backend.projects.clone(),
+    )
+    .await;
+    Ok((backend, project))
+}
+
+/// Diagnostics of the file without hints, errors of the parser are the only diagnostic
+fn file_diagnostics(
+    file: &Path,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    config: &Configuration,
+    project: &backend::Project,
+) -> Vec<Diagnostic> {
+    let Some(uri) = file.to_str().and_then(|f| source_to_uri(f).ok()) else {
+        return Vec::new();
+    };
+    let diagnostics = match Document::setup_read(file.to_path_buf()) {
+        Ok(document) => diagnostics::diagnostics(
+            &document,
+            &uri,
+            class_map,
+            config,
+            project.language_level(file),
+        ),
+        Err(DocumentError::Diagnostic(diagnostic)) => language_level::syntax_error(
+            *diagnostic,
+            &std::fs::read_to_string(file).unwrap_or_default(),
+            project.language_level(file),
+        ),
+        Err(e) => {
+            eprintln!("Unable to read {}: {e:?}", file.display());
+            Vec::new()
+        }
+    };
+    diagnostics
+        .into_iter()
+        .filter(|d| d.severity != Some(DiagnosticSeverity::HINT))
+        .collect()
+}
+
+#[must_use]
+pub fn has_errors(results: &[FileDiagnostics]) -> bool {
+    results
+        .iter()
+        .flat_map(|r| &r.diagnostics)
+        .any(|d| severity(d) == "error")
+}
+
+const fn severity(diagnostic: &Diagnostic) -> &'static str {
+    match diagnostic.severity {
+        Some(DiagnosticSeverity::WARNING) => "warning",
+        Some(DiagnosticSeverity::INFORMATION) => "info",
+        Some(DiagnosticSeverity::HINT) => "hint",
+        _ => "error",
+    }
+}
+
+fn code(diagnostic: &Diagnostic) -> Option<String> {
+    match diagnostic.code.as_ref()? {
+        NumberOrString::Number(n) => Some(n.to_string()),
+        NumberOrString::String(s) => Some(s.clone()),
+    }
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// `src/A.java:3:5: error: message [code]` with 1-based lines and columns and a summary
+#[must_use]
+pub fn human(results: &[FileDiagnostics]) -> String {
+    let mut out = String::new();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for result in results {
+        for d in &result.diagnostics {
+            let severity = severity(d);
+            *counts.entry(severity).or_default() += 1;
+            let _ = write!(
+                out,
+                "{}:{}:{}: {severity}: {}",
+                path_string(&result.path),
+                d.range.start.line + 1,
+                d.range.start.character + 1,
+                d.message
+            );
+            if let Some(code) = code(d) {
+                let _ = write!(out, " [{code}]");
+            }
+            out.push('\n');
+        }
+    }
+    let count = |severity| counts.get(severity).copied().unwrap_or_default();
+    let _ = writeln!(
+        out,
+        "{} errors, {} warnings, {} infos",
+        count("error"),
+        count("warning"),
+        count("info")
+    );
+    out
+}
+
+/// Array of the diagnostics with 1-based lines and columns
+#[must_use]
+pub fn json(results: &[FileDiagnostics]) -> Value {
+    results
+        .iter()
+        .flat_map(|result| {
+            result.diagnostics.iter().map(|d| {
+                json!({
+                    "path": path_string(&result.path),
+                    "line": d.range.start.line + 1,
+                    "column": d.range.start.character + 1,
+                    "endLine": d.range.end.line + 1,
+                    "endColumn": d.range.end.character + 1,
+                    "severity": severity(d),
+                    "code": code(d),
+                    "message": d.message,
+                })
+            })
+        })
+        .collect()
+}
+
+/// SARIF 2.1.0 log with a rule for every diagnostic code
+#[must_use]
+pub fn sarif(results: &[FileDiagnostics]) -> Value {
+    let mut rules: Vec<String> = results
+        .iter()
+        .flat_map(|r| &r.diagnostics)
+        .filter_map(code)
+        .collect();
+    rules.sort_unstable();
+    rules.dedup();
+    let sarif_results: Vec<Value> = results
+        .iter()
+        .flat_map(|result| {
+            result.diagnostics.iter().map(|d| {
+                let level = match severity(d) {
+                    "error" => "error",
+                    "warning" => "warning",
+                    _ => "note",
+                };
+                let mut out = json!({
+                    "level": level,
+                    "message": { "text": d.message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": path_string(&result.path) },
+                            "region": {
+                                "startLine": d.range.start.line + 1,
+                                "startColumn": d.range.start.character + 1,
+                                "endLine": d.range.end.line + 1,
+                                "endColumn": d.range.end.character + 1,
+                            }
+                        }
+                    }],
+                });
+                if let Some(code) = code(d) {
+                    out["ruleId"] = Value::String(code);
+                }
+                out
+            })
+        })
+        .collect();
+    json!({
+        "version": "2.1.0",
+        "$schema": SARIF_SCHEMA,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": SERVER_NAME,
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                }
+            },
+            "results": sarif_results,
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+
+    use super::{FileDiagnostics, has_errors, human, json, sarif};
+
+    fn results() -> Vec<FileDiagnostics> {
+        let diagnostic = |line, severity, code: &str, message: &str| Diagnostic {
+            range: Range {
+                start: Position { line, character: 4 },
+                end: Position { line, character: 8 },
+            },
+            severity: Some(severity),
+            code: Some(NumberOrString::String(code.to_string())),
+            message: message.to_string(),
+            ..Default::default()
+        };
+        vec![FileDiagnostics {
+            path: PathBuf::from("src/A.java"),
+            diagnostics: vec![
+                diagnostic(
+                    2,
+                    DiagnosticSeverity::ERROR,
+                    "unresolved",
+                    "Unknown type Foo",
+                ),
+                diagnostic(
+                    5,
+                    DiagnosticSeverity::WARNING,
+                    "unused",
+                    "Unused variable a",
+                ),
+            ],
+        }]
+    }
+
+    #[test]
+    fn formats() {
+        let results = results();
+        assert!(has_errors(&results));
+        assert!(!has_errors(&results[..0]));
+        assert_eq!(
+            human(&results),
+            "src/A.java:3:5: error: Unknown type Foo [unresolved]\n\
+             src/A.java:6:5: warning: Unused variable a [unused]\n\
+             1 errors, 1 warnings, 0 infos\n"
+        );
+        let json = json(&results);
+        assert_eq!(json[1]["line"], 6);
+        assert_eq!(json[1]["endColumn"], 9);
+        assert_eq!(json[1]["severity"], "warning");
+        let sarif = sarif(&results);
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "unresolved");
+        assert_eq!(run["results"][0]["level"], "error");
+        assert_eq!(run["results"][0]["ruleId"], "unresolved");
+        assert_eq!(
+            run["results"][1]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            6
+        );
+    }
+}
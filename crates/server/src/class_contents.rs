@@ -0,0 +1,161 @@
+//! Read only documents of classes in jars
+//!
+//! Sources of dependencies and stubs of classes without sources are written to the cache
+//! folder. Clients that set `class_file_contents` get definitions into them as `jdt://contents/`
+//! uris instead of file uris, and request the text of such a document with
+//! `java/classContents`. The path of a `jdt` uri is the path of the source in the cache folder.
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+
+use lsp_types::{GotoDefinitionResponse, Location, Uri};
+use serde::Deserialize;
+
+/// Request for the text of a `jdt` uri
+pub const CLASS_CONTENTS: &str = "java/classContents";
+pub const SCHEME: &str = "jdt";
+const AUTHORITY: &str = "contents";
+
+#[derive(Debug, Deserialize)]
+pub struct ClassContentsParams {
+    pub uri: Uri,
+}
+
+#[must_use]
+pub fn is_virtual(uri: &Uri) -> bool {
+    uri.scheme()
+        .is_some_and(|s| s.as_str().eq_ignore_ascii_case(SCHEME))
+}
+
+/// `jdt` uri of a file uri of a source in the cache folder
+#[must_use]
+pub fn to_virtual_uri(uri: &Uri, cache: &Path) -> Option<Uri> {
+    if !uri
+        .scheme()
+        .is_some_and(|s| s.as_str().eq_ignore_ascii_case("file"))
+    {
+        return None;
+    }
+    let path = PathBuf::from(uri.path().as_str());
+    let relative = path.strip_prefix(cache).ok()?;
+    let relative = relative
+        .components()
+        .map(|c| match c {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?
+        .join("/");
+    Uri::from_str(&format!("{SCHEME}://{AUTHORITY}/{relative}")).ok()
+}
+
+/// Source in the cache folder of a `jdt` uri, the path can not leave the cache folder
+#[must_use]
+pub fn source_path(uri: &Uri, cache: &Path) -> Option<PathBuf> {
+    if !is_virtual(uri) {
+        return None;
+    }
+    let relative = Path::new(uri.path().as_str().trim_start_matches('/'));
+    if relative.as_os_str().is_empty()
+        || !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    Some(cache.join(relative))
+}
+
+/// Text of the source of a `jdt` uri
+#[must_use]
+pub fn class_contents(uri: &Uri, cache: &Path) -> Option<String> {
+    fs::read_to_string(source_path(uri, cache)?).ok()
+}
+
+/// Definitions into the cache folder with `jdt` uris
+#[must_use]
+pub fn virtual_definition(
+    response: GotoDefinitionResponse,
+    cache: &Path,
+) -> GotoDefinitionResponse {
+    let location = |mut location: Location| {
+        if let Some(uri) = to_virtual_uri(&location.uri, cache) {
+            location.uri = uri;
+        }
+        location
+    };
+    match response {
+        GotoDefinitionResponse::Scalar(l) => GotoDefinitionResponse::Scalar(location(l)),
+        GotoDefinitionResponse::Array(l) => {
+            GotoDefinitionResponse::Array(l.into_iter().map(location).collect())
+        }
+        GotoDefinitionResponse::Link(links) => GotoDefinitionResponse::Link(
+            links
+                .into_iter()
+                .map(|mut link| {
+                    if let Some(uri) = to_virtual_uri(&link.target_uri, cache) {
+                        link.target_uri = uri;
+                    }
+                    link
+                })
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf, str::FromStr};
+
+    use lsp_types::{GotoDefinitionResponse, Location, Range, Uri};
+
+    use super::{class_contents, source_path, to_virtual_uri, virtual_definition};
+
+    #[test]
+    fn virtual_uris() {
+        let cache = PathBuf::from("/home/a/.cache");
+        let file = Uri::from_str("file:///home/a/.cache/java_lsp/src/java/util/List.java").unwrap();
+        let uri = to_virtual_uri(&file, &cache).unwrap();
+        assert_eq!(
+            uri.as_str(),
+            "jdt://contents/java_lsp/src/java/util/List.java"
+        );
+        assert_eq!(
+            source_path(&uri, &cache),
+            Some(PathBuf::from(
+                "/home/a/.cache/java_lsp/src/java/util/List.java"
+            ))
+        );
+        let project = Uri::from_str("file:///ws/src/main/java/App.java").unwrap();
+        assert_eq!(to_virtual_uri(&project, &cache), None);
+        let escape = Uri::from_str("jdt://contents/../secret.java").unwrap();
+        assert_eq!(source_path(&escape, &cache), None);
+        assert_eq!(source_path(&project, &cache), None);
+    }
+
+    #[test]
+    fn contents() {
+        let cache = std::env::temp_dir().join(format!("class-contents-{}", std::process::id()));
+        fs::create_dir_all(cache.join("src")).unwrap();
+        fs::write(cache.join("src/A.java"), "class A {}").unwrap();
+        let file = Uri::from_str(&format!("file://{}/src/A.java", cache.display())).unwrap();
+        let response = virtual_definition(
+            GotoDefinitionResponse::Scalar(Location {
+                uri: file,
+                range: Range::default(),
+            }),
+            &cache,
+        );
+        let GotoDefinitionResponse::Scalar(location) = response else {
+            unreachable!();
+        };
+        assert_eq!(location.uri.as_str(), "jdt://contents/src/A.java");
+        assert_eq!(
+            class_contents(&location.uri, &cache),
+            Some("class A {}".to_string())
+        );
+        let _ = fs::remove_dir_all(&cache);
+    }
+}
@@ -30,7 +30,12 @@ pub fn tests(
                 return Err(CodeLensError::SkipFile);
             }
         }
-        ProjectKind::Unknown => return Err(CodeLensError::SkipFile),
+        ProjectKind::Eclipse { .. }
+        | ProjectKind::Bazel { .. }
+        | ProjectKind::Bsp { .. }
+        | ProjectKind::Unknown => {
+            return Err(CodeLensError::SkipFile);
+        }
     }
 
     for t in &ast.top {
@@ -123,6 +128,9 @@ fn command_test_class(
                 Value::String(class_name.to_string()),
             ]),
         }),
-        ProjectKind::Unknown => None,
+        ProjectKind::Eclipse { .. }
+        | ProjectKind::Bazel { .. }
+        | ProjectKind::Bsp { .. }
+        | ProjectKind::Unknown => None,
     }
 }
@@ -1,26 +1,32 @@
 use std::{
-    cmp::Ordering,
     collections::HashMap,
     num::TryFromIntError,
+    str::FromStr,
     sync::{Arc, RwLock},
 };
 
 use ast::types::{
-    AstBlockEntry, AstBlockVariable, AstFile, AstForContent, AstIf, AstIfContent, AstPackage,
-    AstPoint, AstRange, AstThing, AstTopLevel, AstWhileContent,
+    AstBlockEntry, AstBlockVariable, AstFile, AstForContent, AstIf, AstIfContent, AstJTypeKind,
+    AstPackage, AstPoint, AstRange, AstThing, AstTopLevel, AstWhileContent,
 };
+use document::DocumentError;
 use dto::{Class, ImportUnit};
 use local_variable::LocalVariable;
-use lsp_extra::{ToLspRangeError, to_lsp_position, to_lsp_range};
+use lsp_extra::{SourceToUriError, ToLspRangeError, to_lsp_position, to_lsp_range};
 use lsp_types::{
-    CodeAction, CodeActionKind, CodeActionOrCommand, Position, Range, TextEdit, Uri, WorkspaceEdit,
+    CodeAction, CodeActionKind, CodeActionOrCommand, Command, CreateFile, CreateFileOptions,
+    DocumentChangeOperation, DocumentChanges, OneOf, OptionalVersionedTextDocumentIdentifier,
+    Position, Range, ResourceOp, TextDocumentEdit, TextEdit, Uri, WorkspaceEdit,
 };
 use my_string::MyString;
+use serde_json::Value;
 use tyres::TyresError;
 
 use crate::{
     document_link::{SRC_MAIN, SRC_TEST},
     hover::jtype_hover_display,
+    import_rank,
+    symbol_index::SymbolIndex,
 };
 
 pub struct CodeActionContext<'a> {
@@ -40,6 +46,8 @@ pub enum CodeActionError {
     Tyres(TyresError),
     Int(TryFromIntError),
     ToLspRange(ToLspRangeError),
+    Document(DocumentError),
+    SourceToUri(SourceToUriError),
 }
 
 pub fn replace_with_value_type(
@@ -121,6 +129,10 @@ pub fn replace_with_value_type(
             &c.jtype
         }
     };
+    // The type of `var` is replaced by refactor::convert_var
+    if matches!(current_type.value, AstJTypeKind::Var) {
+        return Ok(None);
+    }
     point.col += 1;
     // value here
     let call_chain = call_chain::get_call_chain(ast, &point);
@@ -293,24 +305,20 @@ pub fn import_jtype(
     point: &AstPoint,
     imports: &[ImportUnit],
     class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    symbols: &SymbolIndex,
+    chosen: &HashMap<String, u32>,
     current_file: &Uri,
 ) -> Option<Vec<CodeActionOrCommand>> {
     if let Some(class) = get_class::get_class(ast, point)
         && !tyres::is_imported_class_name(&class.name, imports, class_map)
     {
-        let mut resolve_import: Vec<String> = tyres::resolve_import(&class.name, class_map);
-        // Prefer java imports
-        resolve_import.sort_by(|a, b| {
-            let a_j = a.starts_with("java");
-            let b_j = b.starts_with("java");
-            if a_j && !b_j {
-                Ordering::Less
-            } else if !a_j && b_j {
-                Ordering::Greater
-            } else {
-                a.cmp(b)
-            }
-        });
+        let mut resolve_import = symbols.exact(&class.name);
+        if resolve_import.is_empty() {
+            return Some(create_type(ast, &class.name, current_file));
+        }
+        if let Ok(class_map) = class_map.read() {
+            import_rank::rank(&mut resolve_import, imports, &class_map, chosen);
+        }
 
         let i = resolve_import
             .iter()
@@ -321,6 +329,66 @@ pub fn import_jtype(
     None
 }
 
+/// Kinds of declarations that can be created for a unresolved type
+const CREATE_TYPE_KINDS: [&str; 3] = ["class", "interface", "record"];
+
+/// Create a new file next to the current one with a empty declaration
+#[must_use]
+pub fn create_type(ast: &AstFile, name: &str, current_file: &Uri) -> Vec<CodeActionOrCommand> {
+    let Some((folder, _)) = current_file.as_str().rsplit_once('/') else {
+        return vec![];
+    };
+    let Ok(uri) = Uri::from_str(&format!("{folder}/{name}.java")) else {
+        return vec![];
+    };
+    let package = ast.top.iter().find_map(|t| match t {
+        AstTopLevel::Package(package) => Some(format!("package {};\n\n", package.name.value)),
+        _ => None,
+    });
+    CREATE_TYPE_KINDS
+        .iter()
+        .map(|kind| {
+            let declaration = match *kind {
+                "interface" => format!("interface {name} {{"),
+                "record" => format!("record {name}() {{"),
+                _ => format!("class {name} {{"),
+            };
+            let new_text = format!(
+                "{}public {declaration}\n}}\n",
+                package.as_deref().unwrap_or_default()
+            );
+            CodeActionOrCommand::CodeAction(CodeAction {
+                kind: Some(CodeActionKind::QUICKFIX),
+                title: format!("Create {kind} {name}"),
+                edit: Some(WorkspaceEdit {
+                    document_changes: Some(DocumentChanges::Operations(vec![
+                        DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+                            uri: uri.clone(),
+                            options: Some(CreateFileOptions {
+                                overwrite: Some(false),
+                                ignore_if_exists: Some(true),
+                            }),
+                            annotation_id: None,
+                        })),
+                        DocumentChangeOperation::Edit(TextDocumentEdit {
+                            text_document: OptionalVersionedTextDocumentIdentifier {
+                                uri: uri.clone(),
+                                version: None,
+                            },
+                            edits: vec![OneOf::Left(TextEdit {
+                                range: Range::default(),
+                                new_text,
+                            })],
+                        }),
+                    ])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
 pub fn get_import_position(ast: &AstFile) -> Result<Position, CodeActionError> {
     if let Some(import) = ast
         .top
@@ -489,6 +557,14 @@ pub fn import_to_code_action(
             changes: Some(changes),
             ..Default::default()
         }),
+        command: Some(Command {
+            title: format!("Import {classpath}"),
+            command: import_rank::COMMAND_IMPORT_CHOSEN.to_string(),
+            arguments: Some(vec![
+                Value::String(current_file.as_str().to_string()),
+                Value::String(classpath.to_string()),
+            ]),
+        }),
         ..Default::default()
     })
 }
@@ -505,11 +581,16 @@ pub mod tests {
     use document::Document;
     use dto::{Access, Class, ImportUnit, JType, Method, SourceDestination};
     use expect_test::expect;
-    use lsp_types::Uri;
+    use lsp_types::{
+        CodeActionOrCommand, DocumentChangeOperation, DocumentChanges, OneOf, ResourceOp, Uri,
+    };
     use my_string::{MyString, smol_str::SmolStr};
     use variables::VariableContext;
 
-    use crate::codeaction::{generate_class, replace_with_value_type};
+    use crate::{
+        codeaction::{generate_class, import_jtype, replace_with_value_type},
+        symbol_index::SymbolIndex,
+    };
 
     use super::CodeActionContext;
 
@@ -715,6 +796,87 @@ public class Test {
         "#]];
         expected.assert_debug_eq(&result);
     }
+    #[test]
+    fn create_type_unresolved() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public void hello() {
+        Foo foo = null;
+    }
+}
+";
+        let doc = Document::setup(cont, PathBuf::from_str("./").unwrap()).unwrap();
+        let uri = Uri::from_str("file:///project/src/main/java/ch/emilycares/Test.java").unwrap();
+        let imports = imports::imports(&doc.ast);
+        let out: Vec<_> = import_jtype(
+            &doc.ast,
+            &AstPoint::new(4, 9),
+            &imports,
+            &get_class_map(),
+            &SymbolIndex::default(),
+            &HashMap::new(),
+            &uri,
+        )
+        .unwrap()
+        .into_iter()
+        .filter_map(|a| match a {
+            CodeActionOrCommand::CodeAction(a) => Some(a),
+            CodeActionOrCommand::Command(_) => None,
+        })
+        .map(|a| {
+            let Some(DocumentChanges::Operations(operations)) = a.edit.unwrap().document_changes
+            else {
+                unreachable!();
+            };
+            let changes: Vec<_> = operations
+                .into_iter()
+                .map(|o| match o {
+                    DocumentChangeOperation::Op(ResourceOp::Create(c)) => {
+                        format!("create {}", c.uri.as_str())
+                    }
+                    DocumentChangeOperation::Edit(e) => e
+                        .edits
+                        .into_iter()
+                        .map(|e| match e {
+                            OneOf::Left(e) => e.new_text,
+                            OneOf::Right(e) => e.text_edit.new_text,
+                        })
+                        .collect(),
+                    DocumentChangeOperation::Op(_) => String::new(),
+                })
+                .collect();
+            (a.title, changes)
+        })
+        .collect();
+        let expected = expect![[r#"
+            [
+                (
+                    "Create class Foo",
+                    [
+                        "create file:///project/src/main/java/ch/emilycares/Foo.java",
+                        "package ch.emilycares;\n\npublic class Foo {\n}\n",
+                    ],
+                ),
+                (
+                    "Create interface Foo",
+                    [
+                        "create file:///project/src/main/java/ch/emilycares/Foo.java",
+                        "package ch.emilycares;\n\npublic interface Foo {\n}\n",
+                    ],
+                ),
+                (
+                    "Create record Foo",
+                    [
+                        "create file:///project/src/main/java/ch/emilycares/Foo.java",
+                        "package ch.emilycares;\n\npublic record Foo() {\n}\n",
+                    ],
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
     fn get_class_map() -> Arc<RwLock<HashMap<MyString, Class>>> {
         let mut class_map: HashMap<MyString, Class> = HashMap::new();
         class_map.insert(
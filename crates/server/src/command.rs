@@ -4,29 +4,36 @@ use std::{
     path::{Path, PathBuf},
     process::{Command, Stdio},
     sync::{Arc, RwLock},
-    time::UNIX_EPOCH,
+    time::{Instant, UNIX_EPOCH},
 };
 
 use common::{
-    Dependency, TaskProgress, cache_dir, project_cache_dir,
+    Dependency, TaskProgress, cache_dir,
+    dependency_tree::DependencyNode,
+    project_cache_dir,
     project_kind::{ProjectKind, get_project_kind},
 };
+use compile::CompileErrorMessage;
+use config::classpath::classpath_config_path;
 use dto::Class;
+use eclipse::project::get_eclipse_cache_path;
 use gradle::project::get_gradle_cache_path;
 use lsp_extra::SERVER_NAME;
 use lsp_server::Connection;
 use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, ProgressToken, Range};
 use maven::{tree::MavenTreeError, update};
 use my_string::MyString;
+use serde::Serialize;
 use serde_json::Value;
 use tokio::task::JoinSet;
 
 use crate::{
     backend::{
-        Backend, Project, get_project_artifacts, project_deps, read_forward,
+        Backend, Project, get_project_artifacts, progress_summary, project_deps, read_forward,
         report_maven_gradle_diagnostic, update_report,
     },
     command,
+    plain_project::{self, get_plain_cache_path},
 };
 
 #[derive(Debug)]
@@ -52,7 +59,18 @@ pub fn reload_dependencies(
     let Ok(projs) = projects.read() else {
         return None;
     };
-    let projs = projs.clone();
+    reload_projects(con, progress, class_map, projs.clone(), project_artifacts);
+    None
+}
+
+/// Load the dependencies of the given projects in the background
+pub fn reload_projects(
+    con: Arc<Connection>,
+    progress: Option<ProgressToken>,
+    class_map: Arc<RwLock<HashMap<MyString, Class>>>,
+    projs: Vec<Project>,
+    project_artifacts: Arc<Vec<String>>,
+) {
     tokio::spawn(async move {
         let mut handles = JoinSet::new();
         let progress = Arc::new(progress);
@@ -78,12 +96,29 @@ pub fn reload_dependencies(
                         &mut handles,
                     );
                 }
-                ProjectKind::Unknown => (),
+                ProjectKind::Eclipse { .. } => {
+                    reload_eclipse_project(
+                        &con,
+                        &class_map,
+                        PathBuf::from(p.dir.clone()).as_path(),
+                        &mut handles,
+                    );
+                }
+                ProjectKind::Unknown => {
+                    reload_plain_project(
+                        &con,
+                        &class_map,
+                        PathBuf::from(p.dir.clone()).as_path(),
+                        true,
+                        &mut handles,
+                    );
+                }
+                // Targets are loaded when their files are opened
+                ProjectKind::Bazel { .. } | ProjectKind::Bsp { .. } => (),
             }
         }
         let _ = handles.join_all().await;
     });
-    None
 }
 
 pub fn reload_maven_project(
@@ -103,6 +138,7 @@ pub fn reload_maven_project(
     let project_artifacts = project_artifacts.clone();
     handles.spawn(async move {
         let project_dir = project_dir.as_path();
+        let started = Instant::now();
         Backend::progress_start_option_token(&con.clone(), &progress, &task);
         let (sender, receiver) =
             tokio::sync::watch::channel::<TaskProgress>(TaskProgress {
@@ -118,12 +154,12 @@ pub fn reload_maven_project(
                 () = project_deps(sender, project_kind.clone(), class_map.clone(), true, project_dir, &cache, &tree, repos, project_artifacts) => {}
             }
         }
-        Backend::progress_end_option_token(&con, &progress, &task);
+        Backend::progress_end_option_token_message(&con, &progress, &task, Some(progress_summary(None, started)));
     });
 }
 
 /// For gradle there is no difference between update and reload
-/// Does not use a cache
+/// Does not use the project cache, jars that did not change are loaded from their shard
 pub fn reload_gradle_project(
     con: &Arc<Connection>,
     class_map: &Arc<RwLock<HashMap<my_string::smol_str::SmolStr, Class>>>,
@@ -137,6 +173,7 @@ pub fn reload_gradle_project(
     handles.spawn(async move {
         let task = "Load gradle project".to_string();
         let progress = Arc::new(Option::Some(ProgressToken::String(task.clone())));
+        let started = Instant::now();
         Backend::progress_start_option_token(&con.clone(), &progress, &task);
         let project_cache_dir = project_cache_dir();
 
@@ -148,9 +185,72 @@ pub fn reload_gradle_project(
         });
         tokio::select! {
             () = read_forward(receiver, con.clone(), task.clone(), progress.clone())  => {},
-            () = gradle::project::index_project(class_map.clone(), sender, false, cache_path, executable.clone()) => {}
+            () = gradle::project::index_project(class_map.clone(), sender, false, cache_path, executable.clone(), &project_dir) => {}
         }
-        Backend::progress_end_option_token(&con, &progress, &task);
+        Backend::progress_end_option_token_message(&con, &progress, &task, Some(progress_summary(None, started)));
+    });
+}
+
+/// Eclipse projects have no dependency resolution, the libraries of the `.classpath` are loaded
+pub fn reload_eclipse_project(
+    con: &Arc<Connection>,
+    class_map: &Arc<RwLock<HashMap<my_string::smol_str::SmolStr, Class>>>,
+    project_dir: &Path,
+    handles: &mut JoinSet<()>,
+) {
+    let project_dir = project_dir.to_owned();
+    let con = con.clone();
+    let class_map = class_map.clone();
+    handles.spawn(async move {
+        let task = "Load eclipse project".to_string();
+        let progress = Arc::new(Option::Some(ProgressToken::String(task.clone())));
+        let started = Instant::now();
+        Backend::progress_start_option_token(&con.clone(), &progress, &task);
+        let cache_path = get_eclipse_cache_path(&project_dir, &project_cache_dir());
+        let _ = tokio::task::spawn_blocking(move || {
+            eclipse::project::index_project(&class_map, false, &cache_path, &project_dir);
+        })
+        .await;
+        Backend::progress_end_option_token_message(
+            &con,
+            &progress,
+            &task,
+            Some(progress_summary(None, started)),
+        );
+    });
+}
+
+/// Jars of the classpath file of a project without a build tool, with `sources` the source
+/// roots are loaded too
+pub fn reload_plain_project(
+    con: &Arc<Connection>,
+    class_map: &Arc<RwLock<HashMap<my_string::smol_str::SmolStr, Class>>>,
+    project_dir: &Path,
+    sources: bool,
+    handles: &mut JoinSet<()>,
+) {
+    if classpath_config_path(project_dir).is_none() {
+        return;
+    }
+    let project_dir = project_dir.to_owned();
+    let con = con.clone();
+    let class_map = class_map.clone();
+    handles.spawn(async move {
+        let task = "Load classpath".to_string();
+        let progress = Arc::new(Option::Some(ProgressToken::String(task.clone())));
+        let started = Instant::now();
+        Backend::progress_start_option_token(&con.clone(), &progress, &task);
+        let cache_path = get_plain_cache_path(&project_dir, &project_cache_dir());
+        let _ = tokio::task::spawn_blocking(move || {
+            plain_project::index_project(&class_map, &cache_path, &project_dir, sources);
+        })
+        .await;
+        Backend::progress_end_option_token_message(
+            &con,
+            &progress,
+            &task,
+            Some(progress_summary(None, started)),
+        );
     });
 }
 
@@ -173,7 +273,8 @@ pub async fn reload_dependencies_cli() {
         ProjectKind::Gradle { executable, .. } => {
             reload_gradle_project_cli(con, project_dir, executable).await;
         }
-        ProjectKind::Unknown => (),
+        ProjectKind::Eclipse { .. } => reload_eclipse_project_cli(con, project_dir).await,
+        ProjectKind::Bazel { .. } | ProjectKind::Bsp { .. } | ProjectKind::Unknown => (),
     }
 }
 
@@ -251,7 +352,25 @@ pub fn update_dependencies(
                         &mut handles,
                     );
                 }
-                ProjectKind::Unknown => (),
+                ProjectKind::Eclipse { .. } => {
+                    reload_eclipse_project(
+                        &con,
+                        &class_map,
+                        PathBuf::from(p.dir.clone()).as_path(),
+                        &mut handles,
+                    );
+                }
+                ProjectKind::Unknown => {
+                    reload_plain_project(
+                        &con,
+                        &class_map,
+                        PathBuf::from(p.dir.clone()).as_path(),
+                        true,
+                        &mut handles,
+                    );
+                }
+                // Targets are loaded when their files are opened
+                ProjectKind::Bazel { .. } | ProjectKind::Bsp { .. } => (),
             }
         }
         let _ = handles.join_all().await;
@@ -326,12 +445,13 @@ pub async fn update_dependencies_cli() {
         ProjectKind::Gradle { executable, .. } => {
             reload_gradle_project_cli(con, project_dir, executable).await;
         }
-        ProjectKind::Unknown => (),
+        ProjectKind::Eclipse { .. } => reload_eclipse_project_cli(con, project_dir).await,
+        ProjectKind::Bazel { .. } | ProjectKind::Bsp { .. } | ProjectKind::Unknown => (),
     }
 }
 
 /// For gradle there is no difference between update and reload
-/// Does not use a cache
+/// Does not use the project cache, jars that did not change are loaded from their shard
 async fn reload_gradle_project_cli(con: Arc<Connection>, project_dir: PathBuf, executable: String) {
     let class_map = Arc::new(RwLock::new(HashMap::new()));
 
@@ -348,11 +468,18 @@ async fn reload_gradle_project_cli(con: Arc<Connection>, project_dir: PathBuf, e
     });
     tokio::select! {
         () = read_forward(receiver, con.clone(), task.clone(), progress.clone())  => {},
-        () = gradle::project::index_project(class_map.clone(), sender, false, cache_path, executable.clone()) => {}
+        () = gradle::project::index_project(class_map.clone(), sender, false, cache_path, executable.clone(), &project_dir) => {}
     }
     Backend::progress_end_option_token(&con, &progress, &task);
 }
 
+async fn reload_eclipse_project_cli(con: Arc<Connection>, project_dir: PathBuf) {
+    let class_map = Arc::new(RwLock::new(HashMap::new()));
+    let mut handles = JoinSet::new();
+    reload_eclipse_project(&con, &class_map, &project_dir, &mut handles);
+    let _ = handles.join_all().await;
+}
+
 async fn update_dependencies_maven_cli(
     con: Arc<Connection>,
     project_dir: PathBuf,
@@ -393,6 +520,74 @@ async fn update_dependencies_maven_cli(
     Backend::progress_end_option_token(&con.clone(), &progress, &task);
 }
 
+pub const COMMAND_DEPENDENCY_TREE: &str = "java_lsp.dependencyTree";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDependencies {
+    pub project: String,
+    pub dir: String,
+    pub dependencies: Vec<DependencyNode>,
+}
+
+/// Resolved dependency graphs of the maven and gradle projects, the build tool is asked every
+/// time
+#[must_use]
+pub fn dependency_tree(projects: &[Project]) -> Vec<ProjectDependencies> {
+    projects
+        .iter()
+        .filter_map(|p| {
+            let dir = Path::new(&p.dir);
+            let dependencies = match &p.kind {
+                ProjectKind::Maven { executable } => maven::tree::load_graph(executable, dir)
+                    .inspect_err(|e| tracing::error!("Failed to load maven graph: {e:?}"))
+                    .ok()?,
+                ProjectKind::Gradle { executable, .. } => gradle::tree::load_graph(executable, dir)
+                    .inspect_err(|e| tracing::error!("Failed to load gradle graph: {e:?}"))
+                    .ok()?,
+                ProjectKind::Eclipse { .. }
+                | ProjectKind::Bazel { .. }
+                | ProjectKind::Bsp { .. }
+                | ProjectKind::Unknown => return None,
+            };
+            Some(ProjectDependencies {
+                project: p.artifact_id.clone(),
+                dir: p.dir.clone(),
+                dependencies,
+            })
+        })
+        .collect()
+}
+
+pub const COMMAND_COMPILE: &str = "java_lsp.compile";
+
+/// Errors of the compile task of the build tool, with a file only its maven module or gradle
+/// subproject is compiled. None when the build tool can not compile the project.
+#[must_use]
+pub fn build_compile(project: &Project, file: Option<&Path>) -> Option<Vec<CompileErrorMessage>> {
+    let dir = Path::new(&project.dir);
+    match &project.kind {
+        ProjectKind::Maven { executable } => {
+            let module = file
+                .and_then(|file| maven::modules::module_of(&project.modules, file))
+                .and_then(|module| module.dir.strip_prefix(dir).ok())
+                .filter(|module| !module.as_os_str().is_empty());
+            maven::compile::compile(executable, dir, module)
+        }
+        ProjectKind::Gradle { executable, .. } => {
+            let task = file.map_or_else(
+                || gradle::compile::COMPILE_JAVA.to_string(),
+                |file| gradle::compile::subproject_task(dir, file),
+            );
+            gradle::compile::compile_task(executable, &project.dir, &task)
+        }
+        ProjectKind::Eclipse { .. }
+        | ProjectKind::Bazel { .. }
+        | ProjectKind::Bsp { .. }
+        | ProjectKind::Unknown => None,
+    }
+}
+
 pub const COMMAND_CMD: &str = "java_lsp.cmd";
 pub const COMMAND_CMD_EDITOR: &str = "java_lsp.cmd.editor";
 pub fn cmd(
@@ -499,11 +694,15 @@ pub async fn get_tree(
                 None
             }
             Err(e) => {
-                eprintln!("Failed to load tree: {e:?}");
+                tracing::error!("Failed to load tree: {e:?}");
                 None
             }
         },
-        ProjectKind::Gradle { .. } | ProjectKind::Unknown => None,
+        ProjectKind::Gradle { .. }
+        | ProjectKind::Eclipse { .. }
+        | ProjectKind::Bazel { .. }
+        | ProjectKind::Bsp { .. }
+        | ProjectKind::Unknown => None,
     };
     report_maven_gradle_diagnostic(project_kind, con, diagnostics);
     out
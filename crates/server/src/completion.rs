@@ -1,19 +1,26 @@
 use std::{
     collections::HashMap,
+    fmt::Write,
     sync::{Arc, RwLock},
 };
 
 use ast::{
-    range::AstInRange,
-    types::{AstFile, AstImportUnit, AstMethodParameters, AstPoint, AstThing, AstTopLevel},
+    range::{AstInRange, GetRange},
+    types::{
+        AstClassConstructor, AstClassMethod, AstClassVariable, AstFile, AstImportUnit,
+        AstInterfaceConstant, AstInterfaceMethod, AstInterfaceMethodDefault, AstJType,
+        AstJTypeKind, AstMethodHeader, AstMethodParameters, AstPoint, AstThing,
+        AstThrowsDeclaration, AstTopLevel, AstTypeParameters,
+    },
+    visit::{self, Visitor},
 };
 use call_chain::get_call_chain;
 use document::Document;
-use dto::{Access, Class, ImportUnit, JType, Method, Parameter};
+use dto::{Access, Class, Field, ImportUnit, JType, Method, Parameter};
 use local_variable::{LocalVariable, VarFlags};
 use lsp_types::{
     CompletionItem, CompletionItemKind, CompletionItemLabelDetails, CompletionItemTag,
-    InsertTextFormat,
+    CompletionTextEdit, InsertTextFormat, Position, Range, TextEdit,
 };
 use my_string::{MyString, smol_str::SmolStr};
 
@@ -97,20 +104,7 @@ pub fn class_unpack(val: &Class, imports: &[ImportUnit], ast: &AstFile) -> Vec<C
                 }
                 i.access.contains(Access::Public)
             })
-            .map(|f| {
-                access(
-                    CompletionItem {
-                        label: f.name.to_string(),
-                        label_details: Some(CompletionItemLabelDetails {
-                            detail: Some(f.jtype.to_string()),
-                            ..Default::default()
-                        }),
-                        kind: Some(CompletionItemKind::FIELD),
-                        ..Default::default()
-                    },
-                    &f.access,
-                )
-            }),
+            .map(complete_field),
     );
 
     out.sort_by_key(|a| a.label.to_lowercase());
@@ -118,6 +112,21 @@ pub fn class_unpack(val: &Class, imports: &[ImportUnit], ast: &AstFile) -> Vec<C
     out
 }
 
+fn complete_field(f: &Field) -> CompletionItem {
+    access(
+        CompletionItem {
+            label: f.name.to_string(),
+            label_details: Some(CompletionItemLabelDetails {
+                detail: Some(f.jtype.to_string()),
+                ..Default::default()
+            }),
+            kind: Some(CompletionItemKind::FIELD),
+            ..Default::default()
+        },
+        &f.access,
+    )
+}
+
 fn complete_method(
     m: &Method,
     imports: &[ImportUnit],
@@ -330,43 +339,56 @@ pub fn classes(
     out
 }
 
+/// Methods of static imports and the static methods and fields of static star imports
 #[must_use]
-pub fn static_methods(
+pub fn static_members(
     ast: &AstFile,
     imports: &[ImportUnit],
     class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
 ) -> Vec<CompletionItem> {
-    imports
-        .iter()
-        .flat_map(|c| match c {
+    let Ok(cm) = class_map.read() else {
+        return vec![];
+    };
+    let is_static = |a: &Access| a.contains(Access::Static) && !a.contains(Access::Private);
+    let mut out = vec![];
+    for import in imports {
+        match import {
             ImportUnit::Prefix(_)
             | ImportUnit::Package(_)
             | ImportUnit::Class(_)
-            | ImportUnit::StaticClass(_) => vec![],
+            | ImportUnit::StaticClass(_) => (),
             ImportUnit::StaticClassMethod(c, m) => {
-                if let Ok(cm) = class_map.read()
-                    && let Some(class) = cm.get(c)
-                {
-                    return class
-                        .methods
-                        .iter()
-                        .filter(|f| f.name.as_ref().is_some_and(|i| i == m))
-                        .map(Method::clone)
-                        .collect();
+                if let Some(class) = cm.get(c) {
+                    out.extend(
+                        class
+                            .methods
+                            .iter()
+                            .filter(|f| f.name.as_ref().is_some_and(|i| i == m))
+                            .filter_map(|m| complete_method(m, imports, ast, None)),
+                    );
                 }
-                Vec::new()
             }
             ImportUnit::StaticPrefix(c) => {
-                if let Ok(cm) = class_map.read()
-                    && let Some(class) = cm.get(c)
-                {
-                    return class.methods.iter().map(Method::clone).collect();
+                if let Some(class) = cm.get(c) {
+                    out.extend(
+                        class
+                            .methods
+                            .iter()
+                            .filter(|m| is_static(&m.access))
+                            .filter_map(|m| complete_method(m, imports, ast, None)),
+                    );
+                    out.extend(
+                        class
+                            .fields
+                            .iter()
+                            .filter(|f| is_static(&f.access))
+                            .map(complete_field),
+                    );
                 }
-                Vec::new()
             }
-        })
-        .filter_map(|m| complete_method(&m, imports, ast, None))
-        .collect()
+        }
+    }
+    out
 }
 
 pub fn imports(
@@ -527,20 +549,174 @@ fn parameter_helper(
     }
 }
 
+/// Javadoc stub for the method or constructor below a line that starts a doc comment
+#[must_use]
+pub fn javadoc(document: &Document, point: &AstPoint) -> Option<CompletionItem> {
+    let line = document.rope.get_line(point.line)?.to_string();
+    let before: String = line.chars().take(point.col).collect();
+    if before.trim() != "/**" {
+        return None;
+    }
+    let after: String = line.chars().skip(point.col).collect();
+    let closed = after
+        .trim_start()
+        .strip_prefix("*/")
+        .map(|rest| after.chars().count() - rest.chars().count());
+    // The comment is not closed while typing, so the ast would not parse
+    let mut text = document.rope.clone();
+    if closed.is_none() {
+        text.insert(document.point_to_char(point), "*/");
+    }
+    let parsed = Document::setup(&text.to_string(), document.path.clone()).ok()?;
+    let mut finder = NextMember { point, found: None };
+    finder.visit_file(&parsed.ast);
+    let (_, Some(member)) = finder.found? else {
+        return None;
+    };
+
+    let indent: String = before.chars().take_while(|c| c.is_whitespace()).collect();
+    let mut tags = vec![];
+    if let Some(type_parameters) = member.type_parameters {
+        for parameter in &type_parameters.parameters {
+            tags.push(format!("@param <{}>", parameter.name.value));
+        }
+    }
+    for parameter in &member.parameters.parameters {
+        tags.push(format!("@param {}", parameter.name.value));
+    }
+    if member
+        .jtype
+        .is_some_and(|j| !matches!(j.value, AstJTypeKind::Void))
+    {
+        tags.push("@return".to_string());
+    }
+    if let Some(throws) = member.throws {
+        for jtype in &throws.parameters {
+            tags.push(format!("@throws {}", parsed.range_text(&jtype.range)));
+        }
+    }
+    let mut new_text = format!("/**\n{indent} * ${{1}}\n");
+    if !tags.is_empty() {
+        let _ = writeln!(new_text, "{indent} *");
+    }
+    for (i, tag) in tags.iter().enumerate() {
+        let _ = writeln!(new_text, "{indent} * {tag} ${{{}}}", i + 2);
+    }
+    let _ = write!(new_text, "{indent} */");
+
+    let start = before.chars().count() - before.trim_start().chars().count();
+    let end = point.col + closed.unwrap_or_default();
+    let line = u32::try_from(point.line).ok()?;
+    Some(CompletionItem {
+        label: "/** */".to_string(),
+        kind: Some(CompletionItemKind::SNIPPET),
+        detail: Some("Javadoc".to_string()),
+        filter_text: Some("/**".to_string()),
+        text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+            range: Range::new(
+                Position::new(line, u32::try_from(start).ok()?),
+                Position::new(line, u32::try_from(end).ok()?),
+            ),
+            new_text,
+        })),
+        insert_text_format: Some(InsertTextFormat::SNIPPET),
+        ..Default::default()
+    })
+}
+
+/// Header parts of a method or constructor that are documented
+struct MemberHeader<'a> {
+    type_parameters: Option<&'a AstTypeParameters>,
+    parameters: &'a AstMethodParameters,
+    jtype: Option<&'a AstJType>,
+    throws: Option<&'a AstThrowsDeclaration>,
+}
+
+/// Finds the first declaration after the point.
+/// Only methods and constructors have a header.
+struct NextMember<'a, 'b> {
+    point: &'b AstPoint,
+    found: Option<(AstPoint, Option<MemberHeader<'a>>)>,
+}
+
+impl<'a> NextMember<'a, '_> {
+    fn add(&mut self, start: AstPoint, header: Option<MemberHeader<'a>>) {
+        if start < *self.point {
+            return;
+        }
+        if self.found.as_ref().is_none_or(|(s, _)| start < *s) {
+            self.found = Some((start, header));
+        }
+    }
+
+    fn add_method(&mut self, start: AstPoint, header: &'a AstMethodHeader) {
+        self.add(
+            start,
+            Some(MemberHeader {
+                type_parameters: header.type_parameters.as_ref(),
+                parameters: &header.parameters,
+                jtype: Some(&header.jtype),
+                throws: header.throws.as_ref(),
+            }),
+        );
+    }
+}
+
+impl<'a> Visitor<'a> for NextMember<'a, '_> {
+    fn visit_thing(&mut self, thing: &'a AstThing) {
+        self.add(thing.get_range().start, None);
+        visit::walk_thing(self, thing);
+    }
+
+    fn visit_class_variable(&mut self, variable: &'a AstClassVariable) {
+        self.add(variable.range.start, None);
+    }
+
+    fn visit_interface_constant(&mut self, constant: &'a AstInterfaceConstant) {
+        self.add(constant.range.start, None);
+    }
+
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        self.add_method(method.range.start, &method.header);
+    }
+
+    fn visit_interface_method(&mut self, method: &'a AstInterfaceMethod) {
+        self.add_method(method.range.start, &method.header);
+    }
+
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        self.add_method(method.range.start, &method.header);
+    }
+
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        self.add(
+            constructor.range.start,
+            Some(MemberHeader {
+                type_parameters: constructor.header.type_parameters.as_ref(),
+                parameters: &constructor.header.parameters,
+                jtype: None,
+                throws: constructor.header.throws.as_ref(),
+            }),
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::literal_string_with_formatting_args)]
     use super::method_snippet;
-    use crate::completion::{Snippet, classes, complete_call_chain};
+    use crate::completion::{Snippet, classes, complete_call_chain, javadoc, static_members};
     use ast::types::{AstPoint, AstRange};
     use document::Document;
-    use dto::{Access, Class, ImportUnit, JType, Method, Parameter};
+    use dto::{Access, Class, Field, ImportUnit, JType, Method, Parameter};
+    use expect_test::expect;
     use local_variable::{LocalVariable, VarFlags};
     use lsp_types::{
         CompletionItem, CompletionItemKind, CompletionItemLabelDetails, InsertTextFormat, Position,
         Range, TextEdit,
     };
     use my_string::{MyString, smol_str::SmolStr};
+    use ropey::Rope;
     use std::{
         collections::HashMap,
         path::PathBuf,
@@ -716,10 +892,12 @@ public class Test {
         let method = Method {
             access: Access::Public,
             name: Some(SmolStr::new_inline("length")),
+            type_parameters: vec![],
             parameters: vec![],
             ret: JType::Int,
             throws: vec![],
             source: None,
+            annotations: vec![],
         };
         let out = method_snippet(&method, None);
         assert_eq!(out, Some(Snippet::Simple("length()".to_string())));
@@ -730,13 +908,16 @@ public class Test {
         let method = Method {
             access: Access::Public,
             name: Some(SmolStr::new_inline("compute")),
+            type_parameters: vec![],
             parameters: vec![Parameter {
                 name: None,
                 jtype: JType::Int,
+                annotations: vec![],
             }],
             ret: JType::Int,
             throws: vec![],
             source: None,
+            annotations: vec![],
         };
         let out = method_snippet(&method, None);
         assert_eq!(out, Some(Snippet::Simple("compute(${1:int})".to_string())));
@@ -747,13 +928,16 @@ public class Test {
         let method = Method {
             access: Access::Public,
             name: None,
+            type_parameters: vec![],
             parameters: vec![Parameter {
                 name: None,
                 jtype: JType::Int,
+                annotations: vec![],
             }],
             ret: JType::Int,
             throws: vec![],
             source: None,
+            annotations: vec![],
         };
         let out = method_snippet(&method, Some(&SmolStr::new_inline("Computer")));
         assert_eq!(out, Some(Snippet::Simple("Computer(${1:int})".to_string())));
@@ -764,19 +948,23 @@ public class Test {
         let method = Method {
             access: Access::Public,
             name: Some(SmolStr::new_inline("split")),
+            type_parameters: vec![],
             parameters: vec![
                 Parameter {
                     name: None,
                     jtype: JType::Class(SmolStr::new_inline("java.lang.String")),
+                    annotations: vec![],
                 },
                 Parameter {
                     name: None,
                     jtype: JType::Int,
+                    annotations: vec![],
                 },
             ],
             ret: JType::Int,
             throws: vec![],
             source: None,
+            annotations: vec![],
         };
         let out = method_snippet(&method, None);
         assert_eq!(
@@ -919,4 +1107,148 @@ public class Test {
             }]
         );
     }
+
+    #[test]
+    fn static_star_import_members() {
+        let mut class_map: HashMap<MyString, Class> = HashMap::new();
+        class_map.insert(
+            SmolStr::new_inline("java.lang.Math"),
+            Class {
+                class_path: SmolStr::new_inline("java.lang.Math"),
+                access: Access::Public,
+                name: SmolStr::new_inline("Math"),
+                methods: vec![
+                    Method {
+                        access: Access::Public | Access::Static,
+                        name: Some(SmolStr::new_inline("abs")),
+                        ret: JType::Int,
+                        ..Default::default()
+                    },
+                    Method {
+                        access: Access::Private | Access::Static,
+                        name: Some(SmolStr::new_inline("check")),
+                        ..Default::default()
+                    },
+                    Method {
+                        access: Access::Public,
+                        name: Some(SmolStr::new_inline("toString")),
+                        ..Default::default()
+                    },
+                ],
+                fields: vec![
+                    Field {
+                        access: Access::Public | Access::Static | Access::Final,
+                        name: SmolStr::new_inline("PI"),
+                        jtype: JType::Double,
+                        ..Default::default()
+                    },
+                    Field {
+                        access: Access::Public,
+                        name: SmolStr::new_inline("value"),
+                        jtype: JType::Int,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+        let class_map = Arc::new(RwLock::new(class_map));
+        let doc = Document::setup("public class Test {}", PathBuf::new()).unwrap();
+
+        let out = static_members(
+            &doc.ast,
+            &[ImportUnit::StaticPrefix(SmolStr::new_inline(
+                "java.lang.Math",
+            ))],
+            &class_map,
+        );
+        let labels: Vec<&str> = out.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, ["abs", "PI"]);
+    }
+
+    #[test]
+    fn javadoc_method() {
+        let content = "
+package ch.emilycares;
+public class Test {
+    /**
+    public <T> int add(int a, T b) throws java.io.IOException, IllegalStateException {
+        return a;
+    }
+}
+";
+        let mut doc = Document::setup(&content.replace("/**", "/***/"), PathBuf::new()).unwrap();
+        // The open comment does not parse, the ast of the last valid version is kept
+        doc.rope = Rope::from_str(content);
+        let out = javadoc(&doc, &AstPoint::new(3, 7));
+        let expected = expect![[r#"
+            Some(
+                Edit(
+                    TextEdit {
+                        range: Range {
+                            start: Position {
+                                line: 3,
+                                character: 4,
+                            },
+                            end: Position {
+                                line: 3,
+                                character: 7,
+                            },
+                        },
+                        new_text: "/**\n     * ${1}\n     *\n     * @param <T> ${2}\n     * @param a ${3}\n     * @param b ${4}\n     * @return ${5}\n     * @throws java.io.IOException ${6}\n     * @throws IllegalStateException ${7}\n     */",
+                    },
+                ),
+            )
+        "#]];
+        expected.assert_debug_eq(&out.and_then(|i| i.text_edit));
+    }
+
+    #[test]
+    fn javadoc_closed_constructor() {
+        let content = "
+package ch.emilycares;
+public class Test {
+    /** */
+    public Test(String name) {
+    }
+}
+";
+        let doc = Document::setup(content, PathBuf::new()).unwrap();
+        let out = javadoc(&doc, &AstPoint::new(3, 7));
+        let expected = expect![[r#"
+            Some(
+                Edit(
+                    TextEdit {
+                        range: Range {
+                            start: Position {
+                                line: 3,
+                                character: 4,
+                            },
+                            end: Position {
+                                line: 3,
+                                character: 10,
+                            },
+                        },
+                        new_text: "/**\n     * ${1}\n     *\n     * @param name ${2}\n     */",
+                    },
+                ),
+            )
+        "#]];
+        expected.assert_debug_eq(&out.and_then(|i| i.text_edit));
+    }
+
+    #[test]
+    fn javadoc_field() {
+        let content = "
+package ch.emilycares;
+public class Test {
+    /**
+    private String name;
+    public void hello() {}
+}
+";
+        let mut doc = Document::setup(&content.replace("/**", "/***/"), PathBuf::new()).unwrap();
+        doc.rope = Rope::from_str(content);
+        assert!(javadoc(&doc, &AstPoint::new(3, 7)).is_none());
+    }
 }
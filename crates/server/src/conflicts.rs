@@ -0,0 +1,775 @@
+//! Declarations that conflict with other declarations
+//!
+//! The diagnostics point to the other declaration with related information.
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+use ast::{
+    types::{
+        AstAvailability, AstFile, AstIdentifier, AstImportUnit, AstJType, AstJTypeKind,
+        AstMethodHeader, AstMethodParameter, AstMethodParameterFlags, AstRange, AstThing,
+        AstTopLevel,
+    },
+    visit::{self, Visitor},
+};
+use document::Document;
+use dto::{Access, Class, ImportUnit, JType, Method, SuperClass};
+use lsp_extra::{SERVER_NAME, source_to_uri, to_lsp_range};
+use lsp_types::{Diagnostic, DiagnosticRelatedInformation, Location, NumberOrString, Uri};
+use my_string::MyString;
+
+use crate::{
+    diagnostics::{Declarations, default_severity, resolve_class_path, thing_name, thing_supers},
+    type_check::{implicit_super, thing_methods},
+};
+
+/// Diagnostic code of classes, methods and fields that are declared twice
+pub const DUPLICATE: &str = "duplicate_definition";
+/// Diagnostic code of single type imports with the same simple name and of types that several
+/// on demand imports provide
+pub const AMBIGUOUS_IMPORT: &str = "ambiguous_import";
+/// Diagnostic code of methods that can not override the method of a super type
+pub const INVALID_OVERRIDE: &str = "invalid_override";
+
+struct Conflict {
+    range: AstRange,
+    message: String,
+    code: &'static str,
+    related: Vec<(Location, String)>,
+}
+
+/// Duplicate definitions, ambiguous imports and types and invalid overrides of the file at `uri`
+#[must_use]
+pub fn conflicts(
+    ast: &AstFile,
+    uri: &Uri,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<Diagnostic> {
+    let mut declarations = Declarations::default();
+    declarations.visit_file(ast);
+    let mut out = ambiguous_imports(ast, uri);
+    let things: Vec<_> = ast
+        .top
+        .iter()
+        .filter_map(|top| match top {
+            AstTopLevel::Thing(thing) => Some(&**thing),
+            _ => None,
+        })
+        .collect();
+    out.extend(duplicates(
+        things.iter().map(|thing| {
+            (
+                thing_name(thing),
+                format!("class {}", thing_name(thing).value),
+            )
+        }),
+        |key| format!("duplicate {}", key.replacen(' ', ": ", 1)),
+        uri,
+    ));
+    let imports = imports::imports(ast);
+    let shadowing = imports
+        .iter()
+        .filter(|i| {
+            matches!(
+                i,
+                ImportUnit::Class(_) | ImportUnit::StaticClass(_) | ImportUnit::Package(_)
+            )
+        })
+        .cloned()
+        .collect();
+    let mut finder = Finder {
+        uri,
+        imports,
+        shadowing,
+        star_imports: star_imports(ast),
+        type_parameters: &declarations.type_parameters,
+        things: &declarations.things,
+        class_map,
+        qualified: false,
+        found: out,
+    };
+    finder.visit_file(ast);
+    finder
+        .found
+        .into_iter()
+        .filter_map(|conflict| {
+            Some(Diagnostic {
+                range: to_lsp_range(&conflict.range).ok()?,
+                severity: default_severity(conflict.code),
+                code: Some(NumberOrString::String(conflict.code.to_string())),
+                source: Some(SERVER_NAME.to_string()),
+                message: conflict.message,
+                related_information: (!conflict.related.is_empty()).then(|| {
+                    conflict
+                        .related
+                        .into_iter()
+                        .map(|(location, message)| DiagnosticRelatedInformation {
+                            location,
+                            message,
+                        })
+                        .collect()
+                }),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+fn location(uri: &Uri, range: &AstRange) -> Option<Location> {
+    Some(Location {
+        uri: uri.clone(),
+        range: to_lsp_range(range).ok()?,
+    })
+}
+
+/// Single type imports of different classes with the same simple name
+fn ambiguous_imports(ast: &AstFile, uri: &Uri) -> Vec<Conflict> {
+    let mut out = vec![];
+    let mut imported: HashMap<&str, (&AstRange, &AstIdentifier)> = HashMap::new();
+    for top in &ast.top {
+        let AstTopLevel::Import(import) = top else {
+            continue;
+        };
+        let AstImportUnit::Class(class) = &import.unit else {
+            continue;
+        };
+        let name = class
+            .value
+            .rsplit_once('.')
+            .map_or(class.value.as_str(), |(_, n)| n);
+        let Some((range, first)) = imported.get(name) else {
+            imported.insert(name, (&import.range, class));
+            continue;
+        };
+        if first.value == class.value {
+            continue;
+        }
+        out.push(Conflict {
+            range: import.range,
+            message: format!(
+                "a type with the same simple name {name} is already defined by the single-type-import of {}",
+                first.value
+            ),
+            code: AMBIGUOUS_IMPORT,
+            related: [(*range, &first.value), (&import.range, &class.value)]
+                .into_iter()
+                .filter_map(|(range, class)| {
+                    Some((location(uri, range)?, format!("'{class}' is imported here")))
+                })
+                .collect(),
+        });
+    }
+    out
+}
+
+/// Ranges of the on demand imports by package
+fn star_imports(ast: &AstFile) -> HashMap<&str, &AstRange> {
+    ast.top
+        .iter()
+        .filter_map(|top| match top {
+            AstTopLevel::Import(import) => match &import.unit {
+                AstImportUnit::Prefix(p) | AstImportUnit::StaticPrefix(p) => {
+                    Some((p.value.as_str(), &import.range))
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Declarations with a key that was already declared
+fn duplicates<'a>(
+    declarations: impl Iterator<Item = (&'a AstIdentifier, String)>,
+    message: impl Fn(&str) -> String,
+    uri: &Uri,
+) -> Vec<Conflict> {
+    let mut out = vec![];
+    let mut first: HashMap<String, &AstIdentifier> = HashMap::new();
+    for (ident, key) in declarations {
+        let Some(previous) = first.get(&key) else {
+            first.insert(key, ident);
+            continue;
+        };
+        out.push(Conflict {
+            range: ident.range,
+            message: message(&key),
+            code: DUPLICATE,
+            related: location(uri, &previous.range)
+                .map(|l| (l, format!("{key} is first defined here")))
+                .into_iter()
+                .collect(),
+        });
+    }
+    out
+}
+
+struct Finder<'a, 'c> {
+    uri: &'c Uri,
+    imports: Vec<ImportUnit>,
+    /// Single type imports and the package, their classes shadow the on demand imports
+    shadowing: Vec<ImportUnit>,
+    star_imports: HashMap<&'a str, &'a AstRange>,
+    type_parameters: &'c HashSet<&'a str>,
+    things: &'c HashMap<&'a str, &'a AstThing>,
+    class_map: &'c Arc<RwLock<HashMap<MyString, Class>>>,
+    /// The next type is the inner part of `Outer.Inner`
+    qualified: bool,
+    found: Vec<Conflict>,
+}
+
+impl<'a> Finder<'a, '_> {
+    /// Erased simple name of a type, None for type parameters
+    fn erasure(&self, jtype: &AstJType) -> Option<String> {
+        match &jtype.value {
+            AstJTypeKind::Class(ident)
+            | AstJTypeKind::ClassOrPackage(ident)
+            | AstJTypeKind::Generic(ident, _) => {
+                if self.type_parameters.contains(ident.value.as_str()) {
+                    return None;
+                }
+                Some(simple_name(&ident.value).to_string())
+            }
+            AstJTypeKind::Access { inner, .. } => self.erasure(inner),
+            AstJTypeKind::Array(inner) => Some(format!("{}[]", self.erasure(inner)?)),
+            AstJTypeKind::Wildcard | AstJTypeKind::Var => None,
+            kind => Some(kind.to_string()),
+        }
+    }
+
+    fn parameter_erasure(&self, parameter: &AstMethodParameter) -> Option<String> {
+        let erasure = self.erasure(&parameter.jtype)?;
+        if parameter.flags.contains(AstMethodParameterFlags::Variatic) {
+            return Some(format!("{erasure}[]"));
+        }
+        Some(erasure)
+    }
+
+    /// `name(int, String)` of a method
+    fn signature(&self, name: &str, parameters: &[AstMethodParameter]) -> String {
+        let parameters: Vec<_> = parameters
+            .iter()
+            .map(|p| {
+                self.parameter_erasure(p)
+                    .unwrap_or_else(|| p.jtype.value.to_string())
+            })
+            .collect();
+        format!("{name}({})", parameters.join(", "))
+    }
+
+    fn duplicate_members(&mut self, thing: &'a AstThing) {
+        let kind = thing_kind(thing);
+        let name = &thing_name(thing).value;
+        let (variables, constructors, inner): (Vec<&AstIdentifier>, _, _) = match thing {
+            AstThing::Class(class) => (
+                class.block.variables.iter().map(|v| &v.name).collect(),
+                class.block.constructors.as_slice(),
+                class.block.inner.as_slice(),
+            ),
+            AstThing::Record(record) => (
+                record
+                    .record_entries
+                    .entries
+                    .iter()
+                    .map(|e| &e.name)
+                    .chain(record.block.variables.iter().map(|v| &v.name))
+                    .collect(),
+                record.block.constructors.as_slice(),
+                record.block.inner.as_slice(),
+            ),
+            AstThing::Enumeration(enumeration) => (
+                enumeration
+                    .variants
+                    .iter()
+                    .map(|v| &v.name)
+                    .chain(enumeration.variables.iter().map(|v| &v.name))
+                    .collect(),
+                enumeration.constructors.as_slice(),
+                enumeration.inner.as_slice(),
+            ),
+            AstThing::Interface(interface) => (
+                interface.constants.iter().map(|c| &c.name).collect(),
+                &[][..],
+                interface.inner.as_slice(),
+            ),
+            AstThing::Annotation(annotation) => (vec![], &[][..], annotation.inner.as_slice()),
+        };
+        let variables = variables
+            .into_iter()
+            .map(|ident| (ident, format!("variable {}", ident.value)));
+        let methods = thing_methods(thing).into_iter().map(|header| {
+            (
+                &header.name,
+                format!(
+                    "method {}",
+                    self.signature(&header.name.value, &header.parameters.parameters)
+                ),
+            )
+        });
+        let constructors = constructors.iter().map(|constructor| {
+            (
+                &constructor.header.name,
+                format!(
+                    "constructor {}",
+                    self.signature(
+                        &constructor.header.name.value,
+                        &constructor.header.parameters.parameters
+                    )
+                ),
+            )
+        });
+        let inner = inner.iter().map(|thing| {
+            (
+                thing_name(thing),
+                format!("class {}", thing_name(thing).value),
+            )
+        });
+        let found = duplicates(
+            variables.chain(methods).chain(constructors).chain(inner),
+            |key| format!("{key} is already defined in {kind} {name}"),
+            self.uri,
+        );
+        self.found.extend(found);
+    }
+
+    /// The classes of the super types with their sources. Incomplete when one of them is
+    /// not in the class map.
+    fn super_classes(&self, thing: &AstThing) -> (Vec<Class>, bool) {
+        let mut complete = true;
+        let mut pending = vec![];
+        for s in thing_supers(thing)
+            .into_iter()
+            .map(|s| s.name)
+            .chain(std::iter::once(implicit_super(thing).to_string()))
+        {
+            match resolve_class_path(&s, &self.imports, self.class_map) {
+                Some(class_path) => pending.push(class_path),
+                None => complete = false,
+            }
+        }
+        let mut out: Vec<Class> = vec![];
+        while let Some(class_path) = pending.pop() {
+            if out.iter().any(|c| c.class_path == class_path) {
+                continue;
+            }
+            let Some(class) = self
+                .class_map
+                .read()
+                .ok()
+                .and_then(|class_map| class_map.get(&class_path).cloned())
+            else {
+                complete = false;
+                continue;
+            };
+            for s in std::iter::once(&class.super_class).chain(&class.super_interfaces) {
+                match s {
+                    SuperClass::None => (),
+                    SuperClass::ClassPath(p) => pending.push(p.clone()),
+                    SuperClass::Name(n) => {
+                        match resolve_class_path(n, &class.imports, self.class_map) {
+                            Some(p) => pending.push(p),
+                            None => complete = false,
+                        }
+                    }
+                }
+            }
+            out.push(class);
+        }
+        (out, complete)
+    }
+
+    fn overrides(&mut self, thing: &'a AstThing) {
+        let headers = thing_methods(thing);
+        if headers.is_empty() {
+            return;
+        }
+        let (classes, complete) = self.super_classes(thing);
+        let interface = matches!(thing, AstThing::Interface(_));
+        for header in headers {
+            let overridden = classes.iter().find_map(|class| {
+                class
+                    .methods
+                    .iter()
+                    .find(|m| !m.access.contains(Access::Private) && self.same_signature(header, m))
+                    .map(|m| (class, m))
+            });
+            let Some((class, method)) = overridden else {
+                let annotated = header
+                    .annotated
+                    .iter()
+                    .any(|a| simple_name(&a.name.value) == "Override");
+                if annotated && complete && !matches!(thing, AstThing::Record(_)) {
+                    self.found.push(Conflict {
+                        range: header.name.range,
+                        message: "method does not override or implement a method from a supertype"
+                            .to_string(),
+                        code: INVALID_OVERRIDE,
+                        related: vec![],
+                    });
+                }
+                continue;
+            };
+            let is_static = header.availability.contains(AstAvailability::Static);
+            let reason = if method.access.contains(Access::Final) {
+                "overridden method is final".to_string()
+            } else if method.access.contains(Access::Static) && !is_static {
+                "overridden method is static".to_string()
+            } else if is_static && !method.access.contains(Access::Static) {
+                "overriding method is static".to_string()
+            } else if !is_static
+                && access_rank(&header.availability, interface)
+                    < dto_access_rank(&method.access, class.access.contains(Access::Interface))
+            {
+                let was =
+                    match dto_access_rank(&method.access, class.access.contains(Access::Interface))
+                    {
+                        3 => "public",
+                        2 => "protected",
+                        _ => "package-private",
+                    };
+                format!("attempting to assign weaker access privileges; was {was}")
+            } else {
+                continue;
+            };
+            let name = &header.name.value;
+            let super_name = simple_name(&class.class_path);
+            self.found.push(Conflict {
+                range: header.name.range,
+                message: format!(
+                    "'{}' in {} cannot override '{name}' in {super_name}; {reason}",
+                    self.signature(name, &header.parameters.parameters),
+                    thing_name(thing).value,
+                ),
+                code: INVALID_OVERRIDE,
+                related: method_location(class, method)
+                    .map(|l| (l, format!("overridden method '{name}' in {super_name}")))
+                    .into_iter()
+                    .collect(),
+            });
+        }
+    }
+
+    /// A simple type name that more than one on demand import provides
+    fn ambiguous_type(&mut self, ident: &AstIdentifier) {
+        let name = ident.value.as_str();
+        if std::mem::take(&mut self.qualified)
+            || name.contains('.')
+            || self.type_parameters.contains(name)
+            || self.things.contains_key(name)
+        {
+            return;
+        }
+        let candidates = tyres::star_imported(name, &self.imports, self.class_map);
+        let [first, second, ..] = candidates.as_slice() else {
+            return;
+        };
+        if tyres::is_imported(name, &self.shadowing, self.class_map).is_some() {
+            return;
+        }
+        let display = |class_path: &str| class_path.replace('$', ".");
+        self.found.push(Conflict {
+            range: ident.range,
+            message: format!(
+                "reference to {name} is ambiguous, both class {} and class {} match",
+                display(first),
+                display(second)
+            ),
+            code: AMBIGUOUS_IMPORT,
+            related: candidates
+                .iter()
+                .filter_map(|class_path| {
+                    let package = &class_path[..class_path.len() - name.len() - 1];
+                    let range = self.star_imports.get(package)?;
+                    Some((
+                        location(self.uri, range)?,
+                        format!("'{package}.*' is imported here"),
+                    ))
+                })
+                .collect(),
+        });
+    }
+
+    fn same_signature(&self, header: &AstMethodHeader, method: &Method) -> bool {
+        method
+            .name
+            .as_ref()
+            .is_some_and(|n| *n == header.name.value)
+            && method.parameters.len() == header.parameters.parameters.len()
+            && method
+                .parameters
+                .iter()
+                .zip(&header.parameters.parameters)
+                .all(
+                    |(p, a)| match (dto_erasure(&p.jtype), self.parameter_erasure(a)) {
+                        (Some(p), Some(a)) => p == a,
+                        _ => true,
+                    },
+                )
+    }
+}
+
+impl<'a> Visitor<'a> for Finder<'a, '_> {
+    fn visit_thing(&mut self, thing: &'a AstThing) {
+        self.duplicate_members(thing);
+        self.overrides(thing);
+        visit::walk_thing(self, thing);
+    }
+
+    fn visit_jtype(&mut self, jtype: &'a AstJType) {
+        match &jtype.value {
+            AstJTypeKind::Class(ident) | AstJTypeKind::Generic(ident, _) => {
+                self.ambiguous_type(ident);
+            }
+            AstJTypeKind::Access { base, inner } => {
+                self.visit_jtype(base);
+                self.qualified = true;
+                self.visit_jtype(inner);
+                self.qualified = false;
+                return;
+            }
+            _ => (),
+        }
+        visit::walk_jtype(self, jtype);
+    }
+}
+
+const fn thing_kind(thing: &AstThing) -> &'static str {
+    match thing {
+        AstThing::Class(_) => "class",
+        AstThing::Record(_) => "record",
+        AstThing::Interface(_) => "interface",
+        AstThing::Enumeration(_) => "enum",
+        AstThing::Annotation(_) => "@interface",
+    }
+}
+
+fn simple_name(name: &str) -> &str {
+    name.rsplit(['.', '$']).next().unwrap_or(name)
+}
+
+fn dto_erasure(jtype: &JType) -> Option<String> {
+    match jtype {
+        JType::Class(c) | JType::ClassOrPackage(c) | JType::Generic(c, _) => {
+            Some(simple_name(c).to_string())
+        }
+        JType::Access { inner, .. } => dto_erasure(inner),
+        JType::Array(inner) => Some(format!("{}[]", dto_erasure(inner)?)),
+        JType::Parameter(_) | JType::Wildcard | JType::Var | JType::Extends { .. } => None,
+        primitive => Some(primitive.to_string()),
+    }
+}
+
+/// private < package < protected < public
+const fn access_rank(availability: &AstAvailability, interface: bool) -> u8 {
+    if interface || availability.contains(AstAvailability::Public) {
+        3
+    } else if availability.contains(AstAvailability::Protected) {
+        2
+    } else if availability.contains(AstAvailability::Private) {
+        0
+    } else {
+        1
+    }
+}
+
+const fn dto_access_rank(access: &Access, interface: bool) -> u8 {
+    if interface || access.contains(Access::Public) {
+        3
+    } else if access.contains(Access::Protected) {
+        2
+    } else if access.contains(Access::Private) {
+        0
+    } else {
+        1
+    }
+}
+
+/// Location of a method in the source of its class
+fn method_location(class: &Class, method: &Method) -> Option<Location> {
+    let source = method.source.clone().or_else(|| class.get_source())?;
+    let document = Document::setup_read(PathBuf::from(source.as_str())).ok()?;
+    let mut ranges = vec![];
+    position::get_method_position(
+        &document.ast,
+        method.name.as_deref(),
+        Some(method.parameters.len()),
+        &mut ranges,
+    );
+    location(&source_to_uri(&source).ok()?, &ranges.first()?.range)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr};
+
+    use dto::{Parameter, SourceDestination};
+    use expect_test::expect;
+
+    use super::*;
+
+    fn messages(content: &str) -> Vec<String> {
+        let document = Document::setup(content, PathBuf::from_str("/Test.java").unwrap()).unwrap();
+        let uri = Uri::from_str("file:///Test.java").unwrap();
+        conflicts(&document.ast, &uri, &get_class_map())
+            .into_iter()
+            .map(|d| {
+                let related: Vec<_> = d
+                    .related_information
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|r| format!("{}: {}", r.location.range.start.line, r.message))
+                    .collect();
+                format!(
+                    "{}: {} [{}]",
+                    d.range.start.line,
+                    d.message,
+                    related.join(", ")
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn duplicate_definitions() {
+        let content = r"
+package ch.emilycares;
+import java.util.List;
+import java.awt.List;
+public class Test {
+    private int a;
+    private String a;
+    void hello(int b) {}
+    void hello(int c) {}
+    void hello(String b) {}
+    Test() {}
+    Test() {}
+    class Inner {}
+    interface Inner {}
+}
+class Test {}
+";
+        let expected = expect![[r#"
+            [
+                "3: a type with the same simple name List is already defined by the single-type-import of java.util.List [2: 'java.util.List' is imported here, 3: 'java.awt.List' is imported here]",
+                "15: duplicate class: Test [4: class Test is first defined here]",
+                "6: variable a is already defined in class Test [5: variable a is first defined here]",
+                "8: method hello(int) is already defined in class Test [7: method hello(int) is first defined here]",
+                "11: constructor Test() is already defined in class Test [10: constructor Test() is first defined here]",
+                "13: class Inner is already defined in class Test [12: class Inner is first defined here]",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content));
+    }
+
+    #[test]
+    fn ambiguous_star_imports() {
+        let content = r"
+package ch.emilycares;
+import java.util.*;
+import java.awt.*;
+import java.util.Map.*;
+public class Test {
+    List<String> items;
+    java.util.List<String> qualified;
+    Map.Entry<String, String> entry;
+    Entry<String, String> nested;
+}
+";
+        let expected = expect![[r#"
+            [
+                "6: reference to List is ambiguous, both class java.awt.List and class java.util.List match [3: 'java.awt.*' is imported here, 2: 'java.util.*' is imported here]",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content));
+        let shadowed = content.replace(
+            "import java.awt.*;",
+            "import java.awt.*;\nimport java.util.List;",
+        );
+        assert!(messages(&shadowed).is_empty());
+    }
+
+    #[test]
+    fn invalid_overrides() {
+        let content = r"
+package ch.emilycares;
+public class Test extends Base {
+    void fin() {}
+    void weaker() {}
+    static void instance() {}
+    public void fine() {}
+    @Override
+    public void missing() {}
+    @Override
+    public String toString() {
+        return null;
+    }
+}
+";
+        let expected = expect![[r#"
+            [
+                "3: 'fin()' in Test cannot override 'fin' in Base; overridden method is final []",
+                "4: 'weaker()' in Test cannot override 'weaker' in Base; attempting to assign weaker access privileges; was public []",
+                "5: 'instance()' in Test cannot override 'instance' in Base; overriding method is static []",
+                "8: method does not override or implement a method from a supertype []",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content));
+    }
+
+    fn get_class_map() -> Arc<RwLock<HashMap<MyString, Class>>> {
+        let method = |name: &str, access: Access| Method {
+            access,
+            name: Some(name.into()),
+            ret: JType::Void,
+            ..Default::default()
+        };
+        let base = Class {
+            class_path: "ch.emilycares.Base".into(),
+            name: "Base".into(),
+            access: Access::Public,
+            super_class: SuperClass::ClassPath("java.lang.Object".into()),
+            methods: vec![
+                method("fin", Access::Final),
+                method("weaker", Access::Public),
+                method("instance", Access::Public),
+                method("fine", Access::Protected),
+                Method {
+                    parameters: vec![Parameter {
+                        name: None,
+                        jtype: JType::Int,
+                        annotations: vec![],
+                    }],
+                    ..method("missing", Access::Public)
+                },
+            ],
+            source: SourceDestination::None,
+            ..Default::default()
+        };
+        let object = Class {
+            class_path: "java.lang.Object".into(),
+            name: "Object".into(),
+            access: Access::Public,
+            methods: vec![Method {
+                ret: JType::Class("java.lang.String".into()),
+                ..method("toString", Access::Public)
+            }],
+            ..Default::default()
+        };
+        let mut class_map = HashMap::from([
+            (base.class_path.clone(), base),
+            (object.class_path.clone(), object),
+        ]);
+        for class_path in [
+            "java.util.List",
+            "java.util.Map",
+            "java.util.Map$Entry",
+            "java.awt.List",
+        ] {
+            class_map.insert(class_path.into(), Class::default());
+        }
+        Arc::new(RwLock::new(class_map))
+    }
+}
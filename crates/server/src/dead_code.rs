@@ -0,0 +1,340 @@
+//! Workspace report of the classes and members that nothing references
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use ast::{
+    lexer::Token,
+    types::{
+        AstAnnotated, AstAvailability, AstClassMethod, AstClassVariable, AstFile, AstIdentifier,
+        AstThing, AstTopLevel,
+    },
+};
+use document::{Document, read_document_or_open_class, source_set::SourceSet};
+use dto::{Class, SourceDestination};
+use lsp_extra::{source_to_uri, to_lsp_range};
+use lsp_types::{Range, Uri};
+use my_string::MyString;
+use serde::Serialize;
+
+use crate::{
+    diagnostics::{SERIALIZATION, read_names, uses_lombok},
+    name_index::NameIndex,
+};
+
+/// Command that returns the dead code of the workspace
+pub const COMMAND_DEAD_CODE: &str = "java_lsp.deadCode";
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadCode {
+    pub uri: Uri,
+    pub range: Range,
+    /// `class`, `field` or `method`
+    pub kind: &'static str,
+    pub name: String,
+    pub message: String,
+}
+
+/// Declaration that is not visible outside of its package
+#[derive(Debug)]
+struct Candidate<'a> {
+    ident: &'a AstIdentifier,
+    kind: &'static str,
+    private: bool,
+}
+
+/// Dead code of the main sources of the workspace, sorted by file
+#[must_use]
+pub fn dead_code(
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+    names: &NameIndex,
+    annotations: &[String],
+    cancelled: &dyn Fn() -> bool,
+) -> Vec<DeadCode> {
+    let mut sources: Vec<MyString> = class_map.read().map_or_else(
+        |_| vec![],
+        |class_map| {
+            class_map
+                .values()
+                .filter_map(|class| match &class.source {
+                    SourceDestination::Here(source) => Some(source.clone()),
+                    _ => None,
+                })
+                .filter(|source| SourceSet::of(Path::new(source.as_str())) == SourceSet::Main)
+                .collect()
+        },
+    );
+    sources.sort_unstable();
+    sources.dedup();
+    let mut packages: HashMap<&Path, Vec<MyString>> = HashMap::new();
+    for source in &sources {
+        if let Some(folder) = Path::new(source.as_str()).parent() {
+            packages.entry(folder).or_default().push(source.clone());
+        }
+    }
+
+    let mut out = vec![];
+    for source in sources.iter().take_while(|_| !cancelled()) {
+        let Ok(document) = read_document_or_open_class(source, document_map) else {
+            continue;
+        };
+        let Ok(uri) = source_to_uri(source.as_str()) else {
+            continue;
+        };
+        let others: Vec<MyString> = Path::new(source.as_str())
+            .parent()
+            .and_then(|folder| packages.get(folder))
+            .map(|package| package.iter().filter(|s| *s != source).cloned().collect())
+            .unwrap_or_default();
+        out.extend(
+            dead_in_file(&document, annotations, |name| {
+                let mut mentioning = others.clone();
+                names.retain_mentioning(&mut mentioning, name);
+                !mentioning.is_empty()
+            })
+            .into_iter()
+            .filter_map(|candidate| {
+                Some(DeadCode {
+                    uri: uri.clone(),
+                    range: to_lsp_range(&candidate.ident.range).ok()?,
+                    kind: candidate.kind,
+                    name: candidate.ident.value.to_string(),
+                    message: format!(
+                        "{} '{}' is never used",
+                        candidate.kind, candidate.ident.value
+                    ),
+                })
+            }),
+        );
+    }
+    out
+}
+
+/// Declarations of the file that are dead, `package_uses` tells if another file of the package
+/// mentions a name
+fn dead_in_file<'a>(
+    document: &'a Document,
+    annotations: &[String],
+    package_uses: impl Fn(&str) -> bool,
+) -> Vec<Candidate<'a>> {
+    let ast = &document.ast;
+    let mut finder = CandidateFinder {
+        annotations,
+        fields: !uses_lombok(ast),
+        found: vec![],
+    };
+    finder.file(ast);
+    if finder.found.is_empty() {
+        return vec![];
+    }
+    let reads = read_names(ast);
+    let mut mentions: HashMap<&str, usize> = HashMap::new();
+    for token in &document.tokens {
+        if let Token::Identifier(name) = &token.token {
+            *mentions.entry(name.as_str()).or_default() += 1;
+        }
+    }
+    let constructors = constructor_names(ast);
+    finder
+        .found
+        .into_iter()
+        .filter(|candidate| {
+            let name = candidate.ident.value.as_str();
+            let used = if candidate.kind == "class" {
+                // The declaration and the constructors mention the name of a class
+                let declared = 1 + constructors.iter().filter(|c| **c == name).count();
+                mentions.get(name).copied().unwrap_or_default() > declared
+            } else {
+                reads.contains(name)
+            };
+            !used && (candidate.private || !package_uses(name))
+        })
+        .collect()
+}
+
+fn constructor_names(ast: &AstFile) -> Vec<&str> {
+    fn collect<'a>(things: &'a [AstThing], out: &mut Vec<&'a str>) {
+        for thing in things {
+            let (constructors, inner) = match thing {
+                AstThing::Class(c) => (c.block.constructors.as_slice(), &c.block.inner),
+                AstThing::Record(r) => (r.block.constructors.as_slice(), &r.block.inner),
+                AstThing::Enumeration(e) => (e.constructors.as_slice(), &e.inner),
+                AstThing::Interface(i) => (&[][..], &i.inner),
+                AstThing::Annotation(a) => (&[][..], &a.inner),
+            };
+            out.extend(constructors.iter().map(|c| c.header.name.value.as_str()));
+            collect(inner, out);
+        }
+    }
+    let mut out = vec![];
+    for top in &ast.top {
+        if let AstTopLevel::Thing(thing) = top {
+            collect(std::slice::from_ref(thing.as_ref()), &mut out);
+        }
+    }
+    out
+}
+
+struct CandidateFinder<'a, 'b> {
+    annotations: &'b [String],
+    fields: bool,
+    found: Vec<Candidate<'a>>,
+}
+
+impl<'a> CandidateFinder<'a, '_> {
+    fn file(&mut self, ast: &'a AstFile) {
+        for top in &ast.top {
+            if let AstTopLevel::Thing(thing) = top {
+                self.thing(thing, false, false);
+            }
+        }
+    }
+
+    fn entry_point(&self, annotated: &[AstAnnotated]) -> bool {
+        annotated.iter().any(|a| {
+            let name = a.name.value.rsplit('.').next().unwrap_or_default();
+            self.annotations.iter().any(|n| n == name)
+        })
+    }
+
+    /// Things nested in interfaces and annotations are implicitly public
+    fn thing(&mut self, thing: &'a AstThing, nested: bool, public: bool) {
+        let (name, availability, annotated) = match thing {
+            AstThing::Class(c) => (&c.name, &c.availability, &c.annotated),
+            AstThing::Record(r) => (&r.name, &r.availability, &r.annotated),
+            AstThing::Interface(i) => (&i.name, &i.availability, &i.annotated),
+            AstThing::Enumeration(e) => (&e.name, &e.availability, &e.annotated),
+            AstThing::Annotation(a) => (&a.name, &a.availability, &a.annotated),
+        };
+        let (variables, methods, inner, members_public) = match thing {
+            AstThing::Class(c) => (
+                &c.block.variables[..],
+                &c.block.methods[..],
+                &c.block.inner,
+                false,
+            ),
+            AstThing::Record(r) => (
+                &r.block.variables[..],
+                &r.block.methods[..],
+                &r.block.inner,
+                false,
+            ),
+            AstThing::Enumeration(e) => (&e.variables[..], &e.methods[..], &e.inner, false),
+            AstThing::Interface(i) => (&[][..], &[][..], &i.inner, true),
+            AstThing::Annotation(a) => (&[][..], &[][..], &a.inner, true),
+        };
+        let has_main = methods.iter().any(is_main);
+        if !public && !visible(availability, nested) && !has_main && !self.entry_point(annotated) {
+            self.found.push(Candidate {
+                ident: name,
+                kind: "class",
+                private: availability.contains(AstAvailability::Private),
+            });
+        }
+        self.members(variables, methods);
+        for thing in inner {
+            self.thing(thing, true, members_public);
+        }
+    }
+
+    fn members(&mut self, variables: &'a [AstClassVariable], methods: &'a [AstClassMethod]) {
+        for variable in variables {
+            if self.fields
+                && !visible(&variable.availability, true)
+                && variable.name.value != "serialVersionUID"
+                && !self.entry_point(&variable.annotated)
+            {
+                self.found.push(Candidate {
+                    ident: &variable.name,
+                    kind: "field",
+                    private: variable.availability.contains(AstAvailability::Private),
+                });
+            }
+        }
+        for method in methods {
+            let header = &method.header;
+            if !visible(&header.availability, true)
+                && !SERIALIZATION.contains(&header.name.value.as_str())
+                && !is_main(method)
+                && !self.entry_point(&header.annotated)
+            {
+                self.found.push(Candidate {
+                    ident: &header.name,
+                    kind: "method",
+                    private: header.availability.contains(AstAvailability::Private),
+                });
+            }
+        }
+    }
+}
+
+/// Visible outside of the package. Top level things can only be public or package-private
+const fn visible(availability: &AstAvailability, nested: bool) -> bool {
+    availability.contains(AstAvailability::Public)
+        || (nested && availability.contains(AstAvailability::Protected))
+}
+
+fn is_main(method: &AstClassMethod) -> bool {
+    method.header.name.value == "main"
+        && method.header.availability.contains(AstAvailability::Static)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use config::ENTRY_POINT_ANNOTATIONS;
+    use document::Document;
+
+    use super::dead_in_file;
+
+    const CONTENT: &str = "
+package ch.emilycares;
+class Foo {
+    private static final long serialVersionUID = 1L;
+    private int unread;
+    private int read;
+    int shared;
+    int alone;
+    Foo() {}
+    private void helper() {}
+    void run() { System.out.println(read); }
+    @Override
+    public String toString() { return \"\"; }
+    @Override
+    void hook() {}
+    private static class Inner {}
+    private static class Used {}
+    static Used used() { return new Used(); }
+    public static void main(String[] args) {}
+}
+";
+
+    #[test]
+    fn dead_declarations() {
+        let document = Document::setup(CONTENT, PathBuf::new()).unwrap();
+        let annotations: Vec<String> = ENTRY_POINT_ANNOTATIONS
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let dead: Vec<String> = dead_in_file(&document, &annotations, |name| {
+            ["shared", "run", "used"].contains(&name)
+        })
+        .iter()
+        .map(|c| format!("{} {}", c.kind, c.ident.value))
+        .collect();
+        assert_eq!(
+            dead,
+            [
+                "field unread",
+                "field alone",
+                "method helper",
+                "class Inner"
+            ]
+        );
+    }
+}
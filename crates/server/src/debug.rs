@@ -0,0 +1,232 @@
+//! Launch configurations for debug adapters
+//!
+//! `java/debugConfig` returns the main class, classpath and arguments of the JVM for a class or
+//! a file, in the form of the launch configurations of `java-debug`. With `start` the JVM is
+//! started suspended with a JDWP agent and the configuration to attach to it is returned
+//! instead, the output of the program is written to a log file.
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    process::{Command, Stdio},
+};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::main_class::{self, Target};
+
+/// Command with the class path of the main class or the uri of its file, and `{ "start": true }` to start the JVM
+pub const COMMAND_DEBUG_CONFIG: &str = "java/debugConfig";
+const JDWP_LISTENING: &str = "Listening for transport dt_socket at address:";
+
+#[derive(Debug)]
+pub enum DebugConfigError {
+    Io(std::io::Error),
+    /// The JVM exited before the agent listened
+    NotListening,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchConfig {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub name: String,
+    pub request: &'static str,
+    pub main_class: String,
+    pub project_name: String,
+    pub cwd: String,
+    pub class_paths: Vec<String>,
+    pub module_paths: Vec<String>,
+    pub vm_args: String,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachConfig {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub name: String,
+    pub request: &'static str,
+    pub host_name: &'static str,
+    pub port: u16,
+    pub project_name: String,
+    /// Output of the program
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<String>,
+}
+
+/// Launch configuration of the main class, its classpath is the one of javac
+#[must_use]
+pub fn launch_config(target: &Target) -> LaunchConfig {
+    let entries: Vec<String> = target
+        .javac
+        .run_classpath(&target.project.kind, &target.source)
+        .iter()
+        .map(|entry| entry.to_string_lossy().to_string())
+        .collect();
+    // The dependencies of a module are on the module path, the module itself too
+    let (class_paths, module_paths) = if target.javac.module_info.is_some() {
+        (Vec::new(), entries)
+    } else {
+        (entries, Vec::new())
+    };
+    let vm_args = if target.javac.level.preview {
+        "--enable-preview".to_string()
+    } else {
+        String::new()
+    };
+    LaunchConfig {
+        kind: "java",
+        name: format!("Debug {}", target.class_path),
+        request: "launch",
+        main_class: target.class_path.clone(),
+        project_name: target.project.artifact_id.clone(),
+        cwd: target.javac.dir.clone(),
+        class_paths,
+        module_paths,
+        vm_args,
+    }
+}
+
+/// Arguments of `java` that run the main class of the configuration
+#[must_use]
+pub fn java_args(config: &LaunchConfig) -> Vec<String> {
+    let separator = compile::CLASSPATH_SEPARATOR.to_string();
+    let mut args: Vec<String> = config
+        .vm_args
+        .split_whitespace()
+        .map(ToString::to_string)
+        .collect();
+    if !config.class_paths.is_empty() {
+        args.push("-cp".into());
+        args.push(config.class_paths.join(&separator));
+    }
+    if !config.module_paths.is_empty() {
+        args.push("-p".into());
+        args.push(config.module_paths.join(&separator));
+        // The module of the main class is not known, its class is found on the classpath
+        args.push("--add-modules=ALL-MODULE-PATH".into());
+        args.push("-cp".into());
+        args.push(config.module_paths.join(&separator));
+    }
+    args.push(config.main_class.clone());
+    args
+}
+
+/// Arguments of `java` that start the main class suspended until a debugger attaches, the
+/// agent picks a free port
+#[must_use]
+pub fn jdwp_args(config: &LaunchConfig) -> Vec<String> {
+    let mut args =
+        vec!["-agentlib:jdwp=transport=dt_socket,server=y,suspend=y,address=localhost:0".into()];
+    args.extend(java_args(config));
+    args
+}
+
+/// Port of the line that the agent prints when it listens
+#[must_use]
+pub fn parse_listening(line: &str) -> Option<u16> {
+    line.strip_prefix(JDWP_LISTENING)?
+        .trim()
+        .rsplit(':')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Start the JVM of the configuration suspended and wait until its agent listens
+pub fn start(
+    config: &LaunchConfig,
+    java_path: std::ffi::OsString,
+) -> Result<AttachConfig, DebugConfigError> {
+    let log_file = main_class::log_file(&config.main_class);
+    let stderr = match log_file.as_ref().map(File::create) {
+        Some(Ok(file)) => Stdio::from(file),
+        _ => Stdio::null(),
+    };
+    let mut child = Command::new("java")
+        .env("PATH", java_path)
+        .current_dir(&config.cwd)
+        .args(jdwp_args(config))
+        .stdout(Stdio::piped())
+        .stderr(stderr)
+        .spawn()
+        .map_err(DebugConfigError::Io)?;
+    let Some(stdout) = child.stdout.take() else {
+        let _ = child.kill();
+        return Err(DebugConfigError::NotListening);
+    };
+    let mut lines = BufReader::new(stdout).lines();
+    let Some(port) = lines
+        .by_ref()
+        .map_while(Result::ok)
+        .find_map(|line| parse_listening(&line))
+    else {
+        let _ = child.wait();
+        return Err(DebugConfigError::NotListening);
+    };
+    // Output of the program after the debugger attached
+    let log = log_file.clone();
+    std::thread::spawn(move || {
+        let mut out = log.and_then(|log| File::options().append(true).open(log).ok());
+        for line in lines.map_while(Result::ok) {
+            if let Some(out) = &mut out {
+                let _ = writeln!(out, "{line}");
+            }
+        }
+        let _ = child.wait();
+    });
+    Ok(AttachConfig {
+        kind: "java",
+        name: format!("Attach {}", config.main_class),
+        request: "attach",
+        host_name: "localhost",
+        port,
+        project_name: config.project_name.clone(),
+        log_file: log_file.map(|log| log.to_string_lossy().to_string()),
+    })
+}
+
+/// Options of the second argument of the command
+#[must_use]
+pub fn start_requested(options: Option<&Value>) -> bool {
+    options
+        .and_then(|o| o.get("start"))
+        .and_then(Value::as_bool)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LaunchConfig, jdwp_args, parse_listening};
+
+    #[test]
+    fn jdwp() {
+        assert_eq!(
+            parse_listening("Listening for transport dt_socket at address: 40321"),
+            Some(40321)
+        );
+        assert_eq!(
+            parse_listening("Listening for transport dt_socket at address: localhost:5005"),
+            Some(5005)
+        );
+        let config = LaunchConfig {
+            kind: "java",
+            name: "Debug ch.App".to_string(),
+            request: "launch",
+            main_class: "ch.App".to_string(),
+            project_name: "app".to_string(),
+            cwd: "/p".to_string(),
+            class_paths: vec!["/p/target/classes".to_string()],
+            module_paths: Vec::new(),
+            vm_args: "--enable-preview".to_string(),
+        };
+        let args = jdwp_args(&config);
+        assert!(args[0].starts_with("-agentlib:jdwp="));
+        assert_eq!(
+            &args[1..],
+            ["--enable-preview", "-cp", "/p/target/classes", "ch.App"]
+        );
+    }
+}
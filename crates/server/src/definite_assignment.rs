@@ -0,0 +1,837 @@
+//! Definite assignment analysis of local variables
+//!
+//! Every method body is walked in execution order while tracking which variables are
+//! definitely and which are possibly assigned. Lambdas and nested classes are analysed
+//! on their own.
+use std::collections::{HashMap, HashSet};
+
+use ast::{
+    types::{
+        AstBaseExpression, AstBlock, AstBlockEntry, AstClassBlock, AstClassConstructor,
+        AstClassMethod, AstExpressionIdentifier, AstExpressionKind, AstExpressionOperator,
+        AstExpressionOrValue, AstFile, AstForContent, AstIdentifier, AstIf, AstIfContent,
+        AstInterfaceMethodDefault, AstLambda, AstLambdaRhs, AstMethodParameter,
+        AstMethodParameterFlags, AstNewRhs, AstRange, AstSwitch, AstSwitchCaseArrowContent,
+        AstThing, AstTryCatch, AstValue, AstWhile, AstWhileContent,
+    },
+    visit::{self, Visitor},
+};
+use lsp_extra::{SERVER_NAME, to_lsp_range};
+use lsp_types::{Diagnostic, NumberOrString};
+
+use crate::{
+    diagnostics::{assign_target, default_severity},
+    flow,
+};
+
+/// Diagnostic code of local variables that are read before they are assigned
+pub const UNINITIALIZED: &str = "uninitialized";
+/// Diagnostic code of final variables that are assigned more than once
+pub const FINAL_REASSIGNED: &str = "final_reassigned";
+
+/// Reads of local variables that might not be initialized and assignments to final
+/// variables that might already be assigned.
+#[must_use]
+pub fn definite_assignment(ast: &AstFile) -> Vec<Diagnostic> {
+    let mut bodies = Bodies::default();
+    bodies.visit_file(ast);
+    bodies
+        .found
+        .into_iter()
+        .filter_map(|(range, message, code)| {
+            Some(Diagnostic {
+                range: to_lsp_range(&range).ok()?,
+                severity: default_severity(code),
+                code: Some(NumberOrString::String(code.to_string())),
+                source: Some(SERVER_NAME.to_string()),
+                message,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Finds all code bodies of a file
+#[derive(Default)]
+struct Bodies {
+    found: Vec<(AstRange, String, &'static str)>,
+}
+
+impl Bodies {
+    fn body(&mut self, parameters: &[AstMethodParameter], block: &AstBlock) {
+        let mut analysis = Analysis::default();
+        let mut state = State::default();
+        analysis.scopes.push(vec![]);
+        for parameter in parameters {
+            let fin = parameter.flags.contains(AstMethodParameterFlags::Fin);
+            analysis.declare(&parameter.name.value, fin, true, &mut state);
+        }
+        analysis.block(block, state);
+        self.found.extend(analysis.found);
+    }
+}
+
+impl<'a> Visitor<'a> for Bodies {
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        if let Some(block) = &method.block {
+            self.body(&method.header.parameters.parameters, block);
+        }
+        visit::walk_class_method(self, method);
+    }
+
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        self.body(&method.header.parameters.parameters, &method.block);
+        visit::walk_interface_method_default(self, method);
+    }
+
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        self.body(
+            &constructor.header.parameters.parameters,
+            &constructor.block,
+        );
+        visit::walk_class_constructor(self, constructor);
+    }
+
+    fn visit_class_block(&mut self, block: &'a AstClassBlock) {
+        for initializer in block
+            .static_blocks
+            .iter()
+            .map(|s| &s.block)
+            .chain(&block.blocks)
+        {
+            self.body(&[], initializer);
+        }
+        visit::walk_class_block(self, block);
+    }
+
+    fn visit_thing(&mut self, thing: &'a AstThing) {
+        if let AstThing::Enumeration(enumeration) = thing {
+            for initializer in &enumeration.static_blocks {
+                self.body(&[], &initializer.block);
+            }
+        }
+        visit::walk_thing(self, thing);
+    }
+
+    fn visit_lambda(&mut self, lambda: &'a AstLambda) {
+        if let AstLambdaRhs::Block(block) = &lambda.rhs {
+            self.body(&[], block);
+        }
+        visit::walk_lambda(self, lambda);
+    }
+}
+
+/// Assigned variables at a point of the execution
+#[derive(Default, Clone)]
+struct State<'a> {
+    /// Assigned on every path
+    definite: HashSet<&'a str>,
+    /// Assigned on at least one path
+    possibly: HashSet<&'a str>,
+}
+
+/// Combine the states of two paths. None is a path that never reaches this point.
+fn join<'a>(a: Option<State<'a>>, b: Option<State<'a>>) -> Option<State<'a>> {
+    match (a, b) {
+        (Some(mut a), Some(b)) => {
+            a.definite.retain(|v| b.definite.contains(v));
+            a.possibly.extend(b.possibly);
+            Some(a)
+        }
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+/// A statement that can be left with `break`
+struct Target<'a> {
+    label: Option<&'a str>,
+    /// Loops and switches are left by unlabeled breaks
+    unlabeled: bool,
+    breaks: Option<State<'a>>,
+}
+
+#[derive(Default)]
+struct Analysis<'a> {
+    /// Variables that are declared in each scope
+    scopes: Vec<Vec<&'a str>>,
+    /// Declared variables and if they are final
+    variables: HashMap<&'a str, bool>,
+    targets: Vec<Target<'a>>,
+    found: Vec<(AstRange, String, &'static str)>,
+}
+
+impl<'a> Analysis<'a> {
+    fn declare(&mut self, name: &'a str, fin: bool, assigned: bool, state: &mut State<'a>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(name);
+        }
+        self.variables.insert(name, fin);
+        if assigned {
+            state.definite.insert(name);
+            state.possibly.insert(name);
+        } else {
+            state.definite.remove(name);
+            state.possibly.remove(name);
+        }
+    }
+
+    fn scoped<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.scopes.push(vec![]);
+        let out = f(self);
+        for name in self.scopes.pop().unwrap_or_default() {
+            self.variables.remove(name);
+        }
+        out
+    }
+
+    /// Run `f` inside a statement that can be left with break.
+    /// Returns the state after `f` and the joined state of all breaks.
+    fn target(
+        &mut self,
+        label: Option<&'a AstIdentifier>,
+        unlabeled: bool,
+        f: impl FnOnce(&mut Self) -> Option<State<'a>>,
+    ) -> (Option<State<'a>>, Option<State<'a>>) {
+        self.targets.push(Target {
+            label: label.map(|l| l.value.as_str()),
+            unlabeled,
+            breaks: None,
+        });
+        let out = f(self);
+        let breaks = self.targets.pop().and_then(|t| t.breaks);
+        (out, breaks)
+    }
+
+    fn read(&mut self, ident: &'a AstIdentifier, state: &State<'a>) {
+        let name = ident.value.as_str();
+        if self.variables.contains_key(name) && !state.definite.contains(name) {
+            self.found.push((
+                ident.range,
+                format!("variable '{name}' might not have been initialized"),
+                UNINITIALIZED,
+            ));
+        }
+    }
+
+    fn write(&mut self, ident: &'a AstIdentifier, state: &mut State<'a>) {
+        let name = ident.value.as_str();
+        let Some(fin) = self.variables.get(name) else {
+            return;
+        };
+        if *fin {
+            let message = if state.definite.contains(name) {
+                Some(format!("cannot assign a value to final variable '{name}'"))
+            } else if state.possibly.contains(name) {
+                Some(format!(
+                    "variable '{name}' might already have been assigned"
+                ))
+            } else {
+                None
+            };
+            if let Some(message) = message {
+                self.found.push((ident.range, message, FINAL_REASSIGNED));
+            }
+        }
+        state.definite.insert(name);
+        state.possibly.insert(name);
+    }
+
+    fn block(&mut self, block: &'a AstBlock, state: State<'a>) -> Option<State<'a>> {
+        self.scoped(|this| this.entries(&block.entries, Some(state)))
+    }
+
+    fn entries(
+        &mut self,
+        entries: &'a [AstBlockEntry],
+        mut state: Option<State<'a>>,
+    ) -> Option<State<'a>> {
+        let mut i = 0;
+        while i < entries.len() {
+            // Statements that are not reachable are reported by the flow analysis
+            let mut current = state?;
+            let (next, following) = if let Some(chain) = flow::if_chain(entries, i) {
+                let mut out = None;
+                for (control, content) in chain.branches {
+                    if let Some(control) = control {
+                        self.expression(control, &mut current);
+                    }
+                    out = join(out, self.if_content(content, current.clone()));
+                }
+                if chain.has_else {
+                    (out, chain.next)
+                } else {
+                    (join(out, Some(current)), chain.next)
+                }
+            } else {
+                (self.entry(&entries[i], current), i + 1)
+            };
+            state = next;
+            i = following;
+        }
+        state
+    }
+
+    fn entry(&mut self, entry: &'a AstBlockEntry, mut state: State<'a>) -> Option<State<'a>> {
+        match entry {
+            AstBlockEntry::Variable(variables) => {
+                for variable in variables {
+                    if let Some(value) = &variable.value {
+                        self.expression(value, &mut state);
+                    }
+                    let assigned = variable.value.is_some();
+                    self.declare(&variable.name.value, variable.fin, assigned, &mut state);
+                }
+            }
+            AstBlockEntry::Expression(expression) => self.expression(&expression.value, &mut state),
+            AstBlockEntry::Assign(assign) => {
+                self.expression(&assign.expression, &mut state);
+                match (assign.key.as_slice(), assign_target(&assign.key)) {
+                    ([_], Some(ident)) => self.write(ident, &mut state),
+                    _ => self.expression(&assign.key, &mut state),
+                }
+            }
+            AstBlockEntry::Assert(assert) => {
+                // Assertions might not be executed
+                self.expression(&assert.expression, &mut state.clone());
+            }
+            AstBlockEntry::Return(ret) => {
+                if let AstExpressionOrValue::Expression(expression) = &ret.expression {
+                    self.expression(expression, &mut state);
+                }
+                return None;
+            }
+            AstBlockEntry::Yield(ast_yield) => {
+                if let AstExpressionOrValue::Expression(expression) = &ast_yield.expression {
+                    self.expression(expression, &mut state);
+                }
+                return None;
+            }
+            AstBlockEntry::Throw(throw) => {
+                self.expression(&throw.expression, &mut state);
+                return None;
+            }
+            AstBlockEntry::Break(ast_break) => {
+                let label = ast_break.label.as_ref().map(|l| l.value.as_str());
+                let target = self
+                    .targets
+                    .iter_mut()
+                    .rev()
+                    .find(|t| label.map_or(t.unlabeled, |label| t.label == Some(label)));
+                if let Some(target) = target {
+                    target.breaks = join(target.breaks.take(), Some(state));
+                }
+                return None;
+            }
+            AstBlockEntry::Continue(_) => return None,
+            AstBlockEntry::If(ast_if) => match ast_if {
+                AstIf::If {
+                    control, content, ..
+                }
+                | AstIf::ElseIf {
+                    control, content, ..
+                } => {
+                    self.expression(control, &mut state);
+                    let out = self.if_content(content, state.clone());
+                    return join(out, Some(state));
+                }
+                AstIf::Else { content, .. } => return self.if_content(content, state),
+            },
+            AstBlockEntry::While(ast_while) => return self.while_loop(entry, ast_while, state),
+            AstBlockEntry::For(ast_for) => {
+                return self.scoped(|this| {
+                    let mut state = this.entries(&ast_for.vars, Some(state))?;
+                    let infinite = match ast_for.check.as_slice() {
+                        [] => true,
+                        [AstBlockEntry::Expression(check)] => flow::is_true(&check.value),
+                        _ => false,
+                    };
+                    for check in &ast_for.check {
+                        state = this.entry(check, state)?;
+                    }
+                    let body = loop_state(entry, &state);
+                    let (_, breaks) = this.target(ast_for.label.as_ref(), true, |this| {
+                        let end = this.for_content(&ast_for.content, body)?;
+                        this.entries(&ast_for.changes, Some(end))
+                    });
+                    join(if infinite { None } else { Some(state) }, breaks)
+                });
+            }
+            AstBlockEntry::ForEnhanced(ast_for) => {
+                self.expression(&ast_for.rhs, &mut state);
+                return self.scoped(|this| {
+                    let mut body = loop_state(entry, &state);
+                    for variable in &ast_for.var {
+                        this.declare(&variable.name.value, variable.fin, true, &mut body);
+                    }
+                    let (_, breaks) = this.target(ast_for.label.as_ref(), true, |this| {
+                        this.for_content(&ast_for.content, body)
+                    });
+                    join(Some(state), breaks)
+                });
+            }
+            AstBlockEntry::Switch(switch) => return self.switch(switch, state),
+            AstBlockEntry::TryCatch(try_catch) => return self.try_catch(entry, try_catch, &state),
+            AstBlockEntry::SynchronizedBlock(synchronized) => {
+                self.expression(&synchronized.expression, &mut state);
+                return self.block(&synchronized.block, state);
+            }
+            AstBlockEntry::InlineBlock(inline) => {
+                let (end, breaks) = self.target(inline.label.as_ref(), false, |this| {
+                    this.block(&inline.block, state)
+                });
+                return join(end, breaks);
+            }
+            AstBlockEntry::SwitchCase(_)
+            | AstBlockEntry::SwitchDefault(_)
+            | AstBlockEntry::SwitchCaseArrowValues(_)
+            | AstBlockEntry::SwitchCaseArrowType(_)
+            | AstBlockEntry::SwitchCaseArrowDefault(_)
+            | AstBlockEntry::Thing(_)
+            | AstBlockEntry::Semicolon(_) => (),
+        }
+        Some(state)
+    }
+
+    fn if_content(&mut self, content: &'a AstIfContent, state: State<'a>) -> Option<State<'a>> {
+        match content {
+            AstIfContent::Block(block) => self.block(block, state),
+            AstIfContent::BlockEntry(entry) => self.entry(entry, state),
+        }
+    }
+
+    fn for_content(&mut self, content: &'a AstForContent, state: State<'a>) -> Option<State<'a>> {
+        match content {
+            AstForContent::None => Some(state),
+            AstForContent::Block(block) => self.block(block, state),
+            AstForContent::BlockEntry(entry) => self.entry(entry, state),
+        }
+    }
+
+    fn while_loop(
+        &mut self,
+        entry: &'a AstBlockEntry,
+        ast_while: &'a AstWhile,
+        mut state: State<'a>,
+    ) -> Option<State<'a>> {
+        let infinite = flow::is_true(&ast_while.control);
+        let do_while = flow::is_do_while(ast_while);
+        if !do_while {
+            self.expression(&ast_while.control, &mut state);
+        }
+        let body = loop_state(entry, &state);
+        let (end, breaks) = self.target(ast_while.label.as_ref(), true, |this| {
+            match &ast_while.content {
+                AstWhileContent::None => Some(body),
+                AstWhileContent::Block(block) => this.block(block, body),
+                AstWhileContent::BlockEntry(entry) => this.entry(entry, body),
+            }
+        });
+        if do_while {
+            // A body that is left by continue is treated like it would not assign anything
+            state = end.unwrap_or(state);
+            self.expression(&ast_while.control, &mut state);
+        }
+        join(if infinite { None } else { Some(state) }, breaks)
+    }
+
+    fn switch(&mut self, switch: &'a AstSwitch, mut state: State<'a>) -> Option<State<'a>> {
+        self.expression(&switch.check, &mut state);
+        let entries = &switch.block.entries;
+        let (end, breaks) = self.target(None, true, |this| {
+            this.scoped(|this| {
+                let mut end = None;
+                let mut group = 0;
+                let mut arrows = false;
+                for (n, entry) in entries.iter().enumerate() {
+                    if let Some(content) = flow::arrow_content(entry) {
+                        arrows = true;
+                        let mut state = state.clone();
+                        if let AstBlockEntry::SwitchCaseArrowType(case) = entry {
+                            this.declare(&case.var.name.value, false, true, &mut state);
+                        }
+                        end = join(
+                            end,
+                            match content {
+                                AstSwitchCaseArrowContent::Block(block) => this.block(block, state),
+                                AstSwitchCaseArrowContent::Entry(entry) => this.entry(entry, state),
+                            },
+                        );
+                    } else if matches!(
+                        entry,
+                        AstBlockEntry::SwitchCase(_) | AstBlockEntry::SwitchDefault(_)
+                    ) {
+                        // Statements after a case label can be reached by falling through
+                        let fallthrough = this.entries(&entries[group..n], end);
+                        end = join(fallthrough, Some(state.clone()));
+                        group = n + 1;
+                    }
+                }
+                if arrows {
+                    end
+                } else {
+                    this.entries(&entries[group..], end)
+                }
+            })
+        });
+        let exhaustive = flow::is_exhaustive(switch);
+        join(
+            join(end, breaks),
+            if exhaustive { None } else { Some(state) },
+        )
+    }
+
+    fn try_catch(
+        &mut self,
+        entry: &'a AstBlockEntry,
+        try_catch: &'a AstTryCatch,
+        state: &State<'a>,
+    ) -> Option<State<'a>> {
+        let mut out = self.scoped(|this| {
+            let state = match &try_catch.resources_block {
+                Some(resources) => this.entries(&resources.entries, Some(state.clone()))?,
+                None => state.clone(),
+            };
+            this.block(&try_catch.block, state)
+        });
+        // The try block might have been left at any point
+        let caught = loop_state(entry, state);
+        for case in &try_catch.cases {
+            let end = self.scoped(|this| {
+                let mut state = caught.clone();
+                this.declare(
+                    &case.variable.name.value,
+                    case.variable.fin,
+                    true,
+                    &mut state,
+                );
+                this.block(&case.block, state)
+            });
+            out = join(out, end);
+        }
+        if let Some(finally) = &try_catch.finally_block {
+            let finally = self.block(finally, caught)?;
+            if let Some(out) = &mut out {
+                out.definite.extend(finally.definite);
+                out.possibly.extend(finally.possibly);
+            }
+        }
+        out
+    }
+
+    /// Reads and writes of a expression in execution order
+    fn expression(&mut self, expression: &'a [AstExpressionKind], state: &mut State<'a>) {
+        for (i, kind) in expression.iter().enumerate() {
+            match kind {
+                AstExpressionKind::Base(base) => {
+                    match &base.ident {
+                        Some(
+                            AstExpressionIdentifier::Identifier(ident)
+                            | AstExpressionIdentifier::Value(AstValue::Variable(ident)),
+                        ) if !(i > 0 && is_member_access(&expression[i - 1])) => {
+                            let next = expression.get(i + 1).and_then(operator);
+                            let call = base.values.is_none()
+                                && matches!(
+                                    expression.get(i + 1),
+                                    Some(AstExpressionKind::Base(AstBaseExpression {
+                                        ident: None,
+                                        values: Some(_),
+                                        ..
+                                    }))
+                                );
+                            match next {
+                                _ if call => (),
+                                Some(AstExpressionOperator::Assign(_)) => {
+                                    // The value is evaluated before the assignment
+                                    self.expression(&expression[i + 2..], state);
+                                    self.write(ident, state);
+                                    return;
+                                }
+                                Some(operator) if is_compound(operator) => {
+                                    self.read(ident, state);
+                                    self.write(ident, state);
+                                }
+                                _ => {
+                                    self.read(ident, state);
+                                    // `++x`
+                                    if i == 1 && operator(&expression[0]).is_some_and(is_compound) {
+                                        self.write(ident, state);
+                                    }
+                                }
+                            }
+                        }
+                        Some(AstExpressionIdentifier::ArrayAccess { expr, .. }) => {
+                            self.expression(expr, state);
+                        }
+                        _ => (),
+                    }
+                    if let Some(values) = &base.values {
+                        for value in &values.values {
+                            self.expression(value, state);
+                        }
+                    }
+                }
+                AstExpressionKind::NewClass(new_class) => match &*new_class.rhs {
+                    AstNewRhs::Parameters(_, arguments)
+                    | AstNewRhs::ParametersAndBlock(_, arguments, _) => {
+                        for argument in arguments {
+                            self.expression(argument, state);
+                        }
+                    }
+                    AstNewRhs::ArrayParameters(dimensions) => {
+                        for argument in dimensions.iter().flatten() {
+                            self.expression(argument, state);
+                        }
+                    }
+                    AstNewRhs::Array(values) => {
+                        for value in &values.values {
+                            self.expression(value, state);
+                        }
+                    }
+                    AstNewRhs::None | AstNewRhs::Block(_) => (),
+                },
+                AstExpressionKind::Array(values) => {
+                    for value in &values.values {
+                        self.expression(value, state);
+                    }
+                }
+                AstExpressionKind::InlineSwitch(switch) => {
+                    self.expression(&switch.check, state);
+                    // Assignments in the cases are assumed to happen on every path
+                    let mut writes = Writes::default();
+                    writes.visit_block(&switch.block);
+                    state.definite.extend(&writes.names);
+                    state.possibly.extend(writes.names);
+                }
+                // Lambdas are analysed on their own
+                AstExpressionKind::Lambda(_)
+                | AstExpressionKind::Generics(_)
+                | AstExpressionKind::JType(_)
+                | AstExpressionKind::InstanceOf(_) => (),
+            }
+        }
+    }
+}
+
+/// The state at the start of a loop body or catch block. Every variable that is
+/// assigned in `entry` might already have been assigned.
+fn loop_state<'a>(entry: &'a AstBlockEntry, state: &State<'a>) -> State<'a> {
+    let mut writes = Writes::default();
+    writes.visit_block_entry(entry);
+    let mut out = state.clone();
+    out.possibly.extend(writes.names);
+    out
+}
+
+pub(crate) const fn operator(kind: &AstExpressionKind) -> Option<&AstExpressionOperator> {
+    match kind {
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: None,
+            values: None,
+            operator,
+            ..
+        }) => Some(operator),
+        _ => None,
+    }
+}
+
+pub(crate) const fn is_member_access(kind: &AstExpressionKind) -> bool {
+    matches!(
+        operator(kind),
+        Some(AstExpressionOperator::Dot(_) | AstExpressionOperator::ColonColon(_))
+    )
+}
+
+/// Operators that read and write a variable
+const fn is_compound(operator: &AstExpressionOperator) -> bool {
+    matches!(
+        operator,
+        AstExpressionOperator::PlusEqual(_)
+            | AstExpressionOperator::PlusPlus(_)
+            | AstExpressionOperator::MinusEqual(_)
+            | AstExpressionOperator::MinusMinus(_)
+            | AstExpressionOperator::MultiplyEqual(_)
+            | AstExpressionOperator::DivideEqual(_)
+            | AstExpressionOperator::ModuloEqual(_)
+            | AstExpressionOperator::VerticalBarEqual(_)
+    )
+}
+
+/// Names of the variables that are assigned
+#[derive(Default)]
+struct Writes<'a> {
+    names: HashSet<&'a str>,
+}
+
+impl<'a> Visitor<'a> for Writes<'a> {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        if let AstBlockEntry::Assign(assign) = entry
+            && let ([_], Some(ident)) = (assign.key.as_slice(), assign_target(&assign.key))
+        {
+            self.names.insert(&ident.value);
+        }
+        visit::walk_block_entry(self, entry);
+    }
+
+    fn visit_expression(&mut self, expression: &'a [AstExpressionKind]) {
+        for pair in expression.windows(2) {
+            if let [
+                AstExpressionKind::Base(AstBaseExpression {
+                    ident: Some(AstExpressionIdentifier::Identifier(ident)),
+                    values: None,
+                    ..
+                }),
+                next,
+            ] = pair
+                && operator(next).is_some_and(|o| {
+                    matches!(o, AstExpressionOperator::Assign(_)) || is_compound(o)
+                })
+            {
+                self.names.insert(&ident.value);
+            }
+        }
+        visit::walk_expression(self, expression);
+    }
+
+    fn visit_class_block(&mut self, _block: &'a AstClassBlock) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr};
+
+    use document::Document;
+    use expect_test::expect;
+
+    use super::*;
+
+    fn messages(content: &str) -> Vec<String> {
+        let document = Document::setup(content, PathBuf::from_str("/Test.java").unwrap()).unwrap();
+        definite_assignment(&document.ast)
+            .into_iter()
+            .map(|d| format!("{}: {}", d.range.start.line, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn uninitialized_variables() {
+        let content = r#"
+package ch.emilycares;
+public class Test {
+    private int field;
+    void branches(boolean a) {
+        int x;
+        if (a) {
+            x = 1;
+        }
+        System.out.println(x);
+        int y;
+        if (a) {
+            y = 1;
+        } else if (field > 1) {
+            y = 2;
+        } else {
+            throw new IllegalStateException();
+        }
+        System.out.println(y);
+        int z;
+        z++;
+        int w;
+        w = w + 1;
+    }
+    void loops(int n) {
+        int x;
+        while (n > 0) {
+            x = n;
+            n--;
+        }
+        System.out.println(x);
+        int y;
+        while (true) {
+            y = 1;
+            break;
+        }
+        System.out.println(y);
+        int z;
+        do {
+            z = n;
+        } while (z > 0);
+        for (int i; i < n; i++) {}
+    }
+    int switches(int n) {
+        int x;
+        switch (n) {
+            case 1:
+                x = 1;
+                break;
+            default:
+                x = 2;
+        }
+        int y;
+        switch (n) {
+            case 1 -> y = 1;
+            case 2 -> y = 2;
+        }
+        int z;
+        try {
+            z = Integer.parseInt("1");
+        } catch (Exception e) {
+            z = 0;
+        }
+        Runnable r = () -> System.out.println(field);
+        return x + y + z;
+    }
+}
+"#;
+        let expected = expect![[r#"
+            [
+                "9: variable 'x' might not have been initialized",
+                "20: variable 'z' might not have been initialized",
+                "22: variable 'w' might not have been initialized",
+                "30: variable 'x' might not have been initialized",
+                "41: variable 'i' might not have been initialized",
+                "41: variable 'i' might not have been initialized",
+                "64: variable 'y' might not have been initialized",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content));
+    }
+
+    #[test]
+    fn reassigned_final_variables() {
+        let content = r"
+package ch.emilycares;
+public class Test {
+    void hello(final int a, boolean b) {
+        a = 1;
+        final int x = 1;
+        x = 2;
+        final int y;
+        if (b) {
+            y = 1;
+        }
+        y = 2;
+        final int z;
+        z = 1;
+        final int l;
+        while (b) {
+            l = 1;
+        }
+        for (final String s : new String[0]) {
+            s = null;
+        }
+    }
+}
+";
+        let expected = expect![[r#"
+            [
+                "4: cannot assign a value to final variable 'a'",
+                "6: cannot assign a value to final variable 'x'",
+                "11: variable 'y' might already have been assigned",
+                "16: variable 'l' might already have been assigned",
+                "19: cannot assign a value to final variable 's'",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content));
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 use ast::types::{AstFile, AstPoint};
 use call_chain::CallItem;
 use document::{Document, DocumentError, read_document_or_open_class};
-use dto::{Class, ImportUnit, JType};
+use dto::{Class, ImportUnit, JType, Method};
 use local_variable::LocalVariable;
 use lsp_extra::{SourceToUriError, ToLspRangeError, source_to_uri, to_lsp_range};
 use lsp_types::{GotoDefinitionResponse, Location, SymbolKind, Uri};
@@ -17,7 +17,10 @@ use my_string::{
 use position::PositionSymbol;
 use tyres::TyresError;
 
-use crate::hover::{ClassActionError, class_action};
+use crate::{
+    hover::{ClassActionError, class_action},
+    sources, stub,
+};
 
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -58,10 +61,10 @@ pub fn class(
     ) {
         Ok((class, _range)) => {
             let mut ranges = vec![];
-            let uri = class_to_uri(&class)?;
-            let Some(source) = class.get_source() else {
+            let Some(source) = stub::class_source(&class, &context.class_map) else {
                 return Err(DefinitionError::NoSource);
             };
+            let uri = source_to_uri(&source).map_err(DefinitionError::SourceToUri)?;
             if let Ok(c) = read_document_or_open_class(&source, document_map) {
                 position::get_class_position(&c.ast, Some(&class.name), &mut ranges);
             }
@@ -87,22 +90,22 @@ pub fn call_chain_definition(
         context.point,
     )
     .map_err(DefinitionError::Tyres)?;
-    let Some(source) = resolve_state.class.get_source() else {
+    let Some(source) = stub::class_source(&resolve_state.class, &context.class_map) else {
         return Err(DefinitionError::NoSource);
     };
     match relevant.get(item) {
         Some(CallItem::This { range: _ }) => {
             let uri = source_to_uri(&source).map_err(DefinitionError::SourceToUri)?;
-            let ast = document::get_ast(&source, context.document_map)
-                .map_err(DefinitionError::Document)?;
+            let ast =
+                source_ast(&source, context.document_map).map_err(DefinitionError::Document)?;
             let mut ranges = Vec::new();
             position::get_class_position(&ast, None, &mut ranges);
             Ok(go_to_definition_range(uri, &ranges)?)
         }
         Some(CallItem::Class { name, range: _ } | CallItem::ClassGeneric { name, .. }) => {
             let uri = source_to_uri(&source).map_err(DefinitionError::SourceToUri)?;
-            let ast = document::get_ast(&source, context.document_map)
-                .map_err(DefinitionError::Document)?;
+            let ast =
+                source_ast(&source, context.document_map).map_err(DefinitionError::Document)?;
             let mut ranges = Vec::new();
             position::get_class_position(&ast, Some(name), &mut ranges);
             Ok(go_to_definition_range(uri, &ranges)?)
@@ -112,20 +115,32 @@ pub fn call_chain_definition(
             args,
             range: _,
         }) => {
-            let args_len = args.len();
-            let source_file = resolve_state
-                .class
-                .methods
+            let arguments = tyres::resolve_arguments(
+                args,
+                context.vars,
+                context.imports,
+                context.class,
+                &context.class_map,
+            );
+            let selected = tyres::select_methods(
+                &resolve_state.class,
+                Some(name),
+                &arguments,
+                &context.class_map,
+            );
+            let nargs = selected.first().map_or(args.len(), |m| m.parameters.len());
+            let source_file = selected
                 .iter()
-                .filter(|i| i.name.as_ref().is_some_and(|i| i == name))
-                .filter(|i| i.parameters.len() == args_len)
                 .find_map(|i| i.source.clone())
                 .map_or(source, |m| m);
 
-            let ast = document::get_ast(&source_file, context.document_map)
+            let ast = source_ast(&source_file, context.document_map)
                 .map_err(DefinitionError::Document)?;
             let mut ranges = Vec::new();
-            position::get_method_position(&ast, Some(name), Some(args_len), &mut ranges);
+            position::get_method_position(&ast, Some(name), Some(nargs), &mut ranges);
+            if let [method] = selected.as_slice() {
+                ranges = overload_range(ranges, &resolve_state.class, method);
+            }
             let uri = source_to_uri(&source_file).map_err(DefinitionError::SourceToUri)?;
             Ok(go_to_definition_range(uri, &ranges)?)
         }
@@ -186,11 +201,11 @@ pub fn call_chain_definition(
             if let JType::Array(i) = resolve_state.jtype
                 && let Ok(res) = tyres::resolve_jtype(&i, context.imports, &context.class_map)
             {
-                let Some(source) = res.class.get_source() else {
+                let Some(source) = stub::class_source(&res.class, &context.class_map) else {
                     return Err(DefinitionError::NoSource);
                 };
-                let ast = document::get_ast(&source, context.document_map)
-                    .map_err(DefinitionError::Document)?;
+                let ast =
+                    source_ast(&source, context.document_map).map_err(DefinitionError::Document)?;
                 let mut ranges = Vec::new();
                 let name = match *i {
                     JType::Class(name) | JType::Generic(name, _) => Some(name),
@@ -202,7 +217,9 @@ pub fn call_chain_definition(
             }
             Err(DefinitionError::NotAnArray)
         }
-        None => Err(DefinitionError::ValidatedItemDoesNotExists),
+        Some(CallItem::TypeArguments { .. }) | None => {
+            Err(DefinitionError::ValidatedItemDoesNotExists)
+        }
     }
 }
 
@@ -215,14 +232,13 @@ fn field_definition(
         let source = if let Some(s) = field.source.clone() {
             s
         } else {
-            let Some(s) = resolve_state.class.get_source() else {
+            let Some(s) = stub::class_source(&resolve_state.class, &context.class_map) else {
                 return Err(DefinitionError::NoSource);
             };
             s
         };
 
-        let ast =
-            document::get_ast(&source, context.document_map).map_err(DefinitionError::Document)?;
+        let ast = source_ast(&source, context.document_map).map_err(DefinitionError::Document)?;
         let mut ranges = Vec::new();
         position::get_field_position(&ast, Some(name), &mut ranges);
         let uri = source_to_uri(&source).map_err(DefinitionError::SourceToUri)?;
@@ -233,6 +249,58 @@ fn field_definition(
     })
 }
 
+/// Syntax tree of the source, files of dependencies are extracted from their sources jar
+pub fn source_ast(
+    source: &str,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+) -> Result<AstFile, DocumentError> {
+    sources::ensure_source(source);
+    document::get_ast(source, document_map)
+}
+
+/// Location of the declaration of the class, the start of the file when it is not found
+pub fn class_location(
+    class: &Class,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+) -> Result<Location, DefinitionError> {
+    let source = stub::class_source(class, class_map).ok_or(DefinitionError::NoSource)?;
+    let uri = source_to_uri(&source).map_err(DefinitionError::SourceToUri)?;
+    let ast = source_ast(&source, document_map).map_err(DefinitionError::Document)?;
+    let mut ranges = Vec::new();
+    position::get_class_position(&ast, Some(&class.name), &mut ranges);
+    let range = match ranges.first() {
+        Some(r) => to_lsp_range(&r.range).map_err(DefinitionError::ToLspRange)?,
+        None => lsp_types::Range::default(),
+    };
+    Ok(Location { uri, range })
+}
+
+/// Range of the selected overload when the declarations in the file are the overloads of the
+/// class with as many parameters, they are in the same order
+pub(crate) fn overload_range(
+    mut ranges: Vec<PositionSymbol>,
+    class: &Class,
+    selected: &Method,
+) -> Vec<PositionSymbol> {
+    let overloads: Vec<&Method> = class
+        .methods
+        .iter()
+        .filter(|m| {
+            m.name == selected.name
+                && m.parameters.len() == selected.parameters.len()
+                && m.source == selected.source
+        })
+        .collect();
+    if overloads.len() != ranges.len() {
+        return ranges;
+    }
+    match overloads.iter().position(|m| std::ptr::eq(*m, selected)) {
+        Some(index) => vec![ranges.swap_remove(index)],
+        None => ranges,
+    }
+}
+
 pub fn class_to_uri(class: &Class) -> Result<Uri, DefinitionError> {
     let Some(source) = class.get_source() else {
         return Err(DefinitionError::NoSource);
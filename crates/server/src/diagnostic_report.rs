@@ -0,0 +1,170 @@
+//! Diagnostics for clients that pull them with `textDocument/diagnostic` and
+//! `workspace/diagnostic`
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use lsp_types::{
+    Diagnostic, DocumentDiagnosticReport, FullDocumentDiagnosticReport, PreviousResultId,
+    RelatedFullDocumentDiagnosticReport, RelatedUnchangedDocumentDiagnosticReport,
+    UnchangedDocumentDiagnosticReport, Uri, WorkspaceDocumentDiagnosticReport,
+    WorkspaceFullDocumentDiagnosticReport, WorkspaceUnchangedDocumentDiagnosticReport,
+};
+
+/// Last computed diagnostics of each file with their result id
+#[derive(Debug, Default)]
+pub struct DiagnosticReports {
+    reports: HashMap<Uri, FullDocumentDiagnosticReport>,
+}
+
+impl DiagnosticReports {
+    /// Store the diagnostics of a file. Returns true when they changed.
+    pub fn update(&mut self, uri: Uri, items: Vec<Diagnostic>) -> bool {
+        let id = result_id(&items);
+        if self
+            .reports
+            .get(&uri)
+            .is_some_and(|report| report.result_id.as_ref() == Some(&id))
+        {
+            return false;
+        }
+        self.reports.insert(
+            uri,
+            FullDocumentDiagnosticReport {
+                result_id: Some(id),
+                items,
+            },
+        );
+        true
+    }
+
+    /// Forget all reports, the next pull computes them again
+    pub fn clear(&mut self) {
+        self.reports.clear();
+    }
+
+    #[must_use]
+    pub fn contains(&self, uri: &Uri) -> bool {
+        self.reports.contains_key(uri)
+    }
+
+    /// Report of a file, unchanged when the client already has the current result
+    #[must_use]
+    pub fn document(
+        &self,
+        uri: &Uri,
+        previous_result_id: Option<&str>,
+    ) -> DocumentDiagnosticReport {
+        let Some(report) = self.reports.get(uri) else {
+            return DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport::default());
+        };
+        match &report.result_id {
+            Some(id) if previous_result_id == Some(id.as_str()) => {
+                DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+                    related_documents: None,
+                    unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                        result_id: id.clone(),
+                    },
+                })
+            }
+            _ => DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report: report.clone(),
+            }),
+        }
+    }
+
+    /// Reports of all known files
+    #[must_use]
+    pub fn workspace(
+        &self,
+        previous: &[PreviousResultId],
+    ) -> Vec<WorkspaceDocumentDiagnosticReport> {
+        let mut out: Vec<_> = self
+            .reports
+            .iter()
+            .map(|(uri, report)| match &report.result_id {
+                Some(id) if previous.iter().any(|p| &p.uri == uri && &p.value == id) => {
+                    WorkspaceDocumentDiagnosticReport::Unchanged(
+                        WorkspaceUnchangedDocumentDiagnosticReport {
+                            uri: uri.clone(),
+                            version: None,
+                            unchanged_document_diagnostic_report:
+                                UnchangedDocumentDiagnosticReport {
+                                    result_id: id.clone(),
+                                },
+                        },
+                    )
+                }
+                _ => {
+                    WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
+                        uri: uri.clone(),
+                        version: None,
+                        full_document_diagnostic_report: report.clone(),
+                    })
+                }
+            })
+            .collect();
+        out.sort_by(|a, b| report_uri(a).as_str().cmp(report_uri(b).as_str()));
+        out
+    }
+}
+
+const fn report_uri(report: &WorkspaceDocumentDiagnosticReport) -> &Uri {
+    match report {
+        WorkspaceDocumentDiagnosticReport::Full(full) => &full.uri,
+        WorkspaceDocumentDiagnosticReport::Unchanged(unchanged) => &unchanged.uri,
+    }
+}
+
+/// Equal diagnostics get the same id, so recomputing them does not change the result id
+fn result_id(items: &[Diagnostic]) -> String {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(items)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use lsp_types::{Diagnostic, DocumentDiagnosticReport, PreviousResultId, Uri};
+
+    use super::*;
+
+    #[test]
+    fn unchanged_reports() {
+        let uri = Uri::from_str("file:///Test.java").unwrap();
+        let mut reports = DiagnosticReports::default();
+        let diagnostic = Diagnostic {
+            message: "unused variable 'a'".to_string(),
+            ..Default::default()
+        };
+        assert!(reports.update(uri.clone(), vec![diagnostic.clone()]));
+        assert!(!reports.update(uri.clone(), vec![diagnostic]));
+        let DocumentDiagnosticReport::Full(full) = reports.document(&uri, None) else {
+            panic!("expected full report");
+        };
+        let id = full.full_document_diagnostic_report.result_id.unwrap();
+        assert!(matches!(
+            reports.document(&uri, Some(&id)),
+            DocumentDiagnosticReport::Unchanged(_)
+        ));
+        let previous = [PreviousResultId {
+            uri: uri.clone(),
+            value: id,
+        }];
+        assert!(matches!(
+            reports.workspace(&previous).as_slice(),
+            [WorkspaceDocumentDiagnosticReport::Unchanged(_)]
+        ));
+        assert!(reports.update(uri, vec![]));
+        assert!(matches!(
+            reports.workspace(&previous).as_slice(),
+            [WorkspaceDocumentDiagnosticReport::Full(_)]
+        ));
+    }
+}
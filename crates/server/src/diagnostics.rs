@@ -0,0 +1,1402 @@
+//! Diagnostics that are found by analysing the ast of a opened document
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
+
+use ast::{
+    types::{
+        AstAnnotated, AstAvailability, AstBaseExpression, AstBlock, AstBlockEntry,
+        AstBlockVariable, AstClassBlock, AstClassConstructor, AstClassMethod, AstClassVariable,
+        AstEnumerationVariant, AstExpressionIdentifier, AstExpressionKind, AstExpressionOperator,
+        AstFile, AstIdentifier, AstImportUnit, AstInterfaceConstant, AstInterfaceMethod,
+        AstInterfaceMethodDefault, AstJType, AstJTypeKind, AstLambda, AstMethodHeader, AstNewClass,
+        AstNewRhs, AstSuperClass, AstSwitchCaseArrowContent, AstThing, AstTopLevel,
+        AstTryCatchCase, AstTypeParameters,
+    },
+    visit::{self, Visitor},
+};
+use common::language_level::LanguageLevel;
+use config::{Configuration, DiagnosticLevel};
+use document::Document;
+use dto::{Access, Class, ImportUnit, SuperClass};
+use lsp_extra::{SERVER_NAME, to_lsp_range};
+use lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag, NumberOrString, Uri};
+use my_string::MyString;
+use tyres::ImportResult;
+
+use crate::{
+    conflicts, definite_assignment, exceptions, flow, jpms, language_level, nullability, refactor,
+    switch_cases, test_scope, type_check,
+};
+
+/// Diagnostic code of symbols that could not be resolved.
+/// Quick fixes use it to offer imports or to create the missing symbol.
+pub const UNRESOLVED: &str = "unresolved";
+/// Diagnostic code of imports, members and variables that are never used
+pub const UNUSED: &str = "unused";
+
+/// Default severity of every diagnostic code
+const SEVERITIES: [(&str, DiagnosticSeverity); 19] = [
+    (UNRESOLVED, DiagnosticSeverity::ERROR),
+    (UNUSED, DiagnosticSeverity::HINT),
+    (type_check::INCOMPATIBLE_TYPES, DiagnosticSeverity::ERROR),
+    (type_check::WRONG_ARGUMENTS, DiagnosticSeverity::ERROR),
+    (flow::MISSING_RETURN, DiagnosticSeverity::ERROR),
+    (flow::UNREACHABLE, DiagnosticSeverity::HINT),
+    (
+        definite_assignment::UNINITIALIZED,
+        DiagnosticSeverity::ERROR,
+    ),
+    (
+        definite_assignment::FINAL_REASSIGNED,
+        DiagnosticSeverity::ERROR,
+    ),
+    (exceptions::UNREPORTED, DiagnosticSeverity::ERROR),
+    (
+        nullability::NULLABLE_DEREFERENCE,
+        DiagnosticSeverity::WARNING,
+    ),
+    (nullability::NULLABLE_ARGUMENT, DiagnosticSeverity::WARNING),
+    (switch_cases::MISSING_CASES, DiagnosticSeverity::WARNING),
+    (conflicts::DUPLICATE, DiagnosticSeverity::ERROR),
+    (conflicts::AMBIGUOUS_IMPORT, DiagnosticSeverity::ERROR),
+    (conflicts::INVALID_OVERRIDE, DiagnosticSeverity::ERROR),
+    (test_scope::TEST_ONLY, DiagnosticSeverity::ERROR),
+    (jpms::NOT_READ, DiagnosticSeverity::ERROR),
+    (jpms::NOT_EXPORTED, DiagnosticSeverity::ERROR),
+    (language_level::LANGUAGE_LEVEL, DiagnosticSeverity::WARNING),
+];
+
+/// Words that are parsed as identifiers but are never declared
+const KEYWORDS: [&str; 15] = [
+    "this", "super", "null", "true", "false", "class", "void", "int", "long", "short", "byte",
+    "char", "boolean", "float", "double",
+];
+pub(crate) const OBJECT: &str = "java.lang.Object";
+
+/// All diagnostics that are computed from the ast
+#[must_use]
+pub fn diagnostics(
+    document: &Document,
+    uri: &Uri,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    config: &Configuration,
+    level: LanguageLevel,
+) -> Vec<Diagnostic> {
+    let mut out = file_diagnostics(document, uri, class_map, config, level);
+    out.extend(member_diagnostics(&document.ast, class_map, config));
+    apply_levels(out, &config.diagnostic_levels)
+}
+
+/// Diagnostics that depend on the whole file, like imports and members that are never used
+pub(crate) fn file_diagnostics(
+    document: &Document,
+    uri: &Uri,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    config: &Configuration,
+    level: LanguageLevel,
+) -> Vec<Diagnostic> {
+    let ast = &document.ast;
+    let mut out = unresolved(ast, class_map);
+    out.extend(unused_imports(ast, class_map));
+    out.extend(unused(ast, &config.reflection_annotations));
+    out.extend(conflicts::conflicts(ast, uri, class_map));
+    out.extend(test_scope::test_only_imports(document, class_map));
+    out.extend(jpms::module_visibility(document, class_map));
+    out.extend(language_level::language_level(
+        &document.tokens,
+        &document.ast,
+        level,
+    ));
+    out
+}
+
+/// Diagnostics of method bodies that only depend on the body and the declarations of the
+/// file, see [`crate::queries`]
+pub(crate) fn member_diagnostics(
+    ast: &AstFile,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    config: &Configuration,
+) -> Vec<Diagnostic> {
+    let (mut out, selected) = type_check::type_check(ast, class_map);
+    out.extend(flow::control_flow(ast));
+    out.extend(definite_assignment::definite_assignment(ast));
+    out.extend(exceptions::unreported_exceptions(ast, class_map));
+    out.extend(switch_cases::missing_switch_cases(ast, class_map));
+    out.extend(nullability::nullability(
+        ast,
+        &config.nullable_annotations,
+        &config.not_null_annotations,
+        &selected,
+    ));
+    out
+}
+
+pub(crate) fn default_severity(code: &str) -> Option<DiagnosticSeverity> {
+    SEVERITIES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, severity)| *severity)
+}
+
+/// Use the configured level of each diagnostic code, diagnostics that are off are removed
+pub(crate) fn apply_levels(
+    diagnostics: Vec<Diagnostic>,
+    levels: &HashMap<String, DiagnosticLevel>,
+) -> Vec<Diagnostic> {
+    diagnostics
+        .into_iter()
+        .filter_map(|mut diagnostic| {
+            let Some(NumberOrString::String(code)) = &diagnostic.code else {
+                return Some(diagnostic);
+            };
+            diagnostic.severity = match levels.get(code) {
+                None => return Some(diagnostic),
+                Some(DiagnosticLevel::Off) => return None,
+                Some(DiagnosticLevel::Hint) => Some(DiagnosticSeverity::HINT),
+                Some(DiagnosticLevel::Info) => Some(DiagnosticSeverity::INFORMATION),
+                Some(DiagnosticLevel::Warning) => Some(DiagnosticSeverity::WARNING),
+                Some(DiagnosticLevel::Error) => Some(DiagnosticSeverity::ERROR),
+            };
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+/// Imports that are not referenced by any type or identifier of the file.
+/// Static star imports are never reported.
+#[must_use]
+pub fn unused_imports(
+    ast: &AstFile,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<Diagnostic> {
+    let used = refactor::used_type_names(ast);
+    let imports: Vec<_> = ast
+        .top
+        .iter()
+        .filter_map(|top| match top {
+            AstTopLevel::Import(import) => Some(import),
+            _ => None,
+        })
+        .collect();
+    let explicit: HashSet<&str> = imports
+        .iter()
+        .filter_map(|import| match &import.unit {
+            AstImportUnit::Class(c) => c.value.rsplit_once('.').map(|(_, name)| name),
+            _ => None,
+        })
+        .collect();
+    imports
+        .into_iter()
+        .filter(|import| !import_used(&import.unit, &used, &explicit, class_map))
+        .filter_map(|import| {
+            let name = match &import.unit {
+                AstImportUnit::Class(c) | AstImportUnit::StaticClass(c) => c.value.to_string(),
+                AstImportUnit::StaticClassMethod(c, m) => format!("{}.{}", c.value, m.value),
+                AstImportUnit::Prefix(p) | AstImportUnit::StaticPrefix(p) => {
+                    format!("{}.*", p.value)
+                }
+            };
+            Some(Diagnostic {
+                range: to_lsp_range(&import.range).ok()?,
+                severity: default_severity(UNUSED),
+                code: Some(NumberOrString::String(UNUSED.to_string())),
+                source: Some(SERVER_NAME.to_string()),
+                message: format!("import '{name}' is never used"),
+                tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+fn import_used(
+    unit: &AstImportUnit,
+    used: &HashSet<MyString>,
+    explicit: &HashSet<&str>,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> bool {
+    match unit {
+        AstImportUnit::Class(c) | AstImportUnit::StaticClass(c) => {
+            let name = c
+                .value
+                .rsplit_once('.')
+                .map_or(c.value.as_str(), |(_, n)| n);
+            used.contains(name)
+        }
+        AstImportUnit::StaticClassMethod(_, m) => used.contains(&m.value),
+        AstImportUnit::Prefix(p) => {
+            // Without classes of the package or nested classes it is unknown what is imported
+            if !has_package(&p.value, class_map) && !has_nested(&p.value, class_map) {
+                return true;
+            }
+            let Ok(class_map) = class_map.read() else {
+                return true;
+            };
+            // Single type imports shadow the classes of a star import
+            used.iter()
+                .filter(|name| !explicit.contains(name.as_str()))
+                .any(|name| {
+                    class_map.contains_key(format!("{}.{name}", p.value).as_str())
+                        || class_map.contains_key(format!("{}${name}", p.value).as_str())
+                })
+        }
+        AstImportUnit::StaticPrefix(_) => true,
+    }
+}
+
+/// Types and identifiers that can not be found in the file, the imports or the class map.
+///
+/// Declarations are collected for the whole file so a name is only reported when it is
+/// not declared anywhere. Identifiers inside of things that extend a class from the class
+/// map are not checked because the class map does not contain protected members.
+#[must_use]
+pub fn unresolved(
+    ast: &AstFile,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<Diagnostic> {
+    // Nothing can be resolved before the jdk is loaded
+    if !class_map
+        .read()
+        .is_ok_and(|class_map| class_map.contains_key(OBJECT))
+    {
+        return vec![];
+    }
+    let imports = imports::imports(ast);
+    let mut declarations = Declarations::default();
+    declarations.visit_file(ast);
+
+    let mut imported = HashSet::new();
+    let mut lombok = false;
+    let mut unknown_star = false;
+    for import in &imports {
+        match import {
+            ImportUnit::Class(c) | ImportUnit::StaticClass(c) => {
+                lombok |= c.starts_with("lombok.");
+                if let Some((_, name)) = c.rsplit_once('.') {
+                    imported.insert(MyString::from(name));
+                }
+            }
+            ImportUnit::StaticClassMethod(_, name) => {
+                imported.insert(name.clone());
+            }
+            ImportUnit::Prefix(p) => {
+                lombok |= p == "lombok" || p.starts_with("lombok.");
+                // Star imports of classes without nested classes in the class map
+                unknown_star |= !has_package(p, class_map) && !has_nested(p, class_map);
+            }
+            ImportUnit::Package(_) | ImportUnit::StaticPrefix(_) => (),
+        }
+    }
+
+    let mut checker = Unresolved {
+        declarations: &declarations,
+        imports: &imports,
+        class_map,
+        imported,
+        scopes: vec![],
+        check_types: !unknown_star,
+        check_identifiers: !lombok,
+        qualified: false,
+        found: vec![],
+    };
+    let mut static_members = HashSet::new();
+    for import in &imports {
+        if let ImportUnit::StaticPrefix(class_path) = import {
+            if let Some(classes) = hierarchy(class_path, class_map) {
+                for class in classes {
+                    static_members.extend(class.names);
+                }
+            } else {
+                checker.check_identifiers = false;
+            }
+        }
+    }
+    checker.imported.extend(static_members);
+    checker.visit_file(ast);
+
+    checker
+        .found
+        .into_iter()
+        .filter_map(|(ident, kind)| {
+            let range = to_lsp_range(&ident.range).ok()?;
+            let message = format!("cannot resolve {kind} '{}'", ident.value);
+            Some(Diagnostic {
+                range,
+                severity: default_severity(UNRESOLVED),
+                code: Some(NumberOrString::String(UNRESOLVED.to_string())),
+                source: Some(SERVER_NAME.to_string()),
+                message,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Private fields and methods and local variables that are never read.
+/// Members with one of the `annotations` are used by reflection and not reported.
+#[must_use]
+pub fn unused(ast: &AstFile, annotations: &[String]) -> Vec<Diagnostic> {
+    let reads = read_names(ast);
+    let mut finder = UnusedFinder {
+        annotations,
+        reads: &reads,
+        // Lombok generates accessors for private fields
+        fields: !uses_lombok(ast),
+        found: vec![],
+    };
+    finder.visit_file(ast);
+    finder
+        .found
+        .into_iter()
+        .filter_map(|(ident, kind)| {
+            Some(Diagnostic {
+                range: to_lsp_range(&ident.range).ok()?,
+                severity: default_severity(UNUSED),
+                code: Some(NumberOrString::String(UNUSED.to_string())),
+                source: Some(SERVER_NAME.to_string()),
+                message: format!("{kind} '{}' is never used", ident.value),
+                tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// The file imports lombok
+pub(crate) fn uses_lombok(ast: &AstFile) -> bool {
+    imports::imports(ast).iter().any(|i| match i {
+        ImportUnit::Class(c) => c.starts_with("lombok."),
+        ImportUnit::Prefix(p) => p == "lombok" || p.starts_with("lombok."),
+        _ => false,
+    })
+}
+
+/// Names that the file reads
+pub(crate) fn read_names(ast: &AstFile) -> HashSet<&str> {
+    let mut reads = Reads::default();
+    reads.visit_file(ast);
+    reads.names
+}
+
+/// Methods that are called by the serialization
+pub(crate) const SERIALIZATION: [&str; 5] = [
+    "writeObject",
+    "readObject",
+    "readObjectNoData",
+    "writeReplace",
+    "readResolve",
+];
+
+struct UnusedFinder<'a, 'b> {
+    annotations: &'b [String],
+    reads: &'b HashSet<&'a str>,
+    fields: bool,
+    found: Vec<(&'a AstIdentifier, &'static str)>,
+}
+
+impl<'a> UnusedFinder<'a, '_> {
+    fn reflective(&self, annotated: &[AstAnnotated]) -> bool {
+        annotated.iter().any(|a| {
+            let name = a.name.value.rsplit('.').next().unwrap_or_default();
+            self.annotations.iter().any(|n| n == name)
+        })
+    }
+
+    fn members(&mut self, variables: &'a [AstClassVariable], methods: &'a [AstClassMethod]) {
+        for variable in variables {
+            if self.fields
+                && variable.availability.contains(AstAvailability::Private)
+                && variable.name.value != "serialVersionUID"
+                && !self.reflective(&variable.annotated)
+                && !self.reads.contains(variable.name.value.as_str())
+            {
+                self.found.push((&variable.name, "field"));
+            }
+        }
+        for method in methods {
+            let header = &method.header;
+            if header.availability.contains(AstAvailability::Private)
+                && !SERIALIZATION.contains(&header.name.value.as_str())
+                && !self.reflective(&header.annotated)
+                && !self.reads.contains(header.name.value.as_str())
+            {
+                self.found.push((&header.name, "method"));
+            }
+        }
+    }
+
+    fn locals(&mut self, block: &'a AstBlock) {
+        let mut reads = Reads::default();
+        reads.visit_block(block);
+        let mut locals = LocalDeclarations::default();
+        locals.visit_block(block);
+        for variable in locals.variables {
+            if variable.name.value != "_"
+                && !self.reflective(&variable.annotated)
+                && !reads.names.contains(variable.name.value.as_str())
+            {
+                self.found.push((&variable.name, "local variable"));
+            }
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for UnusedFinder<'a, '_> {
+    fn visit_thing(&mut self, thing: &'a AstThing) {
+        if let AstThing::Enumeration(enumeration) = thing {
+            self.members(&enumeration.variables, &enumeration.methods);
+            for static_block in &enumeration.static_blocks {
+                self.locals(&static_block.block);
+            }
+        }
+        visit::walk_thing(self, thing);
+    }
+    fn visit_class_block(&mut self, block: &'a AstClassBlock) {
+        self.members(&block.variables, &block.methods);
+        for static_block in &block.static_blocks {
+            self.locals(&static_block.block);
+        }
+        for b in &block.blocks {
+            self.locals(b);
+        }
+        visit::walk_class_block(self, block);
+    }
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        if let Some(block) = &method.block {
+            self.locals(block);
+        }
+        visit::walk_class_method(self, method);
+    }
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        self.locals(&constructor.block);
+        visit::walk_class_constructor(self, constructor);
+    }
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        self.locals(&method.block);
+        visit::walk_interface_method_default(self, method);
+    }
+}
+
+/// Names that are read. Assigning to a variable or field is not a read
+#[derive(Default)]
+struct Reads<'a> {
+    names: HashSet<&'a str>,
+}
+
+impl<'a> Visitor<'a> for Reads<'a> {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        match entry {
+            AstBlockEntry::Assign(assign) if assign_target(&assign.key).is_some() => {
+                self.visit_expression(&assign.expression);
+            }
+            AstBlockEntry::Expression(expression) => {
+                self.visit_expression(assigned_value(&expression.value));
+            }
+            _ => visit::walk_block_entry(self, entry),
+        }
+    }
+    fn visit_identifier(&mut self, ident: &'a AstIdentifier) {
+        self.names.insert(&ident.value);
+    }
+}
+
+/// Value of `this.x = value` or the whole expression when it does not assign
+pub(crate) fn assigned_value(expression: &[AstExpressionKind]) -> &[AstExpressionKind] {
+    for n in [1, 3] {
+        if let Some(AstExpressionKind::Base(assign)) = expression.get(n)
+            && matches!(assign.operator, AstExpressionOperator::Assign(_))
+            && assign_target(&expression[..n]).is_some()
+        {
+            return &expression[n + 1..];
+        }
+    }
+    expression
+}
+
+/// The variable of `x = ...` or `this.x = ...`
+pub(crate) fn assign_target(key: &[AstExpressionKind]) -> Option<&AstIdentifier> {
+    const fn ident(kind: &AstExpressionKind) -> Option<&AstIdentifier> {
+        match kind {
+            AstExpressionKind::Base(AstBaseExpression {
+                ident: Some(AstExpressionIdentifier::Identifier(ident)),
+                values: None,
+                operator: AstExpressionOperator::None,
+                ..
+            }) => Some(ident),
+            _ => None,
+        }
+    }
+    match key {
+        [variable] => ident(variable),
+        [this, AstExpressionKind::Base(dot), variable]
+            if matches!(dot.operator, AstExpressionOperator::Dot(_))
+                && ident(this).is_some_and(|i| i.value == "this") =>
+        {
+            ident(variable)
+        }
+        _ => None,
+    }
+}
+
+/// Local variables of a block without the ones of nested classes and try resources
+#[derive(Default)]
+struct LocalDeclarations<'a> {
+    variables: Vec<&'a AstBlockVariable>,
+}
+
+impl<'a> Visitor<'a> for LocalDeclarations<'a> {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        match entry {
+            AstBlockEntry::TryCatch(try_catch) => {
+                self.visit_block(&try_catch.block);
+                for case in &try_catch.cases {
+                    self.visit_block(&case.block);
+                }
+                if let Some(finally) = &try_catch.finally_block {
+                    self.visit_block(finally);
+                }
+            }
+            AstBlockEntry::Thing(_) => (),
+            _ => visit::walk_block_entry(self, entry),
+        }
+    }
+    fn visit_block_variable(&mut self, variable: &'a AstBlockVariable) {
+        self.variables.push(variable);
+        visit::walk_block_variable(self, variable);
+    }
+    fn visit_class_block(&mut self, _block: &'a AstClassBlock) {}
+}
+
+/// Names that are declared in a file
+#[derive(Default)]
+pub(crate) struct Declarations<'a> {
+    pub names: HashSet<&'a str>,
+    pub type_parameters: HashSet<&'a str>,
+    /// Things declared in the file by name
+    pub things: HashMap<&'a str, &'a AstThing>,
+}
+
+/// A type that is extended or implemented
+pub(crate) struct Super {
+    pub name: String,
+    pub extends: bool,
+}
+
+impl<'a> Declarations<'a> {
+    fn type_parameters(&mut self, type_parameters: Option<&'a AstTypeParameters>) {
+        if let Some(type_parameters) = type_parameters {
+            for parameter in &type_parameters.parameters {
+                self.names.insert(&parameter.name.value);
+                self.type_parameters.insert(&parameter.name.value);
+            }
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Declarations<'a> {
+    fn visit_thing(&mut self, thing: &'a AstThing) {
+        let name = thing_name(thing);
+        self.names.insert(&name.value);
+        match thing {
+            AstThing::Class(class) => self.type_parameters(class.type_parameters.as_ref()),
+            AstThing::Record(record) => {
+                self.type_parameters(record.type_parameters.as_ref());
+                for entry in &record.record_entries.entries {
+                    self.names.insert(&entry.name.value);
+                }
+            }
+            AstThing::Interface(interface) => {
+                self.type_parameters(interface.type_parameters.as_ref());
+            }
+            AstThing::Annotation(annotation) => {
+                for field in &annotation.fields {
+                    self.names.insert(&field.name.value);
+                }
+            }
+            AstThing::Enumeration(_) => (),
+        }
+        self.things.insert(&name.value, thing);
+        visit::walk_thing(self, thing);
+    }
+    fn visit_class_variable(&mut self, variable: &'a AstClassVariable) {
+        self.names.insert(&variable.name.value);
+        visit::walk_class_variable(self, variable);
+    }
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        for parameter in &constructor.header.parameters.parameters {
+            self.names.insert(&parameter.name.value);
+        }
+        self.type_parameters(constructor.header.type_parameters.as_ref());
+        visit::walk_class_constructor(self, constructor);
+    }
+    fn visit_interface_constant(&mut self, constant: &'a AstInterfaceConstant) {
+        self.names.insert(&constant.name.value);
+        visit::walk_interface_constant(self, constant);
+    }
+    fn visit_enumeration_variant(&mut self, variant: &'a AstEnumerationVariant) {
+        self.names.insert(&variant.name.value);
+        visit::walk_enumeration_variant(self, variant);
+    }
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        if let AstBlockEntry::SwitchCaseArrowType(case) = entry {
+            self.names.insert(&case.var.name.value);
+        }
+        visit::walk_block_entry(self, entry);
+    }
+    fn visit_block_variable(&mut self, variable: &'a AstBlockVariable) {
+        self.names.insert(&variable.name.value);
+        visit::walk_block_variable(self, variable);
+    }
+    fn visit_try_catch_case(&mut self, case: &'a AstTryCatchCase) {
+        self.names.insert(&case.variable.name.value);
+        visit::walk_try_catch_case(self, case);
+    }
+    fn visit_expression_kind(&mut self, kind: &'a AstExpressionKind) {
+        if let AstExpressionKind::InstanceOf(instance_of) = kind
+            && let Some(variable) = &instance_of.variable
+        {
+            self.names.insert(&variable.value);
+        }
+        visit::walk_expression_kind(self, kind);
+    }
+    fn visit_lambda(&mut self, lambda: &'a AstLambda) {
+        for parameter in &lambda.parameters.values {
+            self.names.insert(&parameter.name.value);
+        }
+        visit::walk_lambda(self, lambda);
+    }
+    fn visit_jtype(&mut self, _jtype: &'a AstJType) {}
+    fn visit_annotated(&mut self, _annotated: &'a AstAnnotated) {}
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        self.method_header(&method.header);
+        visit::walk_class_method(self, method);
+    }
+    fn visit_interface_method(&mut self, method: &'a AstInterfaceMethod) {
+        self.method_header(&method.header);
+    }
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        self.method_header(&method.header);
+        visit::walk_interface_method_default(self, method);
+    }
+}
+
+impl<'a> Declarations<'a> {
+    fn method_header(&mut self, header: &'a AstMethodHeader) {
+        self.names.insert(&header.name.value);
+        for parameter in &header.parameters.parameters {
+            self.names.insert(&parameter.name.value);
+        }
+        self.type_parameters(header.type_parameters.as_ref());
+    }
+}
+
+/// Members and inner classes that are visible inside of a thing
+struct Scope {
+    members: HashSet<MyString>,
+    /// Class paths of the resolved super types
+    supers: Vec<MyString>,
+    check_types: bool,
+    check_identifiers: bool,
+}
+
+struct Unresolved<'a, 'b> {
+    declarations: &'b Declarations<'a>,
+    imports: &'b [ImportUnit],
+    class_map: &'b Arc<RwLock<HashMap<MyString, Class>>>,
+    /// Names that are imported by a single type or static import
+    imported: HashSet<MyString>,
+    scopes: Vec<Scope>,
+    check_types: bool,
+    check_identifiers: bool,
+    /// The current identifier follows a `.` or `::`
+    qualified: bool,
+    found: Vec<(&'a AstIdentifier, &'static str)>,
+}
+
+impl<'a> Unresolved<'a, '_> {
+    /// Scope of a thing that extends and implements the given types
+    fn scope(&self, supers: Vec<Super>, implicit: &str) -> Scope {
+        let mut scope = Scope {
+            members: HashSet::new(),
+            supers: vec![],
+            check_types: true,
+            check_identifiers: self.scopes.last().is_none_or(|s| s.check_identifiers),
+        };
+        // Protected members of object
+        scope.members.insert("clone".into());
+        scope.members.insert("finalize".into());
+        if implicit == "java.lang.Enum" {
+            scope.members.insert("values".into());
+            scope.members.insert("valueOf".into());
+        }
+        self.add_super(&mut scope, implicit, false);
+
+        let mut pending = supers;
+        let mut visited = HashSet::new();
+        while let Some(s) = pending.pop() {
+            if !visited.insert(s.name.clone()) {
+                continue;
+            }
+            // Members of things in this file are already declared
+            if let Some(thing) = self.declarations.things.get(s.name.as_str()) {
+                pending.extend(thing_supers(thing));
+                continue;
+            }
+            let Some(class_path) = resolve_class_path(&s.name, self.imports, self.class_map) else {
+                scope.check_types = false;
+                scope.check_identifiers = false;
+                continue;
+            };
+            self.add_super(&mut scope, &class_path, s.extends);
+        }
+        scope
+    }
+
+    fn add_super(&self, scope: &mut Scope, class_path: &str, extends: bool) {
+        let Some(classes) = hierarchy(class_path, self.class_map) else {
+            scope.check_types = false;
+            scope.check_identifiers = false;
+            return;
+        };
+        if extends
+            && classes
+                .first()
+                .is_some_and(|c| !c.interface && c.class_path != OBJECT)
+        {
+            scope.check_identifiers = false;
+        }
+        for class in classes {
+            scope.members.extend(class.names);
+            scope.supers.push(class.class_path);
+        }
+    }
+
+    fn check_type(&mut self, ident: &'a AstIdentifier) {
+        let name = ident.value.as_str();
+        if !self.check_types || name.contains('.') || self.type_known(name) {
+            return;
+        }
+        if self.scopes.iter().all(|s| s.check_types) {
+            self.found.push((ident, "type"));
+        }
+    }
+
+    fn type_known(&self, name: &str) -> bool {
+        if self.declarations.names.contains(name) || self.imported.contains(name) {
+            return true;
+        }
+        if self.contains(&format!("java.lang.{name}"))
+            || matches!(
+                tyres::is_imported(name, self.imports, self.class_map),
+                Some(ImportResult::Class(_) | ImportResult::StaticClass(_))
+            )
+        {
+            return true;
+        }
+        // Inner classes of super types
+        self.scopes.iter().any(|s| {
+            s.supers
+                .iter()
+                .any(|class_path| self.contains(&format!("{class_path}${name}")))
+        })
+    }
+
+    fn check_identifier(&mut self, ident: &'a AstIdentifier) {
+        if !self.check_identifiers || self.qualified {
+            return;
+        }
+        if self.scopes.last().is_some_and(|s| !s.check_identifiers) {
+            return;
+        }
+        let name = ident.value.as_str();
+        if KEYWORDS.contains(&name)
+            || self.scopes.iter().any(|s| s.members.contains(name))
+            || self.type_known(name)
+            || has_package(name, self.class_map)
+        {
+            return;
+        }
+        self.found.push((ident, "symbol"));
+    }
+
+    fn contains(&self, class_path: &str) -> bool {
+        self.class_map
+            .read()
+            .is_ok_and(|class_map| class_map.contains_key(class_path))
+    }
+}
+
+impl<'a> Visitor<'a> for Unresolved<'a, '_> {
+    fn visit_thing(&mut self, thing: &'a AstThing) {
+        let implicit = match thing {
+            AstThing::Enumeration(_) => "java.lang.Enum",
+            AstThing::Record(_) => "java.lang.Record",
+            AstThing::Class(_) | AstThing::Interface(_) | AstThing::Annotation(_) => OBJECT,
+        };
+        let scope = self.scope(thing_supers(thing), implicit);
+        let superclass = match thing {
+            AstThing::Class(class) => class.superclass.as_slice(),
+            AstThing::Record(record) => record.superclass.as_slice(),
+            AstThing::Enumeration(enumeration) => enumeration.superclass.as_slice(),
+            AstThing::Interface(_) | AstThing::Annotation(_) => &[],
+        };
+        // Simple names in extends are not visited
+        for s in superclass {
+            if let AstSuperClass::Name(ident) = s {
+                self.check_type(ident);
+            }
+        }
+        self.scopes.push(scope);
+        visit::walk_thing(self, thing);
+        self.scopes.pop();
+    }
+
+    fn visit_new_class(&mut self, new_class: &'a AstNewClass) {
+        let (parameters, block) = match &*new_class.rhs {
+            AstNewRhs::Block(block) => (&[][..], block),
+            AstNewRhs::ParametersAndBlock(_, parameters, block) => (parameters.as_slice(), block),
+            _ => {
+                visit::walk_new_class(self, new_class);
+                return;
+            }
+        };
+        self.visit_jtype(&new_class.jtype);
+        for parameter in parameters {
+            self.visit_expression(parameter);
+        }
+        let supers: Vec<Super> = qualified_name(&new_class.jtype)
+            .map(|name| Super {
+                name,
+                extends: true,
+            })
+            .into_iter()
+            .collect();
+        let scope = self.scope(supers, OBJECT);
+        self.scopes.push(scope);
+        self.visit_class_block(block);
+        self.scopes.pop();
+    }
+
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        // Case labels can be constants of a enum
+        match entry {
+            AstBlockEntry::SwitchCase(_) => (),
+            AstBlockEntry::SwitchCaseArrowValues(case) => match &*case.content {
+                AstSwitchCaseArrowContent::Block(block) => self.visit_block(block),
+                AstSwitchCaseArrowContent::Entry(entry) => self.visit_block_entry(entry),
+            },
+            _ => visit::walk_block_entry(self, entry),
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &'a [AstExpressionKind]) {
+        let mut qualified = false;
+        for kind in expression {
+            self.qualified = qualified;
+            self.visit_expression_kind(kind);
+            qualified = matches!(
+                kind,
+                AstExpressionKind::Base(base)
+                    if matches!(
+                        base.operator,
+                        AstExpressionOperator::Dot(_) | AstExpressionOperator::ColonColon(_)
+                    )
+            );
+        }
+        self.qualified = false;
+    }
+
+    fn visit_expression_kind(&mut self, kind: &'a AstExpressionKind) {
+        let qualified = self.qualified;
+        if let AstExpressionKind::Base(base) = kind {
+            if let Some(ident) = &base.ident {
+                self.visit_expression_identifier(ident);
+            }
+            self.qualified = false;
+            if let Some(values) = &base.values {
+                for value in &values.values {
+                    self.visit_expression(value);
+                }
+            }
+            self.qualified = qualified;
+            return;
+        }
+        self.qualified = false;
+        visit::walk_expression_kind(self, kind);
+        self.qualified = qualified;
+    }
+
+    fn visit_identifier(&mut self, ident: &'a AstIdentifier) {
+        self.check_identifier(ident);
+    }
+
+    fn visit_jtype(&mut self, jtype: &'a AstJType) {
+        match &jtype.value {
+            AstJTypeKind::Class(ident) | AstJTypeKind::ClassOrPackage(ident) => {
+                for annotated in &jtype.annotated {
+                    self.visit_annotated(annotated);
+                }
+                self.check_type(ident);
+            }
+            AstJTypeKind::Generic(ident, _) => {
+                self.check_type(ident);
+                visit::walk_jtype(self, jtype);
+            }
+            // Only generic arguments of a qualified type are checked
+            AstJTypeKind::Access { base, inner } => {
+                for annotated in &jtype.annotated {
+                    self.visit_annotated(annotated);
+                }
+                for part in [base, inner] {
+                    match &part.value {
+                        AstJTypeKind::Generic(_, args) => {
+                            for arg in args {
+                                self.visit_jtype(arg);
+                            }
+                        }
+                        AstJTypeKind::Access { .. } => self.visit_jtype(part),
+                        _ => (),
+                    }
+                }
+            }
+            _ => visit::walk_jtype(self, jtype),
+        }
+    }
+
+    fn visit_annotated(&mut self, annotated: &'a AstAnnotated) {
+        self.check_type(&annotated.name);
+        visit::walk_annotated(self, annotated);
+    }
+}
+
+pub(crate) const fn thing_name(thing: &AstThing) -> &AstIdentifier {
+    match thing {
+        AstThing::Class(c) => &c.name,
+        AstThing::Record(r) => &r.name,
+        AstThing::Interface(i) => &i.name,
+        AstThing::Enumeration(e) => &e.name,
+        AstThing::Annotation(a) => &a.name,
+    }
+}
+
+pub(crate) fn thing_supers(thing: &AstThing) -> Vec<Super> {
+    let (superclass, implements) = match thing {
+        AstThing::Class(class) => (class.superclass.as_slice(), class.implements.as_slice()),
+        AstThing::Record(record) => (record.superclass.as_slice(), record.implements.as_slice()),
+        AstThing::Enumeration(enumeration) => (
+            enumeration.superclass.as_slice(),
+            enumeration.implements.as_slice(),
+        ),
+        AstThing::Interface(interface) => (
+            &[][..],
+            interface
+                .extends
+                .as_ref()
+                .map_or(&[][..], |e| e.parameters.as_slice()),
+        ),
+        AstThing::Annotation(_) => (&[][..], &[][..]),
+    };
+    let mut out = vec![];
+    for s in superclass {
+        let name = match s {
+            AstSuperClass::None => None,
+            AstSuperClass::Name(ident) => Some(ident.value.to_string()),
+            AstSuperClass::JType(jtype) => qualified_name(jtype),
+        };
+        if let Some(name) = name {
+            out.push(Super {
+                name,
+                extends: true,
+            });
+        }
+    }
+    out.extend(
+        implements
+            .iter()
+            .filter_map(qualified_name)
+            .map(|name| Super {
+                name,
+                extends: false,
+            }),
+    );
+    out
+}
+
+/// Name of a class type without generics
+pub(crate) fn qualified_name(jtype: &AstJType) -> Option<String> {
+    match &jtype.value {
+        AstJTypeKind::Class(ident)
+        | AstJTypeKind::ClassOrPackage(ident)
+        | AstJTypeKind::Generic(ident, _) => Some(ident.value.to_string()),
+        AstJTypeKind::Access { base, inner } => Some(format!(
+            "{}.{}",
+            qualified_name(base)?,
+            qualified_name(inner)?
+        )),
+        _ => None,
+    }
+}
+
+pub(crate) fn resolve_class_path(
+    name: &str,
+    imports: &[ImportUnit],
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Option<MyString> {
+    let contains = |class_path: &str| {
+        class_map
+            .read()
+            .is_ok_and(|class_map| class_map.contains_key(class_path))
+    };
+    if name.contains('.') {
+        return contains(name).then(|| name.into());
+    }
+    let lang = format!("java.lang.{name}");
+    if contains(&lang) {
+        return Some(lang.into());
+    }
+    match tyres::is_imported(name, imports, class_map) {
+        Some(ImportResult::Class(c) | ImportResult::StaticClass(c)) if contains(&c) => Some(c),
+        _ => None,
+    }
+}
+
+/// Member names of a class from the class map
+pub(crate) struct Members {
+    pub class_path: MyString,
+    pub interface: bool,
+    pub names: Vec<MyString>,
+}
+
+/// The class and all of its super types. None when one of them is not in the class map
+pub(crate) fn hierarchy(
+    class_path: &str,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Option<Vec<Members>> {
+    let mut out: Vec<Members> = vec![];
+    let mut pending: Vec<MyString> = vec![class_path.into()];
+    while let Some(class_path) = pending.pop() {
+        if out.iter().any(|c| c.class_path == class_path) {
+            continue;
+        }
+        let class = class_map.read().ok()?.get(&class_path)?.clone();
+        for s in std::iter::once(&class.super_class).chain(&class.super_interfaces) {
+            match s {
+                SuperClass::None => (),
+                SuperClass::ClassPath(p) => pending.push(p.clone()),
+                SuperClass::Name(n) => {
+                    pending.push(resolve_class_path(n, &class.imports, class_map)?);
+                }
+            }
+        }
+        out.push(Members {
+            class_path,
+            interface: class.access.contains(Access::Interface),
+            names: class
+                .methods
+                .iter()
+                .filter_map(|m| m.name.clone())
+                .chain(class.fields.iter().map(|f| f.name.clone()))
+                .collect(),
+        });
+    }
+    Some(out)
+}
+
+/// Is `name` a package that contains classes
+fn has_package(name: &str, class_map: &Arc<RwLock<HashMap<MyString, Class>>>) -> bool {
+    let prefix = format!("{name}.");
+    class_map
+        .read()
+        .is_ok_and(|class_map| class_map.keys().any(|k| k.starts_with(&prefix)))
+}
+
+/// The class map has nested classes of the class
+fn has_nested(class_path: &str, class_map: &Arc<RwLock<HashMap<MyString, Class>>>) -> bool {
+    let prefix = format!("{class_path}$");
+    class_map
+        .read()
+        .is_ok_and(|class_map| class_map.keys().any(|k| k.starts_with(&prefix)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr};
+
+    use document::Document;
+    use dto::{Field, JType, Method};
+    use expect_test::expect;
+
+    use super::*;
+
+    fn messages(content: &str, class_map: &Arc<RwLock<HashMap<MyString, Class>>>) -> Vec<String> {
+        let document = Document::setup(content, PathBuf::from_str("/Test.java").unwrap()).unwrap();
+        let uri = Uri::from_str("file:///Test.java").unwrap();
+        diagnostics(
+            &document,
+            &uri,
+            class_map,
+            &Configuration::default(),
+            LanguageLevel::default(),
+        )
+        .into_iter()
+        .map(|d| format!("{}: {}", d.range.start.line, d.message))
+        .collect()
+    }
+
+    #[test]
+    fn unresolved_types_and_identifiers() {
+        let content = r"
+package ch.emilycares;
+import java.util.List;
+public class Test implements Runnable {
+    private int count;
+    public void run() {}
+    public Foo hello(String name, List<Bar> items) {
+        int local = count + name.length();
+        items.forEach(item -> System.out.println(item + local + missing));
+        java.util.List<Baz> qualified = null;
+        if (name instanceof String s) { s.length(); }
+        nothing(toString());
+        return this.other;
+    }
+}
+";
+        let expected = expect![[r#"
+            [
+                "6: cannot resolve type 'Foo'",
+                "6: cannot resolve type 'Bar'",
+                "8: cannot resolve symbol 'missing'",
+                "9: cannot resolve type 'Baz'",
+                "11: cannot resolve symbol 'nothing'",
+                "9: local variable 'qualified' is never used",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content, &get_class_map()));
+    }
+
+    #[test]
+    fn unresolved_skips_members_of_library_super_class() {
+        let content = r"
+package ch.emilycares;
+public class Test extends java.util.AbstractList<Other> {
+    public int size() {
+        return modCount;
+    }
+}
+";
+        let expected = expect![[r#"
+            [
+                "2: cannot resolve type 'Other'",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content, &get_class_map()));
+    }
+
+    #[test]
+    fn unresolved_without_jdk() {
+        let content = r"
+package ch.emilycares;
+public class Test {
+    Foo a = missing;
+}
+";
+        let class_map = Arc::new(RwLock::new(HashMap::new()));
+        assert!(messages(content, &class_map).is_empty());
+    }
+
+    #[test]
+    fn unused_import() {
+        let content = r"
+package ch.emilycares;
+import java.util.List;
+import java.util.Map;
+import java.util.*;
+import java.io.*;
+import static java.lang.Math.max;
+import static java.lang.Math.min;
+public class Test {
+    List<String> a = null;
+    int b = max(1, 2);
+}
+";
+        let expected = expect![[r#"
+            [
+                "3: import 'java.util.Map' is never used",
+                "4: import 'java.util.*' is never used",
+                "7: import 'java.lang.Math.min' is never used",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content, &get_class_map()));
+    }
+
+    #[test]
+    fn unresolved_star_import_of_nested_classes() {
+        let content = r"
+package ch.emilycares;
+import java.util.Map.*;
+public class Test {
+    Entry<String, String> entry;
+    Missing missing;
+}
+";
+        let class_map = get_class_map();
+        if let Ok(mut class_map) = class_map.write() {
+            for class_path in ["java.util.Map", "java.util.Map$Entry"] {
+                class_map.insert(class_path.into(), Class::default());
+            }
+        }
+        let expected = expect![[r#"
+            [
+                "5: cannot resolve type 'Missing'",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content, &class_map));
+    }
+
+    #[test]
+    fn unused_members() {
+        let content = r"
+package ch.emilycares;
+public class Test {
+    private static final long serialVersionUID = 1L;
+    private int unread;
+    private int read;
+    @Inject
+    private String injected;
+    public int visible;
+    public Test() {
+        this.unread = 1;
+    }
+    private void unusedMethod() {}
+    private int usedMethod() {
+        return read;
+    }
+    private void writeObject(java.io.ObjectOutputStream out) {}
+    public void hello() {
+        int a = usedMethod();
+        int b = 2;
+        b = a;
+        try (var resource = open()) {
+            Runnable r = () -> {
+                String inner = toString();
+            };
+        }
+        for (String s : new String[0]) {}
+    }
+}
+";
+        let expected = expect![[r#"
+            [
+                "4: field 'unread' is never used",
+                "12: method 'unusedMethod' is never used",
+                "19: local variable 'b' is never used",
+                "22: local variable 'r' is never used",
+                "23: local variable 'inner' is never used",
+                "26: local variable 's' is never used",
+            ]
+        "#]];
+        let document = Document::setup(content, PathBuf::from_str("/Test.java").unwrap()).unwrap();
+        let out: Vec<_> = unused(
+            &document.ast,
+            &Configuration::default().reflection_annotations,
+        )
+        .into_iter()
+        .map(|d| format!("{}: {}", d.range.start.line, d.message))
+        .collect();
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn configured_levels() {
+        let content = r"
+package ch.emilycares;
+import java.util.List;
+public class Test {
+    private int unread;
+    int hello() {
+        return;
+        int a = 1;
+    }
+}
+";
+        let mut config = Configuration::default();
+        config
+            .diagnostic_levels
+            .insert(UNUSED.to_string(), DiagnosticLevel::Off);
+        config
+            .diagnostic_levels
+            .insert(flow::UNREACHABLE.to_string(), DiagnosticLevel::Warning);
+        let document = Document::setup(content, PathBuf::from_str("/Test.java").unwrap()).unwrap();
+        let uri = Uri::from_str("file:///Test.java").unwrap();
+        let out: Vec<_> = diagnostics(
+            &document,
+            &uri,
+            &get_class_map(),
+            &config,
+            LanguageLevel::default(),
+        )
+        .into_iter()
+        .map(|d| format!("{}: {:?} {}", d.range.start.line, d.severity, d.message))
+        .collect();
+        let expected = expect![[r#"
+            [
+                "7: Some(Warning) unreachable statement",
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    fn get_class_map() -> Arc<RwLock<HashMap<MyString, Class>>> {
+        let method = |name: &str| Method {
+            access: Access::Public,
+            name: Some(name.into()),
+            ret: JType::Void,
+            ..Default::default()
+        };
+        let class = |class_path: &str, access: Access, methods: Vec<Method>| Class {
+            access,
+            class_path: class_path.into(),
+            name: class_path.rsplit('.').next().unwrap_or_default().into(),
+            methods,
+            ..Default::default()
+        };
+        let mut class_map: HashMap<MyString, Class> = HashMap::new();
+        for c in [
+            class(
+                "java.lang.Object",
+                Access::Public,
+                vec![method("toString"), method("hashCode")],
+            ),
+            class("java.lang.String", Access::Public, vec![method("length")]),
+            Class {
+                fields: vec![Field {
+                    access: Access::Public | Access::Static,
+                    name: "out".into(),
+                    jtype: JType::Class("java.io.PrintStream".into()),
+                    source: None,
+                    annotations: vec![],
+                }],
+                ..class("java.lang.System", Access::Public, vec![])
+            },
+            class(
+                "java.lang.Runnable",
+                Access::Public | Access::Interface,
+                vec![method("run")],
+            ),
+            class(
+                "java.util.List",
+                Access::Public | Access::Interface,
+                vec![method("forEach")],
+            ),
+            Class {
+                super_interfaces: vec![SuperClass::ClassPath("java.util.List".into())],
+                ..class("java.util.AbstractList", Access::Public, vec![])
+            },
+        ] {
+            class_map.insert(c.class_path.clone(), c);
+        }
+        Arc::new(RwLock::new(class_map))
+    }
+}
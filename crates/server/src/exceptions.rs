@@ -0,0 +1,560 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use ast::{
+    types::{
+        AstBaseExpression, AstBlock, AstBlockEntry, AstClassConstructor, AstClassMethod,
+        AstExpression, AstExpressionIdentifier, AstExpressionKind, AstFile, AstIdentifier,
+        AstInterfaceMethodDefault, AstLambda, AstNewClass, AstNewRhs, AstRange, AstThing,
+        AstThrowsDeclaration,
+    },
+    visit::{self, Visitor},
+};
+use call_chain::CallItem;
+use dto::{Class, ImportUnit, JType, Method, SourceDestination};
+use local_variable::LocalVariable;
+use lsp_extra::{SERVER_NAME, to_lsp_range};
+use lsp_types::{Diagnostic, NumberOrString};
+use my_string::MyString;
+use variables::VariableContext;
+
+use crate::{diagnostics::default_severity, refactor::class_name};
+
+/// Diagnostic code of checked exceptions that are neither caught nor declared.
+/// The message matches the one of javac, so the quick fixes handle both.
+pub const UNREPORTED: &str = "unreported_exception";
+
+/// Exceptions that are not checked by the compiler
+const UNCHECKED: [&str; 2] = ["java.lang.RuntimeException", "java.lang.Error"];
+
+pub struct ExceptionContext<'a> {
+    pub ast: &'a AstFile,
+    pub imports: &'a [ImportUnit],
+    pub class_map: &'a Arc<RwLock<HashMap<MyString, Class>>>,
+    pub class: &'a Class,
+    pub vars: &'a [LocalVariable],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThrownException {
+    /// Class path of the exception
+    pub class_path: MyString,
+    /// Where the exception is thrown
+    pub range: AstRange,
+}
+
+/// Checked exceptions thrown by the statements that are not caught inside of them.
+/// Lambdas and local classes are skipped because their exceptions do not propagate.
+#[must_use]
+pub fn thrown_exceptions(
+    entries: &[AstBlockEntry],
+    context: &ExceptionContext,
+) -> Vec<ThrownException> {
+    let mut collector = ThrowsCollector {
+        context,
+        found: vec![],
+    };
+    for entry in entries {
+        collector.visit_block_entry(entry);
+    }
+    collector
+        .found
+        .into_iter()
+        .filter(|e| is_checked(&e.class_path, context.class_map))
+        .collect()
+}
+
+/// Checked exceptions thrown in methods and constructors that are neither caught nor declared.
+/// Only exceptions with a known class are reported.
+#[must_use]
+pub fn unreported_exceptions(
+    ast: &AstFile,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<Diagnostic> {
+    let imports = imports::imports(ast);
+    let class = parser::java::load_java_tree(ast, SourceDestination::None);
+    let Ok(vars) = variables::get_vars(
+        ast,
+        &VariableContext {
+            point: None,
+            imports: &imports,
+            class: &class,
+            class_map: class_map.clone(),
+        },
+    ) else {
+        return vec![];
+    };
+    let mut bodies = Bodies::default();
+    bodies.visit_file(ast);
+    let mut found: Vec<ThrownException> = vec![];
+    for (throws, block) in &bodies.found {
+        // Variables of other bodies would shadow the ones of this body
+        let vars: Vec<LocalVariable> = vars
+            .iter()
+            .filter(|v| {
+                v.range.is_contained_in(&block.range)
+                    || !bodies
+                        .found
+                        .iter()
+                        .any(|(_, b)| v.range.is_contained_in(&b.range))
+            })
+            .cloned()
+            .collect();
+        let declared: Vec<MyString> = throws
+            .iter()
+            .flat_map(|t| &t.parameters)
+            .filter_map(|j| exception_class_path(&JType::from(j), &imports, class_map))
+            .collect();
+        let context = ExceptionContext {
+            ast,
+            imports: &imports,
+            class_map,
+            class: &class,
+            vars: &vars,
+        };
+        for exception in thrown_exceptions(&block.entries, &context) {
+            if get_class(&exception.class_path, class_map).is_none()
+                || declared
+                    .iter()
+                    .any(|d| is_subclass(&exception.class_path, d, class_map))
+                || found.contains(&exception)
+            {
+                continue;
+            }
+            found.push(exception);
+        }
+    }
+    found
+        .into_iter()
+        .filter_map(|exception| {
+            Some(Diagnostic {
+                range: to_lsp_range(&exception.range).ok()?,
+                severity: default_severity(UNREPORTED),
+                code: Some(NumberOrString::String(UNREPORTED.to_string())),
+                source: Some(SERVER_NAME.to_string()),
+                message: format!(
+                    "unreported exception {}; must be caught or declared to be thrown",
+                    class_name(&exception.class_path)
+                ),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Bodies of methods and constructors with their throws declaration
+#[derive(Default)]
+struct Bodies<'a> {
+    found: Vec<(Option<&'a AstThrowsDeclaration>, &'a AstBlock)>,
+}
+
+impl<'a> Visitor<'a> for Bodies<'a> {
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        if let Some(block) = &method.block {
+            self.found.push((method.header.throws.as_ref(), block));
+        }
+        visit::walk_class_method(self, method);
+    }
+
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        self.found
+            .push((method.header.throws.as_ref(), &method.block));
+        visit::walk_interface_method_default(self, method);
+    }
+
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        self.found
+            .push((constructor.header.throws.as_ref(), &constructor.block));
+        visit::walk_class_constructor(self, constructor);
+    }
+}
+
+/// Remove duplicates and exceptions that are a subclass of another one
+#[must_use]
+pub fn distinct_exceptions(
+    exceptions: &[ThrownException],
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<MyString> {
+    let mut out: Vec<MyString> = vec![];
+    for exception in exceptions {
+        if out
+            .iter()
+            .any(|o| is_subclass(&exception.class_path, o, class_map))
+        {
+            continue;
+        }
+        out.retain(|o| !is_subclass(o, &exception.class_path, class_map));
+        out.push(exception.class_path.clone());
+    }
+    out
+}
+
+#[must_use]
+pub fn is_checked(class_path: &str, class_map: &Arc<RwLock<HashMap<MyString, Class>>>) -> bool {
+    if UNCHECKED.contains(&class_path) {
+        return false;
+    }
+    let Some(class) = get_class(class_path, class_map) else {
+        // Unknown exceptions are handled like checked ones
+        return true;
+    };
+    !tyres::super_class_paths(&class, class_map)
+        .iter()
+        .any(|s| UNCHECKED.contains(&s.as_str()))
+}
+
+/// Is `class_path` the same as or a subclass of `parent`
+#[must_use]
+pub fn is_subclass(
+    class_path: &str,
+    parent: &str,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> bool {
+    if class_path == parent {
+        return true;
+    }
+    get_class(class_path, class_map).is_some_and(|class| {
+        tyres::super_class_paths(&class, class_map)
+            .iter()
+            .any(|s| s == parent)
+    })
+}
+
+/// Resolve the class path of a exception type
+#[must_use]
+pub fn exception_class_path(
+    jtype: &JType,
+    imports: &[ImportUnit],
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Option<MyString> {
+    match jtype {
+        JType::Class(c) | JType::ClassOrPackage(c) | JType::Generic(c, _) => {
+            if c.contains('.') {
+                return Some(c.clone());
+            }
+            Some(
+                tyres::resolve(c, imports, class_map)
+                    .map_or_else(|_| c.clone(), |r| r.class.class_path),
+            )
+        }
+        _ => None,
+    }
+}
+
+fn get_class(class_path: &str, class_map: &Arc<RwLock<HashMap<MyString, Class>>>) -> Option<Class> {
+    class_map
+        .read()
+        .ok()
+        .and_then(|cm| cm.get(class_path).cloned())
+}
+
+struct ThrowsCollector<'a> {
+    context: &'a ExceptionContext<'a>,
+    found: Vec<ThrownException>,
+}
+
+impl ThrowsCollector<'_> {
+    fn add_throws(&mut self, method: Option<&Method>, range: AstRange) {
+        let Some(method) = method else {
+            return;
+        };
+        for throws in &method.throws {
+            if let Some(class_path) =
+                exception_class_path(throws, self.context.imports, self.context.class_map)
+            {
+                self.found.push(ThrownException { class_path, range });
+            }
+        }
+    }
+
+    fn method_call(&mut self, ident: &AstIdentifier) {
+        let call_chain = call_chain::get_call_chain(self.context.ast, &ident.range.start);
+        let Some((CallItem::MethodCall { name, args, .. }, receiver)) = call_chain.split_last()
+        else {
+            return;
+        };
+        let receiver = if receiver.is_empty() {
+            &[CallItem::This { range: ident.range }][..]
+        } else {
+            receiver
+        };
+        let Ok(resolve_state) = tyres::resolve_call_chain(
+            receiver,
+            self.context.vars,
+            self.context.imports,
+            self.context.class,
+            self.context.class_map,
+        ) else {
+            return;
+        };
+        let method = self.select(&resolve_state.class, Some(name), args);
+        self.add_throws(method, ident.range);
+    }
+
+    fn constructor_call(&mut self, new_class: &AstNewClass, arguments: &[AstExpression]) {
+        let Ok(resolve_state) = tyres::resolve_jtype(
+            &JType::from(&new_class.jtype),
+            self.context.imports,
+            self.context.class_map,
+        ) else {
+            return;
+        };
+        let args = call_chain::argument_call_chains(arguments);
+        let method = self.select(&resolve_state.class, None, &args);
+        self.add_throws(method, new_class.range);
+    }
+
+    /// The overload that a call with the arguments invokes
+    fn select<'c>(
+        &self,
+        class: &'c Class,
+        name: Option<&str>,
+        args: &[Vec<CallItem>],
+    ) -> Option<&'c Method> {
+        let arguments = tyres::resolve_arguments(
+            args,
+            self.context.vars,
+            self.context.imports,
+            self.context.class,
+            self.context.class_map,
+        );
+        tyres::select_methods(class, name, &arguments, self.context.class_map)
+            .first()
+            .copied()
+    }
+
+    fn throw(&mut self, expression: &[AstExpressionKind], range: AstRange) {
+        let jtype = match expression {
+            [AstExpressionKind::NewClass(new_class)] => JType::from(&new_class.jtype),
+            [
+                AstExpressionKind::Base(AstBaseExpression {
+                    ident: Some(AstExpressionIdentifier::Identifier(ident)),
+                    values: None,
+                    ..
+                }),
+            ] => {
+                let Some(var) = self
+                    .context
+                    .vars
+                    .iter()
+                    .rev()
+                    .find(|v| v.name == ident.value)
+                else {
+                    return;
+                };
+                var.jtype.clone()
+            }
+            _ => return,
+        };
+        if let Some(class_path) =
+            exception_class_path(&jtype, self.context.imports, self.context.class_map)
+        {
+            self.found.push(ThrownException { class_path, range });
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for ThrowsCollector<'_> {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        match entry {
+            AstBlockEntry::Throw(throw) => {
+                self.throw(&throw.expression, throw.range);
+                visit::walk_block_entry(self, entry);
+            }
+            AstBlockEntry::TryCatch(try_catch) => {
+                let before = self.found.len();
+                if let Some(resources) = &try_catch.resources_block {
+                    self.visit_block(resources);
+                }
+                self.visit_block(&try_catch.block);
+                let caught: Vec<MyString> = try_catch
+                    .cases
+                    .iter()
+                    .flat_map(|c| &c.variable.jtypes)
+                    .filter_map(|j| {
+                        exception_class_path(
+                            &JType::from(j),
+                            self.context.imports,
+                            self.context.class_map,
+                        )
+                    })
+                    .collect();
+                let mut i = before;
+                while i < self.found.len() {
+                    let class_path = &self.found[i].class_path;
+                    if caught
+                        .iter()
+                        .any(|c| is_subclass(class_path, c, self.context.class_map))
+                    {
+                        self.found.remove(i);
+                    } else {
+                        i += 1;
+                    }
+                }
+                for case in &try_catch.cases {
+                    self.visit_block(&case.block);
+                }
+                if let Some(finally) = &try_catch.finally_block {
+                    self.visit_block(finally);
+                }
+            }
+            _ => visit::walk_block_entry(self, entry),
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &'a [AstExpressionKind]) {
+        for (i, kind) in expression.iter().enumerate() {
+            if let AstExpressionKind::Base(AstBaseExpression {
+                ident: Some(AstExpressionIdentifier::Identifier(ident)),
+                ..
+            }) = kind
+                && let Some(AstExpressionKind::Base(AstBaseExpression {
+                    ident: None,
+                    values: Some(_),
+                    ..
+                })) = expression.get(i + 1)
+            {
+                self.method_call(ident);
+            }
+        }
+        visit::walk_expression(self, expression);
+    }
+
+    fn visit_new_class(&mut self, new_class: &'a AstNewClass) {
+        match &*new_class.rhs {
+            AstNewRhs::Parameters(_, arguments)
+            | AstNewRhs::ParametersAndBlock(_, arguments, _) => {
+                self.constructor_call(new_class, arguments);
+                for argument in arguments {
+                    self.visit_expression(argument);
+                }
+            }
+            AstNewRhs::Block(_) => self.constructor_call(new_class, &[]),
+            AstNewRhs::None | AstNewRhs::ArrayParameters(_) | AstNewRhs::Array(_) => {
+                visit::walk_new_class(self, new_class);
+            }
+        }
+    }
+
+    fn visit_lambda(&mut self, _lambda: &'a AstLambda) {}
+
+    fn visit_thing(&mut self, _thing: &'a AstThing) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr};
+
+    use document::Document;
+    use dto::{Access, Parameter};
+    use expect_test::expect;
+    use my_string::smol_str::SmolStr;
+
+    use super::*;
+    use crate::refactor::tests::get_class_map;
+
+    #[test]
+    fn unreported() {
+        let content = r#"
+package ch.emilycares;
+import java.io.FileInputStream;
+import java.io.FileNotFoundException;
+import java.io.IOException;
+public class Test {
+    public Test() throws IOException {
+        new FileInputStream("a").read();
+    }
+    public void hello(FileInputStream in) {
+        new FileInputStream("a");
+        in.read();
+        try {
+            in.read();
+        } catch (IOException e) {
+        }
+        self();
+    }
+    public void declared() throws FileNotFoundException {
+        new FileInputStream("a");
+        throw new IOException();
+    }
+    private void self() throws IOException {}
+}
+"#;
+        let document = Document::setup(content, PathBuf::from_str("/Test.java").unwrap()).unwrap();
+        let out: Vec<String> = unreported_exceptions(&document.ast, &get_class_map())
+            .into_iter()
+            .map(|d| format!("{}: {}", d.range.start.line, d.message))
+            .collect();
+        let expected = expect![[r#"
+            [
+                "10: unreported exception FileNotFoundException; must be caught or declared to be thrown",
+                "11: unreported exception IOException; must be caught or declared to be thrown",
+                "16: unreported exception IOException; must be caught or declared to be thrown",
+                "20: unreported exception IOException; must be caught or declared to be thrown",
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn unreported_selects_overload() {
+        let content = r"
+package ch.emilycares;
+import java.io.Reader;
+public class Test {
+    public void hello(Reader in, String text, int count) {
+        in.read(text);
+        in.read(count);
+    }
+}
+";
+        let class_map = get_class_map();
+        class_map.write().unwrap().insert(
+            SmolStr::new("java.io.Reader"),
+            Class {
+                access: Access::Public,
+                class_path: SmolStr::new("java.io.Reader"),
+                name: SmolStr::new("Reader"),
+                methods: vec![
+                    Method {
+                        access: Access::Public,
+                        name: Some(SmolStr::new_inline("read")),
+                        parameters: vec![Parameter {
+                            name: None,
+                            jtype: JType::Class(SmolStr::new_inline("java.lang.String")),
+                            annotations: vec![],
+                        }],
+                        ret: JType::Int,
+                        ..Default::default()
+                    },
+                    Method {
+                        access: Access::Public,
+                        name: Some(SmolStr::new_inline("read")),
+                        parameters: vec![Parameter {
+                            name: None,
+                            jtype: JType::Int,
+                            annotations: vec![],
+                        }],
+                        throws: vec![JType::Class(SmolStr::new("java.io.IOException"))],
+                        ret: JType::Int,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+        let document = Document::setup(content, PathBuf::from_str("/Test.java").unwrap()).unwrap();
+        let out: Vec<String> = unreported_exceptions(&document.ast, &class_map)
+            .into_iter()
+            .map(|d| format!("{}: {}", d.range.start.line, d.message))
+            .collect();
+        let expected = expect![[r#"
+            [
+                "6: unreported exception IOException; must be caught or declared to be thrown",
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+}
@@ -0,0 +1,623 @@
+//! Control flow analysis of method bodies
+use std::collections::HashSet;
+
+use ast::{
+    types::{
+        AstBaseExpression, AstBlock, AstBlockEntry, AstClassBlock, AstClassConstructor,
+        AstClassMethod, AstExpression, AstExpressionIdentifier, AstExpressionKind,
+        AstExpressionOperator, AstExpressionOrDefault, AstFile, AstForContent, AstIf, AstIfContent,
+        AstInterfaceMethodDefault, AstJTypeKind, AstLambda, AstLambdaRhs, AstMethodHeader,
+        AstPoint, AstRange, AstSwitch, AstSwitchCaseArrowContent, AstSwitchCaseArrowDefault,
+        AstSwitchCaseArrowType, AstSwitchCaseArrowValues, AstThing, AstTryCatch, AstValue,
+        AstValueNuget, AstWhile, AstWhileContent,
+    },
+    visit::{self, Visitor},
+};
+use lsp_extra::{SERVER_NAME, to_lsp_range};
+use lsp_types::{Diagnostic, DiagnosticTag, NumberOrString};
+
+use crate::diagnostics::default_severity;
+
+/// Diagnostic code of non void methods that can end without returning a value
+pub const MISSING_RETURN: &str = "missing_return";
+/// Diagnostic code of statements that can never be executed
+pub const UNREACHABLE: &str = "unreachable";
+
+/// Methods with a return type where the end of the body can be reached and
+/// statements after a `return`, `throw`, `break`, `continue` or infinite loop.
+#[must_use]
+pub fn control_flow(ast: &AstFile) -> Vec<Diagnostic> {
+    let mut finder = ControlFlow::default();
+    finder.visit_file(ast);
+    let missing_return = finder.missing_return.into_iter().filter_map(|range| {
+        Some(Diagnostic {
+            range: to_lsp_range(&range).ok()?,
+            severity: default_severity(MISSING_RETURN),
+            code: Some(NumberOrString::String(MISSING_RETURN.to_string())),
+            source: Some(SERVER_NAME.to_string()),
+            message: "missing return statement".to_string(),
+            ..Default::default()
+        })
+    });
+    let unreachable = finder.unreachable.into_iter().filter_map(|range| {
+        Some(Diagnostic {
+            range: to_lsp_range(&range).ok()?,
+            severity: default_severity(UNREACHABLE),
+            code: Some(NumberOrString::String(UNREACHABLE.to_string())),
+            source: Some(SERVER_NAME.to_string()),
+            message: "unreachable statement".to_string(),
+            tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+            ..Default::default()
+        })
+    });
+    missing_return.chain(unreachable).collect()
+}
+
+#[derive(Default)]
+struct ControlFlow {
+    /// Closing braces of the method bodies
+    missing_return: Vec<AstRange>,
+    /// Statements that can not be reached until the end of their block
+    unreachable: Vec<AstRange>,
+}
+
+impl ControlFlow {
+    fn method(&mut self, header: Option<&AstMethodHeader>, block: &AstBlock) {
+        let completion = block_completion(block, &mut self.unreachable);
+        if completion.normal
+            && let Some(header) = header
+            && header.jtype.value != AstJTypeKind::Void
+        {
+            let end = block.range.end;
+            self.missing_return.push(AstRange {
+                start: AstPoint {
+                    line: end.line,
+                    col: end.col.saturating_sub(1),
+                },
+                end,
+            });
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for ControlFlow {
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        if let Some(block) = &method.block {
+            self.method(Some(&method.header), block);
+        }
+        visit::walk_class_method(self, method);
+    }
+
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        self.method(Some(&method.header), &method.block);
+        visit::walk_interface_method_default(self, method);
+    }
+
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        self.method(None, &constructor.block);
+        visit::walk_class_constructor(self, constructor);
+    }
+
+    fn visit_class_block(&mut self, block: &'a AstClassBlock) {
+        for initializer in block
+            .static_blocks
+            .iter()
+            .map(|s| &s.block)
+            .chain(&block.blocks)
+        {
+            self.method(None, initializer);
+        }
+        visit::walk_class_block(self, block);
+    }
+
+    fn visit_thing(&mut self, thing: &'a AstThing) {
+        if let AstThing::Enumeration(enumeration) = thing {
+            for initializer in &enumeration.static_blocks {
+                self.method(None, &initializer.block);
+            }
+        }
+        visit::walk_thing(self, thing);
+    }
+
+    fn visit_lambda(&mut self, lambda: &'a AstLambda) {
+        if let AstLambdaRhs::Block(block) = &lambda.rhs {
+            self.method(None, block);
+        }
+        visit::walk_lambda(self, lambda);
+    }
+}
+
+/// How the execution of a statement can end
+#[derive(Default)]
+struct Completion<'a> {
+    /// The statement after it can be reached
+    normal: bool,
+    /// Labels of the break statements that leave it. None for unlabeled breaks
+    breaks: HashSet<Option<&'a str>>,
+    /// Labels of the continue statements that leave it. None for unlabeled continues
+    continues: HashSet<Option<&'a str>>,
+}
+
+impl<'a> Completion<'a> {
+    fn normal() -> Self {
+        Self {
+            normal: true,
+            ..Default::default()
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.normal |= other.normal;
+        self.breaks.extend(other.breaks);
+        self.continues.extend(other.continues);
+    }
+
+    /// Remove the jumps that target a loop with `label`. Returns true when it was left by a break.
+    fn leave_loop(&mut self, label: Option<&'a str>) -> bool {
+        self.continues.remove(&None);
+        self.continues.remove(&label);
+        self.leave(label)
+    }
+
+    /// Remove the breaks that target a statement with `label`. Returns true when there were any.
+    fn leave(&mut self, label: Option<&'a str>) -> bool {
+        let unlabeled = self.breaks.remove(&None);
+        label.is_some_and(|l| self.breaks.remove(&Some(l))) || unlabeled
+    }
+}
+
+fn block_completion<'a>(block: &'a AstBlock, unreachable: &mut Vec<AstRange>) -> Completion<'a> {
+    entries_completion(&block.entries, unreachable)
+}
+
+/// An `if` with the `else if` and `else` branches that follow it as entries of the block
+pub(crate) struct IfChain<'a> {
+    /// Condition and content of every branch, the `else` branch has no condition
+    pub branches: Vec<(Option<&'a AstExpression>, &'a AstIfContent)>,
+    pub has_else: bool,
+    /// Index of the entry after the chain
+    pub next: usize,
+}
+
+/// The chain of the `if` at `index` of the entries, None when that entry is no `if`
+pub(crate) fn if_chain(entries: &[AstBlockEntry], index: usize) -> Option<IfChain<'_>> {
+    let AstBlockEntry::If(AstIf::If {
+        control, content, ..
+    }) = entries.get(index)?
+    else {
+        return None;
+    };
+    let mut chain = IfChain {
+        branches: vec![(Some(control), content)],
+        has_else: false,
+        next: index + 1,
+    };
+    while let Some(AstBlockEntry::If(branch)) = entries.get(chain.next) {
+        match branch {
+            AstIf::If { .. } => break,
+            AstIf::ElseIf {
+                control, content, ..
+            } => chain.branches.push((Some(control), content)),
+            AstIf::Else { content, .. } => {
+                chain.branches.push((None, content));
+                chain.has_else = true;
+            }
+        }
+        chain.next += 1;
+    }
+    Some(chain)
+}
+
+/// Can the statement after `entries` be reached
+pub(crate) fn completes_normally(entries: &[AstBlockEntry]) -> bool {
+    entries_completion(entries, &mut vec![]).normal
+}
+
+fn entries_completion<'a>(
+    entries: &'a [AstBlockEntry],
+    unreachable: &mut Vec<AstRange>,
+) -> Completion<'a> {
+    let mut out = Completion::normal();
+    let mut i = 0;
+    while out.normal && i < entries.len() {
+        let (next, following) = if let Some(chain) = if_chain(entries, i) {
+            let mut branches = Completion::default();
+            for (_, content) in chain.branches {
+                branches.merge(if_content_completion(content, unreachable));
+            }
+            branches.normal |= !chain.has_else;
+            (branches, chain.next)
+        } else {
+            (entry_completion(&entries[i], unreachable), i + 1)
+        };
+        out.normal = next.normal;
+        out.breaks.extend(next.breaks);
+        out.continues.extend(next.continues);
+        i = following;
+    }
+    if let (Some(first), Some(last)) = (entries.get(i), entries.last()) {
+        unreachable.push(AstRange {
+            start: first.range().start,
+            end: last.range().end,
+        });
+    }
+    out
+}
+
+fn entry_completion<'a>(
+    entry: &'a AstBlockEntry,
+    unreachable: &mut Vec<AstRange>,
+) -> Completion<'a> {
+    match entry {
+        AstBlockEntry::Return(_) | AstBlockEntry::Throw(_) | AstBlockEntry::Yield(_) => {
+            Completion::default()
+        }
+        AstBlockEntry::Break(ast_break) => Completion {
+            breaks: HashSet::from([ast_break.label.as_ref().map(|l| l.value.as_str())]),
+            ..Default::default()
+        },
+        AstBlockEntry::Continue(ast_continue) => Completion {
+            continues: HashSet::from([ast_continue.label.as_ref().map(|l| l.value.as_str())]),
+            ..Default::default()
+        },
+        AstBlockEntry::If(ast_if) => match ast_if {
+            AstIf::If { content, .. } | AstIf::ElseIf { content, .. } => {
+                let mut out = if_content_completion(content, unreachable);
+                out.normal = true;
+                out
+            }
+            AstIf::Else { content, .. } => if_content_completion(content, unreachable),
+        },
+        AstBlockEntry::While(ast_while) => while_completion(ast_while, unreachable),
+        AstBlockEntry::For(ast_for) => {
+            let mut out = for_content_completion(&ast_for.content, unreachable);
+            let label = ast_for.label.as_ref().map(|l| l.value.as_str());
+            let infinite = match ast_for.check.as_slice() {
+                [] => true,
+                [AstBlockEntry::Expression(check)] => is_true(&check.value),
+                _ => false,
+            };
+            out.normal = out.leave_loop(label) || !infinite;
+            out
+        }
+        AstBlockEntry::ForEnhanced(ast_for) => {
+            let mut out = for_content_completion(&ast_for.content, unreachable);
+            out.leave_loop(ast_for.label.as_ref().map(|l| l.value.as_str()));
+            out.normal = true;
+            out
+        }
+        AstBlockEntry::Switch(switch) => switch_completion(switch, unreachable),
+        AstBlockEntry::TryCatch(try_catch) => try_completion(try_catch, unreachable),
+        AstBlockEntry::SynchronizedBlock(synchronized) => {
+            block_completion(&synchronized.block, unreachable)
+        }
+        AstBlockEntry::InlineBlock(inline) => {
+            let mut out = block_completion(&inline.block, unreachable);
+            if let Some(label) = &inline.label {
+                out.normal |= out.breaks.remove(&Some(label.value.as_str()));
+            }
+            out
+        }
+        AstBlockEntry::Variable(_)
+        | AstBlockEntry::Expression(_)
+        | AstBlockEntry::Assign(_)
+        | AstBlockEntry::SwitchCase(_)
+        | AstBlockEntry::SwitchDefault(_)
+        | AstBlockEntry::SwitchCaseArrowValues(_)
+        | AstBlockEntry::SwitchCaseArrowType(_)
+        | AstBlockEntry::SwitchCaseArrowDefault(_)
+        | AstBlockEntry::Thing(_)
+        | AstBlockEntry::Semicolon(_)
+        | AstBlockEntry::Assert(_) => Completion::normal(),
+    }
+}
+
+fn if_content_completion<'a>(
+    content: &'a AstIfContent,
+    unreachable: &mut Vec<AstRange>,
+) -> Completion<'a> {
+    match content {
+        AstIfContent::Block(block) => block_completion(block, unreachable),
+        AstIfContent::BlockEntry(entry) => entry_completion(entry, unreachable),
+    }
+}
+
+fn for_content_completion<'a>(
+    content: &'a AstForContent,
+    unreachable: &mut Vec<AstRange>,
+) -> Completion<'a> {
+    match content {
+        AstForContent::None => Completion::normal(),
+        AstForContent::Block(block) => block_completion(block, unreachable),
+        AstForContent::BlockEntry(entry) => entry_completion(entry, unreachable),
+    }
+}
+
+fn while_completion<'a>(
+    ast_while: &'a AstWhile,
+    unreachable: &mut Vec<AstRange>,
+) -> Completion<'a> {
+    let mut out = match &ast_while.content {
+        AstWhileContent::None => Completion::normal(),
+        AstWhileContent::Block(block) => block_completion(block, unreachable),
+        AstWhileContent::BlockEntry(entry) => entry_completion(entry, unreachable),
+    };
+    let label = ast_while.label.as_ref().map(|l| l.value.as_str());
+    let infinite = is_true(&ast_while.control);
+    let do_while = is_do_while(ast_while);
+    let body = out.normal || out.continues.contains(&None) || out.continues.contains(&label);
+    let left = out.leave_loop(label);
+    out.normal = left || !infinite && (!do_while || body);
+    out
+}
+
+/// A do while loop has the condition after the body
+pub(crate) fn is_do_while(ast_while: &AstWhile) -> bool {
+    ast_while
+        .control
+        .first()
+        .is_some_and(|c| while_content_start(&ast_while.content) < Some(c.range().start))
+}
+
+const fn while_content_start(content: &AstWhileContent) -> Option<AstPoint> {
+    match content {
+        AstWhileContent::None | AstWhileContent::BlockEntry(_) => None,
+        AstWhileContent::Block(block) => Some(block.range.start),
+    }
+}
+
+fn switch_completion<'a>(switch: &'a AstSwitch, unreachable: &mut Vec<AstRange>) -> Completion<'a> {
+    let entries = &switch.block.entries;
+    let exhaustive = is_exhaustive(switch);
+    let mut arrows = false;
+    let mut out = Completion::default();
+    for entry in entries {
+        let Some(content) = arrow_content(entry) else {
+            continue;
+        };
+        arrows = true;
+        out.merge(match content {
+            AstSwitchCaseArrowContent::Block(block) => block_completion(block, unreachable),
+            AstSwitchCaseArrowContent::Entry(entry) => entry_completion(entry, unreachable),
+        });
+    }
+    if !arrows {
+        // Statements after a case label are reachable again
+        let mut i = 0;
+        for (n, entry) in entries.iter().enumerate() {
+            if matches!(
+                entry,
+                AstBlockEntry::SwitchCase(_) | AstBlockEntry::SwitchDefault(_)
+            ) {
+                let group = entries_completion(&entries[i..n], unreachable);
+                out.breaks.extend(group.breaks);
+                out.continues.extend(group.continues);
+                i = n + 1;
+            }
+        }
+        let last = entries_completion(&entries[i..], unreachable);
+        out.normal = last.normal;
+        out.breaks.extend(last.breaks);
+        out.continues.extend(last.continues);
+    }
+    let left = out.breaks.remove(&None);
+    out.normal |= left || !exhaustive;
+    out
+}
+
+/// Content of a `case ... ->` entry
+pub(crate) fn arrow_content(entry: &AstBlockEntry) -> Option<&AstSwitchCaseArrowContent> {
+    match entry {
+        AstBlockEntry::SwitchCaseArrowValues(AstSwitchCaseArrowValues { content, .. })
+        | AstBlockEntry::SwitchCaseArrowType(AstSwitchCaseArrowType { content, .. })
+        | AstBlockEntry::SwitchCaseArrowDefault(AstSwitchCaseArrowDefault { content, .. }) => {
+            Some(content)
+        }
+        _ => None,
+    }
+}
+
+/// Switches with a default case or patterns, which have to be exhaustive
+pub(crate) fn is_exhaustive(switch: &AstSwitch) -> bool {
+    switch.block.entries.iter().any(|entry| match entry {
+        AstBlockEntry::SwitchDefault(_)
+        | AstBlockEntry::SwitchCaseArrowType(_)
+        | AstBlockEntry::SwitchCaseArrowDefault(_) => true,
+        AstBlockEntry::SwitchCase(case) => has_default(&case.expressions),
+        AstBlockEntry::SwitchCaseArrowValues(case) => has_default(&case.values),
+        _ => false,
+    })
+}
+
+fn has_default(values: &[AstExpressionOrDefault]) -> bool {
+    values
+        .iter()
+        .any(|v| matches!(v, AstExpressionOrDefault::Default))
+}
+
+fn try_completion<'a>(
+    try_catch: &'a AstTryCatch,
+    unreachable: &mut Vec<AstRange>,
+) -> Completion<'a> {
+    let mut out = block_completion(&try_catch.block, unreachable);
+    for case in &try_catch.cases {
+        out.merge(block_completion(&case.block, unreachable));
+    }
+    if let Some(finally) = &try_catch.finally_block {
+        let finally = block_completion(finally, unreachable);
+        if !finally.normal {
+            // The jumps of the try block are replaced by the finally block
+            return finally;
+        }
+        out.breaks.extend(finally.breaks);
+        out.continues.extend(finally.continues);
+    }
+    out
+}
+
+/// The constant `true`
+pub(crate) fn is_true(expression: &[AstExpressionKind]) -> bool {
+    match expression {
+        [
+            AstExpressionKind::Base(AstBaseExpression {
+                ident: Some(ident),
+                values: None,
+                operator: AstExpressionOperator::None,
+                ..
+            }),
+        ] => match ident {
+            AstExpressionIdentifier::Identifier(ident)
+            | AstExpressionIdentifier::Value(AstValue::Variable(ident)) => ident.value == "true",
+            AstExpressionIdentifier::Nuget(AstValueNuget::BooleanLiteral(b))
+            | AstExpressionIdentifier::Value(AstValue::Nuget(AstValueNuget::BooleanLiteral(b))) => {
+                b.value
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr};
+
+    use document::Document;
+    use expect_test::expect;
+
+    use super::*;
+
+    fn messages(content: &str) -> Vec<String> {
+        let document = Document::setup(content, PathBuf::from_str("/Test.java").unwrap()).unwrap();
+        control_flow(&document.ast)
+            .into_iter()
+            .map(|d| format!("{}: {}", d.range.start.line, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn missing_return_statements() {
+        let content = r"
+package ch.emilycares;
+public class Test {
+    int ifElse(int x) {
+        if (x > 1) { return 1; } else if (x < 0) return 2; else { return 3; }
+    }
+    int ifWithoutElse(int x) {
+        if (x > 1) { return 1; } else if (x < 0) { return 2; }
+    }
+    int switchDefault(int x) {
+        switch (x) { case 1: return 1; default: return 2; }
+    }
+    int switchWithoutDefault(int x) {
+        switch (x) { case 1: return 1; case 2: return 2; }
+    }
+    int switchBreak(int x) {
+        switch (x) { case 1: break; default: return 2; }
+    }
+    int switchArrow(Object o) {
+        switch (o) {
+            case String s -> { return 1; }
+            default -> throw new IllegalStateException();
+        }
+    }
+    int infinite() {
+        while (true) {}
+    }
+    int infiniteBreak() {
+        outer: while (true) { for (;;) { break outer; } }
+    }
+    int doWhile(int x) {
+        do { return x; } while (x > 1);
+    }
+    int loop(int x) {
+        while (x > 1) { return x; }
+    }
+    int tryCatch() {
+        try { return 1; } catch (Exception e) { throw e; }
+    }
+    int tryFinally() {
+        try { } finally { }
+    }
+    void nothing() {}
+    int empty() {}
+    abstract int none();
+}
+";
+        let expected = expect![[r#"
+            [
+                "8: missing return statement",
+                "14: missing return statement",
+                "17: missing return statement",
+                "29: missing return statement",
+                "35: missing return statement",
+                "41: missing return statement",
+                "43: missing return statement",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content));
+    }
+
+    #[test]
+    fn unreachable_statements() {
+        let content = r"
+package ch.emilycares;
+public class Test {
+    static {
+        return;
+        int a = 1;
+    }
+    void afterReturn(int x) {
+        if (x > 1) {
+            return;
+        } else {
+            throw new IllegalStateException();
+        }
+        x++;
+        x--;
+    }
+    void afterBreak(int x) {
+        for (int i = 0; i < x; i++) {
+            if (i == 2) {
+                continue;
+            }
+            break;
+            x++;
+        }
+        switch (x) {
+            case 1:
+                break;
+                x++;
+            default:
+                x--;
+        }
+    }
+    void afterLoop() {
+        while (true) {
+        }
+        System.out.println();
+    }
+    void reachable() {
+        while (true) {
+            break;
+        }
+        Runnable r = () -> {
+            return;
+            System.out.println();
+        };
+    }
+}
+";
+        let expected = expect![[r#"
+            [
+                "5: unreachable statement",
+                "13: unreachable statement",
+                "22: unreachable statement",
+                "27: unreachable statement",
+                "35: unreachable statement",
+                "43: unreachable statement",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content));
+    }
+}
@@ -1,9 +1,10 @@
 use std::{
     collections::HashMap,
+    fmt::Write,
     sync::{Arc, RwLock},
 };
 
-use ast::types::{AstFile, AstPoint};
+use ast::types::{AstAnnotation, AstFile, AstPoint, AstRange, AstThing, AstTopLevel};
 use call_chain::{self, CallItem};
 use document::get_class_path;
 use dto::{Access, Class, Field, ImportUnit, JType, Method};
@@ -13,6 +14,8 @@ use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Range};
 use my_string::MyString;
 use tyres::TyresError;
 
+use crate::sources::{self, Member};
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum HoverError {
@@ -35,13 +38,16 @@ pub fn base(
 ) -> Result<Hover, HoverError> {
     match class_action(ast, point, lo_va, imports, class_map) {
         Ok((class, range)) => {
+            if let Some(value) = annotation_to_markdown(&class) {
+                return Ok(markdown_hover(value, range));
+            }
             return Ok(class_to_hover(&class, range));
         }
         Err(ClassActionError::NotFound | ClassActionError::Tyres(TyresError::NotImported(_))) => {}
-        Err(e) => eprintln!("class action hover error: {e:?}"),
+        Err(e) => tracing::debug!("class action hover error: {e:?}"),
     }
     let Some(class_path) = get_class_path(ast) else {
-        eprintln!("Could not get class_path");
+        tracing::debug!("Could not get class_path");
         return Err(HoverError::CouldNotFindClassPath);
     };
     let class;
@@ -110,23 +116,41 @@ pub fn call_chain_hover(
     }?;
     match el {
         CallItem::MethodCall { name, args, range } => {
-            let args_len = args.len();
-            let methods: Vec<Method> = resolve_state
-                .class
-                .methods
-                .into_iter()
-                .filter(|i| i.name.as_ref().is_some_and(|i| i == name))
-                .filter(|i| i.parameters.len() == args_len)
-                .collect();
+            let class_source = resolve_state.class.get_source();
+            let arguments = tyres::resolve_arguments(args, lo_va, imports, class, class_map);
+            let mut methods: Vec<Method> =
+                tyres::select_methods(&resolve_state.class, Some(name), &arguments, class_map)
+                    .into_iter()
+                    .cloned()
+                    .collect();
+            for method in &mut methods {
+                sources::fill_parameter_names(method, class_source.as_ref());
+            }
             let range = to_lsp_range(range).map_err(HoverError::ToLspRange)?;
-            Ok(methods_to_hover(&methods, range, &resolve_state.class.name))
+            let hover = methods_to_hover(&methods, range, &resolve_state.class.name);
+            let javadoc = match methods.as_slice() {
+                [method] => method
+                    .source
+                    .as_ref()
+                    .or(class_source.as_ref())
+                    .and_then(|s| {
+                        sources::member_javadoc(s, Member::Method(name, method.parameters.len()))
+                    }),
+                _ => None,
+            };
+            Ok(with_javadoc(hover, javadoc))
         }
         CallItem::FieldAccess { name, range } => {
             let Some(method) = resolve_state.class.fields.iter().find(|m| m.name == *name) else {
                 return Err(HoverError::LocalVariableNotFound { name: name.clone() });
             };
             let range = to_lsp_range(range).map_err(HoverError::ToLspRange)?;
-            Ok(field_to_hover(method, range))
+            let javadoc = method
+                .source
+                .clone()
+                .or_else(|| resolve_state.class.get_source())
+                .and_then(|s| sources::member_javadoc(&s, Member::Field(name)));
+            Ok(with_javadoc(field_to_hover(method, range), javadoc))
         }
         CallItem::Variable { name, range } => {
             let Some(var) = lo_va.iter().find(|v| &v.name == name) else {
@@ -137,7 +161,13 @@ pub fn call_chain_hover(
         }
         CallItem::Class { range, .. } | CallItem::ClassGeneric { range, .. } => {
             let range = to_lsp_range(range).map_err(HoverError::ToLspRange)?;
-            Ok(class_to_hover(&resolve_state.class, range))
+            let javadoc = resolve_state.class.get_source().and_then(|s| {
+                sources::member_javadoc(&s, Member::Class(&resolve_state.class.name))
+            });
+            Ok(with_javadoc(
+                class_to_hover(&resolve_state.class, range),
+                javadoc,
+            ))
         }
         CallItem::ClassOrVariable { name, range } => {
             let range = to_lsp_range(range).map_err(HoverError::ToLspRange)?;
@@ -166,14 +196,31 @@ pub fn call_chain_hover(
             }
             Err(HoverError::ArgumentNotFound)
         }
-        CallItem::ArrayAccess { range: _ } | CallItem::This { range: _ } => {
-            Err(HoverError::Unimlemented)
-        }
+        CallItem::ArrayAccess { range: _ }
+        | CallItem::This { range: _ }
+        | CallItem::TypeArguments { .. } => Err(HoverError::Unimlemented),
     }
 }
 
 fn format_field(f: &Field) -> String {
-    format!("{} {};", jtype_hover_display(&f.jtype), f.name)
+    format!(
+        "{}{} {};",
+        annotations_hover(&f.annotations),
+        jtype_hover_display(&f.jtype),
+        f.name
+    )
+}
+
+/// Simple names of annotations followed by a space: `@Nullable `
+#[must_use]
+pub fn annotations_hover(annotations: &[MyString]) -> String {
+    let mut out = String::new();
+    for annotation in annotations {
+        out.push('@');
+        out.push_str(&class_name_hover(annotation).replace('$', "."));
+        out.push(' ');
+    }
+    out
 }
 
 fn format_method(m: &Method, class_name: &str) -> String {
@@ -183,6 +230,7 @@ fn format_method(m: &Method, class_name: &str) -> String {
     }
 
     if let Some(name) = &m.name {
+        out.push_str(&annotations_hover(&m.annotations));
         out.push_str(jtype_hover_display(&m.ret).as_str());
         out.push(' ');
         out.push_str(name.as_str());
@@ -193,6 +241,7 @@ fn format_method(m: &Method, class_name: &str) -> String {
     out.push('(');
     let mut params = m.parameters.iter().peekable();
     while let Some(param) = params.next() {
+        out.push_str(&annotations_hover(&param.annotations));
         out.push_str(jtype_hover_display(&param.jtype).as_str());
         if let Some(name) = &param.name {
             out.push(' ');
@@ -285,7 +334,12 @@ fn field_to_hover(f: &Field, range: Range) -> Hover {
     Hover {
         contents: HoverContents::Markup(MarkupContent {
             kind: MarkupKind::Markdown,
-            value: format!("```java\n{} {}\n```", f.jtype, f.name),
+            value: format!(
+                "```java\n{}{} {}\n```",
+                annotations_hover(&f.annotations),
+                f.jtype,
+                f.name
+            ),
         }),
         range: Some(range),
     }
@@ -307,12 +361,27 @@ fn methods_to_hover(methods: &[Method], range: Range, class_name: &str) -> Hover
     }
 }
 
+/// Append the Javadoc below the code of the hover
+fn with_javadoc(mut hover: Hover, javadoc: Option<String>) -> Hover {
+    if let Some(javadoc) = javadoc
+        && let HoverContents::Markup(markup) = &mut hover.contents
+    {
+        markup.value.push_str("\n\n");
+        markup.value.push_str(&javadoc);
+    }
+    hover
+}
+
 fn class_to_hover(class: &Class, range: Range) -> Hover {
     let value = format!(
         "# {}\n```java\n{}\n```",
         class.name,
         class_to_markdown(class)
     );
+    markdown_hover(value, range)
+}
+
+const fn markdown_hover(value: String, range: Range) -> Hover {
     Hover {
         contents: HoverContents::Markup(MarkupContent {
             kind: MarkupKind::Markdown,
@@ -322,6 +391,88 @@ fn class_to_hover(class: &Class, range: Range) -> Hover {
     }
 }
 
+/// Declaration of an annotation with its elements, none when the class is not an annotation
+///
+/// The source has the defaults and the `@Retention` and `@Target`, the class file only the
+/// elements.
+#[must_use]
+pub fn annotation_to_markdown(class: &Class) -> Option<String> {
+    let name = class.name.rsplit('$').next().unwrap_or_default();
+    let document = class.get_source().and_then(|s| sources::read_source(&s));
+    let declaration = document.as_ref().and_then(|d| {
+        d.ast.top.iter().find_map(|t| match t {
+            AstTopLevel::Thing(thing) => find_annotation(thing, name),
+            AstTopLevel::Package(_)
+            | AstTopLevel::Import(_)
+            | AstTopLevel::Method(_)
+            | AstTopLevel::Module(_) => None,
+        })
+    });
+    let mut value = format!("# {name}\n```java\n");
+    match (document.as_ref(), declaration) {
+        (Some(document), Some(annotation)) => {
+            for meta in annotation
+                .annotated
+                .iter()
+                .filter(|a| matches!(a.name.value.as_str(), "Retention" | "Target"))
+            {
+                let _ = writeln!(value, "{}", document.range_text(&meta.range).trim());
+            }
+            let _ = writeln!(value, "@interface {name} {{");
+            for field in &annotation.fields {
+                let _ = write!(
+                    value,
+                    "    {} {}()",
+                    document.range_text(&field.jtype.range).trim(),
+                    field.name.value
+                );
+                if field.expression.is_some() {
+                    let rest = document.range_text(&AstRange {
+                        start: field.name.range.end,
+                        end: field.range.end,
+                    });
+                    if let Some((_, default)) = rest.split_once("default") {
+                        let _ = write!(value, " default {}", default.trim().trim_end_matches(';'));
+                    }
+                }
+                value.push_str(";\n");
+            }
+        }
+        _ if class.access.contains(Access::Annotation) => {
+            let _ = writeln!(value, "@interface {name} {{");
+            for method in class
+                .methods
+                .iter()
+                .filter(|m| !m.access.contains(Access::Static))
+            {
+                let _ = writeln!(
+                    value,
+                    "    {} {}();",
+                    jtype_hover_display(&method.ret),
+                    method.name.as_deref().unwrap_or_default()
+                );
+            }
+        }
+        _ => return None,
+    }
+    value.push_str("}\n```");
+    Some(value)
+}
+
+fn find_annotation<'a>(thing: &'a AstThing, name: &str) -> Option<&'a AstAnnotation> {
+    let inner = match thing {
+        AstThing::Annotation(annotation) if annotation.name.value == name => {
+            return Some(annotation);
+        }
+        AstThing::Annotation(annotation) => &annotation.inner,
+        AstThing::Class(class) => &class.block.inner,
+        AstThing::Record(record) => &record.block.inner,
+        AstThing::Interface(interface) => &interface.inner,
+        AstThing::Enumeration(enumeration) => &enumeration.inner,
+    };
+    inner.iter().find_map(|t| find_annotation(t, name))
+}
+
 pub fn class_to_markdown(class: &Class) -> String {
     let methods: Vec<_> = class
         .methods
@@ -366,12 +517,15 @@ mod tests {
 
     use ast::types::AstPoint;
     use document::Document;
-    use dto::{Access, Class, JType, Method};
+    use dto::{Access, Class, JType, Method, Parameter, SourceDestination};
     use expect_test::expect;
+    use lsp_types::HoverContents;
     use my_string::{MyString, smol_str::SmolStr};
     use variables::VariableContext;
 
-    use crate::hover::{call_chain_hover, class_action};
+    use crate::hover::{
+        annotation_to_markdown, base, call_chain_hover, class_action, format_method,
+    };
 
     #[test]
     fn class_action_base() {
@@ -406,10 +560,12 @@ public class Test {
                             name: Some(
                                 "length",
                             ),
+                            type_parameters: [],
                             parameters: [],
                             throws: [],
                             ret: Int,
                             source: None,
+                            annotations: [],
                         },
                     ],
                     fields: [],
@@ -465,10 +621,12 @@ public class Test {
                             name: Some(
                                 "length",
                             ),
+                            type_parameters: [],
                             parameters: [],
                             throws: [],
                             ret: Int,
                             source: None,
+                            annotations: [],
                         },
                     ],
                     fields: [],
@@ -547,6 +705,108 @@ public class Test {
         expected.assert_debug_eq(&out);
     }
 
+    #[test]
+    fn method_annotations() {
+        let method = Method {
+            access: Access::Public,
+            name: Some(SmolStr::new_inline("get")),
+            ret: JType::Class(SmolStr::new_inline("java.lang.String")),
+            annotations: vec![SmolStr::new("org.jspecify.annotations.Nullable")],
+            parameters: vec![Parameter {
+                name: Some(SmolStr::new_inline("key")),
+                jtype: JType::Class(SmolStr::new_inline("java.lang.String")),
+                annotations: vec![SmolStr::new("ch.emilycares.Outer$NonNull")],
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            format_method(&method, "Test"),
+            "@Nullable String get(@Outer.NonNull String key);"
+        );
+    }
+
+    #[test]
+    fn annotation_elements() {
+        let dir = std::env::temp_dir().join(format!("hover-annotation-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("CacheResult.java");
+        std::fs::write(
+            &source,
+            "
+package ch.emilycares;
+import java.lang.annotation.*;
+@Documented
+@Retention(RetentionPolicy.RUNTIME)
+@Target({ElementType.METHOD, ElementType.TYPE})
+public @interface CacheResult {
+    String cacheName() default \"\";
+    boolean skipGet() default false;
+    Class<?>[] keys();
+}
+",
+        )
+        .unwrap();
+        let mut class_map = HashMap::new();
+        class_map.insert(
+            SmolStr::new("ch.emilycares.CacheResult"),
+            Class {
+                access: Access::Public,
+                class_path: SmolStr::new("ch.emilycares.CacheResult"),
+                name: SmolStr::new("CacheResult"),
+                source: SourceDestination::Here(source.to_string_lossy().into()),
+                ..Default::default()
+            },
+        );
+        let class_map = Arc::new(RwLock::new(class_map));
+        let content = "
+package ch.emilycares;
+public class Test {
+    @CacheResult
+    public void hello() {}
+}
+";
+        let doc = Document::setup(content, PathBuf::new()).unwrap();
+        let imports = imports::imports(&doc.ast);
+        let out = base(&doc.ast, &AstPoint::new(3, 9), &[], &imports, &class_map).unwrap();
+        let _ = std::fs::remove_dir_all(dir);
+        let HoverContents::Markup(markup) = out.contents else {
+            unreachable!();
+        };
+        let expected = expect![[r#"
+            # CacheResult
+            ```java
+            @Retention(RetentionPolicy.RUNTIME)
+            @Target({ElementType.METHOD, ElementType.TYPE})
+            @interface CacheResult {
+                String cacheName() default "";
+                boolean skipGet() default false;
+                Class<?>[] keys();
+            }
+            ```"#]];
+        expected.assert_eq(&markup.value);
+
+        let compiled = Class {
+            access: Access::Public | Access::Interface | Access::Annotation,
+            name: SmolStr::new("Generated"),
+            methods: vec![Method {
+                access: Access::Public | Access::Abstract,
+                name: Some(SmolStr::new("value")),
+                ret: JType::Array(Box::new(JType::Class(SmolStr::new("java.lang.String")))),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let expected = expect![[r"
+            # Generated
+            ```java
+            @interface Generated {
+                String[] value();
+            }
+            ```"]];
+        expected.assert_eq(&annotation_to_markdown(&compiled).unwrap());
+        assert_eq!(annotation_to_markdown(&Class::default()), None);
+    }
+
     fn string_class_map() -> Arc<RwLock<HashMap<MyString, Class>>> {
         let mut class_map: HashMap<MyString, Class> = HashMap::new();
         class_map.insert(
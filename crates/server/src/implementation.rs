@@ -0,0 +1,258 @@
+//! Go to implementation and the type hierarchy with the subtypes of the [`SubtypeIndex`]
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use ast::types::{AstFile, AstPoint};
+use document::Document;
+use dto::{Class, Method, SourceDestination};
+use lsp_extra::{source_to_uri, to_lsp_range};
+use lsp_types::{Location, TypeHierarchyItem};
+use my_string::MyString;
+use serde_json::Value;
+
+use crate::{
+    definition::{self, DefinitionError},
+    stub,
+    subtypes::SubtypeIndex,
+    symbol_index::symbol_kind,
+};
+
+/// Methods that override the method at the position
+pub const FIND_OVERRIDES: &str = "java/findOverrides";
+
+/// Declarations of the subtypes of the class
+#[must_use]
+pub fn class_implementations(
+    class_path: &str,
+    subtypes: &SubtypeIndex,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+) -> Vec<Location> {
+    classes(&subtypes.all_subtypes(class_path), class_map)
+        .iter()
+        .filter_map(|class| definition::class_location(class, class_map, document_map).ok())
+        .collect()
+}
+
+/// Methods with the name that subtypes of the class declare, only the ones with the
+/// parameters of the method when there is one
+#[must_use]
+pub fn method_implementations(
+    class_path: &str,
+    name: &str,
+    method: Option<&Method>,
+    subtypes: &SubtypeIndex,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+) -> Vec<Location> {
+    let mut out = vec![];
+    let arguments = method.map(overload::parameter_arguments);
+    for class in classes(&subtypes.all_subtypes(class_path), class_map) {
+        let selected = match &arguments {
+            Some(arguments) => tyres::select_methods(&class, Some(name), arguments, class_map),
+            None => class
+                .methods
+                .iter()
+                .filter(|m| m.name.as_deref() == Some(name))
+                .collect(),
+        };
+        if selected.is_empty() {
+            continue;
+        }
+        let Some(source) = stub::class_source(&class, class_map) else {
+            continue;
+        };
+        let Ok(uri) = source_to_uri(&source) else {
+            continue;
+        };
+        let Ok(ast) = definition::source_ast(&source, document_map) else {
+            continue;
+        };
+        let mut ranges = vec![];
+        let nargs = method.map(|m| m.parameters.len());
+        position::get_method_position(&ast, Some(name), nargs, &mut ranges);
+        if let (Some(_), [selected]) = (method, selected.as_slice()) {
+            ranges = definition::overload_range(ranges, &class, selected);
+        }
+        out.extend(
+            ranges
+                .iter()
+                .filter_map(|r| to_lsp_range(&r.range).ok())
+                .map(|range| Location {
+                    uri: uri.clone(),
+                    range,
+                }),
+        );
+    }
+    out
+}
+
+/// Name of the method that is declared on the line of the point
+#[must_use]
+pub fn declared_method(ast: &AstFile, point: &AstPoint) -> Option<MyString> {
+    let mut methods = vec![];
+    position::get_method_position(ast, None, None, &mut methods);
+    methods
+        .into_iter()
+        .find(|m| m.range.start.line == point.line)
+        .map(|m| m.name)
+}
+
+/// Item of the class for the type hierarchy, the class path is kept in its data
+pub fn type_hierarchy_item(
+    class: &Class,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+) -> Result<TypeHierarchyItem, DefinitionError> {
+    let location = definition::class_location(class, class_map, document_map)?;
+    let detail = match &class.source {
+        SourceDestination::None => None,
+        _ => class
+            .class_path
+            .rsplit_once('.')
+            .map(|(package, _)| package.to_string()),
+    };
+    Ok(TypeHierarchyItem {
+        name: class.name.to_string(),
+        kind: symbol_kind(&class.access),
+        tags: None,
+        detail,
+        uri: location.uri,
+        range: location.range,
+        selection_range: location.range,
+        data: Some(Value::String(class.class_path.to_string())),
+    })
+}
+
+/// Items of the classes for the type hierarchy
+#[must_use]
+pub fn type_hierarchy_items(
+    class_paths: &[MyString],
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+) -> Vec<TypeHierarchyItem> {
+    classes(class_paths, class_map)
+        .iter()
+        .filter_map(|class| type_hierarchy_item(class, class_map, document_map).ok())
+        .collect()
+}
+
+/// Class path of an item of the type hierarchy
+#[must_use]
+pub fn item_class_path(item: &TypeHierarchyItem) -> Option<&str> {
+    item.data.as_ref().and_then(Value::as_str)
+}
+
+fn classes(
+    class_paths: &[MyString],
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<Class> {
+    let Ok(class_map) = class_map.read() else {
+        return vec![];
+    };
+    class_paths
+        .iter()
+        .filter_map(|class_path| class_map.get(class_path).cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        path::PathBuf,
+        sync::{Arc, RwLock},
+    };
+
+    use document::Document;
+    use dto::SourceDestination;
+    use my_string::smol_str::SmolStr;
+
+    use super::{class_implementations, method_implementations};
+    use crate::subtypes::SubtypeIndex;
+
+    #[test]
+    fn interface_with_two_implementors() {
+        let class_map = Arc::new(RwLock::new(HashMap::new()));
+        let document_map = Arc::new(RwLock::new(HashMap::new()));
+        for (name, text) in [
+            (
+                "Greeter",
+                "package ch.emilycares;\npublic interface Greeter {\n    String greet();\n    String greet(int times);\n    String greet(String name);\n}\n",
+            ),
+            (
+                "Hello",
+                "package ch.emilycares;\npublic class Hello implements Greeter {\n    public String greet() {\n        return \"hello\";\n    }\n    public String greet(int times) {\n        return \"hello\";\n    }\n    public String greet(String name) {\n        return name;\n    }\n}\n",
+            ),
+            (
+                "Hi",
+                "package ch.emilycares;\n\npublic class Hi implements Greeter {\n    public String greet() {\n        return \"hi\";\n    }\n}\n",
+            ),
+        ] {
+            let path = format!("/project/src/main/java/ch/emilycares/{name}.java");
+            let document = Document::setup(text, PathBuf::from(&path)).unwrap();
+            let class = parser::java::load_java_tree(
+                &document.ast,
+                SourceDestination::Here(SmolStr::new(&path)),
+            );
+            class_map
+                .write()
+                .unwrap()
+                .insert(class.class_path.clone(), class);
+            document_map
+                .write()
+                .unwrap()
+                .insert(SmolStr::new(&path), document);
+        }
+        let subtypes = SubtypeIndex::new(&class_map.read().unwrap());
+        let locations = |locations: Vec<lsp_types::Location>| {
+            let mut out: Vec<_> = locations
+                .into_iter()
+                .map(|l| format!("{}:{}", l.uri.as_str(), l.range.start.line))
+                .collect();
+            out.sort();
+            out
+        };
+        assert_eq!(
+            locations(class_implementations(
+                "ch.emilycares.Greeter",
+                &subtypes,
+                &class_map,
+                &document_map,
+            )),
+            [
+                "file:///project/src/main/java/ch/emilycares/Hello.java:1",
+                "file:///project/src/main/java/ch/emilycares/Hi.java:2",
+            ]
+        );
+        let greeter = class_map
+            .read()
+            .unwrap()
+            .get("ch.emilycares.Greeter")
+            .cloned()
+            .unwrap();
+        let implementations = |index: usize| {
+            locations(method_implementations(
+                "ch.emilycares.Greeter",
+                "greet",
+                Some(&greeter.methods[index]),
+                &subtypes,
+                &class_map,
+                &document_map,
+            ))
+        };
+        assert_eq!(
+            implementations(0),
+            [
+                "file:///project/src/main/java/ch/emilycares/Hello.java:2",
+                "file:///project/src/main/java/ch/emilycares/Hi.java:3",
+            ]
+        );
+        assert_eq!(
+            implementations(2),
+            ["file:///project/src/main/java/ch/emilycares/Hello.java:8"]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Order of the candidates to import a type, by the imports of the file, the package group, the
+//! project and how often a class was chosen before
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use dto::{Class, ImportUnit, SourceDestination};
+use my_string::MyString;
+
+/// Arguments are the uri of the file and the class path that was imported
+pub const COMMAND_IMPORT_CHOSEN: &str = "java_lsp.importChosen";
+
+/// How often each class was imported, per workspace
+#[derive(Debug, Default)]
+pub struct ImportChoices {
+    /// The project cache folder when none is set
+    dir: Option<PathBuf>,
+    workspaces: Mutex<HashMap<PathBuf, HashMap<String, u32>>>,
+}
+
+impl ImportChoices {
+    #[must_use]
+    pub fn with_dir(dir: PathBuf) -> Self {
+        Self {
+            dir: Some(dir),
+            workspaces: Mutex::default(),
+        }
+    }
+
+    /// Counts of the workspace, read from the cache folder the first time
+    #[must_use]
+    pub fn counts(&self, workspace: &Path) -> HashMap<String, u32> {
+        let Ok(mut workspaces) = self.workspaces.lock() else {
+            return HashMap::new();
+        };
+        workspaces
+            .entry(workspace.to_path_buf())
+            .or_insert_with(|| self.read(workspace))
+            .clone()
+    }
+
+    /// Count the import and store the counts of the workspace
+    pub fn choose(&self, workspace: &Path, class_path: &str) {
+        let Ok(mut workspaces) = self.workspaces.lock() else {
+            return;
+        };
+        let counts = workspaces
+            .entry(workspace.to_path_buf())
+            .or_insert_with(|| self.read(workspace));
+        *counts.entry(class_path.to_string()).or_default() += 1;
+        let Ok(json) = serde_json::to_string(counts) else {
+            return;
+        };
+        if let Err(e) = fs::write(self.file(workspace), json) {
+            tracing::warn!("Failed to store the chosen imports: {e:?}");
+        }
+    }
+
+    fn read(&self, workspace: &Path) -> HashMap<String, u32> {
+        fs::read_to_string(self.file(workspace))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn file(&self, workspace: &Path) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        workspace.hash(&mut hasher);
+        let dir = self.dir.clone().unwrap_or_else(common::project_cache_dir);
+        dir.join(format!("{}.imports.json", hasher.finish()))
+    }
+}
+
+/// Sort the class paths, the best candidate first
+pub fn rank(
+    candidates: &mut [&MyString],
+    imports: &[ImportUnit],
+    class_map: &HashMap<MyString, Class>,
+    counts: &HashMap<String, u32>,
+) {
+    let imported: Vec<&str> = imports
+        .iter()
+        .filter_map(|i| match i {
+            ImportUnit::Class(c) | ImportUnit::StaticClass(c) => Some(package(c)),
+            ImportUnit::Prefix(p) => Some(p.as_str()),
+            ImportUnit::Package(_)
+            | ImportUnit::StaticClassMethod(_, _)
+            | ImportUnit::StaticPrefix(_) => None,
+        })
+        .collect();
+    let groups: Vec<&str> = imports
+        .iter()
+        .map(|i| match i {
+            ImportUnit::Class(c) | ImportUnit::StaticClass(c) => package(c),
+            ImportUnit::Package(p)
+            | ImportUnit::Prefix(p)
+            | ImportUnit::StaticPrefix(p)
+            | ImportUnit::StaticClassMethod(p, _) => p,
+        })
+        .map(group)
+        .collect();
+    candidates.sort_by_cached_key(|class_path| {
+        let package = package(class_path);
+        let project = class_map
+            .get(*class_path)
+            .is_some_and(|c| matches!(c.source, SourceDestination::Here(_)));
+        (
+            !imported.contains(&package),
+            !groups.contains(&group(package)),
+            !project,
+            Reverse(counts.get(class_path.as_str()).copied().unwrap_or_default()),
+            !class_path.starts_with("java"),
+            (*class_path).clone(),
+        )
+    });
+}
+
+/// `java.util` of `java.util.Map$Entry`
+fn package(class_path: &str) -> &str {
+    class_path
+        .rsplit_once('.')
+        .map_or("", |(package, _)| package)
+}
+
+/// `org.junit` of `org.junit.jupiter.api`
+fn group(package: &str) -> &str {
+    package
+        .match_indices('.')
+        .nth(1)
+        .map_or(package, |(i, _)| &package[..i])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::Path};
+
+    use dto::{Class, ImportUnit, SourceDestination};
+    use my_string::MyString;
+
+    use super::{ImportChoices, rank};
+
+    fn ranked(imports: &[ImportUnit], counts: &HashMap<String, u32>) -> Vec<String> {
+        let class_map = HashMap::from([(
+            MyString::from("ch.emilycares.util.List"),
+            Class {
+                source: SourceDestination::Here(MyString::from("/p/src/ch/util/List.java")),
+                ..Default::default()
+            },
+        )]);
+        let candidates = [
+            MyString::from("org.eclipse.List"),
+            MyString::from("java.awt.List"),
+            MyString::from("ch.emilycares.util.List"),
+            MyString::from("java.util.List"),
+        ];
+        let mut candidates: Vec<&MyString> = candidates.iter().collect();
+        rank(&mut candidates, imports, &class_map, counts);
+        candidates.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn ranking() {
+        let none = HashMap::new();
+        assert_eq!(
+            ranked(&[], &none),
+            [
+                "ch.emilycares.util.List",
+                "java.awt.List",
+                "java.util.List",
+                "org.eclipse.List"
+            ]
+        );
+        let counts = HashMap::from([("java.util.List".to_string(), 2)]);
+        assert_eq!(ranked(&[], &counts)[1], "java.util.List");
+        let awt = [ImportUnit::Class(MyString::from("java.awt.Frame"))];
+        assert_eq!(ranked(&awt, &counts)[0], "java.awt.List");
+        let eclipse = [ImportUnit::Class(MyString::from("org.eclipse.core.Job"))];
+        assert_eq!(ranked(&eclipse, &none)[0], "org.eclipse.List");
+    }
+
+    #[test]
+    fn choices_are_stored() {
+        let dir = std::env::temp_dir().join(format!("import-rank-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let workspace = Path::new("/p");
+        ImportChoices::with_dir(dir.clone()).choose(workspace, "java.util.List");
+        let choices = ImportChoices::with_dir(dir.clone());
+        choices.choose(workspace, "java.util.List");
+        assert_eq!(choices.counts(workspace).get("java.util.List"), Some(&2));
+        assert!(choices.counts(Path::new("/other")).is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -3,6 +3,7 @@ use std::{
     sync::{Arc, RwLock},
 };
 
+use config::Configuration;
 use document::Document;
 use dto::{Class, ImportUnit};
 use local_variable::VarFlags;
@@ -11,13 +12,15 @@ use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel};
 use my_string::MyString;
 use variables::VariableContext;
 
-use crate::hover::jtype_hover_display;
+use crate::{hover::jtype_hover_display, refactor};
 
+/// Types of `var` declarations and of lambda parameters without a type, as configured
 pub fn get_inlay_hint(
     document: &Document,
     class: &Class,
     imports: &[ImportUnit],
-    class_map: Arc<RwLock<HashMap<MyString, Class>>>,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    config: &Configuration,
 ) -> Option<Vec<InlayHint>> {
     let vars = match variables::get_vars(
         &document.ast,
@@ -25,34 +28,55 @@ pub fn get_inlay_hint(
             point: None,
             imports,
             class,
-            class_map,
+            class_map: class_map.clone(),
         },
     ) {
         Ok(v) => Some(v),
         Err(e) => {
-            eprintln!("Could not get vars: {e:?}");
+            tracing::debug!("Could not get vars: {e:?}");
             None
         }
     }?;
-    Some(
-        vars.iter()
-            .filter(|i| i.flags.intersects(VarFlags::Computed))
-            .filter_map(|i| {
-                let range = to_lsp_range(&i.range).ok()?;
-                let value = jtype_hover_display(&i.jtype);
-                Some(InlayHint {
-                    position: range.start,
-                    label: InlayHintLabel::String(value),
-                    kind: Some(InlayHintKind::TYPE),
-                    text_edits: None,
-                    tooltip: None,
-                    padding_left: None,
-                    padding_right: None,
-                    data: None,
-                })
+    let mut out: Vec<InlayHint> = vec![];
+    if config.inlay_hints {
+        out.extend(
+            vars.iter()
+                .filter(|i| i.flags.intersects(VarFlags::Computed))
+                .filter_map(|i| {
+                    let range = to_lsp_range(&i.range).ok()?;
+                    let value = jtype_hover_display(&i.jtype);
+                    Some(InlayHint {
+                        position: range.start,
+                        label: InlayHintLabel::String(value),
+                        kind: Some(InlayHintKind::TYPE),
+                        text_edits: None,
+                        tooltip: None,
+                        padding_left: None,
+                        padding_right: None,
+                        data: None,
+                    })
+                }),
+        );
+    }
+    if config.lambda_parameter_hints {
+        let parameters =
+            refactor::implicit_lambda_parameters(&document.ast, &vars, imports, class, class_map);
+        out.extend(parameters.into_iter().filter_map(|(parameter, jtype)| {
+            let range = to_lsp_range(&parameter.name.range).ok()?;
+            Some(InlayHint {
+                position: range.start,
+                label: InlayHintLabel::String(jtype_hover_display(&jtype)),
+                kind: Some(InlayHintKind::TYPE),
+                text_edits: None,
+                tooltip: None,
+                padding_left: None,
+                padding_right: Some(true),
+                data: None,
             })
-            .collect(),
-    )
+        }));
+        out.sort_by_key(|h| h.position);
+    }
+    Some(out)
 }
 
 #[cfg(test)]
@@ -60,7 +84,7 @@ mod tests {
     use std::{path::PathBuf, str::FromStr};
 
     use document::Document;
-    use dto::{Access, JType, Method, SourceDestination};
+    use dto::{Access, ClassSignature, JType, Method, Parameter, SourceDestination};
     use expect_test::expect;
     use my_string::smol_str::SmolStr;
 
@@ -80,7 +104,14 @@ public class Test {
         let document = Document::setup(cont, PathBuf::from_str("/Test.java").unwrap()).unwrap();
         let class = parser::java::load_java_tree(&document.ast, SourceDestination::None);
         let imports = imports::imports(&document.ast);
-        let out = get_inlay_hint(&document, &class, &imports, get_class_map()).unwrap();
+        let out = get_inlay_hint(
+            &document,
+            &class,
+            &imports,
+            &get_class_map(),
+            &Configuration::default(),
+        )
+        .unwrap();
         let expected = expect![[r#"
             [
                 InlayHint {
@@ -118,7 +149,14 @@ public class Test {
         let document = Document::setup(cont, PathBuf::from_str("/Test.java").unwrap()).unwrap();
         let class = parser::java::load_java_tree(&document.ast, SourceDestination::None);
         let imports = imports::imports(&document.ast);
-        let out = get_inlay_hint(&document, &class, &imports, get_class_map()).unwrap();
+        let out = get_inlay_hint(
+            &document,
+            &class,
+            &imports,
+            &get_class_map(),
+            &Configuration::default(),
+        )
+        .unwrap();
         let expected = expect![[r#"
             [
                 InlayHint {
@@ -142,6 +180,97 @@ public class Test {
         "#]];
         expected.assert_debug_eq(&out);
     }
+    #[test]
+    fn generics() {
+        let cont = r#"
+package ch.emilycares;
+import java.util.ArrayList;
+import java.util.Collections;
+import java.util.List;
+public class Test {
+    public String hello(List<String> names) {
+        var a = new ArrayList<>();
+        var b = names.get(0);
+        var c = Collections.<String>emptyList();
+        var d = Collections.emptyList();
+        var e = List.of("a");
+        var f = names.stream().findFirst();
+    }
+}
+        "#;
+        let document = Document::setup(cont, PathBuf::from_str("/Test.java").unwrap()).unwrap();
+        let class = parser::java::load_java_tree(&document.ast, SourceDestination::None);
+        let imports = imports::imports(&document.ast);
+        let out: Vec<String> = get_inlay_hint(
+            &document,
+            &class,
+            &imports,
+            &get_class_map(),
+            &Configuration::default(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|h| match h.label {
+            InlayHintLabel::String(label) => format!("{}: {label}", h.position.line),
+            InlayHintLabel::LabelParts(_) => unreachable!(),
+        })
+        .collect();
+        let expected = expect![[r#"
+            [
+                "7: ArrayList<Object>",
+                "8: String",
+                "9: List<String>",
+                "10: List<Object>",
+                "11: List<String>",
+                "12: Optional<String>",
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn lambda_parameters() {
+        let cont = r"
+package ch.emilycares;
+import java.util.List;
+import java.util.function.Predicate;
+public class Test {
+    public void hello(List<String> names) {
+        names.stream().filter(n -> n.length() > 2);
+        Predicate<String> a = (b) -> true;
+        Predicate<String> c = (String d) -> true;
+    }
+}
+        ";
+        let document = Document::setup(cont, PathBuf::from_str("/Test.java").unwrap()).unwrap();
+        let class = parser::java::load_java_tree(&document.ast, SourceDestination::None);
+        let imports = imports::imports(&document.ast);
+        let hints = |config: &Configuration| -> Vec<String> {
+            get_inlay_hint(&document, &class, &imports, &get_class_map(), config)
+                .unwrap()
+                .into_iter()
+                .map(|h| match h.label {
+                    InlayHintLabel::String(label) => {
+                        format!("{}:{} {label}", h.position.line, h.position.character)
+                    }
+                    InlayHintLabel::LabelParts(_) => unreachable!(),
+                })
+                .collect()
+        };
+        let expected = expect![[r#"
+            [
+                "6:30 String",
+                "7:31 String",
+            ]
+        "#]];
+        expected.assert_debug_eq(&hints(&Configuration::default()));
+        let off = Configuration {
+            lambda_parameter_hints: false,
+            ..Default::default()
+        };
+        assert!(hints(&off).is_empty());
+    }
+
     fn get_class_map() -> Arc<RwLock<HashMap<MyString, Class>>> {
         let mut class_map: HashMap<MyString, Class> = HashMap::new();
 
@@ -160,6 +289,103 @@ public class Test {
                 ..Default::default()
             },
         );
+        let generic = |class_path: &str, args: &[&str]| {
+            JType::Generic(
+                class_path.into(),
+                args.iter().map(|a| JType::Parameter((*a).into())).collect(),
+            )
+        };
+        let method = |name: &str, type_parameters: &[&str], parameters: Vec<JType>, ret| Method {
+            access: Access::Public,
+            name: Some(name.into()),
+            type_parameters: type_parameters.iter().map(|t| (*t).into()).collect(),
+            parameters: parameters
+                .into_iter()
+                .map(|jtype| Parameter {
+                    jtype,
+                    ..Default::default()
+                })
+                .collect(),
+            ret,
+            ..Default::default()
+        };
+        let class = |class_path: &str, args: &[&str], methods| Class {
+            access: Access::Public,
+            class_path: class_path.into(),
+            name: class_path.rsplit('.').next().unwrap_or_default().into(),
+            signature: Some(ClassSignature {
+                args: args.iter().map(|a| (*a).into()).collect(),
+                ret: JType::Var,
+            }),
+            methods,
+            ..Default::default()
+        };
+        for c in [
+            class(
+                "java.util.List",
+                &["E"],
+                vec![
+                    method("get", &[], vec![JType::Int], JType::Parameter("E".into())),
+                    method(
+                        "stream",
+                        &[],
+                        vec![],
+                        generic("java.util.stream.Stream", &["E"]),
+                    ),
+                    method(
+                        "of",
+                        &["E"],
+                        vec![JType::Parameter("E".into())],
+                        generic("java.util.List", &["E"]),
+                    ),
+                ],
+            ),
+            class("java.util.ArrayList", &["E"], vec![]),
+            class(
+                "java.util.Collections",
+                &[],
+                vec![method(
+                    "emptyList",
+                    &["T"],
+                    vec![],
+                    generic("java.util.List", &["T"]),
+                )],
+            ),
+            class(
+                "java.util.stream.Stream",
+                &["T"],
+                vec![
+                    method(
+                        "findFirst",
+                        &[],
+                        vec![],
+                        generic("java.util.Optional", &["T"]),
+                    ),
+                    method(
+                        "filter",
+                        &[],
+                        vec![generic("java.util.function.Predicate", &["T"])],
+                        generic("java.util.stream.Stream", &["T"]),
+                    ),
+                ],
+            ),
+            class(
+                "java.util.function.Predicate",
+                &["T"],
+                vec![Method {
+                    access: Access::Public | Access::Abstract,
+                    ..method(
+                        "test",
+                        &[],
+                        vec![JType::Parameter("T".into())],
+                        JType::Boolean,
+                    )
+                }],
+            ),
+            class("java.util.Optional", &["T"], vec![]),
+        ] {
+            class_map.insert(c.class_path.clone(), c);
+        }
         Arc::new(RwLock::new(class_map))
     }
 }
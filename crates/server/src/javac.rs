@@ -0,0 +1,293 @@
+//! Arguments of javac for the files of a project and the annotation processors it runs
+//!
+//! Build tools generate the sources of annotation processors into their own folders, like
+//! `target/generated-sources` of maven, they are loaded with the project. With `run` in the
+//! `[processors]` of the `java_lsp.toml` javac runs the processors of a saved file with
+//! `-proc:only`, the classes of the generated sources are loaded afterwards.
+use std::path::{Path, PathBuf};
+
+use common::{language_level::LanguageLevel, project_kind::ProjectKind};
+use compile::{CompileError, CompileErrorMessage};
+use config::project::ProjectProcessors;
+use document::source_set::SourceSet;
+use dto::Class;
+use lsp_extra::SERVER_NAME;
+use lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+use maven::modules;
+
+use crate::{backend::Project, jpms, plain_project, test_scope};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Javac {
+    /// Folder that javac runs in
+    pub dir: String,
+    pub classpath: String,
+    /// Folder for the classes, absolute or relative to `dir`
+    pub output: PathBuf,
+    /// Folder for the sources of annotation processors, absolute or relative to `dir`
+    pub generated: PathBuf,
+    /// `module-info.java` of the source root, the classpath is the module path of a module
+    pub module_info: Option<PathBuf>,
+    pub level: LanguageLevel,
+    /// Home of the JDK for the release, see [`jdk::installations::select`]
+    pub jdk: Option<PathBuf>,
+}
+
+impl Javac {
+    /// Compile the file, the dependencies of modules are on the module path
+    pub fn compile(&self, path: &str) -> Result<Vec<CompileErrorMessage>, CompileError> {
+        let output = self.output.to_string_lossy();
+        let Some(source_root) = self.module_info.as_deref().and_then(Path::parent) else {
+            return compile::classpath_compile_java_file(
+                path,
+                &self.classpath,
+                &self.dir,
+                &output,
+                self.level.release,
+                self.level.preview,
+                self.jdk.as_deref(),
+            );
+        };
+        // The module that is compiled is not a module of the module path
+        let compiled = Path::new(&self.dir).join(&self.output);
+        let module_path = self
+            .classpath
+            .split(compile::CLASSPATH_SEPARATOR)
+            .filter(|entry| Path::new(&self.dir).join(entry) != compiled)
+            .collect::<Vec<_>>()
+            .join(&compile::CLASSPATH_SEPARATOR.to_string());
+        compile::module_compile_java_file(
+            path,
+            &module_path,
+            &source_root.to_string_lossy(),
+            &self.dir,
+            &output,
+            self.level.release,
+            self.level.preview,
+            self.jdk.as_deref(),
+        )
+    }
+
+    /// Absolute entries to run the classes of the file with, the output of javac comes first.
+    /// Maven compiles tests into their own folder.
+    #[must_use]
+    pub fn run_classpath(&self, kind: &ProjectKind, path: &Path) -> Vec<PathBuf> {
+        let dir = Path::new(&self.dir);
+        let mut entries = vec![dir.join(&self.output)];
+        if matches!(kind, ProjectKind::Maven { .. }) && SourceSet::of(path) == SourceSet::Test {
+            entries.push(dir.join("target/test-classes"));
+        }
+        entries.extend(
+            self.classpath
+                .split(compile::CLASSPATH_SEPARATOR)
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| dir.join(entry)),
+        );
+        entries
+    }
+}
+
+/// Arguments to compile the file, none when the build tool compiles the project itself or there
+/// is no classpath
+#[must_use]
+pub fn javac(project: &Project, path: &Path) -> Option<Javac> {
+    let mut javac = kind_javac(project, path)?;
+    javac.module_info = jpms::module_info_of(path);
+    javac.level = project.language_level(path);
+    javac.jdk = jdk::installations::home(javac.level.release);
+    Some(javac)
+}
+
+fn kind_javac(project: &Project, path: &Path) -> Option<Javac> {
+    match &project.kind {
+        ProjectKind::Maven { executable } => {
+            let classpath = match maven::compile::generate_classpath(executable) {
+                Ok(classpath) => classpath,
+                Err(e) => {
+                    tracing::error!("Failed to load classpath {e:?}");
+                    return None;
+                }
+            };
+            let Some(module) = modules::module_of(&project.modules, path) else {
+                return Some(Javac {
+                    dir: project.dir.clone(),
+                    classpath: main_or_test_classpath(classpath, path),
+                    output: PathBuf::from("target/classes"),
+                    generated: PathBuf::from("target/generated-sources/annotations"),
+                    module_info: None,
+                    level: LanguageLevel::default(),
+                    jdk: None,
+                });
+            };
+            let mut classpath = main_or_test_classpath(classpath, path);
+            for output in modules::dependency_outputs(&project.modules, module) {
+                classpath.push(compile::CLASSPATH_SEPARATOR);
+                classpath.push_str(&output.to_string_lossy());
+            }
+            Some(Javac {
+                dir: module.dir.to_string_lossy().to_string(),
+                classpath,
+                output: PathBuf::from("target/classes"),
+                generated: PathBuf::from("target/generated-sources/annotations"),
+                module_info: None,
+                level: LanguageLevel::default(),
+                jdk: None,
+            })
+        }
+        ProjectKind::Eclipse { .. } => {
+            let classpath = match eclipse::classpath::load_classpath(Path::new(&project.dir)) {
+                Ok(classpath) => classpath,
+                Err(e) => {
+                    tracing::error!("Failed to load eclipse classpath {e:?}");
+                    return None;
+                }
+            };
+            let output = classpath.output.clone().unwrap_or_else(|| "bin".into());
+            let jars = eclipse::project::classpath_jars(&classpath);
+            let classpath = std::iter::once(output.as_path())
+                .chain(jars)
+                .map(|p| p.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(&compile::CLASSPATH_SEPARATOR.to_string());
+            Some(Javac {
+                dir: project.dir.clone(),
+                classpath,
+                output,
+                generated: PathBuf::from(eclipse::project::APT_GENERATED),
+                module_info: None,
+                level: LanguageLevel::default(),
+                jdk: None,
+            })
+        }
+        ProjectKind::Unknown => {
+            let (classpath, output, generated) =
+                plain_project::javac_classpath(Path::new(&project.dir))?;
+            Some(Javac {
+                dir: project.dir.clone(),
+                classpath,
+                output,
+                generated,
+                module_info: None,
+                level: LanguageLevel::default(),
+                jdk: None,
+            })
+        }
+        // Gradle runs the processors with its compile task, bazel and build servers build the
+        // targets themselves
+        ProjectKind::Gradle { .. } | ProjectKind::Bazel { .. } | ProjectKind::Bsp { .. } => None,
+    }
+}
+
+/// Diagnostic of an error that javac reported, its rows start at one
+#[must_use]
+pub fn diagnostic(e: &CompileErrorMessage) -> Diagnostic {
+    let r = u32::try_from(e.row).unwrap_or_default();
+    let c = u32::try_from(e.col).unwrap_or_default();
+    let p = Position::new(r.saturating_sub(1), c);
+    Diagnostic::new(
+        Range::new(p, p),
+        Some(DiagnosticSeverity::ERROR),
+        None,
+        Some(String::from(SERVER_NAME)),
+        e.message.clone(),
+        None,
+        None,
+    )
+}
+
+/// Main sources do not see the test dependencies
+fn main_or_test_classpath(classpath: String, path: &Path) -> String {
+    match SourceSet::of(path) {
+        SourceSet::Main => test_scope::main_classpath(&classpath),
+        SourceSet::Test => classpath,
+    }
+}
+
+/// Run the annotation processors of the file and parse the sources they generated, empty when
+/// the processors are not configured to run
+#[must_use]
+pub fn generate(project: &Project, path: &str, processors: &ProjectProcessors) -> Vec<Class> {
+    if !processors.run {
+        return Vec::new();
+    }
+    let Some(javac) = javac(project, Path::new(path)) else {
+        return Vec::new();
+    };
+    let processor_path = (!processors.path.is_empty()).then(|| {
+        processors
+            .path
+            .iter()
+            .map(|p| {
+                Path::new(&project.dir)
+                    .join(p)
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(&compile::CLASSPATH_SEPARATOR.to_string())
+    });
+    match compile::generate_sources(
+        path,
+        &javac.classpath,
+        processor_path.as_deref(),
+        &javac.dir,
+        &javac.generated.to_string_lossy(),
+        javac.jdk.as_deref(),
+    ) {
+        Ok(errors) if !errors.is_empty() => {
+            tracing::debug!("Annotation processors reported: {errors:?}");
+        }
+        Ok(_) => (),
+        Err(e) => {
+            tracing::warn!("Failed to run annotation processors: {e:?}");
+            return Vec::new();
+        }
+    }
+    let generated = Path::new(&javac.dir).join(javac.generated);
+    loader::load_java_folders(vec![generated], |_, _| {})
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Position, Range};
+
+    use super::diagnostic;
+
+    #[test]
+    fn javac_output_diagnostics() {
+        let output = "\
+/project/src/App.java:4: error: cannot find symbol
+        Strin name = \"a\";
+        ^
+  symbol:   class Strin
+  location: class App
+/project/src/App.java:7: warning: [deprecation] stop() in Thread has been deprecated
+        thread.stop();
+              ^
+/project/src/Other.java:2: error: ';' expected
+import java.util.List
+                     ^
+3 errors
+";
+        let diagnostics: Vec<_> = compile::parse_compile_errors(output)
+            .iter()
+            .map(|e| (e.path.clone(), diagnostic(e)))
+            .map(|(path, d)| (path, d.range, d.message))
+            .collect();
+        assert_eq!(
+            diagnostics,
+            [
+                (
+                    "/project/src/App.java".to_string(),
+                    Range::new(Position::new(3, 8), Position::new(3, 8)),
+                    "cannot find symbol".to_string(),
+                ),
+                (
+                    "/project/src/Other.java".to_string(),
+                    Range::new(Position::new(1, 21), Position::new(1, 21)),
+                    "';' expected".to_string(),
+                ),
+            ]
+        );
+    }
+}
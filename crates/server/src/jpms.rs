@@ -0,0 +1,426 @@
+//! Java modules of projects with a `module-info.java`
+//!
+//! A module reads `java.base`, the modules it requires and the modules that those require
+//! transitively. Of another module it can only use the packages that are exported to it. The
+//! module of a project class is the `module-info.java` of its source root, jdk classes are in a
+//! folder named after their module. Classes of other dependencies are not checked.
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use ast::types::{AstImportUnit, AstModule, AstModuleRequiresFlags, AstTopLevel};
+use document::Document;
+use dto::{Class, SourceDestination};
+use lsp_extra::{SERVER_NAME, to_lsp_range};
+use lsp_types::{Diagnostic, NumberOrString};
+use my_string::MyString;
+
+use crate::diagnostics::default_severity;
+
+/// Diagnostic code of imports from a module that is not required
+pub const NOT_READ: &str = "module_not_read";
+/// Diagnostic code of imports from a package that its module does not export
+pub const NOT_EXPORTED: &str = "package_not_exported";
+
+pub const MODULE_INFO: &str = "module-info.java";
+const JAVA_BASE: &str = "java.base";
+
+/// Modules of the jdk that other modules of the jdk require transitively
+const JDK_TRANSITIVE: [(&str, &[&str]); 6] = [
+    (
+        "java.sql",
+        &["java.logging", "java.transaction.xa", "java.xml"],
+    ),
+    (
+        "java.sql.rowset",
+        &["java.logging", "java.naming", "java.sql"],
+    ),
+    ("java.desktop", &["java.datatransfer", "java.xml"]),
+    ("java.management.rmi", &["java.management", "java.rmi"]),
+    ("java.xml.crypto", &["java.xml"]),
+    ("java.compiler", &["java.logging"]),
+];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ModuleDescriptor {
+    pub name: String,
+    /// Required modules and whether they are required transitive
+    pub requires: Vec<(String, bool)>,
+    /// Exported packages and the modules they are exported to, all modules when it is empty
+    pub exports: Vec<(String, Vec<String>)>,
+}
+
+impl ModuleDescriptor {
+    #[must_use]
+    pub fn from_ast(module: &AstModule) -> Self {
+        Self {
+            name: module.name.value.to_string(),
+            requires: module
+                .requires
+                .iter()
+                .map(|r| {
+                    (
+                        r.name.value.to_string(),
+                        r.flags.contains(AstModuleRequiresFlags::Transitive),
+                    )
+                })
+                .collect(),
+            exports: module
+                .exports
+                .iter()
+                .map(|e| {
+                    (
+                        e.name.value.to_string(),
+                        e.to.iter().map(|t| t.value.to_string()).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn load(module_info: &Path) -> Option<Self> {
+        let content = fs::read(module_info).ok()?;
+        let tokens = ast::lexer::lex(&content).ok()?;
+        let ast = ast::parse_file(&tokens).ok()?;
+        ast.top.iter().find_map(|top| match top {
+            AstTopLevel::Module(module) => Some(Self::from_ast(module)),
+            _ => None,
+        })
+    }
+
+    #[must_use]
+    pub fn exports_to(&self, package: &str, module: &str) -> bool {
+        self.exports
+            .iter()
+            .any(|(p, to)| p == package && (to.is_empty() || to.iter().any(|t| t == module)))
+    }
+}
+
+/// `module-info.java` of the source root that contains the file
+#[must_use]
+pub fn module_info_of(file: &Path) -> Option<PathBuf> {
+    file.ancestors()
+        .skip(1)
+        .map(|dir| dir.join(MODULE_INFO))
+        .find(|f| f.is_file())
+}
+
+/// Module that a class belongs to
+#[derive(Debug, PartialEq, Eq)]
+enum Owner {
+    /// `module-info.java` of a project module
+    Project(PathBuf),
+    Jdk(String),
+}
+
+fn owner(class: &Class) -> Option<Owner> {
+    match &class.source {
+        SourceDestination::Here(path) => {
+            module_info_of(Path::new(path.as_str())).map(Owner::Project)
+        }
+        SourceDestination::RelativeInFolder(folder)
+        | SourceDestination::RelativeInFolderLang(folder, _) => {
+            let name = Path::new(folder.as_str()).file_name()?.to_str()?;
+            (name.starts_with("java.") || name.starts_with("jdk."))
+                .then(|| Owner::Jdk(name.to_string()))
+        }
+        SourceDestination::None => None,
+    }
+}
+
+/// Modules that the descriptor reads
+struct Readable<'a> {
+    descriptor: &'a ModuleDescriptor,
+    /// Descriptors of the project modules by their `module-info.java`
+    project: HashMap<PathBuf, Option<ModuleDescriptor>>,
+}
+
+impl Readable<'_> {
+    fn project_module(&mut self, module_info: &Path) -> Option<&ModuleDescriptor> {
+        self.project
+            .entry(module_info.to_path_buf())
+            .or_insert_with(|| ModuleDescriptor::load(module_info))
+            .as_ref()
+    }
+
+    /// The module is required, or required transitive by a required module of the jdk or of
+    /// the project
+    fn reads(&mut self, module: &str, project_modules: &[(String, PathBuf)]) -> bool {
+        if module == JAVA_BASE || module == self.descriptor.name {
+            return true;
+        }
+        let mut queue: Vec<String> = self
+            .descriptor
+            .requires
+            .iter()
+            .map(|(r, _)| r.clone())
+            .collect();
+        let mut seen = Vec::new();
+        while let Some(required) = queue.pop() {
+            if required == module || required == "java.se" && module.starts_with("java.") {
+                return true;
+            }
+            if seen.contains(&required) {
+                continue;
+            }
+            if let Some((_, transitive)) = JDK_TRANSITIVE.iter().find(|(m, _)| *m == required) {
+                queue.extend(transitive.iter().map(ToString::to_string));
+            }
+            if let Some((_, module_info)) = project_modules.iter().find(|(m, _)| *m == required)
+                && let Some(descriptor) = self.project_module(module_info)
+            {
+                queue.extend(
+                    descriptor
+                        .requires
+                        .iter()
+                        .filter(|(_, transitive)| *transitive)
+                        .map(|(r, _)| r.clone()),
+                );
+            }
+            seen.push(required);
+        }
+        false
+    }
+}
+
+/// Imports of classes of modules that the module of the document does not read or that are in a
+/// package that is not exported to it
+#[must_use]
+pub fn module_visibility(
+    document: &Document,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<Diagnostic> {
+    let Some(module_info) = module_info_of(&document.path) else {
+        return vec![];
+    };
+    let Some(descriptor) = ModuleDescriptor::load(&module_info) else {
+        return vec![];
+    };
+    let Ok(class_map) = class_map.read() else {
+        return vec![];
+    };
+    let mut readable = Readable {
+        descriptor: &descriptor,
+        project: HashMap::new(),
+    };
+    let imports: Vec<_> = document
+        .ast
+        .top
+        .iter()
+        .filter_map(|top| match top {
+            AstTopLevel::Import(import) => Some(import),
+            _ => None,
+        })
+        .filter_map(|import| match &import.unit {
+            AstImportUnit::Class(c)
+            | AstImportUnit::StaticClass(c)
+            | AstImportUnit::StaticClassMethod(c, _) => {
+                Some((import, class_map.get(c.value.as_str())?))
+            }
+            _ => None,
+        })
+        .collect();
+    let owners: Vec<_> = imports
+        .iter()
+        .filter_map(|(import, class)| Some((*import, *class, owner(class)?)))
+        .filter(|(_, _, owner)| *owner != Owner::Project(module_info.clone()))
+        .collect();
+    let project_modules: Vec<(String, PathBuf)> = owners
+        .iter()
+        .filter_map(|(_, _, owner)| match owner {
+            Owner::Project(other) => Some(other.clone()),
+            Owner::Jdk(_) => None,
+        })
+        .filter_map(|other| {
+            let name = readable.project_module(&other)?.name.clone();
+            Some((name, other))
+        })
+        .collect();
+
+    let mut out = vec![];
+    for (import, class, owner) in owners {
+        let package = class
+            .class_path
+            .rsplit_once('.')
+            .map_or("", |(package, _)| package);
+        let (message, code) = match owner {
+            Owner::Jdk(module) => {
+                if readable.reads(&module, &project_modules) {
+                    continue;
+                }
+                let message = format!(
+                    "module '{}' does not read module '{module}'",
+                    descriptor.name
+                );
+                (message, NOT_READ)
+            }
+            Owner::Project(other) => {
+                let Some((module, exported)) = readable.project_module(&other).map(|other| {
+                    (
+                        other.name.clone(),
+                        other.exports_to(package, &descriptor.name),
+                    )
+                }) else {
+                    continue;
+                };
+                if !exported {
+                    let message =
+                        format!("package '{package}' is not exported by module '{module}'");
+                    (message, NOT_EXPORTED)
+                } else if readable.reads(&module, &project_modules) {
+                    continue;
+                } else {
+                    let message = format!(
+                        "module '{}' does not read module '{module}'",
+                        descriptor.name
+                    );
+                    (message, NOT_READ)
+                }
+            }
+        };
+        let Ok(range) = to_lsp_range(&import.range) else {
+            continue;
+        };
+        out.push(Diagnostic {
+            range,
+            severity: default_severity(code),
+            code: Some(NumberOrString::String(code.to_string())),
+            source: Some(SERVER_NAME.to_string()),
+            message,
+            ..Default::default()
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        fs,
+        sync::{Arc, RwLock},
+    };
+
+    use document::Document;
+    use dto::{Class, SourceDestination};
+    use my_string::MyString;
+
+    use super::{ModuleDescriptor, NOT_EXPORTED, NOT_READ, module_visibility};
+
+    #[test]
+    fn visibility() {
+        let dir = std::env::temp_dir().join(format!("jpms-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let write = |path: &str, content: &str| {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        };
+        write(
+            "app/src/module-info.java",
+            "module ch.app { requires ch.lib; requires java.sql; }",
+        );
+        write(
+            "lib/src/module-info.java",
+            "module ch.lib { requires transitive ch.api; exports ch.lib; exports ch.lib.spi to ch.other; }",
+        );
+        write(
+            "api/src/module-info.java",
+            "module ch.api { exports ch.api; }",
+        );
+        write(
+            "other/src/module-info.java",
+            "module ch.other { exports ch.other; }",
+        );
+        let class = |class_path: &str, source: SourceDestination| {
+            (
+                MyString::from(class_path),
+                Class {
+                    class_path: MyString::from(class_path),
+                    source,
+                    ..Default::default()
+                },
+            )
+        };
+        let here =
+            |path: &str| SourceDestination::Here(MyString::from(dir.join(path).to_str().unwrap()));
+        let jdk = |module: &str| {
+            SourceDestination::RelativeInFolder(MyString::from(format!("/jdk/src/{module}")))
+        };
+        let class_map = Arc::new(RwLock::new(HashMap::from([
+            class("ch.lib.Lib", here("lib/src/ch/lib/Lib.java")),
+            class(
+                "ch.lib.internal.Impl",
+                here("lib/src/ch/lib/internal/Impl.java"),
+            ),
+            class("ch.lib.spi.Spi", here("lib/src/ch/lib/spi/Spi.java")),
+            class("ch.api.Api", here("api/src/ch/api/Api.java")),
+            class("ch.other.Other", here("other/src/ch/other/Other.java")),
+            class("java.util.List", jdk("java.base")),
+            class("java.sql.Connection", jdk("java.sql")),
+            class("java.util.logging.Logger", jdk("java.logging")),
+            class("java.net.http.HttpClient", jdk("java.net.http")),
+        ])));
+        let content = "package ch.app;
+import ch.lib.Lib;
+import ch.lib.internal.Impl;
+import ch.lib.spi.Spi;
+import ch.api.Api;
+import ch.other.Other;
+import java.util.List;
+import java.sql.Connection;
+import java.util.logging.Logger;
+import java.net.http.HttpClient;
+class App {}
+";
+        let path = write("app/src/ch/app/App.java", content);
+        let document = Document::setup(content, path).unwrap();
+        let found: Vec<_> = module_visibility(&document, &class_map)
+            .into_iter()
+            .map(|d| (d.range.start.line, d.code.unwrap(), d.message))
+            .collect();
+        let code = |c: &str| lsp_types::NumberOrString::String(c.to_string());
+        assert_eq!(
+            found,
+            vec![
+                (
+                    2,
+                    code(NOT_EXPORTED),
+                    "package 'ch.lib.internal' is not exported by module 'ch.lib'".to_string()
+                ),
+                (
+                    3,
+                    code(NOT_EXPORTED),
+                    "package 'ch.lib.spi' is not exported by module 'ch.lib'".to_string()
+                ),
+                (
+                    5,
+                    code(NOT_READ),
+                    "module 'ch.app' does not read module 'ch.other'".to_string()
+                ),
+                (
+                    9,
+                    code(NOT_READ),
+                    "module 'ch.app' does not read module 'java.net.http'".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            ModuleDescriptor::load(&dir.join("lib/src/module-info.java")).unwrap(),
+            ModuleDescriptor {
+                name: "ch.lib".to_string(),
+                requires: vec![("ch.api".to_string(), true)],
+                exports: vec![
+                    ("ch.lib".to_string(), vec![]),
+                    ("ch.lib.spi".to_string(), vec!["ch.other".to_string()]),
+                ],
+            }
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,399 @@
+//! `JUnit` tests of the projects and running them with the console launcher of the platform
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{Arc, RwLock},
+};
+
+use document::source_set::SourceSet;
+use dto::{Class, Method, SourceDestination};
+use lsp_extra::source_to_uri;
+use lsp_server::{Connection, Message};
+use lsp_types::Uri;
+use my_string::MyString;
+use serde::{Deserialize, Serialize};
+
+use crate::{backend::Project, javac};
+
+/// Request for the tests of a file or of all projects
+pub const TESTS: &str = "java/tests";
+/// Command that runs a test class, or one method with the method name as second argument
+pub const COMMAND_RUN_TEST: &str = "java/runTest";
+/// Notification with a line of the test feed
+pub const TEST_RESULT: &str = "java/testResult";
+
+/// Annotations of `JUnit` 4 and `JUnit` Jupiter that make a method a test
+const TEST_ANNOTATIONS: [&str; 5] = [
+    "Test",
+    "ParameterizedTest",
+    "RepeatedTest",
+    "TestFactory",
+    "TestTemplate",
+];
+const LAUNCHER_GROUP: &str = "org/junit/platform";
+const LAUNCHER_ARTIFACT: &str = "junit-platform-console-standalone";
+
+#[derive(Debug)]
+pub enum RunTestError {
+    UnknownClass(String),
+    /// The project is built by a tool that javac does not get a classpath of
+    NoClasspath,
+    NoLauncher,
+    Io(std::io::Error),
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TestsParams {
+    /// Only the tests of this file
+    pub uri: Option<Uri>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TestClass {
+    pub class_path: String,
+    pub uri: Uri,
+    pub methods: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TestStatus {
+    Started,
+    Successful,
+    Failed,
+    Aborted,
+    Skipped,
+    /// The launcher exited, `message` has the exit code when tests failed
+    Finished,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TestResult {
+    /// Class that is run
+    pub class_path: String,
+    /// Display names from the engine to the test, joined by ` > `
+    pub test: String,
+    pub status: TestStatus,
+    /// Exception of a failed test
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+fn is_test(method: &Method) -> bool {
+    method.annotations.iter().any(|a| {
+        let name = a.rsplit('.').next().unwrap_or(a);
+        TEST_ANNOTATIONS.contains(&name)
+    })
+}
+
+/// Test classes with their test methods, sorted by class path
+#[must_use]
+pub fn tests(
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    file: Option<&Path>,
+) -> Vec<TestClass> {
+    let Ok(class_map) = class_map.read() else {
+        return Vec::new();
+    };
+    let mut out: Vec<TestClass> = class_map
+        .values()
+        .filter_map(|class| {
+            let SourceDestination::Here(source) = &class.source else {
+                return None;
+            };
+            let source = Path::new(source.as_str());
+            if file.is_some_and(|f| f != source) || SourceSet::of(source) != SourceSet::Test {
+                return None;
+            }
+            let methods: Vec<String> = class
+                .methods
+                .iter()
+                .filter(|m| is_test(m))
+                .filter_map(|m| m.name.as_ref().map(ToString::to_string))
+                .collect();
+            if methods.is_empty() {
+                return None;
+            }
+            Some(TestClass {
+                class_path: class.class_path.to_string(),
+                uri: source_to_uri(source.to_str()?).ok()?,
+                methods,
+            })
+        })
+        .collect();
+    out.sort_by(|a, b| a.class_path.cmp(&b.class_path));
+    out
+}
+
+/// Status of a line of the test feed, `JUnit Jupiter > ATest > adds() :: SUCCESSFUL`
+#[must_use]
+pub fn parse_feed_line(line: &str) -> Option<(String, TestStatus)> {
+    let (test, status) = line
+        .rsplit_once(" :: ")
+        .or_else(|| line.rsplit_once(" > "))?;
+    let status = match status.trim() {
+        "STARTED" => TestStatus::Started,
+        "SUCCESSFUL" => TestStatus::Successful,
+        "FAILED" => TestStatus::Failed,
+        "ABORTED" => TestStatus::Aborted,
+        "SKIPPED" => TestStatus::Skipped,
+        _ => return None,
+    };
+    Some((test.trim().to_string(), status))
+}
+
+/// Console launcher of the local maven repository with the highest version
+#[must_use]
+pub fn find_launcher(m2: &Path) -> Option<PathBuf> {
+    let dir = m2
+        .join("repository")
+        .join(LAUNCHER_GROUP)
+        .join(LAUNCHER_ARTIFACT);
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter_map(|e| {
+            let version = e.file_name().to_str()?.to_string();
+            let jar = e.path().join(format!("{LAUNCHER_ARTIFACT}-{version}.jar"));
+            jar.exists().then_some((version_key(&version), jar))
+        })
+        .max()
+        .map(|(_, jar)| jar)
+}
+
+fn version_key(version: &str) -> Vec<u32> {
+    version
+        .split(['.', '-'])
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// Arguments of `java` that run the class or its method with the launcher
+#[must_use]
+pub fn launcher_args(
+    launcher: &Path,
+    classpath: &str,
+    class_path: &str,
+    method: Option<&str>,
+) -> Vec<String> {
+    let select = method.map_or_else(
+        || format!("--select-class={class_path}"),
+        |method| format!("--select-method={class_path}#{method}"),
+    );
+    vec![
+        "-jar".to_string(),
+        launcher.to_string_lossy().to_string(),
+        "execute".to_string(),
+        "--disable-banner".to_string(),
+        "--disable-ansi-colors".to_string(),
+        "--details=testfeed".to_string(),
+        format!("--class-path={classpath}"),
+        select,
+    ]
+}
+
+/// Classpath of the tests of the file, the classes of javac and of the build tool come first
+fn test_classpath(project: &Project, file: &Path) -> Option<(String, String)> {
+    let javac = javac::javac(project, file)?;
+    let classpath = javac
+        .run_classpath(&project.kind, file)
+        .iter()
+        .map(|entry| entry.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(&compile::CLASSPATH_SEPARATOR.to_string());
+    Some((javac.dir, classpath))
+}
+
+/// Start the launcher for the class and send its results from a background thread
+pub fn run_test(
+    con: &Arc<Connection>,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    projects: &[Project],
+    launcher: Option<&Path>,
+    java_path: std::ffi::OsString,
+    class_path: &str,
+    method: Option<&str>,
+) -> Result<(), RunTestError> {
+    let source = class_map
+        .read()
+        .ok()
+        .and_then(|cm| match cm.get(class_path).map(|c| &c.source) {
+            Some(SourceDestination::Here(source)) => Some(PathBuf::from(source.as_str())),
+            _ => None,
+        })
+        .ok_or_else(|| RunTestError::UnknownClass(class_path.to_string()))?;
+    let project = projects
+        .iter()
+        .find(|p| source.starts_with(&p.dir))
+        .ok_or(RunTestError::NoClasspath)?;
+    let (dir, classpath) = test_classpath(project, &source).ok_or(RunTestError::NoClasspath)?;
+    let launcher = match launcher {
+        Some(launcher) => launcher.to_path_buf(),
+        None => maven::m2::get_maven_m2_folder()
+            .ok()
+            .and_then(|m2| find_launcher(&m2))
+            .ok_or(RunTestError::NoLauncher)?,
+    };
+    let mut child = Command::new("java")
+        .env(
+            "PATH",
+            jdk::installations::java_path(project.language_level(&source).release, java_path),
+        )
+        .current_dir(dir)
+        .args(launcher_args(&launcher, &classpath, class_path, method))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(RunTestError::Io)?;
+    let Some(stdout) = child.stdout.take() else {
+        let _ = child.kill();
+        return Err(RunTestError::NoClasspath);
+    };
+    let con = con.clone();
+    let class_path = class_path.to_string();
+    std::thread::spawn(move || {
+        let send = |test: String, status, message| {
+            send_result(
+                &con,
+                &TestResult {
+                    class_path: class_path.clone(),
+                    test,
+                    status,
+                    message,
+                },
+            );
+        };
+        // The exception of a failed test follows its line
+        let mut failed: Option<(String, TestStatus, Vec<String>)> = None;
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if let Some((test, status)) = parse_feed_line(&line) {
+                if let Some((test, status, message)) = failed.take() {
+                    send(test, status, Some(message.join("\n")));
+                }
+                match status {
+                    TestStatus::Failed | TestStatus::Aborted => {
+                        failed = Some((test, status, Vec::new()));
+                    }
+                    _ => send(test, status, None),
+                }
+            } else if let Some((_, _, message)) = &mut failed
+                && !line.trim().is_empty()
+            {
+                message.push(line.trim().to_string());
+            }
+        }
+        if let Some((test, status, message)) = failed.take() {
+            send(test, status, Some(message.join("\n")));
+        }
+        let message = match child.wait() {
+            Ok(status) if status.success() => None,
+            Ok(status) => Some(format!("Launcher exited with {status}")),
+            Err(e) => Some(format!("Launcher failed: {e}")),
+        };
+        send(String::new(), TestStatus::Finished, message);
+    });
+    Ok(())
+}
+
+fn send_result(con: &Connection, result: &TestResult) {
+    if let Ok(params) = serde_json::to_value(result) {
+        let _ = con
+            .sender
+            .send(Message::Notification(lsp_server::Notification {
+                method: TEST_RESULT.to_string(),
+                params,
+            }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+        sync::{Arc, RwLock},
+    };
+
+    use dto::{Class, Method, SourceDestination};
+    use my_string::MyString;
+
+    use super::{TestStatus, launcher_args, parse_feed_line, tests};
+
+    #[test]
+    fn test_methods() {
+        let method = |name: &str, annotations: &[&str]| Method {
+            name: Some(MyString::from(name)),
+            annotations: annotations.iter().map(|a| MyString::from(*a)).collect(),
+            ..Default::default()
+        };
+        let class = |class_path: &str, source: &str| Class {
+            class_path: MyString::from(class_path),
+            source: SourceDestination::Here(MyString::from(source)),
+            methods: vec![
+                method("adds", &["Test"]),
+                method("cases", &["org.junit.jupiter.params.ParameterizedTest"]),
+                method("setUp", &["BeforeEach"]),
+            ],
+            ..Default::default()
+        };
+        let class_map = Arc::new(RwLock::new(HashMap::from([
+            (
+                MyString::from("ch.CalcTest"),
+                class("ch.CalcTest", "/p/src/test/java/ch/CalcTest.java"),
+            ),
+            (
+                MyString::from("ch.Calc"),
+                class("ch.Calc", "/p/src/main/java/ch/Calc.java"),
+            ),
+        ])));
+        let found = tests(&class_map, None);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].class_path, "ch.CalcTest");
+        assert_eq!(found[0].methods, vec!["adds", "cases"]);
+        assert!(
+            tests(
+                &class_map,
+                Some(Path::new("/p/src/test/java/ch/Other.java"))
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn feed_lines() {
+        assert_eq!(
+            parse_feed_line("JUnit Jupiter > CalcTest > adds() :: SUCCESSFUL"),
+            Some((
+                "JUnit Jupiter > CalcTest > adds()".to_string(),
+                TestStatus::Successful
+            ))
+        );
+        assert_eq!(
+            parse_feed_line("JUnit Vintage > CalcTest > fails > FAILED"),
+            Some((
+                "JUnit Vintage > CalcTest > fails".to_string(),
+                TestStatus::Failed
+            ))
+        );
+        assert_eq!(
+            parse_feed_line("\torg.opentest4j.AssertionFailedError: expected: <1>"),
+            None
+        );
+    }
+
+    #[test]
+    fn launcher_arguments() {
+        let args = launcher_args(
+            &PathBuf::from("/m2/launcher.jar"),
+            "target/classes",
+            "ch.CalcTest",
+            Some("adds"),
+        );
+        assert_eq!(args[1], "/m2/launcher.jar");
+        assert_eq!(args.last().unwrap(), "--select-method=ch.CalcTest#adds");
+    }
+}
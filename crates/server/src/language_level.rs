@@ -0,0 +1,463 @@
+//! Language features that are newer than the release the project compiles with
+use ast::{
+    lexer::{self, PositionToken, Token},
+    types::{AstBlockEntry, AstFile, AstPoint, AstRange},
+    visit::{Visitor, walk_block_entry},
+};
+use common::language_level::LanguageLevel;
+use lsp_extra::{SERVER_NAME, to_lsp_range};
+use lsp_types::{Diagnostic, NumberOrString};
+
+use crate::diagnostics::default_severity;
+
+/// Diagnostic code of features that the language level of the project does not have
+pub const LANGUAGE_LEVEL: &str = "language_level";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    Var,
+    SwitchRules,
+    Yield,
+    TextBlocks,
+    Records,
+    PatternMatching,
+    SealedClasses,
+    SwitchPatterns,
+    RecordPatterns,
+    UnnamedVariables,
+}
+
+impl Feature {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Var => "'var'",
+            Self::SwitchRules => "Switch rules",
+            Self::Yield => "'yield'",
+            Self::TextBlocks => "Text blocks",
+            Self::Records => "Records",
+            Self::PatternMatching => "Pattern matching for instanceof",
+            Self::SealedClasses => "Sealed classes",
+            Self::SwitchPatterns => "Patterns in switch",
+            Self::RecordPatterns => "Record patterns",
+            Self::UnnamedVariables => "Unnamed variables",
+        }
+    }
+
+    /// Release that has the feature and the first release that had it as a preview feature
+    const fn releases(self) -> (u32, Option<u32>) {
+        match self {
+            Self::Var => (10, None),
+            Self::SwitchRules | Self::Yield => (14, Some(12)),
+            Self::TextBlocks => (15, Some(13)),
+            Self::Records | Self::PatternMatching => (16, Some(14)),
+            Self::SealedClasses => (17, Some(15)),
+            Self::SwitchPatterns => (21, Some(17)),
+            Self::RecordPatterns => (21, Some(19)),
+            Self::UnnamedVariables => (22, Some(21)),
+        }
+    }
+
+    /// The feature can be used with the language level
+    #[must_use]
+    pub const fn available(self, level: LanguageLevel) -> bool {
+        let Some(release) = level.release else {
+            return true;
+        };
+        let (since, preview) = self.releases();
+        match preview {
+            _ if release >= since => true,
+            Some(preview) => level.preview && release >= preview,
+            None => false,
+        }
+    }
+
+    fn message(self, level: LanguageLevel) -> String {
+        let (since, preview) = self.releases();
+        let required = preview.map_or_else(
+            || format!("Java {since}"),
+            |preview| format!("Java {since}, or Java {preview} with preview features"),
+        );
+        format!(
+            "{} require {required}, the project compiles with {level}",
+            self.name()
+        )
+    }
+}
+
+/// Features of the tokens and the syntax tree that the language level does not have
+#[must_use]
+pub fn language_level(
+    tokens: &[PositionToken],
+    ast: &AstFile,
+    level: LanguageLevel,
+) -> Vec<Diagnostic> {
+    if level.release.is_none() {
+        return vec![];
+    }
+    let mut found = features(tokens);
+    found.extend(ast_features(ast));
+    found.sort_by_key(|(_, range)| (range.start.line, range.start.col));
+    unavailable(found, level)
+}
+
+/// The diagnostics of the features that the language level does not have when one is on the
+/// line of the syntax error, the syntax error otherwise
+#[must_use]
+pub fn syntax_error(error: Diagnostic, text: &str, level: LanguageLevel) -> Vec<Diagnostic> {
+    if level.release.is_none() {
+        return vec![error];
+    }
+    let Ok(tokens) = lexer::lex(text.as_bytes()) else {
+        return vec![error];
+    };
+    let found = unavailable(features(&tokens), level);
+    if found
+        .iter()
+        .any(|d| d.range.start.line == error.range.start.line)
+    {
+        return found;
+    }
+    vec![error]
+}
+
+fn unavailable(features: Vec<(Feature, AstRange)>, level: LanguageLevel) -> Vec<Diagnostic> {
+    features
+        .into_iter()
+        .filter(|(feature, _)| !feature.available(level))
+        .filter_map(|(feature, range)| {
+            Some(Diagnostic {
+                range: to_lsp_range(&range).ok()?,
+                severity: default_severity(LANGUAGE_LEVEL),
+                code: Some(NumberOrString::String(LANGUAGE_LEVEL.to_string())),
+                source: Some(SERVER_NAME.to_string()),
+                message: feature.message(level),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Features that are nodes of the syntax tree
+#[must_use]
+pub fn ast_features(ast: &AstFile) -> Vec<(Feature, AstRange)> {
+    let mut finder = AstFeatures { found: vec![] };
+    finder.visit_file(ast);
+    finder.found
+}
+
+struct AstFeatures {
+    found: Vec<(Feature, AstRange)>,
+}
+
+impl<'a> Visitor<'a> for AstFeatures {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        if let AstBlockEntry::SwitchCaseArrowType(case) = entry {
+            self.found.push((Feature::SwitchPatterns, case.var.range));
+        }
+        walk_block_entry(self, entry);
+    }
+}
+
+/// Features by the tokens they start with, keywords that are also valid names need the next
+/// tokens
+#[must_use]
+pub fn features(tokens: &[PositionToken]) -> Vec<(Feature, AstRange)> {
+    let code: Vec<&PositionToken> = tokens
+        .iter()
+        .filter(|t| !matches!(t.token, Token::LineComment(_) | Token::BlockComment(_, _)))
+        .collect();
+    let next = |i: usize| code.get(i + 1).map(|t| &t.token);
+    let mut out = Vec::new();
+    for (i, token) in code.iter().enumerate() {
+        let feature = match &token.token {
+            Token::Var if matches!(next(i), Some(Token::Identifier(_))) => Feature::Var,
+            Token::Yield
+                if !matches!(
+                    next(i),
+                    Some(Token::Dot | Token::Equal | Token::LeftParen | Token::Semicolon) | None
+                ) =>
+            {
+                Feature::Yield
+            }
+            // A record pattern in a case needs a newer release than the switch rule
+            Token::InstanceOf | Token::Case if is_record_pattern(&code[i + 1..]) => {
+                Feature::RecordPatterns
+            }
+            Token::Case if is_switch_rule(&code[i + 1..]) => Feature::SwitchRules,
+            Token::StringLiteralMulti(..) => Feature::TextBlocks,
+            Token::Record
+                if matches!(next(i), Some(Token::Identifier(_)))
+                    && matches!(
+                        code.get(i + 2).map(|t| &t.token),
+                        Some(Token::LeftParen | Token::Lt)
+                    ) =>
+            {
+                Feature::Records
+            }
+            Token::InstanceOf if is_type_pattern(&code[i + 1..]) => Feature::PatternMatching,
+            Token::Identifier(name) if name == "when" && is_guard(&code[..i]) => {
+                Feature::SwitchPatterns
+            }
+            Token::Sealed | Token::Permits => Feature::SealedClasses,
+            Token::Underscore => Feature::UnnamedVariables,
+            _ => continue,
+        };
+        let start = token.start_point();
+        let end = match token.token {
+            // Only the opening quotes, the text block spans lines
+            Token::StringLiteralMulti(..) => AstPoint {
+                line: start.line,
+                col: start.col + 3,
+            },
+            _ => token.end_point(),
+        };
+        out.push((feature, AstRange { start, end }));
+    }
+    out
+}
+
+/// The label of the case ends with an arrow instead of a colon
+fn is_switch_rule(rest: &[&PositionToken]) -> bool {
+    let mut depth = 0usize;
+    for token in rest {
+        match token.token {
+            Token::LeftParen | Token::LeftParenCurly => depth += 1,
+            Token::Arrow if depth == 0 => return true,
+            Token::RightParen | Token::RightParenCurly | Token::Colon | Token::Semicolon
+                if depth == 0 =>
+            {
+                return false;
+            }
+            Token::RightParen | Token::RightParenCurly => depth -= 1,
+            _ => (),
+        }
+    }
+    false
+}
+
+/// A parenthesized list of components follows the record type
+fn is_record_pattern(rest: &[&PositionToken]) -> bool {
+    let mut tokens = rest.iter().map(|t| &t.token).peekable();
+    if !matches!(tokens.next(), Some(Token::Identifier(_))) {
+        return false;
+    }
+    while tokens.next_if(|t| matches!(t, Token::Dot)).is_some() {
+        if !matches!(tokens.next(), Some(Token::Identifier(_))) {
+            return false;
+        }
+    }
+    matches!(tokens.next(), Some(Token::LeftParen))
+}
+
+/// The `when` is in the label of a case, before its arrow or colon
+fn is_guard(before: &[&PositionToken]) -> bool {
+    for token in before.iter().rev() {
+        match token.token {
+            Token::Case => return true,
+            Token::Arrow
+            | Token::Colon
+            | Token::Semicolon
+            | Token::LeftParenCurly
+            | Token::RightParenCurly => return false,
+            _ => (),
+        }
+    }
+    false
+}
+
+/// A binding follows the type of the `instanceof`
+fn is_type_pattern(rest: &[&PositionToken]) -> bool {
+    let mut tokens = rest.iter().map(|t| &t.token).peekable();
+    if tokens.next_if(|t| matches!(t, Token::Final)).is_some() {
+        return true;
+    }
+    if !matches!(tokens.next(), Some(Token::Identifier(_))) {
+        return false;
+    }
+    while tokens.next_if(|t| matches!(t, Token::Dot)).is_some() {
+        if !matches!(tokens.next(), Some(Token::Identifier(_))) {
+            return false;
+        }
+    }
+    if tokens.next_if(|t| matches!(t, Token::Lt)).is_some() {
+        let mut depth = 1usize;
+        while depth > 0 {
+            match tokens.next() {
+                Some(Token::Lt) => depth += 1,
+                Some(Token::Gt) => depth -= 1,
+                Some(_) => (),
+                None => return false,
+            }
+        }
+    }
+    while tokens
+        .next_if(|t| matches!(t, Token::LeftParenSquare))
+        .is_some()
+    {
+        if !matches!(tokens.next(), Some(Token::RightParenSquare)) {
+            return false;
+        }
+    }
+    matches!(tokens.next(), Some(Token::Identifier(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ast::lexer;
+    use common::language_level::LanguageLevel;
+    use document::Document;
+    use lsp_types::{Diagnostic, NumberOrString, Position, Range};
+
+    use super::{Feature, ast_features, features, syntax_error};
+
+    #[test]
+    fn language_features() {
+        let content = r#"
+sealed interface Shape permits Circle {}
+record Circle(int r) implements Shape {}
+class A {
+    int record = 1;
+    String a(Object o) {
+        var text = """
+            text""";
+        if (o instanceof String s && o instanceof java.util.List<?> l) {
+            return s;
+        }
+        if (o instanceof String) {}
+        int r = switch (record) {
+            case 1 -> 2;
+            default -> { yield 3; }
+        };
+        switch (r) {
+            case 1:
+                break;
+        }
+        return "";
+    }
+}
+"#;
+        let tokens = lexer::lex(content.as_bytes()).unwrap();
+        let found: Vec<Feature> = features(&tokens).into_iter().map(|(f, _)| f).collect();
+        assert_eq!(
+            found,
+            vec![
+                Feature::SealedClasses,
+                Feature::SealedClasses,
+                Feature::Records,
+                Feature::Var,
+                Feature::TextBlocks,
+                Feature::PatternMatching,
+                Feature::PatternMatching,
+                Feature::SwitchRules,
+                Feature::Yield,
+            ]
+        );
+    }
+
+    #[test]
+    fn available() {
+        let level = |release, preview| LanguageLevel {
+            release: Some(release),
+            preview,
+        };
+        assert!(!Feature::TextBlocks.available(level(8, false)));
+        assert!(!Feature::TextBlocks.available(level(13, false)));
+        assert!(Feature::TextBlocks.available(level(13, true)));
+        assert!(Feature::TextBlocks.available(level(17, false)));
+        assert!(!Feature::Var.available(level(9, true)));
+        assert!(Feature::Records.available(LanguageLevel::default()));
+        assert!(!Feature::SwitchPatterns.available(level(17, false)));
+        assert!(Feature::SwitchPatterns.available(level(21, false)));
+    }
+
+    #[test]
+    fn pattern_features() {
+        let content = "
+class A {
+    int a(Object o) {
+        if (o instanceof Point(int x, int y)) {}
+        return switch (o) {
+            case Point(int x, int y) -> x;
+            case String s when s.isEmpty() -> 0;
+            default -> 1;
+        };
+    }
+}
+";
+        let tokens = lexer::lex(content.as_bytes()).unwrap();
+        let found: Vec<Feature> = features(&tokens).into_iter().map(|(f, _)| f).collect();
+        assert_eq!(
+            found,
+            vec![
+                Feature::RecordPatterns,
+                Feature::RecordPatterns,
+                Feature::SwitchRules,
+                Feature::SwitchPatterns,
+            ]
+        );
+    }
+
+    #[test]
+    fn switch_type_pattern() {
+        let content = "
+class A {
+    int a(Object o) {
+        return switch (o) {
+            case String s -> 1;
+            default -> 0;
+        };
+    }
+}
+";
+        let document = Document::setup(content, PathBuf::new()).unwrap();
+        let found: Vec<(Feature, usize)> = ast_features(&document.ast)
+            .into_iter()
+            .map(|(f, r)| (f, r.start.line))
+            .collect();
+        assert_eq!(found, vec![(Feature::SwitchPatterns, 4)]);
+    }
+
+    #[test]
+    fn replaced_syntax_error() {
+        let content = "
+class A {
+    void a(Object o) {
+        if (o instanceof Point(int x, int y)) {}
+    }
+}
+";
+        let error = Diagnostic {
+            range: Range::new(Position::new(3, 30), Position::new(3, 31)),
+            message: "expected ')' but found '('".to_string(),
+            ..Default::default()
+        };
+        let level = |release| LanguageLevel {
+            release: Some(release),
+            preview: false,
+        };
+        let replaced = syntax_error(error.clone(), content, level(11));
+        assert_eq!(replaced.len(), 1);
+        assert_eq!(
+            replaced[0].code,
+            Some(NumberOrString::String("language_level".to_string()))
+        );
+        assert_eq!(
+            replaced[0].message,
+            "Record patterns require Java 21, or Java 19 with preview features, the project compiles with Java 11"
+        );
+        assert_eq!(
+            syntax_error(error.clone(), content, level(21)),
+            vec![error.clone()]
+        );
+        let elsewhere = Diagnostic {
+            range: Range::new(Position::new(1, 0), Position::new(1, 1)),
+            ..error
+        };
+        assert_eq!(
+            syntax_error(elsewhere.clone(), content, level(11)),
+            vec![elsewhere]
+        );
+    }
+}
@@ -4,29 +4,71 @@
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::too_many_lines)]
 #![allow(clippy::implicit_hasher)]
+pub mod analyzers;
 pub mod backend;
+pub mod call_graph;
+pub mod cancel;
+pub mod check;
+pub mod class_contents;
 pub mod code_lens;
 pub mod codeaction;
 pub mod command;
 pub mod completion;
+pub mod conflicts;
+pub mod dead_code;
+pub mod debug;
+pub mod definite_assignment;
 pub mod definition;
+pub mod diagnostic_report;
+pub mod diagnostics;
 pub mod document_link;
+pub mod exceptions;
+pub mod flow;
 pub mod folding_range;
 pub mod hover;
+pub mod implementation;
+pub mod import_rank;
 pub mod inlay_hint;
+pub mod javac;
+pub mod jpms;
+pub mod junit;
+pub mod language_level;
+pub mod lint_fix;
+pub mod logging;
+pub mod main_class;
+pub mod name_index;
+pub mod nullability;
+pub mod panic_guard;
+pub mod plain_project;
+pub mod queries;
+pub mod quickfix;
+pub mod refactor;
 pub mod references;
 pub mod router;
+pub mod scratch;
 pub mod signature;
 pub mod snipptes;
+pub mod sources;
+pub mod status;
+pub mod stub;
+pub mod subtypes;
+pub mod switch_cases;
+pub mod symbol_index;
+pub mod symbol_search;
+pub mod test_scope;
+pub mod transport;
+pub mod type_check;
+pub mod watched_files;
 
 use std::{ffi::OsString, path::PathBuf, sync::Arc};
 
-use lsp_server::{Connection, IoThreads, ProtocolError};
+use lsp_server::{Connection, ProtocolError};
 use lsp_types::{InitializeParams, ProgressToken};
 
 use crate::{
     backend::{Backend, project_kind_to_project},
     router::get_server_capabilities,
+    transport::{Address, IoThreads, TransportError},
 };
 
 /// Accept connection over stdio
@@ -34,6 +76,7 @@ use crate::{
 /// # Panics
 /// When it could not init project
 pub fn stdio() -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    logging::init();
     let Ok(project_dir) = std::env::current_dir() else {
         return Ok(());
     };
@@ -41,7 +84,7 @@ pub fn stdio() -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
         return Ok(());
     };
     let (connection, io_threads) = Connection::stdio();
-    main(connection, io_threads, &project_dir, path)
+    main(connection, io_threads.into(), &project_dir, path)
 }
 
 /// Accept connection over tcp
@@ -49,14 +92,50 @@ pub fn stdio() -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
 /// # Panics
 /// When it could not init project
 pub fn listen(port: u16) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    serve(|| transport::listen(&Address::Tcp(format!("127.0.0.1:{port}"))))
+}
+
+/// Accept connection on a `tcp://` or `unix://` address
+///
+/// # Panics
+/// When it could not init project
+pub fn listen_address(address: &str) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    serve(|| transport::listen(&Address::parse(address)?))
+}
+
+/// Connect to the named pipe or unix socket of the editor
+///
+/// # Panics
+/// When it could not init project
+pub fn pipe(name: &str) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    serve(|| transport::pipe(name))
+}
+
+/// Connect to the tcp port of the editor
+///
+/// # Panics
+/// When it could not init project
+pub fn socket(port: u16) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    serve(|| transport::socket(port))
+}
+
+fn serve(
+    connect: impl FnOnce() -> Result<(Connection, IoThreads), TransportError>,
+) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    logging::init();
     let Ok(project_dir) = std::env::current_dir() else {
         return Ok(());
     };
     let Some(path) = std::env::var_os("PATH") else {
         return Ok(());
     };
-    let addr = (String::from("127.0.0.1"), port);
-    let (connection, io_threads) = Connection::listen(addr)?;
+    let (connection, io_threads) = match connect() {
+        Ok(it) => it,
+        Err(e) => {
+            tracing::error!("Unable to open connection: {e:?}");
+            return Err(format!("{e:?}").into());
+        }
+    };
     main(connection, io_threads, &project_dir, path)
 }
 
@@ -70,13 +149,14 @@ pub fn main(
     project_dir: &PathBuf,
     path: OsString,
 ) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
-    let project_kind = common::project_kind::get_project_kind(project_dir, &path);
+    logging::connect(&connection);
+    let project_kind = backend::get_project_kind(project_dir, &path);
     if let Err(e) = project_kind {
-        eprintln!("Error with project init: {e:?}");
+        tracing::error!("Error with project init: {e:?}");
         std::process::exit(1);
     }
     let project_kind = project_kind.expect("Program should already have exited");
-    eprintln!("Start java_lsp with project_kind: {project_kind:?}");
+    tracing::info!("Start java_lsp with project_kind: {project_kind:?}");
     let mut backend = Backend::new(connection);
     if let Some(dir) = project_dir.to_str()
         && let Ok(mut projects) = backend.projects.write()
@@ -94,11 +174,11 @@ pub fn main(
             return Err(e.into());
         }
     };
-    main_loop(&backend, path, progress)?;
+    main_loop(&mut backend, path, progress)?;
     io_threads.join()?;
 
     // Shut down gracefully.
-    eprintln!("shutting down server");
+    tracing::info!("shutting down server");
     Ok(())
 }
 
@@ -109,8 +189,10 @@ fn initialize(backend: &mut Backend) -> Result<Option<ProgressToken>, ProtocolEr
     backend.client_capabilities = Arc::new(Some(params.capabilities));
     backend.fill_projects(params.workspace_folders);
     backend.fill_config(params.initialization_options);
-    let server_capabilities =
+    let mut server_capabilities =
         serde_json::to_value(get_server_capabilities(&backend.config)).unwrap_or_default();
+    // Not a field of the server capabilities in lsp-types
+    server_capabilities["typeHierarchyProvider"] = serde_json::Value::Bool(true);
     let initialize_data = serde_json::json!({
         "capabilities": server_capabilities,
     });
@@ -119,10 +201,12 @@ fn initialize(backend: &mut Backend) -> Result<Option<ProgressToken>, ProtocolEr
 }
 
 fn main_loop(
-    backend: &Backend,
+    backend: &mut Backend,
     path: OsString,
     progress: Option<ProgressToken>,
 ) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    let path = backend.index_java_path(path);
+    let release = backend.config.java_release;
     let connection = backend.connection.clone();
     let class_map = backend.class_map.clone();
     let reference_map = backend.reference_map.clone();
@@ -134,10 +218,17 @@ fn main_loop(
             &class_map,
             reference_map,
             &path,
+            release,
             projects,
         )
         .await;
     });
+    if backend.config.status_updates {
+        tokio::spawn(backend.clone().status_updates());
+    }
+    panic_guard::install_hook();
+    backend.register_file_watchers();
+    backend.request_configuration();
     router::route(backend)?;
     Ok(())
 }
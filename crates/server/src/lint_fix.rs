@@ -0,0 +1,333 @@
+//! Code actions of the findings of the external analyzers
+use ast::{
+    range::GetRange,
+    types::{
+        AstAnnotated, AstBlockVariable, AstClassConstructor, AstClassMethod, AstClassVariable,
+        AstInterfaceMethodDefault, AstPoint, AstRange, AstThing,
+    },
+    visit::{self, Visitor},
+};
+use document::Document;
+use lsp_extra::{to_ast_point, to_lsp_range};
+use lsp_types::{CodeActionOrCommand, Diagnostic, NumberOrString, Position, Range, TextEdit};
+use my_string::MyString;
+
+use crate::{
+    analyzers::{self, Analyzer},
+    codeaction::CodeActionContext,
+    quickfix::quick_fix,
+    refactor::{import_edits, line_indent},
+};
+
+const SUPPRESS_WARNINGS: &str = "SuppressWarnings";
+const SUPPRESS_FB_WARNINGS: &str = "edu.umd.cs.findbugs.annotations.SuppressFBWarnings";
+
+/// Suggested fixes and suppressions of the analyzer diagnostics
+#[must_use]
+pub fn code_actions(
+    document: &Document,
+    context: &CodeActionContext,
+    diagnostics: &[Diagnostic],
+) -> Vec<CodeActionOrCommand> {
+    let mut out = Vec::new();
+    for diagnostic in diagnostics {
+        let Some(tool) = Analyzer::of(diagnostic) else {
+            continue;
+        };
+        let Some(NumberOrString::String(rule)) = &diagnostic.code else {
+            continue;
+        };
+        for fix in analyzers::suggested_fixes(diagnostic) {
+            out.push(quick_fix(context, fix.title, diagnostic, fix.edits));
+        }
+        let point = to_ast_point(diagnostic.range.start);
+        let (annotation, value) = match tool {
+            Analyzer::Pmd => (SUPPRESS_WARNINGS, format!("PMD.{rule}")),
+            Analyzer::SpotBugs => (SUPPRESS_FB_WARNINGS, rule.clone()),
+        };
+        // Annotations of local variables are not in the class files
+        let locals = tool == Analyzer::Pmd;
+        if let Some(mut edits) = suppress_edits(document, &point, annotation, &value, locals) {
+            if annotation.contains('.') {
+                edits.extend(import_edits(
+                    document,
+                    context,
+                    &[MyString::from(annotation)],
+                ));
+            }
+            let name = simple_name(annotation);
+            out.push(quick_fix(
+                context,
+                format!("Suppress {rule} with @{name}"),
+                diagnostic,
+                edits,
+            ));
+        }
+        if tool == Analyzer::Pmd {
+            out.push(quick_fix(
+                context,
+                format!("Suppress {rule} with // NOPMD"),
+                diagnostic,
+                vec![nopmd_edit(document, diagnostic.range.start.line, rule)],
+            ));
+        }
+    }
+    out
+}
+
+/// `// NOPMD` at the end of the line
+fn nopmd_edit(document: &Document, line: u32, rule: &str) -> TextEdit {
+    let text = document
+        .rope
+        .get_line(line as usize)
+        .map(|l| l.to_string())
+        .unwrap_or_default();
+    let content = text.trim_end_matches(['\n', '\r']);
+    let end = Position::new(
+        line,
+        u32::try_from(content.chars().count()).unwrap_or_default(),
+    );
+    TextEdit {
+        range: Range::new(end, end),
+        new_text: format!(" // NOPMD - {rule}"),
+    }
+}
+
+/// Annotate the declaration around the point or add the value to its annotation. None when the
+/// value is already suppressed.
+fn suppress_edits(
+    document: &Document,
+    point: &AstPoint,
+    annotation: &str,
+    value: &str,
+    locals: bool,
+) -> Option<Vec<TextEdit>> {
+    let mut finder = DeclarationFinder {
+        point,
+        locals,
+        found: None,
+    };
+    finder.visit_file(&document.ast);
+    let (range, annotated) = finder.found?;
+    let quoted = format!("\"{value}\"");
+    let name = simple_name(annotation);
+    if let Some(existing) = annotated
+        .iter()
+        .find(|a| simple_name(&a.name.value) == name)
+    {
+        let text = document.range_text(&existing.range);
+        if text.contains(&quoted) {
+            return None;
+        }
+        return Some(vec![TextEdit {
+            range: to_lsp_range(&existing.range).ok()?,
+            new_text: add_value(&text, &quoted)?,
+        }]);
+    }
+    let line = u32::try_from(range.start.line).ok()?;
+    let start = Position::new(line, 0);
+    Some(vec![TextEdit {
+        range: Range::new(start, start),
+        new_text: format!(
+            "{}@{name}({quoted})\n",
+            line_indent(document, range.start.line)
+        ),
+    }])
+}
+
+/// `@SuppressWarnings({"a", "b"})` of `@SuppressWarnings("a")` and the value `"b"`
+fn add_value(annotation: &str, value: &str) -> Option<String> {
+    let open = annotation.find('(')?;
+    let close = annotation.rfind(')')?;
+    let arguments = annotation.get(open + 1..close)?.trim();
+    let (prefix, values) = match arguments.split_once('=') {
+        Some((name, values)) if name.trim() == "value" => ("value = ", values.trim()),
+        _ => ("", arguments),
+    };
+    let values = match values.strip_prefix('{').and_then(|v| v.strip_suffix('}')) {
+        Some(list) if list.trim().is_empty() => value.to_string(),
+        Some(list) => format!("{}, {value}", list.trim().trim_end_matches(',')),
+        None => format!("{values}, {value}"),
+    };
+    Some(format!("{}({prefix}{{{values}}})", &annotation[..open]))
+}
+
+fn simple_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// Innermost declaration that can be annotated around the point
+struct DeclarationFinder<'a, 'b> {
+    point: &'b AstPoint,
+    /// Local variables are declarations too
+    locals: bool,
+    found: Option<(AstRange, &'a [AstAnnotated])>,
+}
+
+impl<'a> DeclarationFinder<'a, '_> {
+    fn found(&mut self, range: AstRange, annotated: &'a [AstAnnotated]) {
+        if range.is_in_range(self.point) {
+            self.found = Some((range, annotated));
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for DeclarationFinder<'a, '_> {
+    fn visit_thing(&mut self, thing: &'a AstThing) {
+        let annotated = match thing {
+            AstThing::Class(class) => &class.annotated,
+            AstThing::Record(record) => &record.annotated,
+            AstThing::Interface(interface) => &interface.annotated,
+            AstThing::Enumeration(enumeration) => &enumeration.annotated,
+            AstThing::Annotation(annotation) => &annotation.annotated,
+        };
+        self.found(thing.get_range(), annotated);
+        visit::walk_thing(self, thing);
+    }
+
+    fn visit_class_variable(&mut self, variable: &'a AstClassVariable) {
+        self.found(variable.range, &variable.annotated);
+        visit::walk_class_variable(self, variable);
+    }
+
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        self.found(method.range, &method.header.annotated);
+        visit::walk_class_method(self, method);
+    }
+
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        self.found(constructor.range, &constructor.header.annotated);
+        visit::walk_class_constructor(self, constructor);
+    }
+
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        self.found(method.range, &method.header.annotated);
+        visit::walk_interface_method_default(self, method);
+    }
+
+    fn visit_block_variable(&mut self, variable: &'a AstBlockVariable) {
+        if self.locals {
+            self.found(variable.range, &variable.annotated);
+        }
+        visit::walk_block_variable(self, variable);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::types::AstPoint;
+    use expect_test::expect;
+    use lsp_types::{Diagnostic, NumberOrString, Position, Range, TextEdit};
+
+    use super::{add_value, code_actions};
+    use crate::{
+        analyzers::{Analyzer, SuggestedFix},
+        refactor::tests::run_actions,
+    };
+
+    fn finding(tool: Analyzer, line: u32, character: u32, rule: &str) -> Diagnostic {
+        let position = Position::new(line, character);
+        Diagnostic {
+            range: Range::new(position, position),
+            code: Some(NumberOrString::String(rule.to_string())),
+            source: Some(tool.name().to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn add_suppressed_value() {
+        assert_eq!(
+            add_value("@SuppressWarnings(\"unchecked\")", "\"PMD.X\"").as_deref(),
+            Some("@SuppressWarnings({\"unchecked\", \"PMD.X\"})")
+        );
+        assert_eq!(
+            add_value("@SuppressWarnings({\"a\", \"b\"})", "\"PMD.X\"").as_deref(),
+            Some("@SuppressWarnings({\"a\", \"b\", \"PMD.X\"})")
+        );
+        assert_eq!(
+            add_value("@SuppressWarnings(value = {})", "\"PMD.X\"").as_deref(),
+            Some("@SuppressWarnings(value = {\"PMD.X\"})")
+        );
+    }
+
+    #[test]
+    fn pmd_suppressions() {
+        let cont = r#"
+package ch.emilycares;
+public class Test {
+    @SuppressWarnings("unchecked")
+    public void hello() {
+        int a = 1;
+    }
+}
+"#;
+        let diagnostics = vec![
+            finding(Analyzer::Pmd, 5, 12, "UnusedLocalVariable"),
+            finding(Analyzer::Pmd, 4, 16, "MethodNamingConventions"),
+        ];
+        let out = run_actions(cont, AstPoint::new(5, 12), |d, c| {
+            code_actions(d, c, &diagnostics)
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Suppress UnusedLocalVariable with @SuppressWarnings",
+                    "\npackage ch.emilycares;\npublic class Test {\n    @SuppressWarnings(\"unchecked\")\n    public void hello() {\n        @SuppressWarnings(\"PMD.UnusedLocalVariable\")\n        int a = 1;\n    }\n}\n",
+                ),
+                (
+                    "Suppress UnusedLocalVariable with // NOPMD",
+                    "\npackage ch.emilycares;\npublic class Test {\n    @SuppressWarnings(\"unchecked\")\n    public void hello() {\n        int a = 1; // NOPMD - UnusedLocalVariable\n    }\n}\n",
+                ),
+                (
+                    "Suppress MethodNamingConventions with @SuppressWarnings",
+                    "\npackage ch.emilycares;\npublic class Test {\n    @SuppressWarnings({\"unchecked\", \"PMD.MethodNamingConventions\"})\n    public void hello() {\n        int a = 1;\n    }\n}\n",
+                ),
+                (
+                    "Suppress MethodNamingConventions with // NOPMD",
+                    "\npackage ch.emilycares;\npublic class Test {\n    @SuppressWarnings(\"unchecked\")\n    public void hello() { // NOPMD - MethodNamingConventions\n        int a = 1;\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn spotbugs_fix_and_suppression() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public void hello(String s) {
+        s.length();
+    }
+}
+";
+        let mut diagnostic = finding(Analyzer::SpotBugs, 4, 0, "NP_NULL_ON_SOME_PATH");
+        let position = Position::new(4, 8);
+        diagnostic.data = serde_json::to_value(vec![SuggestedFix {
+            title: "Check s for null".to_string(),
+            edits: vec![TextEdit {
+                range: Range::new(position, position),
+                new_text: "if (s != null) ".to_string(),
+            }],
+        }])
+        .ok();
+        let diagnostics = vec![diagnostic];
+        let out = run_actions(cont, AstPoint::new(4, 8), |d, c| {
+            code_actions(d, c, &diagnostics)
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Check s for null",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(String s) {\n        if (s != null) s.length();\n    }\n}\n",
+                ),
+                (
+                    "Suppress NP_NULL_ON_SOME_PATH with @SuppressFBWarnings",
+                    "\npackage ch.emilycares;\nimport edu.umd.cs.findbugs.annotations.SuppressFBWarnings;\npublic class Test {\n    @SuppressFBWarnings(\"NP_NULL_ON_SOME_PATH\")\n    public void hello(String s) {\n        s.length();\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+}
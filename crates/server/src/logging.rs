@@ -0,0 +1,252 @@
+//! Log events of the server with `tracing`
+//!
+//! Events are written to stderr, warnings and errors are also sent to the client with
+//! `window/logMessage`. Levels are set per module with the `log` setting and can change at
+//! runtime, example: `{ "log": { "default": "info", "server::references": "debug" } }`
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    sync::{
+        Mutex, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use lsp_server::{Connection, Message, Notification as LspNotification};
+use lsp_types::{
+    LogMessageParams, MessageType,
+    notification::{LogMessage, Notification},
+};
+use serde_json::Value;
+use tracing::{
+    Event, Level, Metadata, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    subscriber::Interest,
+};
+
+/// Notification with the state of the server that editors can show in their status bar
+pub const SERVER_STATUS: &str = "java/serverStatus";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ServerStatus {
+    Indexing,
+    Ready,
+    Error,
+}
+
+impl ServerStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Indexing => "indexing",
+            Self::Ready => "ready",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Send the `java/serverStatus` notification
+pub fn server_status(connection: &Connection, status: ServerStatus, message: Option<&str>) {
+    let _ = connection
+        .sender
+        .send(Message::Notification(LspNotification::new(
+            SERVER_STATUS.to_string(),
+            serde_json::json!({
+                "status": status.as_str(),
+                "message": message,
+            }),
+        )));
+}
+
+/// Level of the module with the longest matching prefix, otherwise the default. No level
+/// disables the module.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LogLevels {
+    default: Option<Level>,
+    modules: HashMap<String, Option<Level>>,
+}
+
+impl Default for LogLevels {
+    fn default() -> Self {
+        Self {
+            default: Some(Level::INFO),
+            modules: HashMap::new(),
+        }
+    }
+}
+
+impl LogLevels {
+    /// `{ "default": "info", "server::references": "debug" }`, `off` disables a module
+    #[must_use]
+    pub fn parse(value: &Value) -> Self {
+        let mut out = Self::default();
+        let Value::Object(levels) = value else {
+            return out;
+        };
+        for (module, level) in levels {
+            let Some(level) = level.as_str() else {
+                continue;
+            };
+            let level = match level.to_lowercase().as_str() {
+                "off" => None,
+                level => match level.parse::<Level>() {
+                    Ok(level) => Some(level),
+                    Err(_) => continue,
+                },
+            };
+            if module == "default" {
+                out.default = level;
+            } else {
+                out.modules.insert(module.clone(), level);
+            }
+        }
+        out
+    }
+
+    #[must_use]
+    pub fn enabled(&self, target: &str, level: Level) -> bool {
+        let max = self
+            .modules
+            .iter()
+            .filter(|(module, _)| {
+                target == module.as_str()
+                    || target
+                        .strip_prefix(module.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(module, _)| module.len())
+            .map_or(self.default, |(_, level)| *level);
+        max.is_some_and(|max| level <= max)
+    }
+}
+
+static LEVELS: RwLock<Option<LogLevels>> = RwLock::new(None);
+static CLIENT: Mutex<Option<crossbeam_channel::Sender<Message>>> = Mutex::new(None);
+
+/// Install the subscriber, events are written to stderr until a client is connected
+pub fn init() {
+    let _ = tracing::subscriber::set_global_default(LogSubscriber {
+        next_id: AtomicU64::new(1),
+    });
+}
+
+/// Send warnings and errors to the client of the connection
+pub fn connect(connection: &Connection) {
+    if let Ok(mut client) = CLIENT.lock() {
+        *client = Some(connection.sender.clone());
+    }
+}
+
+/// Change the levels at runtime
+pub fn set_levels(levels: LogLevels) {
+    if let Ok(mut current) = LEVELS.write() {
+        *current = Some(levels);
+    }
+}
+
+struct LogSubscriber {
+    next_id: AtomicU64,
+}
+
+impl Subscriber for LogSubscriber {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        // Levels change at runtime, ask every time
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        LEVELS.read().map_or(true, |levels| {
+            levels.as_ref().map_or_else(
+                || *metadata.level() <= Level::INFO,
+                |levels| levels.enabled(metadata.target(), *metadata.level()),
+            )
+        })
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let level = *metadata.level();
+        eprintln!("{level} {}: {}", metadata.target(), visitor.message);
+        let typ = match level {
+            Level::ERROR => MessageType::ERROR,
+            Level::WARN => MessageType::WARNING,
+            _ => return,
+        };
+        let Ok(client) = CLIENT.lock() else {
+            return;
+        };
+        let Some(sender) = client.as_ref() else {
+            return;
+        };
+        let _ = sender.try_send(Message::Notification(LspNotification::new(
+            LogMessage::METHOD.to_string(),
+            LogMessageParams {
+                typ,
+                message: visitor.message,
+            },
+        )));
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+/// The message of an event followed by its other fields
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let fields = std::mem::take(&mut self.message);
+            let _ = write!(self.message, "{value:?}{fields}");
+        } else {
+            let _ = write!(self.message, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.insert_str(0, value);
+        } else {
+            let _ = write!(self.message, " {}={value}", field.name());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_levels() {
+        let levels = LogLevels::parse(&serde_json::json!({
+            "default": "warn",
+            "server::references": "debug",
+            "server::references::index": "off",
+            "server::hover": "error",
+        }));
+        assert!(levels.enabled("server::backend", Level::WARN));
+        assert!(!levels.enabled("server::backend", Level::INFO));
+        assert!(levels.enabled("server::references", Level::DEBUG));
+        assert!(levels.enabled("server::references::find", Level::DEBUG));
+        assert!(!levels.enabled("server::references_other", Level::DEBUG));
+        assert!(!levels.enabled("server::references::index", Level::ERROR));
+        assert!(levels.enabled("server::hover", Level::ERROR));
+        assert!(!levels.enabled("server::hover", Level::WARN));
+    }
+}
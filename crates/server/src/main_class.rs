@@ -0,0 +1,275 @@
+//! Classes with a main method and running them
+//!
+//! `java/mainClasses` lists the classes of the workspace with a main method for the pickers of
+//! editors. `java/runMain` compiles the file of a main class with javac into its output folder
+//! and runs the class with the classpath of javac. The output of the compiler or of the program
+//! is written to a log file that is shown in the editor.
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{Arc, RwLock},
+    time::UNIX_EPOCH,
+};
+
+use compile::CompileError;
+use dto::{Access, Class, JType, Method, SourceDestination};
+use lsp_extra::source_to_uri;
+use lsp_server::Connection;
+use lsp_types::Uri;
+use my_string::MyString;
+use serde::Serialize;
+
+use crate::{
+    backend::{Backend, Project},
+    debug,
+    javac::{self, Javac},
+};
+
+/// Command that returns the main classes of the workspace
+pub const COMMAND_MAIN_CLASSES: &str = "java/mainClasses";
+/// Command with the class path of the main class or the uri of its file
+pub const COMMAND_RUN_MAIN: &str = "java/runMain";
+
+#[derive(Debug)]
+pub enum MainClassError {
+    NoMainClass(String),
+    /// The project is built by a tool that javac does not get a classpath of
+    NoClasspath,
+}
+
+#[derive(Debug)]
+pub enum RunMainError {
+    MainClass(MainClassError),
+    Compile(CompileError),
+    Io(std::io::Error),
+    NoLogFile,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MainClass {
+    pub class_path: String,
+    pub uri: Uri,
+}
+
+/// Main class with the project and the javac of its file
+#[derive(Debug)]
+pub struct Target<'a> {
+    pub class_path: String,
+    pub source: PathBuf,
+    pub project: &'a Project,
+    pub javac: Javac,
+}
+
+/// `public static void main(String[] args)`
+#[must_use]
+pub fn is_main(method: &Method) -> bool {
+    method.name.as_deref() == Some("main")
+        && method.access.contains(Access::Static)
+        && matches!(
+            method.parameters.as_slice(),
+            [p] if matches!(
+                &p.jtype,
+                JType::Array(t) if matches!(t.as_ref(), JType::Class(c) if c == "String" || c == "java.lang.String")
+            )
+        )
+}
+
+/// Main classes of the workspace, sorted by class path
+#[must_use]
+pub fn main_classes(class_map: &Arc<RwLock<HashMap<MyString, Class>>>) -> Vec<MainClass> {
+    let Ok(class_map) = class_map.read() else {
+        return Vec::new();
+    };
+    let mut out: Vec<MainClass> = class_map
+        .values()
+        .filter(|class| class.methods.iter().any(is_main))
+        .filter_map(|class| {
+            let SourceDestination::Here(source) = &class.source else {
+                return None;
+            };
+            Some(MainClass {
+                class_path: class.class_path.to_string(),
+                uri: source_to_uri(source.as_str()).ok()?,
+            })
+        })
+        .collect();
+    drop(class_map);
+    out.sort_by(|a, b| a.class_path.cmp(&b.class_path));
+    out
+}
+
+/// Class with a main method, by class path or by the path of its source file
+#[must_use]
+pub fn main_class(
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    target: &str,
+) -> Option<(String, PathBuf)> {
+    #[allow(clippy::case_sensitive_file_extension_comparisons)]
+    let file = target.ends_with(".java").then(|| Path::new(target));
+    let class_map = class_map.read().ok()?;
+    let mut candidates: Vec<&Class> = class_map
+        .values()
+        .filter(|class| match (&class.source, file) {
+            (SourceDestination::Here(source), Some(file)) => Path::new(source.as_str()) == file,
+            (SourceDestination::Here(_), None) => class.class_path == target,
+            _ => false,
+        })
+        .filter(|class| class.methods.iter().any(is_main))
+        .collect();
+    candidates.sort_by(|a, b| a.class_path.cmp(&b.class_path));
+    let class = candidates.first()?;
+    let SourceDestination::Here(source) = &class.source else {
+        return None;
+    };
+    let out = (class.class_path.to_string(), PathBuf::from(source.as_str()));
+    drop(class_map);
+    Some(out)
+}
+
+/// Main class of the target with the javac of the project that contains it
+pub fn resolve<'a>(
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    projects: &'a [Project],
+    target: &str,
+) -> Result<Target<'a>, MainClassError> {
+    let (class_path, source) = main_class(class_map, target)
+        .ok_or_else(|| MainClassError::NoMainClass(target.to_string()))?;
+    let project = projects
+        .iter()
+        .find(|p| source.starts_with(&p.dir))
+        .ok_or(MainClassError::NoClasspath)?;
+    let javac = javac::javac(project, &source).ok_or(MainClassError::NoClasspath)?;
+    Ok(Target {
+        class_path,
+        source,
+        project,
+        javac,
+    })
+}
+
+/// Compile the file of the main class and run it in the background, the log file is shown
+/// when the compiler reports errors or the program starts
+pub fn run_main(
+    con: &Arc<Connection>,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    projects: &[Project],
+    java_path: std::ffi::OsString,
+    target: &str,
+) -> Result<(), RunMainError> {
+    let target = resolve(class_map, projects, target).map_err(RunMainError::MainClass)?;
+    let log_file = log_file(&target.class_path).ok_or(RunMainError::NoLogFile)?;
+    let log_path = log_file.to_string_lossy().to_string();
+    let mut log = File::create(&log_file).map_err(RunMainError::Io)?;
+    let errors = target
+        .javac
+        .compile(&target.source.to_string_lossy())
+        .map_err(RunMainError::Compile)?;
+    if !errors.is_empty() {
+        for error in errors {
+            writeln!(
+                log,
+                "{}:{}:{}: {}",
+                error.path, error.row, error.col, error.message
+            )
+            .map_err(RunMainError::Io)?;
+        }
+        Backend::open_log(con, &log_path);
+        return Ok(());
+    }
+    let config = debug::launch_config(&target);
+    let stderr = log.try_clone().map_err(RunMainError::Io)?;
+    let mut child = Command::new("java")
+        .env(
+            "PATH",
+            jdk::installations::java_path(target.javac.level.release, java_path),
+        )
+        .current_dir(&config.cwd)
+        .args(debug::java_args(&config))
+        .stdin(Stdio::null())
+        .stdout(log)
+        .stderr(stderr)
+        .spawn()
+        .map_err(RunMainError::Io)?;
+    Backend::open_log(con, &log_path);
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+/// Log file in the temp folder for the output of the class
+#[must_use]
+pub fn log_file(class_path: &str) -> Option<PathBuf> {
+    let temp = dirs::temp_dir()?;
+    let now = std::time::SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()?;
+    Some(temp.join(format!("{}_{class_path}.log", now.as_secs())))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, RwLock},
+    };
+
+    use dto::{Access, Class, JType, Method, Parameter, SourceDestination};
+    use my_string::MyString;
+
+    use super::{main_class, main_classes};
+
+    #[test]
+    fn main_methods() {
+        let main = Method {
+            name: Some(MyString::from("main")),
+            access: Access::Public | Access::Static,
+            parameters: vec![Parameter {
+                name: Some(MyString::from("args")),
+                jtype: JType::Array(Box::new(JType::Class(MyString::from("String")))),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let instance_main = Method {
+            access: Access::Public,
+            ..main.clone()
+        };
+        let class = |class_path: &str, methods: Vec<Method>| Class {
+            class_path: MyString::from(class_path),
+            source: SourceDestination::Here(MyString::from(format!(
+                "/p/src/main/java/{}.java",
+                class_path.replace('.', "/")
+            ))),
+            methods,
+            ..Default::default()
+        };
+        let class_map = Arc::new(RwLock::new(HashMap::from([
+            (
+                MyString::from("ch.App"),
+                class("ch.App", vec![main.clone()]),
+            ),
+            (MyString::from("ch.Tool"), class("ch.Tool", vec![main])),
+            (
+                MyString::from("ch.Helper"),
+                class("ch.Helper", vec![instance_main]),
+            ),
+        ])));
+        let found: Vec<String> = main_classes(&class_map)
+            .into_iter()
+            .map(|c| c.class_path)
+            .collect();
+        assert_eq!(found, vec!["ch.App", "ch.Tool"]);
+        let expected = Some(("ch.App".to_string(), "/p/src/main/java/ch/App.java".into()));
+        assert_eq!(main_class(&class_map, "ch.App"), expected);
+        assert_eq!(
+            main_class(&class_map, "/p/src/main/java/ch/App.java"),
+            expected
+        );
+        assert_eq!(main_class(&class_map, "ch.Helper"), None);
+    }
+}
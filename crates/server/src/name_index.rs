@@ -0,0 +1,140 @@
+//! Simple names that are mentioned in the source files of the workspace
+//!
+//! Find references and move class only look into the files that mention the name of the symbol.
+//! A file is indexed with its identifier tokens, open documents are indexed again when they are
+//! saved and files that changed on disk are indexed again on the next lookup.
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    sync::RwLock,
+};
+
+use ast::lexer::{self, PositionToken, Token};
+use document::path_without_subclass;
+use my_string::MyString;
+
+#[derive(Debug, Default)]
+pub struct NameIndex {
+    /// Names of a source file
+    files: RwLock<HashMap<MyString, HashSet<MyString>>>,
+}
+
+impl NameIndex {
+    /// Index the tokens of the source file again
+    pub fn update(&self, source: &str, tokens: &[PositionToken]) {
+        if let Ok(mut files) = self.files.write() {
+            files.insert(source.into(), names(tokens));
+        }
+    }
+
+    /// The file was deleted or changed on disk
+    pub fn remove(&self, source: &str) {
+        if let Ok(mut files) = self.files.write() {
+            files.remove(source);
+        }
+    }
+
+    /// Keep the sources that mention the name, files that are not indexed yet are read. Sources
+    /// that could not be read are kept.
+    pub fn retain_mentioning(&self, sources: &mut Vec<MyString>, name: &str) {
+        self.index_missing(sources);
+        let Ok(files) = self.files.read() else {
+            return;
+        };
+        sources.retain(|source| files.get(source).is_none_or(|names| names.contains(name)));
+    }
+
+    fn index_missing(&self, sources: &[MyString]) {
+        let missing: Vec<&MyString> = {
+            let Ok(files) = self.files.read() else {
+                return;
+            };
+            let mut missing: Vec<&MyString> = sources
+                .iter()
+                .filter(|source| !files.contains_key(*source))
+                .collect();
+            missing.sort_unstable();
+            missing.dedup();
+            missing
+        };
+        if missing.is_empty() {
+            return;
+        }
+        let indexed = loader::parallel::parallel_map(
+            &missing,
+            |source| {
+                let content = fs::read(path_without_subclass(source)).ok()?;
+                let tokens = lexer::lex(&content).ok()?;
+                Some(((*source).clone(), names(&tokens)))
+            },
+            |_, _| {},
+        );
+        if let Ok(mut files) = self.files.write() {
+            files.extend(indexed);
+        }
+    }
+}
+
+/// Identifiers of the tokens, this includes the names of types, variables and members
+#[must_use]
+pub fn names(tokens: &[PositionToken]) -> HashSet<MyString> {
+    tokens
+        .iter()
+        .filter_map(|t| match &t.token {
+            Token::Identifier(name) => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Name that a file uses for the class, nested classes are mentioned by their own name
+#[must_use]
+pub fn simple_name(class_path: &str) -> &str {
+    class_path.rsplit(['.', '$']).next().unwrap_or(class_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ast::lexer;
+    use my_string::MyString;
+
+    use super::{NameIndex, simple_name};
+
+    #[test]
+    fn prune_sources() {
+        let index = NameIndex::default();
+        let tokens = lexer::lex(b"class A { void run() { other.call(new B()); } }").unwrap();
+        index.update("A.java", &tokens);
+        let tokens = lexer::lex(b"class B { String call; }").unwrap();
+        index.update("B.java", &tokens);
+        let missing = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("does/not/Exist.java");
+        let missing = MyString::from(missing.to_str().unwrap());
+        let sources = vec!["A.java".into(), "B.java".into(), missing.clone()];
+
+        let mut found = sources.clone();
+        index.retain_mentioning(&mut found, "call");
+        assert_eq!(found, sources);
+
+        let mut found = sources.clone();
+        index.retain_mentioning(&mut found, "B");
+        assert_eq!(found, sources);
+
+        let mut found = sources.clone();
+        index.retain_mentioning(&mut found, "run");
+        assert_eq!(found, vec![MyString::from("A.java"), missing.clone()]);
+
+        index.update("A.java", &lexer::lex(b"class A {}").unwrap());
+        let mut found = sources;
+        index.retain_mentioning(&mut found, "run");
+        assert_eq!(found, vec![missing]);
+    }
+
+    #[test]
+    fn simple_names() {
+        assert_eq!(simple_name("ch.emilycares.Outer$Inner"), "Inner");
+        assert_eq!(simple_name("ch.emilycares.Outer"), "Outer");
+        assert_eq!(simple_name("Outer"), "Outer");
+    }
+}
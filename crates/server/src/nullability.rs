@@ -0,0 +1,958 @@
+//! Nullability analysis based on annotations
+//!
+//! Parameters, fields and return values of methods in this file that are annotated as
+//! nullable are tracked through the method bodies. Null checks in conditions remove them
+//! for the guarded code.
+use std::collections::{HashMap, HashSet};
+
+use ast::{
+    types::{
+        AstAnnotated, AstBaseExpression, AstBlock, AstBlockEntry, AstBlockReturn,
+        AstClassConstructor, AstClassMethod, AstClassVariable, AstExpressionIdentifier,
+        AstExpressionKind, AstExpressionOperator, AstExpressionOrValue, AstFile, AstForContent,
+        AstIdentifier, AstIf, AstIfContent, AstInterfaceMethod, AstInterfaceMethodDefault,
+        AstJType, AstMethodHeader, AstMethodParameter, AstNewRhs, AstRange,
+        AstSwitchCaseArrowContent, AstWhileContent,
+    },
+    visit::{self, Visitor},
+};
+use lsp_extra::{SERVER_NAME, to_lsp_range};
+use lsp_types::{Diagnostic, NumberOrString};
+
+use crate::{
+    definite_assignment::{is_member_access, operator},
+    diagnostics::{assign_target, default_severity},
+    flow,
+    type_check::{Selected, is_this_access},
+};
+
+/// Diagnostic code of values that might be null and are dereferenced
+pub const NULLABLE_DEREFERENCE: &str = "nullable_dereference";
+/// Diagnostic code of values that might be null and are passed to non-null parameters
+pub const NULLABLE_ARGUMENT: &str = "nullable_argument";
+
+/// Dereferences of nullable values without a null check and nullable values that are
+/// passed to non-null parameters of methods and constructors in this file.
+///
+/// Of overloads with as many parameters the one that type checking selected is used.
+#[must_use]
+pub fn nullability(
+    ast: &AstFile,
+    nullable: &[String],
+    not_null: &[String],
+    selected: &Selected,
+) -> Vec<Diagnostic> {
+    let annotations = Annotations { nullable, not_null };
+    let mut signatures = Signatures {
+        annotations: &annotations,
+        methods: HashMap::new(),
+        constructors: HashMap::new(),
+        fields: HashSet::new(),
+    };
+    signatures.visit_file(ast);
+    let mut bodies = Bodies {
+        annotations: &annotations,
+        signatures: &signatures,
+        selected,
+        found: vec![],
+    };
+    bodies.visit_file(ast);
+    bodies
+        .found
+        .into_iter()
+        .filter_map(|(range, message, code)| {
+            Some(Diagnostic {
+                range: to_lsp_range(&range).ok()?,
+                severity: default_severity(code),
+                code: Some(NumberOrString::String(code.to_string())),
+                source: Some(SERVER_NAME.to_string()),
+                message,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+struct Annotations<'c> {
+    nullable: &'c [String],
+    not_null: &'c [String],
+}
+
+impl Annotations<'_> {
+    fn is_nullable(&self, annotated: &[AstAnnotated], jtype: &AstJType) -> bool {
+        has_annotation(self.nullable, annotated, jtype)
+    }
+
+    fn is_not_null(&self, annotated: &[AstAnnotated], jtype: &AstJType) -> bool {
+        has_annotation(self.not_null, annotated, jtype)
+    }
+}
+
+/// Annotations are compared by their simple name
+fn has_annotation(names: &[String], annotated: &[AstAnnotated], jtype: &AstJType) -> bool {
+    let simple = |name: &str| name.rsplit('.').next().unwrap_or_default().to_owned();
+    annotated
+        .iter()
+        .chain(&jtype.annotated)
+        .any(|a| names.iter().any(|n| simple(n) == simple(&a.name.value)))
+}
+
+/// Nullability of a method or constructor
+struct Signature<'a> {
+    /// Range of the header
+    header: AstRange,
+    /// Names of the parameters and if they are non-null
+    parameters: Vec<(&'a str, bool)>,
+    nullable_return: bool,
+}
+
+/// Nullability of the members of this file
+struct Signatures<'a, 'c> {
+    annotations: &'c Annotations<'c>,
+    methods: HashMap<&'a str, Vec<Signature<'a>>>,
+    /// Constructors by class name
+    constructors: HashMap<&'a str, Vec<Signature<'a>>>,
+    /// Nullable fields
+    fields: HashSet<&'a str>,
+}
+
+impl<'a> Signatures<'a, '_> {
+    fn signature(
+        &self,
+        header: AstRange,
+        parameters: &'a [AstMethodParameter],
+        nullable_return: bool,
+    ) -> Signature<'a> {
+        Signature {
+            header,
+            parameters: parameters
+                .iter()
+                .map(|p| {
+                    (
+                        p.name.value.as_str(),
+                        self.annotations.is_not_null(&p.annotated, &p.jtype),
+                    )
+                })
+                .collect(),
+            nullable_return,
+        }
+    }
+
+    fn method(&mut self, header: &'a AstMethodHeader) {
+        let nullable_return = self
+            .annotations
+            .is_nullable(&header.annotated, &header.jtype);
+        let signature =
+            self.signature(header.range, &header.parameters.parameters, nullable_return);
+        self.methods
+            .entry(&header.name.value)
+            .or_default()
+            .push(signature);
+    }
+}
+
+impl<'a> Visitor<'a> for Signatures<'a, '_> {
+    fn visit_class_variable(&mut self, variable: &'a AstClassVariable) {
+        if self
+            .annotations
+            .is_nullable(&variable.annotated, &variable.jtype)
+        {
+            self.fields.insert(&variable.name.value);
+        }
+        visit::walk_class_variable(self, variable);
+    }
+
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        self.method(&method.header);
+        visit::walk_class_method(self, method);
+    }
+
+    fn visit_interface_method(&mut self, method: &'a AstInterfaceMethod) {
+        self.method(&method.header);
+        visit::walk_method_header(self, &method.header);
+    }
+
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        self.method(&method.header);
+        visit::walk_interface_method_default(self, method);
+    }
+
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        let signature = self.signature(
+            constructor.header.range,
+            &constructor.header.parameters.parameters,
+            false,
+        );
+        self.constructors
+            .entry(&constructor.header.name.value)
+            .or_default()
+            .push(signature);
+        visit::walk_class_constructor(self, constructor);
+    }
+}
+
+/// Names of the variables that might be null
+type Nullable<'a> = HashSet<&'a str>;
+
+struct Bodies<'a, 'c> {
+    annotations: &'c Annotations<'c>,
+    signatures: &'c Signatures<'a, 'c>,
+    selected: &'c Selected,
+    found: Vec<(AstRange, String, &'static str)>,
+}
+
+impl<'a, 'c> Bodies<'a, 'c> {
+    /// Signature of the call at the range with `count` arguments. Of more than one with as
+    /// many parameters the one that type checking selected, None when it did not.
+    fn find(
+        &self,
+        signatures: Option<&'c Vec<Signature<'a>>>,
+        count: usize,
+        call: AstRange,
+    ) -> Option<&'c Signature<'a>> {
+        let signatures = signatures?;
+        let mut matching = signatures.iter().filter(|s| s.parameters.len() == count);
+        let first = matching.next()?;
+        if matching.next().is_none() {
+            return Some(first);
+        }
+        let (_, header) = self.selected.iter().find(|(range, _)| *range == call)?;
+        signatures.iter().find(|s| s.header == *header)
+    }
+
+    fn body(&mut self, parameters: &'a [AstMethodParameter], block: &'a AstBlock) {
+        let mut nullable = self.signatures.fields.clone();
+        for parameter in parameters {
+            if self
+                .annotations
+                .is_nullable(&parameter.annotated, &parameter.jtype)
+            {
+                nullable.insert(&parameter.name.value);
+            } else {
+                nullable.remove(parameter.name.value.as_str());
+            }
+        }
+        self.entries(&block.entries, &mut nullable);
+    }
+
+    fn entries(&mut self, entries: &'a [AstBlockEntry], nullable: &mut Nullable<'a>) {
+        let mut i = 0;
+        while i < entries.len() {
+            if let Some(chain) = flow::if_chain(entries, i) {
+                let mut running = nullable.clone();
+                let mut ends = vec![];
+                for (control, content) in chain.branches {
+                    if let Some(control) = control {
+                        self.branch(control, content, &mut running, &mut ends);
+                    } else {
+                        let mut state = running.clone();
+                        if self.if_content(content, &mut state) {
+                            ends.push(state);
+                        }
+                    }
+                }
+                if !chain.has_else {
+                    ends.push(running);
+                }
+                *nullable = ends.into_iter().flatten().collect();
+                i = chain.next;
+            } else {
+                self.entry(&entries[i], nullable);
+                i += 1;
+            }
+        }
+    }
+
+    /// An if or else if branch, `running` is the state for the following branches
+    fn branch(
+        &mut self,
+        control: &'a [AstExpressionKind],
+        content: &'a AstIfContent,
+        running: &mut Nullable<'a>,
+        ends: &mut Vec<Nullable<'a>>,
+    ) {
+        let (then_checked, else_checked) = conditions(control);
+        self.expression(control, running);
+        let mut state = difference(running, &then_checked);
+        if self.if_content(content, &mut state) {
+            ends.push(state);
+        }
+        *running = difference(running, &else_checked);
+    }
+
+    /// Returns if the statement after the content can be reached
+    fn if_content(&mut self, content: &'a AstIfContent, nullable: &mut Nullable<'a>) -> bool {
+        match content {
+            AstIfContent::Block(block) => {
+                self.entries(&block.entries, nullable);
+                flow::completes_normally(&block.entries)
+            }
+            AstIfContent::BlockEntry(entry) => {
+                self.entry(entry, nullable);
+                flow::completes_normally(std::slice::from_ref(&**entry))
+            }
+        }
+    }
+
+    fn entry(&mut self, entry: &'a AstBlockEntry, nullable: &mut Nullable<'a>) {
+        match entry {
+            AstBlockEntry::Variable(variables) => {
+                for variable in variables {
+                    let value_nullable = variable.value.as_ref().is_some_and(|value| {
+                        self.expression(value, nullable);
+                        self.is_nullable(value, nullable)
+                    }) || self
+                        .annotations
+                        .is_nullable(&variable.annotated, &variable.jtype);
+                    if value_nullable {
+                        nullable.insert(&variable.name.value);
+                    } else {
+                        nullable.remove(variable.name.value.as_str());
+                    }
+                }
+            }
+            AstBlockEntry::Expression(expression) => {
+                self.expression(&expression.value, nullable);
+                let value = crate::diagnostics::assigned_value(&expression.value);
+                if value.len() != expression.value.len()
+                    && let Some(target) =
+                        assign_target(&expression.value[..expression.value.len() - value.len() - 1])
+                {
+                    self.assign(target, value, nullable);
+                }
+            }
+            AstBlockEntry::Assign(assign) => {
+                self.expression(&assign.expression, nullable);
+                match assign_target(&assign.key) {
+                    Some(target) => self.assign(target, &assign.expression, nullable),
+                    None => self.expression(&assign.key, nullable),
+                }
+            }
+            AstBlockEntry::Return(AstBlockReturn { expression, .. }) => {
+                if let AstExpressionOrValue::Expression(expression) = expression {
+                    self.expression(expression, nullable);
+                }
+            }
+            AstBlockEntry::Yield(ast_yield) => {
+                if let AstExpressionOrValue::Expression(expression) = &ast_yield.expression {
+                    self.expression(expression, nullable);
+                }
+            }
+            AstBlockEntry::Throw(throw) => self.expression(&throw.expression, nullable),
+            AstBlockEntry::Assert(assert) => {
+                self.expression(&assert.expression, nullable);
+                let (checked, _) = conditions(&assert.expression);
+                *nullable = difference(nullable, &checked);
+            }
+            AstBlockEntry::If(
+                AstIf::If {
+                    control, content, ..
+                }
+                | AstIf::ElseIf {
+                    control, content, ..
+                },
+            ) => {
+                self.expression(control, nullable);
+                let (then_checked, _) = conditions(control);
+                let mut state = difference(nullable, &then_checked);
+                self.if_content(content, &mut state);
+                nullable.extend(state);
+            }
+            AstBlockEntry::If(AstIf::Else { content, .. }) => {
+                let mut state = nullable.clone();
+                self.if_content(content, &mut state);
+                nullable.extend(state);
+            }
+            AstBlockEntry::While(ast_while) => {
+                self.expression(&ast_while.control, nullable);
+                let (then_checked, else_checked) = conditions(&ast_while.control);
+                let mut state = difference(nullable, &then_checked);
+                match &ast_while.content {
+                    AstWhileContent::None => (),
+                    AstWhileContent::Block(block) => self.entries(&block.entries, &mut state),
+                    AstWhileContent::BlockEntry(entry) => self.entry(entry, &mut state),
+                }
+                nullable.extend(state);
+                // The loop ends when the condition is false
+                *nullable = difference(nullable, &else_checked);
+            }
+            AstBlockEntry::For(ast_for) => {
+                let mut state = nullable.clone();
+                for entry in ast_for.vars.iter().chain(&ast_for.check) {
+                    self.entry(entry, &mut state);
+                }
+                self.for_content(&ast_for.content, &mut state);
+                for entry in &ast_for.changes {
+                    self.entry(entry, &mut state);
+                }
+                nullable.extend(state);
+            }
+            AstBlockEntry::ForEnhanced(ast_for) => {
+                self.expression(&ast_for.rhs, nullable);
+                let mut state = nullable.clone();
+                for variable in &ast_for.var {
+                    state.remove(variable.name.value.as_str());
+                }
+                self.for_content(&ast_for.content, &mut state);
+                nullable.extend(state);
+            }
+            AstBlockEntry::Switch(switch) => {
+                self.expression(&switch.check, nullable);
+                let mut state = nullable.clone();
+                self.block_entries(&switch.block.entries, &mut state);
+                nullable.extend(state);
+            }
+            AstBlockEntry::TryCatch(try_catch) => {
+                let mut state = nullable.clone();
+                if let Some(resources) = &try_catch.resources_block {
+                    self.entries(&resources.entries, &mut state);
+                }
+                self.entries(&try_catch.block.entries, &mut state);
+                for case in &try_catch.cases {
+                    let mut case_state = nullable.clone();
+                    case_state.remove(case.variable.name.value.as_str());
+                    self.entries(&case.block.entries, &mut case_state);
+                    state.extend(case_state);
+                }
+                if let Some(finally) = &try_catch.finally_block {
+                    self.entries(&finally.entries, &mut state);
+                }
+                nullable.extend(state);
+            }
+            AstBlockEntry::SynchronizedBlock(synchronized) => {
+                self.expression(&synchronized.expression, nullable);
+                self.entries(&synchronized.block.entries, nullable);
+            }
+            AstBlockEntry::InlineBlock(inline) => self.entries(&inline.block.entries, nullable),
+            AstBlockEntry::SwitchCaseArrowValues(_)
+            | AstBlockEntry::SwitchCaseArrowType(_)
+            | AstBlockEntry::SwitchCaseArrowDefault(_) => {
+                if let Some(content) = flow::arrow_content(entry) {
+                    let mut state = nullable.clone();
+                    if let AstBlockEntry::SwitchCaseArrowType(case) = entry {
+                        state.remove(case.var.name.value.as_str());
+                    }
+                    match content {
+                        AstSwitchCaseArrowContent::Block(block) => {
+                            self.entries(&block.entries, &mut state);
+                        }
+                        AstSwitchCaseArrowContent::Entry(entry) => self.entry(entry, &mut state),
+                    }
+                    nullable.extend(state);
+                }
+            }
+            AstBlockEntry::Break(_)
+            | AstBlockEntry::Continue(_)
+            | AstBlockEntry::SwitchCase(_)
+            | AstBlockEntry::SwitchDefault(_)
+            | AstBlockEntry::Thing(_)
+            | AstBlockEntry::Semicolon(_) => (),
+        }
+    }
+
+    /// Entries of a switch block, every case starts with the state before the switch
+    fn block_entries(&mut self, entries: &'a [AstBlockEntry], nullable: &mut Nullable<'a>) {
+        let before = nullable.clone();
+        for entry in entries {
+            if matches!(
+                entry,
+                AstBlockEntry::SwitchCase(_) | AstBlockEntry::SwitchDefault(_)
+            ) {
+                nullable.extend(before.iter().copied());
+            }
+            self.entry(entry, nullable);
+        }
+    }
+
+    fn for_content(&mut self, content: &'a AstForContent, nullable: &mut Nullable<'a>) {
+        match content {
+            AstForContent::None => (),
+            AstForContent::Block(block) => self.entries(&block.entries, nullable),
+            AstForContent::BlockEntry(entry) => self.entry(entry, nullable),
+        }
+    }
+
+    fn assign(
+        &self,
+        target: &'a AstIdentifier,
+        value: &'a [AstExpressionKind],
+        nullable: &mut Nullable<'a>,
+    ) {
+        if self.is_nullable(value, nullable) {
+            nullable.insert(&target.value);
+        } else {
+            nullable.remove(target.value.as_str());
+        }
+    }
+
+    /// Is the value a nullable variable or the result of a nullable method
+    fn is_nullable(&self, value: &'a [AstExpressionKind], nullable: &Nullable<'a>) -> bool {
+        let call = match value {
+            [kind] => return variable(kind).is_some_and(|v| nullable.contains(v.value.as_str())),
+            [name, arguments] => (name, arguments),
+            [_, _, name, arguments] if is_this_access(&value[..2]) => (name, arguments),
+            _ => return false,
+        };
+        let (Some(name), Some(arguments)) = (variable(call.0), arguments(call.1)) else {
+            return false;
+        };
+        self.find(
+            self.signatures.methods.get(name.value.as_str()),
+            arguments.len(),
+            name.range,
+        )
+        .is_some_and(|s| s.nullable_return)
+    }
+
+    /// Check the dereferences and arguments of a expression
+    fn expression(&mut self, expression: &'a [AstExpressionKind], nullable: &Nullable<'a>) {
+        // Variables that are checked by a previous part of the expression
+        let mut checked = HashSet::new();
+        for (i, kind) in expression.iter().enumerate() {
+            match operator(kind) {
+                Some(
+                    AstExpressionOperator::AmpersandAmpersand(_)
+                    | AstExpressionOperator::QuestionMark(_),
+                ) => checked.extend(conditions(&expression[..i]).0),
+                Some(AstExpressionOperator::VerticalBarVerticalBar(_)) => {
+                    checked.extend(conditions(&expression[..i]).1);
+                }
+                _ => (),
+            }
+            let state = difference(nullable, &checked);
+            match kind {
+                AstExpressionKind::Base(base) => {
+                    if let Some(ident) = variable(kind)
+                        && (i == 0
+                            || !is_member_access(&expression[i - 1])
+                            || i == 2 && is_this_access(&expression[..2]))
+                    {
+                        let next = expression.get(i + 1);
+                        if next.is_some_and(is_member_access)
+                            && matches!(
+                                next.and_then(operator),
+                                Some(AstExpressionOperator::Dot(_))
+                            )
+                            && state.contains(ident.value.as_str())
+                        {
+                            self.found.push((
+                                ident.range,
+                                format!("dereference of '{}' which might be null", ident.value),
+                                NULLABLE_DEREFERENCE,
+                            ));
+                        }
+                        if let Some(arguments) = next.and_then(arguments)
+                            && (i == 0 || i == 2 && is_this_access(&expression[..2]))
+                        {
+                            let signature = self.find(
+                                self.signatures.methods.get(ident.value.as_str()),
+                                arguments.len(),
+                                ident.range,
+                            );
+                            self.arguments(signature, arguments, &state);
+                        }
+                    }
+                    if let Some(AstExpressionIdentifier::ArrayAccess { expr, .. }) = &base.ident {
+                        self.expression(expr, &state);
+                    }
+                    if let Some(values) = &base.values {
+                        for value in &values.values {
+                            self.expression(value, &state);
+                        }
+                    }
+                }
+                AstExpressionKind::NewClass(new_class) => match &*new_class.rhs {
+                    AstNewRhs::Parameters(_, arguments)
+                    | AstNewRhs::ParametersAndBlock(_, arguments, _) => {
+                        let signature = crate::diagnostics::qualified_name(&new_class.jtype)
+                            .and_then(|name| {
+                                self.find(
+                                    self.signatures.constructors.get(name.as_str()),
+                                    arguments.len(),
+                                    new_class.jtype.range,
+                                )
+                            });
+                        self.arguments(signature, arguments, &state);
+                    }
+                    AstNewRhs::ArrayParameters(dimensions) => {
+                        for dimension in dimensions.iter().flatten() {
+                            self.expression(dimension, &state);
+                        }
+                    }
+                    AstNewRhs::Array(values) => {
+                        for value in &values.values {
+                            self.expression(value, &state);
+                        }
+                    }
+                    AstNewRhs::None | AstNewRhs::Block(_) => (),
+                },
+                AstExpressionKind::Array(values) => {
+                    for value in &values.values {
+                        self.expression(value, &state);
+                    }
+                }
+                AstExpressionKind::InlineSwitch(switch) => self.expression(&switch.check, &state),
+                // Captured variables are not tracked into lambdas
+                AstExpressionKind::Lambda(_)
+                | AstExpressionKind::Generics(_)
+                | AstExpressionKind::JType(_)
+                | AstExpressionKind::InstanceOf(_) => (),
+            }
+        }
+    }
+
+    fn arguments(
+        &mut self,
+        signature: Option<&Signature<'a>>,
+        arguments: &'a [Vec<AstExpressionKind>],
+        nullable: &Nullable<'a>,
+    ) {
+        for (n, argument) in arguments.iter().enumerate() {
+            self.expression(argument, nullable);
+            let Some((name, true)) = signature.and_then(|s| s.parameters.get(n)) else {
+                continue;
+            };
+            let (Some(first), Some(last)) = (argument.first(), argument.last()) else {
+                continue;
+            };
+            let message = if matches!(argument.as_slice(), [kind] if variable(kind).is_some_and(|v| v.value == "null"))
+            {
+                format!("passing null to non-null parameter '{name}'")
+            } else if self.is_nullable(argument, nullable) {
+                format!("passing a value that might be null to non-null parameter '{name}'")
+            } else {
+                continue;
+            };
+            let range = AstRange {
+                start: first.range().start,
+                end: last.range().end,
+            };
+            self.found.push((range, message, NULLABLE_ARGUMENT));
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Bodies<'a, '_> {
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        if let Some(block) = &method.block {
+            self.body(&method.header.parameters.parameters, block);
+        }
+        visit::walk_class_method(self, method);
+    }
+
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        self.body(&method.header.parameters.parameters, &method.block);
+        visit::walk_interface_method_default(self, method);
+    }
+
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        self.body(
+            &constructor.header.parameters.parameters,
+            &constructor.block,
+        );
+        visit::walk_class_constructor(self, constructor);
+    }
+}
+
+fn difference<'a>(nullable: &Nullable<'a>, checked: &HashSet<&'a str>) -> Nullable<'a> {
+    nullable.difference(checked).copied().collect()
+}
+
+/// Variables that are not null when the condition is true and when it is false
+fn conditions(condition: &[AstExpressionKind]) -> (HashSet<&str>, HashSet<&str>) {
+    let mut then_checked = HashSet::new();
+    let mut else_checked = HashSet::new();
+    let is_and = |k: &AstExpressionKind| {
+        matches!(
+            operator(k),
+            Some(AstExpressionOperator::AmpersandAmpersand(_))
+        )
+    };
+    let is_or = |k: &AstExpressionKind| {
+        matches!(
+            operator(k),
+            Some(AstExpressionOperator::VerticalBarVerticalBar(_))
+        )
+    };
+    if !condition.iter().any(is_or) {
+        for part in condition.split(is_and) {
+            match part {
+                [not, kind] if is_not(not) => {
+                    if let Some(inner) = parenthesized(kind) {
+                        then_checked.extend(conditions(inner).1);
+                    }
+                }
+                [kind] => {
+                    if let Some(inner) = parenthesized(kind) {
+                        then_checked.extend(conditions(inner).0);
+                    }
+                }
+                [value, AstExpressionKind::InstanceOf(_)] => {
+                    then_checked.extend(variable(value).map(|i| i.value.as_str()));
+                }
+                _ => then_checked.extend(null_comparison(part, true).map(|i| i.value.as_str())),
+            }
+        }
+    }
+    if !condition.iter().any(is_and) {
+        for part in condition.split(is_or) {
+            match part {
+                [not, kind] if is_not(not) => {
+                    if let Some(inner) = parenthesized(kind) {
+                        else_checked.extend(conditions(inner).0);
+                    }
+                }
+                [kind] => {
+                    if let Some(inner) = parenthesized(kind) {
+                        else_checked.extend(conditions(inner).1);
+                    }
+                }
+                _ => else_checked.extend(null_comparison(part, false).map(|i| i.value.as_str())),
+            }
+        }
+    }
+    (then_checked, else_checked)
+}
+
+const fn is_not(kind: &AstExpressionKind) -> bool {
+    matches!(
+        operator(kind),
+        Some(AstExpressionOperator::ExclamationMark(_))
+    )
+}
+
+/// Content of `(a)`
+fn parenthesized(kind: &AstExpressionKind) -> Option<&[AstExpressionKind]> {
+    match kind {
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: None,
+            values: Some(values),
+            ..
+        }) => match values.values.as_slice() {
+            [inner] => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The variable of `x != null` or of `x == null` when `not_equal` is false
+fn null_comparison(part: &[AstExpressionKind], not_equal: bool) -> Option<&AstIdentifier> {
+    let [value, comparison, null] = part else {
+        return None;
+    };
+    let matching = match operator(comparison)? {
+        AstExpressionOperator::NotEqual(_) => not_equal,
+        AstExpressionOperator::Equal(_) => !not_equal,
+        _ => false,
+    };
+    if !matching || variable(null)?.value != "null" {
+        return None;
+    }
+    variable(value)
+}
+
+/// The identifier of a variable without operator
+const fn variable(kind: &AstExpressionKind) -> Option<&AstIdentifier> {
+    match kind {
+        AstExpressionKind::Base(AstBaseExpression {
+            ident:
+                Some(
+                    AstExpressionIdentifier::Identifier(ident)
+                    | AstExpressionIdentifier::Value(ast::types::AstValue::Variable(ident)),
+                ),
+            values: None,
+            operator: AstExpressionOperator::None,
+            ..
+        }) => Some(ident),
+        _ => None,
+    }
+}
+
+/// The arguments of a call
+fn arguments(kind: &AstExpressionKind) -> Option<&[Vec<AstExpressionKind>]> {
+    match kind {
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: None,
+            values: Some(values),
+            ..
+        }) => Some(&values.values),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr};
+
+    use document::Document;
+    use expect_test::expect;
+
+    use super::*;
+    use crate::type_check::{self, tests::get_class_map};
+
+    fn messages(content: &str) -> Vec<String> {
+        let document = Document::setup(content, PathBuf::from_str("/Test.java").unwrap()).unwrap();
+        let config = config::Configuration::default();
+        let (_, selected) = type_check::type_check(&document.ast, &get_class_map());
+        nullability(
+            &document.ast,
+            &config.nullable_annotations,
+            &config.not_null_annotations,
+            &selected,
+        )
+        .into_iter()
+        .map(|d| format!("{}: {}", d.range.start.line, d.message))
+        .collect()
+    }
+
+    #[test]
+    fn nullable_dereferences() {
+        let content = r#"
+package ch.emilycares;
+import javax.annotation.Nullable;
+public class Test {
+    @Nullable
+    private String field;
+    void checks(@Nullable String a, String b) {
+        a.length();
+        b.length();
+        if (a != null) {
+            a.length();
+        }
+        if (a == null) {
+            return;
+        }
+        a.length();
+    }
+    void conditions(@Nullable String a) {
+        boolean c = a != null && a.isEmpty();
+        boolean d = a == null || a.isEmpty();
+        int e = a != null ? a.length() : 0;
+        field.length();
+        if (a instanceof String) {
+            a.length();
+        } else {
+            a.length();
+        }
+    }
+    void assignments(@Nullable String a) {
+        a = "value";
+        a.length();
+        String b = find();
+        b.length();
+        String c = this.find();
+        c.length();
+        while (a != null) {
+            a.length();
+            a = find();
+        }
+    }
+    void chain(@Nullable String a, int n) {
+        if (n == 1) {
+        } else if (a == null) {
+            return;
+        } else if (n == 2) {
+            a.length();
+        }
+        a.length();
+    }
+    void negated(@Nullable String a) {
+        if (!(a == null)) {
+            a.length();
+        }
+        if (!(a != null)) {
+            return;
+        }
+        a.length();
+    }
+    @Nullable
+    String find() {
+        return null;
+    }
+}
+"#;
+        expect![[r#"
+            [
+                "7: dereference of 'a' which might be null",
+                "21: dereference of 'field' which might be null",
+                "25: dereference of 'a' which might be null",
+                "32: dereference of 'b' which might be null",
+                "34: dereference of 'c' which might be null",
+                "47: dereference of 'a' which might be null",
+            ]
+        "#]]
+        .assert_debug_eq(&messages(content));
+    }
+
+    #[test]
+    fn nullable_arguments() {
+        let content = r"
+package ch.emilycares;
+import org.jetbrains.annotations.NotNull;
+import org.jetbrains.annotations.Nullable;
+public class Test {
+    public Test(@NotNull String name) {
+    }
+    void calls(@Nullable String a, String b) {
+        take(null, b);
+        take(a, b);
+        take(b, a);
+        this.take(a, null);
+        new Test(a);
+        if (a != null) {
+            take(a, b);
+        }
+    }
+    void take(@NotNull String value, String other) {
+    }
+}
+";
+        expect![[r#"
+            [
+                "8: passing null to non-null parameter 'value'",
+                "9: passing a value that might be null to non-null parameter 'value'",
+                "11: passing a value that might be null to non-null parameter 'value'",
+                "12: passing a value that might be null to non-null parameter 'name'",
+            ]
+        "#]]
+        .assert_debug_eq(&messages(content));
+    }
+
+    #[test]
+    fn selected_overloads() {
+        let content = r#"
+package ch.emilycares;
+import org.jetbrains.annotations.NotNull;
+import org.jetbrains.annotations.Nullable;
+public class Test {
+    void calls(@Nullable String a, int n) {
+        take(a);
+        take(n);
+        String b = find(n);
+        b.length();
+        String c = find("key");
+        c.length();
+    }
+    void take(@NotNull String value) {
+    }
+    void take(int value) {
+    }
+    String find(int index) {
+        return "";
+    }
+    @Nullable
+    String find(String key) {
+        return null;
+    }
+}
+"#;
+        expect![[r#"
+            [
+                "6: passing a value that might be null to non-null parameter 'value'",
+                "11: dereference of 'c' which might be null",
+            ]
+        "#]]
+        .assert_debug_eq(&messages(content));
+    }
+}
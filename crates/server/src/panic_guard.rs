@@ -0,0 +1,93 @@
+//! Keep the server alive when a handler panics, the request is answered with an internal error
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    cell::{Cell, RefCell},
+    fmt::Write as _,
+    panic::{self, AssertUnwindSafe},
+};
+
+use lsp_server::{ErrorCode, RequestId, Response};
+
+/// Lines of the backtrace that are written to the log
+const BACKTRACE_LINES: usize = 40;
+
+thread_local! {
+    static GUARDED: Cell<bool> = const { Cell::new(false) };
+    static BACKTRACE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Panics inside [`guard`] keep their backtrace for the log instead of printing it, all other
+/// panics go to the previous hook
+pub fn install_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if GUARDED.with(Cell::get) {
+            let backtrace = Backtrace::force_capture().to_string();
+            BACKTRACE.with(|b| *b.borrow_mut() = Some(backtrace));
+        } else {
+            previous(info);
+        }
+    }));
+}
+
+/// Run a handler, a panic is logged and returned as its message
+pub fn guard<T>(name: &str, handler: impl FnOnce() -> T) -> Result<T, String> {
+    let guarded = GUARDED.with(|g| g.replace(true));
+    let out = panic::catch_unwind(AssertUnwindSafe(handler));
+    GUARDED.with(|g| g.set(guarded));
+    out.map_err(|payload| {
+        let message = panic_message(payload.as_ref());
+        tracing::error!("Handler {name} panicked: {message}");
+        if let Some(backtrace) = BACKTRACE.with(|b| b.borrow_mut().take()) {
+            let lines = backtrace.lines().count();
+            let mut out = backtrace
+                .lines()
+                .take(BACKTRACE_LINES)
+                .collect::<Vec<_>>()
+                .join("\n");
+            if lines > BACKTRACE_LINES {
+                let _ = write!(out, "\n... {} more lines", lines - BACKTRACE_LINES);
+            }
+            tracing::debug!("Backtrace of {name}:\n{out}");
+        }
+        message
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return (*message).to_string();
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+    "unknown panic".to_string()
+}
+
+#[must_use]
+pub fn internal_error_response(id: RequestId, message: &str) -> Response {
+    Response::new_err(
+        id,
+        ErrorCode::InternalError as i32,
+        format!("Internal error: {message}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guard_panic() {
+        install_hook();
+        assert_eq!(guard("ok", || 1), Ok(1));
+        let out: Result<(), String> = guard("todo", || todo!("call chain"));
+        assert_eq!(out, Err("not yet implemented: call chain".to_string()));
+        let out: Result<(), String> = guard("index", || {
+            let items: Vec<u8> = Vec::new();
+            let _ = items[usize::from(items.is_empty())];
+        });
+        assert!(out.unwrap_err().contains("index out of bounds"));
+    }
+}
@@ -0,0 +1,192 @@
+//! Projects without a build tool that list their classpath in a [`ClasspathConfig`]
+//!
+//! The jars are loaded with a shard each, when the classpath file changes only the classes of
+//! removed and changed jars are dropped.
+use std::{
+    collections::HashMap,
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use common::{deps_dir, language_level::LanguageLevel};
+use config::classpath::ClasspathConfig;
+use dto::{Class, SourceDestination};
+use gradle::project::get_jar_shard_path;
+use loader::{
+    LoaderError,
+    classpath::{Classpath, classpath_path},
+};
+use my_string::MyString;
+
+#[must_use]
+pub fn get_plain_cache_path(project_dir: &Path, project_cache_dir: &Path) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    project_dir.hash(&mut hasher);
+    project_cache_dir.join(format!("{}.plain.cfc", hasher.finish()))
+}
+
+/// Parse the [`source_folders`], `progress` gets the number of parsed files and the total
+#[must_use]
+pub fn load_project_folders(
+    project_dir: &Path,
+    progress: impl Fn(usize, usize) + Sync,
+) -> Vec<Class> {
+    loader::load_java_folders(source_folders(project_dir), progress)
+}
+
+/// Source roots and the generated sources of the classpath file, otherwise the current folder
+fn source_folders(project_dir: &Path) -> Vec<PathBuf> {
+    match ClasspathConfig::load(project_dir) {
+        Ok(Some(config)) => {
+            let mut folders = config.source_dirs(project_dir);
+            let generated = config.generated_dir(project_dir);
+            if generated.is_dir() && !folders.iter().any(|f| generated.starts_with(f)) {
+                folders.push(generated);
+            }
+            folders
+        }
+        Ok(None) => vec![PathBuf::from("./")],
+        Err(e) => {
+            tracing::warn!("Failed to load classpath file: {e:?}");
+            vec![PathBuf::from("./")]
+        }
+    }
+}
+
+/// Load the jars of the classpath file into the class map, with `sources` the source roots are
+/// parsed again
+pub fn index_project(
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    cache_path: &Path,
+    project_dir: &Path,
+    sources: bool,
+) {
+    let config = match ClasspathConfig::load(project_dir) {
+        Ok(Some(config)) => config,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("Failed to load classpath file: {e:?}");
+            return;
+        }
+    };
+    let shards_dir = deps_dir().join("jars");
+    let _ = fs::create_dir_all(&shards_dir);
+    let shards: Vec<(PathBuf, PathBuf)> = config
+        .jar_paths(project_dir)
+        .into_iter()
+        .map(|jar| {
+            let shard = get_jar_shard_path(&jar, &shards_dir);
+            (jar, shard)
+        })
+        .collect();
+    let classpath_path = classpath_path(cache_path);
+    let old_classpath = Classpath::load(&classpath_path);
+    let diff = old_classpath.diff(&Classpath::new(shards.iter().map(|(j, s)| (j, s))));
+    old_classpath.drop_stale(&diff, class_map);
+
+    let mut classes: Vec<Class> = loader::parallel::parallel_map(
+        &shards,
+        |(jar, shard)| {
+            let classes = loader::load_class_folder(shard).or_else(|_| {
+                let classes = loader::load_classes_jar(jar, SourceDestination::None)?;
+                if let Err(e) = loader::save_class_folder(shard, &classes) {
+                    tracing::warn!("Failed to save shard of {}, {e:?}", jar.display());
+                }
+                Ok::<_, LoaderError>(classes)
+            });
+            match classes {
+                Ok(classes) => Some(classes.classes),
+                Err(e) => {
+                    tracing::warn!("Failed to load jar: {}, {e:?}", jar.display());
+                    None
+                }
+            }
+        },
+        |_, _| {},
+    )
+    .into_iter()
+    .flatten()
+    .collect();
+    if let Err(e) = Classpath::new(shards).save(&classpath_path) {
+        tracing::warn!("Failed to save {} because: {e:?}", classpath_path.display());
+    }
+    if sources {
+        classes.extend(loader::load_java_folders(
+            config.source_dirs(project_dir),
+            |_, _| {},
+        ));
+    }
+    if let Ok(mut cm) = class_map.write() {
+        for class in classes {
+            cm.insert(class.class_path.clone(), class);
+        }
+    }
+}
+
+/// Release and preview features of the classpath file
+#[must_use]
+pub fn language_level(project_dir: &Path) -> LanguageLevel {
+    match ClasspathConfig::load(project_dir) {
+        Ok(Some(config)) => LanguageLevel {
+            release: config.release,
+            preview: config.preview,
+        },
+        _ => LanguageLevel::default(),
+    }
+}
+
+/// Classpath, output folder and folder for generated sources for javac, none without a
+/// classpath file
+#[must_use]
+pub fn javac_classpath(project_dir: &Path) -> Option<(String, PathBuf, PathBuf)> {
+    let config = ClasspathConfig::load(project_dir).ok()??;
+    let output = config.output_dir(project_dir);
+    let classpath = std::iter::once(output.clone())
+        .chain(config.jar_paths(project_dir))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join(&compile::CLASSPATH_SEPARATOR.to_string());
+    Some((classpath, output, config.generated_dir(project_dir)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::source_folders;
+
+    #[test]
+    fn source_roots() {
+        let dir = std::env::temp_dir().join(format!("plain-project-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(source_folders(&dir), [PathBuf::from("./")]);
+
+        fs::write(dir.join(".jvmrc"), "").unwrap();
+        assert_eq!(source_folders(&dir), std::slice::from_ref(&dir));
+
+        fs::write(
+            dir.join(".jvmrc"),
+            "sources = [\"src\", \"test\"]\ngenerated = \"gen\"\n",
+        )
+        .unwrap();
+        assert_eq!(source_folders(&dir), [dir.join("src"), dir.join("test")]);
+        fs::create_dir_all(dir.join("gen")).unwrap();
+        assert_eq!(
+            source_folders(&dir),
+            [dir.join("src"), dir.join("test"), dir.join("gen")]
+        );
+
+        // Generated sources in a source root are already parsed
+        fs::write(
+            dir.join(".jvmrc"),
+            "sources = [\"src\"]\ngenerated = \"src/gen\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("src/gen")).unwrap();
+        assert_eq!(source_folders(&dir), [dir.join("src")]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
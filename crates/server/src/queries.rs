@@ -0,0 +1,484 @@
+//! Memoized diagnostics of the documents, the analyses of method bodies are kept per body
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use ast::{
+    types::{
+        AstAnnotated, AstBlock, AstClassBlock, AstClassConstructor, AstClassMethod,
+        AstClassVariable, AstEnumerationVariant, AstFile, AstInterfaceConstant,
+        AstInterfaceMethodDefault, AstJType, AstRange, AstThing, AstTopLevel,
+    },
+    visit::Visitor,
+};
+use common::language_level::LanguageLevel;
+use config::Configuration;
+use document::Document;
+use dto::Class;
+use lsp_extra::to_lsp_range;
+use lsp_types::{Diagnostic, Range, Uri};
+use my_string::MyString;
+
+use crate::diagnostics;
+
+#[derive(Debug, Default)]
+pub struct Queries {
+    /// Bumped when classes of the class map were replaced
+    classes: AtomicU64,
+    files: Mutex<HashMap<MyString, FileMemo>>,
+}
+
+/// Inputs of the diagnostics of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Inputs {
+    text: u64,
+    /// Revision and size of the class map, loading classes only changes the size
+    classes: (u64, usize),
+    level: LanguageLevel,
+}
+
+#[derive(Debug)]
+struct FileMemo {
+    inputs: Inputs,
+    diagnostics: Vec<Diagnostic>,
+    /// Hash of the text outside of the member bodies, the headers and fields are part of it
+    skeleton: u64,
+    /// Diagnostics of each member by the hash of its index and body, lines are relative to its
+    /// start
+    members: HashMap<u64, Vec<Diagnostic>>,
+}
+
+/// Body of a method or constructor
+#[derive(Debug)]
+struct Member {
+    range: AstRange,
+    hash: u64,
+}
+
+impl Queries {
+    /// Classes of the class map changed, the diagnostics of all documents are computed again
+    pub fn classes_changed(&self) {
+        self.classes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Forget all results, used after the configuration changed
+    pub fn clear(&self) {
+        if let Ok(mut files) = self.files.lock() {
+            files.clear();
+        }
+    }
+
+    /// Forget the results of a closed document
+    pub fn remove(&self, key: &str) {
+        if let Ok(mut files) = self.files.lock() {
+            files.remove(key);
+        }
+    }
+
+    /// Diagnostics of the document, only the results whose inputs changed are computed again
+    pub fn diagnostics(
+        &self,
+        key: &str,
+        document: &Document,
+        uri: &Uri,
+        class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+        config: &Configuration,
+        level: LanguageLevel,
+    ) -> Vec<Diagnostic> {
+        let inputs = Inputs {
+            text: text_hash(document, None),
+            classes: (
+                self.classes.load(Ordering::Relaxed),
+                class_map.read().map_or(0, |class_map| class_map.len()),
+            ),
+            level,
+        };
+        let previous = {
+            let Ok(mut files) = self.files.lock() else {
+                return diagnostics::diagnostics(document, uri, class_map, config, level);
+            };
+            if let Some(memo) = files.get(key)
+                && memo.inputs == inputs
+            {
+                return memo.diagnostics.clone();
+            }
+            files.remove(key)
+        };
+        let members = members(document);
+        let skeleton = skeleton_hash(document, &members);
+        let previous = previous
+            .filter(|memo| memo.skeleton == skeleton && memo.inputs.classes == inputs.classes);
+        let (member_diagnostics, memos) =
+            member_diagnostics(document, class_map, config, &members, previous);
+        let mut out = diagnostics::file_diagnostics(document, uri, class_map, config, level);
+        out.extend(member_diagnostics);
+        let out = diagnostics::apply_levels(out, &config.diagnostic_levels);
+        if let Ok(mut files) = self.files.lock() {
+            files.insert(
+                key.into(),
+                FileMemo {
+                    inputs,
+                    diagnostics: out.clone(),
+                    skeleton,
+                    members: memos,
+                },
+            );
+        }
+        out
+    }
+}
+
+/// Diagnostics of the member bodies and the kept diagnostics of each member. Members that are
+/// unchanged since the previous result are reused when at most one member changed.
+fn member_diagnostics(
+    document: &Document,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    config: &Configuration,
+    members: &[Member],
+    previous: Option<FileMemo>,
+) -> (Vec<Diagnostic>, HashMap<u64, Vec<Diagnostic>>) {
+    let mut memos = previous.map(|memo| memo.members).unwrap_or_default();
+    let changed: Vec<&Member> = members
+        .iter()
+        .filter(|m| !memos.contains_key(&m.hash))
+        .collect();
+    let ranges: Vec<Option<Range>> = members
+        .iter()
+        .map(|m| to_lsp_range(&m.range).ok())
+        .collect();
+    let (found, computed): (Vec<Diagnostic>, Vec<&Member>) = match changed.as_slice() {
+        [] | [_] => {
+            let keep = changed.first().map(|m| m.range);
+            let mut ast = document.ast.clone();
+            strip_file(&mut ast, keep.as_ref());
+            (
+                diagnostics::member_diagnostics(&ast, class_map, config),
+                changed,
+            )
+        }
+        _ => (
+            diagnostics::member_diagnostics(&document.ast, class_map, config),
+            members.iter().collect(),
+        ),
+    };
+    let mut out = Vec::new();
+    let mut computed_diagnostics: HashMap<u64, Vec<Diagnostic>> =
+        computed.iter().map(|m| (m.hash, Vec::new())).collect();
+    for diagnostic in found {
+        let member = members
+            .iter()
+            .zip(&ranges)
+            .find(|(_, range)| range.is_some_and(|r| contains(&r, &diagnostic.range)));
+        match member {
+            // Body of a member that was not computed, the empty body is analysed
+            Some((member, _)) if !computed_diagnostics.contains_key(&member.hash) => {}
+            Some((member, _)) => {
+                out.push(diagnostic.clone());
+                if let Some(kept) = computed_diagnostics.get_mut(&member.hash) {
+                    kept.push(shift(diagnostic, start_line(member), 0));
+                }
+            }
+            None => out.push(diagnostic),
+        }
+    }
+    memos.extend(computed_diagnostics);
+    for member in members {
+        if computed.iter().any(|c| c.hash == member.hash) {
+            continue;
+        }
+        if let Some(kept) = memos.get(&member.hash) {
+            out.extend(kept.iter().map(|d| shift(d.clone(), 0, start_line(member))));
+        }
+    }
+    memos.retain(|hash, _| members.iter().any(|m| m.hash == *hash));
+    (out, memos)
+}
+
+fn start_line(member: &Member) -> u32 {
+    u32::try_from(member.range.start.line).unwrap_or(u32::MAX)
+}
+
+/// Move the diagnostic from lines relative to `from` to lines relative to `to`
+fn shift(mut diagnostic: Diagnostic, from: u32, to: u32) -> Diagnostic {
+    for position in [&mut diagnostic.range.start, &mut diagnostic.range.end] {
+        position.line = position.line.saturating_sub(from).saturating_add(to);
+    }
+    diagnostic
+}
+
+fn contains(outer: &Range, inner: &Range) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+/// Bodies of the members in the order of the file, bodies of anonymous and local classes belong
+/// to the member that contains them
+fn members(document: &Document) -> Vec<Member> {
+    let mut finder = Members::default();
+    finder.visit_file(&document.ast);
+    let mut ranges = finder.ranges;
+    ranges.sort_unstable_by_key(|r| (r.start.line, r.start.col));
+    ranges
+        .into_iter()
+        .enumerate()
+        .map(|(index, range)| {
+            // Bodies with the same text can belong to headers with different diagnostics, the
+            // skeleton is unchanged so the index names the same member
+            let mut hasher = DefaultHasher::new();
+            index.hash(&mut hasher);
+            text_hash(document, Some(&range)).hash(&mut hasher);
+            Member {
+                range,
+                hash: hasher.finish(),
+            }
+        })
+        .collect()
+}
+
+/// Hash of the text in the range, the whole text without a range. The column of the start is
+/// included, diagnostics on the first line depend on it.
+fn text_hash(document: &Document, range: Option<&AstRange>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let text = range.map_or_else(
+        || document.rope.slice(..),
+        |range| {
+            range.start.col.hash(&mut hasher);
+            let start = document.point_to_char(&range.start);
+            let end = document.point_to_char(&range.end).max(start);
+            document.rope.slice(start..end)
+        },
+    );
+    for chunk in text.chunks() {
+        chunk.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn skeleton_hash(document: &Document, members: &[Member]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let mut start = 0;
+    for member in members {
+        let end = document.point_to_char(&member.range.start);
+        if end > start {
+            for chunk in document.rope.slice(start..end).chunks() {
+                chunk.hash(&mut hasher);
+            }
+        }
+        start = start.max(document.point_to_char(&member.range.end));
+    }
+    members.len().hash(&mut hasher);
+    for chunk in document
+        .rope
+        .slice(start.min(document.rope.len_chars())..)
+        .chunks()
+    {
+        chunk.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Finds the bodies of the members, headers, initializers and fields are part of the skeleton
+#[derive(Default)]
+struct Members {
+    ranges: Vec<AstRange>,
+}
+
+impl<'a> Visitor<'a> for Members {
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        if let Some(block) = &method.block {
+            self.ranges.push(block.range);
+        }
+    }
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        self.ranges.push(constructor.block.range);
+    }
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        self.ranges.push(method.block.range);
+    }
+    fn visit_class_variable(&mut self, _variable: &'a AstClassVariable) {}
+    fn visit_interface_constant(&mut self, _constant: &'a AstInterfaceConstant) {}
+    fn visit_enumeration_variant(&mut self, _variant: &'a AstEnumerationVariant) {}
+    fn visit_block(&mut self, _block: &'a AstBlock) {}
+    fn visit_annotated(&mut self, _annotated: &'a AstAnnotated) {}
+    fn visit_jtype(&mut self, _jtype: &'a AstJType) {}
+}
+
+/// Empty the bodies of all members except the kept one
+fn strip_file(file: &mut AstFile, keep: Option<&AstRange>) {
+    for top in &mut file.top {
+        match top {
+            AstTopLevel::Thing(thing) => strip_thing(thing, keep),
+            AstTopLevel::Method(method) => strip_method(method, keep),
+            AstTopLevel::Package(_) | AstTopLevel::Import(_) | AstTopLevel::Module(_) => (),
+        }
+    }
+}
+
+fn strip_thing(thing: &mut AstThing, keep: Option<&AstRange>) {
+    match thing {
+        AstThing::Class(class) => strip_class_block(&mut class.block, keep),
+        AstThing::Record(record) => strip_class_block(&mut record.block, keep),
+        AstThing::Interface(interface) => {
+            for method in &mut interface.default_methods {
+                if Some(&method.block.range) != keep {
+                    method.block.entries.clear();
+                }
+            }
+            for inner in &mut interface.inner {
+                strip_thing(inner, keep);
+            }
+        }
+        AstThing::Enumeration(enumeration) => {
+            for method in &mut enumeration.methods {
+                strip_method(method, keep);
+            }
+            for constructor in &mut enumeration.constructors {
+                if Some(&constructor.block.range) != keep {
+                    constructor.block.entries.clear();
+                }
+            }
+            for inner in &mut enumeration.inner {
+                strip_thing(inner, keep);
+            }
+        }
+        AstThing::Annotation(annotation) => {
+            for inner in &mut annotation.inner {
+                strip_thing(inner, keep);
+            }
+        }
+    }
+}
+
+fn strip_class_block(block: &mut AstClassBlock, keep: Option<&AstRange>) {
+    for method in &mut block.methods {
+        strip_method(method, keep);
+    }
+    for constructor in &mut block.constructors {
+        if Some(&constructor.block.range) != keep {
+            constructor.block.entries.clear();
+        }
+    }
+    for inner in &mut block.inner {
+        strip_thing(inner, keep);
+    }
+}
+
+fn strip_method(method: &mut AstClassMethod, keep: Option<&AstRange>) {
+    if let Some(block) = &mut method.block
+        && Some(&block.range) != keep
+    {
+        block.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        path::PathBuf,
+        str::FromStr,
+        sync::{Arc, RwLock},
+    };
+
+    use common::language_level::LanguageLevel;
+    use config::Configuration;
+    use document::Document;
+    use lsp_types::{TextDocumentContentChangeEvent, Uri};
+
+    use super::Queries;
+    use crate::diagnostics;
+
+    fn lines(diagnostics: &[lsp_types::Diagnostic]) -> Vec<String> {
+        let mut out: Vec<String> = diagnostics
+            .iter()
+            .map(|d| format!("{}: {}", d.range.start.line, d.message))
+            .collect();
+        out.sort();
+        out
+    }
+
+    #[test]
+    fn member_results_are_reused() {
+        let content = r"package ch.emilycares;
+public class Test {
+    int a() {
+    }
+    int b() {
+        return 1;
+    }
+    int c() {
+        return;
+        int d = 1;
+    }
+    void d() {
+        int x;
+        x++;
+    }
+    long x() {
+    }
+    void y() {
+    }
+}
+";
+        let mut document = Document::setup(content, PathBuf::from("Test.java")).unwrap();
+        let uri = Uri::from_str("file:///Test.java").unwrap();
+        let class_map = Arc::new(RwLock::new(HashMap::new()));
+        let config = Configuration::default();
+        let level = LanguageLevel::default();
+        let queries = Queries::default();
+        let first = queries.diagnostics("Test", &document, &uri, &class_map, &config, level);
+        assert_eq!(
+            lines(&first),
+            lines(&diagnostics::diagnostics(
+                &document, &uri, &class_map, &config, level
+            ))
+        );
+        // A new line in the body of b moves the diagnostics of c, y keeps its own result
+        // although its body is the same as the one of x
+        document
+            .apply_text_changes(&[TextDocumentContentChangeEvent {
+                range: Some(lsp_types::Range {
+                    start: lsp_types::Position::new(5, 0),
+                    end: lsp_types::Position::new(5, 0),
+                }),
+                range_length: None,
+                text: "        int e = 2;\n".to_string(),
+            }])
+            .unwrap();
+        let second = queries.diagnostics("Test", &document, &uri, &class_map, &config, level);
+        assert_eq!(
+            lines(&second),
+            lines(&diagnostics::diagnostics(
+                &document, &uri, &class_map, &config, level
+            ))
+        );
+        assert_ne!(lines(&first), lines(&second));
+        assert_eq!(
+            queries.diagnostics("Test", &document, &uri, &class_map, &config, level),
+            second
+        );
+        // A changed header computes all bodies again, a no longer misses a return
+        document
+            .apply_text_changes(&[TextDocumentContentChangeEvent {
+                range: Some(lsp_types::Range {
+                    start: lsp_types::Position::new(2, 4),
+                    end: lsp_types::Position::new(2, 7),
+                }),
+                range_length: None,
+                text: "void".to_string(),
+            }])
+            .unwrap();
+        let third = queries.diagnostics("Test", &document, &uri, &class_map, &config, level);
+        assert_eq!(
+            lines(&third),
+            lines(&diagnostics::diagnostics(
+                &document, &uri, &class_map, &config, level
+            ))
+        );
+        assert_ne!(lines(&second), lines(&third));
+    }
+}
@@ -0,0 +1,944 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use ast::{
+    range::GetRange,
+    types::{
+        AstAvailability, AstBaseExpression, AstBlockEntry, AstBlockReturn, AstClassConstructor,
+        AstClassMethod, AstExpressionIdentifier, AstExpressionKind, AstExpressionOrValue,
+        AstInterfaceMethodDefault, AstJType, AstLambda, AstMethodHeader, AstMethodParameters,
+        AstPoint, AstRange, AstThing, AstThrowsDeclaration,
+    },
+    visit::{self, Visitor},
+};
+use call_chain::CallItem;
+use document::{Document, read_document_or_open_class};
+use dto::{JType, SourceDestination};
+use lsp_extra::{source_to_uri, to_ast_point, to_lsp_range};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, TextEdit, WorkspaceEdit,
+};
+use my_string::{
+    MyString,
+    smol_str::{SmolStr, ToSmolStr},
+};
+
+use crate::{
+    codeaction::{CodeActionContext, CodeActionError, import_text_edit},
+    exceptions::{self, ExceptionContext},
+    refactor::{self, class_name, import_edits, line_indent, selected_statements, type_display},
+};
+
+/// Start of the javac message for a checked exception that is neither caught nor declared
+const UNREPORTED_EXCEPTION: &str = "unreported exception ";
+
+/// Quick fixes for the unreported exception diagnostics.
+/// Either declare the exception on the enclosing method or surround the statement with try/catch.
+pub fn unhandled_exception(
+    document: &Document,
+    context: &CodeActionContext,
+    diagnostics: &[Diagnostic],
+) -> Result<Vec<CodeActionOrCommand>, CodeActionError> {
+    let mut out = vec![];
+    for diagnostic in diagnostics {
+        let Some(name) = diagnostic
+            .message
+            .strip_prefix(UNREPORTED_EXCEPTION)
+            .and_then(|m| m.split_once(';'))
+            .map(|(name, _)| name.trim())
+        else {
+            continue;
+        };
+        let point = to_ast_point(diagnostic.range.start);
+        let selection = AstRange {
+            start: point,
+            end: AstPoint::new(point.line, point.col + 1),
+        };
+        let entries = selected_statements(&document.ast, &selection);
+        // javac reports the simple name when it is not ambiguous
+        let Some(class_path) = entries
+            .and_then(|entries| {
+                exceptions::thrown_exceptions(
+                    entries,
+                    &ExceptionContext {
+                        ast: &document.ast,
+                        imports: context.imports,
+                        class_map: &context.class_map,
+                        class: context.class,
+                        vars: context.vars,
+                    },
+                )
+                .into_iter()
+                .map(|e| e.class_path)
+                .find(|c| c == name || class_name(c) == name)
+            })
+            .or_else(|| {
+                exceptions::exception_class_path(
+                    &JType::Class(name.to_smolstr()),
+                    context.imports,
+                    &context.class_map,
+                )
+            })
+        else {
+            continue;
+        };
+
+        let mut finder = ThrowsTarget {
+            point: &point,
+            found: None,
+            lambda: None,
+        };
+        finder.visit_file(&document.ast);
+        if let Some((parameters, throws)) = finder.found
+            && finder.lambda.is_none()
+        {
+            let mut edits = import_edits(document, context, std::slice::from_ref(&class_path));
+            edits.push(add_throws_edit(
+                parameters,
+                throws,
+                class_name(&class_path),
+            )?);
+            out.push(quick_fix(
+                context,
+                format!("Add throws declaration for {}", class_name(&class_path)),
+                diagnostic,
+                edits,
+            ));
+        }
+
+        // Surrounding the statement that contains a expression lambda would not handle it
+        if let Some(entries) = entries
+            && finder
+                .lambda
+                .is_none_or(|l| entries.get_range().is_contained_in(&l))
+        {
+            out.push(quick_fix(
+                context,
+                "Surround with try/catch".to_owned(),
+                diagnostic,
+                refactor::surround_try_catch(document, context, entries)?,
+            ));
+        }
+    }
+    Ok(out)
+}
+
+/// Quick fix to create the called method when it does not exist in the workspace class of the receiver
+pub fn create_method(
+    document: &Document,
+    context: &CodeActionContext,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+) -> Result<Option<CodeActionOrCommand>, CodeActionError> {
+    let call_chain = call_chain::get_call_chain(&document.ast, context.point);
+    let Some(index) = call_chain.iter().position(
+        |c| matches!(c, CallItem::MethodCall { range, .. } if range.is_in_range(context.point)),
+    ) else {
+        return Ok(None);
+    };
+    let Some(CallItem::MethodCall { name, range, args }) = call_chain.get(index) else {
+        return Ok(None);
+    };
+    let receiver = &call_chain[..index];
+    let receiver_or_this = if receiver.is_empty() {
+        &[CallItem::This { range: *range }][..]
+    } else {
+        receiver
+    };
+    let Ok(resolve_state) = tyres::resolve_call_chain(
+        receiver_or_this,
+        context.vars,
+        context.imports,
+        context.class,
+        &context.class_map,
+    ) else {
+        return Ok(None);
+    };
+    let target_class = resolve_state.class;
+    let arguments = tyres::resolve_arguments(
+        args,
+        context.vars,
+        context.imports,
+        context.class,
+        &context.class_map,
+    );
+    if !tyres::select_methods(&target_class, Some(name), &arguments, &context.class_map).is_empty()
+    {
+        return Ok(None);
+    }
+
+    let same_class = target_class.class_path == context.class.class_path;
+    let (target_document, uri) = if same_class {
+        (None, context.current_file.clone())
+    } else {
+        // Only classes in the workspace can be edited
+        let SourceDestination::Here(source) = &target_class.source else {
+            return Ok(None);
+        };
+        (
+            Some(
+                read_document_or_open_class(source, document_map)
+                    .map_err(CodeActionError::Document)?,
+            ),
+            source_to_uri(source).map_err(CodeActionError::SourceToUri)?,
+        )
+    };
+    let target_document = target_document.as_ref().unwrap_or(document);
+    let thing_name = target_class
+        .name
+        .rsplit('$')
+        .next()
+        .unwrap_or(&target_class.name);
+    let mut thing_finder = ThingFinder {
+        name: thing_name,
+        found: None,
+    };
+    thing_finder.visit_file(&target_document.ast);
+    let Some(thing) = thing_finder.found else {
+        return Ok(None);
+    };
+    let is_interface = matches!(thing, AstThing::Interface(_));
+
+    let mut usage_finder = CallUsage {
+        call: range,
+        method: None,
+        in_lambda: false,
+        found: None,
+    };
+    usage_finder.visit_file(&document.ast);
+    let ret = qualify(&usage_finder.return_type(context), context);
+    let is_static = match receiver.last() {
+        None => usage_finder
+            .method
+            .is_some_and(|m| m.availability.contains(AstAvailability::Static)),
+        Some(CallItem::Class { .. } | CallItem::ClassGeneric { .. }) => true,
+        Some(CallItem::ClassOrVariable { name, .. }) => {
+            !context.vars.iter().any(|v| v.name == *name)
+        }
+        Some(_) => false,
+    };
+
+    let mut parameter_types = vec![];
+    let mut parameters = vec![];
+    for (i, argument) in args.iter().enumerate() {
+        let jtype = qualify(&argument_type(argument, document, context), context);
+        let mut parameter_name = match argument.as_slice() {
+            [CallItem::ClassOrVariable { name, .. } | CallItem::Variable { name, .. }]
+                if name.starts_with(char::is_lowercase) =>
+            {
+                name.to_string()
+            }
+            _ => format!("arg{i}"),
+        };
+        if parameters.iter().any(|(_, n)| *n == parameter_name) {
+            parameter_name = format!("{parameter_name}{i}");
+        }
+        parameter_types.push(type_display(&jtype));
+        parameters.push((jtype, parameter_name));
+    }
+
+    let end = thing.get_range().end;
+    let brace = AstPoint::new(end.line, end.col.saturating_sub(1));
+    let indent = line_indent(target_document, brace.line);
+    let mut modifiers = String::new();
+    if same_class {
+        modifiers.push_str("private ");
+    } else if !is_interface {
+        modifiers.push_str("public ");
+    }
+    if is_static {
+        modifiers.push_str("static ");
+    }
+    let signature = format!(
+        "{modifiers}{} {name}({})",
+        type_display(&ret),
+        parameters
+            .iter()
+            .map(|(jtype, name)| format!("{} {name}", type_display(jtype)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let method = if is_interface && !is_static {
+        format!("{indent}    {signature};\n")
+    } else {
+        let body = default_return(&ret)
+            .map(|r| format!("{indent}        return {r};\n"))
+            .unwrap_or_default();
+        format!("{indent}    {signature} {{\n{body}{indent}    }}\n")
+    };
+    let before_brace = document_line_prefix(target_document, &brace);
+    let (start, new_text) = if before_brace.trim().is_empty() {
+        (AstPoint::new(brace.line, 0), format!("\n{method}"))
+    } else {
+        (brace, format!("\n{method}{indent}"))
+    };
+
+    let mut class_paths = vec![];
+    for jtype in parameters.iter().map(|(j, _)| j).chain([&ret]) {
+        jtype_class_paths(jtype, &mut class_paths);
+    }
+    class_paths.sort();
+    class_paths.dedup();
+    let package = target_class
+        .class_path
+        .rsplit_once('.')
+        .map_or("", |(p, _)| p);
+    let target_imports = imports::imports(&target_document.ast);
+    let mut edits: Vec<TextEdit> = vec![];
+    for class_path in &class_paths {
+        let Some((class_package, simple_name)) = class_path.rsplit_once('.') else {
+            continue;
+        };
+        if class_package == "java.lang"
+            || class_package == package
+            || tyres::is_imported(simple_name, &target_imports, &context.class_map).is_some()
+        {
+            continue;
+        }
+        edits.extend(import_text_edit(class_path, &target_document.ast));
+    }
+    edits.push(TextEdit {
+        range: to_lsp_range(&AstRange { start, end: start })
+            .map_err(CodeActionError::ToLspRange)?,
+        new_text,
+    });
+
+    // Required by lsp types
+    #[allow(clippy::mutable_key_type)]
+    let mut changes = HashMap::new();
+    changes.insert(uri, edits);
+    Ok(Some(CodeActionOrCommand::CodeAction(CodeAction {
+        kind: Some(CodeActionKind::QUICKFIX),
+        title: format!(
+            "Create method {name}({}) in {thing_name}",
+            parameter_types.join(", ")
+        ),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })))
+}
+
+/// Literals are resolved as their boxed class
+fn argument_type(argument: &[CallItem], document: &Document, context: &CodeActionContext) -> JType {
+    if let [CallItem::Class { name, range }] = argument
+        && document.range_text(range) != *name
+    {
+        match name.as_str() {
+            "Integer" => return JType::Int,
+            "Long" => return JType::Long,
+            "Double" => return JType::Double,
+            "Float" => return JType::Float,
+            "Boolean" => return JType::Boolean,
+            "Char" => return JType::Char,
+            _ => (),
+        }
+    }
+    tyres::resolve_call_chain_value(
+        argument,
+        context.vars,
+        context.imports,
+        context.class,
+        &context.class_map,
+    )
+    .map_or_else(|_| object(), |r| r.jtype)
+}
+
+const fn object() -> JType {
+    JType::Class(SmolStr::new_static("java.lang.Object"))
+}
+
+/// Replace class names with their class path
+fn qualify(jtype: &JType, context: &CodeActionContext) -> JType {
+    let qualify_name = |name: &MyString| {
+        if name.contains('.') {
+            return name.clone();
+        }
+        tyres::resolve(name, context.imports, &context.class_map)
+            .map_or_else(|_| name.clone(), |r| r.class.class_path)
+    };
+    match jtype {
+        JType::Class(name) | JType::ClassOrPackage(name) => JType::Class(qualify_name(name)),
+        JType::Generic(name, args) => JType::Generic(
+            qualify_name(name),
+            args.iter().map(|a| qualify(a, context)).collect(),
+        ),
+        JType::Array(jtype) => JType::Array(Box::new(qualify(jtype, context))),
+        JType::Var => object(),
+        jtype => jtype.clone(),
+    }
+}
+
+pub(crate) fn jtype_class_paths(jtype: &JType, out: &mut Vec<MyString>) {
+    match jtype {
+        JType::Class(name) => out.push(name.clone()),
+        JType::Generic(name, args) => {
+            out.push(name.clone());
+            for arg in args {
+                jtype_class_paths(arg, out);
+            }
+        }
+        JType::Array(jtype) => jtype_class_paths(jtype, out),
+        JType::Extends { base, extends } => {
+            jtype_class_paths(base, out);
+            jtype_class_paths(extends, out);
+        }
+        _ => (),
+    }
+}
+
+const fn default_return(jtype: &JType) -> Option<&'static str> {
+    match jtype {
+        JType::Void => None,
+        JType::Boolean => Some("false"),
+        JType::Byte
+        | JType::Char
+        | JType::Double
+        | JType::Float
+        | JType::Int
+        | JType::Long
+        | JType::Short => Some("0"),
+        _ => Some("null"),
+    }
+}
+
+fn document_line_prefix(document: &Document, point: &AstPoint) -> String {
+    document.range_text(&AstRange {
+        start: AstPoint::new(point.line, 0),
+        end: *point,
+    })
+}
+
+/// Finds a class, interface, enum or record by name
+struct ThingFinder<'a, 'b> {
+    name: &'b str,
+    found: Option<&'a AstThing>,
+}
+
+impl<'a> Visitor<'a> for ThingFinder<'a, '_> {
+    fn visit_thing(&mut self, thing: &'a AstThing) {
+        let name = match thing {
+            AstThing::Class(c) => &c.name,
+            AstThing::Record(r) => &r.name,
+            AstThing::Interface(i) => &i.name,
+            AstThing::Enumeration(e) => &e.name,
+            AstThing::Annotation(a) => &a.name,
+        };
+        if self.found.is_none() && name.value == self.name {
+            self.found = Some(thing);
+        }
+        visit::walk_thing(self, thing);
+    }
+}
+
+/// How the result of a method call is used
+enum Usage<'a> {
+    Statement,
+    Variable(&'a AstJType),
+    Assign(&'a [AstExpressionKind]),
+    Return(Option<&'a AstJType>),
+}
+
+/// Finds the usage of the call and the method that contains it
+struct CallUsage<'a, 'b> {
+    call: &'b AstRange,
+    method: Option<&'a AstMethodHeader>,
+    in_lambda: bool,
+    found: Option<Usage<'a>>,
+}
+
+impl CallUsage<'_, '_> {
+    fn return_type(&self, context: &CodeActionContext) -> JType {
+        match &self.found {
+            Some(Usage::Statement) => JType::Void,
+            Some(Usage::Variable(jtype) | Usage::Return(Some(jtype))) => JType::from(*jtype),
+            Some(Usage::Assign(
+                [
+                    AstExpressionKind::Base(AstBaseExpression {
+                        ident: Some(AstExpressionIdentifier::Identifier(ident)),
+                        values: None,
+                        ..
+                    }),
+                ],
+            )) => context
+                .vars
+                .iter()
+                .rev()
+                .find(|v| v.name == ident.value)
+                .map_or_else(object, |v| v.jtype.clone()),
+            Some(Usage::Assign(_) | Usage::Return(None)) | None => object(),
+        }
+    }
+
+    /// Is the method call the outermost part of the expression
+    fn is_call(&self, expression: &[AstExpressionKind]) -> bool {
+        matches!(
+            expression,
+            [
+                ..,
+                AstExpressionKind::Base(AstBaseExpression {
+                    ident: Some(AstExpressionIdentifier::Identifier(ident)),
+                    ..
+                }),
+                AstExpressionKind::Base(AstBaseExpression {
+                    ident: None,
+                    values: Some(_),
+                    ..
+                }),
+            ] if ident.range == *self.call
+        )
+    }
+}
+
+impl<'a> Visitor<'a> for CallUsage<'a, '_> {
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        if method
+            .block
+            .as_ref()
+            .is_some_and(|b| b.range.is_in_range(&self.call.start))
+        {
+            self.method = Some(&method.header);
+            self.in_lambda = false;
+        }
+        visit::walk_class_method(self, method);
+    }
+
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        if method.block.range.is_in_range(&self.call.start) {
+            self.method = Some(&method.header);
+            self.in_lambda = false;
+        }
+        visit::walk_interface_method_default(self, method);
+    }
+
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        match entry {
+            AstBlockEntry::Expression(expression) if self.is_call(&expression.value) => {
+                self.found = Some(Usage::Statement);
+            }
+            AstBlockEntry::Variable(variables) => {
+                if let Some(variable) = variables
+                    .iter()
+                    .find(|v| v.value.as_ref().is_some_and(|e| self.is_call(e)))
+                {
+                    self.found = Some(Usage::Variable(&variable.jtype));
+                }
+            }
+            AstBlockEntry::Assign(assign) if self.is_call(&assign.expression) => {
+                self.found = Some(Usage::Assign(&assign.key));
+            }
+            AstBlockEntry::Return(AstBlockReturn {
+                expression: AstExpressionOrValue::Expression(expression),
+                ..
+            }) if self.is_call(expression) => {
+                self.found = Some(Usage::Return(
+                    self.method.filter(|_| !self.in_lambda).map(|m| &m.jtype),
+                ));
+            }
+            _ => (),
+        }
+        visit::walk_block_entry(self, entry);
+    }
+
+    fn visit_lambda(&mut self, lambda: &'a AstLambda) {
+        // The return type of a lambda is not known here
+        if lambda.range.is_in_range(&self.call.start) {
+            self.in_lambda = true;
+        }
+        visit::walk_lambda(self, lambda);
+    }
+}
+
+/// Append the exception to the throws clause or add one after the parameters
+fn add_throws_edit(
+    parameters: &AstMethodParameters,
+    throws: Option<&AstThrowsDeclaration>,
+    name: &str,
+) -> Result<TextEdit, CodeActionError> {
+    let (end, new_text) = throws.map_or_else(
+        || (parameters.range.end, format!(" throws {name}")),
+        |throws| (throws.range.end, format!(", {name}")),
+    );
+    Ok(TextEdit {
+        range: to_lsp_range(&AstRange { start: end, end }).map_err(CodeActionError::ToLspRange)?,
+        new_text,
+    })
+}
+
+pub(crate) fn quick_fix(
+    context: &CodeActionContext,
+    title: String,
+    diagnostic: &Diagnostic,
+    edits: Vec<TextEdit>,
+) -> CodeActionOrCommand {
+    // Required by lsp types
+    #[allow(clippy::mutable_key_type)]
+    let mut changes = HashMap::new();
+    changes.insert(context.current_file.to_owned(), edits);
+    CodeActionOrCommand::CodeAction(CodeAction {
+        kind: Some(CodeActionKind::QUICKFIX),
+        title,
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+/// Finds the header of the method that the exception propagates to
+/// and the innermost lambda in that method. A lambda body can not declare exceptions.
+struct ThrowsTarget<'a, 'b> {
+    point: &'b AstPoint,
+    found: Option<(&'a AstMethodParameters, Option<&'a AstThrowsDeclaration>)>,
+    lambda: Option<AstRange>,
+}
+
+const fn header_target(
+    header: &AstMethodHeader,
+) -> (&AstMethodParameters, Option<&AstThrowsDeclaration>) {
+    (&header.parameters, header.throws.as_ref())
+}
+
+impl<'a> Visitor<'a> for ThrowsTarget<'a, '_> {
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        if method
+            .block
+            .as_ref()
+            .is_some_and(|b| b.range.is_in_range(self.point))
+        {
+            self.found = Some(header_target(&method.header));
+            self.lambda = None;
+        }
+        visit::walk_class_method(self, method);
+    }
+
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        if method.block.range.is_in_range(self.point) {
+            self.found = Some(header_target(&method.header));
+            self.lambda = None;
+        }
+        visit::walk_interface_method_default(self, method);
+    }
+
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        if constructor.block.range.is_in_range(self.point) {
+            self.found = Some((
+                &constructor.header.parameters,
+                constructor.header.throws.as_ref(),
+            ));
+            self.lambda = None;
+        }
+        visit::walk_class_constructor(self, constructor);
+    }
+
+    fn visit_lambda(&mut self, lambda: &'a AstLambda) {
+        if lambda.range.is_in_range(self.point) {
+            self.lambda = Some(lambda.range);
+        }
+        visit::walk_lambda(self, lambda);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{
+        collections::HashMap,
+        path::PathBuf,
+        str::FromStr,
+        sync::{Arc, RwLock},
+    };
+
+    use ast::types::AstPoint;
+    use document::Document;
+    use dto::{Access, Class, JType, Method, SourceDestination};
+    use expect_test::expect;
+    use lsp_types::{CodeActionOrCommand, Diagnostic, Position, Range, Uri};
+    use my_string::{MyString, smol_str::SmolStr};
+    use variables::VariableContext;
+
+    use crate::{
+        codeaction::CodeActionContext,
+        refactor::tests::{apply_edits, get_class_map, run_actions},
+    };
+
+    use super::{create_method, unhandled_exception};
+
+    fn diagnostic(line: u32, character: u32, exception: &str) -> Diagnostic {
+        let position = Position::new(line, character);
+        Diagnostic::new_simple(
+            Range::new(position, position),
+            format!("unreported exception {exception}; must be caught or declared to be thrown"),
+        )
+    }
+
+    #[test]
+    fn unhandled_exception_new_throws() {
+        let cont = r#"
+package ch.emilycares;
+import java.io.FileInputStream;
+public class Test {
+    public void hello() {
+        FileInputStream in = new FileInputStream("a");
+    }
+}
+"#;
+        let diagnostics = vec![diagnostic(5, 29, "FileNotFoundException")];
+        let out = run_actions(cont, AstPoint::new(5, 29), |d, c| {
+            unhandled_exception(d, c, &diagnostics).unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Add throws declaration for FileNotFoundException",
+                    "\npackage ch.emilycares;\nimport java.io.FileInputStream;\nimport java.io.FileNotFoundException;\npublic class Test {\n    public void hello() throws FileNotFoundException {\n        FileInputStream in = new FileInputStream(\"a\");\n    }\n}\n",
+                ),
+                (
+                    "Surround with try/catch",
+                    "\npackage ch.emilycares;\nimport java.io.FileInputStream;\nimport java.io.FileNotFoundException;\npublic class Test {\n    public void hello() {\n        try {\n            FileInputStream in = new FileInputStream(\"a\");\n        } catch (FileNotFoundException e) {\n            throw new RuntimeException(e);\n        }\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn unhandled_exception_append_throws() {
+        let cont = r"
+package ch.emilycares;
+import java.io.FileInputStream;
+import java.io.FileNotFoundException;
+public class Test {
+    public Test(FileInputStream in) throws FileNotFoundException {
+        in.read();
+    }
+}
+";
+        let diagnostics = vec![diagnostic(6, 15, "IOException")];
+        let out = run_actions(cont, AstPoint::new(6, 15), |d, c| {
+            unhandled_exception(d, c, &diagnostics).unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Add throws declaration for IOException",
+                    "\npackage ch.emilycares;\nimport java.io.FileInputStream;\nimport java.io.FileNotFoundException;\nimport java.io.IOException;\npublic class Test {\n    public Test(FileInputStream in) throws FileNotFoundException, IOException {\n        in.read();\n    }\n}\n",
+                ),
+                (
+                    "Surround with try/catch",
+                    "\npackage ch.emilycares;\nimport java.io.FileInputStream;\nimport java.io.FileNotFoundException;\nimport java.io.IOException;\npublic class Test {\n    public Test(FileInputStream in) throws FileNotFoundException {\n        try {\n            in.read();\n        } catch (IOException e) {\n            throw new RuntimeException(e);\n        }\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn unhandled_exception_lambda() {
+        let cont = r"
+package ch.emilycares;
+import java.io.FileInputStream;
+public class Test {
+    public void hello(FileInputStream in) {
+        Runnable r = () -> {
+            in.read();
+        };
+    }
+}
+";
+        let diagnostics = vec![diagnostic(6, 15, "java.io.IOException")];
+        let out = run_actions(cont, AstPoint::new(6, 15), |d, c| {
+            unhandled_exception(d, c, &diagnostics).unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Surround with try/catch",
+                    "\npackage ch.emilycares;\nimport java.io.FileInputStream;\nimport java.io.IOException;\npublic class Test {\n    public void hello(FileInputStream in) {\n        Runnable r = () -> {\n            try {\n                in.read();\n            } catch (IOException e) {\n                throw new RuntimeException(e);\n            }\n        };\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    const OTHER_SOURCE: &str = "/project/src/main/java/ch/emilycares/Other.java";
+    const OTHER: &str = r"
+package ch.emilycares;
+
+public class Other {
+    public void existing(int a) {
+    }
+}
+";
+
+    /// Create the method and return the title and the changed files
+    fn create_method_edit(cont: &str, point: AstPoint) -> Option<(String, Vec<(String, String)>)> {
+        let doc = Document::setup(cont, PathBuf::from_str("./").unwrap()).unwrap();
+        let imports = imports::imports(&doc.ast);
+        let class = parser::java::load_java_tree(&doc.ast, SourceDestination::None);
+        let uri = Uri::from_str("file:///a").unwrap();
+        let other_uri = Uri::from_str(&format!("file://{OTHER_SOURCE}")).unwrap();
+        let class_map = get_class_map();
+        class_map.write().unwrap().insert(
+            SmolStr::new("ch.emilycares.Other"),
+            Class {
+                access: Access::Public,
+                class_path: SmolStr::new("ch.emilycares.Other"),
+                name: SmolStr::new_inline("Other"),
+                source: SourceDestination::Here(SmolStr::new(OTHER_SOURCE)),
+                methods: vec![Method {
+                    access: Access::Public,
+                    name: Some(SmolStr::new_inline("existing")),
+                    parameters: vec![dto::Parameter {
+                        name: None,
+                        jtype: JType::Int,
+                        annotations: vec![],
+                    }],
+                    ret: JType::Void,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        );
+        let document_map: Arc<RwLock<HashMap<MyString, Document>>> =
+            Arc::new(RwLock::new(HashMap::new()));
+        document_map.write().unwrap().insert(
+            SmolStr::new(OTHER_SOURCE),
+            Document::setup(OTHER, PathBuf::from_str(OTHER_SOURCE).unwrap()).unwrap(),
+        );
+        let vars = variables::get_vars(
+            &doc.ast,
+            &VariableContext {
+                point: Some(point),
+                imports: &imports,
+                class: &class,
+                class_map: class_map.clone(),
+            },
+        )
+        .unwrap();
+        let context = CodeActionContext {
+            point: &point,
+            imports: &imports,
+            class_map,
+            class: &class,
+            vars: &vars,
+            current_file: &uri,
+        };
+        let CodeActionOrCommand::CodeAction(action) =
+            create_method(&doc, &context, &document_map).unwrap()?
+        else {
+            return None;
+        };
+        #[allow(clippy::mutable_key_type)]
+        let changes = action.edit?.changes?;
+        let mut files: Vec<_> = changes
+            .iter()
+            .map(|(u, edits)| {
+                let text = if *u == other_uri { OTHER } else { cont };
+                (u.to_string(), apply_edits(text, edits))
+            })
+            .collect();
+        files.sort();
+        Some((action.title, files))
+    }
+
+    #[test]
+    fn create_method_same_class() {
+        let cont = r#"
+package ch.emilycares;
+public class Test {
+    public static void hello(String name) {
+        String out = format(name, 1, "a");
+    }
+}
+"#;
+        let out = create_method_edit(cont, AstPoint::new(4, 23));
+        let expected = expect![[r#"
+            Some(
+                (
+                    "Create method format(String, int, String) in Test",
+                    [
+                        (
+                            "file:///a",
+                            "\npackage ch.emilycares;\npublic class Test {\n    public static void hello(String name) {\n        String out = format(name, 1, \"a\");\n    }\n\n    private static String format(String name, int arg1, String arg2) {\n        return null;\n    }\n}\n",
+                        ),
+                    ],
+                ),
+            )
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn create_method_other_class() {
+        let cont = r#"
+package ch.emilycares;
+import java.io.FileInputStream;
+public class Test {
+    public void hello(Other other, FileInputStream in) {
+        other.doThing(in, "a");
+    }
+}
+"#;
+        let out = create_method_edit(cont, AstPoint::new(5, 16));
+        let expected = expect![[r#"
+            Some(
+                (
+                    "Create method doThing(FileInputStream, String) in Other",
+                    [
+                        (
+                            "file:///project/src/main/java/ch/emilycares/Other.java",
+                            "\npackage ch.emilycares;\nimport java.io.FileInputStream;\n\npublic class Other {\n    public void existing(int a) {\n    }\n\n    public void doThing(FileInputStream in, String arg1) {\n    }\n}\n",
+                        ),
+                    ],
+                ),
+            )
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn create_method_static_return() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public int hello() {
+        return Other.count();
+    }
+}
+";
+        let out = create_method_edit(cont, AstPoint::new(4, 22));
+        let expected = expect![[r#"
+            Some(
+                (
+                    "Create method count() in Other",
+                    [
+                        (
+                            "file:///project/src/main/java/ch/emilycares/Other.java",
+                            "\npackage ch.emilycares;\n\npublic class Other {\n    public void existing(int a) {\n    }\n\n    public static int count() {\n        return 0;\n    }\n}\n",
+                        ),
+                    ],
+                ),
+            )
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn create_method_existing() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public void hello(Other other) {
+        other.existing(1);
+    }
+}
+";
+        let out = create_method_edit(cont, AstPoint::new(4, 16));
+        assert_eq!(out, None);
+    }
+}
@@ -0,0 +1,4180 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    path::Path,
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
+
+use ast::{
+    range::GetRange,
+    types::{
+        AstAnnotated, AstAvailability, AstBaseExpression, AstBlock, AstBlockBreak, AstBlockEntry,
+        AstBlockReturn, AstBlockVariable, AstClassBlock, AstClassConstructor, AstClassMethod,
+        AstClassVariable, AstExpression, AstExpressionIdentifier, AstExpressionKind,
+        AstExpressionOperator, AstExpressionOrDefault, AstExpressionOrValue, AstFile, AstFor,
+        AstForContent, AstIdentifier, AstIf, AstIfContent, AstImportUnit,
+        AstInterfaceMethodDefault, AstJType, AstJTypeKind, AstLambda, AstLambdaParameter,
+        AstLambdaRhs, AstMethodParameterFlags, AstMethodParameters, AstNewClass, AstNewRhs,
+        AstPackage, AstPoint, AstRange, AstSuperClass, AstSwitch, AstSwitchCaseArrowContent,
+        AstThing, AstTopLevel, AstValue, AstValueNuget, AstValues,
+    },
+    visit::{self, Visitor},
+};
+use call_chain::CallItem;
+use document::{Document, read_document_or_open_class};
+use dto::{Access, Class, ImportUnit, JType, Method, SourceDestination};
+use local_variable::{LocalVariable, VarFlags};
+use lsp_extra::{source_to_uri, to_lsp_range};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Command, DocumentChangeOperation,
+    DocumentChanges, OneOf, OptionalVersionedTextDocumentIdentifier, Range, RenameFile,
+    RenameFileOptions, ResourceOp, TextDocumentEdit, TextDocumentIdentifier,
+    TextDocumentPositionParams, TextEdit, Uri, WorkspaceEdit,
+};
+use my_string::{
+    MyString,
+    smol_str::{SmolStr, ToSmolStr, format_smolstr},
+};
+
+use crate::{
+    codeaction::{CodeActionContext, CodeActionError, import_text_edit},
+    exceptions::{self, ExceptionContext},
+    hover::jtype_hover_display,
+    name_index::NameIndex,
+    quickfix::jtype_class_paths,
+};
+
+/// Methods of `java.lang.Object` do not count towards the functional method
+const OBJECT_METHODS: [&str; 3] = ["equals", "hashCode", "toString"];
+
+pub fn lambda_to_anonymous_class(
+    document: &Document,
+    context: &CodeActionContext,
+) -> Result<Option<CodeActionOrCommand>, CodeActionError> {
+    let mut finder = LambdaFinder {
+        point: Some(context.point),
+        returns: Vec::new(),
+        found: Vec::new(),
+    };
+    finder.visit_file(&document.ast);
+    let Some((lambda, target)) = finder.found.pop() else {
+        return Ok(None);
+    };
+    let Some(interface) = lambda_target_type(
+        &document.ast,
+        context.vars,
+        context.imports,
+        context.class,
+        &context.class_map,
+        &target,
+    )?
+    else {
+        return Ok(None);
+    };
+    let Some(sam) = interface_method(&interface, context.imports, &context.class_map)? else {
+        return Ok(None);
+    };
+    if sam.parameters.len() != lambda.parameters.values.len() {
+        return Ok(None);
+    }
+
+    let indent = line_indent(document, lambda.range.start.line);
+    let parameters = sam
+        .parameters
+        .iter()
+        .zip(&lambda.parameters.values)
+        .map(|(p, l)| format!("{} {}", type_display(&p.jtype), l.name.value))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let throws = if sam.throws.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " throws {}",
+            sam.throws
+                .iter()
+                .map(type_display)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+    let body = match &lambda.rhs {
+        AstLambdaRhs::None => format!("{{\n{indent}    }}"),
+        // Block is already indented one level deeper than the lambda
+        AstLambdaRhs::Block(block) => document.range_text(&block.range).replace('\n', "\n    "),
+        AstLambdaRhs::Expr(expr) => {
+            let text = document.range_text(&expr.get_range());
+            if sam.ret == JType::Void {
+                format!("{{\n{indent}        {text};\n{indent}    }}")
+            } else {
+                format!("{{\n{indent}        return {text};\n{indent}    }}")
+            }
+        }
+    };
+    let name = sam.name.as_deref().unwrap_or_default();
+    let new_text = format!(
+        "new {}() {{\n{indent}    @Override\n{indent}    public {} {name}({parameters}){throws} {body}\n{indent}}}",
+        type_display(&interface),
+        type_display(&sam.ret),
+    );
+
+    let range = to_lsp_range(&lambda.range).map_err(CodeActionError::ToLspRange)?;
+    Ok(Some(edit_to_code_action(
+        context,
+        "Convert lambda to anonymous class",
+        CodeActionKind::REFACTOR_REWRITE,
+        vec![TextEdit { range, new_text }],
+    )))
+}
+
+/// Where the type of the lambda comes from
+enum LambdaTarget<'a> {
+    /// Declared type of a variable or the return type of a method
+    JType(&'a AstJType),
+    /// Argument at index of a method call
+    Argument {
+        method: &'a AstIdentifier,
+        index: usize,
+    },
+    /// Argument at index of a constructor call
+    Constructor {
+        jtype: &'a AstJType,
+        index: usize,
+        arguments: &'a [AstExpression],
+    },
+}
+
+/// Finds the lambdas that contain the point, the innermost one last. Without a point all lambdas
+/// are found.
+struct LambdaFinder<'a> {
+    point: Option<&'a AstPoint>,
+    /// Return type of the enclosing method. None inside of a lambda
+    returns: Vec<Option<&'a AstJType>>,
+    found: Vec<(&'a AstLambda, LambdaTarget<'a>)>,
+}
+
+impl<'a> LambdaFinder<'a> {
+    fn check(&mut self, expression: &'a [AstExpressionKind], target: LambdaTarget<'a>) {
+        if let [AstExpressionKind::Lambda(lambda)] = expression
+            && self
+                .point
+                .is_none_or(|point| lambda.range.is_in_range(point))
+        {
+            self.found.push((lambda, target));
+        }
+    }
+
+    fn check_arguments(
+        &mut self,
+        arguments: &'a [Vec<AstExpressionKind>],
+        target: impl Fn(usize) -> LambdaTarget<'a>,
+    ) {
+        for (index, argument) in arguments.iter().enumerate() {
+            self.check(argument, target(index));
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for LambdaFinder<'a> {
+    fn visit_class_variable(&mut self, variable: &'a AstClassVariable) {
+        if let Some(expression) = &variable.expression {
+            self.check(expression, LambdaTarget::JType(&variable.jtype));
+        }
+        visit::walk_class_variable(self, variable);
+    }
+
+    fn visit_block_variable(&mut self, variable: &'a AstBlockVariable) {
+        if let Some(value) = &variable.value
+            && variable.jtype.value != AstJTypeKind::Var
+        {
+            self.check(value, LambdaTarget::JType(&variable.jtype));
+        }
+        visit::walk_block_variable(self, variable);
+    }
+
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        self.returns.push(Some(&method.header.jtype));
+        visit::walk_class_method(self, method);
+        self.returns.pop();
+    }
+
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        self.returns.push(Some(&method.header.jtype));
+        visit::walk_interface_method_default(self, method);
+        self.returns.pop();
+    }
+
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        if let AstBlockEntry::Return(ret) = entry
+            && let AstExpressionOrValue::Expression(expression) = &ret.expression
+            && let Some(Some(jtype)) = self.returns.last()
+        {
+            self.check(expression, LambdaTarget::JType(jtype));
+        }
+        visit::walk_block_entry(self, entry);
+    }
+
+    fn visit_expression(&mut self, expression: &'a [AstExpressionKind]) {
+        let mut method = None;
+        for kind in expression {
+            if let AstExpressionKind::Base(base) = kind {
+                if let Some(AstExpressionIdentifier::Identifier(ident)) = &base.ident {
+                    method = Some(ident);
+                }
+                if let (Some(values), Some(method)) = (&base.values, method) {
+                    self.check_arguments(&values.values, |index| LambdaTarget::Argument {
+                        method,
+                        index,
+                    });
+                }
+                if base.values.is_some() || base.ident.is_none() {
+                    method = None;
+                }
+            } else {
+                method = None;
+            }
+        }
+        visit::walk_expression(self, expression);
+    }
+
+    fn visit_new_class(&mut self, new_class: &'a AstNewClass) {
+        if let AstNewRhs::Parameters(_, arguments)
+        | AstNewRhs::ParametersAndBlock(_, arguments, _) = &*new_class.rhs
+        {
+            self.check_arguments(arguments, |index| LambdaTarget::Constructor {
+                jtype: &new_class.jtype,
+                index,
+                arguments,
+            });
+        }
+        visit::walk_new_class(self, new_class);
+    }
+
+    fn visit_lambda(&mut self, lambda: &'a AstLambda) {
+        self.returns.push(None);
+        visit::walk_lambda(self, lambda);
+        self.returns.pop();
+    }
+}
+
+/// Parameters without a type of all lambdas with the type of the matching parameter of the
+/// functional method of their target type
+pub fn implicit_lambda_parameters<'a>(
+    ast: &'a AstFile,
+    vars: &[LocalVariable],
+    imports: &[ImportUnit],
+    class: &Class,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<(&'a AstLambdaParameter, JType)> {
+    let mut finder = LambdaFinder {
+        point: None,
+        returns: Vec::new(),
+        found: Vec::new(),
+    };
+    finder.visit_file(ast);
+    let mut out = vec![];
+    for (lambda, target) in finder.found {
+        let parameters = &lambda.parameters.values;
+        if parameters.iter().all(|p| p.jtype.is_some()) {
+            continue;
+        }
+        let Ok(Some(interface)) = lambda_target_type(ast, vars, imports, class, class_map, &target)
+        else {
+            continue;
+        };
+        let Ok(Some(sam)) = interface_method(&interface, imports, class_map) else {
+            continue;
+        };
+        if sam.parameters.len() != parameters.len() {
+            continue;
+        }
+        out.extend(
+            parameters
+                .iter()
+                .zip(sam.parameters)
+                .filter(|(l, _)| l.jtype.is_none() && l.name.value != "_")
+                // Type parameters that were not substituted tell nothing
+                .filter(|(_, p)| !matches!(p.jtype, JType::Parameter(_) | JType::Wildcard))
+                .map(|(l, p)| (l, p.jtype)),
+        );
+    }
+    out
+}
+
+/// The functional method of the interface with its type arguments substituted
+fn interface_method(
+    interface: &JType,
+    imports: &[ImportUnit],
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Result<Option<Method>, CodeActionError> {
+    let args = match interface {
+        JType::Generic(_, args) => args.as_slice(),
+        _ => &[],
+    };
+    let resolve_state = tyres::resolve_jtype_with_generic(interface, args, imports, class_map)
+        .map_err(CodeActionError::Tyres)?;
+    Ok(functional_method(&resolve_state.class.methods).cloned())
+}
+
+fn lambda_target_type(
+    ast: &AstFile,
+    vars: &[LocalVariable],
+    imports: &[ImportUnit],
+    class: &Class,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    target: &LambdaTarget,
+) -> Result<Option<JType>, CodeActionError> {
+    match target {
+        LambdaTarget::JType(jtype) => Ok(Some(JType::from(*jtype))),
+        LambdaTarget::Argument { method, index } => {
+            let call_chain = call_chain::get_call_chain(ast, &method.range.start);
+            let Some((CallItem::MethodCall { name, args, .. }, receiver)) = call_chain.split_last()
+            else {
+                return Ok(None);
+            };
+            let receiver = if receiver.is_empty() {
+                &[CallItem::This {
+                    range: method.range,
+                }][..]
+            } else {
+                receiver
+            };
+            let resolve_state =
+                tyres::resolve_call_chain_value(receiver, vars, imports, class, class_map)
+                    .map_err(CodeActionError::Tyres)?;
+            let arguments = tyres::resolve_arguments(args, vars, imports, class, class_map);
+            let methods =
+                tyres::select_methods(&resolve_state.class, Some(name), &arguments, class_map);
+            Ok(parameter_type(&methods, *index))
+        }
+        LambdaTarget::Constructor {
+            jtype,
+            index,
+            arguments,
+        } => {
+            let resolve_state = tyres::resolve_jtype(&JType::from(*jtype), imports, class_map)
+                .map_err(CodeActionError::Tyres)?;
+            let args = call_chain::argument_call_chains(arguments);
+            let arguments = tyres::resolve_arguments(&args, vars, imports, class, class_map);
+            let methods = tyres::select_methods(&resolve_state.class, None, &arguments, class_map);
+            Ok(parameter_type(&methods, *index))
+        }
+    }
+}
+
+/// Type of the parameter at index of the selected overload
+fn parameter_type(methods: &[&Method], index: usize) -> Option<JType> {
+    methods
+        .first()
+        .and_then(|m| m.parameters.get(index))
+        .map(|p| p.jtype.clone())
+}
+
+/// The single abstract method of a functional interface
+fn functional_method(methods: &[Method]) -> Option<&Method> {
+    let mut abstract_methods = methods.iter().filter(|m| {
+        m.access.contains(Access::Abstract)
+            && !m.access.contains(Access::Static)
+            && m.name
+                .as_ref()
+                .is_some_and(|n| !OBJECT_METHODS.contains(&n.as_str()))
+    });
+    let method = abstract_methods.next()?;
+    if abstract_methods.next().is_some() {
+        return None;
+    }
+    Some(method)
+}
+
+/// Wildcards are not allowed when instantiating a type
+#[must_use]
+pub fn type_display(jtype: &JType) -> String {
+    match jtype {
+        JType::Extends { base, extends } if **base == JType::Wildcard => type_display(extends),
+        JType::Generic(name, args) => jtype_hover_display(&JType::Generic(
+            name.clone(),
+            args.iter()
+                .map(|a| match a {
+                    JType::Extends { base, extends } if **base == JType::Wildcard => {
+                        (**extends).clone()
+                    }
+                    a => a.clone(),
+                })
+                .collect(),
+        )),
+        jtype => jtype_hover_display(jtype),
+    }
+}
+
+#[must_use]
+pub fn line_indent(document: &Document, line: usize) -> String {
+    document.rope.get_line(line).map_or_else(String::new, |l| {
+        l.chars().take_while(|c| *c == ' ' || *c == '\t').collect()
+    })
+}
+
+/// How the loop index is used to access the collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoopSource {
+    /// `arr[i]` bounded by `arr.length`
+    Array,
+    /// `list.get(i)` bounded by `list.size()`
+    List,
+}
+
+pub fn convert_for_loop(
+    document: &Document,
+    context: &CodeActionContext,
+) -> Result<Vec<CodeActionOrCommand>, CodeActionError> {
+    let mut finder = ForFinder {
+        point: context.point,
+        found: None,
+    };
+    finder.visit_file(&document.ast);
+    let Some(ast_for) = finder.found else {
+        return Ok(vec![]);
+    };
+    let Some((index, collection, source)) = indexed_loop(ast_for) else {
+        return Ok(vec![]);
+    };
+    let Some(content_start) = for_content_start(&ast_for.content) else {
+        return Ok(vec![]);
+    };
+
+    let mut uses = IndexUses {
+        index: &index.value,
+        collection: &collection.value,
+        source,
+        replacements: vec![],
+        names: vec![],
+        other_use: false,
+    };
+    match &ast_for.content {
+        AstForContent::None => (),
+        AstForContent::Block(block) => uses.visit_block(block),
+        AstForContent::BlockEntry(entry) => uses.visit_block_entry(entry),
+    }
+    if uses.other_use || uses.replacements.is_empty() {
+        return Ok(vec![]);
+    }
+    let Some(element) = element_name(&collection.value, &uses.names, context.vars) else {
+        return Ok(vec![]);
+    };
+    let element_type = element_type(&collection.value, source, context);
+
+    let mut out = vec![];
+    let mut edits = vec![TextEdit {
+        range: to_lsp_range(&AstRange {
+            start: ast_for.range.start,
+            end: content_start,
+        })
+        .map_err(CodeActionError::ToLspRange)?,
+        new_text: format!("for ({element_type} {element} : {}) ", collection.value),
+    }];
+    for range in &uses.replacements {
+        edits.push(TextEdit {
+            range: to_lsp_range(range).map_err(CodeActionError::ToLspRange)?,
+            new_text: element.clone(),
+        });
+    }
+    out.push(edit_to_code_action(
+        context,
+        "Convert to enhanced for loop",
+        CodeActionKind::REFACTOR_REWRITE,
+        edits,
+    ));
+
+    if let Some(pipeline) = stream_pipeline(document, ast_for, &uses.replacements, &element) {
+        let mut edits = vec![];
+        let stream = match source {
+            LoopSource::Array => {
+                if !tyres::is_imported_class_name("Arrays", context.imports, &context.class_map) {
+                    edits.extend(import_text_edit("java.util.Arrays", &document.ast));
+                }
+                format!("Arrays.stream({})", collection.value)
+            }
+            LoopSource::List => format!("{}.stream()", collection.value),
+        };
+        edits.push(TextEdit {
+            range: to_lsp_range(&ast_for.range).map_err(CodeActionError::ToLspRange)?,
+            new_text: format!("{stream}{pipeline};"),
+        });
+        out.push(edit_to_code_action(
+            context,
+            "Convert to stream",
+            CodeActionKind::REFACTOR_REWRITE,
+            edits,
+        ));
+    }
+
+    Ok(out)
+}
+
+/// Finds the innermost classic for loop with the point in its header
+struct ForFinder<'a> {
+    point: &'a AstPoint,
+    found: Option<&'a AstFor>,
+}
+
+impl<'a> Visitor<'a> for ForFinder<'a> {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        if let AstBlockEntry::For(ast_for) = entry
+            && let Some(content_start) = for_content_start(&ast_for.content)
+            && (AstRange {
+                start: ast_for.range.start,
+                end: content_start,
+            })
+            .is_in_range(self.point)
+        {
+            self.found = Some(ast_for);
+        }
+        visit::walk_block_entry(self, entry);
+    }
+}
+
+/// Collects the accesses `arr[i]` or `list.get(i)` in the loop body
+struct IndexUses<'a> {
+    index: &'a str,
+    collection: &'a str,
+    source: LoopSource,
+    replacements: Vec<AstRange>,
+    /// Names used in the body
+    names: Vec<&'a str>,
+    /// The index or collection is used in any other way
+    other_use: bool,
+}
+
+impl IndexUses<'_> {
+    /// Range of the access at the start of the expression and the count of parts it uses
+    fn access_at(&self, kinds: &[AstExpressionKind]) -> Option<(AstRange, usize)> {
+        let collection = expression_ident(kinds.first()?, self.collection)?;
+        match self.source {
+            LoopSource::Array => {
+                let AstExpressionKind::Base(AstBaseExpression {
+                    ident: Some(AstExpressionIdentifier::ArrayAccess { expr, range }),
+                    values: None,
+                    ..
+                }) = kinds.get(1)?
+                else {
+                    return None;
+                };
+                let [index] = expr.as_slice() else {
+                    return None;
+                };
+                expression_ident(index, self.index)?;
+                Some((
+                    AstRange {
+                        start: collection.range.start,
+                        end: range.end,
+                    },
+                    2,
+                ))
+            }
+            LoopSource::List => {
+                if !is_operator_dot(kinds.get(1)?) {
+                    return None;
+                }
+                expression_ident(kinds.get(2)?, "get")?;
+                let values = expression_values(kinds.get(3)?)?;
+                let [argument] = values.values.as_slice() else {
+                    return None;
+                };
+                let [index] = argument.as_slice() else {
+                    return None;
+                };
+                expression_ident(index, self.index)?;
+                Some((
+                    AstRange {
+                        start: collection.range.start,
+                        end: values.range.end,
+                    },
+                    4,
+                ))
+            }
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for IndexUses<'a> {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        if let AstBlockEntry::Assign(assign) = entry {
+            // Elements can not be assigned with a enhanced for loop
+            let before = self.replacements.len();
+            self.visit_expression(&assign.key);
+            if self.replacements.len() != before {
+                self.other_use = true;
+            }
+            self.visit_expression(&assign.expression);
+            return;
+        }
+        visit::walk_block_entry(self, entry);
+    }
+
+    fn visit_block_variable(&mut self, variable: &'a AstBlockVariable) {
+        self.names.push(&variable.name.value);
+        visit::walk_block_variable(self, variable);
+    }
+
+    fn visit_lambda(&mut self, lambda: &'a AstLambda) {
+        self.names.extend(
+            lambda
+                .parameters
+                .values
+                .iter()
+                .map(|p| p.name.value.as_str()),
+        );
+        visit::walk_lambda(self, lambda);
+    }
+
+    fn visit_expression(&mut self, expression: &'a [AstExpressionKind]) {
+        let mut i = 0;
+        while i < expression.len() {
+            if let Some((range, len)) = self.access_at(&expression[i..]) {
+                let prev = i.checked_sub(1).and_then(|p| expression.get(p));
+                let next = expression.get(i + len);
+                if prev.is_some_and(|p| is_operator_dot(p) || is_operator_increment(p))
+                    || next.is_some_and(is_operator_increment)
+                {
+                    self.other_use = true;
+                }
+                self.replacements.push(range);
+                i += len;
+                continue;
+            }
+            self.visit_expression_kind(&expression[i]);
+            i += 1;
+        }
+    }
+
+    fn visit_identifier(&mut self, ident: &'a AstIdentifier) {
+        if ident.value == self.index || ident.value == self.collection {
+            self.other_use = true;
+        }
+        self.names.push(&ident.value);
+    }
+}
+
+/// Matches `for (int i = 0; i < arr.length; i++)` and `for (int i = 0; i < list.size(); i++)`
+fn indexed_loop(ast_for: &AstFor) -> Option<(&AstIdentifier, &AstIdentifier, LoopSource)> {
+    if ast_for.label.is_some() {
+        return None;
+    }
+    let [AstBlockEntry::Variable(vars)] = ast_for.vars.as_slice() else {
+        return None;
+    };
+    let [var] = vars.as_slice() else {
+        return None;
+    };
+    if var.jtype.value != AstJTypeKind::Int {
+        return None;
+    }
+    let Some(
+        [
+            AstExpressionKind::Base(AstBaseExpression {
+                ident:
+                    Some(AstExpressionIdentifier::Value(AstValue::Nuget(AstValueNuget::Int(zero)))),
+                ..
+            }),
+        ],
+    ) = var.value.as_deref()
+    else {
+        return None;
+    };
+    if zero.value != "0" {
+        return None;
+    }
+    let index = &var.name;
+
+    let [AstBlockEntry::Expression(check)] = ast_for.check.as_slice() else {
+        return None;
+    };
+    let (collection, source) = match check.value.as_slice() {
+        [i, lt, collection, dot, length]
+            if is_operator_lt(lt) && expression_ident(length, "length").is_some() =>
+        {
+            expression_ident(i, &index.value)?;
+            if !is_operator_dot(dot) {
+                return None;
+            }
+            (expression_any_ident(collection)?, LoopSource::Array)
+        }
+        [i, lt, collection, dot, size, call]
+            if is_operator_lt(lt) && expression_ident(size, "size").is_some() =>
+        {
+            expression_ident(i, &index.value)?;
+            if !is_operator_dot(dot) || !expression_values(call)?.values.is_empty() {
+                return None;
+            }
+            (expression_any_ident(collection)?, LoopSource::List)
+        }
+        _ => return None,
+    };
+
+    let [AstBlockEntry::Expression(change)] = ast_for.changes.as_slice() else {
+        return None;
+    };
+    match change.value.as_slice() {
+        [i, plus_plus] | [plus_plus, i]
+            if is_operator_plus_plus(plus_plus) && expression_ident(i, &index.value).is_some() => {}
+        _ => return None,
+    }
+    Some((index, collection, source))
+}
+
+/// Builds `.filter(...).map(...).forEach(...)` for a body with a single statement
+fn stream_pipeline(
+    document: &Document,
+    ast_for: &AstFor,
+    replacements: &[AstRange],
+    element: &str,
+) -> Option<String> {
+    let entry = single_statement_for(&ast_for.content)?;
+    let (filter, entry) = match entry {
+        AstBlockEntry::If(AstIf::If {
+            control, content, ..
+        }) => {
+            let entry = match content {
+                AstIfContent::Block(block) => single_statement(block)?,
+                AstIfContent::BlockEntry(entry) => entry,
+            };
+            let control = text_with_replacements(
+                document,
+                &control.as_slice().get_range(),
+                replacements,
+                element,
+            );
+            (Some(format!(".filter({element} -> {control})")), entry)
+        }
+        entry => (None, entry),
+    };
+    let AstBlockEntry::Expression(statement) = entry else {
+        return None;
+    };
+    // Only method calls
+    expression_values(statement.value.last()?)?;
+
+    if let [target, dot, add, call] = statement.value.as_slice()
+        && let Some(target) = expression_any_ident(target)
+        && is_operator_dot(dot)
+        && expression_ident(add, "add").is_some()
+        && let Some(values) = expression_values(call)
+        && let [argument] = values.values.as_slice()
+    {
+        let argument = text_with_replacements(
+            document,
+            &argument.as_slice().get_range(),
+            replacements,
+            element,
+        );
+        let map = if argument == element {
+            String::new()
+        } else {
+            format!(".map({element} -> {argument})")
+        };
+        return Some(format!(
+            "{}{map}.forEach({}::add)",
+            filter.unwrap_or_default(),
+            target.value
+        ));
+    }
+
+    // A plain forEach is not a transform or filter
+    let filter = filter?;
+    let statement = text_with_replacements(
+        document,
+        &statement.value.as_slice().get_range(),
+        replacements,
+        element,
+    );
+    Some(format!("{filter}.forEach({element} -> {statement})"))
+}
+
+fn single_statement_for(content: &AstForContent) -> Option<&AstBlockEntry> {
+    match content {
+        AstForContent::None => None,
+        AstForContent::Block(block) => single_statement(block),
+        AstForContent::BlockEntry(entry) => Some(entry),
+    }
+}
+
+fn single_statement(block: &AstBlock) -> Option<&AstBlockEntry> {
+    match block.entries.as_slice() {
+        [entry] => Some(entry),
+        _ => None,
+    }
+}
+
+fn for_content_start(content: &AstForContent) -> Option<AstPoint> {
+    match content {
+        AstForContent::None => None,
+        AstForContent::Block(block) => Some(block.range.start),
+        AstForContent::BlockEntry(entry) => Some(entry.get_range().start),
+    }
+}
+
+/// Name for the loop element derived from the collection name
+fn element_name(collection: &str, names: &[&str], vars: &[LocalVariable]) -> Option<String> {
+    let singular = collection
+        .strip_suffix("ies")
+        .map(|s| format!("{s}y"))
+        .or_else(|| collection.strip_suffix("List").map(ToOwned::to_owned))
+        .or_else(|| collection.strip_suffix('s').map(ToOwned::to_owned))
+        .unwrap_or_default();
+    [singular.as_str(), "item", "element"]
+        .into_iter()
+        .find(|name| {
+            !name.is_empty()
+                && *name != collection
+                && !names.contains(name)
+                && !vars.iter().any(|v| v.name == *name)
+        })
+        .map(ToOwned::to_owned)
+}
+
+fn element_type(collection: &str, source: LoopSource, context: &CodeActionContext) -> String {
+    let jtype = context
+        .vars
+        .iter()
+        .rev()
+        .find(|v| v.name == collection)
+        .map(|v| &v.jtype)
+        .or_else(|| {
+            context
+                .class
+                .fields
+                .iter()
+                .find(|f| f.name == collection)
+                .map(|f| &f.jtype)
+        });
+    match (source, jtype) {
+        (LoopSource::Array, Some(JType::Array(inner))) => type_display(inner),
+        (LoopSource::List, Some(JType::Generic(_, args))) if args.len() == 1 => {
+            type_display(&args[0])
+        }
+        _ => "var".to_owned(),
+    }
+}
+
+/// Source text of the range with each of the replacements swapped for `with`
+fn text_with_replacements(
+    document: &Document,
+    range: &AstRange,
+    replacements: &[AstRange],
+    with: &str,
+) -> String {
+    let mut pos = document.point_to_char(&range.start);
+    let end = document.point_to_char(&range.end);
+    let mut out = String::new();
+    for replacement in replacements.iter().filter(|r| r.is_contained_in(range)) {
+        let start = document.point_to_char(&replacement.start);
+        if start < pos {
+            continue;
+        }
+        out.push_str(&document.rope.slice(pos..start).to_string());
+        out.push_str(with);
+        pos = document.point_to_char(&replacement.end);
+    }
+    if pos < end {
+        out.push_str(&document.rope.slice(pos..end).to_string());
+    }
+    out
+}
+
+const fn expression_any_ident(kind: &AstExpressionKind) -> Option<&AstIdentifier> {
+    match kind {
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: Some(AstExpressionIdentifier::Identifier(ident)),
+            values: None,
+            ..
+        }) => Some(ident),
+        _ => None,
+    }
+}
+
+fn expression_ident<'a>(kind: &'a AstExpressionKind, name: &str) -> Option<&'a AstIdentifier> {
+    expression_any_ident(kind).filter(|i| i.value == name)
+}
+
+const fn expression_values(kind: &AstExpressionKind) -> Option<&AstValues> {
+    match kind {
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: None,
+            values: Some(values),
+            ..
+        }) => Some(values),
+        _ => None,
+    }
+}
+
+const fn is_operator_dot(kind: &AstExpressionKind) -> bool {
+    matches!(
+        kind,
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: None,
+            operator: AstExpressionOperator::Dot(_),
+            ..
+        })
+    )
+}
+
+const fn is_operator_lt(kind: &AstExpressionKind) -> bool {
+    matches!(
+        kind,
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: None,
+            operator: AstExpressionOperator::Lt(_),
+            ..
+        })
+    )
+}
+
+const fn is_operator_plus_plus(kind: &AstExpressionKind) -> bool {
+    matches!(
+        kind,
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: None,
+            operator: AstExpressionOperator::PlusPlus(_),
+            ..
+        })
+    )
+}
+
+const fn is_operator_increment(kind: &AstExpressionKind) -> bool {
+    matches!(
+        kind,
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: None,
+            operator: AstExpressionOperator::PlusPlus(_) | AstExpressionOperator::MinusMinus(_),
+            ..
+        })
+    )
+}
+
+pub(crate) fn edit_to_code_action(
+    context: &CodeActionContext,
+    title: &str,
+    kind: CodeActionKind,
+    edits: Vec<TextEdit>,
+) -> CodeActionOrCommand {
+    // Required by lsp types
+    #[allow(clippy::mutable_key_type)]
+    let mut changes = HashMap::new();
+    changes.insert(context.current_file.to_owned(), edits);
+    CodeActionOrCommand::CodeAction(CodeAction {
+        kind: Some(kind),
+        title: title.to_owned(),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+/// Replace `var` of the local variable under the point with its inferred type, or its type
+/// with `var` when the initializer has exactly that type
+pub fn convert_var(
+    document: &Document,
+    context: &CodeActionContext,
+    var_available: bool,
+) -> Result<Vec<CodeActionOrCommand>, CodeActionError> {
+    let mut finder = VarFinder {
+        point: context.point,
+        declarators: 1,
+        found: None,
+    };
+    finder.visit_file(&document.ast);
+    let Some((variable, declarators)) = finder.found else {
+        return Ok(vec![]);
+    };
+    let range = to_lsp_range(&variable.jtype.range).map_err(CodeActionError::ToLspRange)?;
+    if matches!(variable.jtype.value, AstJTypeKind::Var) {
+        let Some(local) = context
+            .vars
+            .iter()
+            .find(|v| v.range == variable.range && v.flags.contains(VarFlags::Computed))
+        else {
+            return Ok(vec![]);
+        };
+        if matches!(local.jtype, JType::Var | JType::Void) {
+            return Ok(vec![]);
+        }
+        let mut class_paths = vec![];
+        jtype_class_paths(&local.jtype, &mut class_paths);
+        // Nested classes are written with the name of the outer class
+        let class_paths: Vec<MyString> = class_paths
+            .iter()
+            .map(|c| c.split('$').next().unwrap_or(c).to_smolstr())
+            .collect();
+        let mut edits = vec![TextEdit {
+            range,
+            new_text: type_display(&local.jtype),
+        }];
+        edits.extend(import_edits(document, context, &class_paths));
+        return Ok(vec![edit_to_code_action(
+            context,
+            "Replace var with explicit type",
+            CodeActionKind::REFACTOR_REWRITE,
+            edits,
+        )]);
+    }
+    // `var` declares one variable with an initializer that is not a lambda or an array initializer
+    if !var_available || declarators != 1 {
+        return Ok(vec![]);
+    }
+    let Some(value) = &variable.value else {
+        return Ok(vec![]);
+    };
+    if matches!(
+        value.as_slice(),
+        [AstExpressionKind::Lambda(_) | AstExpressionKind::Array(_)]
+    ) {
+        return Ok(vec![]);
+    }
+    let mut point = value.get_range().end;
+    point.col += 1;
+    let mut call_chain = vec![];
+    call_chain::cc_expr(value, &point, false, &mut call_chain);
+    // Numbers and booleans resolve as their boxed class
+    if let [CallItem::Class { name, range }] = call_chain.as_slice()
+        && document.range_text(range) != *name
+        && name != "String"
+    {
+        return Ok(vec![]);
+    }
+    let Ok(resolved) = tyres::resolve_call_chain_value(
+        &call_chain,
+        context.vars,
+        context.imports,
+        context.class,
+        &context.class_map,
+    ) else {
+        return Ok(vec![]);
+    };
+    if type_display(&resolved.jtype) != type_display(&JType::from(&variable.jtype)) {
+        return Ok(vec![]);
+    }
+    Ok(vec![edit_to_code_action(
+        context,
+        "Replace with var",
+        CodeActionKind::REFACTOR_REWRITE,
+        vec![TextEdit {
+            range,
+            new_text: "var".to_string(),
+        }],
+    )])
+}
+
+/// Local variable whose type or name is under the point, with the count of the variables that
+/// its statement declares
+struct VarFinder<'a, 'b> {
+    point: &'b AstPoint,
+    declarators: usize,
+    found: Option<(&'a AstBlockVariable, usize)>,
+}
+
+impl<'a> Visitor<'a> for VarFinder<'a, '_> {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        self.declarators = match entry {
+            AstBlockEntry::Variable(variables) => variables.len(),
+            _ => 1,
+        };
+        visit::walk_block_entry(self, entry);
+    }
+
+    fn visit_block_variable(&mut self, variable: &'a AstBlockVariable) {
+        let declaration = AstRange {
+            start: variable.range.start,
+            end: variable.name.range.end,
+        };
+        if declaration.is_in_range(self.point) {
+            self.found = Some((variable, self.declarators));
+        }
+        visit::walk_block_variable(self, variable);
+    }
+}
+
+/// Split the initializer of the local variable under the point into an assignment, or join its
+/// declaration with the assignment that directly follows it
+pub fn split_join_declaration(
+    document: &Document,
+    context: &CodeActionContext,
+) -> Result<Vec<CodeActionOrCommand>, CodeActionError> {
+    let mut finder = DeclarationStatementFinder {
+        point: context.point,
+        resources: vec![],
+        found: None,
+    };
+    finder.visit_file(&document.ast);
+    let Some((entries, index)) = finder.found else {
+        return Ok(vec![]);
+    };
+    let Some(AstBlockEntry::Variable(variables)) = entries.get(index) else {
+        return Ok(vec![]);
+    };
+    let [variable] = variables.as_slice() else {
+        return Ok(vec![]);
+    };
+    let name = &variable.name.value;
+    if let Some(value) = &variable.value {
+        // `var` needs the initializer and an array initializer can not be assigned
+        if matches!(variable.jtype.value, AstJTypeKind::Var)
+            || matches!(value.as_slice(), [AstExpressionKind::Array(_)])
+        {
+            return Ok(vec![]);
+        }
+        let indent = line_indent(document, variable.range.start.line);
+        let range = to_lsp_range(&AstRange {
+            start: variable.name.range.end,
+            end: value.get_range().start,
+        })
+        .map_err(CodeActionError::ToLspRange)?;
+        return Ok(vec![edit_to_code_action(
+            context,
+            "Split declaration and assignment",
+            CodeActionKind::REFACTOR_REWRITE,
+            vec![TextEdit {
+                range,
+                new_text: format!(";\n{indent}{name} = "),
+            }],
+        )]);
+    }
+    let Some(AstBlockEntry::Assign(assign)) = entries.get(index + 1) else {
+        return Ok(vec![]);
+    };
+    if document.range_text(&assign.key.get_range()) != *name {
+        return Ok(vec![]);
+    }
+    // Nothing but the semicolon, a comment in between would be lost
+    let between = document.range_text(&AstRange {
+        start: variable.name.range.end,
+        end: assign.range.start,
+    });
+    if !between.chars().all(|c| c.is_whitespace() || c == ';') {
+        return Ok(vec![]);
+    }
+    let range = to_lsp_range(&AstRange {
+        start: variable.name.range.end,
+        end: assign.expression.get_range().start,
+    })
+    .map_err(CodeActionError::ToLspRange)?;
+    Ok(vec![edit_to_code_action(
+        context,
+        "Join declaration and assignment",
+        CodeActionKind::REFACTOR_REWRITE,
+        vec![TextEdit {
+            range,
+            new_text: " = ".to_string(),
+        }],
+    )])
+}
+
+/// Statements of the block with the index of the local variable declaration under the point.
+/// Resources of a try are declared in a block too, they can not be split.
+struct DeclarationStatementFinder<'a, 'b> {
+    point: &'b AstPoint,
+    resources: Vec<&'a AstBlock>,
+    found: Option<(&'a [AstBlockEntry], usize)>,
+}
+
+impl<'a> Visitor<'a> for DeclarationStatementFinder<'a, '_> {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        if let AstBlockEntry::TryCatch(try_catch) = entry
+            && let Some(resources) = &try_catch.resources_block
+        {
+            self.resources.push(resources);
+        }
+        visit::walk_block_entry(self, entry);
+    }
+
+    fn visit_block(&mut self, block: &'a AstBlock) {
+        if !self.resources.iter().any(|r| std::ptr::eq(*r, block)) {
+            let declaration = block.entries.iter().position(|entry| {
+                let AstBlockEntry::Variable(variables) = entry else {
+                    return false;
+                };
+                variables.iter().any(|v| {
+                    AstRange {
+                        start: v.range.start,
+                        end: v.name.range.end,
+                    }
+                    .is_in_range(self.point)
+                })
+            });
+            if let Some(index) = declaration {
+                self.found = Some((&block.entries, index));
+            }
+        }
+        visit::walk_block(self, block);
+    }
+}
+
+/// Negate the condition of the if whose header contains the point and, when it is followed by an
+/// else, swap the branches
+pub fn invert_if(
+    document: &Document,
+    context: &CodeActionContext,
+) -> Result<Vec<CodeActionOrCommand>, CodeActionError> {
+    let mut finder = IfFinder {
+        point: context.point,
+        found: None,
+    };
+    finder.visit_file(&document.ast);
+    let Some((entries, index)) = finder.found else {
+        return Ok(vec![]);
+    };
+    let Some(AstBlockEntry::If(
+        AstIf::If {
+            control, content, ..
+        }
+        | AstIf::ElseIf {
+            control, content, ..
+        },
+    )) = entries.get(index)
+    else {
+        return Ok(vec![]);
+    };
+    let new_text = match control.as_slice() {
+        // The parentheses of `!(a < 3)` are not needed around the whole condition
+        [
+            not,
+            AstExpressionKind::Base(AstBaseExpression {
+                ident: None,
+                values: Some(values),
+                ..
+            }),
+        ] if matches!(
+            operator(not),
+            Some(AstExpressionOperator::ExclamationMark(_))
+        ) && values.values.len() == 1 =>
+        {
+            document.range_text(&values.values[0].get_range())
+        }
+        _ => negate(document, control),
+    };
+    let control_edit = TextEdit {
+        range: to_lsp_range(&control.get_range()).map_err(CodeActionError::ToLspRange)?,
+        new_text,
+    };
+    let mut out = vec![edit_to_code_action(
+        context,
+        "Negate condition",
+        CodeActionKind::REFACTOR_REWRITE,
+        vec![control_edit.clone()],
+    )];
+    if let Some(AstBlockEntry::If(AstIf::Else {
+        content: else_content,
+        ..
+    })) = entries.get(index + 1)
+    {
+        let content_range = (&content).get_range();
+        let else_range = (&else_content).get_range();
+        out.push(edit_to_code_action(
+            context,
+            "Invert if-else",
+            CodeActionKind::REFACTOR_REWRITE,
+            vec![
+                control_edit,
+                TextEdit {
+                    range: to_lsp_range(&content_range).map_err(CodeActionError::ToLspRange)?,
+                    new_text: document.range_text(&else_range),
+                },
+                TextEdit {
+                    range: to_lsp_range(&else_range).map_err(CodeActionError::ToLspRange)?,
+                    new_text: document.range_text(&content_range),
+                },
+            ],
+        ));
+    }
+    Ok(out)
+}
+
+/// The negated condition, `!` is pushed through `&&` and `||` and comparisons are flipped
+fn negate(document: &Document, expression: &[AstExpressionKind]) -> String {
+    let text = |e: &[AstExpressionKind]| document.range_text(&e.get_range());
+    let operators: Vec<(usize, &AstExpressionOperator)> = expression
+        .iter()
+        .enumerate()
+        .filter_map(|(i, e)| operator(e).map(|o| (i, o)))
+        .collect();
+    let has = |f: fn(&AstExpressionOperator) -> bool| operators.iter().any(|(_, o)| f(o));
+    let split = |f: fn(&AstExpressionOperator) -> bool| {
+        let mut parts = vec![];
+        let mut start = 0;
+        for (i, o) in &operators {
+            if f(o) {
+                parts.push(&expression[start..*i]);
+                start = i + 1;
+            }
+        }
+        parts.push(&expression[start..]);
+        parts
+    };
+
+    if has(|o| {
+        matches!(
+            o,
+            AstExpressionOperator::QuestionMark(_)
+                | AstExpressionOperator::Colon(_)
+                | AstExpressionOperator::Assign(_)
+                | AstExpressionOperator::PlusEqual(_)
+                | AstExpressionOperator::MinusEqual(_)
+                | AstExpressionOperator::MultiplyEqual(_)
+                | AstExpressionOperator::DivideEqual(_)
+                | AstExpressionOperator::ModuloEqual(_)
+                | AstExpressionOperator::VerticalBarEqual(_)
+        )
+    }) {
+        return format!("!({})", text(expression));
+    }
+    let is_or =
+        |o: &AstExpressionOperator| matches!(o, AstExpressionOperator::VerticalBarVerticalBar(_));
+    let is_and =
+        |o: &AstExpressionOperator| matches!(o, AstExpressionOperator::AmpersandAmpersand(_));
+    if has(is_or) {
+        return split(is_or)
+            .into_iter()
+            .map(|part| {
+                let negated = negate(document, part);
+                // `&&` binds stronger than the `||` of the negation
+                if part.iter().filter_map(operator).any(is_and) {
+                    format!("({negated})")
+                } else {
+                    negated
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" && ");
+    }
+    if has(is_and) {
+        return split(is_and)
+            .into_iter()
+            .map(|part| negate(document, part))
+            .collect::<Vec<_>>()
+            .join(" || ");
+    }
+    if has(|o| {
+        matches!(
+            o,
+            AstExpressionOperator::Ampersand(_)
+                | AstExpressionOperator::VerticalBar(_)
+                | AstExpressionOperator::Caret(_)
+        )
+    }) || expression.iter().any(|e| {
+        !matches!(
+            e,
+            AstExpressionKind::Base(_)
+                | AstExpressionKind::NewClass(_)
+                | AstExpressionKind::Generics(_)
+        )
+    }) {
+        return format!("!({})", text(expression));
+    }
+    let comparisons: Vec<(usize, &str)> = operators
+        .iter()
+        .filter_map(|(i, o)| {
+            let flipped = match o {
+                AstExpressionOperator::Equal(_) => "!=",
+                AstExpressionOperator::NotEqual(_) => "==",
+                AstExpressionOperator::Lt(_) => ">=",
+                AstExpressionOperator::Le(_) => ">",
+                AstExpressionOperator::Gt(_) => "<=",
+                AstExpressionOperator::Ge(_) => "<",
+                _ => return None,
+            };
+            Some((*i, flipped))
+        })
+        .collect();
+    match comparisons.as_slice() {
+        [(i, flipped)] => {
+            let range = expression[*i].get_range();
+            let all = expression.get_range();
+            return format!(
+                "{}{flipped}{}",
+                document.range_text(&AstRange {
+                    start: all.start,
+                    end: range.start,
+                }),
+                document.range_text(&AstRange {
+                    start: range.end,
+                    end: all.end,
+                })
+            );
+        }
+        [] => (),
+        _ => return format!("!({})", text(expression)),
+    }
+    let is_access = |o: &AstExpressionOperator| {
+        matches!(
+            o,
+            AstExpressionOperator::Dot(_) | AstExpressionOperator::ColonColon(_)
+        )
+    };
+    if let [(0, AstExpressionOperator::ExclamationMark(_)), rest @ ..] = operators.as_slice()
+        && rest.iter().all(|(_, o)| is_access(o))
+    {
+        return text(&expression[1..]);
+    }
+    if operators.iter().all(|(_, o)| is_access(o)) {
+        if let [
+            AstExpressionKind::Base(AstBaseExpression {
+                ident: None,
+                values: Some(values),
+                ..
+            }),
+        ] = expression
+            && let [inner] = values.values.as_slice()
+        {
+            return format!("({})", negate(document, inner));
+        }
+        if let [
+            AstExpressionKind::Base(AstBaseExpression {
+                ident:
+                    Some(
+                        AstExpressionIdentifier::Nuget(AstValueNuget::BooleanLiteral(b))
+                        | AstExpressionIdentifier::Value(AstValue::Nuget(
+                            AstValueNuget::BooleanLiteral(b),
+                        )),
+                    ),
+                ..
+            }),
+        ] = expression
+        {
+            return (!b.value).to_string();
+        }
+        return format!("!{}", text(expression));
+    }
+    format!("!({})", text(expression))
+}
+
+/// The operator of an expression part that is nothing else
+const fn operator(kind: &AstExpressionKind) -> Option<&AstExpressionOperator> {
+    match kind {
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: None,
+            values: None,
+            operator,
+            ..
+        }) if !matches!(operator, AstExpressionOperator::None) => Some(operator),
+        _ => None,
+    }
+}
+
+/// Statements of the block with the index of the if whose header contains the point
+struct IfFinder<'a, 'b> {
+    point: &'b AstPoint,
+    found: Option<(&'a [AstBlockEntry], usize)>,
+}
+
+impl<'a> Visitor<'a> for IfFinder<'a, '_> {
+    fn visit_block(&mut self, block: &'a AstBlock) {
+        let header = block.entries.iter().position(|entry| match entry {
+            AstBlockEntry::If(
+                AstIf::If {
+                    range,
+                    control_range,
+                    ..
+                }
+                | AstIf::ElseIf {
+                    range,
+                    control_range,
+                    ..
+                },
+            ) => AstRange {
+                start: range.start,
+                end: control_range.end,
+            }
+            .is_in_range(self.point),
+            _ => false,
+        });
+        if let Some(index) = header {
+            self.found = Some((&block.entries, index));
+        }
+        visit::walk_block(self, block);
+    }
+}
+
+pub fn surround_with(
+    document: &Document,
+    context: &CodeActionContext,
+    selection: &AstRange,
+) -> Result<Vec<CodeActionOrCommand>, CodeActionError> {
+    // Without a selection it is not clear what should be surrounded
+    if selection.start == selection.end {
+        return Ok(vec![]);
+    }
+    let Some(entries) = selected_statements(&document.ast, selection) else {
+        return Ok(vec![]);
+    };
+
+    let index = ["i", "j", "k", "index"]
+        .into_iter()
+        .find(|name| !context.vars.iter().any(|v| v.name == *name))
+        .unwrap_or("index");
+    let mut out = vec![edit_to_code_action(
+        context,
+        "Surround with try/catch",
+        CodeActionKind::REFACTOR,
+        surround_try_catch(document, context, entries)?,
+    )];
+    for (title, header) in [
+        ("Surround with if", "if (true)".to_owned()),
+        (
+            "Surround with synchronized",
+            "synchronized (this)".to_owned(),
+        ),
+        (
+            "Surround with for",
+            format!("for (int {index} = 0; {index} < 10; {index}++)"),
+        ),
+    ] {
+        let (range, new_text) = surround_text(document, entries, &header, "");
+        out.push(edit_to_code_action(
+            context,
+            title,
+            CodeActionKind::REFACTOR,
+            vec![TextEdit {
+                range: to_lsp_range(&range).map_err(CodeActionError::ToLspRange)?,
+                new_text,
+            }],
+        ));
+    }
+    Ok(out)
+}
+
+/// Wrap the statements in a try block with a catch clause for each checked exception
+pub fn surround_try_catch(
+    document: &Document,
+    context: &CodeActionContext,
+    entries: &[AstBlockEntry],
+) -> Result<Vec<TextEdit>, CodeActionError> {
+    let thrown = exceptions::thrown_exceptions(
+        entries,
+        &ExceptionContext {
+            ast: &document.ast,
+            imports: context.imports,
+            class_map: &context.class_map,
+            class: context.class,
+            vars: context.vars,
+        },
+    );
+    let mut exceptions = exceptions::distinct_exceptions(&thrown, &context.class_map);
+    if exceptions.is_empty() {
+        exceptions.push(SmolStr::new_static("java.lang.Exception"));
+    }
+    let name = ["e", "ex", "exception"]
+        .into_iter()
+        .find(|name| !context.vars.iter().any(|v| v.name == *name))
+        .unwrap_or("exception");
+    let Some(first) = entries.first() else {
+        return Ok(vec![]);
+    };
+    let indent = line_indent(document, first.get_range().start.line);
+    let mut catches = String::new();
+    for exception in &exceptions {
+        let _ = write!(
+            catches,
+            " catch ({} {name}) {{\n{indent}    throw new RuntimeException({name});\n{indent}}}",
+            class_name(exception)
+        );
+    }
+    let (range, new_text) = surround_text(document, entries, "try", &catches);
+    let mut edits = import_edits(document, context, &exceptions);
+    edits.push(TextEdit {
+        range: to_lsp_range(&range).map_err(CodeActionError::ToLspRange)?,
+        new_text,
+    });
+    Ok(edits)
+}
+
+/// Range of the statements and the text to replace it with
+fn surround_text(
+    document: &Document,
+    entries: &[AstBlockEntry],
+    header: &str,
+    footer: &str,
+) -> (AstRange, String) {
+    let range = statement_range(document, entries.get_range());
+    let indent = line_indent(document, range.start.line);
+    let body = document.range_text(&range).replace('\n', "\n    ");
+    (
+        range,
+        format!("{header} {{\n{indent}    {body}\n{indent}}}{footer}"),
+    )
+}
+
+/// The statements in the innermost block that overlap with the selection
+#[must_use]
+pub fn selected_statements<'a>(
+    ast: &'a AstFile,
+    selection: &AstRange,
+) -> Option<&'a [AstBlockEntry]> {
+    let mut finder = StatementFinder {
+        selection,
+        found: None,
+    };
+    finder.visit_file(ast);
+    finder.found
+}
+
+struct StatementFinder<'a, 'b> {
+    selection: &'b AstRange,
+    found: Option<&'a [AstBlockEntry]>,
+}
+
+impl<'a> Visitor<'a> for StatementFinder<'a, '_> {
+    fn visit_block(&mut self, block: &'a AstBlock) {
+        if self.selection.is_contained_in(&block.range) {
+            let overlaps = |e: &AstBlockEntry| {
+                let range = e.get_range();
+                range.start < self.selection.end && self.selection.start < range.end
+            };
+            if let Some(first) = block.entries.iter().position(overlaps)
+                && let Some(last) = block.entries.iter().rposition(overlaps)
+            {
+                self.found = Some(&block.entries[first..=last]);
+            }
+        }
+        visit::walk_block(self, block);
+    }
+}
+
+/// Imports for class paths that are not imported yet
+#[must_use]
+pub fn import_edits(
+    document: &Document,
+    context: &CodeActionContext,
+    class_paths: &[MyString],
+) -> Vec<TextEdit> {
+    class_paths
+        .iter()
+        .filter(|c| c.contains('.') && !c.starts_with("java.lang."))
+        .filter(|c| {
+            tyres::is_imported(class_name(c), context.imports, &context.class_map).is_none()
+        })
+        .flat_map(|c| import_text_edit(c, &document.ast))
+        .collect()
+}
+
+#[must_use]
+pub fn class_name(class_path: &str) -> &str {
+    class_path
+        .rsplit_once('.')
+        .map_or(class_path, |(_, name)| name)
+}
+
+/// Rewrite the switch whose header contains the point between the classic colon form,
+/// the arrow form and a switch expression
+pub fn convert_switch(
+    document: &Document,
+    context: &CodeActionContext,
+) -> Result<Vec<CodeActionOrCommand>, CodeActionError> {
+    let mut finder = SwitchFinder {
+        point: context.point,
+        statements: vec![],
+        found: None,
+    };
+    finder.visit_file(&document.ast);
+    let Some((statement, switch, inline)) = finder.found else {
+        return Ok(vec![]);
+    };
+    let indent = line_indent(document, statement.get_range().start.line);
+    let case_indent = switch.block.entries.first().map_or_else(
+        || format!("{indent}    "),
+        |e| line_indent(document, e.get_range().start.line),
+    );
+    let header = format!(
+        "switch ({}) {{",
+        document.range_text(&switch.check.get_range())
+    );
+    let layout = SwitchLayout {
+        document,
+        indent: &indent,
+        case_indent: &case_indent,
+        header: &header,
+    };
+
+    let mut out = vec![];
+    let range = statement_range(document, statement.get_range());
+    let mut push = |title: &str, new_text: String| -> Result<(), CodeActionError> {
+        out.push(edit_to_code_action(
+            context,
+            title,
+            CodeActionKind::REFACTOR_REWRITE,
+            vec![TextEdit {
+                range: to_lsp_range(&range).map_err(CodeActionError::ToLspRange)?,
+                new_text,
+            }],
+        ));
+        Ok(())
+    };
+    if inline {
+        if let Some(new_text) = layout.expression_to_statement(statement, switch) {
+            push("Convert to switch statement", new_text)?;
+        }
+    } else if let Some(groups) = colon_groups(&switch.block) {
+        if let Some(new_text) = layout.colon_to_expression(&groups) {
+            push("Convert to switch expression", new_text)?;
+        }
+        push("Convert to arrow switch", layout.colon_to_arrow(&groups))?;
+    } else if let Some(new_text) = layout.arrow_to_colon(&switch.block) {
+        push("Convert to classic switch", new_text)?;
+    }
+    Ok(out)
+}
+
+/// The labels of a classic switch case and the statements until the next case.
+/// A label of `None` is the default case.
+struct ColonGroup<'a> {
+    labels: Vec<Option<&'a AstExpression>>,
+    body: &'a [AstBlockEntry],
+}
+
+impl<'a> ColonGroup<'a> {
+    /// The statements without the break at the end
+    fn statements(&self) -> &'a [AstBlockEntry] {
+        match self.body {
+            [
+                rest @ ..,
+                AstBlockEntry::Break(AstBlockBreak { label: None, .. }),
+            ] => rest,
+            body => body,
+        }
+    }
+}
+
+/// Group the entries of a classic switch. Falling through to the next case is not supported.
+fn colon_groups(block: &AstBlock) -> Option<Vec<ColonGroup<'_>>> {
+    let mut bounds: Vec<(Vec<Option<&AstExpression>>, usize, usize)> = vec![];
+    for (i, entry) in block.entries.iter().enumerate() {
+        let labels: Vec<_> = match entry {
+            AstBlockEntry::SwitchCase(case) => case
+                .expressions
+                .iter()
+                .map(|e| match e {
+                    AstExpressionOrDefault::Default => None,
+                    AstExpressionOrDefault::Expression(e) => Some(e),
+                })
+                .collect(),
+            AstBlockEntry::SwitchDefault(_) => vec![None],
+            AstBlockEntry::SwitchCaseArrowValues(_)
+            | AstBlockEntry::SwitchCaseArrowType(_)
+            | AstBlockEntry::SwitchCaseArrowDefault(_) => return None,
+            _ => {
+                let (_, _, end) = bounds.last_mut()?;
+                *end = i + 1;
+                continue;
+            }
+        };
+        match bounds.last_mut() {
+            Some((group_labels, start, end)) if start == end => {
+                group_labels.extend(labels);
+                *start = i + 1;
+                *end = i + 1;
+            }
+            _ => bounds.push((labels, i + 1, i + 1)),
+        }
+    }
+    let groups: Vec<_> = bounds
+        .into_iter()
+        .map(|(labels, start, end)| ColonGroup {
+            labels,
+            body: &block.entries[start..end],
+        })
+        .collect();
+    let last = groups.len().checked_sub(1)?;
+    for (i, group) in groups.iter().enumerate() {
+        let statements = group.statements();
+        if statements
+            .iter()
+            .any(|e| matches!(e, AstBlockEntry::Break(_)))
+        {
+            return None;
+        }
+        let terminated =
+            statements.last().is_some_and(terminates) || statements.len() < group.body.len();
+        if i != last && !terminated {
+            return None;
+        }
+    }
+    Some(groups)
+}
+
+/// Control does not continue after this statement
+const fn terminates(entry: &AstBlockEntry) -> bool {
+    matches!(
+        entry,
+        AstBlockEntry::Break(_)
+            | AstBlockEntry::Return(_)
+            | AstBlockEntry::Throw(_)
+            | AstBlockEntry::Continue(_)
+            | AstBlockEntry::Yield(_)
+    )
+}
+
+/// Range of a statement including the semicolon
+fn statement_range(document: &Document, mut range: AstRange) -> AstRange {
+    // The range of a variable declaration does not include the semicolon
+    if document.rope.get_char(document.point_to_char(&range.end)) == Some(';') {
+        range.end.col += 1;
+    }
+    range
+}
+
+fn statements_text(document: &Document, entries: &[AstBlockEntry]) -> String {
+    document.range_text(&statement_range(document, entries.get_range()))
+}
+
+fn expression_or_value_text(
+    document: &Document,
+    expression: &AstExpressionOrValue,
+) -> Option<String> {
+    match expression {
+        AstExpressionOrValue::None => None,
+        AstExpressionOrValue::Expression(e) => Some(document.range_text(&e.get_range())),
+        AstExpressionOrValue::Value(v) => Some(document.range_text(&v.get_range())),
+    }
+}
+
+/// The text around the cases of the new switch
+struct SwitchLayout<'a> {
+    document: &'a Document,
+    indent: &'a str,
+    case_indent: &'a str,
+    header: &'a str,
+}
+
+/// Where the value of a switch expression goes
+enum SwitchTarget<'a> {
+    Assign(String),
+    Return,
+    Declare(&'a AstBlockVariable),
+}
+
+impl SwitchLayout<'_> {
+    fn switch_text(&self, cases: &[String]) -> String {
+        format!(
+            "{}\n{}{}\n{}}}",
+            self.header,
+            self.case_indent,
+            cases.join(&format!("\n{}", self.case_indent)),
+            self.indent
+        )
+    }
+
+    fn label_text(&self, labels: &[Option<&AstExpression>]) -> String {
+        if labels.contains(&None) {
+            return "default".to_owned();
+        }
+        let labels: Vec<_> = labels
+            .iter()
+            .flatten()
+            .map(|l| self.document.range_text(&l.get_range()))
+            .collect();
+        format!("case {}", labels.join(", "))
+    }
+
+    /// Statements of a case as the content of a arrow case
+    fn arrow_content(&self, statements: &[AstBlockEntry], value: Option<String>) -> String {
+        let ci = self.case_indent;
+        match (statements, value) {
+            ([], None) => "{}".to_owned(),
+            ([], Some(value)) => format!("{value};"),
+            (
+                [
+                    entry @ (AstBlockEntry::Expression(_)
+                    | AstBlockEntry::Assign(_)
+                    | AstBlockEntry::Throw(_)),
+                ],
+                None,
+            ) => statements_text(self.document, std::slice::from_ref(entry)),
+            (statements, None) => {
+                format!(
+                    "{{\n{ci}    {}\n{ci}}}",
+                    statements_text(self.document, statements)
+                )
+            }
+            (statements, Some(value)) => format!(
+                "{{\n{ci}    {}\n{ci}    yield {value};\n{ci}}}",
+                statements_text(self.document, statements)
+            ),
+        }
+    }
+
+    fn colon_to_arrow(&self, groups: &[ColonGroup]) -> String {
+        let cases: Vec<_> = groups
+            .iter()
+            .map(|g| {
+                format!(
+                    "{} -> {}",
+                    self.label_text(&g.labels),
+                    self.arrow_content(g.statements(), None)
+                )
+            })
+            .collect();
+        self.switch_text(&cases)
+    }
+
+    /// Every case assigns the same variable or every case returns
+    fn colon_to_expression(&self, groups: &[ColonGroup]) -> Option<String> {
+        // A switch expression has to be exhaustive
+        if !groups.iter().any(|g| g.labels.contains(&None)) {
+            return None;
+        }
+        let mut target: Option<Option<String>> = None;
+        let mut cases = vec![];
+        for (i, group) in groups.iter().enumerate() {
+            let statements = group.statements();
+            let (last, rest) = statements.split_last()?;
+            if contains_break(rest) {
+                return None;
+            }
+            let breaks = statements.len() < group.body.len() || i + 1 == groups.len();
+            let (key, value) = match last {
+                AstBlockEntry::Assign(assign) if breaks => (
+                    Some(self.document.range_text(&assign.key.get_range())),
+                    self.document.range_text(&assign.expression.get_range()),
+                ),
+                AstBlockEntry::Return(returns) if statements.len() == group.body.len() => (
+                    None,
+                    expression_or_value_text(self.document, &returns.expression)?,
+                ),
+                AstBlockEntry::Throw(_) if statements.len() == group.body.len() => {
+                    cases.push(format!(
+                        "{} -> {}",
+                        self.label_text(&group.labels),
+                        self.arrow_content(statements, None)
+                    ));
+                    continue;
+                }
+                _ => return None,
+            };
+            if target.get_or_insert_with(|| key.clone()) != &key {
+                return None;
+            }
+            cases.push(format!(
+                "{} -> {}",
+                self.label_text(&group.labels),
+                self.arrow_content(rest, Some(value))
+            ));
+        }
+        let switch = self.switch_text(&cases);
+        Some(target?.map_or_else(
+            || format!("return {switch};"),
+            |key| format!("{key} = {switch};"),
+        ))
+    }
+
+    fn arrow_to_colon(&self, block: &AstBlock) -> Option<String> {
+        let ci = self.case_indent;
+        let mut cases = vec![];
+        for entry in &block.entries {
+            let (label, content) = self.arrow_case(entry)?;
+            let body = match content {
+                AstSwitchCaseArrowContent::Entry(entry) => std::slice::from_ref(&**entry),
+                AstSwitchCaseArrowContent::Block(block) => &block.entries[..],
+            };
+            let mut case = format!("{label}:");
+            if !body.is_empty() {
+                let _ = write!(case, "\n{ci}    {}", statements_text(self.document, body));
+            }
+            if !body.last().is_some_and(terminates) {
+                let _ = write!(case, "\n{ci}    break;");
+            }
+            cases.push(case);
+        }
+        Some(self.switch_text(&cases))
+    }
+
+    fn arrow_case<'b>(
+        &self,
+        entry: &'b AstBlockEntry,
+    ) -> Option<(String, &'b AstSwitchCaseArrowContent)> {
+        match entry {
+            AstBlockEntry::SwitchCaseArrowValues(case) => {
+                let labels: Vec<_> = case
+                    .values
+                    .iter()
+                    .map(|e| match e {
+                        AstExpressionOrDefault::Default => None,
+                        AstExpressionOrDefault::Expression(e) => Some(e),
+                    })
+                    .collect();
+                Some((self.label_text(&labels), &case.content))
+            }
+            AstBlockEntry::SwitchCaseArrowType(case) => Some((
+                format!("case {}", self.document.range_text(&case.var.range)),
+                &case.content,
+            )),
+            AstBlockEntry::SwitchCaseArrowDefault(case) => {
+                Some(("default".to_owned(), &case.content))
+            }
+            _ => None,
+        }
+    }
+
+    fn expression_to_statement(
+        &self,
+        statement: &AstBlockEntry,
+        switch: &AstSwitch,
+    ) -> Option<String> {
+        let target = match statement {
+            AstBlockEntry::Assign(assign)
+                if matches!(
+                    assign.expression.as_slice(),
+                    [AstExpressionKind::InlineSwitch(_)]
+                ) =>
+            {
+                SwitchTarget::Assign(self.document.range_text(&assign.key.get_range()))
+            }
+            AstBlockEntry::Return(AstBlockReturn {
+                expression: AstExpressionOrValue::Expression(expression),
+                ..
+            }) if matches!(expression.as_slice(), [AstExpressionKind::InlineSwitch(_)]) => {
+                SwitchTarget::Return
+            }
+            AstBlockEntry::Variable(variables) => match variables.as_slice() {
+                [variable]
+                    if !matches!(variable.jtype.value, AstJTypeKind::Var)
+                        && matches!(
+                            variable.value.as_deref(),
+                            Some([AstExpressionKind::InlineSwitch(_)])
+                        ) =>
+                {
+                    SwitchTarget::Declare(variable)
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let result = |value: &str| match &target {
+            SwitchTarget::Assign(key) => {
+                format!("{key} = {value};\n{}    break;", self.case_indent)
+            }
+            SwitchTarget::Declare(variable) => format!(
+                "{} = {value};\n{}    break;",
+                variable.name.value, self.case_indent
+            ),
+            SwitchTarget::Return => format!("return {value};"),
+        };
+        let ci = self.case_indent;
+        let mut cases = vec![];
+        for entry in &switch.block.entries {
+            let (label, content) = self.arrow_case(entry)?;
+            let body = match content {
+                AstSwitchCaseArrowContent::Entry(entry) => match &**entry {
+                    AstBlockEntry::Expression(expression) => {
+                        result(&self.document.range_text(&expression.value.get_range()))
+                    }
+                    AstBlockEntry::Throw(_) => {
+                        statements_text(self.document, std::slice::from_ref(&**entry))
+                    }
+                    _ => return None,
+                },
+                AstSwitchCaseArrowContent::Block(block) => {
+                    let (last, rest) = block.entries.split_last()?;
+                    if contains_yield(rest) {
+                        return None;
+                    }
+                    let last = match last {
+                        AstBlockEntry::Yield(yield_entry) => result(&expression_or_value_text(
+                            self.document,
+                            &yield_entry.expression,
+                        )?),
+                        AstBlockEntry::Throw(_) => {
+                            statements_text(self.document, std::slice::from_ref(last))
+                        }
+                        _ => return None,
+                    };
+                    if rest.is_empty() {
+                        last
+                    } else {
+                        format!("{}\n{ci}    {last}", statements_text(self.document, rest))
+                    }
+                }
+            };
+            cases.push(format!("{label}:\n{ci}    {body}"));
+        }
+        let switch = self.switch_text(&cases);
+        Some(match target {
+            SwitchTarget::Declare(variable) => format!(
+                "{} {};\n{}{switch}",
+                self.document.range_text(&variable.jtype.range),
+                variable.name.value,
+                self.indent
+            ),
+            SwitchTarget::Assign(_) | SwitchTarget::Return => switch,
+        })
+    }
+}
+
+fn contains_break(entries: &[AstBlockEntry]) -> bool {
+    let mut finder = EntryFinder {
+        matches: |e| matches!(e, AstBlockEntry::Break(_)),
+        found: false,
+    };
+    for entry in entries {
+        finder.visit_block_entry(entry);
+    }
+    finder.found
+}
+
+fn contains_yield(entries: &[AstBlockEntry]) -> bool {
+    let mut finder = EntryFinder {
+        matches: |e| matches!(e, AstBlockEntry::Yield(_)),
+        found: false,
+    };
+    for entry in entries {
+        finder.visit_block_entry(entry);
+    }
+    finder.found
+}
+
+/// Is there any nested statement that matches
+struct EntryFinder<F: Fn(&AstBlockEntry) -> bool> {
+    matches: F,
+    found: bool,
+}
+
+impl<'a, F: Fn(&AstBlockEntry) -> bool> Visitor<'a> for EntryFinder<F> {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        if (self.matches)(entry) {
+            self.found = true;
+        }
+        visit::walk_block_entry(self, entry);
+    }
+}
+
+/// Finds the switch whose header contains the point and the statement it is part of
+struct SwitchFinder<'a, 'b> {
+    point: &'b AstPoint,
+    statements: Vec<&'a AstBlockEntry>,
+    found: Option<(&'a AstBlockEntry, &'a AstSwitch, bool)>,
+}
+
+impl SwitchFinder<'_, '_> {
+    fn in_header(&self, switch: &AstSwitch) -> bool {
+        switch.range.start <= *self.point && *self.point <= switch.block.range.start
+    }
+}
+
+impl<'a> Visitor<'a> for SwitchFinder<'a, '_> {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        if let AstBlockEntry::Switch(switch) = entry
+            && self.in_header(switch)
+        {
+            self.found = Some((entry, switch, false));
+        }
+        self.statements.push(entry);
+        visit::walk_block_entry(self, entry);
+        self.statements.pop();
+    }
+
+    fn visit_expression_kind(&mut self, kind: &'a AstExpressionKind) {
+        if let AstExpressionKind::InlineSwitch(switch) = kind
+            && self.in_header(switch)
+            && let Some(statement) = self.statements.last()
+        {
+            self.found = Some((statement, switch, true));
+        }
+        visit::walk_expression_kind(self, kind);
+    }
+}
+
+pub const COMMAND_MOVE_CLASS: &str = "java_lsp.moveClass";
+
+/// Top level type under the point with the package and source root of its file
+struct MovedType<'a> {
+    name: &'a AstIdentifier,
+    package: Option<&'a AstPackage>,
+    root: &'a str,
+}
+
+impl MovedType<'_> {
+    fn old_package(&self) -> &str {
+        self.package.map_or("", |p| p.name.value.as_str())
+    }
+}
+
+fn moved_type<'a>(
+    document: &'a Document,
+    point: &AstPoint,
+    current_file: &'a Uri,
+) -> Option<MovedType<'a>> {
+    let name = document.ast.top.iter().find_map(|t| match t {
+        AstTopLevel::Thing(thing) => Some(thing_name(thing)).filter(|n| n.range.is_in_range(point)),
+        _ => None,
+    })?;
+    let package = file_package(&document.ast);
+    let old_path = qualified(package.map_or("", |p| p.name.value.as_str()), &name.value);
+    let root = current_file
+        .as_str()
+        .strip_suffix(&format!("{}.java", old_path.replace('.', "/")))?;
+    Some(MovedType {
+        name,
+        package,
+        root,
+    })
+}
+
+/// Offer to move the top level type under the point to another package. The edit is made by
+/// [`COMMAND_MOVE_CLASS`] once the package is chosen, it has to read the sources of the workspace.
+pub fn move_class(
+    document: &Document,
+    context: &CodeActionContext,
+) -> Result<Vec<CodeActionOrCommand>, CodeActionError> {
+    let Some(moved) = moved_type(document, context.point, context.current_file) else {
+        return Ok(vec![]);
+    };
+    let position = TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier {
+            uri: context.current_file.clone(),
+        },
+        position: to_lsp_range(&moved.name.range)
+            .map_err(CodeActionError::ToLspRange)?
+            .start,
+    };
+    let title = format!("Move {} to package…", moved.name.value);
+    Ok(vec![CodeActionOrCommand::CodeAction(CodeAction {
+        title: title.clone(),
+        kind: Some(CodeActionKind::REFACTOR),
+        command: Some(Command {
+            title,
+            command: COMMAND_MOVE_CLASS.to_string(),
+            arguments: serde_json::to_value(position).ok().map(|p| vec![p]),
+        }),
+        ..Default::default()
+    })])
+}
+
+/// Packages of the workspace the top level type under the point can be moved to
+#[must_use]
+pub fn move_class_packages(
+    document: &Document,
+    point: &AstPoint,
+    current_file: &Uri,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<MyString> {
+    let Some(moved) = moved_type(document, point, current_file) else {
+        return vec![];
+    };
+    let mut packages: Vec<MyString> = class_map
+        .read()
+        .map(|class_map| {
+            class_map
+                .values()
+                .filter(|c| matches!(c.source, SourceDestination::Here(_)))
+                .filter_map(|c| Some(c.class_path.rsplit_once('.')?.0.to_smolstr()))
+                .collect()
+        })
+        .unwrap_or_default();
+    packages.sort_unstable();
+    packages.dedup();
+    packages.retain(|p| p != moved.old_package());
+    packages
+}
+
+/// Move the top level type under the point to `new_package`. The package declaration is
+/// rewritten, the file is moved and imports in the workspace are updated.
+pub fn move_class_edit(
+    document: &Document,
+    point: &AstPoint,
+    current_file: &Uri,
+    new_package: &str,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+    names: &NameIndex,
+) -> Result<Option<WorkspaceEdit>, CodeActionError> {
+    let Some(moved) = moved_type(document, point, current_file) else {
+        return Ok(None);
+    };
+    let name = moved.name;
+    let old_package = moved.old_package();
+    if new_package == old_package {
+        return Ok(None);
+    }
+    let new_path = qualified(new_package, &name.value);
+    let Ok(new_uri) = Uri::from_str(&format!(
+        "{}{}.java",
+        moved.root,
+        new_path.replace('.', "/")
+    )) else {
+        return Ok(None);
+    };
+
+    let mut sources = vec![];
+    if let Ok(class_map) = class_map.read() {
+        for class in class_map.values() {
+            let SourceDestination::Here(source) = &class.source else {
+                continue;
+            };
+            if Path::new(source.as_str()) != document.path {
+                sources.push(source.clone());
+            }
+        }
+    }
+    sources.sort_unstable();
+    sources.dedup();
+
+    // Types of the old package that are used without a import
+    let used = used_type_names(&document.ast);
+    let imports = imports::imports(&document.ast);
+    let imported: Vec<&str> = imports
+        .iter()
+        .filter_map(|i| match i {
+            ImportUnit::Class(c) => Some(class_name(c)),
+            _ => None,
+        })
+        .collect();
+    let mut same_package: Vec<MyString> = sources
+        .iter()
+        .filter_map(|s| Path::new(s.as_str()).file_stem()?.to_str())
+        .filter(|n| used.contains(*n) && !imported.contains(n))
+        .map(|n| qualified(old_package, n))
+        .filter(|c| class_map.read().is_ok_and(|cm| cm.contains_key(c.as_str())))
+        .collect();
+    same_package.sort_unstable();
+    same_package.dedup();
+
+    let mut edits = vec![match moved.package {
+        Some(p) => TextEdit {
+            range: to_lsp_range(&p.name.range).map_err(CodeActionError::ToLspRange)?,
+            new_text: new_package.to_string(),
+        },
+        None => TextEdit {
+            range: Range::default(),
+            new_text: format!("package {new_package};\n\n"),
+        },
+    }];
+    if !old_package.is_empty() {
+        edits.extend(
+            same_package
+                .iter()
+                .flat_map(|c| import_text_edit(c, &document.ast)),
+        );
+    }
+    let mut operations = vec![
+        document_edit(current_file, edits),
+        DocumentChangeOperation::Op(ResourceOp::Rename(RenameFile {
+            old_uri: current_file.clone(),
+            new_uri,
+            options: Some(RenameFileOptions {
+                overwrite: Some(false),
+                ignore_if_exists: Some(false),
+            }),
+            annotation_id: None,
+        })),
+    ];
+
+    // Only files that mention the type can reference it
+    let mut mentioning = sources;
+    names.retain_mentioning(&mut mentioning, &name.value);
+    for source in &mentioning {
+        let other =
+            read_document_or_open_class(source, document_map).map_err(CodeActionError::Document)?;
+        let uri = source_to_uri(source).map_err(CodeActionError::SourceToUri)?;
+        let edits = reference_edits(&other, &name.value, old_package, new_package)?;
+        if !edits.is_empty() {
+            operations.push(document_edit(&uri, edits));
+        }
+    }
+    Ok(Some(WorkspaceEdit {
+        document_changes: Some(DocumentChanges::Operations(operations)),
+        ..Default::default()
+    }))
+}
+
+/// Update imports of a file that could reference the moved type
+fn reference_edits(
+    document: &Document,
+    name: &str,
+    old_package: &str,
+    new_package: &str,
+) -> Result<Vec<TextEdit>, CodeActionError> {
+    let old_path = qualified(old_package, name);
+    let new_path = qualified(new_package, name);
+    let mut edits = vec![];
+    let mut wildcard = false;
+    for import in document.ast.top.iter().filter_map(|t| match t {
+        AstTopLevel::Import(import) => Some(import),
+        _ => None,
+    }) {
+        let ident = match &import.unit {
+            AstImportUnit::Prefix(prefix) => {
+                wildcard |= prefix.value == old_package;
+                continue;
+            }
+            AstImportUnit::Class(ident)
+            | AstImportUnit::StaticClass(ident)
+            | AstImportUnit::StaticClassMethod(ident, _)
+            | AstImportUnit::StaticPrefix(ident) => ident,
+        };
+        let Some(rest) = ident.value.strip_prefix(old_path.as_str()) else {
+            continue;
+        };
+        if !rest.is_empty() && !rest.starts_with('.') {
+            continue;
+        }
+        edits.push(TextEdit {
+            range: to_lsp_range(&ident.range).map_err(CodeActionError::ToLspRange)?,
+            new_text: format!("{new_path}{rest}"),
+        });
+    }
+    if !edits.is_empty() {
+        return Ok(edits);
+    }
+    let package = file_package(&document.ast).map_or("", |p| p.name.value.as_str());
+    if package == new_package || (package != old_package && !wildcard) {
+        return Ok(edits);
+    }
+    if used_type_names(&document.ast).contains(name) {
+        edits.extend(import_text_edit(&new_path, &document.ast));
+    }
+    Ok(edits)
+}
+
+fn document_edit(uri: &Uri, edits: Vec<TextEdit>) -> DocumentChangeOperation {
+    DocumentChangeOperation::Edit(TextDocumentEdit {
+        text_document: OptionalVersionedTextDocumentIdentifier {
+            uri: uri.clone(),
+            version: None,
+        },
+        edits: edits.into_iter().map(OneOf::Left).collect(),
+    })
+}
+
+fn file_package(ast: &AstFile) -> Option<&AstPackage> {
+    ast.top.iter().find_map(|t| match t {
+        AstTopLevel::Package(package) => Some(package),
+        _ => None,
+    })
+}
+
+fn qualified(package: &str, name: &str) -> MyString {
+    if package.is_empty() {
+        return name.to_smolstr();
+    }
+    format_smolstr!("{package}.{name}")
+}
+
+const fn thing_name(thing: &AstThing) -> &AstIdentifier {
+    match thing {
+        AstThing::Class(c) => &c.name,
+        AstThing::Record(r) => &r.name,
+        AstThing::Interface(i) => &i.name,
+        AstThing::Enumeration(e) => &e.name,
+        AstThing::Annotation(a) => &a.name,
+    }
+}
+
+/// Names that could reference a type in the file
+pub(crate) fn used_type_names(ast: &AstFile) -> HashSet<MyString> {
+    let mut collector = TypeNames {
+        names: HashSet::new(),
+    };
+    collector.visit_file(ast);
+    collector.names
+}
+
+struct TypeNames {
+    names: HashSet<MyString>,
+}
+
+impl TypeNames {
+    fn add(&mut self, name: &str) {
+        let first = name.split_once('.').map_or(name, |(first, _)| first);
+        self.names.insert(first.to_smolstr());
+    }
+}
+
+impl<'a> Visitor<'a> for TypeNames {
+    fn visit_thing(&mut self, thing: &'a AstThing) {
+        let superclass = match thing {
+            AstThing::Class(c) => c.superclass.as_slice(),
+            AstThing::Record(r) => r.superclass.as_slice(),
+            AstThing::Enumeration(e) => e.superclass.as_slice(),
+            AstThing::Interface(_) | AstThing::Annotation(_) => &[],
+        };
+        for s in superclass {
+            if let AstSuperClass::Name(name) = s {
+                self.add(&name.value);
+            }
+        }
+        visit::walk_thing(self, thing);
+    }
+
+    fn visit_jtype(&mut self, jtype: &'a AstJType) {
+        if let AstJTypeKind::Class(name)
+        | AstJTypeKind::ClassOrPackage(name)
+        | AstJTypeKind::Generic(name, _) = &jtype.value
+        {
+            self.add(&name.value);
+        }
+        visit::walk_jtype(self, jtype);
+    }
+
+    fn visit_identifier(&mut self, ident: &'a AstIdentifier) {
+        self.add(&ident.value);
+    }
+
+    fn visit_annotated(&mut self, annotated: &'a AstAnnotated) {
+        self.add(&annotated.name.value);
+        visit::walk_annotated(self, annotated);
+    }
+}
+
+/// Add or remove `final` on the local variable, parameter or field under the point.
+/// Also offers to make every effectively final local of the method final.
+pub fn toggle_final(
+    document: &Document,
+    context: &CodeActionContext,
+) -> Result<Vec<CodeActionOrCommand>, CodeActionError> {
+    let mut finder = MemberFinder {
+        point: context.point,
+        class_block: None,
+        member: None,
+        field: None,
+    };
+    finder.visit_file(&document.ast);
+    let mut out = vec![];
+    if let Some((field, class_block)) = finder.field {
+        let name = &field.name.value;
+        if field.availability.contains(AstAvailability::Final) {
+            if let Some(edit) =
+                remove_final_edit(document, field.range.start, field.jtype.range.start)?
+            {
+                out.push(edit_to_code_action(
+                    context,
+                    &format!("Remove final from {name}"),
+                    CodeActionKind::REFACTOR_REWRITE,
+                    vec![edit],
+                ));
+            }
+        } else if field_effectively_final(field, class_block) {
+            out.push(edit_to_code_action(
+                context,
+                &format!("Make {name} final"),
+                CodeActionKind::REFACTOR_REWRITE,
+                vec![add_final_edit(&field.jtype)?],
+            ));
+        }
+        return Ok(out);
+    }
+    let Some(member) = finder.member else {
+        return Ok(out);
+    };
+    let mut collector = LocalCollector {
+        locals: vec![],
+        siblings: &[],
+        resources: false,
+    };
+    if let Some(body) = member.body {
+        collector.visit_block(body);
+    }
+    let count = |name: &str| {
+        member.body.map_or(0, |body| {
+            assignment_count(name, false, |v| v.visit_block(body))
+        })
+    };
+
+    if let Some(parameter) = member
+        .parameters
+        .parameters
+        .iter()
+        .find(|p| p.name.range.is_in_range(context.point))
+    {
+        let name = &parameter.name.value;
+        if parameter.flags.contains(AstMethodParameterFlags::Fin) {
+            if let Some(edit) =
+                remove_final_edit(document, parameter.range.start, parameter.jtype.range.start)?
+            {
+                out.push(edit_to_code_action(
+                    context,
+                    &format!("Remove final from {name}"),
+                    CodeActionKind::REFACTOR_REWRITE,
+                    vec![edit],
+                ));
+            }
+        } else if count(name) == 0 {
+            out.push(edit_to_code_action(
+                context,
+                &format!("Make {name} final"),
+                CodeActionKind::REFACTOR_REWRITE,
+                vec![add_final_edit(&parameter.jtype)?],
+            ));
+        }
+        return Ok(out);
+    }
+
+    let candidates: Vec<&Local> = collector
+        .locals
+        .iter()
+        .filter(|l| {
+            !l.fin()
+                && l.variables
+                    .iter()
+                    .all(|v| l.effectively_final(v, count(&v.name.value)))
+        })
+        .collect();
+    let on_name = member.name.range.is_in_range(context.point);
+    let selected = collector.locals.iter().find(|l| {
+        l.variables
+            .iter()
+            .any(|v| v.name.range.is_in_range(context.point))
+    });
+    if let Some(local) = selected
+        && let [variable] = local.variables
+    {
+        let name = &variable.name.value;
+        if variable.fin {
+            if let Some(edit) =
+                remove_final_edit(document, variable.range.start, variable.jtype.range.start)?
+            {
+                out.push(edit_to_code_action(
+                    context,
+                    &format!("Remove final from {name}"),
+                    CodeActionKind::REFACTOR_REWRITE,
+                    vec![edit],
+                ));
+            }
+        } else if candidates.iter().any(|c| std::ptr::eq(*c, local)) {
+            out.push(edit_to_code_action(
+                context,
+                &format!("Make {name} final"),
+                CodeActionKind::REFACTOR_REWRITE,
+                vec![add_final_edit(&variable.jtype)?],
+            ));
+        }
+    }
+    let bulk = match selected {
+        Some(_) => candidates.len() > 1,
+        None => on_name && !candidates.is_empty(),
+    };
+    if bulk {
+        let edits = candidates
+            .iter()
+            .filter_map(|l| l.variables.first())
+            .map(|v| add_final_edit(&v.jtype))
+            .collect::<Result<_, _>>()?;
+        out.push(edit_to_code_action(
+            context,
+            "Make effectively final locals final",
+            CodeActionKind::REFACTOR_REWRITE,
+            edits,
+        ));
+    }
+    Ok(out)
+}
+
+/// A field is effectively final when it is initialized and never assigned,
+/// or when every constructor assigns it exactly once and nothing else does
+fn field_effectively_final(field: &AstClassVariable, class_block: &AstClassBlock) -> bool {
+    let name = &field.name.value;
+    let outside = assignment_count(name, true, |v| {
+        for method in &class_block.methods {
+            v.visit_class_method(method);
+        }
+        for variable in &class_block.variables {
+            v.visit_class_variable(variable);
+        }
+        for block in &class_block.static_blocks {
+            v.visit_block(&block.block);
+        }
+        for block in &class_block.blocks {
+            v.visit_block(block);
+        }
+        for thing in &class_block.inner {
+            v.visit_thing(thing);
+        }
+    });
+    if outside > 0 {
+        return false;
+    }
+    let in_constructors = class_block
+        .constructors
+        .iter()
+        .map(|c| assignment_count(name, true, |v| v.visit_class_constructor(c)));
+    if field.expression.is_some() {
+        return in_constructors.sum::<usize>() == 0;
+    }
+    if field.availability.contains(AstAvailability::Static) || class_block.constructors.is_empty() {
+        return false;
+    }
+    in_constructors.into_iter().all(|c| c == 1)
+}
+
+fn add_final_edit(jtype: &AstJType) -> Result<TextEdit, CodeActionError> {
+    Ok(TextEdit {
+        range: to_lsp_range(&AstRange {
+            start: jtype.range.start,
+            end: jtype.range.start,
+        })
+        .map_err(CodeActionError::ToLspRange)?,
+        new_text: "final ".to_string(),
+    })
+}
+
+/// Remove the `final` keyword between `start` and `end`
+fn remove_final_edit(
+    document: &Document,
+    start: AstPoint,
+    end: AstPoint,
+) -> Result<Option<TextEdit>, CodeActionError> {
+    let text = document.range_text(&AstRange { start, end });
+    let Some(offset) = text.match_indices("final").map(|(i, _)| i).find(|i| {
+        let before = text[..*i].chars().next_back();
+        let after = text[i + "final".len()..].chars().next();
+        !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+    }) else {
+        return Ok(None);
+    };
+    let whitespace = text[offset + "final".len()..]
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .count();
+    let first = document.point_to_char(&start) + text[..offset].chars().count();
+    let last = first + "final".len() + whitespace;
+    Ok(Some(TextEdit {
+        range: to_lsp_range(&AstRange {
+            start: char_to_point(document, first),
+            end: char_to_point(document, last),
+        })
+        .map_err(CodeActionError::ToLspRange)?,
+        new_text: String::new(),
+    }))
+}
+
+const fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$'
+}
+
+fn char_to_point(document: &Document, index: usize) -> AstPoint {
+    let line = document.rope.char_to_line(index);
+    AstPoint::new(line, index - document.rope.line_to_char(line))
+}
+
+/// Method or constructor that contains the point
+struct Member<'a> {
+    name: &'a AstIdentifier,
+    parameters: &'a AstMethodParameters,
+    body: Option<&'a AstBlock>,
+}
+
+/// Finds the innermost member or the field under the point
+struct MemberFinder<'a, 'b> {
+    point: &'b AstPoint,
+    class_block: Option<&'a AstClassBlock>,
+    member: Option<Member<'a>>,
+    field: Option<(&'a AstClassVariable, &'a AstClassBlock)>,
+}
+
+impl<'a> Visitor<'a> for MemberFinder<'a, '_> {
+    fn visit_class_block(&mut self, block: &'a AstClassBlock) {
+        let outer = self.class_block.replace(block);
+        visit::walk_class_block(self, block);
+        self.class_block = outer;
+    }
+
+    fn visit_class_variable(&mut self, variable: &'a AstClassVariable) {
+        if variable.name.range.is_in_range(self.point)
+            && let Some(class_block) = self.class_block
+        {
+            self.field = Some((variable, class_block));
+        }
+        visit::walk_class_variable(self, variable);
+    }
+
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        if method.range.is_in_range(self.point) {
+            self.member = Some(Member {
+                name: &method.header.name,
+                parameters: &method.header.parameters,
+                body: method.block.as_ref(),
+            });
+        }
+        visit::walk_class_method(self, method);
+    }
+
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        if constructor.range.is_in_range(self.point) {
+            self.member = Some(Member {
+                name: &constructor.header.name,
+                parameters: &constructor.header.parameters,
+                body: Some(&constructor.block),
+            });
+        }
+        visit::walk_class_constructor(self, constructor);
+    }
+}
+
+/// Local variable declaration statement
+struct Local<'a> {
+    variables: &'a [AstBlockVariable],
+    /// Statements of the block that declares the variables.
+    /// Empty for enhanced for loop variables which are always initialized.
+    siblings: &'a [AstBlockEntry],
+    foreach: bool,
+}
+
+impl Local<'_> {
+    fn fin(&self) -> bool {
+        self.variables.iter().any(|v| v.fin)
+    }
+
+    /// Initialized variables may not be assigned, others only once in the declaring block
+    fn effectively_final(&self, variable: &AstBlockVariable, assignments: usize) -> bool {
+        if self.foreach || variable.value.is_some() {
+            return assignments == 0;
+        }
+        assignments == 1
+            && self.siblings.iter().any(|s| {
+                matches!(s, AstBlockEntry::Assign(assign)
+                    if is_assign_target(&assign.key, &variable.name.value, false))
+            })
+    }
+}
+
+/// Collects local variable declarations outside of try with resources
+struct LocalCollector<'a> {
+    locals: Vec<Local<'a>>,
+    siblings: &'a [AstBlockEntry],
+    resources: bool,
+}
+
+impl<'a> Visitor<'a> for LocalCollector<'a> {
+    fn visit_block(&mut self, block: &'a AstBlock) {
+        let outer = std::mem::replace(&mut self.siblings, &block.entries);
+        visit::walk_block(self, block);
+        self.siblings = outer;
+    }
+
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        match entry {
+            AstBlockEntry::Variable(variables) if !self.resources => {
+                self.locals.push(Local {
+                    variables,
+                    siblings: self.siblings,
+                    foreach: false,
+                });
+            }
+            AstBlockEntry::ForEnhanced(for_enhanced) => self.locals.push(Local {
+                variables: &for_enhanced.var,
+                siblings: &[],
+                foreach: true,
+            }),
+            AstBlockEntry::TryCatch(try_catch) => {
+                if let Some(resources) = &try_catch.resources_block {
+                    self.resources = true;
+                    self.visit_block(resources);
+                    self.resources = false;
+                }
+                self.visit_block(&try_catch.block);
+                for case in &try_catch.cases {
+                    self.visit_try_catch_case(case);
+                }
+                if let Some(finally) = &try_catch.finally_block {
+                    self.visit_block(finally);
+                }
+                return;
+            }
+            _ => (),
+        }
+        visit::walk_block_entry(self, entry);
+    }
+
+    fn visit_thing(&mut self, _thing: &'a AstThing) {}
+}
+
+/// How often the variable is assigned, `this.name` is only matched when `field` is set
+fn assignment_count(
+    name: &str,
+    field: bool,
+    visit: impl FnOnce(&mut AssignmentCounter<'_>),
+) -> usize {
+    let mut counter = AssignmentCounter {
+        name,
+        field,
+        count: 0,
+    };
+    visit(&mut counter);
+    counter.count
+}
+
+struct AssignmentCounter<'b> {
+    name: &'b str,
+    field: bool,
+    count: usize,
+}
+
+impl<'a> Visitor<'a> for AssignmentCounter<'_> {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        if let AstBlockEntry::Assign(assign) = entry
+            && is_assign_target(&assign.key, self.name, self.field)
+        {
+            self.count += 1;
+        }
+        visit::walk_block_entry(self, entry);
+    }
+
+    fn visit_expression(&mut self, expression: &'a [AstExpressionKind]) {
+        for (i, kind) in expression.iter().enumerate() {
+            let AstExpressionKind::Base(AstBaseExpression {
+                ident: None,
+                operator,
+                ..
+            }) = kind
+            else {
+                continue;
+            };
+            let assigned = match operator {
+                AstExpressionOperator::Assign(_)
+                | AstExpressionOperator::PlusEqual(_)
+                | AstExpressionOperator::MinusEqual(_)
+                | AstExpressionOperator::MultiplyEqual(_)
+                | AstExpressionOperator::DivideEqual(_)
+                | AstExpressionOperator::ModuloEqual(_)
+                | AstExpressionOperator::VerticalBarEqual(_) => {
+                    is_assign_target(&expression[..i], self.name, self.field)
+                        || compound_assign_target(&expression[..i], operator)
+                            .is_some_and(|t| is_assign_target(t, self.name, self.field))
+                }
+                AstExpressionOperator::Ge(_) => compound_assign_target(&expression[..i], operator)
+                    .is_some_and(|t| is_assign_target(t, self.name, self.field)),
+                AstExpressionOperator::PlusPlus(_) | AstExpressionOperator::MinusMinus(_) => {
+                    is_assign_target(&expression[..i], self.name, self.field)
+                        || is_prefix_target(&expression[i + 1..], self.name)
+                }
+                _ => false,
+            };
+            if assigned {
+                self.count += 1;
+            }
+        }
+        visit::walk_expression(self, expression);
+    }
+}
+
+/// `&=`, `^=` and `<<=` are parsed as the operator followed by `=`, `>>=` and `>>>=` as `>` or
+/// `>>` followed by `>=`. The expression before the first of the two operators
+fn compound_assign_target<'e>(
+    before: &'e [AstExpressionKind],
+    operator: &AstExpressionOperator,
+) -> Option<&'e [AstExpressionKind]> {
+    let (
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: None,
+            operator: first,
+            ..
+        }),
+        rest,
+    ) = before.split_last()?
+    else {
+        return None;
+    };
+    let compound = match operator {
+        AstExpressionOperator::Assign(_) => matches!(
+            first,
+            AstExpressionOperator::Ampersand(_)
+                | AstExpressionOperator::Caret(_)
+                | AstExpressionOperator::LtLt(_)
+        ),
+        AstExpressionOperator::Ge(_) => matches!(
+            first,
+            AstExpressionOperator::Gt(_) | AstExpressionOperator::GtGt(_)
+        ),
+        _ => false,
+    };
+    compound.then_some(rest)
+}
+
+/// Does the expression end with `name` or `this.name`
+fn is_assign_target(expression: &[AstExpressionKind], name: &str, field: bool) -> bool {
+    let Some((last, rest)) = expression.split_last() else {
+        return false;
+    };
+    if !is_identifier(last, name) {
+        return false;
+    }
+    match rest {
+        [
+            ..,
+            this,
+            AstExpressionKind::Base(AstBaseExpression {
+                ident: None,
+                operator: AstExpressionOperator::Dot(_),
+                ..
+            }),
+        ] => field && is_identifier(this, "this"),
+        _ => true,
+    }
+}
+
+/// Does the expression start with `name` that is not followed by a member access
+fn is_prefix_target(expression: &[AstExpressionKind], name: &str) -> bool {
+    let Some((first, rest)) = expression.split_first() else {
+        return false;
+    };
+    is_identifier(first, name)
+        && !matches!(
+            rest.first(),
+            Some(AstExpressionKind::Base(AstBaseExpression {
+                ident: None,
+                operator: AstExpressionOperator::Dot(_),
+                ..
+            }))
+        )
+}
+
+fn is_identifier(kind: &AstExpressionKind, name: &str) -> bool {
+    matches!(kind, AstExpressionKind::Base(AstBaseExpression {
+        ident: Some(AstExpressionIdentifier::Identifier(ident)),
+        values: None,
+        ..
+    }) if ident.value == name)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{
+        collections::HashMap,
+        path::PathBuf,
+        str::FromStr,
+        sync::{Arc, RwLock},
+    };
+
+    use ast::{
+        types::{AstBlockEntry, AstIf, AstPoint, AstRange},
+        visit::Visitor,
+    };
+    use document::Document;
+    use dto::{Access, Class, JType, Method, Parameter, SourceDestination, SuperClass};
+    use expect_test::expect;
+    use lsp_types::{
+        CodeActionOrCommand, DocumentChangeOperation, DocumentChanges, OneOf, ResourceOp, TextEdit,
+        Uri,
+    };
+    use my_string::{MyString, smol_str::SmolStr};
+    use ropey::Rope;
+    use variables::VariableContext;
+
+    use crate::{codeaction::CodeActionContext, name_index::NameIndex};
+
+    use super::{
+        COMMAND_MOVE_CLASS, IfFinder, convert_for_loop, convert_switch, convert_var, invert_if,
+        lambda_to_anonymous_class, move_class, move_class_edit, move_class_packages, negate,
+        split_join_declaration, surround_with, toggle_final,
+    };
+
+    fn lambda_edit(cont: &str, point: AstPoint) -> Option<String> {
+        let doc = Document::setup(cont, PathBuf::from_str("./").unwrap()).unwrap();
+        let imports = imports::imports(&doc.ast);
+        let class = parser::java::load_java_tree(&doc.ast, SourceDestination::None);
+        let uri = Uri::from_str("file:///a").unwrap();
+        let class_map = get_class_map();
+        let vars = variables::get_vars(
+            &doc.ast,
+            &VariableContext {
+                point: Some(point),
+                imports: &imports,
+                class: &class,
+                class_map: class_map.clone(),
+            },
+        )
+        .unwrap();
+        let context = CodeActionContext {
+            point: &point,
+            imports: &imports,
+            class_map,
+            class: &class,
+            vars: &vars,
+            current_file: &uri,
+        };
+        let CodeActionOrCommand::CodeAction(action) =
+            lambda_to_anonymous_class(&doc, &context).unwrap()?
+        else {
+            return None;
+        };
+        #[allow(clippy::mutable_key_type)]
+        let changes = action.edit?.changes?;
+        Some(changes.get(&uri)?[0].new_text.clone())
+    }
+
+    #[test]
+    fn lambda_variable_block() {
+        let cont = r#"
+package ch.emilycares;
+public class Test {
+    public void hello() {
+        Runnable r = () -> {
+            System.out.println("hi");
+        };
+    }
+}
+"#;
+        let out = lambda_edit(cont, AstPoint::new(4, 24));
+        let expected = expect![[r#"
+            new Runnable() {
+                        @Override
+                        public void run() {
+                            System.out.println("hi");
+                        }
+                    }"#]];
+        expected.assert_eq(&out.unwrap());
+    }
+
+    #[test]
+    fn lambda_variable_generic_expression() {
+        let cont = r"
+package ch.emilycares;
+import java.util.function.Function;
+public class Test {
+    Function<String, Integer> f = s -> s.length();
+}
+";
+        let out = lambda_edit(cont, AstPoint::new(4, 37));
+        let expected = expect![[r"
+            new Function<String, Integer>() {
+                    @Override
+                    public Integer apply(String s) {
+                        return s.length();
+                    }
+                }"]];
+        expected.assert_eq(&out.unwrap());
+    }
+
+    #[test]
+    fn lambda_method_argument() {
+        let cont = r#"
+package ch.emilycares;
+public class Test {
+    public void hello() {
+        String a = "";
+        a.run(() -> a.length());
+    }
+}
+"#;
+        let out = lambda_edit(cont, AstPoint::new(5, 16));
+        let expected = expect![[r"
+            new Runnable() {
+                        @Override
+                        public void run() {
+                            a.length();
+                        }
+                    }"]];
+        expected.assert_eq(&out.unwrap());
+    }
+
+    #[test]
+    fn lambda_not_in_lambda() {
+        let cont = r#"
+package ch.emilycares;
+public class Test {
+    public void hello() {
+        String a = "";
+    }
+}
+"#;
+        let out = lambda_edit(cont, AstPoint::new(4, 10));
+        assert_eq!(out, None);
+    }
+
+    /// Apply the edits of each code action and return the title and the new text
+    pub(crate) fn run_actions(
+        cont: &str,
+        point: AstPoint,
+        action: impl Fn(&Document, &CodeActionContext) -> Vec<CodeActionOrCommand>,
+    ) -> Vec<(String, String)> {
+        let doc = Document::setup(cont, PathBuf::from_str("./").unwrap()).unwrap();
+        let imports = imports::imports(&doc.ast);
+        let class = parser::java::load_java_tree(&doc.ast, SourceDestination::None);
+        let uri = Uri::from_str("file:///a").unwrap();
+        let class_map = get_class_map();
+        let vars = variables::get_vars(
+            &doc.ast,
+            &VariableContext {
+                point: Some(point),
+                imports: &imports,
+                class: &class,
+                class_map: class_map.clone(),
+            },
+        )
+        .unwrap();
+        let context = CodeActionContext {
+            point: &point,
+            imports: &imports,
+            class_map,
+            class: &class,
+            vars: &vars,
+            current_file: &uri,
+        };
+        action(&doc, &context)
+            .into_iter()
+            .filter_map(|a| match a {
+                CodeActionOrCommand::CodeAction(a) => Some(a),
+                CodeActionOrCommand::Command(_) => None,
+            })
+            .map(|a| {
+                #[allow(clippy::mutable_key_type)]
+                let changes = a.edit.unwrap().changes.unwrap();
+                (a.title, apply_edits(cont, &changes[&uri]))
+            })
+            .collect()
+    }
+
+    pub(crate) fn apply_edits(cont: &str, edits: &[TextEdit]) -> String {
+        let mut rope = Rope::from_str(cont);
+        let mut edits = edits.to_vec();
+        edits.sort_by_key(|e| std::cmp::Reverse(e.range.start));
+        for edit in edits {
+            let start = rope.line_to_char(edit.range.start.line as usize)
+                + edit.range.start.character as usize;
+            let end =
+                rope.line_to_char(edit.range.end.line as usize) + edit.range.end.character as usize;
+            rope.remove(start..end);
+            rope.insert(start, &edit.new_text);
+        }
+        rope.to_string()
+    }
+
+    #[test]
+    fn var_and_explicit_type() {
+        let cont = r"
+package ch.emilycares;
+import java.io.FileInputStream;
+public class Test {
+    public void hello(String name) {
+        var in = new FileInputStream(name);
+        var length = name.length();
+        String copy = name;
+        int count = name.length();
+        String a = name, b = name;
+        Runnable r = () -> {};
+    }
+}
+";
+        let actions = |line, var_available| {
+            run_actions(cont, AstPoint::new(line, 9), |d, c| {
+                convert_var(d, c, var_available).unwrap()
+            })
+        };
+        let expected = expect![[r#"
+            [
+                (
+                    "Replace var with explicit type",
+                    "\npackage ch.emilycares;\nimport java.io.FileInputStream;\npublic class Test {\n    public void hello(String name) {\n        FileInputStream in = new FileInputStream(name);\n        var length = name.length();\n        String copy = name;\n        int count = name.length();\n        String a = name, b = name;\n        Runnable r = () -> {};\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&actions(5, true));
+        let expected = expect![[r#"
+            [
+                (
+                    "Replace var with explicit type",
+                    "\npackage ch.emilycares;\nimport java.io.FileInputStream;\npublic class Test {\n    public void hello(String name) {\n        var in = new FileInputStream(name);\n        int length = name.length();\n        String copy = name;\n        int count = name.length();\n        String a = name, b = name;\n        Runnable r = () -> {};\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&actions(6, true));
+        let expected = expect![[r#"
+            [
+                (
+                    "Replace with var",
+                    "\npackage ch.emilycares;\nimport java.io.FileInputStream;\npublic class Test {\n    public void hello(String name) {\n        var in = new FileInputStream(name);\n        var length = name.length();\n        var copy = name;\n        int count = name.length();\n        String a = name, b = name;\n        Runnable r = () -> {};\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&actions(7, true));
+        assert_eq!(actions(8, true).len(), 1);
+        assert!(actions(7, false).is_empty());
+        assert!(actions(9, true).is_empty());
+        assert!(actions(10, true).is_empty());
+    }
+
+    #[test]
+    fn split_and_join_declaration() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public void hello(String name) {
+        final String copy = name;
+        int length;
+        length = name.length();
+        String other;
+        // comment
+        other = name;
+        try (String in = name) {
+        }
+    }
+}
+";
+        let actions = |line| {
+            run_actions(cont, AstPoint::new(line, 14), |d, c| {
+                split_join_declaration(d, c).unwrap()
+            })
+        };
+        let expected = expect![[r#"
+            [
+                (
+                    "Split declaration and assignment",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(String name) {\n        final String copy;\n        copy = name;\n        int length;\n        length = name.length();\n        String other;\n        // comment\n        other = name;\n        try (String in = name) {\n        }\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&actions(4));
+        let expected = expect![[r#"
+            [
+                (
+                    "Join declaration and assignment",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(String name) {\n        final String copy = name;\n        int length = name.length();\n        String other;\n        // comment\n        other = name;\n        try (String in = name) {\n        }\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&actions(5));
+        assert!(actions(7).is_empty());
+        assert!(actions(10).is_empty());
+    }
+
+    #[test]
+    fn invert_if_else() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public void hello(String name, int a, boolean done) {
+        if (name == null || a > 2 && !done) {
+            a = 1;
+        } else if (!(a < 3)) {
+            a = 2;
+        } else {
+            a = 3;
+        }
+        if (done) a = 4;
+    }
+}
+";
+        let actions = |line| {
+            run_actions(cont, AstPoint::new(line, 13), |d, c| {
+                invert_if(d, c).unwrap()
+            })
+        };
+        let expected = expect![[r#"
+            [
+                (
+                    "Negate condition",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(String name, int a, boolean done) {\n        if (name != null && (a <= 2 || done)) {\n            a = 1;\n        } else if (!(a < 3)) {\n            a = 2;\n        } else {\n            a = 3;\n        }\n        if (done) a = 4;\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&actions(4));
+        let expected = expect![[r#"
+            [
+                (
+                    "Negate condition",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(String name, int a, boolean done) {\n        if (name == null || a > 2 && !done) {\n            a = 1;\n        } else if (a < 3) {\n            a = 2;\n        } else {\n            a = 3;\n        }\n        if (done) a = 4;\n    }\n}\n",
+                ),
+                (
+                    "Invert if-else",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(String name, int a, boolean done) {\n        if (name == null || a > 2 && !done) {\n            a = 1;\n        } else if (a < 3) {\n            a = 3;\n        } else {\n            a = 2;\n        }\n        if (done) a = 4;\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&actions(6));
+        let expected = expect![[r#"
+            [
+                (
+                    "Negate condition",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(String name, int a, boolean done) {\n        if (name == null || a > 2 && !done) {\n            a = 1;\n        } else if (!(a < 3)) {\n            a = 2;\n        } else {\n            a = 3;\n        }\n        if (!done) a = 4;\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&actions(11));
+        assert!(actions(5).is_empty());
+    }
+
+    #[test]
+    fn negate_conditions() {
+        let negated = |condition: &str| {
+            let cont = format!("class A {{ void a() {{ if ({condition}) {{}} }} }}");
+            let document = Document::setup(&cont, PathBuf::from("A.java")).unwrap();
+            let mut finder = IfFinder {
+                point: &AstPoint::new(0, 23),
+                found: None,
+            };
+            finder.visit_file(&document.ast);
+            let (entries, index) = finder.found.unwrap();
+            let AstBlockEntry::If(AstIf::If { control, .. }) = &entries[index] else {
+                panic!("no if");
+            };
+            negate(&document, control)
+        };
+        assert_eq!(negated("a"), "!a");
+        assert_eq!(negated("!a.b()"), "a.b()");
+        assert_eq!(negated("true"), "false");
+        assert_eq!(negated("a != b"), "a == b");
+        assert_eq!(negated("a + 1 <= b"), "a + 1 > b");
+        assert_eq!(negated("a && b || c"), "(!a || !b) && !c");
+        assert_eq!(negated("(a || b)"), "(!a && !b)");
+        assert_eq!(negated("a == b == c"), "!(a == b == c)");
+        assert_eq!(negated("a & b"), "!(a & b)");
+        assert_eq!(negated("o instanceof String s"), "!(o instanceof String s)");
+    }
+
+    #[test]
+    fn for_array_enhanced() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public void hello(String[] names) {
+        for (int i = 0; i < names.length; i++) {
+            System.out.println(names[i]);
+        }
+    }
+}
+";
+        let out = run_actions(cont, AstPoint::new(4, 12), |d, c| {
+            convert_for_loop(d, c).unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Convert to enhanced for loop",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(String[] names) {\n        for (String name : names) {\n            System.out.println(name);\n        }\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn for_list_stream() {
+        let cont = r"
+package ch.emilycares;
+import java.util.List;
+public class Test {
+    public void hello(List<String> items, List<Integer> out) {
+        for (int i = 0; i < items.size(); ++i) {
+            if (items.get(i).length() > 2) {
+                out.add(items.get(i).length());
+            }
+        }
+    }
+}
+";
+        let out = run_actions(cont, AstPoint::new(5, 12), |d, c| {
+            convert_for_loop(d, c).unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Convert to enhanced for loop",
+                    "\npackage ch.emilycares;\nimport java.util.List;\npublic class Test {\n    public void hello(List<String> items, List<Integer> out) {\n        for (String item : items) {\n            if (item.length() > 2) {\n                out.add(item.length());\n            }\n        }\n    }\n}\n",
+                ),
+                (
+                    "Convert to stream",
+                    "\npackage ch.emilycares;\nimport java.util.List;\npublic class Test {\n    public void hello(List<String> items, List<Integer> out) {\n        items.stream().filter(item -> item.length() > 2).map(item -> item.length()).forEach(out::add);\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn for_array_stream_import() {
+        let cont = r"
+package ch.emilycares;
+import java.util.List;
+public class Test {
+    public void hello(String[] values, List<String> out) {
+        for (int i = 0; i < values.length; i++) out.add(values[i]);
+    }
+}
+";
+        let out = run_actions(cont, AstPoint::new(5, 12), |d, c| {
+            convert_for_loop(d, c).unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Convert to enhanced for loop",
+                    "\npackage ch.emilycares;\nimport java.util.List;\npublic class Test {\n    public void hello(String[] values, List<String> out) {\n        for (String value : values) out.add(value);\n    }\n}\n",
+                ),
+                (
+                    "Convert to stream",
+                    "\npackage ch.emilycares;\nimport java.util.List;\nimport java.util.Arrays;\npublic class Test {\n    public void hello(String[] values, List<String> out) {\n        Arrays.stream(values).forEach(out::add);\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn for_index_used() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public void hello(String[] names) {
+        for (int i = 0; i < names.length; i++) {
+            names[i] = names[i] + i;
+        }
+    }
+}
+";
+        let out = run_actions(cont, AstPoint::new(4, 12), |d, c| {
+            convert_for_loop(d, c).unwrap()
+        });
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn surround_all() {
+        let cont = r#"
+package ch.emilycares;
+public class Test {
+    public void hello() {
+        String a = "";
+        a.length();
+    }
+}
+"#;
+        let out = run_actions(cont, AstPoint::new(5, 8), |d, c| {
+            surround_with(
+                d,
+                c,
+                &AstRange {
+                    start: AstPoint::new(5, 8),
+                    end: AstPoint::new(5, 10),
+                },
+            )
+            .unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Surround with try/catch",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello() {\n        String a = \"\";\n        try {\n            a.length();\n        } catch (Exception e) {\n            throw new RuntimeException(e);\n        }\n    }\n}\n",
+                ),
+                (
+                    "Surround with if",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello() {\n        String a = \"\";\n        if (true) {\n            a.length();\n        }\n    }\n}\n",
+                ),
+                (
+                    "Surround with synchronized",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello() {\n        String a = \"\";\n        synchronized (this) {\n            a.length();\n        }\n    }\n}\n",
+                ),
+                (
+                    "Surround with for",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello() {\n        String a = \"\";\n        for (int i = 0; i < 10; i++) {\n            a.length();\n        }\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn surround_try_catch_exceptions() {
+        let cont = r#"
+package ch.emilycares;
+import java.io.FileInputStream;
+public class Test {
+    public void hello() {
+        FileInputStream in = new FileInputStream("a");
+        in.read();
+        try {
+            in.read();
+        } catch (IOException e) {
+        }
+    }
+}
+"#;
+        let out = run_actions(cont, AstPoint::new(5, 8), |d, c| {
+            surround_with(
+                d,
+                c,
+                &AstRange {
+                    start: AstPoint::new(5, 8),
+                    end: AstPoint::new(10, 9),
+                },
+            )
+            .unwrap()
+            .into_iter()
+            .take(1)
+            .collect()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Surround with try/catch",
+                    "\npackage ch.emilycares;\nimport java.io.FileInputStream;\nimport java.io.IOException;\npublic class Test {\n    public void hello() {\n        try {\n            FileInputStream in = new FileInputStream(\"a\");\n            in.read();\n            try {\n                in.read();\n            } catch (IOException e) {\n            }\n        } catch (IOException e) {\n            throw new RuntimeException(e);\n        }\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn switch_colon_to_arrow_and_expression() {
+        let cont = r#"
+package ch.emilycares;
+public class Test {
+    public void hello(int a) {
+        String name;
+        switch (a) {
+            case 1:
+            case 2:
+                name = a + "";
+                break;
+            case 3:
+                System.out.println(a);
+                name = "three";
+                break;
+            default:
+                throw new IllegalStateException();
+        }
+    }
+}
+"#;
+        let out = run_actions(cont, AstPoint::new(5, 10), |d, c| {
+            convert_switch(d, c).unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Convert to switch expression",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(int a) {\n        String name;\n        name = switch (a) {\n            case 1, 2 -> a + \"\";\n            case 3 -> {\n                System.out.println(a);\n                yield \"three\";\n            }\n            default -> throw new IllegalStateException();\n        };\n    }\n}\n",
+                ),
+                (
+                    "Convert to arrow switch",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(int a) {\n        String name;\n        switch (a) {\n            case 1, 2 -> name = a + \"\";\n            case 3 -> {\n                System.out.println(a);\n                name = \"three\";\n            }\n            default -> throw new IllegalStateException();\n        }\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn switch_colon_return() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public int hello(int a) {
+        switch (a) {
+            case 1:
+                return 10;
+            default:
+                return 0;
+        }
+    }
+}
+";
+        let out = run_actions(cont, AstPoint::new(4, 10), |d, c| {
+            convert_switch(d, c).unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Convert to switch expression",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public int hello(int a) {\n        return switch (a) {\n            case 1 -> 10;\n            default -> 0;\n        };\n    }\n}\n",
+                ),
+                (
+                    "Convert to arrow switch",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public int hello(int a) {\n        switch (a) {\n            case 1 -> {\n                return 10;\n            }\n            default -> {\n                return 0;\n            }\n        }\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn switch_fall_through() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public void hello(int a) {
+        switch (a) {
+            case 1:
+                System.out.println(a);
+            default:
+                System.out.println(0);
+        }
+    }
+}
+";
+        let out = run_actions(cont, AstPoint::new(4, 10), |d, c| {
+            convert_switch(d, c).unwrap()
+        });
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn switch_arrow_to_colon() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public void hello(int a) {
+        switch (a) {
+            case 1, 2 -> System.out.println(a);
+            default -> {
+                System.out.println(0);
+            }
+        }
+    }
+}
+";
+        let out = run_actions(cont, AstPoint::new(4, 10), |d, c| {
+            convert_switch(d, c).unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Convert to classic switch",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(int a) {\n        switch (a) {\n            case 1, 2:\n                System.out.println(a);\n                break;\n            default:\n                System.out.println(0);\n                break;\n        }\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn switch_expression_to_statement() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public void hello(int a) {
+        int b = switch (a) {
+            case 1, 2 -> 3;
+            default -> {
+                System.out.println(a);
+                yield 4;
+            }
+        };
+    }
+}
+";
+        let out = run_actions(cont, AstPoint::new(4, 18), |d, c| {
+            convert_switch(d, c).unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Convert to switch statement",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(int a) {\n        int b;\n        switch (a) {\n            case 1, 2:\n                b = 3;\n                break;\n            default:\n                System.out.println(a);\n                b = 4;\n                break;\n        }\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn move_class_to_package() {
+        const ROOT: &str = "/project/src/main/java/ch/emilycares";
+        let cont = "package ch.emilycares;
+
+public class Test {
+    Helper helper;
+}
+";
+        let helper = "package ch.emilycares;
+
+public class Helper {
+    Test test;
+}
+";
+        let user = "package ch.emilycares.other;
+
+import ch.emilycares.Test;
+
+public class User {
+    Test test;
+}
+";
+        let source = format!("{ROOT}/Test.java");
+        let doc = Document::setup(cont, PathBuf::from_str(&source).unwrap()).unwrap();
+        let imports = imports::imports(&doc.ast);
+        let class = parser::java::load_java_tree(&doc.ast, SourceDestination::None);
+        let uri = Uri::from_str(&format!("file://{source}")).unwrap();
+        let class_map = get_class_map();
+        let document_map: Arc<RwLock<HashMap<MyString, Document>>> =
+            Arc::new(RwLock::new(HashMap::new()));
+        for (class_path, path, text) in [
+            ("ch.emilycares.Test", source, cont),
+            (
+                "ch.emilycares.Helper",
+                format!("{ROOT}/Helper.java"),
+                helper,
+            ),
+            (
+                "ch.emilycares.other.User",
+                format!("{ROOT}/other/User.java"),
+                user,
+            ),
+        ] {
+            class_map.write().unwrap().insert(
+                SmolStr::new(class_path),
+                Class {
+                    class_path: SmolStr::new(class_path),
+                    source: SourceDestination::Here(SmolStr::new(&path)),
+                    ..Default::default()
+                },
+            );
+            document_map.write().unwrap().insert(
+                SmolStr::new(&path),
+                Document::setup(text, PathBuf::from_str(&path).unwrap()).unwrap(),
+            );
+        }
+        let point = AstPoint::new(2, 15);
+        let context = CodeActionContext {
+            point: &point,
+            imports: &imports,
+            class_map,
+            class: &class,
+            vars: &[],
+            current_file: &uri,
+        };
+        let actions: Vec<_> = move_class(&doc, &context)
+            .unwrap()
+            .into_iter()
+            .filter_map(|a| match a {
+                CodeActionOrCommand::CodeAction(a) => Some((a.title, a.command?.command)),
+                CodeActionOrCommand::Command(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            actions,
+            [(
+                "Move Test to package…".to_string(),
+                COMMAND_MOVE_CLASS.to_string()
+            )]
+        );
+        assert_eq!(
+            move_class_packages(&doc, &point, &uri, &context.class_map),
+            ["ch.emilycares.other"]
+        );
+        let edit = move_class_edit(
+            &doc,
+            &point,
+            &uri,
+            "ch.emilycares.other",
+            &context.class_map,
+            &document_map,
+            &NameIndex::default(),
+        )
+        .unwrap();
+        let Some(DocumentChanges::Operations(operations)) = edit.and_then(|e| e.document_changes)
+        else {
+            unreachable!();
+        };
+        let out: Vec<_> = operations
+            .into_iter()
+            .map(|o| match o {
+                DocumentChangeOperation::Op(ResourceOp::Rename(r)) => {
+                    format!("rename {} -> {}", r.old_uri.as_str(), r.new_uri.as_str())
+                }
+                DocumentChangeOperation::Edit(e) => {
+                    let path = e.text_document.uri.path().to_string();
+                    let text = document_map.read().unwrap()[path.as_str()].rope.to_string();
+                    let edits: Vec<_> = e
+                        .edits
+                        .into_iter()
+                        .filter_map(|e| match e {
+                            OneOf::Left(e) => Some(e),
+                            OneOf::Right(_) => None,
+                        })
+                        .collect();
+                    format!("{path}\n{}", apply_edits(&text, &edits))
+                }
+                DocumentChangeOperation::Op(_) => String::new(),
+            })
+            .collect();
+        let expected = expect![[r#"
+            [
+                "/project/src/main/java/ch/emilycares/Test.java\npackage ch.emilycares.other;\nimport ch.emilycares.Helper;\n\npublic class Test {\n    Helper helper;\n}\n",
+                "rename file:///project/src/main/java/ch/emilycares/Test.java -> file:///project/src/main/java/ch/emilycares/other/Test.java",
+                "/project/src/main/java/ch/emilycares/Helper.java\npackage ch.emilycares;\nimport ch.emilycares.other.Test;\n\npublic class Helper {\n    Test test;\n}\n",
+                "/project/src/main/java/ch/emilycares/other/User.java\npackage ch.emilycares.other;\n\nimport ch.emilycares.other.Test;\n\npublic class User {\n    Test test;\n}\n",
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn final_locals() {
+        let cont = r#"
+package ch.emilycares;
+public class Test {
+    public void hello(int p, int q) {
+        int a = 1;
+        int b = 2;
+        b++;
+        String c;
+        c = "x";
+        for (String s : java.util.List.of("s")) {}
+        q = 3;
+        System.out.println(a + b + c + p + q);
+    }
+}
+"#;
+        let on_local = run_actions(cont, AstPoint::new(4, 12), |d, c| {
+            toggle_final(d, c).unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Make a final",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(int p, int q) {\n        final int a = 1;\n        int b = 2;\n        b++;\n        String c;\n        c = \"x\";\n        for (String s : java.util.List.of(\"s\")) {}\n        q = 3;\n        System.out.println(a + b + c + p + q);\n    }\n}\n",
+                ),
+                (
+                    "Make effectively final locals final",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(int p, int q) {\n        final int a = 1;\n        int b = 2;\n        b++;\n        final String c;\n        c = \"x\";\n        for (final String s : java.util.List.of(\"s\")) {}\n        q = 3;\n        System.out.println(a + b + c + p + q);\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&on_local);
+        let on_assigned = run_actions(cont, AstPoint::new(5, 12), |d, c| {
+            toggle_final(d, c).unwrap()
+        });
+        let expected = expect![[r#"
+            [
+                (
+                    "Make effectively final locals final",
+                    "\npackage ch.emilycares;\npublic class Test {\n    public void hello(int p, int q) {\n        final int a = 1;\n        int b = 2;\n        b++;\n        final String c;\n        c = \"x\";\n        for (final String s : java.util.List.of(\"s\")) {}\n        q = 3;\n        System.out.println(a + b + c + p + q);\n    }\n}\n",
+                ),
+            ]
+        "#]];
+        expected.assert_debug_eq(&on_assigned);
+    }
+
+    #[test]
+    fn final_compound_assignments() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public void hello() {
+        int x = 1;
+        int y = 1;
+        int z = 1;
+        int w = 1;
+        x &= 1;
+        y ^= 1;
+        z <<= 1;
+        w >>>= 1;
+    }
+}
+";
+        for line in 4..8 {
+            let out = run_actions(cont, AstPoint::new(line, 12), |d, c| {
+                toggle_final(d, c).unwrap()
+            });
+            assert_eq!(out, [], "line {line}");
+        }
+    }
+
+    #[test]
+    fn final_parameters() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    public void hello(int p, int q, final int r) {
+        q = 3;
+    }
+}
+";
+        let p = run_actions(cont, AstPoint::new(3, 26), |d, c| {
+            toggle_final(d, c).unwrap()
+        });
+        let q = run_actions(cont, AstPoint::new(3, 33), |d, c| {
+            toggle_final(d, c).unwrap()
+        });
+        let r = run_actions(cont, AstPoint::new(3, 46), |d, c| {
+            toggle_final(d, c).unwrap()
+        });
+        let expected = expect![[r#"
+            (
+                [
+                    (
+                        "Make p final",
+                        "\npackage ch.emilycares;\npublic class Test {\n    public void hello(final int p, int q, final int r) {\n        q = 3;\n    }\n}\n",
+                    ),
+                ],
+                [],
+                [
+                    (
+                        "Remove final from r",
+                        "\npackage ch.emilycares;\npublic class Test {\n    public void hello(int p, int q, int r) {\n        q = 3;\n    }\n}\n",
+                    ),
+                ],
+            )
+        "#]];
+        expected.assert_debug_eq(&(p, q, r));
+    }
+
+    #[test]
+    fn final_fields() {
+        let cont = r"
+package ch.emilycares;
+public class Test {
+    private final int f = 1;
+    private int g;
+    private int h = 0;
+    public Test() {
+        this.g = 1;
+    }
+    public void hello() {
+        h++;
+    }
+}
+";
+        let f = run_actions(cont, AstPoint::new(3, 22), |d, c| {
+            toggle_final(d, c).unwrap()
+        });
+        let g = run_actions(cont, AstPoint::new(4, 16), |d, c| {
+            toggle_final(d, c).unwrap()
+        });
+        let h = run_actions(cont, AstPoint::new(5, 16), |d, c| {
+            toggle_final(d, c).unwrap()
+        });
+        let expected = expect![[r#"
+            (
+                [
+                    (
+                        "Remove final from f",
+                        "\npackage ch.emilycares;\npublic class Test {\n    private int f = 1;\n    private int g;\n    private int h = 0;\n    public Test() {\n        this.g = 1;\n    }\n    public void hello() {\n        h++;\n    }\n}\n",
+                    ),
+                ],
+                [
+                    (
+                        "Make g final",
+                        "\npackage ch.emilycares;\npublic class Test {\n    private final int f = 1;\n    private final int g;\n    private int h = 0;\n    public Test() {\n        this.g = 1;\n    }\n    public void hello() {\n        h++;\n    }\n}\n",
+                    ),
+                ],
+                [],
+            )
+        "#]];
+        expected.assert_debug_eq(&(f, g, h));
+    }
+
+    pub(crate) fn get_class_map() -> Arc<RwLock<HashMap<MyString, Class>>> {
+        let mut class_map: HashMap<MyString, Class> = HashMap::new();
+        class_map.insert(
+            SmolStr::new_inline("java.lang.String"),
+            Class {
+                access: Access::Public,
+                class_path: SmolStr::new_inline("java.lang.String"),
+                name: SmolStr::new_inline("String"),
+                methods: vec![
+                    Method {
+                        access: Access::Public,
+                        name: Some(SmolStr::new_inline("length")),
+                        ret: JType::Int,
+                        ..Default::default()
+                    },
+                    Method {
+                        access: Access::Public,
+                        name: Some(SmolStr::new_inline("run")),
+                        parameters: vec![Parameter {
+                            name: None,
+                            jtype: JType::Class(SmolStr::new_inline("java.lang.Runnable")),
+                            annotations: vec![],
+                        }],
+                        ret: JType::Void,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+        class_map.insert(
+            SmolStr::new_inline("java.lang.Runnable"),
+            Class {
+                access: Access::Public | Access::Interface,
+                class_path: SmolStr::new_inline("java.lang.Runnable"),
+                name: SmolStr::new_inline("Runnable"),
+                methods: vec![Method {
+                    access: Access::Public | Access::Abstract,
+                    name: Some(SmolStr::new_inline("run")),
+                    ret: JType::Void,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        );
+        class_map.insert(
+            SmolStr::new("java.util.function.Function"),
+            Class {
+                access: Access::Public | Access::Interface,
+                class_path: SmolStr::new("java.util.function.Function"),
+                name: SmolStr::new_inline("Function"),
+                signature: Some(dto::ClassSignature {
+                    args: vec![SmolStr::new_inline("T"), SmolStr::new_inline("R")],
+                    ..Default::default()
+                }),
+                methods: vec![
+                    Method {
+                        access: Access::Public | Access::Abstract,
+                        name: Some(SmolStr::new_inline("apply")),
+                        parameters: vec![Parameter {
+                            name: None,
+                            jtype: JType::Parameter(SmolStr::new_inline("T")),
+                            annotations: vec![],
+                        }],
+                        ret: JType::Parameter(SmolStr::new_inline("R")),
+                        ..Default::default()
+                    },
+                    Method {
+                        access: Access::Public | Access::Static,
+                        name: Some(SmolStr::new_inline("identity")),
+                        ret: JType::Class(SmolStr::new("java.util.function.Function")),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+        for (class_path, super_class) in [
+            ("java.lang.Exception", "java.lang.Throwable"),
+            ("java.io.IOException", "java.lang.Exception"),
+            ("java.io.FileNotFoundException", "java.io.IOException"),
+        ] {
+            class_map.insert(
+                SmolStr::new(class_path),
+                Class {
+                    access: Access::Public,
+                    class_path: SmolStr::new(class_path),
+                    name: SmolStr::new(class_path.rsplit_once('.').unwrap().1),
+                    super_class: SuperClass::ClassPath(SmolStr::new(super_class)),
+                    ..Default::default()
+                },
+            );
+        }
+        class_map.insert(
+            SmolStr::new("java.io.FileInputStream"),
+            Class {
+                access: Access::Public,
+                class_path: SmolStr::new("java.io.FileInputStream"),
+                name: SmolStr::new("FileInputStream"),
+                methods: vec![
+                    Method {
+                        access: Access::Public,
+                        name: None,
+                        parameters: vec![Parameter {
+                            name: None,
+                            jtype: JType::Class(SmolStr::new_inline("java.lang.String")),
+                            annotations: vec![],
+                        }],
+                        throws: vec![JType::Class(SmolStr::new("java.io.FileNotFoundException"))],
+                        ret: JType::Void,
+                        ..Default::default()
+                    },
+                    Method {
+                        access: Access::Public,
+                        name: Some(SmolStr::new_inline("read")),
+                        throws: vec![JType::Class(SmolStr::new("java.io.IOException"))],
+                        ret: JType::Int,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+        Arc::new(RwLock::new(class_map))
+    }
+}
@@ -3,16 +3,31 @@ use std::{
     sync::{Arc, Mutex, RwLock},
 };
 
-use ast::types::{AstFile, AstPoint};
+use ast::{
+    types::{
+        AstClassConstructor, AstExpressionIdentifier, AstExpressionKind, AstExpressionOperator,
+        AstFile, AstIdentifier, AstJType, AstJTypeKind, AstMethodParameterFlags, AstNewClass,
+        AstNewRhs, AstPoint, AstRange,
+    },
+    visit::{self, Visitor},
+};
 use call_chain::CallItem;
-use document::Document;
-use dto::{Class, ImportUnit};
+use document::{Document, get_class_path, read_document_or_open_class};
+use dto::{Access, Class, ImportUnit, Method, SourceDestination};
 use local_variable::LocalVariable;
 use lsp_extra::{SourceToUriError, ToLspRangeError, source_to_uri, to_lsp_range};
 use lsp_types::Location;
 use my_string::MyString;
 use position::PositionSymbol;
 
+use variables::VariableContext;
+
+use crate::{
+    implementation,
+    name_index::{NameIndex, simple_name},
+    subtypes::SubtypeIndex,
+};
+
 #[derive(Debug)]
 pub enum ReferencesError {
     Tyres(tyres::TyresError),
@@ -40,54 +55,242 @@ pub struct ReferencesContext<'a> {
     pub vars: &'a [LocalVariable],
 }
 
-#[must_use]
-pub fn class_path(
+/// Request for the references of the constructors of the class at the position, the
+/// parameters are [`lsp_types::TextDocumentPositionParams`]
+pub const CONSTRUCTOR_REFERENCES: &str = "java/constructorReferences";
+
+/// Constructors of a class to find the calls of
+#[derive(Debug)]
+pub struct Constructor {
+    pub class_path: MyString,
+    /// Number of parameters of a single constructor, none for all constructors and varargs
+    pub arity: Option<usize>,
+}
+
+/// Sources of the classes that import the class and mention its name
+fn referencing_sources(
     class_path: &str,
     reference_map: &Arc<Mutex<HashMap<MyString, Vec<ReferenceUnit>>>>,
     class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
-    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
-) -> Option<Vec<Location>> {
-    if let Ok(class_map) = class_map.read()
-        && let Ok(reference_map) = reference_map.lock()
-        && let Some(crefs) = reference_map.get(class_path)
-        && let Ok(document_map) = document_map.read()
-    {
-        let refs = crefs
+    names: &NameIndex,
+) -> Option<Vec<MyString>> {
+    let mut sources: Vec<MyString> = {
+        let class_map = class_map.read().ok()?;
+        let reference_map = reference_map.lock().ok()?;
+        reference_map
+            .get(class_path)?
             .iter()
             .filter_map(|i| match i {
                 ReferenceUnit::Class(s) | ReferenceUnit::StaticClass(s) => class_map.get(s),
             })
             .filter_map(Class::get_source)
-            .filter_map(|i| document_map.get(&i))
-            .filter_map(|lookup| {
-                let refs = pos_refs_helper(&lookup.ast, class_path);
-                let a = refs.first().map(|i| i.0.range);
-                a.map(|a| (lookup, a))
-            })
-            .filter_map(
-                |(lookup, range)| match source_to_uri(lookup.path.to_str()?) {
-                    Ok(u) => Some((u, range)),
-                    Err(e) => {
-                        eprintln!("References Uri error {e:?}");
-                        None
-                    }
-                },
-            )
-            .filter_map(|(i, range)| {
-                let range = to_lsp_range(&range).ok()?;
-                Some(Location { uri: i, range })
-            })
-            .collect();
-        return Some(refs);
+            .collect()
+    };
+    names.retain_mentioning(&mut sources, simple_name(class_path));
+    Some(sources)
+}
+
+/// References of the class in the files that import it and mention its name
+#[must_use]
+pub fn class_path(
+    class_path: &str,
+    reference_map: &Arc<Mutex<HashMap<MyString, Vec<ReferenceUnit>>>>,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+    names: &NameIndex,
+    cancelled: &dyn Fn() -> bool,
+) -> Option<Vec<Location>> {
+    let sources = referencing_sources(class_path, reference_map, class_map, names)?;
+    let refs = sources
+        .iter()
+        .take_while(|_| !cancelled())
+        .filter_map(|source| read_document_or_open_class(source, document_map).ok())
+        .filter_map(|lookup| {
+            let refs = pos_refs_helper(&lookup.ast, class_path);
+            let a = refs.first().map(|i| i.0.range);
+            a.map(|a| (lookup, a))
+        })
+        .filter_map(
+            |(lookup, range)| match source_to_uri(lookup.path.to_str()?) {
+                Ok(u) => Some((u, range)),
+                Err(e) => {
+                    tracing::debug!("References Uri error {e:?}");
+                    None
+                }
+            },
+        )
+        .filter_map(|(i, range)| {
+            let range = to_lsp_range(&range).ok()?;
+            Some(Location { uri: i, range })
+        })
+        .collect();
+    Some(refs)
+}
+
+/// Simple name of the class and the number of parameters of the constructor declared at the
+/// point
+#[must_use]
+pub fn constructor_at(ast: &AstFile, point: &AstPoint) -> Option<(MyString, Option<usize>)> {
+    struct Declaration<'a> {
+        point: &'a AstPoint,
+        found: Option<&'a AstClassConstructor>,
+    }
+    impl<'a> Visitor<'a> for Declaration<'a> {
+        fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+            if constructor.header.name.range.is_in_range(self.point) {
+                self.found = Some(constructor);
+            }
+            visit::walk_class_constructor(self, constructor);
+        }
+    }
+    let mut declaration = Declaration { point, found: None };
+    declaration.visit_file(ast);
+    let header = &declaration.found?.header;
+    let parameters = &header.parameters.parameters;
+    let varargs = parameters
+        .last()
+        .is_some_and(|p| p.flags.contains(AstMethodParameterFlags::Variatic));
+    Some((
+        header.name.value.clone(),
+        (!varargs).then_some(parameters.len()),
+    ))
+}
+
+/// `new Foo(...)` and `Foo::new` of the class in its own file and the files that import it
+#[must_use]
+pub fn constructor(
+    constructor: &Constructor,
+    reference_map: &Arc<Mutex<HashMap<MyString, Vec<ReferenceUnit>>>>,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+    names: &NameIndex,
+    cancelled: &dyn Fn() -> bool,
+) -> Option<Vec<Location>> {
+    let class_path = constructor.class_path.as_str();
+    let own = class_map
+        .read()
+        .ok()?
+        .get(class_path)
+        .and_then(Class::get_source);
+    let mut sources =
+        referencing_sources(class_path, reference_map, class_map, names).unwrap_or_default();
+    if let Some(own) = own
+        && !sources.contains(&own)
+    {
+        sources.insert(0, own);
+    }
+    let name = simple_name(class_path)
+        .rsplit('$')
+        .next()
+        .unwrap_or_default();
+    let mut locations = Vec::new();
+    for source in sources.iter().take_while(|_| !cancelled()) {
+        let Ok(document) = read_document_or_open_class(source, document_map) else {
+            continue;
+        };
+        let Some(uri) = document.path.to_str().and_then(|p| source_to_uri(p).ok()) else {
+            continue;
+        };
+        let mut calls = ConstructorCalls {
+            name,
+            arity: constructor.arity,
+            found: Vec::new(),
+        };
+        calls.visit_file(&document.ast);
+        locations.extend(
+            calls
+                .found
+                .iter()
+                .filter_map(|range| to_lsp_range(range).ok())
+                .map(|range| Location::new(uri.clone(), range)),
+        );
+    }
+    Some(locations)
+}
+
+/// Class names of the calls of a constructor
+struct ConstructorCalls<'n> {
+    name: &'n str,
+    arity: Option<usize>,
+    found: Vec<AstRange>,
+}
+
+impl ConstructorCalls<'_> {
+    /// Identifier of the class, `Inner` of `Outer.Inner`
+    fn class_name(jtype: &AstJType) -> Option<&AstIdentifier> {
+        match &jtype.value {
+            AstJTypeKind::Class(name) | AstJTypeKind::Generic(name, _) => Some(name),
+            AstJTypeKind::Access { inner, .. } => Self::class_name(inner),
+            _ => None,
+        }
+    }
+
+    fn arguments(new_class: &AstNewClass) -> Option<usize> {
+        match &*new_class.rhs {
+            AstNewRhs::Parameters(_, arguments)
+            | AstNewRhs::ParametersAndBlock(_, arguments, _) => Some(arguments.len()),
+            AstNewRhs::None
+            | AstNewRhs::ArrayParameters(_)
+            | AstNewRhs::Block(_)
+            | AstNewRhs::Array(_) => None,
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for ConstructorCalls<'_> {
+    fn visit_new_class(&mut self, new_class: &'a AstNewClass) {
+        if let Some(name) = Self::class_name(&new_class.jtype)
+            && name.value == self.name
+            && let Some(arguments) = Self::arguments(new_class)
+            && self.arity.is_none_or(|arity| arity == arguments)
+        {
+            self.found.push(name.range);
+        }
+        visit::walk_new_class(self, new_class);
+    }
+
+    fn visit_expression(&mut self, expression: &'a [AstExpressionKind]) {
+        let ident = |kind: &AstExpressionKind| match kind {
+            AstExpressionKind::Base(base) => match &base.ident {
+                Some(AstExpressionIdentifier::Identifier(ident)) => Some(ident.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+        for window in expression.windows(3) {
+            if let [class, AstExpressionKind::Base(colon), new] = window
+                && matches!(colon.operator, AstExpressionOperator::ColonColon(_))
+                && let Some(class) = ident(class)
+                && class.value == self.name
+                && ident(new).is_some_and(|new| new.value == "new")
+            {
+                self.found.push(class.range);
+            }
+        }
+        visit::walk_expression(self, expression);
     }
-    None
+}
+
+/// Method of a class to find the references of
+#[derive(Debug)]
+pub struct MethodQuery {
+    pub class_path: MyString,
+    pub name: MyString,
+    /// Overload that was selected, all overloads with the name when None
+    pub method: Option<Method>,
+    /// Include the methods that override it and the calls through the supertypes that declare
+    /// it
+    pub overrides: bool,
 }
 
 pub fn call_chain_references(
     call_chain: &[CallItem],
     context: &ReferencesContext,
+    overrides: bool,
+    subtypes: &SubtypeIndex,
     reference_map: &Arc<Mutex<HashMap<MyString, Vec<ReferenceUnit>>>>,
     document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+    names: &NameIndex,
 ) -> Result<Vec<Location>, ReferencesError> {
     let (item, relevant) = call_chain::validate(call_chain, context.point);
 
@@ -101,35 +304,37 @@ pub fn call_chain_references(
     .map_err(ReferencesError::Tyres)?;
 
     match relevant.get(item) {
-        Some(CallItem::MethodCall { name, .. }) => {
-            let mut locations = vec![];
-            if let Ok(reference_map) = reference_map.lock()
-                && let Some(used_in) = reference_map.get(&reference_state.class.class_path)
-                && let Ok(class_map) = context.class_map.read()
-            {
-                for ref_unit in used_in {
-                    let Some(class) = (match ref_unit {
-                        ReferenceUnit::Class(c) | ReferenceUnit::StaticClass(c) => class_map.get(c),
-                    }) else {
-                        continue;
-                    };
-                    let method_refs = method_references(class, name, document_map)?;
-                    let Some(source) = class.get_source() else {
-                        return Err(ReferencesError::NoSource);
-                    };
-                    let uri = source_to_uri(&source).map_err(|e| {
-                        eprintln!("Got into definition error: {e:?}");
-                        ReferencesError::SourceToUri(e)
-                    })?;
-                    for i in method_refs {
-                        let r = to_lsp_range(&i.0.range).map_err(ReferencesError::ToLspRange)?;
-                        let loc = Location::new(uri.clone(), r);
-                        locations.push(loc);
-                    }
-                }
-            }
-
-            Ok(locations)
+        Some(CallItem::MethodCall { name, args, .. }) => {
+            let arguments = tyres::resolve_arguments(
+                args,
+                context.vars,
+                context.imports,
+                context.class,
+                &context.class_map,
+            );
+            let selected = tyres::select_methods(
+                &reference_state.class,
+                Some(name),
+                &arguments,
+                &context.class_map,
+            )
+            .first()
+            .map(|m| (*m).clone());
+            let query = MethodQuery {
+                class_path: reference_state.class.class_path,
+                name: name.clone(),
+                method: selected,
+                overrides,
+            };
+            Ok(method(
+                &query,
+                subtypes,
+                reference_map,
+                &context.class_map,
+                document_map,
+                names,
+                &|| false,
+            ))
         }
         Some(CallItem::ArgumentList {
             prev: _,
@@ -140,7 +345,15 @@ pub fn call_chain_references(
             if let Some(active_param) = active_param
                 && let Some(current_param) = filled_params.get(*active_param)
             {
-                return call_chain_references(current_param, context, reference_map, document_map);
+                return call_chain_references(
+                    current_param,
+                    context,
+                    overrides,
+                    subtypes,
+                    reference_map,
+                    document_map,
+                    names,
+                );
             }
             Err(ReferencesError::ArgumentNotFound)
         }
@@ -149,15 +362,207 @@ pub fn call_chain_references(
     }
 }
 
-/// remove clippy when done
-#[allow(clippy::nursery, clippy::pedantic)]
-fn method_references(
-    _class: &Class,
-    _query_method_name: &str,
-    _document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
-) -> Result<Vec<ReferencePosition>, ReferencesError> {
-    //TODO
-    Ok(Vec::new())
+/// Calls of the method on the class and its subtypes in the files that mention it
+#[must_use]
+pub fn method(
+    query: &MethodQuery,
+    subtypes: &SubtypeIndex,
+    reference_map: &Arc<Mutex<HashMap<MyString, Vec<ReferenceUnit>>>>,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+    names: &NameIndex,
+    cancelled: &dyn Fn() -> bool,
+) -> Vec<Location> {
+    let class_path = query.class_path.as_str();
+    let mut receivers = vec![query.class_path.clone()];
+    receivers.extend(subtypes.all_subtypes(class_path));
+    if query.overrides {
+        let arguments = query.method.as_ref().map(overload::parameter_arguments);
+        let supertypes: Vec<Class> = class_map.read().map_or_else(
+            |_| vec![],
+            |class_map| {
+                subtypes
+                    .all_supertypes(class_path)
+                    .iter()
+                    .filter_map(|supertype| class_map.get(supertype).cloned())
+                    .collect()
+            },
+        );
+        receivers.extend(
+            supertypes
+                .into_iter()
+                .filter(|c| {
+                    arguments.as_ref().map_or_else(
+                        || {
+                            c.methods
+                                .iter()
+                                .any(|m| m.name.as_ref() == Some(&query.name))
+                        },
+                        |arguments| {
+                            !tyres::select_methods(c, Some(&query.name), arguments, class_map)
+                                .is_empty()
+                        },
+                    )
+                })
+                .map(|c| c.class_path),
+        );
+    }
+    let mut sources: Vec<MyString> = vec![];
+    for receiver in &receivers {
+        let own = class_map
+            .read()
+            .ok()
+            .and_then(|c| c.get(receiver).and_then(Class::get_source));
+        let referencing =
+            referencing_sources(receiver, reference_map, class_map, names).unwrap_or_default();
+        for source in own.into_iter().chain(referencing) {
+            if !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+    }
+    names.retain_mentioning(&mut sources, &query.name);
+    let mut locations = vec![];
+    for source in sources.iter().take_while(|_| !cancelled()) {
+        let Ok(document) = read_document_or_open_class(source, document_map) else {
+            continue;
+        };
+        let Some(uri) = document.path.to_str().and_then(|p| source_to_uri(p).ok()) else {
+            continue;
+        };
+        locations.extend(
+            method_calls(&document.ast, query, &receivers, class_map)
+                .iter()
+                .filter_map(|range| to_lsp_range(range).ok())
+                .map(|range| Location::new(uri.clone(), range)),
+        );
+    }
+    if query.overrides {
+        locations.extend(implementation::method_implementations(
+            class_path,
+            &query.name,
+            query.method.as_ref(),
+            subtypes,
+            class_map,
+            document_map,
+        ));
+    }
+    locations
+}
+
+/// Names of the calls of the method in the file whose receiver is one of the classes
+fn method_calls(
+    ast: &AstFile,
+    query: &MethodQuery,
+    receivers: &[MyString],
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<AstRange> {
+    let mut calls = MethodCalls {
+        name: Some(&query.name),
+        nargs: query
+            .method
+            .as_ref()
+            .filter(|m| !m.access.contains(Access::Varargs))
+            .map(|m| m.parameters.len()),
+        found: vec![],
+    };
+    calls.visit_file(ast);
+    if calls.found.is_empty() {
+        return vec![];
+    }
+    let class = file_class(ast, class_map);
+    let imports = imports::imports(ast);
+    calls
+        .found
+        .into_iter()
+        .filter(|(ident, _)| {
+            call_receiver(ast, &ident.range.start, &imports, &class, class_map)
+                .is_some_and(|receiver| receivers.contains(&receiver))
+        })
+        .map(|(ident, _)| ident.range)
+        .collect()
+}
+
+/// Class of the file from the class map, parsed from the file when it is not in there
+pub(crate) fn file_class(
+    ast: &AstFile,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Class {
+    get_class_path(ast)
+        .and_then(|class_path| class_map.read().ok()?.get(&class_path).cloned())
+        .unwrap_or_else(|| parser::java::load_java_tree(ast, SourceDestination::None))
+}
+
+/// Class path of the class that the method called at the point is resolved in
+pub(crate) fn call_receiver(
+    ast: &AstFile,
+    point: &AstPoint,
+    imports: &[ImportUnit],
+    class: &Class,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Option<MyString> {
+    let call_chain = call_chain::flatten_argument_lists(&call_chain::get_call_chain(ast, point));
+    let (item, relevant) = call_chain::validate(&call_chain, point);
+    if !matches!(relevant.get(item), Some(CallItem::MethodCall { .. })) {
+        return None;
+    }
+    // Without a receiver the method is one of the class
+    if item == 0 {
+        return Some(class.class_path.clone());
+    }
+    let vars = variables::get_vars(
+        ast,
+        &VariableContext {
+            point: Some(*point),
+            imports,
+            class,
+            class_map: class_map.clone(),
+        },
+    )
+    .ok()?;
+    tyres::resolve_call_chain_to_point(&relevant, &vars, imports, class, class_map, point)
+        .ok()
+        .map(|resolved| resolved.class.class_path)
+}
+
+/// Names of the method calls with the name, or of all calls, and their number of arguments
+pub(crate) struct MethodCalls<'a, 'n> {
+    pub name: Option<&'n str>,
+    pub nargs: Option<usize>,
+    pub found: Vec<(&'a AstIdentifier, usize)>,
+}
+
+impl<'a> Visitor<'a> for MethodCalls<'a, '_> {
+    fn visit_expression(&mut self, expression: &'a [AstExpressionKind]) {
+        for (i, kind) in expression.iter().enumerate() {
+            let AstExpressionKind::Base(base) = kind else {
+                continue;
+            };
+            let Some(AstExpressionIdentifier::Identifier(ident)) = &base.ident else {
+                continue;
+            };
+            if self.name.is_some_and(|name| ident.value != name) {
+                continue;
+            }
+            let arguments = base
+                .values
+                .as_ref()
+                .or_else(|| match expression.get(i + 1) {
+                    Some(AstExpressionKind::Base(next)) if next.ident.is_none() => {
+                        next.values.as_ref()
+                    }
+                    _ => None,
+                });
+            if let Some(arguments) = arguments
+                && self
+                    .nargs
+                    .is_none_or(|nargs| nargs == arguments.values.len())
+            {
+                self.found.push((ident, arguments.values.len()));
+            }
+        }
+        visit::walk_expression(self, expression);
+    }
 }
 
 pub fn init_reference_map(
@@ -259,3 +664,83 @@ fn get_implicit_imports(
         .map(|i| i.0)
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ast::{types::AstPoint, visit::Visitor};
+    use document::Document;
+
+    use super::{ConstructorCalls, MethodCalls, constructor_at};
+
+    const CONTENT: &str = "
+package ch.emilycares;
+public class Foo {
+    public Foo() {}
+    public Foo(int a) {}
+    public static Foo create(int a) {
+        Supplier<Foo> s = Foo::new;
+        Foo b = new Foo();
+        return new Foo(a + new Bar(1).size());
+    }
+}
+";
+
+    fn calls(arity: Option<usize>) -> Vec<String> {
+        let document = Document::setup(CONTENT, PathBuf::new()).unwrap();
+        let mut calls = ConstructorCalls {
+            name: "Foo",
+            arity,
+            found: Vec::new(),
+        };
+        calls.visit_file(&document.ast);
+        calls
+            .found
+            .iter()
+            .map(|range| {
+                format!(
+                    "{} {}:{}",
+                    document.range_text(range),
+                    range.start.line,
+                    range.start.col
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn constructor_calls() {
+        assert_eq!(calls(None), ["Foo 6:26", "Foo 7:20", "Foo 8:19"]);
+        assert_eq!(calls(Some(1)), ["Foo 6:26", "Foo 8:19"]);
+    }
+
+    #[test]
+    fn constructor_declaration() {
+        let document = Document::setup(CONTENT, PathBuf::new()).unwrap();
+        let at = |line, col| constructor_at(&document.ast, &AstPoint::new(line, col));
+        assert_eq!(at(3, 12), Some(("Foo".into(), Some(0))));
+        assert_eq!(at(4, 13), Some(("Foo".into(), Some(1))));
+        assert_eq!(at(2, 14), None);
+    }
+
+    #[test]
+    fn method_calls() {
+        let document = Document::setup(CONTENT, PathBuf::new()).unwrap();
+        let found = |nargs| {
+            let mut calls = MethodCalls {
+                name: Some("size"),
+                nargs,
+                found: Vec::new(),
+            };
+            calls.visit_file(&document.ast);
+            calls
+                .found
+                .iter()
+                .map(|(ident, _)| format!("{}:{}", ident.range.start.line, ident.range.start.col))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found(Some(0)), ["8:38"]);
+        assert_eq!(found(Some(1)), Vec::<String>::new());
+    }
+}
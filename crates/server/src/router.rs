@@ -1,32 +1,51 @@
 use config::{Configuration, FormatterConfig};
 use lsp_types::{
-    CodeActionKind, CodeActionOptions, CodeActionParams, CodeActionProviderCapability,
-    CodeLensOptions, CodeLensParams, CompletionOptions, CompletionParams,
-    DidChangeTextDocumentParams, DidChangeWorkspaceFoldersParams, DidCloseTextDocumentParams,
-    DidOpenTextDocumentParams, DidSaveTextDocumentParams, DocumentFormattingParams,
+    CancelParams, CodeActionKind, CodeActionOptions, CodeActionParams,
+    CodeActionProviderCapability, CodeLensOptions, CodeLensParams, CompletionOptions,
+    CompletionParams, DiagnosticOptions, DiagnosticServerCapabilities,
+    DidChangeConfigurationParams, DidChangeTextDocumentParams, DidChangeWatchedFilesParams,
+    DidChangeWorkspaceFoldersParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, DocumentDiagnosticParams, DocumentFormattingParams,
     DocumentLinkOptions, DocumentLinkParams, DocumentSymbolParams, ExecuteCommandOptions,
     ExecuteCommandParams, FoldingRangeParams, GotoDefinitionParams, HoverParams,
-    HoverProviderCapability, InlayHintParams, OneOf, ReferenceParams, ServerCapabilities,
-    SignatureHelpOptions, SignatureHelpParams, TextDocumentSyncCapability, TextDocumentSyncKind,
-    TextDocumentSyncOptions, WorkDoneProgressOptions, WorkspaceFoldersServerCapabilities,
-    WorkspaceServerCapabilities,
+    HoverProviderCapability, ImplementationProviderCapability, InlayHintParams, OneOf,
+    ReferenceParams, ServerCapabilities, SignatureHelpOptions, SignatureHelpParams,
+    TextDocumentPositionParams, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextDocumentSyncOptions, TypeHierarchyPrepareParams, TypeHierarchySubtypesParams,
+    TypeHierarchySupertypesParams, Uri, WorkDoneProgressOptions, WorkspaceDiagnosticParams,
+    WorkspaceFoldersServerCapabilities, WorkspaceServerCapabilities, WorkspaceSymbol,
+    WorkspaceSymbolOptions, WorkspaceSymbolParams,
     notification::{
-        Cancel, DidChangeConfiguration, DidChangeTextDocument, DidChangeWorkspaceFolders,
-        DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument, Notification, SetTrace,
+        Cancel, DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles,
+        DidChangeWorkspaceFolders, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Exit, Notification, SetTrace,
     },
     request::{
-        CodeActionRequest, CodeLensRequest, Completion, DocumentLinkRequest, DocumentSymbolRequest,
-        ExecuteCommand, FoldingRangeRequest, Formatting, GotoDefinition, HoverRequest,
-        InlayHintRequest, References, Request, SignatureHelpRequest,
+        CodeActionRequest, CodeLensRequest, Completion, DocumentDiagnosticRequest,
+        DocumentLinkRequest, DocumentSymbolRequest, ExecuteCommand, FoldingRangeRequest,
+        Formatting, GotoDefinition, GotoImplementation, GotoImplementationParams, HoverRequest,
+        InlayHintRequest, References, Request, Shutdown, SignatureHelpRequest,
+        TypeHierarchyPrepare, TypeHierarchySubtypes, TypeHierarchySupertypes,
+        WorkspaceDiagnosticRequest, WorkspaceSymbolRequest, WorkspaceSymbolResolve,
     },
 };
 
+use lsp_extra::SERVER_NAME;
 use lsp_server::{Message, RequestId, Response};
 use serde_json::{Value, from_value, to_value};
 
 use crate::{
     backend::Backend,
-    command::{COMMAND_CMD, COMMAND_RELOAD_DEPENDENCIES, COMMAND_UPDATE_DEPENDENCIES},
+    call_graph,
+    cancel::{cancelled_response, content_modified_response},
+    class_contents,
+    command::{
+        COMMAND_CMD, COMMAND_COMPILE, COMMAND_DEPENDENCY_TREE, COMMAND_RELOAD_DEPENDENCIES,
+        COMMAND_UPDATE_DEPENDENCIES,
+    },
+    dead_code, debug, implementation, import_rank, junit, main_class,
+    panic_guard::{self, internal_error_response},
+    refactor, references, status,
 };
 
 #[must_use]
@@ -38,6 +57,16 @@ pub fn get_server_capabilities(config: &Configuration) -> ServerCapabilities {
     if !config.editor_runs_commands {
         commands.push(COMMAND_CMD.to_owned());
     }
+    commands.push(COMMAND_DEPENDENCY_TREE.to_owned());
+    commands.push(COMMAND_COMPILE.to_owned());
+    commands.push(dead_code::COMMAND_DEAD_CODE.to_owned());
+    commands.push(call_graph::COMMAND_CALL_GRAPH.to_owned());
+    commands.push(refactor::COMMAND_MOVE_CLASS.to_owned());
+    commands.push(junit::COMMAND_RUN_TEST.to_owned());
+    commands.push(debug::COMMAND_DEBUG_CONFIG.to_owned());
+    commands.push(main_class::COMMAND_MAIN_CLASSES.to_owned());
+    commands.push(main_class::COMMAND_RUN_MAIN.to_owned());
+    commands.push(import_rank::COMMAND_IMPORT_CHOSEN.to_owned());
     ServerCapabilities {
         text_document_sync: Some(TextDocumentSyncCapability::Options(
             TextDocumentSyncOptions {
@@ -50,8 +79,13 @@ pub fn get_server_capabilities(config: &Configuration) -> ServerCapabilities {
         )),
         definition_provider: Some(OneOf::Left(true)),
         references_provider: Some(OneOf::Left(true)),
+        implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
         code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
-            code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+            code_action_kinds: Some(vec![
+                CodeActionKind::QUICKFIX,
+                CodeActionKind::REFACTOR,
+                CodeActionKind::REFACTOR_REWRITE,
+            ]),
             ..CodeActionOptions::default()
         })),
         completion_provider: Some(CompletionOptions {
@@ -59,6 +93,10 @@ pub fn get_server_capabilities(config: &Configuration) -> ServerCapabilities {
             ..CompletionOptions::default()
         }),
         document_symbol_provider: Some(OneOf::Left(true)),
+        workspace_symbol_provider: Some(OneOf::Right(WorkspaceSymbolOptions {
+            resolve_provider: Some(true),
+            work_done_progress_options: WorkDoneProgressOptions::default(),
+        })),
         document_formatting_provider: Some(OneOf::Left(config.formatter != FormatterConfig::None)),
         hover_provider: Some(HoverProviderCapability::Simple(true)),
         signature_help_provider: Some(SignatureHelpOptions {
@@ -83,6 +121,12 @@ pub fn get_server_capabilities(config: &Configuration) -> ServerCapabilities {
             resolve_provider: None,
         }),
         folding_range_provider: Some(lsp_types::FoldingRangeProviderCapability::Simple(true)),
+        diagnostic_provider: Some(DiagnosticServerCapabilities::Options(DiagnosticOptions {
+            identifier: Some(SERVER_NAME.to_string()),
+            inter_file_dependencies: true,
+            workspace_diagnostics: true,
+            work_done_progress_options: WorkDoneProgressOptions::default(),
+        })),
         workspace: Some(WorkspaceServerCapabilities {
             workspace_folders: Some(WorkspaceFoldersServerCapabilities {
                 supported: Some(true),
@@ -93,141 +137,389 @@ pub fn get_server_capabilities(config: &Configuration) -> ServerCapabilities {
         ..Default::default()
     }
 }
-pub fn route(backend: &Backend) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-    while let Ok(msg) = backend.connection.receiver.recv() {
+pub fn route(
+    backend: &mut Backend,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    while let Some(msg) = backend.cancellation.next(&backend.connection) {
         match msg {
             Message::Request(req) => {
-                if backend.connection.handle_shutdown(&req)? {
+                if req.method == Shutdown::METHOD {
+                    send(backend, req.id, to_value(()).ok());
+                    wait_for_exit(backend);
                     break;
                 }
+                if !backend.cancellation.start(&req.id) {
+                    let _ = backend
+                        .connection
+                        .sender
+                        .send(Message::Response(cancelled_response(req.id)));
+                    continue;
+                }
 
-                match req.method.as_str() {
-                    HoverRequest::METHOD => {
-                        if let Ok(params) = from_value::<HoverParams>(req.params) {
-                            let result = backend.hover(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    Formatting::METHOD => {
-                        if let Ok(params) = from_value::<DocumentFormattingParams>(req.params) {
-                            let result = backend.formatting(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    GotoDefinition::METHOD => {
-                        if let Ok(params) = from_value::<GotoDefinitionParams>(req.params) {
-                            let result = backend.goto_definition(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    Completion::METHOD => {
-                        if let Ok(params) = from_value::<CompletionParams>(req.params) {
-                            let result = backend.completion(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    References::METHOD => {
-                        if let Ok(params) = from_value::<ReferenceParams>(req.params) {
-                            let result = backend.references(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    CodeActionRequest::METHOD => {
-                        if let Ok(params) = from_value::<CodeActionParams>(req.params) {
-                            let result = backend.code_action(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    DocumentSymbolRequest::METHOD => {
-                        if let Ok(params) = from_value::<DocumentSymbolParams>(req.params) {
-                            let result = backend.document_symbol(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    SignatureHelpRequest::METHOD => {
-                        if let Ok(params) = from_value::<SignatureHelpParams>(req.params) {
-                            let result = backend.signature_help(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    ExecuteCommand::METHOD => {
-                        if let Ok(params) = from_value::<ExecuteCommandParams>(req.params) {
-                            let result = backend.execute_command(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    DocumentLinkRequest::METHOD => {
-                        if let Ok(params) = from_value::<DocumentLinkParams>(req.params) {
-                            let result = backend.document_link(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    InlayHintRequest::METHOD => {
-                        if let Ok(params) = from_value::<InlayHintParams>(req.params) {
-                            let result = backend.inlay_hint(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    CodeLensRequest::METHOD => {
-                        if let Ok(params) = from_value::<CodeLensParams>(req.params) {
-                            let result = backend.code_lens(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    FoldingRangeRequest::METHOD => {
-                        if let Ok(params) = from_value::<FoldingRangeParams>(req.params) {
-                            let result = backend.folding_range(params);
-                            send(backend, req.id, to_value(result).ok());
-                        }
-                    }
-                    r => {
-                        eprintln!("Got unsupported request: {r}");
-                    }
+                let id = req.id.clone();
+                let method = req.method.clone();
+                if let Err(message) = panic_guard::guard(&method, || handle_request(backend, req)) {
+                    backend.clear_poison();
+                    backend.cancellation.finish(&id);
+                    let _ = backend
+                        .connection
+                        .sender
+                        .send(Message::Response(internal_error_response(id, &message)));
                 }
+                backend.evict_documents();
             }
             Message::Response(resp) => {
-                eprintln!("got response: {resp:?}");
-            }
-            Message::Notification(not) => match not.method.as_str() {
-                DidOpenTextDocument::METHOD => {
-                    if let Ok(params) = from_value::<DidOpenTextDocumentParams>(not.params) {
-                        backend.did_open(&params);
-                    }
+                if backend.configuration_requests.answered(&resp.id) {
+                    backend.configuration_response(resp);
+                } else {
+                    tracing::debug!("got response: {resp:?}");
                 }
-                DidCloseTextDocument::METHOD => {
-                    if let Ok(params) = from_value::<DidCloseTextDocumentParams>(not.params) {
-                        backend.did_close(&params);
-                    }
+            }
+            Message::Notification(not) => {
+                let method = not.method.clone();
+                if panic_guard::guard(&method, || handle_notification(backend, not)).is_err() {
+                    backend.clear_poison();
                 }
-                DidChangeTextDocument::METHOD => {
-                    if let Ok(params) = from_value::<DidChangeTextDocumentParams>(not.params) {
-                        backend.did_change(&params);
-                    }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_request(backend: &Backend, req: lsp_server::Request) {
+    match req.method.as_str() {
+        HoverRequest::METHOD => {
+            if let Ok(params) = from_value::<HoverParams>(req.params) {
+                let issued = issued(
+                    backend,
+                    &params.text_document_position_params.text_document.uri,
+                );
+                let result = backend.hover(params);
+                respond(backend, req.id, Some(&issued), to_value(result).ok());
+            }
+        }
+        Formatting::METHOD => {
+            if let Ok(params) = from_value::<DocumentFormattingParams>(req.params) {
+                let issued = issued(backend, &params.text_document.uri);
+                let result = backend.formatting(params);
+                respond(backend, req.id, Some(&issued), to_value(result).ok());
+            }
+        }
+        GotoDefinition::METHOD => {
+            if let Ok(params) = from_value::<GotoDefinitionParams>(req.params) {
+                let result = backend.goto_definition(params);
+                send(backend, req.id, to_value(result).ok());
+            }
+        }
+        Completion::METHOD => {
+            if let Ok(params) = from_value::<CompletionParams>(req.params) {
+                let issued = issued(backend, &params.text_document_position.text_document.uri);
+                let result = backend.completion(params);
+                respond(backend, req.id, Some(&issued), to_value(result).ok());
+            }
+        }
+        References::METHOD => {
+            if let Ok(params) = from_value::<ReferenceParams>(req.params) {
+                let issued = issued(backend, &params.text_document_position.text_document.uri);
+                spawn(backend, req.id, Some(issued), move |backend| {
+                    to_value(backend.references(params)).ok()
+                });
+            }
+        }
+        CodeActionRequest::METHOD => {
+            if let Ok(params) = from_value::<CodeActionParams>(req.params) {
+                let issued = issued(backend, &params.text_document.uri);
+                let result = backend.code_action(params);
+                respond(backend, req.id, Some(&issued), to_value(result).ok());
+            }
+        }
+        DocumentSymbolRequest::METHOD => {
+            if let Ok(params) = from_value::<DocumentSymbolParams>(req.params) {
+                let result = backend.document_symbol(params);
+                send(backend, req.id, to_value(result).ok());
+            }
+        }
+        SignatureHelpRequest::METHOD => {
+            if let Ok(params) = from_value::<SignatureHelpParams>(req.params) {
+                let issued = issued(
+                    backend,
+                    &params.text_document_position_params.text_document.uri,
+                );
+                let result = backend.signature_help(params);
+                respond(backend, req.id, Some(&issued), to_value(result).ok());
+            }
+        }
+        ExecuteCommand::METHOD => {
+            if let Ok(params) = from_value::<ExecuteCommandParams>(req.params) {
+                // The build tool resolves the graph, that takes a while
+                if params.command == COMMAND_DEPENDENCY_TREE {
+                    spawn(backend, req.id, None, move |backend| {
+                        backend.dependency_tree(&params.arguments)
+                    });
+                    return;
                 }
-                DidSaveTextDocument::METHOD => {
-                    if let Ok(params) = from_value::<DidSaveTextDocumentParams>(not.params) {
-                        backend.did_save(&params);
-                    }
+                // Every source of the workspace is read
+                if params.command == dead_code::COMMAND_DEAD_CODE {
+                    spawn(backend, req.id, None, Backend::dead_code);
+                    return;
                 }
-                DidChangeWorkspaceFolders::METHOD => {
-                    if let Ok(params) = from_value::<DidChangeWorkspaceFoldersParams>(not.params) {
-                        backend.did_change_folders(&params);
-                    }
+                if params.command == refactor::COMMAND_MOVE_CLASS {
+                    spawn(backend, req.id, None, move |backend| {
+                        backend.move_class(&params.arguments)
+                    });
+                    return;
                 }
-                DidChangeConfiguration::METHOD | SetTrace::METHOD | Cancel::METHOD => {}
-                r => {
-                    eprintln!("Got unsupported notification: {r}");
+                if params.command == call_graph::COMMAND_CALL_GRAPH {
+                    spawn(backend, req.id, None, move |backend| {
+                        backend.call_graph(&params.arguments)
+                    });
+                    return;
                 }
-            },
+                let result = backend.execute_command(params);
+                send(backend, req.id, to_value(result).ok());
+            }
+        }
+        DocumentLinkRequest::METHOD => {
+            if let Ok(params) = from_value::<DocumentLinkParams>(req.params) {
+                let result = backend.document_link(params);
+                send(backend, req.id, to_value(result).ok());
+            }
+        }
+        InlayHintRequest::METHOD => {
+            if let Ok(params) = from_value::<InlayHintParams>(req.params) {
+                let issued = issued(backend, &params.text_document.uri);
+                let result = backend.inlay_hint(params);
+                respond(backend, req.id, Some(&issued), to_value(result).ok());
+            }
+        }
+        CodeLensRequest::METHOD => {
+            if let Ok(params) = from_value::<CodeLensParams>(req.params) {
+                let result = backend.code_lens(params);
+                send(backend, req.id, to_value(result).ok());
+            }
+        }
+        FoldingRangeRequest::METHOD => {
+            if let Ok(params) = from_value::<FoldingRangeParams>(req.params) {
+                let result = backend.folding_range(params);
+                send(backend, req.id, to_value(result).ok());
+            }
+        }
+        DocumentDiagnosticRequest::METHOD => {
+            if let Ok(params) = from_value::<DocumentDiagnosticParams>(req.params) {
+                let result = backend.document_diagnostic(&params);
+                send(backend, req.id, to_value(result).ok());
+            }
+        }
+        GotoImplementation::METHOD => {
+            if let Ok(params) = from_value::<GotoImplementationParams>(req.params) {
+                spawn(backend, req.id, None, move |backend| {
+                    to_value(backend.implementation(params)).ok()
+                });
+            }
+        }
+        TypeHierarchyPrepare::METHOD => {
+            if let Ok(params) = from_value::<TypeHierarchyPrepareParams>(req.params) {
+                let result = backend.prepare_type_hierarchy(params);
+                send(backend, req.id, to_value(result).ok());
+            }
+        }
+        TypeHierarchySupertypes::METHOD => {
+            if let Ok(params) = from_value::<TypeHierarchySupertypesParams>(req.params) {
+                spawn(backend, req.id, None, move |backend| {
+                    to_value(backend.type_hierarchy_supertypes(&params)).ok()
+                });
+            }
+        }
+        TypeHierarchySubtypes::METHOD => {
+            if let Ok(params) = from_value::<TypeHierarchySubtypesParams>(req.params) {
+                spawn(backend, req.id, None, move |backend| {
+                    to_value(backend.type_hierarchy_subtypes(&params)).ok()
+                });
+            }
+        }
+        WorkspaceSymbolRequest::METHOD => {
+            if let Ok(params) = from_value::<WorkspaceSymbolParams>(req.params) {
+                spawn(backend, req.id, None, move |backend| {
+                    to_value(backend.workspace_symbol(&params)).ok()
+                });
+            }
+        }
+        WorkspaceSymbolResolve::METHOD => {
+            if let Ok(params) = from_value::<WorkspaceSymbol>(req.params) {
+                spawn(backend, req.id, None, move |backend| {
+                    to_value(backend.workspace_symbol_resolve(params)).ok()
+                });
+            }
+        }
+        WorkspaceDiagnosticRequest::METHOD => {
+            if let Ok(params) = from_value::<WorkspaceDiagnosticParams>(req.params) {
+                spawn(backend, req.id, None, move |backend| {
+                    to_value(backend.workspace_diagnostic(&params)).ok()
+                });
+            }
+        }
+        class_contents::CLASS_CONTENTS => {
+            if let Ok(params) = from_value::<class_contents::ClassContentsParams>(req.params) {
+                let result = Backend::class_contents(&params);
+                send(backend, req.id, to_value(result).ok());
+            }
+        }
+        status::STATUS => {
+            send(backend, req.id, to_value(backend.status()).ok());
+        }
+        references::CONSTRUCTOR_REFERENCES => {
+            if let Ok(params) = from_value::<TextDocumentPositionParams>(req.params) {
+                let issued = issued(backend, &params.text_document.uri);
+                spawn(backend, req.id, Some(issued), move |backend| {
+                    to_value(backend.constructor_references(&params)).ok()
+                });
+            }
+        }
+        implementation::FIND_OVERRIDES => {
+            if let Ok(params) = from_value::<TextDocumentPositionParams>(req.params) {
+                let issued = issued(backend, &params.text_document.uri);
+                spawn(backend, req.id, Some(issued), move |backend| {
+                    to_value(backend.find_overrides(&params)).ok()
+                });
+            }
+        }
+        junit::TESTS => {
+            if let Ok(params) = from_value::<junit::TestsParams>(req.params) {
+                let result = backend.tests(&params);
+                send(backend, req.id, to_value(result).ok());
+            }
+        }
+        r => {
+            tracing::debug!("Got unsupported request: {r}");
+        }
+    }
+}
+
+fn handle_notification(backend: &mut Backend, not: lsp_server::Notification) {
+    match not.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            if let Ok(params) = from_value::<DidOpenTextDocumentParams>(not.params) {
+                backend.did_open(&params);
+                backend.evict_documents();
+            }
+        }
+        DidCloseTextDocument::METHOD => {
+            if let Ok(params) = from_value::<DidCloseTextDocumentParams>(not.params) {
+                backend.did_close(&params);
+            }
+        }
+        DidChangeTextDocument::METHOD => {
+            if let Ok(params) = from_value::<DidChangeTextDocumentParams>(not.params) {
+                backend.did_change(&params);
+            }
+        }
+        DidSaveTextDocument::METHOD => {
+            if let Ok(params) = from_value::<DidSaveTextDocumentParams>(not.params) {
+                backend.did_save(&params);
+            }
+        }
+        DidChangeWorkspaceFolders::METHOD => {
+            if let Ok(params) = from_value::<DidChangeWorkspaceFoldersParams>(not.params) {
+                backend.did_change_folders(&params);
+            }
+        }
+        DidChangeWatchedFiles::METHOD => {
+            if let Ok(params) = from_value::<DidChangeWatchedFilesParams>(not.params) {
+                backend.did_change_watched_files(&params);
+            }
+        }
+        DidChangeConfiguration::METHOD => {
+            if let Ok(params) = from_value::<DidChangeConfigurationParams>(not.params) {
+                backend.did_change_configuration(&params);
+            }
+        }
+        Cancel::METHOD => {
+            if let Ok(params) = from_value::<CancelParams>(not.params) {
+                backend.cancellation.cancel(params);
+            }
+        }
+        SetTrace::METHOD => {}
+        r => {
+            tracing::debug!("Got unsupported notification: {r}");
         }
     }
-    Ok(())
 }
 
 fn send(backend: &Backend, id: RequestId, result: Option<Value>) {
-    let _ = backend.connection.sender.send(Message::Response(Response {
-        id,
-        result,
-        error: None,
-    }));
+    let response = if backend.cancellation.finish(&id) {
+        Response {
+            id,
+            result,
+            error: None,
+        }
+    } else {
+        cancelled_response(id)
+    };
+    let _ = backend.connection.sender.send(Message::Response(response));
+}
+
+/// Slow requests run on the blocking pool with a snapshot of the backend, so edits and
+/// completions are handled while they run
+fn spawn(
+    backend: &Backend,
+    id: RequestId,
+    issued: Option<Issued>,
+    handler: impl FnOnce(&Backend) -> Option<Value> + Send + 'static,
+) {
+    backend.cancellation.detach(&id);
+    let backend = backend.clone();
+    tokio::task::spawn_blocking(move || {
+        let out = panic_guard::guard("background request", || {
+            backend.cancellation.background(&id, || handler(&backend))
+        });
+        match out {
+            Ok(result) => respond(&backend, id, issued.as_ref(), result),
+            Err(message) => {
+                backend.clear_poison();
+                backend.cancellation.finish(&id);
+                let _ = backend
+                    .connection
+                    .sender
+                    .send(Message::Response(internal_error_response(id, &message)));
+            }
+        }
+    });
+}
+
+/// Document of a request and its version when the request was received
+struct Issued {
+    uri: Uri,
+    version: Option<i32>,
+}
+
+fn issued(backend: &Backend, uri: &Uri) -> Issued {
+    Issued {
+        uri: uri.clone(),
+        version: backend.document_version(uri),
+    }
+}
+
+/// Results computed for a document that changed in the meantime are not sent, the client
+/// asks again for the new text
+fn respond(backend: &Backend, id: RequestId, issued: Option<&Issued>, result: Option<Value>) {
+    if let Some(issued) = issued
+        && backend.content_modified(&issued.uri, issued.version)
+    {
+        backend.cancellation.finish(&id);
+        let _ = backend
+            .connection
+            .sender
+            .send(Message::Response(content_modified_response(id)));
+        return;
+    }
+    send(backend, id, result);
+}
+
+/// After the shutdown response only the exit notification is expected
+fn wait_for_exit(backend: &Backend) {
+    while let Some(msg) = backend.cancellation.next(&backend.connection) {
+        if matches!(&msg, Message::Notification(not) if not.method == Exit::METHOD) {
+            break;
+        }
+    }
 }
@@ -0,0 +1,95 @@
+//! Documents without a file, like the `untitled:` buffers of editors
+use ast::types::AstFile;
+use dto::Class;
+use lsp_types::Uri;
+use my_string::{MyString, smol_str::ToSmolStr};
+
+use crate::class_contents;
+
+/// The language id of documents that are java
+pub const LANGUAGE_ID: &str = "java";
+
+/// Neither a file nor a read only `jdt` document
+#[must_use]
+pub fn is_scratch(uri: &Uri) -> bool {
+    uri.scheme().is_some_and(|s| {
+        let scheme = s.as_str();
+        !scheme.eq_ignore_ascii_case("file") && !scheme.eq_ignore_ascii_case(class_contents::SCHEME)
+    })
+}
+
+/// Key in the document map, the path of `untitled:Untitled-1` would clash with a relative file
+#[must_use]
+pub fn key(uri: &Uri) -> MyString {
+    uri.as_str().to_smolstr()
+}
+
+/// Class of the document, it is never put into the class map
+#[must_use]
+pub fn class(uri: &Uri, ast: &AstFile) -> Class {
+    let mut class = parser::update_project_java_file(key(uri).as_str(), ast);
+    // Scratch buffers often have no package
+    class.class_path = class.class_path.trim_start_matches('.').to_smolstr();
+    class
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use lsp_server::Connection;
+    use lsp_types::{
+        DidOpenTextDocumentParams, DocumentSymbolParams, DocumentSymbolResponse,
+        PartialResultParams, TextDocumentIdentifier, TextDocumentItem, Uri, WorkDoneProgressParams,
+    };
+
+    use super::{class, is_scratch, key};
+    use crate::backend::Backend;
+
+    #[test]
+    fn scratch_documents() {
+        let untitled = Uri::from_str("untitled:Untitled-1").unwrap();
+        assert!(is_scratch(&untitled));
+        assert_eq!(key(&untitled), "untitled:Untitled-1");
+        assert!(!is_scratch(
+            &Uri::from_str("file:///p/src/ch/App.java").unwrap()
+        ));
+        assert!(!is_scratch(
+            &Uri::from_str("jdt://contents/java/lang/String.java").unwrap()
+        ));
+
+        let content = "public class Scratch { String name() { return \"\"; } }";
+        let tokens = ast::lexer::lex(content.as_bytes()).unwrap();
+        let ast = ast::parse_file(&tokens).unwrap();
+        let class = class(&untitled, &ast);
+        assert_eq!(class.class_path, "Scratch");
+        assert_eq!(class.methods.len(), 1);
+    }
+
+    #[test]
+    fn untitled_document() {
+        let (con, _client) = Connection::memory();
+        let backend = Backend::new(con);
+        let open = |language_id: &str| {
+            let uri = Uri::from_str(&format!("untitled:{language_id}")).unwrap();
+            backend.did_open(&DidOpenTextDocumentParams {
+                text_document: TextDocumentItem::new(
+                    uri.clone(),
+                    language_id.to_string(),
+                    1,
+                    "public class Scratch { void run() {} }".to_string(),
+                ),
+            });
+            backend.document_symbol(DocumentSymbolParams {
+                text_document: TextDocumentIdentifier::new(uri),
+                work_done_progress_params: WorkDoneProgressParams::default(),
+                partial_result_params: PartialResultParams::default(),
+            })
+        };
+        let Some(DocumentSymbolResponse::Flat(symbols)) = open("java") else {
+            panic!("No symbols of the scratch document");
+        };
+        assert_eq!(symbols.len(), 2);
+        assert!(open("plaintext").is_none());
+    }
+}
@@ -12,8 +12,11 @@ use lsp_types::{
     Documentation, ParameterInformation, ParameterLabel, SignatureHelp, SignatureInformation,
 };
 use my_string::MyString;
+use overload::{Argument, Signature};
 use variables::{VariableContext, VariablesError};
 
+use crate::{hover, sources};
+
 #[derive(Debug)]
 pub enum SignatureError {
     Tyres(tyres::TyresError),
@@ -63,7 +66,6 @@ pub fn get_signature(
     let Some(active_param) = active_param else {
         return Err(SignatureError::NotAnArgumentList);
     };
-    let num_params = filled_params.len();
     match &prev.last() {
         Some(CallItem::MethodCall {
             name: method_name,
@@ -76,7 +78,7 @@ pub fn get_signature(
             class_map,
             prev,
             *active_param,
-            num_params,
+            filled_params,
             method_name,
         ),
         Some(CallItem::Class { .. } | CallItem::ClassGeneric { .. }) => {
@@ -87,7 +89,7 @@ pub fn get_signature(
                 class_map,
                 prev,
                 *active_param,
-                num_params,
+                filled_params,
             )
         }
 
@@ -103,7 +105,7 @@ fn signature_help_for_method(
     class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
     prev: &[CallItem],
     active_param: usize,
-    num_params: usize,
+    filled_params: &[Vec<CallItem>],
     method_name: &str,
 ) -> Result<SignatureHelp, SignatureError> {
     // trim last method call
@@ -119,17 +121,18 @@ fn signature_help_for_method(
         .filter(|i| i.name.as_ref().is_some_and(|i| *i == method_name))
         .collect();
 
-    let Some(active_signature) = methods
-        .iter()
-        .enumerate()
-        .find(|(_, m)| m.parameters.len() >= num_params)
-    else {
+    let arguments = tyres::resolve_arguments(filled_params, vars, imports, class, class_map);
+    let Some(active_signature_id) = active_signature(&methods, &arguments, class_map) else {
         return Err(SignatureError::CouldNoteGetActiveSignature);
     };
-    let active_signature_id = active_signature.0;
+    let class_source = resolve_state.class.get_source();
     let signatures = methods
-        .iter()
-        .map(|m| method_to_signature_information(m, &resolve_state.class.name))
+        .into_iter()
+        .map(|m| {
+            let mut m = m.clone();
+            sources::fill_parameter_names(&mut m, class_source.as_ref());
+            method_to_signature_information(&m, &resolve_state.class.name)
+        })
         .collect();
 
     Ok(SignatureHelp {
@@ -145,7 +148,7 @@ fn signature_help_for_constructor(
     class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
     prev: &[CallItem],
     active_param: usize,
-    num_params: usize,
+    filled_params: &[Vec<CallItem>],
 ) -> Result<SignatureHelp, SignatureError> {
     // trim last method call
     let prev = &prev[..1];
@@ -160,17 +163,18 @@ fn signature_help_for_constructor(
         .filter(|m| m.name.is_none())
         .collect();
 
-    let Some(active_signature) = methods
-        .iter()
-        .enumerate()
-        .find(|(_, m)| m.parameters.len() >= num_params)
-    else {
+    let arguments = tyres::resolve_arguments(filled_params, vars, imports, class, class_map);
+    let Some(active_signature_id) = active_signature(&methods, &arguments, class_map) else {
         return Err(SignatureError::CouldNoteGetActiveSignature);
     };
-    let active_signature_id = active_signature.0;
+    let class_source = resolve_state.class.get_source();
     let signatures = methods
-        .iter()
-        .map(|m| method_to_signature_information(m, &resolve_state.class.name))
+        .into_iter()
+        .map(|m| {
+            let mut m = m.clone();
+            sources::fill_parameter_names(&mut m, class_source.as_ref());
+            method_to_signature_information(&m, &resolve_state.class.name)
+        })
         .collect();
 
     Ok(SignatureHelp {
@@ -180,6 +184,18 @@ fn signature_help_for_constructor(
     })
 }
 
+/// First overload that the arguments typed so far can be passed to
+fn active_signature(
+    methods: &[&Method],
+    arguments: &[Argument],
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Option<usize> {
+    let class_map = class_map.read().ok()?;
+    methods
+        .iter()
+        .position(|m| overload::accepts_prefix(&Signature::of(m), arguments, &class_map))
+}
+
 fn get_args(call_chain: &[CallItem]) -> Option<&CallItem> {
     call_chain.iter().rev().find(|i| match i {
         CallItem::MethodCall { .. }
@@ -189,6 +205,7 @@ fn get_args(call_chain: &[CallItem]) -> Option<&CallItem> {
         | CallItem::ClassGeneric { .. }
         | CallItem::ClassOrVariable { .. }
         | CallItem::ArrayAccess { .. }
+        | CallItem::TypeArguments { .. }
         | CallItem::This { .. } => false,
         CallItem::ArgumentList {
             prev: _,
@@ -207,7 +224,11 @@ fn method_to_signature_information(method: &Method, class_name: &str) -> Signatu
     let mut parameters = Vec::with_capacity(method.parameters.len());
     let mut peekable = method.parameters.iter().peekable();
     while let Some(param) = peekable.next() {
-        let jtype = param.jtype.to_string();
+        let jtype = format!(
+            "{}{}",
+            hover::annotations_hover(&param.annotations),
+            param.jtype
+        );
         if let Some(name) = &param.name {
             let named = format!("{jtype} {name}");
             label.push_str(&named);
@@ -230,7 +251,11 @@ fn method_to_signature_information(method: &Method, class_name: &str) -> Signatu
     label.push(')');
     SignatureInformation {
         label,
-        documentation: Some(Documentation::String(method.ret.to_string())),
+        documentation: Some(Documentation::String(format!(
+            "{}{}",
+            hover::annotations_hover(&method.annotations),
+            method.ret
+        ))),
         parameters: Some(parameters),
         active_parameter: None,
     }
@@ -266,6 +291,7 @@ pub mod tests {
                     parameters: vec![Parameter {
                         name: None,
                         jtype: JType::Class(SmolStr::new_inline("java.lang.String")),
+                        annotations: vec![],
                     }],
                     ret: JType::Class(SmolStr::new_inline("java.lang.String")),
                     ..Default::default()
@@ -337,30 +363,37 @@ public class Test {
                     Method {
                         access: Access::Public,
                         name: Some(SmolStr::new_inline("concat")),
+                        type_parameters: vec![],
                         parameters: vec![Parameter {
                             name: None,
                             jtype: JType::Class(SmolStr::new_inline("java.lang.String")),
+                            annotations: vec![],
                         }],
                         ret: JType::Class(SmolStr::new_inline("java.lang.String")),
                         throws: vec![],
                         source: None,
+                        annotations: vec![],
                     },
                     Method {
                         access: Access::Public,
                         name: Some(SmolStr::new_inline("concat")),
+                        type_parameters: vec![],
                         parameters: vec![
                             Parameter {
                                 name: None,
                                 jtype: JType::Class(SmolStr::new_inline("java.lang.String")),
+                                annotations: vec![],
                             },
                             Parameter {
                                 name: None,
                                 jtype: JType::Class(SmolStr::new_inline("java.lang.String")),
+                                annotations: vec![],
                             },
                         ],
                         ret: JType::Class(SmolStr::new_inline("java.lang.String")),
                         throws: vec![],
                         source: None,
+                        annotations: vec![],
                     },
                 ],
                 ..Default::default()
@@ -455,30 +488,37 @@ public class Test {
                     Method {
                         access: Access::Public,
                         name: Some(SmolStr::new_inline("concat")),
+                        type_parameters: vec![],
                         parameters: vec![Parameter {
                             name: None,
                             jtype: JType::Class(SmolStr::new_inline("java.lang.String")),
+                            annotations: vec![],
                         }],
                         ret: JType::Class(SmolStr::new_inline("java.lang.String")),
                         throws: vec![],
                         source: None,
+                        annotations: vec![],
                     },
                     Method {
                         access: Access::Public,
                         name: Some(SmolStr::new_inline("concat")),
+                        type_parameters: vec![],
                         parameters: vec![
                             Parameter {
                                 name: None,
                                 jtype: JType::Class(SmolStr::new_inline("java.lang.String")),
+                                annotations: vec![],
                             },
                             Parameter {
                                 name: None,
                                 jtype: JType::Class(SmolStr::new_inline("java.lang.String")),
+                                annotations: vec![],
                             },
                         ],
                         ret: JType::Class(SmolStr::new_inline("java.lang.String")),
                         throws: vec![],
                         source: None,
+                        annotations: vec![],
                     },
                 ],
                 ..Default::default()
@@ -572,13 +612,16 @@ public class Test {
                 methods: vec![Method {
                     access: Access::Public,
                     name: None,
+                    type_parameters: vec![],
                     parameters: vec![Parameter {
                         name: None,
                         jtype: JType::Class(SmolStr::new_inline("java.lang.String")),
+                        annotations: vec![],
                     }],
                     ret: JType::Class(SmolStr::new_inline("java.lang.String")),
                     throws: vec![],
                     source: None,
+                    annotations: vec![],
                 }],
                 ..Default::default()
             },
@@ -0,0 +1,255 @@
+//! Sources of dependencies
+//!
+//! Classes of dependencies point to their folder in the deps cache. A file of that folder is
+//! extracted from the `-sources.jar` in the local maven repository or the gradle cache the first
+//! time it is needed, the folder is the extraction cache for later requests.
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use common::{Dependency, deps::deps_source_dependency, deps_dir_no_create};
+use document::{Document, path_without_subclass};
+use dto::{Method, SourceDestination};
+use my_string::MyString;
+
+use crate::{hover::jtype_hover_display, stub};
+
+/// Member of a class to look up in its source
+#[derive(Debug, Clone, Copy)]
+pub enum Member<'a> {
+    Class(&'a str),
+    Method(&'a str, usize),
+    Field(&'a str),
+}
+
+/// Sources jar of the dependency in the local maven repository or the gradle cache
+#[must_use]
+pub fn sources_jar(dependency: &Dependency) -> Option<PathBuf> {
+    if let Ok(m2) = maven::m2::get_maven_m2_folder() {
+        let jar = maven::m2::pom_sources_jar(dependency, &maven::m2::pom_m2(dependency, &m2));
+        if jar.exists() {
+            return Some(jar);
+        }
+    }
+    gradle::project::cached_sources_jar(dependency)
+}
+
+/// Extract the file of the source from the sources jar when it does not exist yet or is a stub
+pub fn ensure_source(source: &str) -> bool {
+    let path = path_without_subclass(source);
+    let exists = path.exists();
+    if exists && !stub::is_stub(&path) {
+        return true;
+    }
+    let Some((dependency, entry)) = deps_source_dependency(&deps_dir_no_create(), &path) else {
+        return exists;
+    };
+    let Some(jar) = sources_jar(&dependency) else {
+        return exists;
+    };
+    match zip_util::read_entry(&jar, &entry) {
+        Ok(Some(content)) => {
+            if let Some(parent) = path.parent()
+                && let Err(e) = fs::create_dir_all(parent)
+            {
+                tracing::debug!("Unable to create {}: {e:?}", parent.display());
+                return false;
+            }
+            if exists {
+                let _ = fs::remove_file(&path);
+            }
+            if let Err(e) = fs::write(&path, content) {
+                tracing::debug!("Unable to extract {}: {e:?}", path.display());
+                return false;
+            }
+            true
+        }
+        Ok(None) => exists,
+        Err(e) => {
+            tracing::debug!("Unable to read {}: {e:?}", jar.display());
+            exists
+        }
+    }
+}
+
+/// Parsed source file, not added to the document map
+#[must_use]
+pub fn read_source(source: &str) -> Option<Document> {
+    if !ensure_source(source) {
+        return None;
+    }
+    Document::setup_read(path_without_subclass(source)).ok()
+}
+
+/// Javadoc of a member as markdown
+#[must_use]
+pub fn member_javadoc(source: &str, member: Member) -> Option<String> {
+    let document = read_source(source)?;
+    let mut positions = vec![];
+    match member {
+        Member::Class(name) => {
+            position::get_class_position(&document.ast, Some(name), &mut positions);
+        }
+        Member::Method(name, nargs) => {
+            position::get_method_position(&document.ast, Some(name), Some(nargs), &mut positions);
+        }
+        Member::Field(name) => {
+            position::get_field_position(&document.ast, Some(name), &mut positions);
+        }
+    }
+    let position = positions.first()?;
+    javadoc(&document, position.range.start.line)
+}
+
+/// Names of the parameters as written in the source of the method
+#[must_use]
+pub fn parameter_names(source: &str, method: &Method) -> Option<Vec<MyString>> {
+    let document = read_source(source)?;
+    let class = parser::java::load_java_tree(&document.ast, SourceDestination::None);
+    let display = |m: &Method| {
+        m.parameters
+            .iter()
+            .map(|p| jtype_hover_display(&p.jtype))
+            .collect::<Vec<_>>()
+    };
+    // Types of the source are not qualified and there is no class map, among overloads that
+    // stay ambiguous the one that displays the same is taken
+    let selected = overload::select_methods(
+        class.methods.iter().filter(|m| m.name == method.name),
+        &overload::parameter_arguments(method),
+        &HashMap::new(),
+    );
+    let types = display(method);
+    let found = match selected.as_slice() {
+        [found] => found,
+        selected => selected.iter().find(|m| display(m) == types)?,
+    };
+    found.parameters.iter().map(|p| p.name.clone()).collect()
+}
+
+/// Fill the parameters without a name from the source of the method
+pub fn fill_parameter_names(method: &mut Method, class_source: Option<&MyString>) {
+    if method.parameters.iter().all(|p| p.name.is_some()) {
+        return;
+    }
+    let Some(source) = method.source.as_ref().or(class_source) else {
+        return;
+    };
+    let Some(names) = parameter_names(source, method) else {
+        return;
+    };
+    for (parameter, name) in method.parameters.iter_mut().zip(names) {
+        parameter.name.get_or_insert(name);
+    }
+}
+
+/// Doc comment that ends above the line, annotations between the comment and the line are
+/// skipped
+#[must_use]
+pub fn javadoc(document: &Document, line: usize) -> Option<String> {
+    let mut end = line;
+    loop {
+        end = end.checked_sub(1)?;
+        let text = document.rope.get_line(end)?.to_string();
+        let text = text.trim();
+        if text.ends_with("*/") {
+            break;
+        }
+        if !text.is_empty() && !text.starts_with('@') {
+            return None;
+        }
+    }
+    let mut lines = vec![];
+    let mut current = end;
+    loop {
+        let text = document.rope.get_line(current)?.to_string();
+        let text = text.trim();
+        if let Some(rest) = text.strip_prefix("/**") {
+            lines.push(rest.to_string());
+            break;
+        }
+        if text.starts_with("/*") {
+            return None;
+        }
+        lines.push(text.to_string());
+        current = current.checked_sub(1)?;
+    }
+    lines.reverse();
+    if let Some(last) = lines.last_mut() {
+        *last = last.trim_end().trim_end_matches("*/").to_string();
+    }
+    let mut out = String::new();
+    let mut in_tags = false;
+    for line in lines {
+        let line = line.trim();
+        let line = line.strip_prefix('*').unwrap_or(line).trim();
+        if line.starts_with('@') {
+            if !in_tags && !out.ends_with("\n\n") {
+                out.push('\n');
+            }
+            in_tags = true;
+            out.push_str("- ");
+        }
+        out.push_str(&inline_tags(line));
+        out.push('\n');
+    }
+    let out = out.trim().to_string();
+    if out.is_empty() {
+        return None;
+    }
+    Some(out)
+}
+
+/// `{@code a}` and `{@link A}` as inline code
+fn inline_tags(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("{@") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let tag = &rest[start + 2..start + end];
+        let content = tag.split_once(' ').map_or("", |(_, c)| c.trim());
+        out.push('`');
+        out.push_str(content);
+        out.push('`');
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn javadoc_of_method() {
+        let content = "package a;
+public class A {
+    /**
+     * Value of the key
+     *
+     * @param key name of the {@code value}
+     * @return the value or {@link java.util.Optional empty}
+     */
+    @Override
+    public String get(String key) {
+        return key;
+    }
+    // Not a doc comment
+    public void other() {}
+}
+";
+        let document = Document::setup(content, PathBuf::new()).unwrap();
+        assert_eq!(
+            javadoc(&document, 9).as_deref(),
+            Some(
+                "Value of the key\n\n- @param key name of the `value`\n- @return the value or `java.util.Optional empty`"
+            )
+        );
+        assert_eq!(javadoc(&document, 13), None);
+        assert_eq!(javadoc(&document, 1), None);
+    }
+}
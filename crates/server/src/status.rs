@@ -0,0 +1,187 @@
+//! Metrics of the server to find out why it is slow without reading the logs
+//!
+//! `java/status` returns the sizes of the indexes, the hit rate of the document map, the
+//! percentiles of the parse and index durations, the memory that is used and the installed JDKs
+//! with the one that is indexed. With the `status_updates` setting the same summary is reported
+//! every minute as `$/progress`.
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use common::timings::{self, Percentiles};
+use document::{Document, cache};
+use dto::{Class, SourceDestination};
+use jdk::installations::{self, JdkInstall};
+use my_string::MyString;
+use serde::Serialize;
+
+use crate::references::ReferenceUnit;
+
+/// Request without parameters that returns the [`Status`]
+pub const STATUS: &str = "java/status";
+
+/// Progress token of the periodic status updates
+pub const STATUS_TASK: &str = "Status";
+
+/// Time between the status updates
+pub const STATUS_INTERVAL: Duration = Duration::from_mins(1);
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Status {
+    pub classes: usize,
+    /// Classes of the workspace sources
+    pub project_classes: usize,
+    pub documents: usize,
+    /// Documents that are open in the client
+    pub open_documents: usize,
+    /// Classes with an entry in the reference map
+    pub references: usize,
+    pub document_cache: CacheStatus,
+    pub parse: Percentiles,
+    pub index: Percentiles,
+    pub memory: MemoryStatus,
+    pub jdks: Vec<JdkInstall>,
+    /// Home of the JDK whose classes are indexed
+    pub jdk: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStatus {
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStatus {
+    /// Estimated bytes of the documents, see [`Document::memory_size`]
+    pub documents: usize,
+    /// Budget of the documents from the `cache_memory` setting
+    pub budget: usize,
+    /// Resident bytes of the process, only known on linux
+    pub resident: Option<u64>,
+}
+
+impl Status {
+    #[must_use]
+    pub fn collect(
+        class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+        document_map: &Arc<RwLock<HashMap<MyString, Document>>>,
+        reference_map: &Arc<Mutex<HashMap<MyString, Vec<ReferenceUnit>>>>,
+        budget: usize,
+        jdk_release: Option<u32>,
+    ) -> Self {
+        let (classes, project_classes) = class_map.read().map_or((0, 0), |class_map| {
+            let project = class_map
+                .values()
+                .filter(|c| matches!(c.source, SourceDestination::Here(_)))
+                .count();
+            (class_map.len(), project)
+        });
+        let (documents, open_documents, document_memory) =
+            document_map.read().map_or((0, 0, 0), |document_map| {
+                (
+                    document_map.len(),
+                    document_map.values().filter(|d| d.opened).count(),
+                    document_map.values().map(Document::memory_size).sum(),
+                )
+            });
+        let references = reference_map.lock().map_or(0, |r| r.len());
+        let (hits, misses) = cache::hits_and_misses();
+        Self {
+            classes,
+            project_classes,
+            documents,
+            open_documents,
+            references,
+            document_cache: CacheStatus {
+                hits,
+                misses,
+                hit_rate: hit_rate(hits, misses),
+            },
+            parse: timings::PARSE.percentiles(),
+            index: timings::INDEX.percentiles(),
+            memory: MemoryStatus {
+                documents: document_memory,
+                budget,
+                resident: resident_memory(),
+            },
+            jdks: installations::installed(),
+            jdk: installations::home(jdk_release),
+        }
+    }
+
+    /// One line for the status updates
+    #[must_use]
+    pub fn summary(&self) -> String {
+        format!(
+            "{} classes, {} documents ({} MiB), {:.0}% cache hits, parse p90 {:.1}ms",
+            self.classes,
+            self.documents,
+            self.memory.documents / (1024 * 1024),
+            self.document_cache.hit_rate * 100.0,
+            self.parse.p90_ms,
+        )
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn hit_rate(hits: u64, misses: u64) -> f64 {
+    let total = hits + misses;
+    if total == 0 {
+        return 0.0;
+    }
+    hits as f64 / total as f64
+}
+
+/// Second field of `/proc/self/statm` in pages of 4 KiB
+fn resident_memory() -> Option<u64> {
+    let statm = fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages * 4096)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        path::PathBuf,
+        sync::{Arc, Mutex, RwLock},
+    };
+
+    use document::Document;
+    use my_string::MyString;
+
+    use super::{Status, hit_rate};
+
+    #[test]
+    fn collect() {
+        let mut document = Document::setup("class A {}", PathBuf::from("A.java")).unwrap();
+        document.opened = true;
+        let document_map = Arc::new(RwLock::new(HashMap::from([(
+            MyString::from("A.java"),
+            document,
+        )])));
+        let status = Status::collect(
+            &Arc::new(RwLock::new(HashMap::new())),
+            &document_map,
+            &Arc::new(Mutex::new(HashMap::new())),
+            1024,
+            None,
+        );
+        assert_eq!(status.documents, 1);
+        assert_eq!(status.open_documents, 1);
+        assert!(status.memory.documents > 0);
+        assert!(status.parse.count > 0);
+        assert!(status.summary().starts_with("0 classes, 1 documents"));
+        assert!((hit_rate(3, 1) - 0.75).abs() < f64::EPSILON);
+        assert!(hit_rate(0, 0).abs() < f64::EPSILON);
+    }
+}
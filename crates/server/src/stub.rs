@@ -0,0 +1,410 @@
+//! Stubs of classes without sources
+//!
+//! When neither the source nor a sources jar of a dependency or JDK class exists, a stub with
+//! the signatures of the class file is written to the place of the missing source so that
+//! definitions can navigate into it. The stub is read only and is replaced by the real source
+//! once a sources jar is found.
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs,
+    io::{BufRead, BufReader},
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use document::path_without_subclass;
+use dto::{Access, Class, Field, ImportUnit, JType, Method, SuperClass};
+use my_string::MyString;
+
+use crate::{
+    hover::{annotations_hover, jtype_hover_display},
+    sources,
+};
+
+/// First line of every stub
+pub const STUB_HEADER: &str = "// Generated from the class file, no sources are available";
+
+const BODY: &str = "{ /* compiled code */ }";
+
+/// Whether the file is a stub written by [`class_source`]
+#[must_use]
+pub fn is_stub(path: &Path) -> bool {
+    let Ok(file) = fs::File::open(path) else {
+        return false;
+    };
+    let mut line = String::new();
+    BufReader::new(file).read_line(&mut line).is_ok() && line.trim_end() == STUB_HEADER
+}
+
+/// Source of the class, a stub is written to it when there are no sources
+#[must_use]
+pub fn class_source(
+    class: &Class,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Option<MyString> {
+    let source = class.get_source()?;
+    if sources::ensure_source(&source) {
+        return Some(source);
+    }
+    let path = path_without_subclass(&source);
+    // Only generated files in the cache, never next to the sources of a project
+    if !path.starts_with(common::cache_dir_no_create()) {
+        return Some(source);
+    }
+    let outer_path = class
+        .class_path
+        .split_once('$')
+        .map_or(class.class_path.as_str(), |(outer, _)| outer);
+    let Ok(cm) = class_map.read() else {
+        tracing::error!("Could not get read lock on class_map");
+        return Some(source);
+    };
+    let outer = cm.get(outer_path).unwrap_or(class);
+    let prefix = format!("{outer_path}$");
+    let mut nested: Vec<&Class> = cm
+        .values()
+        .filter(|c| {
+            c.class_path
+                .strip_prefix(&prefix)
+                .is_some_and(|name| !name.contains('$'))
+        })
+        .collect();
+    nested.sort_by(|a, b| a.class_path.cmp(&b.class_path));
+    let content = class_stub(outer, &nested);
+    drop(cm);
+
+    if let Some(parent) = path.parent()
+        && let Err(e) = fs::create_dir_all(parent)
+    {
+        tracing::debug!("Unable to create {}: {e:?}", parent.display());
+        return Some(source);
+    }
+    if let Err(e) = fs::write(&path, content) {
+        tracing::debug!("Unable to write stub {}: {e:?}", path.display());
+        return Some(source);
+    }
+    if let Ok(metadata) = fs::metadata(&path) {
+        let mut permissions = metadata.permissions();
+        permissions.set_readonly(true);
+        let _ = fs::set_permissions(&path, permissions);
+    }
+    Some(source)
+}
+
+/// Java source with the signatures of the class and its nested classes
+#[must_use]
+pub fn class_stub(class: &Class, nested: &[&Class]) -> String {
+    let mut out = String::new();
+    out.push_str(STUB_HEADER);
+    out.push('\n');
+    if let Some((package, _)) = class.class_path.rsplit_once('.') {
+        let _ = writeln!(out, "package {package};");
+    }
+    let mut imports: Vec<String> = class
+        .imports
+        .iter()
+        .filter_map(|i| match i {
+            ImportUnit::Class(c) => Some(c.replace('$', ".")),
+            _ => None,
+        })
+        .filter(|c| {
+            c.rsplit_once('.')
+                .is_some_and(|(package, _)| package != "java.lang")
+        })
+        .collect();
+    imports.sort_unstable();
+    imports.dedup();
+    if !imports.is_empty() {
+        out.push('\n');
+        for import in imports {
+            let _ = writeln!(out, "import {import};");
+        }
+    }
+    out.push('\n');
+    write_class(&mut out, class, nested, "");
+    out
+}
+
+fn write_class(out: &mut String, class: &Class, nested: &[&Class], indent: &str) {
+    let access = &class.access;
+    let kind = if access.contains(Access::Annotation) {
+        "@interface"
+    } else if access.contains(Access::Interface) {
+        "interface"
+    } else if access.contains(Access::Enum) {
+        "enum"
+    } else {
+        "class"
+    };
+    let mut modifiers = visibility(access);
+    if kind == "class" {
+        if access.contains(Access::Abstract) {
+            modifiers.push_str("abstract ");
+        }
+        if access.contains(Access::Final) {
+            modifiers.push_str("final ");
+        }
+    }
+    if access.contains(Access::Static) && !indent.is_empty() {
+        modifiers.push_str("static ");
+    }
+    let _ = write!(out, "{indent}{modifiers}{kind} {}", class.name);
+    if let Some(signature) = &class.signature
+        && !signature.args.is_empty()
+    {
+        let _ = write!(out, "<{}>", signature.args.join(", "));
+    }
+    let super_class = match &class.super_class {
+        SuperClass::Name(name) | SuperClass::ClassPath(name) => Some(name),
+        SuperClass::None => None,
+    };
+    if let Some(super_class) = super_class
+        && kind == "class"
+        && super_class != "java.lang.Object"
+    {
+        let _ = write!(out, " extends {}", class_name(super_class));
+    }
+    let interfaces: Vec<String> = class
+        .super_interfaces
+        .iter()
+        .filter_map(|i| match i {
+            SuperClass::Name(name) | SuperClass::ClassPath(name) => Some(class_name(name)),
+            SuperClass::None => None,
+        })
+        .filter(|name| kind != "@interface" || name != "Annotation")
+        .collect();
+    if !interfaces.is_empty() {
+        let keyword = if kind == "interface" {
+            "extends"
+        } else {
+            "implements"
+        };
+        let _ = write!(out, " {keyword} {}", interfaces.join(", "));
+    }
+    out.push_str(" {\n");
+
+    let inner = format!("{indent}    ");
+    let is_constant = |f: &&Field| {
+        kind == "enum"
+            && f.access.contains(Access::Static | Access::Final)
+            && matches!(&f.jtype, JType::Class(c) if *c == class.class_path)
+    };
+    let constants: Vec<&str> = class
+        .fields
+        .iter()
+        .filter(is_constant)
+        .map(|f| f.name.as_str())
+        .collect();
+    if kind == "enum" {
+        let _ = writeln!(out, "{inner}{};", constants.join(", "));
+    }
+    for field in class.fields.iter().filter(|f| !is_constant(f)) {
+        let _ = writeln!(
+            out,
+            "{inner}{}{}{}{} {};",
+            annotations_hover(&field.annotations),
+            member_modifiers(&field.access, kind),
+            final_modifier(&field.access),
+            jtype_hover_display(&field.jtype),
+            field.name
+        );
+    }
+    for method in &class.methods {
+        if kind == "enum"
+            && matches!(method.name.as_deref(), Some("values" | "valueOf"))
+            && method.access.contains(Access::Static)
+        {
+            continue;
+        }
+        write_method(out, method, class, kind, &inner);
+    }
+    for nested_class in nested {
+        out.push('\n');
+        write_class(out, nested_class, &[], &inner);
+    }
+    let _ = writeln!(out, "{indent}}}");
+}
+
+fn write_method(out: &mut String, method: &Method, class: &Class, kind: &str, indent: &str) {
+    let mut line = format!(
+        "{indent}{}{}",
+        annotations_hover(&method.annotations),
+        member_modifiers(&method.access, kind)
+    );
+    let is_abstract = method.access.contains(Access::Abstract);
+    if kind == "interface" && !is_abstract && !method.access.contains(Access::Static) {
+        line.push_str("default ");
+    } else if kind == "class" && is_abstract {
+        line.push_str("abstract ");
+    }
+    line.push_str(&final_modifier(&method.access));
+    if let Some(name) = &method.name {
+        let _ = write!(line, "{} {name}(", jtype_hover_display(&method.ret));
+    } else {
+        let _ = write!(line, "{}(", class.name);
+    }
+    let parameters: Vec<String> = method
+        .parameters
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let name = p
+                .name
+                .as_ref()
+                .map_or_else(|| format!("arg{i}"), ToString::to_string);
+            format!(
+                "{}{} {name}",
+                annotations_hover(&p.annotations),
+                jtype_hover_display(&p.jtype)
+            )
+        })
+        .collect();
+    line.push_str(&parameters.join(", "));
+    line.push(')');
+    if !method.throws.is_empty() {
+        let throws: Vec<String> = method.throws.iter().map(jtype_hover_display).collect();
+        let _ = write!(line, " throws {}", throws.join(", "));
+    }
+    if is_abstract {
+        line.push(';');
+    } else {
+        line.push(' ');
+        line.push_str(BODY);
+    }
+    out.push_str(&line);
+    out.push('\n');
+}
+
+fn visibility(access: &Access) -> String {
+    if access.contains(Access::Public) {
+        "public ".to_string()
+    } else if access.contains(Access::Protected) {
+        "protected ".to_string()
+    } else if access.contains(Access::Private) {
+        "private ".to_string()
+    } else {
+        String::new()
+    }
+}
+
+fn member_modifiers(access: &Access, kind: &str) -> String {
+    let mut out = if kind == "interface" || kind == "@interface" {
+        String::new()
+    } else {
+        visibility(access)
+    };
+    if access.contains(Access::Static) {
+        out.push_str("static ");
+    }
+    out
+}
+
+fn final_modifier(access: &Access) -> String {
+    if access.contains(Access::Final) {
+        "final ".to_string()
+    } else {
+        String::new()
+    }
+}
+
+fn class_name(class_path: &str) -> String {
+    jtype_hover_display(&JType::Class(class_path.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use dto::{ClassSignature, Parameter};
+    use my_string::smol_str::SmolStr;
+
+    use super::*;
+
+    #[test]
+    fn stub_of_class() {
+        let class = Class {
+            class_path: SmolStr::new("org.example.Store"),
+            access: Access::Public | Access::Abstract,
+            imports: vec![
+                ImportUnit::Package(SmolStr::new("org.example")),
+                ImportUnit::Class(SmolStr::new("java.util.List")),
+                ImportUnit::Class(SmolStr::new("java.lang.String")),
+            ],
+            signature: Some(ClassSignature {
+                args: vec![SmolStr::new("T")],
+                ret: JType::Void,
+            }),
+            name: SmolStr::new("Store"),
+            methods: vec![
+                Method {
+                    access: Access::Public,
+                    parameters: vec![Parameter {
+                        name: Some(SmolStr::new("capacity")),
+                        jtype: JType::Int,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                Method {
+                    access: Access::Public | Access::Abstract,
+                    name: Some(SmolStr::new("get")),
+                    annotations: vec![SmolStr::new("org.example.Nullable")],
+                    parameters: vec![Parameter {
+                        name: None,
+                        jtype: JType::Class(SmolStr::new("java.lang.String")),
+                        ..Default::default()
+                    }],
+                    ret: JType::Parameter(SmolStr::new("T")),
+                    throws: vec![JType::Class(SmolStr::new("java.io.IOException"))],
+                    ..Default::default()
+                },
+            ],
+            fields: vec![Field {
+                access: Access::Public | Access::Static | Access::Final,
+                name: SmolStr::new("ITEMS"),
+                jtype: JType::Generic(
+                    SmolStr::new("java.util.List"),
+                    vec![JType::Class(SmolStr::new("java.lang.String"))],
+                ),
+                ..Default::default()
+            }],
+            super_class: SuperClass::ClassPath(SmolStr::new("java.lang.Object")),
+            super_interfaces: vec![SuperClass::ClassPath(SmolStr::new("java.lang.Runnable"))],
+            ..Default::default()
+        };
+        let entry = Class {
+            class_path: SmolStr::new("org.example.Store$Kind"),
+            access: Access::Public | Access::Static | Access::Enum | Access::Final,
+            name: SmolStr::new("Kind"),
+            fields: vec![Field {
+                access: Access::Public | Access::Static | Access::Final | Access::Enum,
+                name: SmolStr::new("A"),
+                jtype: JType::Class(SmolStr::new("org.example.Store$Kind")),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let stub = class_stub(&class, &[&entry]);
+        assert_eq!(
+            stub,
+            "// Generated from the class file, no sources are available
+package org.example;
+
+import java.util.List;
+
+public abstract class Store<T> implements Runnable {
+    public static final List<String> ITEMS;
+    public Store(int capacity) { /* compiled code */ }
+    @Nullable public abstract T get(String arg0) throws IOException;
+
+    public static enum Kind {
+        A;
+    }
+}
+"
+        );
+        let document = document::Document::setup(&stub, std::path::PathBuf::new()).unwrap();
+        let mut positions = vec![];
+        position::get_method_position(&document.ast, Some("get"), Some(1), &mut positions);
+        assert_eq!(positions.len(), 1);
+    }
+}
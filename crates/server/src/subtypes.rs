@@ -0,0 +1,310 @@
+//! Reverse index of the extends and implements edges of all classes
+//!
+//! Go to implementation and the type hierarchy look up the subtypes of a class here instead of
+//! scanning the class map. The index is built from the class map when it is needed, rebuilt after
+//! classes were added or removed and updated when a source file changed.
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, RwLock},
+};
+
+use dto::{Class, ImportUnit, SuperClass};
+use my_string::{MyString, smol_str::format_smolstr};
+
+#[derive(Debug, Default, Clone)]
+pub struct SubtypeIndex {
+    classes: usize,
+    /// Direct subtypes of a class path
+    subtypes: HashMap<MyString, Vec<MyString>>,
+    /// Resolved direct supertypes of a class path
+    supertypes: HashMap<MyString, Vec<MyString>>,
+}
+
+impl SubtypeIndex {
+    #[must_use]
+    pub fn new(class_map: &HashMap<MyString, Class>) -> Self {
+        let mut out = Self {
+            classes: class_map.len(),
+            ..Default::default()
+        };
+        for class in class_map.values() {
+            out.add(class, class_map);
+        }
+        for subtypes in out.subtypes.values_mut() {
+            subtypes.sort_unstable();
+        }
+        out
+    }
+
+    /// Replace the edges of the class
+    pub fn update(&mut self, class: &Class, class_map: &HashMap<MyString, Class>) {
+        self.remove(&class.class_path);
+        self.add(class, class_map);
+        self.classes = class_map.len();
+    }
+
+    fn add(&mut self, class: &Class, class_map: &HashMap<MyString, Class>) {
+        let supertypes = supertypes(class, class_map);
+        for supertype in &supertypes {
+            self.subtypes
+                .entry(supertype.clone())
+                .or_default()
+                .push(class.class_path.clone());
+        }
+        if !supertypes.is_empty() {
+            self.supertypes.insert(class.class_path.clone(), supertypes);
+        }
+    }
+
+    fn remove(&mut self, class_path: &MyString) {
+        let Some(supertypes) = self.supertypes.remove(class_path) else {
+            return;
+        };
+        for supertype in supertypes {
+            if let Some(subtypes) = self.subtypes.get_mut(&supertype) {
+                subtypes.retain(|s| s != class_path);
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn direct_subtypes(&self, class_path: &str) -> &[MyString] {
+        self.subtypes.get(class_path).map_or(&[], Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn direct_supertypes(&self, class_path: &str) -> &[MyString] {
+        self.supertypes.get(class_path).map_or(&[], Vec::as_slice)
+    }
+
+    /// Subtypes of the class and their subtypes, nearest first
+    #[must_use]
+    pub fn all_subtypes(&self, class_path: &str) -> Vec<MyString> {
+        let mut seen: HashSet<&str> = HashSet::from([class_path]);
+        let mut queue = VecDeque::from([class_path]);
+        let mut out = vec![];
+        while let Some(current) = queue.pop_front() {
+            for subtype in self.direct_subtypes(current) {
+                if seen.insert(subtype) {
+                    out.push(subtype.clone());
+                    queue.push_back(subtype);
+                }
+            }
+        }
+        out
+    }
+
+    /// Supertypes of the class and their supertypes, nearest first
+    #[must_use]
+    pub fn all_supertypes(&self, class_path: &str) -> Vec<MyString> {
+        let mut seen: HashSet<&str> = HashSet::from([class_path]);
+        let mut queue = VecDeque::from([class_path]);
+        let mut out = vec![];
+        while let Some(current) = queue.pop_front() {
+            for supertype in self.direct_supertypes(current) {
+                if seen.insert(supertype) {
+                    out.push(supertype.clone());
+                    queue.push_back(supertype);
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Index of the class map that is rebuilt when classes are added or removed
+#[derive(Debug, Default)]
+pub struct Subtypes {
+    index: RwLock<Option<Arc<SubtypeIndex>>>,
+}
+
+impl Subtypes {
+    pub fn get(&self, class_map: &RwLock<HashMap<MyString, Class>>) -> Arc<SubtypeIndex> {
+        let Ok(class_map) = class_map.read() else {
+            return Arc::default();
+        };
+        if let Ok(index) = self.index.read()
+            && let Some(index) = index.as_ref()
+            && index.classes == class_map.len()
+        {
+            return index.clone();
+        }
+        let index = Arc::new(SubtypeIndex::new(&class_map));
+        if let Ok(mut current) = self.index.write() {
+            *current = Some(index.clone());
+        }
+        index
+    }
+
+    /// The class was inserted into the class map
+    pub fn update(&self, class: &Class, class_map: &RwLock<HashMap<MyString, Class>>) {
+        let Ok(class_map) = class_map.read() else {
+            return;
+        };
+        if let Ok(mut index) = self.index.write()
+            && let Some(index) = index.as_mut()
+        {
+            Arc::make_mut(index).update(class, &class_map);
+        }
+    }
+
+    pub fn invalidate(&self) {
+        if let Ok(mut index) = self.index.write() {
+            *index = None;
+        }
+    }
+}
+
+/// Class paths of the super class and interfaces, names of source classes are resolved with
+/// the imports
+#[must_use]
+pub fn supertypes(class: &Class, class_map: &HashMap<MyString, Class>) -> Vec<MyString> {
+    std::iter::once(&class.super_class)
+        .chain(&class.super_interfaces)
+        .filter_map(|s| match s {
+            SuperClass::None => None,
+            SuperClass::ClassPath(class_path) => Some(class_path.clone()),
+            SuperClass::Name(name) => resolve_name(name, &class.imports, class_map),
+        })
+        .filter(|s| s != "java.lang.Object")
+        .collect()
+}
+
+fn resolve_name(
+    name: &str,
+    imports: &[ImportUnit],
+    class_map: &HashMap<MyString, Class>,
+) -> Option<MyString> {
+    let name = name.split('<').next().unwrap_or(name).trim();
+    if class_map.contains_key(name) {
+        return Some(name.into());
+    }
+    // Nested classes are `Outer$Inner` in the class map
+    let (outer, nested) = name.split_once('.').unwrap_or((name, ""));
+    let nested = nested.replace('.', "$");
+    let with_nested = |class_path: &str| {
+        if nested.is_empty() {
+            class_path.into()
+        } else {
+            format_smolstr!("{class_path}${nested}")
+        }
+    };
+    let explicit = imports.iter().find_map(|i| match i {
+        ImportUnit::Class(c) | ImportUnit::StaticClass(c)
+            if ImportUnit::class_path_match_class_name(c, outer) =>
+        {
+            Some(with_nested(c))
+        }
+        _ => None,
+    });
+    if explicit.is_some() {
+        return explicit;
+    }
+    imports
+        .iter()
+        .filter_map(|i| match i {
+            ImportUnit::Package(p) | ImportUnit::Prefix(p) => {
+                Some(with_nested(&format_smolstr!("{p}.{outer}")))
+            }
+            _ => None,
+        })
+        .chain(std::iter::once(with_nested(&format_smolstr!(
+            "java.lang.{outer}"
+        ))))
+        .find(|c| class_map.contains_key(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dto::{Class, ImportUnit, SuperClass};
+    use my_string::{MyString, smol_str::SmolStr};
+
+    use super::SubtypeIndex;
+
+    fn class(class_path: &str, super_class: SuperClass, interfaces: Vec<SuperClass>) -> Class {
+        let (package, name) = class_path.rsplit_once('.').unwrap_or_default();
+        Class {
+            class_path: SmolStr::new(class_path),
+            name: SmolStr::new(name),
+            imports: vec![
+                ImportUnit::Package(SmolStr::new(package)),
+                ImportUnit::Class(SmolStr::new("java.util.List")),
+            ],
+            super_class,
+            super_interfaces: interfaces,
+            ..Default::default()
+        }
+    }
+
+    fn class_map(classes: Vec<Class>) -> HashMap<MyString, Class> {
+        classes
+            .into_iter()
+            .map(|c| (c.class_path.clone(), c))
+            .collect()
+    }
+
+    #[test]
+    fn subtypes_of_sources_and_class_files() {
+        let mut class_map = class_map(vec![
+            class("java.util.List", SuperClass::None, vec![]),
+            class(
+                "java.util.ArrayList",
+                SuperClass::ClassPath("java.util.AbstractList".into()),
+                vec![SuperClass::ClassPath("java.util.List".into())],
+            ),
+            class("java.util.AbstractList", SuperClass::None, vec![]),
+            class(
+                "ch.emilycares.MyList",
+                SuperClass::Name("ArrayList".into()),
+                vec![],
+            ),
+            class(
+                "ch.emilycares.Other",
+                SuperClass::None,
+                vec![SuperClass::Name("List<String>".into())],
+            ),
+            class(
+                "ch.emilycares.ArrayList",
+                SuperClass::Name("Object".into()),
+                vec![],
+            ),
+        ]);
+        let index = SubtypeIndex::new(&class_map);
+        assert_eq!(
+            index.direct_subtypes("java.util.List"),
+            ["ch.emilycares.Other", "java.util.ArrayList"]
+        );
+        assert_eq!(
+            index.direct_subtypes("ch.emilycares.ArrayList"),
+            ["ch.emilycares.MyList"]
+        );
+        assert_eq!(
+            index.all_subtypes("java.util.AbstractList"),
+            vec!["java.util.ArrayList"]
+        );
+        assert_eq!(
+            index.direct_supertypes("java.util.ArrayList"),
+            ["java.util.AbstractList", "java.util.List"]
+        );
+        assert!(
+            index
+                .direct_supertypes("ch.emilycares.ArrayList")
+                .is_empty()
+        );
+        assert_eq!(
+            index.all_supertypes("ch.emilycares.Other"),
+            vec!["java.util.List"]
+        );
+
+        let mut index = index;
+        let other = class("ch.emilycares.Other", SuperClass::None, vec![]);
+        class_map.insert(other.class_path.clone(), other.clone());
+        index.update(&other, &class_map);
+        assert_eq!(
+            index.all_subtypes("java.util.List"),
+            vec!["java.util.ArrayList"]
+        );
+    }
+}
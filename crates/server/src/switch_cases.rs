@@ -0,0 +1,704 @@
+//! Cases that a switch over an enum or a sealed type does not handle
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::Write,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+use ast::{
+    range::GetRange,
+    types::{
+        AstBaseExpression, AstBlockEntry, AstExpressionIdentifier, AstExpressionKind,
+        AstExpressionOrDefault, AstFile, AstPoint, AstRange, AstSwitch, AstThing,
+        AstThingAttributes, AstTopLevel,
+    },
+    visit::{self, Visitor},
+};
+use document::Document;
+use dto::{Access, Class, ImportUnit, JType, SourceDestination};
+use local_variable::LocalVariable;
+use lsp_extra::{SERVER_NAME, to_lsp_range};
+use lsp_types::{CodeActionKind, CodeActionOrCommand, Diagnostic, NumberOrString, TextEdit};
+use my_string::{MyString, smol_str::ToSmolStr};
+use variables::VariableContext;
+
+use crate::{
+    codeaction::{CodeActionContext, CodeActionError},
+    diagnostics::{default_severity, qualified_name, thing_supers},
+    refactor::{edit_to_code_action, import_edits, line_indent, type_display},
+};
+
+/// Diagnostic code of switches that do not handle every enum constant or permitted subtype
+pub const MISSING_CASES: &str = "missing_cases";
+
+/// Permitted types that are sealed themselves are followed this deep
+const MAX_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MissingCase {
+    /// Name of an enum constant
+    Constant(MyString),
+    /// Class path of a permitted subtype
+    Type(MyString),
+}
+
+impl MissingCase {
+    fn name(&self) -> &str {
+        match self {
+            Self::Constant(name) => name,
+            Self::Type(class_path) => simple_name(class_path),
+        }
+    }
+}
+
+/// Switches without a default case that miss constants of their enum or permitted subtypes of
+/// their sealed type
+#[must_use]
+pub fn missing_switch_cases(
+    ast: &AstFile,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<Diagnostic> {
+    let mut switches = Switches {
+        point: None,
+        found: vec![],
+    };
+    switches.visit_file(ast);
+    if switches.found.iter().all(|(switch, _)| has_default(switch)) {
+        return vec![];
+    }
+    let imports = imports::imports(ast);
+    let class = parser::java::load_java_tree(ast, SourceDestination::None);
+    switches
+        .found
+        .into_iter()
+        .filter(|(switch, _)| !has_default(switch))
+        .filter_map(|(switch, _)| {
+            let vars = variables::get_vars(
+                ast,
+                &VariableContext {
+                    point: Some(switch.range.start),
+                    imports: &imports,
+                    class: &class,
+                    class_map: class_map.clone(),
+                },
+            )
+            .ok()?;
+            let missing = missing_cases(switch, ast, &imports, &class, class_map, &vars);
+            if missing.is_empty() {
+                return None;
+            }
+            let names: Vec<&str> = missing.iter().map(MissingCase::name).collect();
+            Some(Diagnostic {
+                range: to_lsp_range(&header_range(switch)).ok()?,
+                severity: default_severity(MISSING_CASES),
+                code: Some(NumberOrString::String(MISSING_CASES.to_string())),
+                source: Some(SERVER_NAME.to_string()),
+                message: format!("the switch does not handle {}", names.join(", ")),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Insert the cases that the switch whose header contains the point does not handle
+pub fn add_missing_cases(
+    document: &Document,
+    context: &CodeActionContext,
+) -> Result<Vec<CodeActionOrCommand>, CodeActionError> {
+    let mut switches = Switches {
+        point: Some(context.point),
+        found: vec![],
+    };
+    switches.visit_file(&document.ast);
+    let Some((switch, inline)) = switches.found.pop() else {
+        return Ok(vec![]);
+    };
+    if has_default(switch) {
+        return Ok(vec![]);
+    }
+    let missing = missing_cases(
+        switch,
+        &document.ast,
+        context.imports,
+        context.class,
+        &context.class_map,
+        context.vars,
+    );
+    if missing.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let entries = &switch.block.entries;
+    let close = AstPoint {
+        line: switch.block.range.end.line,
+        col: switch.block.range.end.col.saturating_sub(1),
+    };
+    let close_indent = line_indent(document, close.line);
+    let case_indent = entries.iter().find(|e| is_case(e)).map_or_else(
+        || format!("{close_indent}    "),
+        |e| line_indent(document, e.get_range().start.line),
+    );
+    let arrow = entries.iter().any(|e| {
+        matches!(
+            e,
+            AstBlockEntry::SwitchCaseArrowValues(_)
+                | AstBlockEntry::SwitchCaseArrowType(_)
+                | AstBlockEntry::SwitchCaseArrowDefault(_)
+        )
+    }) || (!entries.iter().any(is_case)
+        && (inline || missing.iter().any(|m| matches!(m, MissingCase::Type(_)))));
+    let body = if inline {
+        "throw new UnsupportedOperationException();"
+    } else if arrow {
+        "{}"
+    } else {
+        "break;"
+    };
+    let mut cases = String::new();
+    for case in &missing {
+        let label = match case {
+            MissingCase::Constant(name) => name.to_string(),
+            MissingCase::Type(class_path) => {
+                format!(
+                    "{} {}",
+                    type_display(&JType::Class(class_path.clone())),
+                    variable_name(simple_name(class_path))
+                )
+            }
+        };
+        if arrow {
+            let _ = writeln!(cases, "{case_indent}case {label} -> {body}");
+        } else {
+            let _ = writeln!(cases, "{case_indent}case {label}:\n{case_indent}    {body}");
+        }
+    }
+    let before_close = document.range_text(&AstRange {
+        start: AstPoint::new(close.line, 0),
+        end: close,
+    });
+    let (start, new_text) = if before_close.trim().is_empty() {
+        (AstPoint::new(close.line, 0), cases)
+    } else {
+        (close, format!("\n{cases}{close_indent}"))
+    };
+    let mut edits = vec![TextEdit {
+        range: to_lsp_range(&AstRange { start, end: start })
+            .map_err(CodeActionError::ToLspRange)?,
+        new_text,
+    }];
+    // Nested classes are written with the name of the outer class
+    let class_paths: Vec<MyString> = missing
+        .iter()
+        .filter_map(|m| match m {
+            MissingCase::Type(class_path) => Some(
+                class_path
+                    .split('$')
+                    .next()
+                    .unwrap_or(class_path)
+                    .to_smolstr(),
+            ),
+            MissingCase::Constant(_) => None,
+        })
+        .collect();
+    edits.extend(import_edits(document, context, &class_paths));
+    Ok(vec![edit_to_code_action(
+        context,
+        "Add missing switch cases",
+        CodeActionKind::QUICKFIX,
+        edits,
+    )])
+}
+
+/// Constants or permitted subtypes of the type of the checked value that no case handles
+#[must_use]
+pub fn missing_cases(
+    switch: &AstSwitch,
+    ast: &AstFile,
+    imports: &[ImportUnit],
+    class: &Class,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    vars: &[LocalVariable],
+) -> Vec<MissingCase> {
+    let mut handled = vec![];
+    let mut patterns = false;
+    for entry in &switch.block.entries {
+        let labels = match entry {
+            AstBlockEntry::SwitchCase(case) => &case.expressions,
+            AstBlockEntry::SwitchCaseArrowValues(case) => &case.values,
+            AstBlockEntry::SwitchCaseArrowType(case) => {
+                patterns = true;
+                if let Some(name) = qualified_name(&case.var.jtype) {
+                    handled.push(simple_name(&name).to_string());
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        for label in labels {
+            let AstExpressionOrDefault::Expression(expression) = label else {
+                continue;
+            };
+            match case_label(expression) {
+                Some(Label::Constant(name)) => handled.push(simple_name(name).to_string()),
+                Some(Label::Type(name)) => {
+                    patterns = true;
+                    handled.push(simple_name(name).to_string());
+                }
+                Some(Label::Guarded) => patterns = true,
+                None => (),
+            }
+        }
+    }
+
+    let mut point = switch.check.get_range().end;
+    point.col += 1;
+    let mut call_chain = vec![];
+    call_chain::cc_expr(&switch.check, &point, false, &mut call_chain);
+    let Ok(resolved) =
+        tyres::resolve_call_chain_value(&call_chain, vars, imports, class, class_map)
+    else {
+        return vec![];
+    };
+    if resolved.class.access.contains(Access::Enum) {
+        return resolved
+            .class
+            .fields
+            .iter()
+            .filter(|f| f.access.contains(Access::Enum))
+            .filter(|f| !handled.iter().any(|h| *h == f.name))
+            .map(|f| MissingCase::Constant(f.name.clone()))
+            .collect();
+    }
+    if !patterns {
+        return vec![];
+    }
+    let Some(permitted) = permitted_types(&resolved.class.class_path, ast, class_map) else {
+        return vec![];
+    };
+    permitted
+        .into_iter()
+        .filter(|p| !is_handled(p, &handled, ast, class_map, 0))
+        .map(MissingCase::Type)
+        .collect()
+}
+
+fn is_handled(
+    class_path: &str,
+    handled: &[String],
+    ast: &AstFile,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+    depth: usize,
+) -> bool {
+    if handled.iter().any(|h| h == simple_name(class_path)) {
+        return true;
+    }
+    if depth >= MAX_DEPTH {
+        return false;
+    }
+    permitted_types(class_path, ast, class_map).is_some_and(|permitted| {
+        !permitted.is_empty()
+            && permitted
+                .iter()
+                .all(|p| is_handled(p, handled, ast, class_map, depth + 1))
+    })
+}
+
+/// Class paths of the types that may extend the class when it is sealed
+fn permitted_types(
+    class_path: &str,
+    ast: &AstFile,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Option<Vec<MyString>> {
+    let (package, names) = class_path.rsplit_once('.').unwrap_or(("", class_path));
+    let names: Vec<&str> = names.split('$').collect();
+    let declaring = if file_package(ast) == package && find_thing(ast, &names).is_some() {
+        Cow::Borrowed(ast)
+    } else {
+        let source = class_map.read().ok()?.get(class_path)?.get_source()?;
+        if !source.ends_with(".java") {
+            return None;
+        }
+        Cow::Owned(
+            Document::setup_read(PathBuf::from(source.as_str()))
+                .ok()?
+                .ast,
+        )
+    };
+    let thing = find_thing(&declaring, &names)?;
+    let (attributes, permits) = match thing {
+        AstThing::Class(class) => (&class.attributes, &class.permits),
+        AstThing::Interface(interface) => (&interface.attributes, &interface.permits),
+        AstThing::Record(_) | AstThing::Enumeration(_) | AstThing::Annotation(_) => return None,
+    };
+    if !attributes.contains(AstThingAttributes::Sealed) {
+        return None;
+    }
+    if !permits.is_empty() {
+        let imports = imports::imports(&declaring);
+        return Some(
+            permits
+                .iter()
+                .filter_map(|p| {
+                    tyres::resolve_jtype(&JType::from(p), &imports, class_map)
+                        .ok()
+                        .map(|r| r.class.class_path)
+                })
+                .collect(),
+        );
+    }
+    // Without a permits clause the subtypes are declared in the same file
+    let name = names.last()?;
+    let mut out = vec![];
+    let mut path = vec![];
+    for top in &declaring.top {
+        if let AstTopLevel::Thing(thing) = top {
+            subtypes_in_file(thing, name, package, &mut path, &mut out);
+        }
+    }
+    Some(out)
+}
+
+fn subtypes_in_file<'a>(
+    thing: &'a AstThing,
+    supertype: &str,
+    package: &str,
+    path: &mut Vec<&'a str>,
+    out: &mut Vec<MyString>,
+) {
+    path.push(&thing_name(thing).value);
+    if thing_supers(thing)
+        .iter()
+        .any(|s| simple_name(s.name.split('<').next().unwrap_or(&s.name)) == supertype)
+    {
+        let names = path.join("$");
+        out.push(if package.is_empty() {
+            names.into()
+        } else {
+            format!("{package}.{names}").into()
+        });
+    }
+    for inner in inner_things(thing) {
+        subtypes_in_file(inner, supertype, package, path, out);
+    }
+    path.pop();
+}
+
+fn find_thing<'a>(ast: &'a AstFile, names: &[&str]) -> Option<&'a AstThing> {
+    let (first, rest) = names.split_first()?;
+    let mut thing = ast.top.iter().find_map(|t| match t {
+        AstTopLevel::Thing(thing) if thing_name(thing).value == *first => Some(&**thing),
+        _ => None,
+    })?;
+    for name in rest {
+        thing = inner_things(thing)
+            .iter()
+            .find(|t| thing_name(t).value == *name)?;
+    }
+    Some(thing)
+}
+
+const fn thing_name(thing: &AstThing) -> &ast::types::AstIdentifier {
+    match thing {
+        AstThing::Class(class) => &class.name,
+        AstThing::Record(record) => &record.name,
+        AstThing::Interface(interface) => &interface.name,
+        AstThing::Enumeration(enumeration) => &enumeration.name,
+        AstThing::Annotation(annotation) => &annotation.name,
+    }
+}
+
+fn inner_things(thing: &AstThing) -> &[AstThing] {
+    match thing {
+        AstThing::Class(class) => &class.block.inner,
+        AstThing::Record(record) => &record.block.inner,
+        AstThing::Interface(interface) => &interface.inner,
+        AstThing::Enumeration(enumeration) => &enumeration.inner,
+        AstThing::Annotation(annotation) => &annotation.inner,
+    }
+}
+
+fn file_package(ast: &AstFile) -> &str {
+    ast.top
+        .iter()
+        .find_map(|t| match t {
+            AstTopLevel::Package(package) => Some(package.name.value.as_str()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// What a case label matches
+enum Label<'a> {
+    /// `RED` or `Color.RED`
+    Constant(&'a str),
+    /// `Circle c` or the record pattern `Square(var side)`
+    Type(&'a str),
+    /// A pattern with a `when` guard, it does not handle its type for sure
+    Guarded,
+}
+
+fn case_label(expression: &[AstExpressionKind]) -> Option<Label<'_>> {
+    fn ident(kind: &AstExpressionKind) -> Option<&str> {
+        match kind {
+            AstExpressionKind::Base(AstBaseExpression {
+                ident: Some(AstExpressionIdentifier::Identifier(ident)),
+                values: None,
+                ..
+            }) => Some(ident.value.as_str()),
+            _ => None,
+        }
+    }
+    if expression.iter().any(|e| ident(e) == Some("when")) {
+        return Some(Label::Guarded);
+    }
+    match expression {
+        [
+            name,
+            AstExpressionKind::Base(AstBaseExpression {
+                ident: None,
+                values: Some(_),
+                ..
+            }),
+        ] => ident(name).map(Label::Type),
+        [.., jtype, name] if ident(name).is_some() && ident(jtype).is_some() => {
+            ident(jtype).map(Label::Type)
+        }
+        [.., name] => ident(name).map(Label::Constant),
+        [] => None,
+    }
+}
+
+fn has_default(switch: &AstSwitch) -> bool {
+    switch.block.entries.iter().any(|entry| match entry {
+        AstBlockEntry::SwitchDefault(_) | AstBlockEntry::SwitchCaseArrowDefault(_) => true,
+        AstBlockEntry::SwitchCase(case) => case
+            .expressions
+            .iter()
+            .any(|e| matches!(e, AstExpressionOrDefault::Default)),
+        AstBlockEntry::SwitchCaseArrowValues(case) => case
+            .values
+            .iter()
+            .any(|e| matches!(e, AstExpressionOrDefault::Default)),
+        _ => false,
+    })
+}
+
+const fn is_case(entry: &AstBlockEntry) -> bool {
+    matches!(
+        entry,
+        AstBlockEntry::SwitchCase(_)
+            | AstBlockEntry::SwitchDefault(_)
+            | AstBlockEntry::SwitchCaseArrowValues(_)
+            | AstBlockEntry::SwitchCaseArrowType(_)
+            | AstBlockEntry::SwitchCaseArrowDefault(_)
+    )
+}
+
+/// From `switch` to the end of the checked value
+fn header_range(switch: &AstSwitch) -> AstRange {
+    AstRange {
+        start: switch.range.start,
+        end: switch.check.get_range().end,
+    }
+}
+
+/// `Circle` of `ch.emilycares.Shape$Circle`
+fn simple_name(class_path: &str) -> &str {
+    class_path.rsplit(['.', '$']).next().unwrap_or(class_path)
+}
+
+/// `circle` for a pattern of `Circle`
+fn variable_name(type_name: &str) -> String {
+    let mut chars = type_name.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_lowercase().chain(chars).collect()
+    })
+}
+
+/// All switch statements and expressions, or the one whose header contains the point.
+/// Switch expressions are marked with true.
+struct Switches<'a, 'b> {
+    point: Option<&'b AstPoint>,
+    found: Vec<(&'a AstSwitch, bool)>,
+}
+
+impl<'a> Switches<'a, '_> {
+    fn add(&mut self, switch: &'a AstSwitch, inline: bool) {
+        let in_header = self
+            .point
+            .is_none_or(|point| switch.range.start <= *point && *point <= switch.block.range.start);
+        if in_header {
+            self.found.push((switch, inline));
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Switches<'a, '_> {
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        if let AstBlockEntry::Switch(switch) = entry {
+            self.add(switch, false);
+        }
+        visit::walk_block_entry(self, entry);
+    }
+
+    fn visit_expression_kind(&mut self, kind: &'a AstExpressionKind) {
+        if let AstExpressionKind::InlineSwitch(switch) = kind {
+            self.add(switch, true);
+        }
+        visit::walk_expression_kind(self, kind);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr};
+
+    use document::Document;
+    use dto::Field;
+    use expect_test::expect;
+    use lsp_types::Uri;
+
+    use super::*;
+    use crate::refactor::tests::{apply_edits, get_class_map};
+
+    const CONTENT: &str = r#"
+package ch.emilycares;
+public class Test {
+    public void hello(Color color, Shape shape, Animal animal) {
+        switch (color) {
+            case RED:
+                break;
+        }
+        String a = switch (color) {
+            case RED, GREEN -> "a";
+            default -> "b";
+        };
+        switch (shape) {
+            case Circle c -> {}
+        }
+        switch (animal) {
+            case Dog d when d.old() -> {}
+        }
+    }
+}
+sealed interface Shape permits Circle, Square {}
+final class Circle implements Shape {}
+final class Square implements Shape {}
+sealed interface Animal {}
+final class Dog implements Animal {}
+final class Cat implements Animal {}
+"#;
+
+    fn class_map() -> Arc<RwLock<HashMap<MyString, Class>>> {
+        let class_map = get_class_map();
+        let mut classes = class_map.write().unwrap();
+        let constant = |name: &str| Field {
+            access: Access::Public | Access::Static | Access::Final | Access::Enum,
+            name: name.into(),
+            jtype: JType::Class("ch.emilycares.Color".into()),
+            ..Default::default()
+        };
+        classes.insert(
+            "ch.emilycares.Color".into(),
+            Class {
+                class_path: "ch.emilycares.Color".into(),
+                name: "Color".into(),
+                access: Access::Public | Access::Enum,
+                fields: vec![constant("RED"), constant("GREEN"), constant("BLUE")],
+                ..Default::default()
+            },
+        );
+        for name in ["Shape", "Circle", "Square", "Animal", "Dog", "Cat"] {
+            let class_path = MyString::from(format!("ch.emilycares.{name}"));
+            classes.insert(
+                class_path.clone(),
+                Class {
+                    class_path,
+                    name: name.into(),
+                    source: SourceDestination::Here("/Test.java".into()),
+                    ..Default::default()
+                },
+            );
+        }
+        drop(classes);
+        class_map
+    }
+
+    fn document() -> Document {
+        Document::setup(CONTENT, PathBuf::from_str("/Test.java").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn missing_cases_diagnostics() {
+        let document = document();
+        let out: Vec<String> = missing_switch_cases(&document.ast, &class_map())
+            .into_iter()
+            .map(|d| format!("{}: {}", d.range.start.line, d.message))
+            .collect();
+        let expected = expect![[r#"
+            [
+                "4: the switch does not handle GREEN, BLUE",
+                "12: the switch does not handle Square",
+                "15: the switch does not handle Dog, Cat",
+            ]
+        "#]];
+        expected.assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn add_cases() {
+        let document = document();
+        let imports = imports::imports(&document.ast);
+        let class = parser::java::load_java_tree(&document.ast, SourceDestination::None);
+        let uri = Uri::from_str("file:///Test.java").unwrap();
+        let class_map = class_map();
+        let actions = |point: AstPoint| {
+            let vars = variables::get_vars(
+                &document.ast,
+                &VariableContext {
+                    point: Some(point),
+                    imports: &imports,
+                    class: &class,
+                    class_map: class_map.clone(),
+                },
+            )
+            .unwrap();
+            let context = CodeActionContext {
+                point: &point,
+                imports: &imports,
+                class_map: class_map.clone(),
+                class: &class,
+                vars: &vars,
+                current_file: &uri,
+            };
+            add_missing_cases(&document, &context)
+                .unwrap()
+                .into_iter()
+                .filter_map(|a| match a {
+                    CodeActionOrCommand::CodeAction(a) => Some(a),
+                    CodeActionOrCommand::Command(_) => None,
+                })
+                .map(|a| {
+                    #[allow(clippy::mutable_key_type)]
+                    let changes = a.edit.unwrap().changes.unwrap();
+                    apply_edits(&document.rope.to_string(), &changes[&uri])
+                })
+                .collect::<Vec<_>>()
+        };
+        let expected = expect![[r#"
+            [
+                "\npackage ch.emilycares;\npublic class Test {\n    public void hello(Color color, Shape shape, Animal animal) {\n        switch (color) {\n            case RED:\n                break;\n            case GREEN:\n                break;\n            case BLUE:\n                break;\n        }\n        String a = switch (color) {\n            case RED, GREEN -> \"a\";\n            default -> \"b\";\n        };\n        switch (shape) {\n            case Circle c -> {}\n        }\n        switch (animal) {\n            case Dog d when d.old() -> {}\n        }\n    }\n}\nsealed interface Shape permits Circle, Square {}\nfinal class Circle implements Shape {}\nfinal class Square implements Shape {}\nsealed interface Animal {}\nfinal class Dog implements Animal {}\nfinal class Cat implements Animal {}\n",
+            ]
+        "#]];
+        expected.assert_debug_eq(&actions(AstPoint::new(4, 10)));
+        let expected = expect![[r#"
+            [
+                "\npackage ch.emilycares;\npublic class Test {\n    public void hello(Color color, Shape shape, Animal animal) {\n        switch (color) {\n            case RED:\n                break;\n        }\n        String a = switch (color) {\n            case RED, GREEN -> \"a\";\n            default -> \"b\";\n        };\n        switch (shape) {\n            case Circle c -> {}\n            case Square square -> {}\n        }\n        switch (animal) {\n            case Dog d when d.old() -> {}\n        }\n    }\n}\nsealed interface Shape permits Circle, Square {}\nfinal class Circle implements Shape {}\nfinal class Square implements Shape {}\nsealed interface Animal {}\nfinal class Dog implements Animal {}\nfinal class Cat implements Animal {}\n",
+            ]
+        "#]];
+        expected.assert_debug_eq(&actions(AstPoint::new(12, 10)));
+        assert!(actions(AstPoint::new(8, 20)).is_empty());
+    }
+}
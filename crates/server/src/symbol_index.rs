@@ -0,0 +1,436 @@
+//! Index of the class names for `workspace/symbol` and import candidates
+//!
+//! Names are kept sorted for prefix queries, sorted by the initials of their camel humps for
+//! queries like `ArrLi` and in trigram posting lists for substring queries. The index is built
+//! from the keys of the class map when it is needed and rebuilt after the class map changed.
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use dto::{Access, Class};
+use lsp_types::SymbolKind;
+use my_string::MyString;
+
+/// Class of the index
+#[derive(Debug, PartialEq, Eq)]
+pub struct IndexedClass {
+    pub class_path: MyString,
+    pub kind: SymbolKind,
+    pub deprecated: bool,
+    /// Name without the package, nested classes keep their outer class `Outer$Inner`
+    name_start: usize,
+    /// Name of the innermost class, the part that is searched
+    search_start: usize,
+    lower: String,
+    humps: String,
+}
+
+impl IndexedClass {
+    fn new(class: &Class) -> Self {
+        let class_path = class.class_path.clone();
+        let name_start = class_path.rfind('.').map_or(0, |i| i + 1);
+        let search_start = class_path[name_start..]
+            .rfind('$')
+            .map_or(name_start, |i| name_start + i + 1);
+        let search = &class_path[search_start..];
+        Self {
+            kind: symbol_kind(&class.access),
+            deprecated: class.access.contains(Access::Deprecated),
+            lower: search.to_lowercase(),
+            humps: humps(search)
+                .iter()
+                .filter_map(|h| h.chars().next())
+                .flat_map(char::to_lowercase)
+                .collect(),
+            class_path,
+            name_start,
+            search_start,
+        }
+    }
+
+    /// `Outer$Inner` for nested classes
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.class_path[self.name_start..]
+    }
+
+    /// Name of the innermost class
+    #[must_use]
+    pub fn search_name(&self) -> &str {
+        &self.class_path[self.search_start..]
+    }
+
+    #[must_use]
+    pub fn package(&self) -> &str {
+        self.class_path[..self.name_start].trim_end_matches('.')
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SymbolIndex {
+    classes: Vec<IndexedClass>,
+    /// Indexes of the classes sorted by their lowercase name
+    by_name: Vec<u32>,
+    /// Indexes of the classes sorted by the initials of their camel humps
+    by_humps: Vec<u32>,
+    trigrams: HashMap<[u8; 3], Vec<u32>>,
+}
+
+impl SymbolIndex {
+    #[must_use]
+    pub fn new(class_map: &HashMap<MyString, Class>) -> Self {
+        let classes: Vec<IndexedClass> = class_map.values().map(IndexedClass::new).collect();
+        let ids = 0..u32::try_from(classes.len()).unwrap_or(u32::MAX);
+        let mut by_name: Vec<u32> = ids.clone().collect();
+        by_name.sort_unstable_by(|a, b| {
+            let (a, b) = (&classes[*a as usize], &classes[*b as usize]);
+            a.lower
+                .cmp(&b.lower)
+                .then_with(|| a.class_path.cmp(&b.class_path))
+        });
+        let mut by_humps: Vec<u32> = ids.clone().collect();
+        by_humps
+            .sort_unstable_by(|a, b| classes[*a as usize].humps.cmp(&classes[*b as usize].humps));
+        let mut trigrams: HashMap<[u8; 3], Vec<u32>> = HashMap::new();
+        for id in ids {
+            for trigram in classes[id as usize].lower.as_bytes().windows(3) {
+                let list = trigrams
+                    .entry([trigram[0], trigram[1], trigram[2]])
+                    .or_default();
+                // Ids are increasing, a repeated trigram of the name is the last entry
+                if list.last() != Some(&id) {
+                    list.push(id);
+                }
+            }
+        }
+        Self {
+            classes,
+            by_name,
+            by_humps,
+            trigrams,
+        }
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.classes.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// Class paths of the classes with the name, the candidates to import a type
+    #[must_use]
+    pub fn exact(&self, name: &str) -> Vec<&MyString> {
+        let search = name.rsplit('$').next().unwrap_or(name);
+        self.prefix_ids(&search.to_lowercase())
+            .iter()
+            .map(|id| &self.classes[*id as usize])
+            .filter(|c| c.name() == name)
+            .map(|c| &c.class_path)
+            .collect()
+    }
+
+    /// Classes that match the query, best matches first. A query matches the name of a class
+    /// exactly, as prefix, as camel humps or as substring, the case is ignored.
+    #[must_use]
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&IndexedClass> {
+        if query.is_empty() {
+            return vec![];
+        }
+        let lower = query.to_lowercase();
+        let mut scored: HashMap<u32, u8> = HashMap::new();
+        let mut add = |id: u32, score: u8| {
+            scored
+                .entry(id)
+                .and_modify(|s| *s = (*s).min(score))
+                .or_insert(score);
+        };
+        for id in self.prefix_ids(&lower) {
+            let score = u8::from(self.classes[*id as usize].lower != lower);
+            add(*id, score);
+        }
+        let query_humps = query_humps(query);
+        if query_humps.len() > 1 {
+            let initials: String = query_humps
+                .iter()
+                .filter_map(|h| h.chars().next())
+                .flat_map(char::to_lowercase)
+                .collect();
+            let start = self
+                .by_humps
+                .partition_point(|id| self.classes[*id as usize].humps < initials);
+            for id in &self.by_humps[start..] {
+                let class = &self.classes[*id as usize];
+                if !class.humps.starts_with(&initials) {
+                    break;
+                }
+                if camel_match(&query_humps, class.search_name()) {
+                    add(*id, 2);
+                }
+            }
+        }
+        for id in self.substring_ids(&lower) {
+            add(id, 3);
+        }
+        let mut out: Vec<(u8, &IndexedClass)> = scored
+            .into_iter()
+            .map(|(id, score)| (score, &self.classes[id as usize]))
+            .collect();
+        out.sort_unstable_by(|(a_score, a), (b_score, b)| {
+            a_score
+                .cmp(b_score)
+                .then_with(|| a.lower.len().cmp(&b.lower.len()))
+                .then_with(|| prefer_java(&a.class_path, &b.class_path))
+        });
+        out.into_iter().take(limit).map(|(_, c)| c).collect()
+    }
+
+    /// Classes with the prefix, they are next to each other in `by_name`
+    fn prefix_ids(&self, lower: &str) -> &[u32] {
+        let lower_of = |id: &u32| self.classes[*id as usize].lower.as_str();
+        let start = self.by_name.partition_point(|id| lower_of(id) < lower);
+        let rest = &self.by_name[start..];
+        &rest[..rest.partition_point(|id| lower_of(id).starts_with(lower))]
+    }
+
+    /// Classes that contain the query, shorter queries than a trigram are not searched
+    fn substring_ids(&self, lower: &str) -> Vec<u32> {
+        let mut lists: Vec<&Vec<u32>> = Vec::new();
+        for trigram in lower.as_bytes().windows(3) {
+            let Some(list) = self.trigrams.get(&[trigram[0], trigram[1], trigram[2]]) else {
+                return vec![];
+            };
+            lists.push(list);
+        }
+        lists.sort_unstable_by_key(|l| l.len());
+        let Some((first, rest)) = lists.split_first() else {
+            return vec![];
+        };
+        first
+            .iter()
+            .filter(|id| rest.iter().all(|l| l.binary_search(id).is_ok()))
+            .filter(|id| self.classes[**id as usize].lower.contains(lower))
+            .copied()
+            .collect()
+    }
+}
+
+/// Index of the class map that is rebuilt when the class map changed
+#[derive(Debug, Default)]
+pub struct Symbols {
+    index: RwLock<Option<Arc<SymbolIndex>>>,
+}
+
+impl Symbols {
+    /// Current index, a class map with another number of classes or an invalidated index is
+    /// indexed again
+    pub fn get(&self, class_map: &RwLock<HashMap<MyString, Class>>) -> Arc<SymbolIndex> {
+        let Ok(class_map) = class_map.read() else {
+            return Arc::default();
+        };
+        if let Ok(index) = self.index.read()
+            && let Some(index) = index.as_ref()
+            && index.len() == class_map.len()
+        {
+            return index.clone();
+        }
+        let index = Arc::new(SymbolIndex::new(&class_map));
+        if let Ok(mut current) = self.index.write() {
+            *current = Some(index.clone());
+        }
+        index
+    }
+
+    /// Classes of the class map were replaced
+    pub fn invalidate(&self) {
+        if let Ok(mut index) = self.index.write() {
+            *index = None;
+        }
+    }
+}
+
+#[must_use]
+pub const fn symbol_kind(access: &Access) -> SymbolKind {
+    if access.contains(Access::Annotation) || access.contains(Access::Interface) {
+        SymbolKind::INTERFACE
+    } else if access.contains(Access::Enum) {
+        SymbolKind::ENUM
+    } else {
+        SymbolKind::CLASS
+    }
+}
+
+/// Score of a name for the query with the rules of [`SymbolIndex::search`], lower is better.
+/// Used for names that are not in the index like the members of classes.
+#[must_use]
+pub fn name_score(query: &str, name: &str) -> Option<u8> {
+    if query.is_empty() {
+        return None;
+    }
+    let lower = query.to_lowercase();
+    let name_lower = name.to_lowercase();
+    if name_lower == lower {
+        return Some(0);
+    }
+    if name_lower.starts_with(&lower) {
+        return Some(1);
+    }
+    let query_humps = query_humps(query);
+    if query_humps.len() > 1 && camel_match(&query_humps, name) {
+        return Some(2);
+    }
+    (lower.len() >= 3 && name_lower.contains(&lower)).then_some(3)
+}
+
+/// `java` packages before others
+fn prefer_java(a: &str, b: &str) -> Ordering {
+    let a_j = a.starts_with("java");
+    let b_j = b.starts_with("java");
+    b_j.cmp(&a_j).then_with(|| a.cmp(b))
+}
+
+/// Words of a camel case name, `HTTPServer2Impl` is `HTTP`, `Server2` and `Impl`
+fn humps(name: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = name.char_indices().collect();
+    let mut out = vec![];
+    let mut start = None;
+    for (i, (pos, c)) in chars.iter().enumerate() {
+        if *c == '_' || *c == '$' {
+            if let Some(s) = start.take() {
+                out.push(&name[s..*pos]);
+            }
+            continue;
+        }
+        let Some(s) = start else {
+            start = Some(*pos);
+            continue;
+        };
+        let prev = chars[i - 1].1;
+        let next_lower = chars.get(i + 1).is_some_and(|(_, n)| n.is_lowercase());
+        if c.is_uppercase() && (!prev.is_uppercase() || next_lower) {
+            out.push(&name[s..*pos]);
+            start = Some(*pos);
+        }
+    }
+    if let Some(s) = start {
+        out.push(&name[s..]);
+    }
+    out
+}
+
+/// A query in capitals like `NPE` are the initials of the humps
+fn query_humps(query: &str) -> Vec<&str> {
+    if query.len() > 1 && query.chars().all(|c| c.is_ascii_uppercase()) {
+        return (0..query.len()).map(|i| &query[i..=i]).collect();
+    }
+    humps(query)
+}
+
+/// The humps of the query are the prefixes of the first humps of the name
+fn camel_match(query_humps: &[&str], name: &str) -> bool {
+    let name_humps = humps(name);
+    query_humps.len() <= name_humps.len()
+        && query_humps.iter().zip(name_humps).all(|(q, n)| {
+            n.len() >= q.len() && n.get(..q.len()).is_some_and(|n| n.eq_ignore_ascii_case(q))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dto::{Access, Class};
+    use my_string::smol_str::SmolStr;
+
+    use super::*;
+
+    fn index(class_paths: &[&str]) -> SymbolIndex {
+        let class_map: HashMap<MyString, Class> = class_paths
+            .iter()
+            .map(|class_path| {
+                let name = class_path.rsplit('.').next().unwrap_or_default();
+                let class = Class {
+                    class_path: SmolStr::new(class_path),
+                    name: SmolStr::new(name),
+                    access: Access::Public,
+                    ..Default::default()
+                };
+                (SmolStr::new(class_path), class)
+            })
+            .collect();
+        SymbolIndex::new(&class_map)
+    }
+
+    fn search(index: &SymbolIndex, query: &str) -> Vec<String> {
+        index
+            .search(query, 10)
+            .iter()
+            .map(|c| c.class_path.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn split_humps() {
+        assert_eq!(humps("ArrayList"), vec!["Array", "List"]);
+        assert_eq!(humps("HTTPServer2Impl"), vec!["HTTP", "Server2", "Impl"]);
+        assert_eq!(humps("snake_case"), vec!["snake", "case"]);
+        assert_eq!(humps("Map$Entry"), vec!["Map", "Entry"]);
+    }
+
+    #[test]
+    fn search_classes() {
+        let index = index(&[
+            "java.util.ArrayList",
+            "java.util.List",
+            "java.util.LinkedList",
+            "java.util.Map$Entry",
+            "org.example.ArrayListener",
+            "org.example.List",
+            "org.example.HTTPServer",
+        ]);
+        assert_eq!(
+            search(&index, "list"),
+            vec![
+                "java.util.List",
+                "org.example.List",
+                "java.util.ArrayList",
+                "java.util.LinkedList",
+                "org.example.ArrayListener",
+            ]
+        );
+        assert_eq!(
+            search(&index, "ArrLi"),
+            vec!["java.util.ArrayList", "org.example.ArrayListener"]
+        );
+        assert_eq!(search(&index, "HS"), vec!["org.example.HTTPServer"]);
+        assert_eq!(search(&index, "entry"), vec!["java.util.Map$Entry"]);
+        assert_eq!(search(&index, "st"), Vec::<String>::new());
+        assert_eq!(search(&index, ""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn score_names() {
+        assert_eq!(name_score("getName", "getName"), Some(0));
+        assert_eq!(name_score("get", "getName"), Some(1));
+        assert_eq!(name_score("gNa", "getName"), Some(2));
+        assert_eq!(name_score("name", "getName"), Some(3));
+        assert_eq!(name_score("na", "getName"), None);
+        assert_eq!(name_score("", "getName"), None);
+    }
+
+    #[test]
+    fn exact_names() {
+        let index = index(&["java.util.List", "org.example.List", "java.util.Map$Entry"]);
+        let mut out = index.exact("List");
+        out.sort();
+        assert_eq!(out, vec!["java.util.List", "org.example.List"]);
+        assert!(index.exact("Entry").is_empty());
+        assert_eq!(index.exact("Map$Entry"), vec!["java.util.Map$Entry"]);
+        assert!(index.exact("list").is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+//! Symbol search without an editor
+//!
+//! `java_lsp symbols <query>` indexes the project of a folder the way `java_lsp check` does and
+//! prints the classes that `workspace/symbol` finds for the query. Methods and fields of the
+//! classes of the project are matched with the same rules, the classes of dependencies only
+//! with their names.
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use dto::{Class, SourceDestination};
+use lsp_extra::to_lsp_range;
+use lsp_types::{Range, SymbolKind};
+use my_string::MyString;
+
+use crate::{check, definition, stub, symbol_index::name_score};
+
+const LIMIT: usize = 100;
+
+/// Symbol with the location of its declaration
+#[derive(Debug, PartialEq, Eq)]
+pub struct FoundSymbol {
+    pub score: u8,
+    pub kind: SymbolKind,
+    /// Class path of a class, `Class.member` of a member
+    pub name: String,
+    pub path: PathBuf,
+    pub range: Range,
+}
+
+/// Member of a class of the project that matches the query
+#[derive(Debug, PartialEq, Eq)]
+pub struct Member {
+    pub score: u8,
+    pub kind: SymbolKind,
+    pub class_path: MyString,
+    pub name: MyString,
+    pub source: MyString,
+}
+
+/// Index the project of the folder, print the symbols that match the query and return the
+/// exit code, 1 when nothing was found
+pub async fn symbols_cli(query: &str, folder: PathBuf) -> i32 {
+    let Ok(folder) = folder.canonicalize() else {
+        eprintln!("Folder {} does not exist", folder.display());
+        return 2;
+    };
+    let (backend, _project) = match check::load_folder(&folder).await {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("{e}");
+            return 2;
+        }
+    };
+    let index = backend.symbols.get(&backend.class_map);
+    let classes: Vec<(u8, SymbolKind, Class)> = {
+        let Ok(class_map) = backend.class_map.read() else {
+            return 2;
+        };
+        index
+            .search(query, LIMIT)
+            .into_iter()
+            .filter_map(|c| {
+                let score = name_score(query, c.search_name()).unwrap_or(3);
+                Some((score, c.kind, class_map.get(&c.class_path)?.clone()))
+            })
+            .collect()
+    };
+    let mut found: Vec<FoundSymbol> = classes
+        .into_iter()
+        .filter_map(|(score, kind, class)| {
+            // Sources that do not parse are shown from their start
+            let (path, range) =
+                match definition::class_location(&class, &backend.class_map, &backend.document_map)
+                {
+                    Ok(location) => (PathBuf::from(location.uri.path().as_str()), location.range),
+                    Err(_) => (
+                        PathBuf::from(stub::class_source(&class, &backend.class_map)?.as_str()),
+                        Range::default(),
+                    ),
+                };
+            Some(FoundSymbol {
+                score,
+                kind,
+                name: class.class_path.to_string(),
+                path,
+                range,
+            })
+        })
+        .collect();
+    let members = backend
+        .class_map
+        .read()
+        .map_or_else(|_| Vec::new(), |class_map| members(query, &class_map));
+    for member in members {
+        let Ok(ast) = definition::source_ast(&member.source, &backend.document_map) else {
+            continue;
+        };
+        let mut positions = Vec::new();
+        if member.kind == SymbolKind::FIELD {
+            position::get_field_position(&ast, Some(&member.name), &mut positions);
+        } else {
+            position::get_method_position(&ast, Some(&member.name), None, &mut positions);
+        }
+        found.extend(positions.iter().filter_map(|p| {
+            Some(FoundSymbol {
+                score: member.score,
+                kind: member.kind,
+                name: format!("{}.{}", member.class_path, member.name),
+                path: PathBuf::from(member.source.as_str()),
+                range: to_lsp_range(&p.range).ok()?,
+            })
+        }));
+    }
+    found.sort_by(|a, b| {
+        a.score
+            .cmp(&b.score)
+            .then_with(|| a.name.len().cmp(&b.name.len()))
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.range.start.line.cmp(&b.range.start.line))
+    });
+    found.truncate(LIMIT);
+    for symbol in &found {
+        println!("{}", line(symbol, &folder));
+    }
+    i32::from(found.is_empty())
+}
+
+/// Methods and fields of the classes of the project that match the query, overloads are
+/// found together in the file
+#[must_use]
+pub fn members(query: &str, class_map: &HashMap<MyString, Class>) -> Vec<Member> {
+    let mut out: Vec<Member> = Vec::new();
+    for class in class_map.values() {
+        let SourceDestination::Here(source) = &class.source else {
+            continue;
+        };
+        let methods = class
+            .methods
+            .iter()
+            .filter(|m| m.source.is_none())
+            .filter_map(|m| Some((SymbolKind::METHOD, m.name.as_ref()?)));
+        let fields = class
+            .fields
+            .iter()
+            .filter(|f| f.source.is_none())
+            .map(|f| (SymbolKind::FIELD, &f.name));
+        for (kind, name) in methods.chain(fields) {
+            let Some(score) = name_score(query, name) else {
+                continue;
+            };
+            if out
+                .iter()
+                .any(|m| m.kind == kind && &m.name == name && m.class_path == class.class_path)
+            {
+                continue;
+            }
+            out.push(Member {
+                score,
+                kind,
+                class_path: class.class_path.clone(),
+                name: name.clone(),
+                source: source.clone(),
+            });
+        }
+    }
+    out.sort_by(|a, b| {
+        a.score
+            .cmp(&b.score)
+            .then_with(|| a.class_path.cmp(&b.class_path))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    out
+}
+
+/// `src/App.java:3:14: class ch.App` with 1-based lines and columns, paths in the folder are
+/// relative
+#[must_use]
+pub fn line(symbol: &FoundSymbol, folder: &Path) -> String {
+    let path = symbol.path.strip_prefix(folder).unwrap_or(&symbol.path);
+    format!(
+        "{}:{}:{}: {} {}",
+        path.display(),
+        symbol.range.start.line + 1,
+        symbol.range.start.character + 1,
+        kind_name(symbol.kind),
+        symbol.name
+    )
+}
+
+const fn kind_name(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::INTERFACE => "interface",
+        SymbolKind::ENUM => "enum",
+        SymbolKind::METHOD => "method",
+        SymbolKind::FIELD => "field",
+        _ => "class",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use dto::{Class, Field, Method, SourceDestination};
+    use lsp_types::{Position, Range, SymbolKind};
+    use my_string::MyString;
+
+    use super::{FoundSymbol, line, members};
+
+    #[test]
+    fn member_symbols() {
+        let method = |name: &str| Method {
+            name: Some(MyString::from(name)),
+            ..Default::default()
+        };
+        let class = Class {
+            class_path: MyString::from("ch.App"),
+            source: SourceDestination::Here(MyString::from("/p/src/ch/App.java")),
+            methods: vec![
+                method("getName"),
+                method("getName"),
+                method("run"),
+                Method {
+                    source: Some(MyString::from("ch.Base")),
+                    ..method("getNameOfBase")
+                },
+            ],
+            fields: vec![Field {
+                name: MyString::from("name"),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let dependency = Class {
+            class_path: MyString::from("lib.Names"),
+            methods: vec![method("name")],
+            ..Default::default()
+        };
+        let class_map = HashMap::from([
+            (MyString::from("ch.App"), class),
+            (MyString::from("lib.Names"), dependency),
+        ]);
+        let found: Vec<(SymbolKind, String)> = members("name", &class_map)
+            .into_iter()
+            .map(|m| (m.kind, m.name.to_string()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (SymbolKind::FIELD, "name".to_string()),
+                (SymbolKind::METHOD, "getName".to_string()),
+            ]
+        );
+        let symbol = FoundSymbol {
+            score: 0,
+            kind: SymbolKind::METHOD,
+            name: "ch.App.getName".to_string(),
+            path: PathBuf::from("/p/src/ch/App.java"),
+            range: Range {
+                start: Position::new(4, 18),
+                end: Position::new(6, 5),
+            },
+        };
+        assert_eq!(
+            line(&symbol, &PathBuf::from("/p")),
+            "src/ch/App.java:5:19: method ch.App.getName"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Classes that only test sources see
+//!
+//! Test sources see the main sources, the other test sources and the dependencies with the test
+//! scope. Main sources that import one of the test classes are reported, test sources are never
+//! checked.
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use ast::types::{AstImportUnit, AstTopLevel};
+use common::{
+    deps::{deps_base, deps_get_source},
+    deps_dir_no_create,
+};
+use document::{Document, source_set::SourceSet};
+use dto::{Class, SourceDestination};
+use lsp_extra::{SERVER_NAME, to_lsp_range};
+use lsp_types::{Diagnostic, NumberOrString};
+use my_string::MyString;
+
+use crate::diagnostics::default_severity;
+
+/// Diagnostic code of imports of main sources that only the tests see
+pub const TEST_ONLY: &str = "test_only";
+
+/// Source folders of the dependencies with the test scope in the deps cache
+#[must_use]
+pub fn test_dependency_sources() -> Vec<PathBuf> {
+    let deps = deps_dir_no_create();
+    maven::tree::test_dependencies()
+        .iter()
+        .map(|dep| deps_get_source(&deps_base(dep, &deps)))
+        .collect()
+}
+
+/// The class is a test source or from a dependency with one of the `test_sources`
+#[must_use]
+pub fn is_test_only(class: &Class, test_sources: &[PathBuf]) -> bool {
+    match &class.source {
+        SourceDestination::Here(path) => SourceSet::of(Path::new(path.as_str())) == SourceSet::Test,
+        SourceDestination::RelativeInFolder(folder)
+        | SourceDestination::RelativeInFolderLang(folder, _) => test_sources
+            .iter()
+            .any(|source| Path::new(folder.as_str()).starts_with(source)),
+        SourceDestination::None => false,
+    }
+}
+
+/// Imports of a main source that resolve to a test class
+#[must_use]
+pub fn test_only_imports(
+    document: &Document,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<Diagnostic> {
+    if document.source_set == SourceSet::Test {
+        return vec![];
+    }
+    let test_sources = test_dependency_sources();
+    let Ok(class_map) = class_map.read() else {
+        return vec![];
+    };
+    document
+        .ast
+        .top
+        .iter()
+        .filter_map(|top| match top {
+            AstTopLevel::Import(import) => Some(import),
+            _ => None,
+        })
+        .filter_map(|import| {
+            let (AstImportUnit::Class(c)
+            | AstImportUnit::StaticClass(c)
+            | AstImportUnit::StaticClassMethod(c, _)) = &import.unit
+            else {
+                return None;
+            };
+            let class = class_map.get(c.value.as_str())?;
+            if !is_test_only(class, &test_sources) {
+                return None;
+            }
+            Some(Diagnostic {
+                range: to_lsp_range(&import.range).ok()?,
+                severity: default_severity(TEST_ONLY),
+                code: Some(NumberOrString::String(TEST_ONLY.to_string())),
+                source: Some(SERVER_NAME.to_string()),
+                message: format!("'{}' is only visible to test sources", c.value),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Remove the jars of the test dependencies from the classpath of main sources
+#[must_use]
+pub fn main_classpath(classpath: &str) -> String {
+    let Ok(m2) = maven::m2::get_maven_m2_folder() else {
+        return classpath.to_string();
+    };
+    let test_jars: Vec<PathBuf> = maven::tree::test_dependencies()
+        .iter()
+        .map(|dep| maven::m2::pom_classes_jar(dep, &maven::m2::pom_m2(dep, &m2)))
+        .collect();
+    classpath
+        .split(compile::CLASSPATH_SEPARATOR)
+        .filter(|entry| !test_jars.iter().any(|jar| Path::new(entry) == jar))
+        .collect::<Vec<_>>()
+        .join(&compile::CLASSPATH_SEPARATOR.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use dto::{Class, SourceDestination};
+    use my_string::MyString;
+
+    use super::is_test_only;
+
+    #[test]
+    fn test_classes() {
+        let class = |source| Class {
+            source,
+            ..Default::default()
+        };
+        let test_sources = [PathBuf::from("/deps/org/junit/junit/4.13/source")];
+        assert!(is_test_only(
+            &class(SourceDestination::Here(MyString::from(
+                "/p/src/test/java/ch/ATest.java"
+            ))),
+            &test_sources
+        ));
+        assert!(!is_test_only(
+            &class(SourceDestination::Here(MyString::from(
+                "/p/src/main/java/ch/A.java"
+            ))),
+            &test_sources
+        ));
+        assert!(is_test_only(
+            &class(SourceDestination::RelativeInFolder(MyString::from(
+                "/deps/org/junit/junit/4.13/source"
+            ))),
+            &test_sources
+        ));
+        assert!(!is_test_only(
+            &class(SourceDestination::RelativeInFolder(MyString::from(
+                "/deps/com/google/guava/33/source"
+            ))),
+            &test_sources
+        ));
+    }
+}
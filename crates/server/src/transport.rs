@@ -0,0 +1,260 @@
+//! Transports besides stdio for editors and containers that can not spawn the server
+use std::{
+    io::{self, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    thread,
+};
+
+use crossbeam_channel::bounded;
+use lsp_server::{Connection, Message};
+use lsp_types::notification::{Exit, Notification};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Address {
+    Tcp(String),
+    Unix(PathBuf),
+}
+
+#[derive(Debug)]
+pub enum TransportError {
+    IO(std::io::Error),
+    InvalidAddress(String),
+    UnsupportedPlatform,
+}
+
+impl Address {
+    /// `tcp://HOST:PORT` or `unix://PATH`, without a scheme the address is a tcp address
+    pub fn parse(address: &str) -> Result<Self, TransportError> {
+        if let Some(path) = address.strip_prefix("unix://") {
+            if path.is_empty() {
+                return Err(TransportError::InvalidAddress(address.to_string()));
+            }
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+        let tcp = address.strip_prefix("tcp://").unwrap_or(address);
+        match tcp.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {
+                Ok(Self::Tcp(tcp.to_string()))
+            }
+            _ => Err(TransportError::InvalidAddress(address.to_string())),
+        }
+    }
+}
+
+/// Threads that read and write the messages of a connection
+pub enum IoThreads {
+    Stdio(lsp_server::IoThreads),
+    Stream {
+        reader: thread::JoinHandle<io::Result<()>>,
+        close: Box<dyn FnOnce() + Send>,
+    },
+}
+
+impl From<lsp_server::IoThreads> for IoThreads {
+    fn from(value: lsp_server::IoThreads) -> Self {
+        Self::Stdio(value)
+    }
+}
+
+impl IoThreads {
+    /// Wait for the client to exit or disconnect, then close the stream. The writer is not
+    /// joined because background tasks can still hold a sender.
+    pub fn join(self) -> io::Result<()> {
+        match self {
+            Self::Stdio(threads) => threads.join(),
+            Self::Stream { reader, close } => {
+                let out = reader
+                    .join()
+                    .unwrap_or_else(|_| Err(io::Error::other("reader thread panicked")));
+                close();
+                out
+            }
+        }
+    }
+}
+
+/// Wait for one client on the address. The bound address is printed to stdout so that a
+/// launcher that asked for port 0 knows where to connect.
+pub fn listen(address: &Address) -> Result<(Connection, IoThreads), TransportError> {
+    match address {
+        Address::Tcp(address) => {
+            let listener = TcpListener::bind(address).map_err(TransportError::IO)?;
+            let local = listener.local_addr().map_err(TransportError::IO)?;
+            announce(&format!("tcp://{local}"));
+            let (stream, peer) = listener.accept().map_err(TransportError::IO)?;
+            tracing::info!("Accepted connection from {peer}");
+            tcp_transport(stream)
+        }
+        #[cfg(unix)]
+        Address::Unix(path) => {
+            let _ = std::fs::remove_file(path);
+            let listener =
+                std::os::unix::net::UnixListener::bind(path).map_err(TransportError::IO)?;
+            announce(&format!("unix://{}", path.display()));
+            let (stream, _) = listener.accept().map_err(TransportError::IO)?;
+            tracing::info!("Accepted connection on {}", path.display());
+            let path = path.clone();
+            let reader = stream.try_clone().map_err(TransportError::IO)?;
+            let writer = stream.try_clone().map_err(TransportError::IO)?;
+            Ok(stream_transport(reader, writer, move || {
+                let _ = stream.shutdown(std::net::Shutdown::Both);
+                let _ = std::fs::remove_file(path);
+            }))
+        }
+        #[cfg(not(unix))]
+        Address::Unix(_) => Err(TransportError::UnsupportedPlatform),
+    }
+}
+
+/// Connect to the tcp port of the editor
+pub fn socket(port: u16) -> Result<(Connection, IoThreads), TransportError> {
+    let stream = TcpStream::connect(("127.0.0.1", port)).map_err(TransportError::IO)?;
+    tcp_transport(stream)
+}
+
+/// Connect to the pipe of the editor, a unix socket or a windows named pipe
+pub fn pipe(name: &str) -> Result<(Connection, IoThreads), TransportError> {
+    #[cfg(unix)]
+    {
+        let stream = std::os::unix::net::UnixStream::connect(name).map_err(TransportError::IO)?;
+        let reader = stream.try_clone().map_err(TransportError::IO)?;
+        let writer = stream.try_clone().map_err(TransportError::IO)?;
+        Ok(stream_transport(reader, writer, move || {
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+        }))
+    }
+    #[cfg(windows)]
+    {
+        let path = if name.starts_with(r"\\") {
+            name.to_string()
+        } else {
+            format!(r"\\.\pipe\{name}")
+        };
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(TransportError::IO)?;
+        let writer = file.try_clone().map_err(TransportError::IO)?;
+        Ok(stream_transport(file, writer, || {}))
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = name;
+        Err(TransportError::UnsupportedPlatform)
+    }
+}
+
+fn tcp_transport(stream: TcpStream) -> Result<(Connection, IoThreads), TransportError> {
+    let reader = stream.try_clone().map_err(TransportError::IO)?;
+    let writer = stream.try_clone().map_err(TransportError::IO)?;
+    Ok(stream_transport(reader, writer, move || {
+        let _ = stream.shutdown(std::net::Shutdown::Both);
+    }))
+}
+
+fn announce(address: &str) {
+    let mut stdout = io::stdout();
+    let _ = writeln!(stdout, "Listening on {address}");
+    let _ = stdout.flush();
+}
+
+/// Read messages until the exit notification or the end of the stream, write messages until
+/// all senders are dropped
+pub fn stream_transport(
+    reader: impl Read + Send + 'static,
+    mut writer: impl Write + Send + 'static,
+    close: impl FnOnce() + Send + 'static,
+) -> (Connection, IoThreads) {
+    let (reader_sender, reader_receiver) = bounded::<Message>(0);
+    let (writer_sender, writer_receiver) = bounded::<Message>(0);
+    let reader = thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        while let Some(message) = Message::read(&mut reader)? {
+            let exit = matches!(&message, Message::Notification(n) if n.method == Exit::METHOD);
+            if reader_sender.send(message).is_err() || exit {
+                break;
+            }
+        }
+        Ok(())
+    });
+    thread::spawn(move || {
+        for message in writer_receiver {
+            if let Err(e) = message.write(&mut writer) {
+                tracing::error!("Unable to write message: {e:?}");
+                break;
+            }
+        }
+    });
+    let connection = Connection {
+        sender: writer_sender,
+        receiver: reader_receiver,
+    };
+    let threads = IoThreads::Stream {
+        reader,
+        close: Box::new(close),
+    };
+    (connection, threads)
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_server::Notification as LspNotification;
+
+    use super::*;
+
+    #[test]
+    fn parse_address() {
+        assert_eq!(
+            Address::parse("tcp://127.0.0.1:9000").unwrap(),
+            Address::Tcp("127.0.0.1:9000".to_string())
+        );
+        assert_eq!(
+            Address::parse("localhost:9000").unwrap(),
+            Address::Tcp("localhost:9000".to_string())
+        );
+        assert_eq!(
+            Address::parse("unix:///tmp/java_lsp.sock").unwrap(),
+            Address::Unix(PathBuf::from("/tmp/java_lsp.sock"))
+        );
+        assert!(Address::parse("tcp://127.0.0.1").is_err());
+        assert!(Address::parse("unix://").is_err());
+    }
+
+    #[test]
+    fn tcp_until_exit() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(address).unwrap();
+            let hello = LspNotification::new("hello".to_string(), ());
+            Message::Notification(hello).write(&mut stream).unwrap();
+            let exit = LspNotification::new(Exit::METHOD.to_string(), ());
+            Message::Notification(exit).write(&mut stream).unwrap();
+            let mut reader = BufReader::new(stream);
+            Message::read(&mut reader).unwrap()
+        });
+        let (stream, _) = listener.accept().unwrap();
+        let (connection, threads) = tcp_transport(stream).unwrap();
+
+        let Ok(Message::Notification(hello)) = connection.receiver.recv() else {
+            panic!("expected notification");
+        };
+        assert_eq!(hello.method, "hello");
+        let reply = LspNotification::new("reply".to_string(), ());
+        connection
+            .sender
+            .send(Message::Notification(reply))
+            .unwrap();
+        let Ok(Message::Notification(exit)) = connection.receiver.recv() else {
+            panic!("expected exit");
+        };
+        assert_eq!(exit.method, Exit::METHOD);
+        assert!(matches!(
+            client.join().unwrap(),
+            Some(Message::Notification(n)) if n.method == "reply"
+        ));
+        threads.join().unwrap();
+    }
+}
@@ -0,0 +1,998 @@
+//! Type checking of assignments, returns and calls without running javac
+//!
+//! Only values with a type that is known for sure are checked. Everything that would
+//! need type inference like generics or method calls on other classes is skipped.
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
+
+use ast::{
+    types::{
+        AstBaseExpression, AstBlock, AstBlockEntry, AstBlockVariable, AstClassConstructor,
+        AstClassMethod, AstClassVariable, AstExpressionIdentifier, AstExpressionKind,
+        AstExpressionOperator, AstExpressionOrValue, AstFile, AstIdentifier, AstInterfaceConstant,
+        AstInterfaceMethodDefault, AstJType, AstJTypeKind, AstLambda, AstMethodHeader,
+        AstMethodParameter, AstMethodParameterFlags, AstNewClass, AstNewRhs, AstRange, AstThing,
+        AstTryCatchCase, AstValue, AstValueNuget,
+    },
+    visit::{self, Visitor},
+};
+use dto::{Class, ImportUnit, JType};
+use lsp_extra::{SERVER_NAME, to_lsp_range};
+use lsp_types::{Diagnostic, NumberOrString};
+use my_string::MyString;
+use overload::{Argument, Signature, boxed, unboxed, widens};
+
+use crate::{
+    diagnostics::{
+        Declarations, OBJECT, default_severity, hierarchy, qualified_name, resolve_class_path,
+        thing_supers,
+    },
+    hover::jtype_hover_display,
+};
+
+/// Diagnostic code of values that can not be assigned to the expected type
+pub const INCOMPATIBLE_TYPES: &str = "incompatible_types";
+/// Diagnostic code of calls with the wrong number of arguments
+pub const WRONG_ARGUMENTS: &str = "wrong_arguments";
+
+/// Calls of methods and constructors of this file with the range of the header of the
+/// overload that they invoke
+pub type Selected = Vec<(AstRange, AstRange)>;
+
+/// Assignments, returns and arguments with a incompatible type and calls to methods
+/// or constructors of this file with the wrong number of arguments. Also the overloads
+/// that the calls select.
+#[must_use]
+pub fn type_check(
+    ast: &AstFile,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> (Vec<Diagnostic>, Selected) {
+    if !class_map
+        .read()
+        .is_ok_and(|class_map| class_map.contains_key(OBJECT))
+    {
+        return (vec![], vec![]);
+    }
+    let imports = imports::imports(ast);
+    let mut declarations = Declarations::default();
+    declarations.visit_file(ast);
+    let static_imports = imports
+        .iter()
+        .filter_map(|i| match i {
+            ImportUnit::StaticClassMethod(_, name) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+    let mut checker = TypeChecker {
+        declarations: &declarations,
+        imports: &imports,
+        static_imports,
+        static_prefix: imports
+            .iter()
+            .any(|i| matches!(i, ImportUnit::StaticPrefix(_))),
+        class_map,
+        scopes: vec![],
+        things: vec![],
+        returns: vec![],
+        anonymous: 0,
+        found: vec![],
+        selected: vec![],
+    };
+    checker.visit_file(ast);
+    let diagnostics = checker
+        .found
+        .into_iter()
+        .filter_map(|(range, message, code)| {
+            Some(Diagnostic {
+                range: to_lsp_range(&range).ok()?,
+                severity: default_severity(code),
+                code: Some(NumberOrString::String(code.to_string())),
+                source: Some(SERVER_NAME.to_string()),
+                message,
+                ..Default::default()
+            })
+        })
+        .collect();
+    (diagnostics, checker.selected)
+}
+
+/// Type of a value
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    /// A literal, int literals can be assigned to smaller types
+    Constant(JType),
+    Typed(JType),
+}
+
+struct TypeChecker<'a, 'b> {
+    declarations: &'b Declarations<'a>,
+    imports: &'b [ImportUnit],
+    static_imports: HashSet<&'b str>,
+    static_prefix: bool,
+    class_map: &'b Arc<RwLock<HashMap<MyString, Class>>>,
+    /// Variables with their type
+    scopes: Vec<Vec<(&'a str, Option<JType>)>>,
+    /// Enclosing things
+    things: Vec<&'a AstThing>,
+    /// Return types of the enclosing methods and lambdas
+    returns: Vec<Option<JType>>,
+    /// Depth of anonymous classes, their super types are not known
+    anonymous: usize,
+    found: Vec<(AstRange, String, &'static str)>,
+    selected: Selected,
+}
+
+/// A method or constructor of this file
+struct Overload {
+    signature: Signature,
+    ret: Option<JType>,
+    /// Range of the header, None for the implicit constructors
+    header: Option<AstRange>,
+}
+
+impl<'a> TypeChecker<'a, '_> {
+    fn resolve(&self, jtype: &AstJType) -> Option<JType> {
+        match &jtype.value {
+            AstJTypeKind::Void => Some(JType::Void),
+            AstJTypeKind::Byte => Some(JType::Byte),
+            AstJTypeKind::Char => Some(JType::Char),
+            AstJTypeKind::Double => Some(JType::Double),
+            AstJTypeKind::Float => Some(JType::Float),
+            AstJTypeKind::Int => Some(JType::Int),
+            AstJTypeKind::Long => Some(JType::Long),
+            AstJTypeKind::Short => Some(JType::Short),
+            AstJTypeKind::Boolean => Some(JType::Boolean),
+            AstJTypeKind::Array(inner) => Some(JType::Array(Box::new(self.resolve(inner)?))),
+            AstJTypeKind::Class(_)
+            | AstJTypeKind::ClassOrPackage(_)
+            | AstJTypeKind::Generic(_, _)
+            | AstJTypeKind::Access { .. } => self.resolve_name(&qualified_name(jtype)?),
+            AstJTypeKind::Wildcard | AstJTypeKind::Var => None,
+        }
+    }
+
+    fn resolve_name(&self, name: &str) -> Option<JType> {
+        if self.declarations.type_parameters.contains(name) {
+            return None;
+        }
+        // Things of this file are only compared by name
+        if self.declarations.things.contains_key(name) {
+            return Some(JType::Class(name.into()));
+        }
+        resolve_class_path(name, self.imports, self.class_map).map(JType::Class)
+    }
+
+    fn declare(&mut self, name: &'a str, jtype: Option<JType>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push((name, jtype));
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<JType> {
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|s| s.iter().rev())
+            .find(|(n, _)| *n == name)
+            .and_then(|(_, jtype)| jtype.clone())
+    }
+
+    fn value(&self, expression: &[AstExpressionKind]) -> Option<Value> {
+        match expression {
+            [
+                AstExpressionKind::Base(AstBaseExpression {
+                    ident: Some(ident),
+                    values: None,
+                    operator: AstExpressionOperator::None,
+                    ..
+                }),
+            ] => match ident {
+                AstExpressionIdentifier::Nuget(nuget)
+                | AstExpressionIdentifier::Value(AstValue::Nuget(nuget)) => literal(nuget),
+                AstExpressionIdentifier::Identifier(ident)
+                | AstExpressionIdentifier::Value(AstValue::Variable(ident)) => {
+                    self.identifier(ident)
+                }
+                AstExpressionIdentifier::ArrayAccess { .. }
+                | AstExpressionIdentifier::EmptyArrayAccess(_) => None,
+            },
+            [AstExpressionKind::NewClass(new_class)] => match &*new_class.rhs {
+                AstNewRhs::None
+                | AstNewRhs::Parameters(_, _)
+                | AstNewRhs::Block(_)
+                | AstNewRhs::ParametersAndBlock(_, _, _) => {
+                    self.resolve(&new_class.jtype).map(Value::Typed)
+                }
+                AstNewRhs::ArrayParameters(_) | AstNewRhs::Array(_) => None,
+            },
+            [
+                AstExpressionKind::Base(AstBaseExpression {
+                    ident: Some(AstExpressionIdentifier::Identifier(name)),
+                    values: None,
+                    operator: AstExpressionOperator::None,
+                    ..
+                }),
+                AstExpressionKind::Base(AstBaseExpression {
+                    ident: None,
+                    values: Some(arguments),
+                    operator: AstExpressionOperator::None,
+                    ..
+                }),
+            ] => {
+                let overloads = self.methods(&name.value)?;
+                let signatures: Vec<Signature> =
+                    overloads.iter().map(|o| o.signature.clone()).collect();
+                let arguments = self.arguments(&arguments.values);
+                let selection =
+                    overload::select(&signatures, &arguments, &*self.class_map.read().ok()?)?;
+                let [index] = selection.methods[..] else {
+                    return None;
+                };
+                overloads[index]
+                    .ret
+                    .clone()
+                    .filter(|r| *r != JType::Void)
+                    .map(Value::Typed)
+            }
+            _ => None,
+        }
+    }
+
+    fn identifier(&self, ident: &AstIdentifier) -> Option<Value> {
+        match ident.value.as_str() {
+            "null" => Some(Value::Null),
+            "true" | "false" => Some(Value::Constant(JType::Boolean)),
+            name => self.lookup(name).map(Value::Typed),
+        }
+    }
+
+    fn check_value(&mut self, target: Option<&JType>, expression: &[AstExpressionKind]) {
+        let (Some(target), Some(first), Some(last)) =
+            (target, expression.first(), expression.last())
+        else {
+            return;
+        };
+        let Some(value) = self.value(expression) else {
+            return;
+        };
+        if self.assignable(&value, target) == Some(false) {
+            let range = AstRange {
+                start: first.range().start,
+                end: last.range().end,
+            };
+            self.found.push((
+                range,
+                format!(
+                    "incompatible types: {} cannot be converted to {}",
+                    value_display(&value),
+                    jtype_hover_display(target)
+                ),
+                INCOMPATIBLE_TYPES,
+            ));
+        }
+    }
+
+    /// Can `value` be assigned to `target`. None when it is not known
+    fn assignable(&self, value: &Value, target: &JType) -> Option<bool> {
+        let (from, constant) = match value {
+            Value::Null => return Some(boxed(target).is_none()),
+            Value::Constant(jtype) => (jtype, true),
+            Value::Typed(jtype) => (jtype, false),
+        };
+        // Int constants can be narrowed
+        let narrowing = constant
+            && *from == JType::Int
+            && matches!(target, JType::Byte | JType::Short | JType::Char);
+        match (from, target) {
+            (JType::Class(c), JType::Class(t)) => self.subtype(c, t),
+            (JType::Class(c), _) if boxed(target).is_some() => {
+                Some(unboxed(c).is_some_and(|p| widens(&p, target)))
+            }
+            (_, JType::Class(t)) if boxed(from).is_some() => {
+                let boxed_from = boxed(from)?;
+                if narrowing_box(t) && constant && *from == JType::Int {
+                    return Some(true);
+                }
+                self.subtype(boxed_from, t)
+            }
+            (JType::Array(a), JType::Array(b)) => {
+                if boxed(a).is_some() || boxed(b).is_some() {
+                    return Some(a == b);
+                }
+                self.assignable(&Value::Typed((**a).clone()), b)
+            }
+            (JType::Array(_), JType::Class(t)) => {
+                Some(t == OBJECT || t == "java.lang.Cloneable" || t == "java.io.Serializable")
+            }
+            (JType::Class(_), JType::Array(_)) => Some(false),
+            _ if boxed(from).is_some() && boxed(target).is_some() => {
+                Some(widens(from, target) || narrowing)
+            }
+            _ if boxed(from).is_some() && matches!(target, JType::Array(_))
+                || matches!(from, JType::Array(_)) && boxed(target).is_some() =>
+            {
+                Some(false)
+            }
+            _ => None,
+        }
+    }
+
+    fn subtype(&self, class_path: &str, target: &str) -> Option<bool> {
+        if class_path == target || target == OBJECT {
+            return Some(true);
+        }
+        let classes = hierarchy(class_path, self.class_map)?;
+        Some(classes.iter().any(|c| c.class_path == target))
+    }
+
+    /// Signatures and return types of the methods with `name` that can be called without
+    /// a receiver. None when it is not known which methods can be called.
+    fn methods(&self, name: &str) -> Option<Vec<Overload>> {
+        if self.anonymous > 0 || self.static_prefix || self.static_imports.contains(name) {
+            return None;
+        }
+        for thing in self.things.iter().rev() {
+            let mut out = vec![];
+            let mut pending = vec![*thing];
+            let mut supers = vec![];
+            let mut visited = HashSet::new();
+            while let Some(thing) = pending.pop() {
+                if !visited.insert(std::ptr::from_ref(thing)) {
+                    continue;
+                }
+                for header in thing_methods(thing) {
+                    if header.name.value == name {
+                        out.push(Overload {
+                            signature: self.signature(&header.parameters.parameters),
+                            ret: self.resolve(&header.jtype),
+                            header: Some(header.range),
+                        });
+                    }
+                }
+                for s in thing_supers(thing) {
+                    match self.declarations.things.get(s.name.as_str()) {
+                        Some(inner) => pending.push(inner),
+                        None => supers.push(s.name),
+                    }
+                }
+                supers.push(implicit_super(thing).to_string());
+            }
+            // Inherited overloads are not known in detail
+            for s in supers {
+                let class_path = resolve_class_path(&s, self.imports, self.class_map)?;
+                let classes = hierarchy(&class_path, self.class_map)?;
+                if classes.iter().any(|c| c.names.iter().any(|n| n == name)) {
+                    return None;
+                }
+            }
+            if !out.is_empty() {
+                return Some(out);
+            }
+        }
+        None
+    }
+
+    fn signature(&self, parameters: &[AstMethodParameter]) -> Signature {
+        let variadic = parameters
+            .last()
+            .is_some_and(|p| p.flags.contains(AstMethodParameterFlags::Variatic));
+        varargs(
+            parameters.iter().map(|p| self.resolve(&p.jtype)).collect(),
+            variadic,
+        )
+    }
+
+    /// Signatures of the constructors of a class or record in this file
+    fn constructors(&self, jtype: &AstJType) -> Option<Vec<Overload>> {
+        let name = qualified_name(jtype)?;
+        let thing = self.declarations.things.get(name.as_str())?;
+        let (constructors, canonical) = match thing {
+            AstThing::Class(class) => (&class.block.constructors, None),
+            AstThing::Record(record) => (
+                &record.block.constructors,
+                Some(varargs(
+                    record
+                        .record_entries
+                        .entries
+                        .iter()
+                        .map(|e| self.resolve(&e.jtype))
+                        .collect(),
+                    record
+                        .record_entries
+                        .entries
+                        .last()
+                        .is_some_and(|e| e.variadic),
+                )),
+            ),
+            AstThing::Interface(_) | AstThing::Enumeration(_) | AstThing::Annotation(_) => {
+                return None;
+            }
+        };
+        let mut out: Vec<Overload> = constructors
+            .iter()
+            .map(|c| Overload {
+                signature: self.signature(&c.header.parameters.parameters),
+                ret: None,
+                header: Some(c.header.range),
+            })
+            .collect();
+        let implicit = |signature| Overload {
+            signature,
+            ret: None,
+            header: None,
+        };
+        if let Some(canonical) = canonical {
+            out.push(implicit(canonical));
+        } else if out.is_empty() {
+            out.push(implicit(Signature {
+                parameters: vec![],
+                variadic: false,
+            }));
+        }
+        Some(out)
+    }
+
+    fn check_call(
+        &mut self,
+        name: &str,
+        overloads: &[Overload],
+        arguments: &'a [Vec<AstExpressionKind>],
+        range: AstRange,
+    ) {
+        let mut matching = overloads
+            .iter()
+            .filter(|o| o.signature.accepts(arguments.len()));
+        let Some(overload) = matching.next() else {
+            let mut counts: Vec<(usize, bool)> = overloads
+                .iter()
+                .map(|o| &o.signature)
+                .map(|s| (s.parameters.len() - usize::from(s.variadic), s.variadic))
+                .collect();
+            counts.sort_unstable();
+            counts.dedup();
+            let expected = counts
+                .iter()
+                .map(|(count, variadic)| {
+                    if *variadic {
+                        format!("at least {count}")
+                    } else {
+                        count.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" or ");
+            let plural = if counts.iter().all(|(c, _)| *c == 1) {
+                ""
+            } else {
+                "s"
+            };
+            self.found.push((
+                range,
+                format!(
+                    "'{name}' expects {expected} argument{plural} but got {}",
+                    arguments.len()
+                ),
+                WRONG_ARGUMENTS,
+            ));
+            return;
+        };
+        if matching.next().is_none() && !overload.signature.variadic {
+            self.select(range, overload);
+            for (parameter, argument) in overload.signature.parameters.iter().zip(arguments) {
+                self.check_value(parameter.as_ref(), argument);
+            }
+            return;
+        }
+        let arguments = self.arguments(arguments);
+        let Ok(class_map) = self.class_map.read() else {
+            return;
+        };
+        let signatures: Vec<Signature> = overloads.iter().map(|o| o.signature.clone()).collect();
+        let selection = overload::select(&signatures, &arguments, &class_map);
+        drop(class_map);
+        if let Some(selection) = selection {
+            if let [index] = selection.methods[..] {
+                self.select(range, &overloads[index]);
+            }
+            return;
+        }
+        if arguments.contains(&Argument::Unknown) {
+            return;
+        }
+        let types = arguments
+            .iter()
+            .map(|a| match a {
+                Argument::Typed(jtype) => jtype_hover_display(jtype),
+                Argument::Null | Argument::Unknown => "<null>".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.found.push((
+            range,
+            format!("no suitable method found for '{name}({types})'"),
+            INCOMPATIBLE_TYPES,
+        ));
+    }
+
+    fn arguments(&self, arguments: &[Vec<AstExpressionKind>]) -> Vec<Argument> {
+        arguments
+            .iter()
+            .map(|a| match self.value(a) {
+                Some(Value::Null) => Argument::Null,
+                Some(Value::Constant(jtype) | Value::Typed(jtype)) => Argument::Typed(jtype),
+                None => Argument::Unknown,
+            })
+            .collect()
+    }
+
+    /// Remember the declaration that the call at the range invokes
+    fn select(&mut self, range: AstRange, overload: &Overload) {
+        if let Some(header) = overload.header {
+            self.selected.push((range, header));
+        }
+    }
+
+    fn thing_scope(&mut self, thing: &'a AstThing) {
+        let mut scope: Vec<(&'a str, Option<JType>)> = vec![];
+        let variables = match thing {
+            AstThing::Class(class) => class.block.variables.as_slice(),
+            AstThing::Record(record) => {
+                for entry in &record.record_entries.entries {
+                    scope.push((&entry.name.value, self.resolve(&entry.jtype)));
+                }
+                record.block.variables.as_slice()
+            }
+            AstThing::Enumeration(enumeration) => {
+                let name = &enumeration.name.value;
+                for variant in &enumeration.variants {
+                    scope.push((&variant.name.value, Some(JType::Class(name.clone()))));
+                }
+                enumeration.variables.as_slice()
+            }
+            AstThing::Interface(interface) => {
+                for constant in &interface.constants {
+                    scope.push((&constant.name.value, self.resolve(&constant.jtype)));
+                }
+                &[]
+            }
+            AstThing::Annotation(_) => &[],
+        };
+        for variable in variables {
+            scope.push((&variable.name.value, self.resolve(&variable.jtype)));
+        }
+        self.scopes.push(scope);
+    }
+
+    fn method(
+        &mut self,
+        header: Option<&'a AstMethodHeader>,
+        parameters: &'a [AstMethodParameter],
+    ) {
+        let mut scope = vec![];
+        for parameter in parameters {
+            scope.push((
+                parameter.name.value.as_str(),
+                self.resolve(&parameter.jtype),
+            ));
+        }
+        self.scopes.push(scope);
+        self.returns
+            .push(header.map_or(Some(JType::Void), |h| self.resolve(&h.jtype)));
+    }
+
+    fn end_method(&mut self) {
+        self.scopes.pop();
+        self.returns.pop();
+    }
+}
+
+impl<'a> Visitor<'a> for TypeChecker<'a, '_> {
+    fn visit_thing(&mut self, thing: &'a AstThing) {
+        self.things.push(thing);
+        self.thing_scope(thing);
+        visit::walk_thing(self, thing);
+        self.scopes.pop();
+        self.things.pop();
+    }
+
+    fn visit_class_variable(&mut self, variable: &'a AstClassVariable) {
+        if let Some(expression) = &variable.expression {
+            let target = self.resolve(&variable.jtype);
+            self.check_value(target.as_ref(), expression);
+        }
+        visit::walk_class_variable(self, variable);
+    }
+
+    fn visit_interface_constant(&mut self, constant: &'a AstInterfaceConstant) {
+        if let Some(expression) = &constant.expression {
+            let target = self.resolve(&constant.jtype);
+            self.check_value(target.as_ref(), expression);
+        }
+        visit::walk_interface_constant(self, constant);
+    }
+
+    fn visit_class_method(&mut self, method: &'a AstClassMethod) {
+        self.method(Some(&method.header), &method.header.parameters.parameters);
+        visit::walk_class_method(self, method);
+        self.end_method();
+    }
+
+    fn visit_class_constructor(&mut self, constructor: &'a AstClassConstructor) {
+        self.method(None, &constructor.header.parameters.parameters);
+        visit::walk_class_constructor(self, constructor);
+        self.end_method();
+    }
+
+    fn visit_interface_method_default(&mut self, method: &'a AstInterfaceMethodDefault) {
+        self.method(Some(&method.header), &method.header.parameters.parameters);
+        visit::walk_interface_method_default(self, method);
+        self.end_method();
+    }
+
+    fn visit_lambda(&mut self, lambda: &'a AstLambda) {
+        let scope = lambda
+            .parameters
+            .values
+            .iter()
+            .map(|p| {
+                (
+                    p.name.value.as_str(),
+                    p.jtype.as_ref().and_then(|j| self.resolve(j)),
+                )
+            })
+            .collect();
+        self.scopes.push(scope);
+        self.returns.push(None);
+        visit::walk_lambda(self, lambda);
+        self.end_method();
+    }
+
+    fn visit_block(&mut self, block: &'a AstBlock) {
+        self.scopes.push(vec![]);
+        visit::walk_block(self, block);
+        self.scopes.pop();
+    }
+
+    fn visit_block_entry(&mut self, entry: &'a AstBlockEntry) {
+        match entry {
+            AstBlockEntry::Assign(assign) => {
+                if let Some(target) = crate::diagnostics::assign_target(&assign.key) {
+                    let target = self.lookup(&target.value);
+                    self.check_value(target.as_ref(), &assign.expression);
+                }
+            }
+            AstBlockEntry::Expression(expression) => {
+                let value = crate::diagnostics::assigned_value(&expression.value);
+                if value.len() != expression.value.len()
+                    && let Some(target) = crate::diagnostics::assign_target(
+                        &expression.value[..expression.value.len() - value.len() - 1],
+                    )
+                {
+                    let target = self.lookup(&target.value);
+                    self.check_value(target.as_ref(), value);
+                }
+            }
+            AstBlockEntry::Return(ret) => {
+                let target = self.returns.last().cloned().flatten();
+                match &ret.expression {
+                    AstExpressionOrValue::Expression(expression) => {
+                        self.check_value(target.as_ref(), expression);
+                    }
+                    AstExpressionOrValue::Value(_) | AstExpressionOrValue::None => (),
+                }
+            }
+            AstBlockEntry::SwitchCaseArrowType(case) => {
+                let jtype = self.resolve(&case.var.jtype);
+                self.declare(&case.var.name.value, jtype);
+            }
+            _ => (),
+        }
+        visit::walk_block_entry(self, entry);
+    }
+
+    fn visit_block_variable(&mut self, variable: &'a AstBlockVariable) {
+        let jtype = self.resolve(&variable.jtype);
+        if let Some(value) = &variable.value {
+            self.check_value(jtype.as_ref(), value);
+        }
+        visit::walk_block_variable(self, variable);
+        self.declare(&variable.name.value, jtype);
+    }
+
+    fn visit_try_catch_case(&mut self, case: &'a AstTryCatchCase) {
+        let jtype = match case.variable.jtypes.as_slice() {
+            [jtype] => self.resolve(jtype),
+            _ => None,
+        };
+        self.scopes
+            .push(vec![(case.variable.name.value.as_str(), jtype)]);
+        visit::walk_try_catch_case(self, case);
+        self.scopes.pop();
+    }
+
+    fn visit_expression(&mut self, expression: &'a [AstExpressionKind]) {
+        for (i, kind) in expression.iter().enumerate() {
+            if let AstExpressionKind::Base(AstBaseExpression {
+                ident: Some(AstExpressionIdentifier::Identifier(name)),
+                values: None,
+                ..
+            }) = kind
+                && let Some(AstExpressionKind::Base(AstBaseExpression {
+                    ident: None,
+                    values: Some(arguments),
+                    ..
+                })) = expression.get(i + 1)
+                && (i == 0 || i == 2 && is_this_access(&expression[..2]))
+                && let Some(signatures) = self.methods(&name.value)
+            {
+                self.check_call(&name.value, &signatures, &arguments.values, name.range);
+            }
+            if let AstExpressionKind::InstanceOf(instance_of) = kind
+                && let Some(variable) = &instance_of.variable
+            {
+                let jtype = self.resolve(&instance_of.jtype);
+                self.declare(&variable.value, jtype);
+            }
+        }
+        visit::walk_expression(self, expression);
+    }
+
+    fn visit_new_class(&mut self, new_class: &'a AstNewClass) {
+        match &*new_class.rhs {
+            AstNewRhs::None => {
+                if let Some(signatures) = self.constructors(&new_class.jtype)
+                    && let Some(name) = qualified_name(&new_class.jtype)
+                {
+                    self.check_call(&name, &signatures, &[], new_class.jtype.range);
+                }
+            }
+            AstNewRhs::Parameters(_, arguments) => {
+                if let Some(signatures) = self.constructors(&new_class.jtype)
+                    && let Some(name) = qualified_name(&new_class.jtype)
+                {
+                    self.check_call(&name, &signatures, arguments, new_class.jtype.range);
+                }
+            }
+            AstNewRhs::Block(_) | AstNewRhs::ParametersAndBlock(_, _, _) => {
+                self.anonymous += 1;
+                visit::walk_new_class(self, new_class);
+                self.anonymous -= 1;
+                return;
+            }
+            AstNewRhs::ArrayParameters(_) | AstNewRhs::Array(_) => (),
+        }
+        visit::walk_new_class(self, new_class);
+    }
+}
+
+/// `this.`
+pub(crate) fn is_this_access(expression: &[AstExpressionKind]) -> bool {
+    matches!(
+        expression,
+        [
+            AstExpressionKind::Base(AstBaseExpression {
+                ident: Some(AstExpressionIdentifier::Identifier(this)),
+                ..
+            }),
+            AstExpressionKind::Base(AstBaseExpression {
+                operator: AstExpressionOperator::Dot(_),
+                ..
+            }),
+        ] if this.value == "this"
+    )
+}
+
+pub(crate) fn thing_methods(thing: &AstThing) -> Vec<&AstMethodHeader> {
+    match thing {
+        AstThing::Class(class) => class.block.methods.iter().map(|m| &m.header).collect(),
+        AstThing::Record(record) => record.block.methods.iter().map(|m| &m.header).collect(),
+        AstThing::Enumeration(enumeration) => {
+            enumeration.methods.iter().map(|m| &m.header).collect()
+        }
+        AstThing::Interface(interface) => interface
+            .methods
+            .iter()
+            .map(|m| &m.header)
+            .chain(interface.default_methods.iter().map(|m| &m.header))
+            .collect(),
+        AstThing::Annotation(_) => vec![],
+    }
+}
+
+pub(crate) const fn implicit_super(thing: &AstThing) -> &'static str {
+    match thing {
+        AstThing::Enumeration(_) => "java.lang.Enum",
+        AstThing::Record(_) => "java.lang.Record",
+        AstThing::Class(_) | AstThing::Interface(_) | AstThing::Annotation(_) => OBJECT,
+    }
+}
+
+fn literal(nuget: &AstValueNuget) -> Option<Value> {
+    let jtype = match nuget {
+        AstValueNuget::Int(_) => JType::Int,
+        AstValueNuget::Long(_) => JType::Long,
+        AstValueNuget::Double(_) => JType::Double,
+        AstValueNuget::Float(_) => JType::Float,
+        AstValueNuget::StringLiteral { .. } => JType::Class("java.lang.String".into()),
+        AstValueNuget::CharLiteral(_) => JType::Char,
+        AstValueNuget::BooleanLiteral(_) => JType::Boolean,
+        AstValueNuget::HexLiteral(_) | AstValueNuget::BinaryLiteral(_) => return None,
+    };
+    Some(Value::Constant(jtype))
+}
+
+fn value_display(value: &Value) -> String {
+    match value {
+        Value::Null => "<null>".to_string(),
+        Value::Constant(jtype) | Value::Typed(jtype) => jtype_hover_display(jtype),
+    }
+}
+
+/// Signature with the varargs parameter as array
+fn varargs(mut parameters: Vec<Option<JType>>, variadic: bool) -> Signature {
+    if variadic && let Some(last) = parameters.last_mut() {
+        *last = last.take().map(|l| JType::Array(Box::new(l)));
+    }
+    Signature {
+        parameters,
+        variadic,
+    }
+}
+
+/// Box types that int constants can be assigned to
+fn narrowing_box(class_path: &str) -> bool {
+    matches!(
+        class_path,
+        "java.lang.Byte" | "java.lang.Short" | "java.lang.Character"
+    )
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{path::PathBuf, str::FromStr};
+
+    use document::Document;
+    use dto::{Access, SuperClass};
+    use expect_test::expect;
+
+    use super::*;
+
+    fn messages(content: &str) -> Vec<String> {
+        let document = Document::setup(content, PathBuf::from_str("/Test.java").unwrap()).unwrap();
+        type_check(&document.ast, &get_class_map())
+            .0
+            .into_iter()
+            .map(|d| format!("{}: {}", d.range.start.line, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn incompatible_assignments() {
+        let content = r#"
+package ch.emilycares;
+public class Test {
+    private String name = 1;
+    private long count = 1;
+    public Number hello(int a, Integer b) {
+        String s = 1;
+        byte small = 1;
+        double d = a;
+        Object o = "text";
+        Integer boxed = a;
+        int[] numbers = null;
+        int unboxed = null;
+        a = "text";
+        this.name = "ok";
+        boolean flag = b;
+        return "number";
+    }
+}
+"#;
+        let expected = expect![[r#"
+            [
+                "3: incompatible types: int cannot be converted to String",
+                "6: incompatible types: int cannot be converted to String",
+                "12: incompatible types: <null> cannot be converted to int",
+                "13: incompatible types: String cannot be converted to int",
+                "15: incompatible types: Integer cannot be converted to boolean",
+                "16: incompatible types: String cannot be converted to Number",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content));
+    }
+
+    #[test]
+    fn wrong_arguments() {
+        let content = r#"
+package ch.emilycares;
+public class Test {
+    public Test(int a) {}
+    private void one(int a) {}
+    private void many(String a, int... rest) {}
+    private void overloaded(int a) {}
+    private void overloaded(int a, int b) {}
+    public void hello() {
+        one();
+        one(1, 2);
+        one("text");
+        this.one(1);
+        many("a");
+        many("a", 1, 2);
+        many();
+        overloaded(1, 2, 3);
+        new Test();
+        new Test(1);
+        toString();
+    }
+}
+"#;
+        let expected = expect![[r#"
+            [
+                "9: 'one' expects 1 argument but got 0",
+                "10: 'one' expects 1 argument but got 2",
+                "11: incompatible types: String cannot be converted to int",
+                "15: 'many' expects at least 1 argument but got 0",
+                "16: 'overloaded' expects 1 or 2 arguments but got 3",
+                "17: 'Test' expects 1 argument but got 0",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content));
+    }
+
+    #[test]
+    fn overloads() {
+        let content = r#"
+package ch.emilycares;
+public class Test {
+    private void show(int a) {}
+    private void show(String a) {}
+    private void join(String a, String... rest) {}
+    public void hello() {
+        show(1);
+        show("text");
+        show(null);
+        show(true);
+        join("a", "b", "c");
+        join("a", 1);
+    }
+}
+"#;
+        let expected = expect![[r#"
+            [
+                "10: no suitable method found for 'show(boolean)'",
+                "12: no suitable method found for 'join(String, int)'",
+            ]
+        "#]];
+        expected.assert_debug_eq(&messages(content));
+    }
+
+    pub(crate) fn get_class_map() -> Arc<RwLock<HashMap<MyString, Class>>> {
+        let class = |class_path: &str| Class {
+            access: Access::Public,
+            class_path: class_path.into(),
+            name: class_path.rsplit('.').next().unwrap_or_default().into(),
+            ..Default::default()
+        };
+        let mut class_map: HashMap<MyString, Class> = HashMap::new();
+        for c in [
+            class("java.lang.Object"),
+            class("java.lang.String"),
+            class("java.lang.Number"),
+            Class {
+                super_class: SuperClass::ClassPath("java.lang.Number".into()),
+                ..class("java.lang.Integer")
+            },
+            Class {
+                super_class: SuperClass::ClassPath("java.lang.Number".into()),
+                ..class("java.lang.Long")
+            },
+            class("java.lang.Boolean"),
+        ] {
+            class_map.insert(c.class_path.clone(), c);
+        }
+        Arc::new(RwLock::new(class_map))
+    }
+}
@@ -0,0 +1,245 @@
+//! Files watched by the client with `workspace/didChangeWatchedFiles`
+use std::path::Path;
+
+use common::Dependency;
+use config::project::PROJECT_CONFIG;
+use lsp_types::{
+    DidChangeWatchedFilesRegistrationOptions, FileSystemWatcher, GlobPattern, Registration,
+    notification::{DidChangeWatchedFiles, Notification},
+};
+use my_string::{MyString, smol_str::ToSmolStr};
+
+pub const REGISTRATION_ID: &str = "java_lsp/watchedFiles";
+
+/// Sources are reparsed, build files and jars reload the classpath of the project. Class files
+/// of the build output are loaded for classes that have no source, like classes of annotation
+/// processors.
+///
+/// A changed `java_lsp.toml` merges the settings again.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum WatchedKind {
+    Java,
+    Build,
+    Jar,
+    Class,
+    Settings,
+}
+
+#[must_use]
+pub fn registration() -> Registration {
+    let watchers = [
+        "**/*.java",
+        "**/pom.xml",
+        "**/build.gradle*",
+        "**/settings.gradle*",
+        "**/.classpath",
+        "**/.java_lsp/classpath.toml",
+        "**/java_lsp.toml",
+        "**/.jvmrc",
+        "**/BUILD",
+        "**/BUILD.bazel",
+        "**/MODULE.bazel",
+        "**/WORKSPACE*",
+        "**/gradle/*.versions.toml",
+        "**/*.jar",
+        "**/target/classes/**/*.class",
+        "**/build/classes/**/*.class",
+    ]
+    .into_iter()
+    .map(|glob| FileSystemWatcher {
+        glob_pattern: GlobPattern::String(glob.to_string()),
+        kind: None,
+    })
+    .collect();
+    Registration {
+        id: REGISTRATION_ID.to_string(),
+        method: DidChangeWatchedFiles::METHOD.to_string(),
+        register_options: serde_json::to_value(DidChangeWatchedFilesRegistrationOptions {
+            watchers,
+        })
+        .ok(),
+    }
+}
+
+#[must_use]
+pub fn watched_kind(path: &Path) -> Option<WatchedKind> {
+    let name = path.file_name()?.to_str()?;
+    // Have no extension
+    if matches!(
+        name,
+        ".classpath"
+            | ".jvmrc"
+            | "BUILD"
+            | "BUILD.bazel"
+            | "MODULE.bazel"
+            | "WORKSPACE"
+            | "WORKSPACE.bazel"
+    ) {
+        return Some(WatchedKind::Build);
+    }
+    if name == PROJECT_CONFIG {
+        return Some(WatchedKind::Settings);
+    }
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    match extension.as_str() {
+        "java" => Some(WatchedKind::Java),
+        "jar" => Some(WatchedKind::Jar),
+        "class" => Some(WatchedKind::Class),
+        "xml" if name == "pom.xml" => Some(WatchedKind::Build),
+        _ if name.starts_with("build.gradle") || name.starts_with("settings.gradle") => {
+            Some(WatchedKind::Build)
+        }
+        "toml" if name.ends_with(".versions.toml") || name == "classpath.toml" => {
+            Some(WatchedKind::Build)
+        }
+        _ => None,
+    }
+}
+
+/// Class path of a class file in the build output of maven `target/classes` or gradle
+/// `build/classes/<language>/<source set>`
+#[must_use]
+pub fn output_class_path(class_file: &Path) -> Option<MyString> {
+    let parts: Vec<&str> = class_file
+        .iter()
+        .map(|p| p.to_str())
+        .collect::<Option<_>>()?;
+    let start = parts
+        .windows(2)
+        .rposition(|w| matches!(w, ["target" | "build", "classes"]))?;
+    let skip = if parts[start] == "build" { 4 } else { 2 };
+    let rest = parts.get(start + skip..)?;
+    let (file_name, packages) = rest.split_last()?;
+    let name = file_name.strip_suffix(".class")?;
+    let mut class_path = packages.join(".");
+    if !class_path.is_empty() {
+        class_path.push('.');
+    }
+    class_path.push_str(name);
+    Some(class_path.to_smolstr())
+}
+
+/// Dependency of a jar in the maven repository layout
+/// `repository/org/example/lib/1.0/lib-1.0.jar`
+#[must_use]
+pub fn jar_dependency(jar: &Path) -> Option<Dependency> {
+    let version_dir = jar.parent()?;
+    let artifact_dir = version_dir.parent()?;
+    let version = version_dir.file_name()?.to_str()?;
+    let artivact_id = artifact_dir.file_name()?.to_str()?;
+    let name = jar.file_stem()?.to_str()?;
+    if !name.starts_with(&format!("{artivact_id}-{version}")) {
+        return None;
+    }
+    let mut group = Vec::new();
+    let mut dir = artifact_dir.parent();
+    while let Some(d) = dir {
+        let part = d.file_name()?.to_str()?;
+        if part == "repository" {
+            break;
+        }
+        group.push(part);
+        dir = d.parent();
+    }
+    if group.is_empty() || dir.is_none() {
+        return None;
+    }
+    group.reverse();
+    Some(Dependency {
+        group_id: group.join("."),
+        artivact_id: artivact_id.to_string(),
+        version: version.to_string(),
+        version_suffix: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn kinds() {
+        assert_eq!(
+            watched_kind(&PathBuf::from("/p/src/main/java/A.java")),
+            Some(WatchedKind::Java)
+        );
+        assert_eq!(
+            watched_kind(&PathBuf::from("/p/pom.xml")),
+            Some(WatchedKind::Build)
+        );
+        assert_eq!(
+            watched_kind(&PathBuf::from("/p/build.gradle.kts")),
+            Some(WatchedKind::Build)
+        );
+        assert_eq!(
+            watched_kind(&PathBuf::from("/p/settings.gradle")),
+            Some(WatchedKind::Build)
+        );
+        assert_eq!(
+            watched_kind(&PathBuf::from("/p/gradle/libs.versions.toml")),
+            Some(WatchedKind::Build)
+        );
+        assert_eq!(
+            watched_kind(&PathBuf::from("/p/.classpath")),
+            Some(WatchedKind::Build)
+        );
+        assert_eq!(
+            watched_kind(&PathBuf::from("/p/app/BUILD")),
+            Some(WatchedKind::Build)
+        );
+        assert_eq!(
+            watched_kind(&PathBuf::from("/p/.java_lsp/classpath.toml")),
+            Some(WatchedKind::Build)
+        );
+        assert_eq!(
+            watched_kind(&PathBuf::from("/p/libs/a.jar")),
+            Some(WatchedKind::Jar)
+        );
+        assert_eq!(
+            watched_kind(&PathBuf::from("/p/target/classes/a/A.class")),
+            Some(WatchedKind::Class)
+        );
+        assert_eq!(
+            watched_kind(&PathBuf::from("/p/java_lsp.toml")),
+            Some(WatchedKind::Settings)
+        );
+        assert_eq!(watched_kind(&PathBuf::from("/p/other.xml")), None);
+    }
+
+    #[test]
+    fn output_class_paths() {
+        assert_eq!(
+            output_class_path(&PathBuf::from("/p/target/classes/ch/emilycares/A$B.class")),
+            Some("ch.emilycares.A$B".into())
+        );
+        assert_eq!(
+            output_class_path(&PathBuf::from(
+                "/p/build/classes/java/main/ch/emilycares/A.class"
+            )),
+            Some("ch.emilycares.A".into())
+        );
+        assert_eq!(
+            output_class_path(&PathBuf::from("/p/target/classes/A.class")),
+            Some("A".into())
+        );
+        assert_eq!(
+            output_class_path(&PathBuf::from("/p/build/classes/java/main")),
+            None
+        );
+        assert_eq!(output_class_path(&PathBuf::from("/p/lib/A.class")), None);
+    }
+
+    #[test]
+    fn maven_jar() {
+        let jar = PathBuf::from(
+            "/home/u/.m2/repository/org/junit/jupiter/junit-jupiter-api/5.9.0/junit-jupiter-api-5.9.0.jar",
+        );
+        let dep = jar_dependency(&jar).unwrap();
+        assert_eq!(dep.group_id, "org.junit.jupiter");
+        assert_eq!(dep.artivact_id, "junit-jupiter-api");
+        assert_eq!(dep.version, "5.9.0");
+        assert_eq!(jar_dependency(&PathBuf::from("/p/libs/a.jar")), None);
+    }
+}
@@ -19,6 +19,7 @@ use my_string::{
     MyString,
     smol_str::{SmolStr, SmolStrBuilder, format_smolstr},
 };
+use overload::{Argument, Signature};
 
 use crate::parent::{populate_super_class, populate_super_interfaces};
 
@@ -60,6 +61,8 @@ pub enum ImportResult {
     StaticClass(MyString),
 }
 
+/// Class of a simple name. Single type imports shadow the classes of the package, which
+/// shadow the classes of on demand imports.
 #[must_use]
 pub fn is_imported<'a>(
     jtype: &'a str,
@@ -70,39 +73,75 @@ pub fn is_imported<'a>(
         return Some(ImportResult::Class(jtype.into()));
     }
 
-    imports.iter().find_map(|i| match i {
-        ImportUnit::Class(c) => {
-            if ImportUnit::class_path_match_class_name(c, jtype) {
-                return Some(ImportResult::Class(c.clone()));
-            }
-            None
-        }
-        ImportUnit::StaticClass(c) => {
-            if ImportUnit::class_path_match_class_name(c, jtype) {
-                return Some(ImportResult::StaticClass(c.clone()));
+    imports
+        .iter()
+        .find_map(|i| match i {
+            ImportUnit::Class(c) if ImportUnit::class_path_match_class_name(c, jtype) => {
+                Some(ImportResult::Class(c.clone()))
             }
-            None
-        }
-        ImportUnit::Package(p) | ImportUnit::Prefix(p) => {
-            if let Some(value) = star_import_subclass(jtype, class_map, p) {
-                return Some(value);
+            ImportUnit::StaticClass(c) if ImportUnit::class_path_match_class_name(c, jtype) => {
+                Some(ImportResult::StaticClass(c.clone()))
             }
-            None
-        }
-        ImportUnit::StaticPrefix(p) => {
-            let mut possible_class_path = SmolStrBuilder::new();
-            possible_class_path.push_str(p);
-            possible_class_path.push('.');
-            possible_class_path.push_str(jtype);
-            let possible_class_path = possible_class_path.finish();
-            if let Ok(class_map) = class_map.read()
-                && class_map.contains_key(&possible_class_path)
-            {
-                return Some(ImportResult::StaticClass(possible_class_path));
+            _ => None,
+        })
+        .or_else(|| {
+            imports.iter().find_map(|i| match i {
+                ImportUnit::Package(p) => {
+                    member_class(p, jtype, class_map).map(ImportResult::Class)
+                }
+                _ => None,
+            })
+        })
+        .or_else(|| {
+            imports.iter().find_map(|i| match i {
+                ImportUnit::Prefix(p) => member_class(p, jtype, class_map).map(ImportResult::Class),
+                ImportUnit::StaticPrefix(p) => {
+                    member_class(p, jtype, class_map).map(ImportResult::StaticClass)
+                }
+                _ => None,
+            })
+        })
+}
+
+/// Classes of the on demand imports and of `java.lang` with the name. When there are several
+/// the name is ambiguous, unless a single type import or a class of the package shadows them.
+#[must_use]
+pub fn star_imported(
+    jtype: &str,
+    imports: &[ImportUnit],
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<MyString> {
+    let mut out: Vec<MyString> = imports
+        .iter()
+        .filter_map(|i| match i {
+            ImportUnit::Prefix(p) | ImportUnit::StaticPrefix(p) => {
+                member_class(p, jtype, class_map)
             }
-            None
-        }
-        ImportUnit::StaticClassMethod(_, _) => None,
+            _ => None,
+        })
+        .chain(member_class("java.lang", jtype, class_map))
+        .collect();
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// Class of a package or nested class of a class, `java.util.Map$Entry` for `java.util.Map`
+fn member_class(
+    p: &str,
+    jtype: &str,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Option<MyString> {
+    let class_map = class_map.read().ok()?;
+    ['.', '$'].into_iter().find_map(|separator| {
+        let mut possible_class_path = SmolStrBuilder::new();
+        possible_class_path.push_str(p);
+        possible_class_path.push(separator);
+        possible_class_path.push_str(jtype);
+        let possible_class_path = possible_class_path.finish();
+        class_map
+            .contains_key(&possible_class_path)
+            .then_some(possible_class_path)
     })
 }
 
@@ -117,7 +156,7 @@ struct ImportedMethod {
     resolve_state: ResolveState,
 }
 
-/// Returns methods that are static imported and from parent
+/// Returns methods and fields that are static imported and from parent
 fn get_methods_and_fields(
     class: &Class,
     imports: &[ImportUnit],
@@ -133,8 +172,36 @@ fn get_methods_and_fields(
             ImportUnit::Package(_)
             | ImportUnit::Class(_)
             | ImportUnit::StaticClass(_)
-            | ImportUnit::Prefix(_)
-            | ImportUnit::StaticPrefix(_) => (),
+            | ImportUnit::Prefix(_) => (),
+            ImportUnit::StaticPrefix(c) => {
+                let Ok(r) = resolve_classpath(c, class_map, &[]) else {
+                    continue;
+                };
+                let is_static = |access: &Access| {
+                    access.contains(Access::Static) && !access.contains(Access::Private)
+                };
+                methods.extend(
+                    r.class
+                        .methods
+                        .iter()
+                        .filter(|m| is_static(&m.access))
+                        .filter_map(|m| m.name.clone())
+                        .map(|name| ImportedMethod {
+                            name,
+                            resolve_state: r.clone(),
+                        }),
+                );
+                fields.extend(
+                    r.class
+                        .fields
+                        .iter()
+                        .filter(|f| is_static(&f.access))
+                        .map(|f| ImportedField {
+                            name: f.name.clone(),
+                            resolve_state: r.clone(),
+                        }),
+                );
+            }
             ImportUnit::StaticClassMethod(c, name) => {
                 if let Ok(r) = resolve_classpath(c, class_map, &[]) {
                     methods.push(ImportedMethod {
@@ -149,6 +216,25 @@ fn get_methods_and_fields(
     (methods, fields)
 }
 
+/// Static field of a static import or a super class with the name
+fn static_field<'a>(
+    fields: &'a [ImportedField],
+    name: &str,
+) -> Option<(&'a ImportedField, &'a Field)> {
+    fields.iter().find_map(|imported| {
+        if imported.name != name {
+            return None;
+        }
+        let field = imported
+            .resolve_state
+            .class
+            .fields
+            .iter()
+            .find(|f| f.name == name && f.access.contains(Access::Static))?;
+        Some((imported, field))
+    })
+}
+
 fn parent_t(
     class: &Class,
     class_map: &Arc<RwLock<HashMap<SmolStr, Class>>>,
@@ -191,25 +277,6 @@ fn parent_t(
     }
 }
 
-fn star_import_subclass(
-    jtype: &str,
-    class_map: &Arc<RwLock<HashMap<my_string::smol_str::SmolStr, Class>>>,
-    p: &my_string::smol_str::SmolStr,
-) -> Option<ImportResult> {
-    let mut possible_class_path = SmolStrBuilder::new();
-    possible_class_path.push_str(p);
-    possible_class_path.push('.');
-    possible_class_path.push_str(jtype);
-    let possible_class_path = possible_class_path.finish();
-
-    if let Ok(class_map) = class_map.read()
-        && class_map.contains_key(&possible_class_path)
-    {
-        return Some(ImportResult::Class(possible_class_path));
-    }
-    None
-}
-
 pub fn resolve(
     class_name: &str,
     imports: &[ImportUnit],
@@ -296,7 +363,7 @@ pub fn resolve_with_generic(
                 let class = parent::include_parent(imported_class, class_map, args);
                 if args.is_empty() {
                     return Ok(ResolveState {
-                        jtype: JType::Class(lang_class_key),
+                        jtype: JType::Class(c),
                         class,
                     });
                 }
@@ -338,6 +405,17 @@ fn resolve_classpath(
     })
 }
 
+/// Class paths of all super classes. Starting with the direct parent
+#[must_use]
+pub fn super_class_paths(
+    class: &Class,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<MyString> {
+    let mut s = vec![];
+    parent::populate_super_class(class, class_map, &mut s);
+    s.into_iter().rev().map(|c| c.class_path).collect()
+}
+
 #[must_use]
 pub fn resolve_import(
     jtype: &str,
@@ -387,6 +465,44 @@ pub fn resolve_params(
         .collect()
 }
 
+/// Types of the arguments of a call, arguments that can not be resolved are not known
+#[must_use]
+pub fn resolve_arguments(
+    args: &[Vec<CallItem>],
+    lo_va: &[LocalVariable],
+    imports: &[ImportUnit],
+    class: &Class,
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<Argument> {
+    args.iter()
+        .map(
+            |c| match resolve_call_chain_value(c, lo_va, imports, class, class_map) {
+                Ok(state) if state.jtype != JType::Void => Argument::Typed(state.jtype),
+                _ => Argument::Unknown,
+            },
+        )
+        .collect()
+}
+
+/// Methods of the class with the name that a call with the arguments invokes, the
+/// constructors when the name is None. The most specific ones of the overloads, in the order
+/// of the class
+#[must_use]
+pub fn select_methods<'a>(
+    class: &'a Class,
+    name: Option<&str>,
+    arguments: &[Argument],
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Vec<&'a Method> {
+    let methods = class.methods.iter().filter(|m| m.name.as_deref() == name);
+    let Ok(class_map) = class_map.read() else {
+        return methods
+            .filter(|m| Signature::of(m).accepts(arguments.len()))
+            .collect();
+    };
+    overload::select_methods(methods, arguments, &class_map)
+}
+
 pub fn resolve_call_chain(
     call_chain: &[CallItem],
     lo_va: &[LocalVariable],
@@ -402,17 +518,42 @@ pub fn resolve_call_chain(
         jtype: JType::Class(class.class_path.clone()),
     }];
     let (methods, fields) = get_methods_and_fields(class, imports, class_map);
+    let mut type_arguments: &[JType] = &[];
     let mut cc = call_chain.iter().peekable();
     while let Some(item) = cc.next() {
+        if let CallItem::TypeArguments { args, .. } = item {
+            type_arguments = args;
+            continue;
+        }
         let op = if cc.peek().is_some() {
             call_chain_op(
-                item, &ops, lo_va, imports, &methods, &fields, class, class_map, true, false,
+                item,
+                &ops,
+                type_arguments,
+                lo_va,
+                imports,
+                &methods,
+                &fields,
+                class,
+                class_map,
+                true,
+                false,
             )?
         } else {
             call_chain_op_self(
-                item, &ops, lo_va, imports, &methods, &fields, class, class_map, true,
+                item,
+                &ops,
+                type_arguments,
+                lo_va,
+                imports,
+                &methods,
+                &fields,
+                class,
+                class_map,
+                true,
             )?
         };
+        type_arguments = &[];
 
         ops.push(op);
     }
@@ -436,10 +577,26 @@ pub fn resolve_call_chain_value(
         jtype: JType::Class(class.class_path.clone()),
     }];
     let (methods, fields) = get_methods_and_fields(class, imports, class_map);
+    let mut type_arguments: &[JType] = &[];
     for item in call_chain {
+        if let CallItem::TypeArguments { args, .. } = item {
+            type_arguments = args;
+            continue;
+        }
         let op = call_chain_op(
-            item, &ops, lo_va, imports, &methods, &fields, class, class_map, false, true,
+            item,
+            &ops,
+            type_arguments,
+            lo_va,
+            imports,
+            &methods,
+            &fields,
+            class,
+            class_map,
+            false,
+            true,
         )?;
+        type_arguments = &[];
         ops.push(op);
     }
     ops.last().map_or_else(
@@ -464,20 +621,45 @@ pub fn resolve_call_chain_to_point(
     }];
     let (methods, fields) = get_methods_and_fields(class, imports, class_map);
 
+    let mut type_arguments: &[JType] = &[];
     let mut cc = call_chain.iter().peekable();
     while let Some(item) = cc.next() {
         if item.get_range().is_after_range(point) {
             break;
         }
+        if let CallItem::TypeArguments { args, .. } = item {
+            type_arguments = args;
+            continue;
+        }
         let op = if cc.peek().is_some() {
             call_chain_op(
-                item, &ops, lo_va, imports, &methods, &fields, class, class_map, true, false,
+                item,
+                &ops,
+                type_arguments,
+                lo_va,
+                imports,
+                &methods,
+                &fields,
+                class,
+                class_map,
+                true,
+                false,
             )
         } else {
             call_chain_op_self(
-                item, &ops, lo_va, imports, &methods, &fields, class, class_map, true,
+                item,
+                &ops,
+                type_arguments,
+                lo_va,
+                imports,
+                &methods,
+                &fields,
+                class,
+                class_map,
+                true,
             )
         };
+        type_arguments = &[];
 
         if let Ok(op) = op {
             ops.push(op);
@@ -493,6 +675,7 @@ pub fn resolve_call_chain_to_point(
 fn call_chain_op(
     item: &CallItem,
     ops: &[ResolveState],
+    type_arguments: &[JType],
     lo_va: &[LocalVariable],
     imports: &[ImportUnit],
     methods: &[ImportedMethod],
@@ -508,26 +691,30 @@ fn call_chain_op(
             args,
             range: _,
         } => {
-            let Some(ResolveState { class, jtype: _ }) = ops.last() else {
+            let Some(ResolveState {
+                class: receiver,
+                jtype: _,
+            }) = ops.last()
+            else {
                 return Err(TyresError::NoClassInOps);
             };
-            let args_len = args.len();
-            if let Some(method) = class
-                .methods
-                .iter()
-                .filter(|m| m.name == Some(name.clone()))
-                .find(|i| i.parameters.len() == args_len)
+            let arguments = resolve_arguments(args, lo_va, imports, class, class_map);
+            if let Some(method) =
+                select_methods(receiver, Some(name), &arguments, class_map).first()
             {
-                let mut oargs = Vec::with_capacity(args.len());
-
-                for c in args {
-                    let n = resolve_call_chain_value(c, lo_va, imports, class, class_map)?;
-                    oargs.push(n.jtype);
-                }
-
-                return resolve_jtype_with_generic(&method.ret, &oargs, imports, class_map);
+                let ret = method_return(method, &arguments, type_arguments);
+                return resolve_jtype(&ret, imports, class_map);
             }
             if let Some(m) = methods.iter().find(|i| i.name == *name) {
+                // The value of a static imported method is its return type
+                if let Some(method) =
+                    select_methods(&m.resolve_state.class, Some(name), &arguments, class_map)
+                        .first()
+                    && method.access.contains(Access::Static)
+                {
+                    let ret = method_return(method, &arguments, type_arguments);
+                    return resolve_jtype(&ret, imports, class_map);
+                }
                 return Ok(m.resolve_state.clone());
             }
             Err(TyresError::MethodNotFound(name.clone()))
@@ -539,6 +726,9 @@ fn call_chain_op(
             if let Some(field) = class.fields.iter().find(|m| m.name == *name) {
                 return resolve_jtype(&field.jtype, imports, class_map);
             }
+            if let Some((_, field)) = static_field(fields, name) {
+                return resolve_jtype(&field.jtype, imports, class_map);
+            }
             if let Some(m) = fields.iter().find(|m| &m.name == name) {
                 return Ok(m.resolve_state.clone());
             }
@@ -559,11 +749,14 @@ fn call_chain_op(
             name,
             range: _,
             args,
-        } => resolve_with_generic(name, args, imports, class_map),
+        } => resolve_class_generic(name, args, imports, class_map),
         CallItem::ClassOrVariable { name, range: _ } => {
             if let Some(lo) = lo_va.iter().find(|va| va.name == *name) {
                 return resolve_var(lo, imports, class_map);
             }
+            if let Some((_, field)) = static_field(fields, name) {
+                return resolve_jtype(&field.jtype, imports, class_map);
+            }
             resolve(name, imports, class_map)
         }
         CallItem::ArgumentList {
@@ -595,12 +788,14 @@ fn call_chain_op(
             }
             Err(TyresError::NotAnArray)
         }
+        CallItem::TypeArguments { .. } => ops.last().cloned().ok_or(TyresError::NoClassInOps),
     }
 }
 #[allow(clippy::too_many_arguments)]
 fn call_chain_op_self(
     item: &CallItem,
     ops: &[ResolveState],
+    type_arguments: &[JType],
     lo_va: &[LocalVariable],
     imports: &[ImportUnit],
     methods: &[ImportedMethod],
@@ -618,17 +813,10 @@ fn call_chain_op_self(
             let Some(last) = ops.last() else {
                 return Err(TyresError::NoClassInOps);
             };
-            let args_len = args.len();
-            if let Some(method) = class
-                .methods
-                .iter()
-                .filter(|m| m.name == Some(name.clone()))
-                .find(|i| i.parameters.len() == args_len)
-            {
-                if let JType::Generic(gname, args) = &method.ret {
-                    return resolve_with_generic(gname, args, imports, class_map);
-                }
-                return resolve_jtype(&method.ret, imports, class_map);
+            let arguments = resolve_arguments(args, lo_va, imports, class, class_map);
+            if let Some(method) = select_methods(class, Some(name), &arguments, class_map).first() {
+                let ret = method_return(method, &arguments, type_arguments);
+                return resolve_jtype(&ret, imports, class_map);
             }
             if last
                 .class
@@ -673,11 +861,14 @@ fn call_chain_op_self(
             name,
             range: _,
             args,
-        } => resolve_with_generic(name, args, imports, class_map),
+        } => resolve_class_generic(name, args, imports, class_map),
         CallItem::ClassOrVariable { name, range: _ } => {
             if let Some(lo) = lo_va.iter().find(|va| va.name == *name) {
                 return resolve_var(lo, imports, class_map);
             }
+            if let Some((imported, _)) = static_field(fields, name) {
+                return Ok(imported.resolve_state.clone());
+            }
             resolve(name, imports, class_map)
         }
         CallItem::ArgumentList {
@@ -705,8 +896,53 @@ fn call_chain_op_self(
             }
             Err(TyresError::NotAnArray)
         }
+        CallItem::TypeArguments { .. } => ops.last().cloned().ok_or(TyresError::NoClassInOps),
+    }
+}
+/// Return type of a call of the method. The type parameters of the method are the explicit
+/// type arguments or are inferred from the arguments, the ones that are not are Object
+fn method_return(method: &Method, arguments: &[Argument], type_arguments: &[JType]) -> JType {
+    if method.type_parameters.is_empty() {
+        return method.ret.clone();
+    }
+    let mut bindings = if type_arguments.is_empty() {
+        overload::infer(&Signature::of(method), arguments)
+    } else {
+        method
+            .type_parameters
+            .iter()
+            .cloned()
+            .zip(type_arguments.iter().cloned())
+            .collect()
+    };
+    bindings.retain(|name, _| method.type_parameters.contains(name));
+    for name in &method.type_parameters {
+        bindings
+            .entry(name.clone())
+            .or_insert_with(|| JType::Class(SmolStr::new_static(overload::OBJECT)));
+    }
+    overload::substitute(&method.ret, &bindings)
+}
+
+/// Class of `new ArrayList<>()`, the type arguments of the diamond are Object without a
+/// target type
+fn resolve_class_generic(
+    class_name: &str,
+    args: &[JType],
+    imports: &[ImportUnit],
+    class_map: &Arc<RwLock<HashMap<MyString, Class>>>,
+) -> Result<ResolveState, TyresError> {
+    let state = resolve_with_generic(class_name, args, imports, class_map)?;
+    match &state.class.signature {
+        Some(signature) if args.is_empty() && !signature.args.is_empty() => {
+            let object = JType::Class(SmolStr::new_static(overload::OBJECT));
+            let args = vec![object; signature.args.len()];
+            resolve_with_generic(class_name, &args, imports, class_map)
+        }
+        _ => Ok(state),
     }
 }
+
 pub fn resolve_jtype(
     jtype: &JType,
     imports: &[ImportUnit],
@@ -806,12 +1042,15 @@ pub fn resolve_jtype_with_generic(
                     name: SmolStr::new_inline("length"),
                     jtype: JType::Int,
                     source: None,
+                    annotations: vec![],
                 }],
                 ..Default::default()
             },
         }),
+        JType::Generic(c, generic_args) if args.is_empty() => {
+            resolve_with_generic(c, generic_args, imports, class_map)
+        }
         JType::Class(c) | JType::Generic(c, _) | JType::ClassOrPackage(c) => {
-            // TODO: Genireics not used
             resolve_with_generic(c, args, imports, class_map)
         }
         JType::Parameter(p) => {
@@ -857,6 +1096,106 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn import_precedence() {
+        let class_map = get_class_map();
+        if let Ok(mut cm) = class_map.write() {
+            for class_path in ["java.awt.List", "java.util.Map", "java.util.Map$Entry"] {
+                cm.insert(SmolStr::new(class_path), Class::default());
+            }
+        }
+        let imported =
+            |imports: &[ImportUnit], name: &str| match is_imported(name, imports, &class_map) {
+                Some(ImportResult::Class(c) | ImportResult::StaticClass(c)) => Some(c),
+                None => None,
+            };
+        let package = ImportUnit::Package(SmolStr::new("java.awt"));
+        let util = ImportUnit::Prefix(SmolStr::new("java.util"));
+        let list = ImportUnit::Class(SmolStr::new("java.util.List"));
+        assert_eq!(
+            imported(&[package.clone(), list], "List"),
+            Some(SmolStr::new("java.util.List"))
+        );
+        assert_eq!(
+            imported(&[util.clone(), package], "List"),
+            Some(SmolStr::new("java.awt.List"))
+        );
+        assert_eq!(
+            imported(
+                &[ImportUnit::Prefix(SmolStr::new("java.util.Map"))],
+                "Entry"
+            ),
+            Some(SmolStr::new("java.util.Map$Entry"))
+        );
+        let awt = ImportUnit::Prefix(SmolStr::new("java.awt"));
+        assert_eq!(
+            star_imported("List", &[util.clone(), awt], &class_map),
+            vec![
+                SmolStr::new("java.awt.List"),
+                SmolStr::new("java.util.List")
+            ]
+        );
+        assert_eq!(
+            star_imported("String", &[util], &class_map),
+            vec![SmolStr::new("java.lang.String")]
+        );
+    }
+
+    #[test]
+    fn static_star_import() {
+        let class_map = get_class_map();
+        if let Ok(mut cm) = class_map.write() {
+            cm.insert(
+                SmolStr::new("java.lang.Math"),
+                Class {
+                    class_path: SmolStr::new("java.lang.Math"),
+                    methods: vec![Method {
+                        access: Access::Public | Access::Static,
+                        name: Some(SmolStr::new("name")),
+                        ret: JType::Class(SmolStr::new("java.lang.String")),
+                        ..Default::default()
+                    }],
+                    fields: vec![Field {
+                        access: Access::Public | Access::Static,
+                        name: SmolStr::new("E"),
+                        jtype: JType::Class(SmolStr::new("java.lang.String")),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            );
+        }
+        let imports = [ImportUnit::StaticPrefix(SmolStr::new("java.lang.Math"))];
+        let range = ast::types::AstRange::default();
+        let call = CallItem::MethodCall {
+            name: SmolStr::new("name"),
+            range,
+            args: vec![],
+        };
+        let constant = CallItem::ClassOrVariable {
+            name: SmolStr::new("E"),
+            range,
+        };
+        let resolve = |chain: &[CallItem]| {
+            resolve_call_chain(chain, &[], &imports, &Class::default(), &class_map).map(|r| r.jtype)
+        };
+        let math = Ok(JType::Class(SmolStr::new("java.lang.Math")));
+        assert_eq!(resolve(std::slice::from_ref(&call)), math);
+        assert_eq!(resolve(std::slice::from_ref(&constant)), math);
+        // `name().length()` calls length of the return type
+        let length = CallItem::MethodCall {
+            name: SmolStr::new("length"),
+            range,
+            args: vec![],
+        };
+        assert!(resolve(&[call, length]).is_ok());
+        assert_eq!(
+            resolve_call_chain_value(&[constant], &[], &imports, &Class::default(), &class_map)
+                .map(|r| r.jtype),
+            Ok(JType::Class(SmolStr::new("java.lang.String")))
+        );
+    }
+
     #[test]
     fn resolve_generic() {
         let out = resolve_with_generic(
@@ -887,12 +1226,14 @@ mod tests {
                     name: Some(
                         "first",
                     ),
+                    type_parameters: [],
                     parameters: [],
                     throws: [],
                     ret: Class(
                         "java.lang.String",
                     ),
                     source: None,
+                    annotations: [],
                 },
             )
         "#]];
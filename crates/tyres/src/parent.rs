@@ -38,9 +38,11 @@ pub fn include_parent(
     }
 
     if let Some(b) = base {
-        return overlay_class(class, &b);
+        class = overlay_class(class, &b);
     }
 
+    // Inherited methods are filled with the type parameters of the class, they mostly have
+    // the names of the parents
     class_fill_generics(&mut class, args);
 
     class
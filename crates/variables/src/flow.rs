@@ -0,0 +1,116 @@
+//! Scope of pattern variables
+//!
+//! The variable of `x instanceof Foo f` is only in scope where the pattern definitely
+//! matched. That is the branch of an if that the condition selects, the following branches
+//! when it did not match and the statements after an if whose branch can not complete
+//! normally, like `if (!(x instanceof Foo f)) return;`.
+use ast::types::{
+    AstBaseExpression, AstBlockEntry, AstExpressionKind, AstExpressionOperator, AstIfContent,
+    AstInstanceOf,
+};
+
+/// Patterns with a variable that matched when the condition evaluated to `when`
+pub fn matched(condition: &[AstExpressionKind], when: bool) -> Vec<&AstInstanceOf> {
+    let is_or = |k: &AstExpressionKind| {
+        matches!(
+            operator(k),
+            Some(AstExpressionOperator::VerticalBarVerticalBar(_))
+        )
+    };
+    let is_and = |k: &AstExpressionKind| {
+        matches!(
+            operator(k),
+            Some(AstExpressionOperator::AmpersandAmpersand(_))
+        )
+    };
+    let alternatives: Vec<&[AstExpressionKind]> = condition.split(is_or).collect();
+    if when {
+        // Every part of `a && b` was true
+        let [alternative] = alternatives.as_slice() else {
+            return Vec::new();
+        };
+        return alternative
+            .split(is_and)
+            .flat_map(|part| part_matched(part, true))
+            .collect();
+    }
+    // Every part of `a || b` was false
+    alternatives
+        .into_iter()
+        .filter(|alternative| !alternative.iter().any(is_and))
+        .flat_map(|part| part_matched(part, false))
+        .collect()
+}
+
+fn part_matched(part: &[AstExpressionKind], when: bool) -> Vec<&AstInstanceOf> {
+    match part {
+        [not, group]
+            if matches!(
+                operator(not),
+                Some(AstExpressionOperator::ExclamationMark(_))
+            ) =>
+        {
+            parenthesized(group).map_or_else(Vec::new, |inner| matched(inner, !when))
+        }
+        [group] => parenthesized(group).map_or_else(Vec::new, |inner| matched(inner, when)),
+        [.., AstExpressionKind::InstanceOf(instance_of)]
+            if when && instance_of.variable.is_some() =>
+        {
+            vec![instance_of]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Content of `(a)`
+fn parenthesized(kind: &AstExpressionKind) -> Option<&[AstExpressionKind]> {
+    match kind {
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: None,
+            values: Some(values),
+            operator: AstExpressionOperator::None,
+            ..
+        }) => match values.values.as_slice() {
+            [inner] => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+const fn operator(kind: &AstExpressionKind) -> Option<&AstExpressionOperator> {
+    match kind {
+        AstExpressionKind::Base(AstBaseExpression {
+            ident: None,
+            values: None,
+            operator,
+            ..
+        }) => Some(operator),
+        _ => None,
+    }
+}
+
+/// The content can end without a `return`, `throw`, `break`, `continue` or `yield` as last
+/// statement
+pub fn completes_normally(content: &AstIfContent) -> bool {
+    match content {
+        AstIfContent::Block(block) => block.entries.last().is_none_or(entry_completes_normally),
+        AstIfContent::BlockEntry(entry) => entry_completes_normally(entry),
+    }
+}
+
+fn entry_completes_normally(entry: &AstBlockEntry) -> bool {
+    match entry {
+        AstBlockEntry::Return(_)
+        | AstBlockEntry::Throw(_)
+        | AstBlockEntry::Break(_)
+        | AstBlockEntry::Continue(_)
+        | AstBlockEntry::Yield(_) => false,
+        AstBlockEntry::InlineBlock(inline) => inline
+            .block
+            .entries
+            .last()
+            .is_none_or(entry_completes_normally),
+        _ => true,
+    }
+}
@@ -3,6 +3,8 @@
 #![deny(clippy::nursery)]
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::too_many_lines)]
+mod flow;
+
 use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
@@ -14,10 +16,10 @@ use ast::{
         AstBaseExpression, AstBlock, AstBlockEntry, AstBlockExpression, AstBlockVariable,
         AstClassConstructor, AstClassMethod, AstExpression, AstExpressionKind,
         AstExpressionOrValue, AstFile, AstFor, AstForContent, AstForEnhanced, AstIf, AstIfContent,
-        AstInterfaceConstant, AstInterfaceMethod, AstInterfaceMethodDefault, AstJTypeKind,
-        AstLambda, AstLambdaRhs, AstNewRhs, AstPoint, AstSwitch, AstSwitchCaseArrowContent,
-        AstSwitchCaseArrowType, AstSwitchCaseArrowVar, AstThing, AstTopLevel, AstTryCatch,
-        AstWhile, AstWhileContent,
+        AstInstanceOf, AstInterfaceConstant, AstInterfaceMethod, AstInterfaceMethodDefault,
+        AstJTypeKind, AstLambda, AstLambdaRhs, AstNewRhs, AstPoint, AstSwitch,
+        AstSwitchCaseArrowContent, AstSwitchCaseArrowType, AstSwitchCaseArrowVar, AstThing,
+        AstTopLevel, AstTryCatch, AstWhile, AstWhileContent,
     },
 };
 use dto::{Class, ImportUnit, JType};
@@ -280,8 +282,35 @@ fn get_block_vars(
     if !is_in_range_c(block.range, &context.point) {
         return Ok(());
     }
-    for e in &block.entries {
-        get_block_entry_vars(e, context, out)?;
+    // Conditions of the earlier branches of an if chain
+    let mut previous = Vec::new();
+    let mut entries = block.entries.iter().peekable();
+    while let Some(e) = entries.next() {
+        let AstBlockEntry::If(ast_if) = e else {
+            get_block_entry_vars(e, context, out)?;
+            continue;
+        };
+        if_vars(ast_if, &mut previous, context, out)?;
+        // `if (!(x instanceof Foo f)) return;` matched for the following statements
+        if let AstIf::If {
+            range,
+            control,
+            content,
+            ..
+        } = ast_if
+            && !matches!(
+                entries.peek(),
+                Some(AstBlockEntry::If(AstIf::ElseIf { .. } | AstIf::Else { .. }))
+            )
+            && !flow::completes_normally(content)
+            && context.point.is_none_or(|p| range.end < p)
+        {
+            out.extend(
+                flow::matched(control, false)
+                    .into_iter()
+                    .filter_map(pattern_variable),
+            );
+        }
     }
     Ok(())
 }
@@ -309,7 +338,7 @@ fn get_block_entry_vars(
         AstBlockEntry::Throw(t) => expression(&t.expression, context, out),
         AstBlockEntry::Return(r) => expression_or_value(&r.expression, context, out),
         AstBlockEntry::Expression(ast_expression) => block_expr(ast_expression, context, out),
-        AstBlockEntry::If(ast_if) => if_vars(ast_if, context, out),
+        AstBlockEntry::If(ast_if) => if_vars(ast_if, &mut Vec::new(), context, out),
         AstBlockEntry::While(ast_while) => while_vars(ast_while, context, out),
         AstBlockEntry::For(ast_for) => for_vars(ast_for, context, out),
         AstBlockEntry::ForEnhanced(ast_for_enhanced) => {
@@ -419,16 +448,7 @@ fn expression(
             AstExpressionKind::InlineSwitch(ast_switch) => {
                 get_block_vars(&ast_switch.block, context, out)?;
             }
-            AstExpressionKind::InstanceOf(i) => {
-                if let Some(name) = &i.variable {
-                    out.push(LocalVariable {
-                        jtype: i.jtype.clone().into(),
-                        name: name.value.clone(),
-                        range: name.range,
-                        flags: VarFlags::empty(),
-                    });
-                }
-            }
+            AstExpressionKind::InstanceOf(i) => out.extend(pattern_variable(i)),
             AstExpressionKind::NewClass(nc) => new_class(nc, context, out)?,
             AstExpressionKind::Generics(_)
             | AstExpressionKind::JType(_)
@@ -658,42 +678,71 @@ fn while_vars(
     }
     Ok(())
 }
-fn if_vars(
-    ast_if: &AstIf,
+/// Branch of an if chain, `previous` are the conditions of the earlier branches that did not
+/// match
+fn if_vars<'a>(
+    ast_if: &'a AstIf,
+    previous: &mut Vec<&'a AstExpression>,
     context: &VariableContext,
     out: &mut Vec<LocalVariable>,
 ) -> Result<(), VariablesError> {
-    match ast_if {
-        AstIf::ElseIf {
+    let (range, control, control_range, body) = match ast_if {
+        AstIf::If {
             range,
             control,
-            control_range: _,
-            content,
+            control_range,
+            content: body,
+        } => {
+            previous.clear();
+            (range, Some(control), Some(control_range), body)
         }
-        | AstIf::If {
+        AstIf::ElseIf {
             range,
             control,
-            control_range: _,
-            content,
-        } => {
-            if is_in_range_c(content.get_range(), &context.point) {
-                expression(control, context, out)?;
-            }
-            if is_in_range_c(*range, &context.point)
-                && let AstIfContent::Block(block) = content
-            {
-                get_block_vars(block, context, out)?;
-            }
-        }
-        AstIf::Else { range, content } => {
-            if is_in_range_c(*range, &context.point)
-                && let AstIfContent::Block(block) = content
-            {
-                get_block_vars(block, context, out)?;
-            }
+            control_range,
+            content: body,
+        } => (range, Some(control), Some(control_range), body),
+        AstIf::Else {
+            range,
+            content: body,
+        } => (range, None, None, body),
+    };
+    if !is_in_range_c(*range, &context.point) {
+        previous.extend(control);
+        return Ok(());
+    }
+    out.extend(
+        previous
+            .iter()
+            .flat_map(|p| flow::matched(p, false))
+            .filter_map(pattern_variable),
+    );
+    if let (Some(control), Some(control_range)) = (control, control_range) {
+        if is_in_range_c(*control_range, &context.point) {
+            expression(control, context, out)?;
+        } else if is_in_range_c(body.get_range(), &context.point) {
+            out.extend(
+                flow::matched(control, true)
+                    .into_iter()
+                    .filter_map(pattern_variable),
+            );
         }
+        previous.push(control);
     }
-    Ok(())
+    match body {
+        AstIfContent::Block(block) => get_block_vars(block, context, out),
+        AstIfContent::BlockEntry(entry) => get_block_entry_vars(entry, context, out),
+    }
+}
+
+fn pattern_variable(instance_of: &AstInstanceOf) -> Option<LocalVariable> {
+    let name = instance_of.variable.as_ref()?;
+    Some(LocalVariable {
+        jtype: (&instance_of.jtype).into(),
+        name: name.value.clone(),
+        range: name.range,
+        flags: VarFlags::empty(),
+    })
 }
 fn variables(variables: &[ast::types::AstClassVariable]) -> impl Iterator<Item = LocalVariable> {
     variables.iter().map(move |i| {
@@ -719,7 +768,7 @@ pub mod tests {
     use expect_test::expect;
     use my_string::{MyString, smol_str::SmolStr};
 
-    use crate::{VariableContext, get_vars};
+    use crate::{VarFlags, VariableContext, get_vars};
 
     fn get_class_map() -> Arc<RwLock<HashMap<MyString, Class>>> {
         let mut class_map: HashMap<MyString, Class> = HashMap::new();
@@ -820,7 +869,7 @@ public class Test {
                     name: "other",
                     range: AstRange {
                         start: AstPoint { 8:4 },
-                        end: AstPoint { 8:28 },
+                        end: AstPoint { 8:29 },
                     },
                     flags: VarFlags(
                         0x0,
@@ -857,7 +906,7 @@ public class Test {
                     name: "local",
                     range: AstRange {
                         start: AstPoint { 11:8 },
-                        end: AstPoint { 11:24 },
+                        end: AstPoint { 11:25 },
                     },
                     flags: VarFlags(
                         0x0,
@@ -870,7 +919,7 @@ public class Test {
                     name: "lo",
                     range: AstRange {
                         start: AstPoint { 13:8 },
-                        end: AstPoint { 13:19 },
+                        end: AstPoint { 13:18 },
                     },
                     flags: VarFlags(
                         Computed,
@@ -1001,7 +1050,7 @@ public class Test {
                     name: "other",
                     range: AstRange {
                         start: AstPoint { 8:4 },
-                        end: AstPoint { 8:30 },
+                        end: AstPoint { 8:31 },
                     },
                     flags: VarFlags(
                         0x0,
@@ -1042,7 +1091,7 @@ public class Test {
                     name: "local",
                     range: AstRange {
                         start: AstPoint { 11:8 },
-                        end: AstPoint { 11:26 },
+                        end: AstPoint { 11:27 },
                     },
                     flags: VarFlags(
                         0x0,
@@ -1053,7 +1102,7 @@ public class Test {
                     name: "lo",
                     range: AstRange {
                         start: AstPoint { 13:8 },
-                        end: AstPoint { 13:17 },
+                        end: AstPoint { 13:16 },
                     },
                     flags: VarFlags(
                         0x0,
@@ -1133,7 +1182,7 @@ public class Test {
                     name: "i",
                     range: AstRange {
                         start: AstPoint { 5:13 },
-                        end: AstPoint { 5:23 },
+                        end: AstPoint { 5:22 },
                     },
                     flags: VarFlags(
                         0x0,
@@ -1389,7 +1438,7 @@ public class Test {
                     name: "inner",
                     range: AstRange {
                         start: AstPoint { 5:6 },
-                        end: AstPoint { 5:22 },
+                        end: AstPoint { 5:23 },
                     },
                     flags: VarFlags(
                         0x0,
@@ -1528,4 +1577,66 @@ public class Test {
         "#]];
         expected.assert_debug_eq(&out);
     }
+
+    #[test]
+    fn instanceof_flow() {
+        let content = "
+public class Test {
+    public void test(Object o, Object p) {
+        if (!(o instanceof String s)) {
+            return;
+        }
+        s.length();
+        if (o instanceof Integer i && p instanceof Long l) {
+            i.intValue();
+        } else if (!(p instanceof Double d) || o == null) {
+            o.hashCode();
+        } else {
+            d.intValue();
+        }
+        if (o instanceof Integer a || p instanceof Integer b) {
+            o.hashCode();
+        }
+    }
+}
+";
+        let tokens = ast::lexer::lex(content.as_bytes()).unwrap();
+        let ast = ast::parse_file(&tokens).unwrap();
+        let class = Class::default();
+        let names = |line, col| {
+            get_vars(
+                &ast,
+                &VariableContext {
+                    point: Some(AstPoint::new(line, col)),
+                    imports: Default::default(),
+                    class: &class,
+                    class_map: get_class_map(),
+                },
+            )
+            .unwrap()
+            .into_iter()
+            .filter(|v| !v.flags.contains(VarFlags::Function))
+            .map(|v| format!("{} {}", v.jtype, v.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+        };
+        let expected = expect![[r"
+            Object o, Object p
+            Object o, Object p, String s
+            Object o, Object p, String s, Integer i, Long l
+            Object o, Object p, String s
+            Object o, Object p, String s, Double d
+            Object o, Object p, String s"]];
+        expected.assert_eq(
+            &[
+                names(4, 12),
+                names(6, 10),
+                names(8, 14),
+                names(10, 14),
+                names(12, 14),
+                names(15, 14),
+            ]
+            .join("\n"),
+        );
+    }
 }
@@ -3,14 +3,23 @@
 #![deny(clippy::nursery)]
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::too_many_lines)]
-use rc_zip_tokio::{ReadZip, rc_zip::parse::EntryKind};
+use memmap2::Mmap;
+use rc_zip_tokio::{
+    ReadZip,
+    rc_zip::{
+        fsm::{ArchiveFsm, EntryFsm, FsmResult},
+        parse::Archive,
+    },
+};
 use std::{
     fs::File,
-    io::Write,
+    io::{self, Write},
     path::{Path, PathBuf},
 };
 use tokio::fs::read;
 
+pub use rc_zip_tokio::rc_zip::parse::{Entry, EntryKind};
+
 #[derive(Debug)]
 pub enum ZipUtilError {
     IO(std::io::Error),
@@ -95,3 +104,151 @@ pub async fn extract_jar(jar: &PathBuf, source_dir: &Path) -> Result<(), ZipUtil
 
     Ok(())
 }
+
+/// Read one file of the archive without extracting the others
+pub fn read_entry(jar: &Path, name: &str) -> Result<Option<Vec<u8>>, ZipUtilError> {
+    MappedArchive::open(jar)?.read_name(name)
+}
+
+/// Archive that is read in place
+///
+/// Files are memory-mapped and only the central directory is parsed when the archive is opened,
+/// entries are decompressed when they are read.
+pub struct MappedArchive {
+    data: ArchiveData,
+    offset: usize,
+    archive: Archive,
+}
+
+enum ArchiveData {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl MappedArchive {
+    pub fn open(path: &Path) -> Result<Self, ZipUtilError> {
+        Self::open_at(path, 0)
+    }
+
+    /// The archive starts after `offset` bytes, a jmod has a header of 4 bytes
+    pub fn open_at(path: &Path, offset: usize) -> Result<Self, ZipUtilError> {
+        let file = File::open(path).map_err(ZipUtilError::IO)?;
+        let mmap = unsafe { Mmap::map(&file) }.map_err(ZipUtilError::IO)?;
+        Self::new(ArchiveData::Mapped(mmap), offset)
+    }
+
+    /// Archive in memory, for example a jar inside of a jar
+    pub fn from_bytes(buf: Vec<u8>) -> Result<Self, ZipUtilError> {
+        Self::new(ArchiveData::Owned(buf), 0)
+    }
+
+    fn new(data: ArchiveData, offset: usize) -> Result<Self, ZipUtilError> {
+        let buf = data
+            .as_slice()
+            .get(offset..)
+            .ok_or_else(|| ZipUtilError::IO(io::ErrorKind::UnexpectedEof.into()))?;
+        let archive = read_archive(buf)?;
+        Ok(Self {
+            data,
+            offset,
+            archive,
+        })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.archive.entries()
+    }
+
+    /// Decompress the content of the entry
+    pub fn read(&self, entry: &Entry) -> Result<Vec<u8>, ZipUtilError> {
+        let offset = usize::try_from(entry.header_offset)
+            .map_err(|_| ZipUtilError::IO(io::ErrorKind::InvalidData.into()))?;
+        let buf = self.data.as_slice().get(self.offset..).unwrap_or_default();
+        let mut input = buf.get(offset..).unwrap_or_default();
+        let mut fsm = EntryFsm::new(Some(entry.clone()), None);
+        let mut out = Vec::with_capacity(usize::try_from(entry.uncompressed_size).unwrap_or(0));
+        let mut chunk = vec![0; 64 * 1024];
+        loop {
+            let mut filled = 0;
+            if fsm.wants_read() {
+                let space = fsm.space();
+                filled = space.len().min(input.len());
+                space[..filled].copy_from_slice(&input[..filled]);
+                input = &input[filled..];
+                fsm.fill(filled);
+            }
+            match fsm.process(&mut chunk).map_err(ZipUtilError::Zip)? {
+                FsmResult::Continue((next, outcome)) => {
+                    out.extend_from_slice(&chunk[..outcome.bytes_written]);
+                    if outcome.bytes_written == 0 && outcome.bytes_read == 0 && filled == 0 {
+                        return Err(ZipUtilError::IO(io::ErrorKind::UnexpectedEof.into()));
+                    }
+                    fsm = next;
+                }
+                FsmResult::Done(_) => return Ok(out),
+            }
+        }
+    }
+
+    /// Decompress the content of the entry with the name
+    pub fn read_name(&self, name: &str) -> Result<Option<Vec<u8>>, ZipUtilError> {
+        let Some(entry) = self.archive.by_name(name) else {
+            return Ok(None);
+        };
+        self.read(entry).map(Some)
+    }
+}
+
+impl ArchiveData {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Self::Mapped(mmap) => mmap,
+            Self::Owned(buf) => buf,
+        }
+    }
+}
+
+fn read_archive(buf: &[u8]) -> Result<Archive, ZipUtilError> {
+    let mut fsm = ArchiveFsm::new(buf.len() as u64);
+    loop {
+        if let Some(offset) = fsm.wants_read() {
+            let start = usize::try_from(offset)
+                .map_err(|_| ZipUtilError::IO(io::ErrorKind::InvalidData.into()))?;
+            let input = buf.get(start..).unwrap_or_default();
+            let space = fsm.space();
+            let filled = space.len().min(input.len());
+            if filled == 0 {
+                return Err(ZipUtilError::IO(io::ErrorKind::UnexpectedEof.into()));
+            }
+            space[..filled].copy_from_slice(&input[..filled]);
+            fsm.fill(filled);
+        }
+        fsm = match fsm.process().map_err(ZipUtilError::Zip)? {
+            FsmResult::Continue(fsm) => fsm,
+            FsmResult::Done(archive) => return Ok(archive),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn read_one_entry() {
+        let jar = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/annotations-sources.jar");
+        let content = read_entry(&jar, "ch/emilycares/Annotations.java")
+            .unwrap()
+            .unwrap();
+        let content = String::from_utf8(content).unwrap();
+        assert!(content.starts_with("package ch.emilycares;"));
+        assert!(content.contains("Value of the key"));
+        assert!(
+            read_entry(&jar, "ch/emilycares/Other.java")
+                .unwrap()
+                .is_none()
+        );
+    }
+}